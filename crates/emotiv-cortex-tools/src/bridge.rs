@@ -0,0 +1,77 @@
+//! `bridge`: hold a Cortex session and relay its streams over a Unix
+//! socket, so consumers can attach and detach without touching Cortex.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use emotiv_cortex_v2::ResilientClient;
+use emotiv_cortex_v2::protocol::headset::QueryHeadsetsOptions;
+use emotiv_cortex_v2::supervisor::StreamSupervisor;
+
+use crate::ConnectArgs;
+
+#[derive(clap::Args)]
+pub struct BridgeArgs {
+    #[command(flatten)]
+    pub connect: ConnectArgs,
+
+    /// Headset ID (default: first discovered headset)
+    #[arg(long)]
+    headset: Option<String>,
+
+    /// Streams to relay
+    #[arg(long, value_delimiter = ',', default_value = "eeg")]
+    streams: Vec<String>,
+
+    /// Socket path consumers connect to
+    #[arg(long, default_value = "/tmp/emotiv-cortex.sock")]
+    socket: PathBuf,
+}
+
+pub async fn run(args: BridgeArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let config = args.connect.load_config()?;
+    let client = ResilientClient::connect(config).await?;
+
+    let headsets = client
+        .query_headsets(QueryHeadsetsOptions::default())
+        .await?;
+    let headset = match &args.headset {
+        Some(id) => headsets.iter().find(|h| &h.id == id),
+        None => headsets.first(),
+    }
+    .ok_or("No matching headset found. Make sure your headset is turned on.")?;
+
+    if headset.status != "connected" {
+        client.connect_headset(&headset.id).await?;
+        // Give the Bluetooth link time to settle before creating a session.
+        tokio::time::sleep(Duration::from_secs(4)).await;
+    }
+    let session = client.create_session(&headset.id).await?;
+
+    let streams: Vec<&str> = args.streams.iter().map(String::as_str).collect();
+    let supervisor = StreamSupervisor::start(&client, &session.id, &streams, &args.socket).await?;
+    eprintln!(
+        "Relaying {} from {} on {} (Ctrl-C to stop)",
+        streams.join(","),
+        headset.id,
+        supervisor.path().display()
+    );
+
+    client.attach_sink(supervisor).await;
+
+    tokio::signal::ctrl_c().await?;
+    let report = client.shutdown().await;
+    for step in report.problems() {
+        eprintln!(
+            "Teardown: {:?} {} {:?}",
+            step.stage, step.target, step.outcome
+        );
+    }
+    for sink in report.sinks.iter().filter(|s| !s.is_lossless()) {
+        eprintln!(
+            "{}: {} relayed, {} dropped, {} undelivered",
+            sink.sink, sink.written, sink.dropped, sink.unflushed
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,129 @@
+//! `doctor`: step-by-step checks of everything a recording depends on.
+
+use emotiv_cortex_v2::protocol::headset::QueryHeadsetsOptions;
+use emotiv_cortex_v2::{CortexClient, CortexConfig};
+
+use crate::ConnectArgs;
+
+#[derive(clap::Args)]
+pub struct DoctorArgs {
+    #[command(flatten)]
+    pub connect: ConnectArgs,
+}
+
+/// Tally of check results.
+#[derive(Default)]
+struct Checks {
+    passed: usize,
+    failed: usize,
+}
+
+impl Checks {
+    fn pass(&mut self, name: &str, detail: impl std::fmt::Display) {
+        self.passed += 1;
+        println!("ok    {name:<12} {detail}");
+    }
+
+    fn fail(&mut self, name: &str, detail: impl std::fmt::Display) {
+        self.failed += 1;
+        println!("FAIL  {name:<12} {detail}");
+    }
+}
+
+pub async fn run(args: DoctorArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let mut checks = Checks::default();
+
+    let config = match args.connect.load_config() {
+        Ok(config) if config.client_id.is_empty() => {
+            checks.fail("config", "client_id is empty");
+            config
+        }
+        Ok(config) => {
+            checks.pass("config", format!("client id {}", redact(&config.client_id)));
+            for issue in config.validate().issues {
+                checks.fail("config", issue);
+            }
+            config
+        }
+        Err(e) => {
+            checks.fail("config", e);
+            let mut config = CortexConfig::new("", "");
+            if let Some(url) = &args.connect.url {
+                config.cortex_url.clone_from(url);
+            }
+            config
+        }
+    };
+
+    let mut client = match CortexClient::connect(&config).await {
+        Ok(client) => client,
+        Err(e) => {
+            checks.fail("launcher", format!("{}: {e}", config.cortex_url));
+            return finish(&checks);
+        }
+    };
+    match client.get_cortex_info().await {
+        Ok(info) => checks.pass(
+            "launcher",
+            format!(
+                "Cortex {} at {}",
+                info["version"].as_str().unwrap_or("(unknown version)"),
+                config.cortex_url
+            ),
+        ),
+        Err(e) => checks.fail("launcher", e),
+    }
+
+    match client
+        .authenticate(&config.client_id, &config.client_secret)
+        .await
+    {
+        Ok(token) => {
+            checks.pass("credentials", "authorized");
+            check_sessions(&client, &token, &mut checks).await;
+        }
+        Err(e) => checks.fail("credentials", e),
+    }
+
+    match client.query_headsets(QueryHeadsetsOptions::default()).await {
+        Ok(headsets) if headsets.is_empty() => {
+            checks.fail("headsets", "none found; is the headset on and paired?");
+        }
+        Ok(headsets) => {
+            let list: Vec<String> = headsets
+                .iter()
+                .map(|h| format!("{} ({})", h.id, h.status))
+                .collect();
+            checks.pass("headsets", list.join(", "));
+        }
+        Err(e) => checks.fail("headsets", e),
+    }
+
+    let _ = client.disconnect().await;
+    finish(&checks)
+}
+
+async fn check_sessions(client: &CortexClient, token: &str, checks: &mut Checks) {
+    match client.query_sessions(token).await {
+        Ok(sessions) => {
+            let open = sessions.iter().filter(|s| s.is_open()).count();
+            checks.pass("sessions", format!("{open} open"));
+        }
+        Err(e) => checks.fail("sessions", e),
+    }
+}
+
+fn finish(checks: &Checks) -> Result<(), Box<dyn std::error::Error>> {
+    println!("\n{} passed, {} failed", checks.passed, checks.failed);
+    if checks.failed == 0 {
+        Ok(())
+    } else {
+        Err(format!("{} check(s) failed", checks.failed).into())
+    }
+}
+
+/// First four characters of a credential.
+fn redact(value: &str) -> String {
+    let prefix: String = value.chars().take(4).collect();
+    format!("{prefix}…")
+}
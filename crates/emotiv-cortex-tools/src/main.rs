@@ -0,0 +1,151 @@
+//! # emotiv-cortex-tools
+//!
+//! One executable for acquisition machines, bundling:
+//!
+//! - `tui` — the full-screen dashboard from `emotiv-cortex-tui`
+//! - `cli` — its headless commands (`cli stream eeg --stdout`, `cli watch`,
+//!   `cli headsets`, `cli records`, `cli init`; `--json` for scripts)
+//! - `bridge` — relays streams to detachable consumers over a Unix socket;
+//!   `serve` lets local processes pick headsets, sessions, and streams
+//!   themselves over the same kind of socket
+//! - `doctor` — checks config, Launcher connectivity, credentials, and
+//!   headsets
+//!
+//! Each component is behind the cargo feature of the same name (all on by
+//! default). Build the distribution with the `dist` profile, e.g.
+//! `cargo build -p emotiv-cortex-tools --profile dist --target
+//! x86_64-unknown-linux-musl` for a fully static Linux binary.
+
+#[cfg(not(any(
+    feature = "tui",
+    feature = "cli",
+    feature = "bridge",
+    feature = "doctor"
+)))]
+compile_error!("enable at least one of the `tui`, `cli`, `bridge`, or `doctor` features");
+
+use clap::{Parser, Subcommand};
+
+#[cfg(all(feature = "bridge", unix))]
+mod bridge;
+#[cfg(feature = "doctor")]
+mod doctor;
+#[cfg(all(feature = "bridge", unix))]
+mod serve;
+
+/// Emotiv Cortex dashboard, headless commands, stream bridge, and
+/// diagnostics in one binary.
+#[derive(Parser)]
+#[command(name = "emotiv-cortex-tools", version, about)]
+struct Tools {
+    #[command(subcommand)]
+    tool: Tool,
+}
+
+#[derive(Subcommand)]
+enum Tool {
+    /// Full-screen dashboard
+    #[cfg(feature = "tui")]
+    Tui(emotiv_cortex_tui::Options),
+    /// Headless commands: `stream`, `watch`, `headsets`, `sessions`, `records`, `init`
+    #[cfg(feature = "cli")]
+    Cli(HeadlessArgs),
+    /// Relay streams to consumers over a Unix socket
+    #[cfg(all(feature = "bridge", unix))]
+    Bridge(bridge::BridgeArgs),
+    /// Serve headsets, sessions, and streams to local processes over a Unix socket
+    #[cfg(all(feature = "bridge", unix))]
+    Serve(serve::ServeArgs),
+    /// Check config, connectivity, credentials, and headsets
+    #[cfg(feature = "doctor")]
+    Doctor(doctor::DoctorArgs),
+}
+
+#[cfg(feature = "cli")]
+#[derive(clap::Args)]
+struct HeadlessArgs {
+    #[command(flatten)]
+    options: emotiv_cortex_tui::Options,
+
+    #[command(subcommand)]
+    command: emotiv_cortex_tui::Command,
+}
+
+/// How to reach Cortex, for the components that connect directly.
+#[cfg(any(all(feature = "bridge", unix), feature = "doctor"))]
+#[derive(clap::Args)]
+struct ConnectArgs {
+    /// Path to cortex.toml config file
+    #[arg(short, long)]
+    config: Option<std::path::PathBuf>,
+
+    /// Cortex API URL override
+    #[arg(long)]
+    url: Option<String>,
+
+    /// Enable verbose logging
+    #[arg(short, long)]
+    verbose: bool,
+}
+
+#[cfg(any(all(feature = "bridge", unix), feature = "doctor"))]
+impl ConnectArgs {
+    /// Discover `cortex.toml` and apply the URL override.
+    fn load_config(&self) -> emotiv_cortex_v2::CortexResult<emotiv_cortex_v2::CortexConfig> {
+        let mut config = emotiv_cortex_v2::CortexConfig::discover(self.config.as_deref())?;
+        if let Some(url) = &self.url {
+            config.cortex_url.clone_from(url);
+        }
+        Ok(config)
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    match Tools::parse().tool {
+        #[cfg(feature = "tui")]
+        Tool::Tui(options) => {
+            emotiv_cortex_tui::run(emotiv_cortex_tui::Cli {
+                options,
+                command: None,
+            })
+            .await
+        }
+        #[cfg(feature = "cli")]
+        Tool::Cli(args) => {
+            emotiv_cortex_tui::run(emotiv_cortex_tui::Cli {
+                options: args.options,
+                command: Some(args.command),
+            })
+            .await
+        }
+        #[cfg(all(feature = "bridge", unix))]
+        Tool::Bridge(args) => {
+            init_tracing(args.connect.verbose);
+            bridge::run(args).await
+        }
+        #[cfg(all(feature = "bridge", unix))]
+        Tool::Serve(args) => {
+            init_tracing(args.connect.verbose);
+            serve::run(args).await
+        }
+        #[cfg(feature = "doctor")]
+        Tool::Doctor(args) => {
+            init_tracing(args.connect.verbose);
+            doctor::run(args).await
+        }
+    }
+}
+
+/// Log to stderr; the dashboard installs its own subscriber.
+#[cfg(any(all(feature = "bridge", unix), feature = "doctor"))]
+fn init_tracing(verbose: bool) {
+    tracing_subscriber::fmt()
+        .with_env_filter(if verbose {
+            "emotiv_cortex_v2=debug"
+        } else {
+            "emotiv_cortex_v2=warn"
+        })
+        .with_writer(std::io::stderr)
+        .init();
+}
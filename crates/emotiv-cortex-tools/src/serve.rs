@@ -0,0 +1,51 @@
+//! `serve`: expose the Cortex connection to local processes over a Unix
+//! socket, letting each pick its headset, session, and streams.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use emotiv_cortex_v2::ResilientClient;
+use emotiv_cortex_v2::ipc::IpcServer;
+
+use crate::ConnectArgs;
+
+#[derive(clap::Args)]
+pub struct ServeArgs {
+    #[command(flatten)]
+    pub connect: ConnectArgs,
+
+    /// Socket path processes connect to
+    #[arg(long, default_value = "/tmp/emotiv-cortex-api.sock")]
+    socket: PathBuf,
+}
+
+pub async fn run(args: ServeArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let config = args.connect.load_config()?;
+    let client = Arc::new(ResilientClient::connect(config).await?);
+
+    let server = IpcServer::start(Arc::clone(&client), &args.socket).await?;
+    eprintln!(
+        "Serving Cortex on {} (Ctrl-C to stop)",
+        server.path().display()
+    );
+
+    tokio::signal::ctrl_c().await?;
+    let stats = server.stats();
+    let relayed = server.stop().await;
+    eprintln!(
+        "{} connections, {} requests, {} samples relayed, {} dropped",
+        stats.connections, stats.requests, relayed.written, relayed.dropped
+    );
+
+    let Some(client) = Arc::into_inner(client) else {
+        return Ok(());
+    };
+    let report = client.shutdown().await;
+    for step in report.problems() {
+        eprintln!(
+            "Teardown: {:?} {} {:?}",
+            step.stage, step.target, step.outcome
+        );
+    }
+    Ok(())
+}
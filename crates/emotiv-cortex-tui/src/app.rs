@@ -2,9 +2,11 @@
 //!
 //! [`App`] holds all mutable state consumed by the rendering and event-loop
 //! layers: connection info, ring buffers for stream data, UI navigation,
-//! and the optional LSL handle.
+//! persisted [`Preferences`], the active record and its markers, the
+//! [`Training`] tab state, per-stream [`StreamStats`], and the optional
+//! LSL handle.
 
-use std::collections::{HashSet, VecDeque};
+use std::collections::{BTreeMap, HashSet, VecDeque};
 use std::sync::Arc;
 
 use emotiv_cortex_v2::headset::HeadsetModel;
@@ -12,17 +14,24 @@ use emotiv_cortex_v2::protocol::headset::HeadsetInfo;
 use emotiv_cortex_v2::protocol::streams::{
     DeviceQuality, FacialExpression, MentalCommand, PerformanceMetrics,
 };
+use emotiv_cortex_v2::protocol::training::TrainingStatus;
+use emotiv_cortex_v2::quality::{ChannelTrend, QualityAlertKind, TrendConfig};
+use emotiv_cortex_v2::stream_stats::StreamStats;
+use emotiv_cortex_v2::streams::StreamSample;
 use emotiv_cortex_v2::{CortexClient, CortexConfig};
+use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 
 use crate::event::{AppEvent, LogEntry};
-
-/// Maximum number of samples kept per ring buffer channel.
-const RING_BUFFER_CAP: usize = 256;
+use crate::prefs::Preferences;
+use crate::training::{Training, TrainingEvent, TrainingPhase};
 
 /// Maximum number of log entries retained.
 const LOG_CAP: usize = 500;
 
+/// Maximum number of injected markers listed on the Records tab.
+const MARKER_CAP: usize = 50;
+
 // ─── Tab Enum ────────────────────────────────────────────────────────────
 
 /// Top-level TUI tabs.
@@ -30,6 +39,8 @@ const LOG_CAP: usize = 500;
 pub enum Tab {
     Dashboard,
     Streams,
+    Records,
+    Training,
     #[cfg(all(feature = "lsl", not(target_os = "linux")))]
     Lsl,
     Device,
@@ -42,6 +53,8 @@ impl Tab {
         &[
             Tab::Dashboard,
             Tab::Streams,
+            Tab::Records,
+            Tab::Training,
             #[cfg(all(feature = "lsl", not(target_os = "linux")))]
             Tab::Lsl,
             Tab::Device,
@@ -53,6 +66,8 @@ impl Tab {
         match self {
             Tab::Dashboard => "Dashboard",
             Tab::Streams => "Streams",
+            Tab::Records => "Records",
+            Tab::Training => "Training",
             #[cfg(all(feature = "lsl", not(target_os = "linux")))]
             Tab::Lsl => "LSL",
             Tab::Device => "Device",
@@ -76,7 +91,8 @@ impl Tab {
 // ─── Stream view selector (for Streams tab) ─────────────────────────────
 
 /// Which stream is displayed in the Streams tab.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum StreamView {
     Eeg,
     Motion,
@@ -139,7 +155,8 @@ impl ConnectionPhase {
 // ─── Subscribed stream tracking ──────────────────────────────────────────
 
 /// Which Cortex streams we have active subscriptions on.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 #[allow(dead_code)]
 pub enum StreamType {
     Eeg,
@@ -152,6 +169,46 @@ pub enum StreamType {
     EegQuality,
 }
 
+// ─── Recording ───────────────────────────────────────────────────────────
+
+/// The record currently being written on the session.
+#[derive(Debug, Clone)]
+pub struct ActiveRecord {
+    pub uuid: String,
+    pub title: String,
+    pub started: std::time::Instant,
+}
+
+/// A marker injected from the Records tab.
+#[derive(Debug, Clone)]
+pub struct MarkerEntry {
+    pub at: std::time::Instant,
+    pub label: String,
+    pub value: i32,
+    pub uuid: String,
+}
+
+/// Record state of the session, shown on the Records tab.
+#[derive(Debug)]
+pub struct Recording {
+    /// The record being written, if any.
+    pub active: Option<ActiveRecord>,
+    /// A start or stop request is in flight.
+    pub pending: bool,
+    /// Markers injected into the active record, newest last.
+    pub markers: VecDeque<MarkerEntry>,
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self {
+            active: None,
+            pending: false,
+            markers: VecDeque::with_capacity(MARKER_CAP),
+        }
+    }
+}
+
 // ─── Main App State ──────────────────────────────────────────────────────
 
 /// All mutable TUI state.
@@ -169,6 +226,8 @@ pub struct App {
     // ── Device discovery ─────────────────────────────────────────────
     pub discovered_headsets: Vec<HeadsetInfo>,
     pub selected_headset_idx: usize,
+    /// Last-used headset still waiting to be pre-selected.
+    pending_headset_restore: Option<String>,
     // ── Event channel (for spawning async work from key handlers) ──
     tx: mpsc::UnboundedSender<AppEvent>,
     /// Shutdown broadcast — shared with stream subscriber tasks.
@@ -189,8 +248,12 @@ pub struct App {
     // ── Latest snapshot values ───────────────────────────────────────
     pub metrics: Option<PerformanceMetrics>,
     pub device_quality: Option<DeviceQuality>,
+    /// Per-channel contact-quality trend; alerts go to the log.
+    pub quality_trend: Option<ChannelTrend>,
     pub mental_command: Option<MentalCommand>,
     pub facial_expression: Option<FacialExpression>,
+    /// Rate, jitter and packet loss per Cortex stream name.
+    pub stream_stats: BTreeMap<&'static str, StreamStats>,
 
     // ── Subscriptions ───────────────────────────────────────────────
     pub subscribed_streams: HashSet<StreamType>,
@@ -214,19 +277,31 @@ pub struct App {
     #[cfg(all(feature = "lsl", not(target_os = "linux")))]
     pub lsl_show_xml: bool,
 
+    // ── Recording ───────────────────────────────────────────────────
+    pub recording: Recording,
+
+    // ── Training ────────────────────────────────────────────────────
+    pub training: Training,
+
     // ── Log ─────────────────────────────────────────────────────────
     pub log_entries: VecDeque<LogEntry>,
     pub log_auto_scroll: bool,
 
+    // ── Preferences ─────────────────────────────────────────────────
+    /// Persisted settings; written back on exit.
+    pub prefs: Preferences,
+
     // ── Timing ──────────────────────────────────────────────────────
     pub started_at: std::time::Instant,
 }
 
 impl App {
-    /// Create a new `App` with default (empty) state.
+    /// Create a new `App` with default (empty) state and the given
+    /// preferences.
     pub fn new(
         client: Arc<CortexClient>,
         config: CortexConfig,
+        prefs: Preferences,
         tx: mpsc::UnboundedSender<AppEvent>,
         shutdown_tx: tokio::sync::broadcast::Sender<()>,
     ) -> Self {
@@ -242,25 +317,28 @@ impl App {
 
             discovered_headsets: Vec::new(),
             selected_headset_idx: 0,
+            pending_headset_restore: prefs.last_headset.clone(),
 
             tx,
             shutdown_tx,
 
             active_tab: Tab::Dashboard,
-            stream_view: StreamView::Eeg,
+            stream_view: prefs.stream_view,
             scroll_offset: 0,
             show_help: false,
             should_quit: false,
 
             eeg_buffers: Vec::new(),
-            motion_accel: VecDeque::with_capacity(RING_BUFFER_CAP),
-            motion_mag: VecDeque::with_capacity(RING_BUFFER_CAP),
+            motion_accel: VecDeque::with_capacity(prefs.chart_windows.motion),
+            motion_mag: VecDeque::with_capacity(prefs.chart_windows.motion),
             band_power_buffers: Vec::new(),
 
             metrics: None,
             device_quality: None,
+            quality_trend: None,
             mental_command: None,
             facial_expression: None,
+            stream_stats: BTreeMap::new(),
 
             subscribed_streams: HashSet::new(),
 
@@ -277,24 +355,47 @@ impl App {
             #[cfg(all(feature = "lsl", not(target_os = "linux")))]
             lsl_show_xml: false,
 
+            recording: Recording::default(),
+
+            training: Training::default(),
+
             log_entries: VecDeque::with_capacity(LOG_CAP),
             log_auto_scroll: true,
 
+            prefs,
+
             started_at: std::time::Instant::now(),
         }
     }
 
+    /// EEG sampling rate of the connected headset (128 Hz until known).
+    pub fn eeg_rate_hz(&self) -> f64 {
+        self.headset_model
+            .as_ref()
+            .map_or(128.0, HeadsetModel::sampling_rate_hz)
+    }
+
+    /// Samples kept per EEG channel: the configured chart window, or
+    /// enough for the EEG chart's time window if that is longer.
+    fn eeg_capacity(&self) -> usize {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let chart = (self.prefs.eeg_chart.window_secs * self.eeg_rate_hz()).ceil() as usize;
+        self.prefs.chart_windows.eeg.max(chart)
+    }
+
     /// Initialize EEG ring buffers for the given channel count.
     pub fn init_eeg_buffers(&mut self, num_channels: usize) {
+        let cap = self.eeg_capacity();
         self.eeg_buffers = (0..num_channels)
-            .map(|_| VecDeque::with_capacity(RING_BUFFER_CAP))
+            .map(|_| VecDeque::with_capacity(cap))
             .collect();
     }
 
     /// Initialize band-power ring buffers for the given channel count.
     pub fn init_band_power_buffers(&mut self, num_channels: usize) {
+        let cap = self.prefs.chart_windows.band_power;
         self.band_power_buffers = (0..num_channels)
-            .map(|_| VecDeque::with_capacity(RING_BUFFER_CAP))
+            .map(|_| VecDeque::with_capacity(cap))
             .collect();
     }
 
@@ -311,6 +412,7 @@ impl App {
     /// Returns `true` if the app should quit.
     #[expect(clippy::too_many_lines)]
     pub fn handle_event(&mut self, event: AppEvent) -> bool {
+        self.observe_stream_stats(&event);
         match event {
             AppEvent::Terminal(crossterm::event::Event::Key(key))
                 if key.kind == crossterm::event::KeyEventKind::Press =>
@@ -318,7 +420,7 @@ impl App {
                 self.handle_key(key);
             }
             AppEvent::Eeg(ref data) => self.push_eeg(data),
-            AppEvent::DeviceQuality(dq) => self.device_quality = Some(dq),
+            AppEvent::DeviceQuality(dq) => self.push_device_quality(dq),
             AppEvent::Motion(ref m) => self.push_motion(m),
             AppEvent::BandPower(ref bp) => self.push_band_power(bp),
             AppEvent::Metrics(m) => self.metrics = Some(m),
@@ -327,6 +429,13 @@ impl App {
             AppEvent::EegQuality(_eq) => { /* stored as part of DeviceQuality for now */ }
             AppEvent::HeadsetUpdate(headsets) => {
                 self.discovered_headsets = headsets;
+                // Pre-select the headset used last time, once per run
+                if let Some(last) = &self.pending_headset_restore {
+                    if let Some(idx) = self.discovered_headsets.iter().position(|h| &h.id == last) {
+                        self.selected_headset_idx = idx;
+                        self.pending_headset_restore = None;
+                    }
+                }
                 // Clamp selection index
                 if self.discovered_headsets.is_empty() {
                     self.selected_headset_idx = 0;
@@ -367,10 +476,16 @@ impl App {
                     .iter()
                     .find(|h| h.id == headset_id)
                     .cloned();
+                self.prefs.last_headset = Some(headset_id.clone());
                 self.headset_id = Some(headset_id);
+                self.quality_trend = Some(ChannelTrend::new(
+                    model.channel_names(),
+                    TrendConfig::default(),
+                ));
                 self.headset_model = Some(model);
                 self.phase = ConnectionPhase::Ready;
                 self.log(LogEntry::info("Connection ready"));
+                self.refresh_training();
             }
             AppEvent::StreamsSubscribed(streams) => {
                 self.subscribed_streams = streams.into_iter().collect();
@@ -395,13 +510,19 @@ impl App {
                 self.phase = ConnectionPhase::Discovered;
                 self.subscribed_streams.clear();
                 self.device_quality = None;
+                self.quality_trend = None;
                 self.metrics = None;
                 self.mental_command = None;
                 self.facial_expression = None;
+                self.stream_stats.clear();
                 self.eeg_buffers.clear();
                 self.motion_accel.clear();
                 self.motion_mag.clear();
                 self.band_power_buffers.clear();
+                // Cortex stops the record when its session closes.
+                self.recording.active = None;
+                self.recording.pending = false;
+                self.training.reset();
                 self.log(LogEntry::info(
                     "Disconnected — select a headset to reconnect",
                 ));
@@ -422,6 +543,59 @@ impl App {
                 self.lsl_xml_stream_idx = 0;
                 self.lsl_xml_scroll = 0;
             }
+            AppEvent::Sys(event) => {
+                if let Some((detection, event)) = TrainingEvent::parse(&event) {
+                    let message = format!("{} training: {}", detection.label(), event.label());
+                    self.log(if event == TrainingEvent::Failed {
+                        LogEntry::warn(message)
+                    } else {
+                        LogEntry::info(message)
+                    });
+                    if self.training.apply(detection, event) {
+                        self.refresh_training();
+                    }
+                }
+            }
+            AppEvent::SysSubscribed => self.training.sys_subscribed = true,
+            AppEvent::TrainingInfo {
+                detection,
+                actions,
+                trained,
+            } => self.training.set_info(detection, actions, trained),
+            AppEvent::TrainingRequestFailed => self.training.finish(),
+            AppEvent::RecordStarted(record) => {
+                self.recording.pending = false;
+                self.log(LogEntry::info(format!(
+                    "Recording started: {}",
+                    record.uuid
+                )));
+                self.recording.markers.clear();
+                self.recording.active = Some(ActiveRecord {
+                    uuid: record.uuid,
+                    title: record.title.unwrap_or_default(),
+                    started: std::time::Instant::now(),
+                });
+            }
+            AppEvent::RecordStopped(record) => {
+                self.recording.pending = false;
+                self.recording.active = None;
+                self.log(LogEntry::info(format!(
+                    "Recording stopped: {}",
+                    record.uuid
+                )));
+            }
+            AppEvent::RecordFailed => self.recording.pending = false,
+            AppEvent::MarkerInjected { label, value, uuid } => {
+                if self.recording.markers.len() >= MARKER_CAP {
+                    self.recording.markers.pop_front();
+                }
+                self.recording.markers.push_back(MarkerEntry {
+                    at: std::time::Instant::now(),
+                    label,
+                    value,
+                    uuid,
+                });
+            }
             AppEvent::Log(entry) => self.log(entry),
             AppEvent::Quit => self.should_quit = true,
             AppEvent::Tick | AppEvent::Terminal(_) => {}
@@ -447,6 +621,14 @@ impl App {
             // Help overlay toggle
             KeyCode::Char('?') => self.show_help = !self.show_help,
 
+            // Recording toggle (any tab, once connected)
+            KeyCode::Char('R') => self.toggle_recording(),
+
+            // Records tab: digits inject markers instead of switching tabs
+            KeyCode::Char(c @ '1'..='9') if self.active_tab == Tab::Records => {
+                self.inject_marker(c);
+            }
+
             // Tab switching by number
             KeyCode::Char(c @ '1'..='9') => {
                 let idx = (c as usize) - ('1' as usize);
@@ -471,6 +653,10 @@ impl App {
             KeyCode::Up | KeyCode::Char('k') => {
                 if self.active_tab == Tab::Device && self.phase == ConnectionPhase::Discovered {
                     self.selected_headset_idx = self.selected_headset_idx.saturating_sub(1);
+                } else if self.active_tab == Tab::Training {
+                    if self.training.phase == TrainingPhase::Idle {
+                        self.training.select_prev();
+                    }
                 } else {
                     #[cfg(all(feature = "lsl", not(target_os = "linux")))]
                     if self.active_tab == Tab::Lsl {
@@ -489,6 +675,10 @@ impl App {
                     let max = self.discovered_headsets.len().saturating_sub(1);
                     self.selected_headset_idx =
                         self.selected_headset_idx.saturating_add(1).min(max);
+                } else if self.active_tab == Tab::Training {
+                    if self.training.phase == TrainingPhase::Idle {
+                        self.training.select_next();
+                    }
                 } else {
                     #[cfg(all(feature = "lsl", not(target_os = "linux")))]
                     if self.active_tab == Tab::Lsl {
@@ -530,9 +720,32 @@ impl App {
                 self.refresh_headsets();
             }
 
+            // Training tab: detection, start, accept / reject, cancel, refresh
+            KeyCode::Char('t') if self.active_tab == Tab::Training => self.switch_detection(),
+            KeyCode::Enter if self.active_tab == Tab::Training => self.start_training(),
+            KeyCode::Char(c @ ('y' | 'n')) if self.active_tab == Tab::Training => {
+                self.decide_training(c == 'y');
+            }
+            KeyCode::Char('c') if self.active_tab == Tab::Training => self.cancel_training(),
+            KeyCode::Char('r') if self.active_tab == Tab::Training => self.refresh_training(),
+
             // Stream view cycling (on Streams tab)
             KeyCode::Char('v') if self.active_tab == Tab::Streams => {
                 self.stream_view = self.stream_view.next();
+                self.prefs.stream_view = self.stream_view;
+            }
+
+            // EEG chart: '[' / ']' time window, '-' / '+' gain
+            KeyCode::Char(c @ ('[' | ']'))
+                if self.active_tab == Tab::Streams && self.stream_view == StreamView::Eeg =>
+            {
+                self.prefs.eeg_chart.step_window(c == ']');
+            }
+            KeyCode::Char(c @ ('+' | '=' | '-'))
+                if self.active_tab == Tab::Streams && self.stream_view == StreamView::Eeg =>
+            {
+                // Gain up means a narrower range.
+                self.prefs.eeg_chart.step_scale(c == '-');
             }
 
             // LSL toggle (on LSL tab)
@@ -612,13 +825,32 @@ impl App {
 
     // ── Ring buffer pushers ──────────────────────────────────────────
 
+    fn observe_stream_stats(&mut self, event: &AppEvent) {
+        let sample: &dyn StreamSample = match event {
+            AppEvent::Eeg(s) => s,
+            AppEvent::DeviceQuality(s) => s,
+            AppEvent::Motion(s) => s,
+            AppEvent::BandPower(s) => s,
+            AppEvent::Metrics(s) => s,
+            AppEvent::MentalCommand(s) => s,
+            AppEvent::FacialExpression(s) => s,
+            AppEvent::EegQuality(s) => s,
+            _ => return,
+        };
+        self.stream_stats
+            .entry(sample.kind())
+            .or_default()
+            .observe(sample);
+    }
+
     fn push_eeg(&mut self, data: &emotiv_cortex_v2::protocol::streams::EegData) {
         if self.eeg_buffers.is_empty() && !data.channels.is_empty() {
             self.init_eeg_buffers(data.channels.len());
         }
+        let cap = self.eeg_capacity();
         for (i, &val) in data.channels.iter().enumerate() {
             if let Some(buf) = self.eeg_buffers.get_mut(i) {
-                if buf.len() >= RING_BUFFER_CAP {
+                while buf.len() >= cap {
                     buf.pop_front();
                 }
                 buf.push_back(f64::from(val));
@@ -626,13 +858,40 @@ impl App {
         }
     }
 
+    fn push_device_quality(&mut self, dq: DeviceQuality) {
+        let alerts = self
+            .quality_trend
+            .as_mut()
+            .map(|trend| trend.observe(&dq))
+            .unwrap_or_default();
+        for alert in alerts {
+            self.log(match alert.kind {
+                QualityAlertKind::Degrading {
+                    time_to_failure, ..
+                } => LogEntry::warn(format!(
+                    "{} contact degrading — poor in ~{} min",
+                    alert.name,
+                    time_to_failure.as_secs().div_ceil(60)
+                )),
+                QualityAlertKind::Failed => {
+                    LogEntry::warn(format!("{} contact quality is poor", alert.name))
+                }
+                QualityAlertKind::Recovered => {
+                    LogEntry::info(format!("{} contact quality recovered", alert.name))
+                }
+            });
+        }
+        self.device_quality = Some(dq);
+    }
+
     fn push_motion(&mut self, data: &emotiv_cortex_v2::protocol::streams::MotionData) {
-        if self.motion_accel.len() >= RING_BUFFER_CAP {
+        let cap = self.prefs.chart_windows.motion;
+        if self.motion_accel.len() >= cap {
             self.motion_accel.pop_front();
         }
         self.motion_accel.push_back(data.accelerometer);
 
-        if self.motion_mag.len() >= RING_BUFFER_CAP {
+        if self.motion_mag.len() >= cap {
             self.motion_mag.pop_front();
         }
         self.motion_mag.push_back(data.magnetometer);
@@ -642,9 +901,10 @@ impl App {
         if self.band_power_buffers.is_empty() && !data.channel_powers.is_empty() {
             self.init_band_power_buffers(data.channel_powers.len());
         }
+        let cap = self.prefs.chart_windows.band_power;
         for (i, &powers) in data.channel_powers.iter().enumerate() {
             if let Some(buf) = self.band_power_buffers.get_mut(i) {
-                if buf.len() >= RING_BUFFER_CAP {
+                if buf.len() >= cap {
                     buf.pop_front();
                 }
                 buf.push_back(powers);
@@ -684,10 +944,14 @@ impl App {
         let token = self.token.clone().unwrap_or_default();
         let tx = self.tx.clone();
         let shutdown = self.shutdown_tx.clone();
+        let wanted = self.prefs.streams.clone();
+        let scope = self.config.session_cleanup;
 
         tokio::spawn(async move {
-            match crate::bridge::connect_headset_and_create_session(&client, &token, &headset, &tx)
-                .await
+            match crate::bridge::connect_headset_and_create_session(
+                &client, &token, &headset, scope, &tx,
+            )
+            .await
             {
                 Ok(result) => {
                     let _ = tx.send(AppEvent::ConnectionReady {
@@ -702,6 +966,7 @@ impl App {
                         &token,
                         &result.session_id,
                         &result.model,
+                        &wanted,
                         tx.clone(),
                         shutdown,
                     )
@@ -801,6 +1066,214 @@ impl App {
         });
     }
 
+    // ── Recording ────────────────────────────────────────────────────
+
+    /// Start a record on the session, or stop the active one.
+    fn toggle_recording(&mut self) {
+        if self.recording.pending {
+            return;
+        }
+        let (ConnectionPhase::Ready, Some(token), Some(session_id)) =
+            (self.phase, self.token.clone(), self.session_id.clone())
+        else {
+            self.log(LogEntry::warn("Cannot record — not yet connected"));
+            return;
+        };
+
+        self.recording.pending = true;
+        let client = Arc::clone(&self.client);
+        let tx = self.tx.clone();
+        if self.recording.active.is_some() {
+            self.log(LogEntry::info("Stopping recording…"));
+            tokio::spawn(async move {
+                if let Err(e) = crate::bridge::stop_record(&client, &token, &session_id, &tx).await
+                {
+                    let _ = tx.send(AppEvent::Log(LogEntry::error(format!(
+                        "Stop recording failed: {e}"
+                    ))));
+                    let _ = tx.send(AppEvent::RecordFailed);
+                }
+            });
+        } else {
+            let title = self.prefs.record_title.clone();
+            self.log(LogEntry::info("Starting recording…"));
+            tokio::spawn(async move {
+                if let Err(e) =
+                    crate::bridge::start_record(&client, &token, &session_id, &title, &tx).await
+                {
+                    let _ = tx.send(AppEvent::Log(LogEntry::error(format!(
+                        "Start recording failed: {e}"
+                    ))));
+                    let _ = tx.send(AppEvent::RecordFailed);
+                }
+            });
+        }
+    }
+
+    /// Inject the marker bound to digit key `key` into the active record.
+    fn inject_marker(&mut self, key: char) {
+        if self.recording.active.is_none() {
+            self.log(LogEntry::warn("Not recording — press R to start a record"));
+            return;
+        }
+        let (Some(token), Some(session_id)) = (self.token.clone(), self.session_id.clone()) else {
+            return;
+        };
+        let Some(number) = key.to_digit(10) else {
+            return;
+        };
+        let label = self.prefs.marker_label(number as usize).to_string();
+        let value = i32::try_from(number).unwrap_or_default();
+
+        let client = Arc::clone(&self.client);
+        let tx = self.tx.clone();
+        tokio::spawn(async move {
+            if let Err(e) =
+                crate::bridge::inject_marker(&client, &token, &session_id, label, value, &tx).await
+            {
+                let _ = tx.send(AppEvent::Log(LogEntry::error(format!(
+                    "Marker injection failed: {e}"
+                ))));
+            }
+        });
+    }
+
+    // ── Training ─────────────────────────────────────────────────────
+
+    /// Token and session for a training request, if connected.
+    fn training_session(&self) -> Option<(String, String)> {
+        if self.phase != ConnectionPhase::Ready {
+            return None;
+        }
+        Some((self.token.clone()?, self.session_id.clone()?))
+    }
+
+    /// Switch between mental command and facial expression training.
+    fn switch_detection(&mut self) {
+        if self.training.toggle_detection() {
+            self.refresh_training();
+        }
+    }
+
+    /// Start training the selected action.
+    fn start_training(&mut self) {
+        if self.training.phase != TrainingPhase::Idle {
+            return;
+        }
+        let Some((token, session_id)) = self.training_session() else {
+            self.log(LogEntry::warn("Cannot train — not yet connected"));
+            return;
+        };
+        let Some(action) = self.training.selected_action().map(ToString::to_string) else {
+            return;
+        };
+        let detection = self.training.detection;
+        let subscribe_sys = !self.training.sys_subscribed;
+        self.training.phase = TrainingPhase::Starting;
+        self.training.action = Some(action.clone());
+        self.log(LogEntry::info(format!(
+            "Training {} \"{action}\"…",
+            detection.label().to_lowercase()
+        )));
+
+        let client = Arc::clone(&self.client);
+        let tx = self.tx.clone();
+        let shutdown = self.shutdown_tx.clone();
+        tokio::spawn(async move {
+            if let Err(e) = crate::bridge::start_training(
+                &client,
+                &token,
+                &session_id,
+                detection,
+                &action,
+                subscribe_sys,
+                &tx,
+                &shutdown,
+            )
+            .await
+            {
+                let _ = tx.send(AppEvent::Log(LogEntry::error(format!(
+                    "Training start failed: {e}"
+                ))));
+                let _ = tx.send(AppEvent::TrainingRequestFailed);
+            }
+        });
+    }
+
+    /// Accept or reject a succeeded training.
+    fn decide_training(&mut self, accept: bool) {
+        if self.training.phase != TrainingPhase::AwaitingDecision {
+            return;
+        }
+        let status = if accept {
+            TrainingStatus::Accept
+        } else {
+            TrainingStatus::Reject
+        };
+        self.training.phase = TrainingPhase::Deciding;
+        self.send_training_control(status);
+    }
+
+    /// Cancel a running or succeeded training.
+    fn cancel_training(&mut self) {
+        if matches!(
+            self.training.phase,
+            TrainingPhase::Running { .. } | TrainingPhase::AwaitingDecision
+        ) {
+            self.send_training_control(TrainingStatus::Reset);
+        }
+    }
+
+    fn send_training_control(&mut self, status: TrainingStatus) {
+        let (Some((token, session_id)), Some(action)) =
+            (self.training_session(), self.training.action.clone())
+        else {
+            self.training.finish();
+            return;
+        };
+        let detection = self.training.detection;
+        let client = Arc::clone(&self.client);
+        let tx = self.tx.clone();
+        tokio::spawn(async move {
+            if let Err(e) = crate::bridge::control_training(
+                &client,
+                &token,
+                &session_id,
+                detection,
+                status,
+                &action,
+            )
+            .await
+            {
+                let _ = tx.send(AppEvent::Log(LogEntry::error(format!(
+                    "Training {} failed: {e}",
+                    status.as_str()
+                ))));
+                let _ = tx.send(AppEvent::TrainingRequestFailed);
+            }
+        });
+    }
+
+    /// Re-fetch the actions and trained counts of the selected detection.
+    fn refresh_training(&mut self) {
+        let Some((token, session_id)) = self.training_session() else {
+            return;
+        };
+        let detection = self.training.detection;
+        let client = Arc::clone(&self.client);
+        let tx = self.tx.clone();
+        tokio::spawn(async move {
+            if let Err(e) =
+                crate::bridge::fetch_training_info(&client, &token, &session_id, detection, &tx)
+                    .await
+            {
+                let _ = tx.send(AppEvent::Log(LogEntry::warn(format!(
+                    "Trained actions unavailable: {e}"
+                ))));
+            }
+        });
+    }
+
     // ── LSL toggle ───────────────────────────────────────────────────
 
     /// Start or stop LSL streaming.
@@ -820,7 +1293,14 @@ impl App {
             self.log(LogEntry::info("Stopping LSL streaming…"));
             tokio::spawn(async move {
                 match crate::lsl::stop_lsl_streaming(handle, &client, &token, &session_id).await {
-                    Ok(()) => {
+                    Ok(reports) => {
+                        for report in reports.iter().filter(|r| r.lost() > 0) {
+                            let _ = tx.send(AppEvent::Log(LogEntry::warn(format!(
+                                "{}: {} samples lost at stop",
+                                report.sink,
+                                report.lost()
+                            ))));
+                        }
                         let _ = tx.send(AppEvent::LslStopped);
                     }
                     Err(e) => {
@@ -6,13 +6,16 @@
 //! 2. **Connect** (user-initiated) — connect headset → create session → subscribe.
 
 use emotiv_cortex_v2::headset::HeadsetModel;
+use emotiv_cortex_v2::ownership::CleanupScope;
 use emotiv_cortex_v2::protocol::headset::QueryHeadsetsOptions;
+use emotiv_cortex_v2::protocol::training::TrainingStatus;
 use emotiv_cortex_v2::streams;
 use futures_util::StreamExt;
 use tokio::sync::mpsc;
 
 use crate::app::StreamType;
 use crate::event::{AppEvent, LogEntry};
+use crate::training::Detection;
 
 // ─── Phase 1: Authenticate & Discover ────────────────────────────────────
 
@@ -61,7 +64,7 @@ pub async fn authenticate_and_discover(
     tx.send(AppEvent::HeadsetUpdate(headsets))?;
 
     // 3. Clean up stale sessions from previous runs
-    if let Err(e) = close_stale_sessions(client, &token, tx).await {
+    if let Err(e) = close_stale_sessions(client, &token, config.session_cleanup, tx).await {
         tx.send(AppEvent::Log(LogEntry::warn(format!(
             "Stale session cleanup failed: {e}"
         ))))?;
@@ -82,11 +85,13 @@ pub struct ConnectResult {
 /// Connect to a specific headset and create a session.
 ///
 /// Called when the user selects a headset in the Device tab and presses
-/// Enter.
+/// Enter. Existing sessions on the headset are closed first if `scope`
+/// allows it.
 pub async fn connect_headset_and_create_session(
     client: &emotiv_cortex_v2::CortexClient,
     token: &str,
     headset: &emotiv_cortex_v2::protocol::headset::HeadsetInfo,
+    scope: CleanupScope,
     tx: &mpsc::UnboundedSender<AppEvent>,
 ) -> Result<ConnectResult, Box<dyn std::error::Error + Send + Sync>> {
     let headset_id = headset.id.clone();
@@ -110,19 +115,20 @@ pub async fn connect_headset_and_create_session(
         tx.send(AppEvent::Log(LogEntry::info("Headset connected")))?;
     }
 
-    // 2. Close any existing sessions for this headset to avoid "busy" errors
-    let sessions = client.query_sessions(token).await.unwrap_or_default();
-    for s in &sessions {
-        let owns_headset = s.headset.as_ref().is_some_and(|h| h.id == headset_id);
-        if owns_headset && s.status != "closed" {
-            tx.send(AppEvent::Log(LogEntry::info(format!(
-                "Closing existing session {} for {headset_id}\u{2026}",
-                &s.id[..16.min(s.id.len())]
-            ))))?;
-            let _ = client.close_session(token, &s.id).await;
-            // Brief pause for the API to release the headset
-            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-        }
+    // 2. Close existing sessions for this headset to avoid "busy" errors
+    let closed = client
+        .close_sessions(token, scope, Some(&headset_id))
+        .await
+        .unwrap_or_default();
+    for (session_id, _) in &closed {
+        tx.send(AppEvent::Log(LogEntry::info(format!(
+            "Closed existing session {} for {headset_id}",
+            &session_id[..16.min(session_id.len())]
+        ))))?;
+    }
+    if !closed.is_empty() {
+        // Brief pause for the API to release the headset
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
     }
 
     // 3. Create session (with retry — headset may need a moment after cleanup)
@@ -196,40 +202,34 @@ pub async fn disconnect_and_close_session(
     Ok(())
 }
 
-/// Close all active sessions for a given token (stale session cleanup).
+/// Close stale sessions in `scope` left over from previous runs.
 ///
-/// Called during startup to prevent "headset busy" errors from orphaned
-/// sessions left by previous runs.
+/// With [`CleanupScope::Owned`] only sessions of this Cortex application
+/// are closed; sessions of other apps are left alone.
 pub async fn close_stale_sessions(
     client: &emotiv_cortex_v2::CortexClient,
     token: &str,
+    scope: CleanupScope,
     tx: &mpsc::UnboundedSender<AppEvent>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let sessions = client.query_sessions(token).await?;
-    let active: Vec<_> = sessions
-        .iter()
-        .filter(|s| s.status == "activated" || s.status == "active" || s.status == "opened")
-        .collect();
-
-    if active.is_empty() {
-        return Ok(());
-    }
+    let closed = client.close_sessions(token, scope, None).await?;
 
-    tx.send(AppEvent::Log(LogEntry::info(format!(
-        "Cleaning up {} stale session(s)…",
-        active.len()
-    ))))?;
+    if scope == CleanupScope::Owned && client.app_id().is_none() {
+        tx.send(AppEvent::Log(LogEntry::info(
+            "Application ID unknown; leaving existing sessions open \
+             (set app_id in cortex.toml or pass --close-all-sessions)",
+        )))?;
+    }
 
-    for session in &active {
-        if let Err(e) = client.close_session(token, &session.id).await {
+    for (session_id, result) in &closed {
+        let short = &session_id[..16.min(session_id.len())];
+        if let Err(e) = result {
             tx.send(AppEvent::Log(LogEntry::warn(format!(
-                "Failed to close stale session {}: {e}",
-                &session.id[..16.min(session.id.len())]
+                "Failed to close stale session {short}: {e}"
             ))))?;
         } else {
             tx.send(AppEvent::Log(LogEntry::info(format!(
-                "Closed stale session {}",
-                &session.id[..16.min(session.id.len())]
+                "Closed stale session {short}"
             ))))?;
         }
     }
@@ -256,7 +256,8 @@ pub async fn refresh_headsets(
     Ok(())
 }
 
-/// Subscribe to default streams (dev + metrics) and spawn forwarding tasks.
+/// Subscribe to `dev` plus each stream in `wanted` and spawn forwarding
+/// tasks.
 ///
 /// Each task reads from a `Pin<Box<dyn Stream>>` and sends parsed data
 /// through the event channel.
@@ -265,6 +266,7 @@ pub async fn subscribe_default_streams(
     token: &str,
     session_id: &str,
     model: &HeadsetModel,
+    wanted: &[StreamType],
     tx: mpsc::UnboundedSender<AppEvent>,
     shutdown: tokio::sync::broadcast::Sender<()>,
 ) -> Result<Vec<StreamType>, Box<dyn std::error::Error + Send + Sync>> {
@@ -291,7 +293,7 @@ pub async fn subscribe_default_streams(
     }
 
     // Subscribe to performance metrics
-    {
+    if wanted.contains(&StreamType::Metrics) {
         let mut stream = streams::subscribe_metrics(client, token, session_id).await?;
         let tx = tx.clone();
         let mut shutdown_rx = shutdown.subscribe();
@@ -310,7 +312,7 @@ pub async fn subscribe_default_streams(
     }
 
     // Subscribe to EEG
-    {
+    if wanted.contains(&StreamType::Eeg) {
         let num_ch = model.num_channels();
         let mut stream = streams::subscribe_eeg(client, token, session_id, num_ch).await?;
         let tx = tx.clone();
@@ -330,7 +332,7 @@ pub async fn subscribe_default_streams(
     }
 
     // Subscribe to motion
-    {
+    if wanted.contains(&StreamType::Motion) {
         let mut stream = streams::subscribe_motion(client, token, session_id).await?;
         let tx = tx.clone();
         let mut shutdown_rx = shutdown.subscribe();
@@ -349,7 +351,7 @@ pub async fn subscribe_default_streams(
     }
 
     // Subscribe to band power
-    {
+    if wanted.contains(&StreamType::BandPower) {
         let num_ch = model.num_channels();
         let mut stream = streams::subscribe_band_power(client, token, session_id, num_ch).await?;
         let tx = tx.clone();
@@ -375,3 +377,140 @@ pub async fn subscribe_default_streams(
 
     Ok(subscribed)
 }
+
+// ─── Recording (user-initiated) ─────────────────────────────────────────
+
+/// Marker port shown in Cortex for markers injected from the TUI.
+const MARKER_PORT: &str = "emotiv-cortex-tui";
+
+/// Start a record on the session. Called when the user presses `R`.
+pub async fn start_record(
+    client: &emotiv_cortex_v2::CortexClient,
+    token: &str,
+    session_id: &str,
+    title: &str,
+    tx: &mpsc::UnboundedSender<AppEvent>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let record = client.create_record(token, session_id, title).await?;
+    tx.send(AppEvent::RecordStarted(record))?;
+    Ok(())
+}
+
+/// Stop the session's active record.
+pub async fn stop_record(
+    client: &emotiv_cortex_v2::CortexClient,
+    token: &str,
+    session_id: &str,
+    tx: &mpsc::UnboundedSender<AppEvent>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let record = client.stop_record(token, session_id).await?;
+    tx.send(AppEvent::RecordStopped(record))?;
+    Ok(())
+}
+
+/// Inject an instance marker at the current time into the active record.
+pub async fn inject_marker(
+    client: &emotiv_cortex_v2::CortexClient,
+    token: &str,
+    session_id: &str,
+    label: String,
+    value: i32,
+    tx: &mpsc::UnboundedSender<AppEvent>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let marker = client
+        .inject_marker(token, session_id, &label, value, MARKER_PORT, None)
+        .await?;
+    tx.send(AppEvent::MarkerInjected {
+        label,
+        value,
+        uuid: marker.uuid,
+    })?;
+    Ok(())
+}
+
+// ─── Training (user-initiated) ──────────────────────────────────────────
+
+/// Start training `action`, first subscribing to `sys` if `subscribe_sys`.
+///
+/// Training progress arrives as [`AppEvent::Sys`] from the forwarding
+/// task, which stops on `shutdown` like the data streams.
+#[allow(clippy::too_many_arguments)]
+pub async fn start_training(
+    client: &emotiv_cortex_v2::CortexClient,
+    token: &str,
+    session_id: &str,
+    detection: Detection,
+    action: &str,
+    subscribe_sys: bool,
+    tx: &mpsc::UnboundedSender<AppEvent>,
+    shutdown: &tokio::sync::broadcast::Sender<()>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if subscribe_sys {
+        let mut stream = streams::subscribe_sys(client, token, session_id).await?;
+        let sys_tx = tx.clone();
+        let mut shutdown_rx = shutdown.subscribe();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    item = stream.next() => {
+                        let Some(event) = item else { break };
+                        if sys_tx.send(AppEvent::Sys(event)).is_err() { break; }
+                    }
+                    _ = shutdown_rx.recv() => break,
+                }
+            }
+        });
+        tx.send(AppEvent::SysSubscribed)?;
+    }
+    client
+        .training(
+            token,
+            session_id,
+            detection.protocol(),
+            TrainingStatus::Start,
+            action,
+        )
+        .await?;
+    Ok(())
+}
+
+/// Send `accept`, `reject`, or `reset` for the training of `action`.
+pub async fn control_training(
+    client: &emotiv_cortex_v2::CortexClient,
+    token: &str,
+    session_id: &str,
+    detection: Detection,
+    status: TrainingStatus,
+    action: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    client
+        .training(token, session_id, detection.protocol(), status, action)
+        .await?;
+    Ok(())
+}
+
+/// Fetch the actions of `detection` and the trained counts of the
+/// session's profile.
+pub async fn fetch_training_info(
+    client: &emotiv_cortex_v2::CortexClient,
+    token: &str,
+    session_id: &str,
+    detection: Detection,
+    tx: &mpsc::UnboundedSender<AppEvent>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // The built-in action list is good enough if this fails.
+    let actions = client
+        .get_detection_info(detection.protocol())
+        .await
+        .map(|info| info.actions)
+        .unwrap_or_default();
+    let trained = client
+        .get_trained_signature_actions(token, detection.protocol(), None, Some(session_id))
+        .await?;
+    tx.send(AppEvent::TrainingInfo {
+        detection,
+        actions,
+        trained,
+    })?;
+    Ok(())
+}
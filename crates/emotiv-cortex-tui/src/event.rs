@@ -7,10 +7,14 @@
 
 use emotiv_cortex_v2::headset::HeadsetModel;
 use emotiv_cortex_v2::protocol::headset::HeadsetInfo;
+use emotiv_cortex_v2::protocol::records::RecordInfo;
 use emotiv_cortex_v2::protocol::streams::{
     BandPowerData, DeviceQuality, EegData, EegQuality, FacialExpression, MentalCommand, MotionData,
-    PerformanceMetrics,
+    PerformanceMetrics, SysEvent,
 };
+use emotiv_cortex_v2::protocol::training::TrainedSignatureActions;
+
+use crate::training::Detection;
 
 /// Every event the TUI main loop can receive.
 #[derive(Debug)]
@@ -57,6 +61,34 @@ pub enum AppEvent {
     Disconnected,
     /// Streams successfully subscribed — updates the active-streams list.
     StreamsSubscribed(Vec<crate::app::StreamType>),
+    // ── Training ─────────────────────────────────────────────────────
+    /// Training event (or other system event) from the `sys` stream.
+    Sys(SysEvent),
+    /// The `sys` stream is now forwarded for the current session.
+    SysSubscribed,
+    /// Actions and trained counts of a detection.
+    TrainingInfo {
+        detection: Detection,
+        actions: Vec<String>,
+        trained: TrainedSignatureActions,
+    },
+    /// A `training` request failed; the error is logged separately.
+    TrainingRequestFailed,
+
+    // ── Recording ────────────────────────────────────────────────────
+    /// A record was created on the session.
+    RecordStarted(RecordInfo),
+    /// The active record was stopped.
+    RecordStopped(RecordInfo),
+    /// Starting or stopping a record failed; the error is logged separately.
+    RecordFailed,
+    /// A marker was injected into the active record.
+    MarkerInjected {
+        label: String,
+        value: i32,
+        uuid: String,
+    },
+
     /// Informational / error log entry.
     Log(LogEntry),
     /// Request application quit.
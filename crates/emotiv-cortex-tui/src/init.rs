@@ -0,0 +1,332 @@
+//! Interactive `init` subcommand — first-run setup wizard.
+//!
+//! Collects the Cortex client ID and secret, checks them against the
+//! running Cortex service (waiting for the user to approve the app in the
+//! EMOTIV Launcher if needed), and writes a `cortex.toml` that
+//! [`CortexConfig::discover`] will pick up on the next run:
+//!
+//! ```text
+//! emotiv-cortex-tui init            # writes ./cortex.toml
+//! emotiv-cortex-tui init --user     # writes ~/.config/emotiv-cortex/cortex.toml
+//! ```
+//!
+//! With `--doctor`, a few environment checks (Cortex version, logged-in
+//! user, license, visible headsets) run after authentication succeeds.
+//!
+//! Prompts and progress always go to stderr. With the global `--json`
+//! flag, the outcome is printed to stdout as one JSON object:
+//! `{"path": ..., "written": ..., "authenticated": ...}`.
+
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use clap::Args;
+use emotiv_cortex_v2::protocol::headset::QueryHeadsetsOptions;
+use emotiv_cortex_v2::{CortexClient, CortexConfig, CortexError};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// How often `hasAccessRight` is polled while waiting for approval.
+const APPROVAL_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Arguments for `emotiv-cortex-tui init`.
+#[derive(Debug, Args)]
+pub struct InitArgs {
+    /// Where to write the config file (default: ./cortex.toml)
+    #[arg(long, conflicts_with = "user")]
+    pub path: Option<PathBuf>,
+
+    /// Write the per-user config (~/.config/emotiv-cortex/cortex.toml)
+    #[arg(long)]
+    pub user: bool,
+
+    /// Overwrite an existing config file without asking
+    #[arg(long)]
+    pub force: bool,
+
+    /// Seconds to wait for app approval in the EMOTIV Launcher
+    #[arg(long, default_value_t = 120)]
+    pub approval_timeout: u64,
+
+    /// Run environment checks after authenticating
+    #[arg(long)]
+    pub doctor: bool,
+}
+
+/// Run the setup wizard.
+///
+/// Nothing is written if the user declines to save after a failed
+/// authentication check, or declines to overwrite an existing file.
+pub async fn run(args: &InitArgs, cortex_url: Option<&str>, json: bool) -> Result<(), BoxError> {
+    let path = target_path(args)?;
+    if path.exists() && !args.force {
+        let overwrite = confirm(&format!("{} already exists. Overwrite?", path.display()))?;
+        if !overwrite {
+            eprintln!("Leaving {} unchanged.", path.display());
+            return print_outcome(json, &path, false, None);
+        }
+    }
+
+    eprintln!("Create a Cortex app at https://www.emotiv.com/developer/ to get these values.");
+    let env_client_id = std::env::var("EMOTIV_CLIENT_ID").ok();
+    let client_id = prompt("Client ID", env_client_id.as_deref())?;
+    let client_secret = prompt_secret("Client secret")?;
+    let default_url =
+        cortex_url.map_or_else(|| CortexConfig::new("", "").cortex_url, str::to_string);
+    let url = prompt("Cortex URL", Some(&default_url))?;
+
+    let mut config = CortexConfig::new(client_id, client_secret);
+    config.cortex_url = url;
+
+    let authenticated = match check_credentials(&config, args).await {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("error: {e}");
+            false
+        }
+    };
+    if !authenticated && !confirm("Authentication did not succeed. Save the config anyway?")? {
+        return Err("setup aborted; no config written".into());
+    }
+
+    write_config(&path, &config)?;
+    eprintln!("Wrote {}", path.display());
+    if !is_discovered(&path) {
+        eprintln!(
+            "note: this file is not on the default search path; pass `--config {}` \
+             or set CORTEX_CONFIG to use it.",
+            path.display()
+        );
+    }
+    print_outcome(json, &path, true, Some(authenticated))
+}
+
+/// With `--json`, report what the wizard did on stdout.
+fn print_outcome(
+    json: bool,
+    path: &Path,
+    written: bool,
+    authenticated: Option<bool>,
+) -> Result<(), BoxError> {
+    if json {
+        crate::pipe::write_json_line(&serde_json::json!({
+            "path": path,
+            "written": written,
+            "authenticated": authenticated,
+        }))?;
+    }
+    Ok(())
+}
+
+fn target_path(args: &InitArgs) -> Result<PathBuf, BoxError> {
+    if args.user {
+        return CortexConfig::user_config_path()
+            .ok_or_else(|| "cannot determine the per-user config directory".into());
+    }
+    Ok(args
+        .path
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("cortex.toml")))
+}
+
+fn is_discovered(path: &Path) -> bool {
+    path == Path::new("cortex.toml")
+        || CortexConfig::user_config_path().is_some_and(|user| user == path)
+}
+
+/// Connect, wait for Launcher approval, authorize, and optionally run the
+/// doctor checks.
+async fn check_credentials(config: &CortexConfig, args: &InitArgs) -> Result<(), BoxError> {
+    eprintln!("Connecting to {} ...", config.cortex_url);
+    let mut client = CortexClient::connect(config).await.map_err(|e| {
+        format!(
+            "connection to {} failed: {e}\nMake sure the EMOTIV Launcher is running.",
+            config.cortex_url
+        )
+    })?;
+
+    let result = async {
+        wait_for_approval(&client, config, Duration::from_secs(args.approval_timeout)).await?;
+        let token = client
+            .authenticate(&config.client_id, &config.client_secret)
+            .await?;
+        eprintln!("Authentication succeeded.");
+        if args.doctor {
+            run_doctor(&client, &token).await;
+        }
+        Ok::<(), BoxError>(())
+    }
+    .await;
+
+    let _ = client.disconnect().await;
+    result
+}
+
+/// Request access and poll until the user approves the app in the
+/// Launcher. Older Cortex versions without `requestAccess` approve at
+/// `authorize` time, so a missing method is not an error.
+async fn wait_for_approval(
+    client: &CortexClient,
+    config: &CortexConfig,
+    timeout: Duration,
+) -> Result<(), BoxError> {
+    match client
+        .request_access(&config.client_id, &config.client_secret)
+        .await
+    {
+        Ok(_) | Err(CortexError::MethodNotFound { .. }) => {}
+        Err(e) => return Err(e.into()),
+    }
+
+    let deadline = Instant::now() + timeout;
+    let mut announced = false;
+    loop {
+        match client
+            .has_access_right(&config.client_id, &config.client_secret)
+            .await
+        {
+            Ok(true) | Err(CortexError::MethodNotFound { .. }) => return Ok(()),
+            Ok(false) => {}
+            Err(e) => return Err(e.into()),
+        }
+        if Instant::now() >= deadline {
+            return Err(format!(
+                "app was not approved in the EMOTIV Launcher within {}s",
+                timeout.as_secs()
+            )
+            .into());
+        }
+        if !announced {
+            eprintln!("Waiting for you to approve the app in the EMOTIV Launcher ...");
+            announced = true;
+        }
+        tokio::time::sleep(APPROVAL_POLL_INTERVAL).await;
+    }
+}
+
+/// Print one line per environment check. Failures are reported, not
+/// returned, so every check runs.
+async fn run_doctor(client: &CortexClient, token: &str) {
+    eprintln!("Running checks:");
+    match client.get_cortex_info().await {
+        Ok(info) => {
+            let version = info
+                .get("version")
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or("unknown");
+            eprintln!("  ok    Cortex version {version}");
+        }
+        Err(e) => eprintln!("  FAIL  getCortexInfo: {e}"),
+    }
+    match client.get_user_login().await {
+        Ok(users) if users.is_empty() => {
+            eprintln!("  FAIL  no user is logged in to the EMOTIV Launcher");
+        }
+        Ok(users) => {
+            let names: Vec<&str> = users.iter().map(|u| u.username.as_str()).collect();
+            eprintln!("  ok    logged in as {}", names.join(", "));
+        }
+        Err(e) => eprintln!("  FAIL  getUserLogin: {e}"),
+    }
+    match client.get_license_info(token).await {
+        Ok(info) if info.is_expired() => {
+            eprintln!(
+                "  FAIL  license expired ({})",
+                info.license.valid_to.as_deref().unwrap_or("unknown date")
+            );
+        }
+        Ok(info) if !info.has_eeg_access() => {
+            eprintln!("  warn  license has no eeg scope (raw EEG streams will be refused)");
+        }
+        Ok(info) => eprintln!(
+            "  ok    license {} valid until {}",
+            info.license.license_name.as_deref().unwrap_or("(unnamed)"),
+            info.license.valid_to.as_deref().unwrap_or("unknown date")
+        ),
+        Err(e) => eprintln!("  FAIL  getLicenseInfo: {e}"),
+    }
+    match client.query_headsets(QueryHeadsetsOptions::default()).await {
+        Ok(headsets) if headsets.is_empty() => {
+            eprintln!("  warn  no headsets found (turn the headset on to stream data)");
+        }
+        Ok(headsets) => {
+            for h in &headsets {
+                eprintln!("  ok    headset {} ({})", h.id, h.status);
+            }
+        }
+        Err(e) => eprintln!("  FAIL  queryHeadsets: {e}"),
+    }
+}
+
+/// Write `client_id`, `client_secret`, and `cortex_url` as TOML. The
+/// file holds a secret, so on Unix it is created owner-readable only.
+fn write_config(path: &Path, config: &CortexConfig) -> Result<(), BoxError> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+
+    // JSON string escapes are valid TOML basic-string escapes.
+    let contents = format!(
+        "# Generated by `emotiv-cortex-tui init`. See cortex.toml.example for all options.\n\
+         client_id = {}\n\
+         client_secret = {}\n\
+         cortex_url = {}\n",
+        serde_json::to_string(&config.client_id)?,
+        serde_json::to_string(&config.client_secret)?,
+        serde_json::to_string(&config.cortex_url)?,
+    );
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+fn prompt(label: &str, default: Option<&str>) -> Result<String, BoxError> {
+    loop {
+        match default {
+            Some(d) if !d.is_empty() => eprint!("{label} [{d}]: "),
+            _ => eprint!("{label}: "),
+        }
+        std::io::stderr().flush()?;
+
+        let mut line = String::new();
+        if std::io::stdin().lock().read_line(&mut line)? == 0 {
+            return Err("unexpected end of input".into());
+        }
+        let value = line.trim();
+        if !value.is_empty() {
+            return Ok(value.to_string());
+        }
+        if let Some(d) = default.filter(|d| !d.is_empty()) {
+            return Ok(d.to_string());
+        }
+    }
+}
+
+fn prompt_secret(label: &str) -> Result<String, BoxError> {
+    loop {
+        let value = rpassword::prompt_password(format!("{label} (hidden): "))?;
+        let value = value.trim();
+        if !value.is_empty() {
+            return Ok(value.to_string());
+        }
+    }
+}
+
+fn confirm(question: &str) -> Result<bool, BoxError> {
+    eprint!("{question} [y/N]: ");
+    std::io::stderr().flush()?;
+    let mut line = String::new();
+    std::io::stdin().lock().read_line(&mut line)?;
+    Ok(matches!(line.trim(), "y" | "Y" | "yes" | "Yes"))
+}
@@ -0,0 +1,365 @@
+//! # emotiv-cortex-tui
+//!
+//! Terminal UI dashboard for the Emotiv Cortex v2 API.
+//!
+//! Displays real-time device status, EEG/motion/band-power stream
+//! visualisations, performance metrics, and optional LSL forwarding
+//! in a full-screen ratatui interface.
+//!
+//! The `stream` subcommand runs headless instead, writing samples from a
+//! single stream to stdout as newline-delimited JSON, and `watch` redraws
+//! a plain-text summary of battery, signal, quality, and metrics (for SSH
+//! sessions where the dashboard is too heavy). `headsets`, `sessions`,
+//! and `records` list what Cortex knows about. The `init` subcommand is an
+//! interactive first-run wizard that writes `cortex.toml`.
+//!
+//! The global `--json` flag makes every headless command print
+//! machine-readable JSON on stdout instead of human text (`watch` and
+//! `stream` as one JSON object per line), for `jq` and orchestration
+//! scripts. Diagnostics always go to stderr.
+//!
+//! The library target exposes the command line ([`Cli`]) and its entry
+//! point ([`run`]) so the `emotiv-cortex-tools` distribution binary can
+//! embed the dashboard and the headless commands.
+
+#[cfg(all(feature = "lsl", target_os = "linux"))]
+compile_error!(
+    "The `lsl` feature is currently unsupported on Linux due to upstream `lsl-sys` \
+build incompatibilities. Build without `--features lsl`, or use Windows/macOS for LSL."
+);
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand};
+use crossterm::event::EventStream;
+use futures_util::StreamExt;
+use tokio::sync::mpsc;
+
+mod app;
+mod bridge;
+mod event;
+mod init;
+mod list;
+#[cfg(all(feature = "lsl", not(target_os = "linux")))]
+mod lsl;
+mod pipe;
+mod prefs;
+mod training;
+mod tui;
+mod ui;
+mod util;
+mod watch;
+
+use app::App;
+use event::{AppEvent, LogEntry};
+use prefs::Preferences;
+
+use emotiv_cortex_v2::ownership::CleanupScope;
+use emotiv_cortex_v2::{CortexClient, CortexConfig};
+
+/// Terminal UI dashboard for the Emotiv Cortex v2 API.
+#[derive(Parser)]
+#[command(name = "emotiv-cortex-tui", version, about)]
+pub struct Cli {
+    #[command(flatten)]
+    pub options: Options,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Options shared by the dashboard and the headless subcommands.
+#[derive(Args)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Options {
+    /// Path to cortex.toml config file
+    #[arg(short, long)]
+    config: Option<String>,
+
+    /// Cortex API URL override
+    #[arg(long, default_value = "wss://localhost:6868")]
+    url: Option<String>,
+
+    /// Preferences file (default: tui-state.json next to the per-user cortex.toml)
+    #[arg(long)]
+    prefs: Option<PathBuf>,
+
+    /// Neither load nor save preferences
+    #[arg(long, conflicts_with = "prefs")]
+    no_prefs: bool,
+
+    /// Enable verbose logging (set `RUST_LOG` for fine-grained control)
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Also close stale sessions opened by other applications
+    #[arg(long, global = true)]
+    close_all_sessions: bool,
+
+    /// Print machine-readable JSON on stdout instead of human text
+    #[arg(long, global = true)]
+    json: bool,
+}
+
+/// Headless subcommands. Without one, the full-screen TUI starts.
+#[derive(Subcommand)]
+pub enum Command {
+    /// Pipe one data stream to stdout (e.g. `stream eeg --format jsonl --stdout`)
+    Stream(pipe::StreamArgs),
+    /// Refreshing text summary of device and metrics (e.g. `watch --streams dev,met`)
+    Watch(watch::WatchArgs),
+    /// Interactive first-run setup: credentials, Launcher approval, cortex.toml
+    Init(init::InitArgs),
+    /// List headsets Cortex can see
+    Headsets,
+    /// List this application's sessions
+    Sessions,
+    /// List recorded sessions (UUID, start time, title), newest first
+    Records(list::RecordsArgs),
+}
+
+/// Target frame interval (~30 fps).
+const TICK_RATE: Duration = Duration::from_millis(33);
+
+/// Run the dashboard, or the headless subcommand in `cli.command`.
+///
+/// Installs the global tracing subscriber, so call it at most once.
+///
+/// # Errors
+/// Returns an error if connecting to Cortex, the selected subcommand, or
+/// terminal setup fails.
+pub async fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+    let Cli { options, command } = cli;
+
+    // ── Tracing ──────────────────────────────────────────────────────
+    // When the TUI is active we only want tracing going to a file or
+    // the log panel, not stdout.  For now we just silence console
+    // output unless --verbose is given (which is mainly useful when
+    // the TUI is not yet fully initialised).  Tracing always targets
+    // stderr so headless subcommands keep stdout clean for data.
+    if options.verbose {
+        tracing_subscriber::fmt()
+            .with_env_filter("emotiv_cortex_v2=debug,emotiv_cortex_cli=debug")
+            .with_writer(std::io::stderr)
+            .init();
+    } else {
+        tracing_subscriber::fmt()
+            .with_env_filter("emotiv_cortex_v2=warn")
+            .with_writer(std::io::stderr)
+            .init();
+    }
+
+    // ── Setup wizard (runs before any config exists) ─────────────────
+    if let Some(Command::Init(args)) = &command {
+        return init::run(args, options.url.as_deref(), options.json)
+            .await
+            .map_err(|e| -> Box<dyn std::error::Error> { e });
+    }
+
+    // ── Config ───────────────────────────────────────────────────────
+    let config = load_config(&options);
+
+    // ── Connect ──────────────────────────────────────────────────────
+    let mut client = CortexClient::connect(&config).await.map_err(|e| {
+        format!(
+            "Connection to {} failed: {e}\nMake sure the EMOTIV Launcher is running.",
+            config.cortex_url
+        )
+    })?;
+
+    // ── Headless subcommands ─────────────────────────────────────────
+    if let Some(command) = &command {
+        let result = run_headless(&client, &config, command, options.json).await;
+        let _ = client.disconnect().await;
+        return result.map_err(|e| -> Box<dyn std::error::Error> { e });
+    }
+
+    // ── App state ────────────────────────────────────────────────────
+    let client = Arc::new(client);
+    let (prefs_path, prefs, prefs_warning) = load_prefs(&options);
+
+    // ── Event channel ────────────────────────────────────────────────
+    let (tx, mut rx) = mpsc::unbounded_channel::<AppEvent>();
+
+    // ── Shutdown broadcast ───────────────────────────────────────────
+    let (shutdown_tx, _) = tokio::sync::broadcast::channel::<()>(1);
+
+    let mut app = App::new(
+        Arc::clone(&client),
+        config,
+        prefs,
+        tx.clone(),
+        shutdown_tx.clone(),
+    );
+    if let Some(warning) = prefs_warning {
+        app.log(LogEntry::warn(warning));
+    }
+
+    // ── Enter TUI ────────────────────────────────────────────────────
+    let mut tui = tui::Tui::enter()?;
+
+    // ── Spawn authenticate + discover background task ────────────────
+    spawn_authenticate(Arc::clone(&client), app.config.clone(), tx.clone());
+
+    // ── Main event loop ──────────────────────────────────────────────
+    let mut terminal_events = EventStream::new();
+    let mut tick = tokio::time::interval(TICK_RATE);
+
+    loop {
+        // Draw
+        tui.terminal.draw(|frame| ui::draw(frame, &app))?;
+
+        // Wait for next event
+        tokio::select! {
+            // Terminal input (keyboard/mouse/resize)
+            maybe_event = terminal_events.next() => {
+                if let Some(Ok(evt)) = maybe_event {
+                    if app.handle_event(AppEvent::Terminal(evt)) {
+                        break;
+                    }
+                }
+            }
+            // Tick timer
+            _ = tick.tick() => {
+                if app.handle_event(AppEvent::Tick) {
+                    break;
+                }
+            }
+            // Data / lifecycle events from background tasks
+            Some(event) = rx.recv() => {
+                if app.handle_event(event) {
+                    break;
+                }
+            }
+        }
+
+        if app.should_quit {
+            break;
+        }
+    }
+
+    // ── Shutdown ─────────────────────────────────────────────────────
+    let _ = shutdown_tx.send(());
+
+    save_prefs(prefs_path.as_deref(), &app.prefs);
+
+    // Gracefully stop LSL streaming if active
+    #[cfg(all(feature = "lsl", not(target_os = "linux")))]
+    if let Some(lsl_handle) = app.lsl_streaming.take() {
+        if let (Some(token), Some(session_id)) = (&app.token, &app.session_id) {
+            let _ = lsl::stop_lsl_streaming(lsl_handle, &app.client, token, session_id).await;
+        }
+    }
+
+    // Stop an active record so it is saved with an end time
+    if app.recording.active.is_some() {
+        if let (Some(token), Some(session_id)) = (&app.token, &app.session_id) {
+            if let Err(e) = app.client.stop_record(token, session_id).await {
+                tracing::warn!("Failed to stop record on exit: {e}");
+            }
+        }
+    }
+
+    // Gracefully close the active session so the next run doesn't
+    // hit a "headset busy" / stale-session error.
+    if let (Some(token), Some(session_id)) = (&app.token, &app.session_id) {
+        if let Err(e) = app.client.close_session(token, session_id).await {
+            tracing::warn!("Failed to close session on exit: {e}");
+        }
+        if let Some(hid) = &app.headset_id {
+            let _ = app.client.disconnect_headset(hid).await;
+        }
+    }
+
+    // Tui::drop restores the terminal automatically.
+    drop(tui);
+
+    Ok(())
+}
+
+/// Run a headless subcommand on a connected client.
+async fn run_headless(
+    client: &CortexClient,
+    config: &CortexConfig,
+    command: &Command,
+    json: bool,
+) -> Result<(), pipe::BoxError> {
+    match command {
+        Command::Stream(args) => pipe::run(client, config, args).await,
+        Command::Watch(args) => watch::run(client, config, args, json).await,
+        Command::Headsets => list::headsets(client, json).await,
+        Command::Sessions => list::sessions(client, config, json).await,
+        Command::Records(args) => list::records(client, config, args, json).await,
+        // Runs before connecting; see `run`.
+        Command::Init(_) => Ok(()),
+    }
+}
+
+/// Discover `cortex.toml` and apply command-line overrides.
+fn load_config(options: &Options) -> CortexConfig {
+    let mut config = CortexConfig::discover(options.config.as_deref().map(Path::new))
+        .unwrap_or_else(|e| {
+            eprintln!(
+                "Note: {e}\nSet EMOTIV_CLIENT_ID / EMOTIV_CLIENT_SECRET env vars, \
+                 or create a cortex.toml file."
+            );
+            CortexConfig::new("", "")
+        });
+
+    if let Some(url) = &options.url {
+        config.cortex_url.clone_from(url);
+    }
+    if options.close_all_sessions {
+        config.session_cleanup = CleanupScope::All;
+    }
+    config
+}
+
+/// Load preferences, or defaults with no path under `--no-prefs`.
+fn load_prefs(options: &Options) -> (Option<PathBuf>, Preferences, Option<String>) {
+    let path = options
+        .prefs
+        .clone()
+        .or_else(Preferences::default_path)
+        .filter(|_| !options.no_prefs);
+    let (prefs, warning) = path
+        .as_deref()
+        .map_or_else(|| (Preferences::default(), None), Preferences::load);
+    (path, prefs, warning)
+}
+
+/// Best-effort save on exit; failures only go to the tracing log.
+fn save_prefs(path: Option<&Path>, prefs: &Preferences) {
+    if let Some(path) = path {
+        if let Err(e) = prefs.save(path) {
+            tracing::warn!("Failed to save preferences to {}: {e}", path.display());
+        }
+    }
+}
+
+/// Spawns the background authenticate + discover task.
+///
+/// Does NOT connect to any headset — the user selects one from the
+/// Device tab and presses Enter.
+fn spawn_authenticate(
+    client: Arc<CortexClient>,
+    config: CortexConfig,
+    tx: mpsc::UnboundedSender<AppEvent>,
+) {
+    tokio::spawn(async move {
+        match bridge::authenticate_and_discover(&client, &config, &tx).await {
+            Ok(result) => {
+                let _ = tx.send(AppEvent::AuthReady {
+                    token: result.token,
+                });
+            }
+            Err(e) => {
+                let _ = tx.send(AppEvent::Log(LogEntry::error(format!(
+                    "Authentication failed: {e}"
+                ))));
+            }
+        }
+    });
+}
@@ -0,0 +1,159 @@
+//! Headless `headsets`, `sessions`, and `records` subcommands.
+//!
+//! Each prints one line per item in plain text, or with the global
+//! `--json` flag a single JSON array of the Cortex objects, for scripts:
+//!
+//! ```text
+//! emotiv-cortex-tui headsets
+//! emotiv-cortex-tui --json records --limit 5 | jq -r '.[].uuid'
+//! ```
+//!
+//! Diagnostics go to stderr.
+
+use std::fmt::Write as _;
+use std::io::Write;
+
+use clap::Args;
+use emotiv_cortex_v2::protocol::headset::{HeadsetInfo, QueryHeadsetsOptions};
+use emotiv_cortex_v2::protocol::records::RecordInfo;
+use emotiv_cortex_v2::protocol::session::SessionInfo;
+use emotiv_cortex_v2::{CortexClient, CortexConfig};
+use serde::Serialize;
+
+use crate::pipe::{BoxError, write_json_line};
+
+/// Arguments for `emotiv-cortex-tui records`.
+#[derive(Debug, Args)]
+pub struct RecordsArgs {
+    /// Show at most this many records, newest first
+    #[arg(long)]
+    pub limit: Option<u32>,
+}
+
+/// List the headsets Cortex can see. Needs no authentication.
+pub async fn headsets(client: &CortexClient, json: bool) -> Result<(), BoxError> {
+    let headsets = client
+        .query_headsets(QueryHeadsetsOptions::default())
+        .await?;
+    print_list(&headsets, json, "headsets", headset_line)
+}
+
+/// List this application's sessions.
+pub async fn sessions(
+    client: &CortexClient,
+    config: &CortexConfig,
+    json: bool,
+) -> Result<(), BoxError> {
+    let token = client
+        .authenticate(&config.client_id, &config.client_secret)
+        .await?;
+    let sessions = client.query_sessions(&token).await?;
+    print_list(&sessions, json, "sessions", session_line)
+}
+
+/// List the user's records, newest first.
+pub async fn records(
+    client: &CortexClient,
+    config: &CortexConfig,
+    args: &RecordsArgs,
+    json: bool,
+) -> Result<(), BoxError> {
+    let token = client
+        .authenticate(&config.client_id, &config.client_secret)
+        .await?;
+    let records = client.query_records(&token, args.limit, None).await?;
+    print_list(&records, json, "records", record_line)
+}
+
+fn print_list<T: Serialize>(
+    items: &[T],
+    json: bool,
+    noun: &str,
+    line: fn(&T) -> String,
+) -> Result<(), BoxError> {
+    if json {
+        write_json_line(&items)?;
+        return Ok(());
+    }
+    if items.is_empty() {
+        eprintln!("No {noun} found.");
+        return Ok(());
+    }
+    let mut out = std::io::stdout().lock();
+    for item in items {
+        match writeln!(out, "{}", line(item)) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => return Ok(()),
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(())
+}
+
+fn headset_line(headset: &HeadsetInfo) -> String {
+    let mut line = format!(
+        "{}  {}  {}",
+        headset.id,
+        headset.status,
+        headset.connected_by.as_deref().unwrap_or("-")
+    );
+    if let Some(battery) = headset.battery_percent {
+        let _ = write!(line, "  battery {battery}%");
+    }
+    if let Some(name) = &headset.custom_name {
+        let _ = write!(line, "  \"{name}\"");
+    }
+    line
+}
+
+fn session_line(session: &SessionInfo) -> String {
+    format!(
+        "{}  {}  {}  started {}",
+        session.id,
+        session.status,
+        session.headset.as_ref().map_or("-", |h| h.id.as_str()),
+        session.started
+    )
+}
+
+fn record_line(record: &RecordInfo) -> String {
+    format!(
+        "{}  {}  {}",
+        record.uuid,
+        record.start_datetime.as_deref().unwrap_or("-"),
+        record.title.as_deref().unwrap_or("")
+    )
+    .trim_end()
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_show_identifiers_first() {
+        let headset: HeadsetInfo = serde_json::from_value(serde_json::json!({
+            "id": "INSIGHT-1",
+            "status": "connected",
+            "connectedBy": "dongle",
+            "batteryPercent": 80
+        }))
+        .unwrap();
+        assert_eq!(
+            headset_line(&headset),
+            "INSIGHT-1  connected  dongle  battery 80%"
+        );
+
+        let record: RecordInfo = serde_json::from_value(serde_json::json!({
+            "uuid": "rec-1",
+            "startDatetime": "2024-01-01T00:00:00Z"
+        }))
+        .unwrap();
+        assert_eq!(record_line(&record), "rec-1  2024-01-01T00:00:00Z");
+        assert_eq!(
+            serde_json::to_value(&record).unwrap()["startDatetime"],
+            "2024-01-01T00:00:00Z"
+        );
+    }
+}
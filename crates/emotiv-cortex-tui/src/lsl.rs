@@ -29,12 +29,15 @@
 use emotiv_cortex_v2::CortexClient;
 use emotiv_cortex_v2::headset::HeadsetModel;
 use emotiv_cortex_v2::protocol::constants::Streams;
+use emotiv_cortex_v2::sink::SinkReport;
 use emotiv_cortex_v2::streams;
-use futures_util::StreamExt;
+use futures_core::Stream;
+use futures_util::{FutureExt, StreamExt};
 use lsl::Pushable;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::mpsc as std_mpsc;
+use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle as ThreadJoinHandle;
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
@@ -514,9 +517,22 @@ fn spawn_outlet_worker(
             };
             let _ = ready_tx.send(Ok(()));
 
+            // Exiting closes the channel, which the forwarding task treats
+            // as a dead outlet and recreates.
+            let mut consecutive_errors = 0_u32;
             while let Some(sample) = sample_rx.blocking_recv() {
-                if let Err(err) = outlet.push_sample(&sample) {
-                    tracing::warn!("Failed to push LSL sample: {err:?}");
+                match outlet.push_sample(&sample) {
+                    Ok(()) => consecutive_errors = 0,
+                    Err(err) => {
+                        tracing::warn!("Failed to push LSL sample: {err:?}");
+                        consecutive_errors += 1;
+                        if consecutive_errors >= MAX_CONSECUTIVE_PUSH_ERRORS {
+                            tracing::error!(
+                                "Giving up on LSL outlet after {consecutive_errors} consecutive push errors"
+                            );
+                            break;
+                        }
+                    }
                 }
             }
         })?;
@@ -553,18 +569,205 @@ fn format_outlet_summary(meta: &OutletMeta) -> String {
     )
 }
 
-fn register_outlet(
-    active_outlets: &mut Vec<String>,
-    outlet_workers: &mut Vec<OutletWorker>,
+// ─── Forwarder supervision ──────────────────────────────────────────────
+//
+// An outlet worker can die independently of the Cortex connection (outlet
+// creation fails after a network change, repeated push errors, a panic in
+// liblsl). The forwarding task notices when its worker's channel closes,
+// keeps draining the source stream, and recreates the outlet with
+// exponential backoff.
+
+/// First delay before recreating a dead outlet.
+const RESTART_BACKOFF_INITIAL: Duration = Duration::from_millis(500);
+/// Upper bound for the restart delay.
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(30);
+/// Consecutive `push_sample` failures after which a worker gives up.
+const MAX_CONSECUTIVE_PUSH_ERRORS: u32 = 50;
+
+/// Health of one forwarder, shared between its task and the UI.
+#[derive(Debug, Default)]
+pub struct ForwarderHealth {
+    down: AtomicBool,
+    restarts: AtomicU64,
+    dropped_while_down: AtomicU64,
+    last_error: Mutex<Option<String>>,
+}
+
+impl ForwarderHealth {
+    /// Whether the outlet is currently accepting samples.
+    pub fn is_up(&self) -> bool {
+        !self.down.load(Ordering::Relaxed)
+    }
+
+    /// Number of successful outlet restarts.
+    pub fn restarts(&self) -> u64 {
+        self.restarts.load(Ordering::Relaxed)
+    }
+
+    /// Source samples discarded while the outlet was down.
+    pub fn dropped_while_down(&self) -> u64 {
+        self.dropped_while_down.load(Ordering::Relaxed)
+    }
+
+    /// Most recent failure reason, if any.
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().ok().and_then(|e| e.clone())
+    }
+
+    fn mark_down(&self, reason: String) {
+        self.down.store(true, Ordering::Relaxed);
+        if let Ok(mut last_error) = self.last_error.lock() {
+            *last_error = Some(reason);
+        }
+    }
+
+    fn mark_restarted(&self) {
+        self.restarts.fetch_add(1, Ordering::Relaxed);
+        self.down.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Source stream already flattened to LSL sample vectors.
+type SampleStream = Pin<Box<dyn Stream<Item = Vec<f32>> + Send>>;
+
+/// Everything a forwarding task needs to (re)create its outlet.
+struct Forwarder {
+    label: &'static str,
     meta: OutletMeta,
-    source_id: &str,
-    model: &HeadsetModel,
-) -> Result<mpsc::Sender<Vec<f32>>, Box<dyn std::error::Error>> {
-    active_outlets.push(format_outlet_summary(&meta));
-    let worker = spawn_outlet_worker(meta, source_id.to_string(), model.clone())?;
-    let sample_tx = worker.sample_tx.clone();
-    outlet_workers.push(worker);
-    Ok(sample_tx)
+    source_id: String,
+    model: HeadsetModel,
+    counter: Arc<AtomicU64>,
+    health: Arc<ForwarderHealth>,
+}
+
+/// Recreate an outlet worker without blocking the runtime on its startup
+/// handshake.
+async fn respawn_outlet_worker(forwarder: &Forwarder) -> Result<OutletWorker, String> {
+    let meta = forwarder.meta.clone();
+    let source_id = forwarder.source_id.clone();
+    let model = forwarder.model.clone();
+    tokio::task::spawn_blocking(move || {
+        spawn_outlet_worker(meta, source_id, model).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("outlet startup task failed: {e}"))?
+}
+
+/// Close a worker's channel and wait for its thread to exit.
+async fn join_outlet_worker(worker: OutletWorker, label: &str) {
+    let OutletWorker {
+        sample_tx,
+        thread_handle,
+    } = worker;
+    drop(sample_tx);
+    let joined = tokio::task::spawn_blocking(move || thread_handle.join()).await;
+    if !matches!(joined, Ok(Ok(()))) {
+        tracing::warn!("{label} LSL outlet thread panicked");
+    }
+}
+
+/// Forward `samples` to the outlet, restarting it with backoff whenever it
+/// dies, until the source ends or shutdown is signalled.
+async fn run_forwarder(
+    forwarder: Forwarder,
+    mut samples: SampleStream,
+    worker: OutletWorker,
+    mut shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+) {
+    let label = forwarder.label;
+    let mut worker = Some(worker);
+    let mut backoff = RESTART_BACKOFF_INITIAL;
+    let mut next_restart = Instant::now();
+    let mut alerted = false;
+
+    loop {
+        let sample = tokio::select! {
+            item = samples.next() => match item {
+                Some(sample) => sample,
+                None => break,
+            },
+            _ = shutdown_rx.recv() => {
+                drain_forwarder(&forwarder, &mut samples, worker.as_ref()).await;
+                break;
+            }
+        };
+
+        if let Some(current) = worker.as_ref() {
+            if current.sample_tx.send(sample).await.is_ok() {
+                forwarder.counter.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+        }
+
+        if let Some(dead) = worker.take() {
+            forwarder
+                .health
+                .mark_down(format!("{label} outlet worker stopped"));
+            tracing::warn!("{label} LSL outlet worker stopped; restarting");
+            join_outlet_worker(dead, label).await;
+            next_restart = Instant::now();
+        }
+        forwarder
+            .health
+            .dropped_while_down
+            .fetch_add(1, Ordering::Relaxed);
+        if !alerted {
+            tracing::warn!(
+                "{label} LSL forwarder is down while its source stream is still flowing"
+            );
+            alerted = true;
+        }
+
+        if Instant::now() >= next_restart {
+            match respawn_outlet_worker(&forwarder).await {
+                Ok(restarted) => {
+                    forwarder.health.mark_restarted();
+                    tracing::info!(
+                        "{label} LSL outlet restarted (restart #{})",
+                        forwarder.health.restarts()
+                    );
+                    worker = Some(restarted);
+                    backoff = RESTART_BACKOFF_INITIAL;
+                    alerted = false;
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        "{label} LSL outlet restart failed, retrying in {backoff:?}: {err}"
+                    );
+                    forwarder.health.mark_down(err);
+                    next_restart = Instant::now() + backoff;
+                    backoff = (backoff * 2).min(RESTART_BACKOFF_MAX);
+                }
+            }
+        }
+    }
+
+    if let Some(worker) = worker {
+        join_outlet_worker(worker, label).await;
+    }
+}
+
+/// Push the samples the source has already delivered before the outlet
+/// closes; without a live outlet they count as dropped.
+async fn drain_forwarder(
+    forwarder: &Forwarder,
+    samples: &mut SampleStream,
+    worker: Option<&OutletWorker>,
+) {
+    while let Some(Some(sample)) = samples.next().now_or_never() {
+        let pushed = match worker {
+            Some(worker) => worker.sample_tx.send(sample).await.is_ok(),
+            None => false,
+        };
+        if pushed {
+            forwarder.counter.fetch_add(1, Ordering::Relaxed);
+        } else {
+            forwarder
+                .health
+                .dropped_while_down
+                .fetch_add(1, Ordering::Relaxed);
+        }
+    }
 }
 
 /// Handle to a running background LSL streaming session.
@@ -573,9 +776,10 @@ fn register_outlet(
 pub struct LslStreamingHandle {
     shutdown_tx: tokio::sync::broadcast::Sender<()>,
     tasks: Vec<JoinHandle<()>>,
-    outlet_workers: Vec<OutletWorker>,
     /// Per-stream (label, counter) pairs for status display.
     pub sample_counts: Arc<Vec<(String, Arc<AtomicU64>)>>,
+    /// Per-stream (label, health) pairs, in the same order as `sample_counts`.
+    pub forwarder_health: Arc<Vec<(String, Arc<ForwarderHealth>)>>,
     /// When streaming was started.
     pub started_at: Instant,
     /// LSL outlet summaries shown in CLI status (name + schema details).
@@ -601,19 +805,135 @@ impl std::fmt::Debug for LslStreamingHandle {
 impl LslStreamingHandle {
     /// Format a compact status string for display in the status bar.
     ///
-    /// Example: `LSL ▶ EEG, Motion`
+    /// Example: `LSL ▶ EEG, Motion` or `LSL ▶ EEG, Motion (1 down)`
     pub fn format_status(&self) -> String {
         let streams: Vec<&str> = self.subscribed.iter().map(|s| s.label()).collect();
-        format!("LSL ▶ {}", streams.join(", "))
+        let down = self.forwarders_down();
+        if down == 0 {
+            format!("LSL ▶ {}", streams.join(", "))
+        } else {
+            format!("LSL ▶ {} ({down} down)", streams.join(", "))
+        }
+    }
+
+    /// Number of forwarders whose outlet is currently down.
+    pub fn forwarders_down(&self) -> usize {
+        self.forwarder_health
+            .iter()
+            .filter(|(_, health)| !health.is_up())
+            .count()
     }
 }
 
+/// Subscribe to the Cortex stream behind `stream_type` and flatten each
+/// sample into the outlet's channel order.
+async fn subscribe_samples(
+    client: &CortexClient,
+    token: &str,
+    session_id: &str,
+    model: &HeadsetModel,
+    stream_type: LslStream,
+) -> Result<SampleStream, Box<dyn std::error::Error>> {
+    let samples: SampleStream = match stream_type {
+        LslStream::Eeg => Box::pin(
+            streams::subscribe_eeg(client, token, session_id, model.num_channels())
+                .await?
+                .map(|data| data.channels),
+        ),
+        LslStream::Motion => Box::pin(
+            streams::subscribe_motion(client, token, session_id)
+                .await?
+                .map(|data| {
+                    let mut sample = Vec::with_capacity(10);
+                    sample.extend_from_slice(&data.accelerometer);
+                    sample.extend_from_slice(&data.magnetometer);
+                    if let Some(quat) = data.quaternion {
+                        sample.extend_from_slice(&quat);
+                    } else {
+                        sample.extend_from_slice(&[0.0, 0.0, 0.0, 1.0]);
+                    }
+                    sample
+                }),
+        ),
+        LslStream::BandPower => Box::pin(
+            streams::subscribe_band_power(client, token, session_id, model.num_channels())
+                .await?
+                .map(|data| {
+                    data.channel_powers
+                        .iter()
+                        .flatten()
+                        .copied()
+                        .collect::<Vec<f32>>()
+                }),
+        ),
+        LslStream::Metrics => Box::pin(
+            streams::subscribe_metrics(client, token, session_id)
+                .await?
+                .map(|data| {
+                    vec![
+                        data.engagement.unwrap_or(0.0),
+                        data.excitement.unwrap_or(0.0),
+                        data.long_excitement.unwrap_or(0.0),
+                        data.stress.unwrap_or(0.0),
+                        data.relaxation.unwrap_or(0.0),
+                        data.interest.unwrap_or(0.0),
+                        data.attention.unwrap_or(0.0),
+                        data.focus.unwrap_or(0.0),
+                    ]
+                }),
+        ),
+        LslStream::MentalCommands => Box::pin(
+            streams::subscribe_mental_commands(client, token, session_id)
+                .await?
+                .map(|data| vec![data.power]),
+        ),
+        LslStream::FacialExpressions => Box::pin(
+            streams::subscribe_facial_expressions(client, token, session_id)
+                .await?
+                .map(|data| {
+                    vec![
+                        data.upper_face_power,
+                        data.lower_face_power,
+                        0.0, // placeholder
+                    ]
+                }),
+        ),
+        LslStream::DeviceQuality => Box::pin(
+            streams::subscribe_dev(client, token, session_id, model.num_channels())
+                .await?
+                .map(|data| {
+                    let mut sample = Vec::with_capacity(data.channel_quality.len() + 3);
+                    sample.extend_from_slice(&data.channel_quality);
+                    sample.push(f32::from(data.battery_percent));
+                    sample.push(data.signal_strength);
+                    sample.push(data.overall_quality);
+                    sample
+                }),
+        ),
+        LslStream::EegQuality => Box::pin(
+            streams::subscribe_eq(client, token, session_id, model.num_channels())
+                .await?
+                .map(|data| {
+                    let mut sample = Vec::with_capacity(data.sensor_quality.len() + 3);
+                    // Push in API cols order: batteryPercent, overall,
+                    // sampleRateQuality, then per-sensor values.
+                    sample.push(f32::from(data.battery_percent));
+                    sample.push(data.overall);
+                    sample.push(data.sample_rate_quality);
+                    sample.extend_from_slice(&data.sensor_quality);
+                    sample
+                }),
+        ),
+    };
+    Ok(samples)
+}
+
 /// Start LSL streaming in the background.
 ///
 /// Subscribes to selected Cortex streams, creates schema-annotated LSL outlets,
-/// and spawns async forwarding tasks. Returns a [`LslStreamingHandle`] that can
-/// be used to monitor status and stop streaming later via
-/// [`stop_lsl_streaming`].
+/// and spawns supervised forwarding tasks that recreate a dead outlet with
+/// backoff. Returns a [`LslStreamingHandle`] that can be used to monitor
+/// status and health and stop streaming later via [`stop_lsl_streaming`].
 pub async fn start_lsl_streaming(
     client: &CortexClient,
     token: &str,
@@ -631,15 +951,20 @@ pub async fn start_lsl_streaming(
     let (shutdown_tx, _) = tokio::sync::broadcast::channel::<()>(1);
     let mut tasks = Vec::new();
     let mut active_outlets = Vec::new();
-    let mut outlet_workers = Vec::new();
 
-    // Sample counters for status reporting
+    // Sample counters and forwarder health for status reporting
     let sample_counts: Arc<Vec<(String, Arc<AtomicU64>)>> = Arc::new(
         selected
             .iter()
             .map(|s| (s.label().to_string(), Arc::new(AtomicU64::new(0))))
             .collect(),
     );
+    let forwarder_health: Arc<Vec<(String, Arc<ForwarderHealth>)>> = Arc::new(
+        selected
+            .iter()
+            .map(|s| (s.label().to_string(), Arc::new(ForwarderHealth::default())))
+            .collect(),
+    );
 
     // XML metadata strings for each selected stream (for TUI display)
     let stream_xml_metadata: Vec<(String, String)> = selected
@@ -652,270 +977,26 @@ pub async fn start_lsl_streaming(
         .collect();
 
     for (idx, stream_type) in selected.iter().enumerate() {
-        let mut shutdown_rx = shutdown_tx.subscribe();
-        let counter = sample_counts[idx].1.clone();
-
-        match stream_type {
-            LslStream::Eeg => {
-                let mut stream =
-                    streams::subscribe_eeg(client, token, session_id, model.num_channels()).await?;
-                let sample_tx = register_outlet(
-                    &mut active_outlets,
-                    &mut outlet_workers,
-                    outlet_meta(LslStream::Eeg, model),
-                    source_id,
-                    model,
-                )?;
-
-                tasks.push(tokio::spawn(async move {
-                    loop {
-                        tokio::select! {
-                            item = stream.next() => {
-                                let Some(data) = item else { break };
-                                if sample_tx.send(data.channels).await.is_err() {
-                                    tracing::warn!("EEG outlet worker stopped");
-                                    break;
-                                }
-                                counter.fetch_add(1, Ordering::Relaxed);
-                            }
-                            _ = shutdown_rx.recv() => break,
-                        }
-                    }
-                }));
-            }
-
-            LslStream::Motion => {
-                let mut stream = streams::subscribe_motion(client, token, session_id).await?;
-                let sample_tx = register_outlet(
-                    &mut active_outlets,
-                    &mut outlet_workers,
-                    outlet_meta(LslStream::Motion, model),
-                    source_id,
-                    model,
-                )?;
-
-                tasks.push(tokio::spawn(async move {
-                    loop {
-                        tokio::select! {
-                            item = stream.next() => {
-                                let Some(data) = item else { break };
-                                let mut sample = Vec::with_capacity(10);
-                                sample.extend_from_slice(&data.accelerometer);
-                                sample.extend_from_slice(&data.magnetometer);
-                                if let Some(quat) = data.quaternion {
-                                    sample.extend_from_slice(&quat);
-                                } else {
-                                    sample.extend_from_slice(&[0.0, 0.0, 0.0, 1.0]);
-                                }
-                                if sample_tx.send(sample).await.is_err() {
-                                    tracing::warn!("Motion outlet worker stopped");
-                                    break;
-                                }
-                                counter.fetch_add(1, Ordering::Relaxed);
-                            }
-                            _ = shutdown_rx.recv() => break,
-                        }
-                    }
-                }));
-            }
-
-            LslStream::BandPower => {
-                let mut stream =
-                    streams::subscribe_band_power(client, token, session_id, model.num_channels())
-                        .await?;
-                let sample_tx = register_outlet(
-                    &mut active_outlets,
-                    &mut outlet_workers,
-                    outlet_meta(LslStream::BandPower, model),
-                    source_id,
-                    model,
-                )?;
-
-                tasks.push(tokio::spawn(async move {
-                    loop {
-                        tokio::select! {
-                            item = stream.next() => {
-                                let Some(data) = item else { break };
-                                let sample: Vec<f32> = data.channel_powers.iter().flatten().copied().collect();
-                                if sample_tx.send(sample).await.is_err() {
-                                    tracing::warn!("Band Power outlet worker stopped");
-                                    break;
-                                }
-                                counter.fetch_add(1, Ordering::Relaxed);
-                            }
-                            _ = shutdown_rx.recv() => break,
-                        }
-                    }
-                }));
-            }
-
-            LslStream::Metrics => {
-                let mut stream = streams::subscribe_metrics(client, token, session_id).await?;
-                let sample_tx = register_outlet(
-                    &mut active_outlets,
-                    &mut outlet_workers,
-                    outlet_meta(LslStream::Metrics, model),
-                    source_id,
-                    model,
-                )?;
-
-                tasks.push(tokio::spawn(async move {
-                    loop {
-                        tokio::select! {
-                            item = stream.next() => {
-                                let Some(data) = item else { break };
-                                let sample = vec![
-                                    data.engagement.unwrap_or(0.0),
-                                    data.excitement.unwrap_or(0.0),
-                                    data.long_excitement.unwrap_or(0.0),
-                                    data.stress.unwrap_or(0.0),
-                                    data.relaxation.unwrap_or(0.0),
-                                    data.interest.unwrap_or(0.0),
-                                    data.attention.unwrap_or(0.0),
-                                    data.focus.unwrap_or(0.0),
-                                ];
-                                if sample_tx.send(sample).await.is_err() {
-                                    tracing::warn!("Metrics outlet worker stopped");
-                                    break;
-                                }
-                                counter.fetch_add(1, Ordering::Relaxed);
-                            }
-                            _ = shutdown_rx.recv() => break,
-                        }
-                    }
-                }));
-            }
-
-            LslStream::MentalCommands => {
-                let mut stream =
-                    streams::subscribe_mental_commands(client, token, session_id).await?;
-                let sample_tx = register_outlet(
-                    &mut active_outlets,
-                    &mut outlet_workers,
-                    outlet_meta(LslStream::MentalCommands, model),
-                    source_id,
-                    model,
-                )?;
-
-                tasks.push(tokio::spawn(async move {
-                    loop {
-                        tokio::select! {
-                            item = stream.next() => {
-                                let Some(data) = item else { break };
-                                let sample = vec![data.power];
-                                if sample_tx.send(sample).await.is_err() {
-                                    tracing::warn!("Mental Command outlet worker stopped");
-                                    break;
-                                }
-                                counter.fetch_add(1, Ordering::Relaxed);
-                            }
-                            _ = shutdown_rx.recv() => break,
-                        }
-                    }
-                }));
-            }
-
-            LslStream::FacialExpressions => {
-                let mut stream =
-                    streams::subscribe_facial_expressions(client, token, session_id).await?;
-                let sample_tx = register_outlet(
-                    &mut active_outlets,
-                    &mut outlet_workers,
-                    outlet_meta(LslStream::FacialExpressions, model),
-                    source_id,
-                    model,
-                )?;
-
-                tasks.push(tokio::spawn(async move {
-                    loop {
-                        tokio::select! {
-                            item = stream.next() => {
-                                let Some(data) = item else { break };
-                                let sample = vec![
-                                    data.upper_face_power,
-                                    data.lower_face_power,
-                                    0.0, // placeholder
-                                ];
-                                if sample_tx.send(sample).await.is_err() {
-                                    tracing::warn!("Facial Expression outlet worker stopped");
-                                    break;
-                                }
-                                counter.fetch_add(1, Ordering::Relaxed);
-                            }
-                            _ = shutdown_rx.recv() => break,
-                        }
-                    }
-                }));
-            }
-
-            LslStream::DeviceQuality => {
-                let mut stream =
-                    streams::subscribe_dev(client, token, session_id, model.num_channels()).await?;
-                let sample_tx = register_outlet(
-                    &mut active_outlets,
-                    &mut outlet_workers,
-                    outlet_meta(LslStream::DeviceQuality, model),
-                    source_id,
-                    model,
-                )?;
-
-                tasks.push(tokio::spawn(async move {
-                    loop {
-                        tokio::select! {
-                            item = stream.next() => {
-                                let Some(data) = item else { break };
-                                let mut sample = Vec::with_capacity(data.channel_quality.len() + 3);
-                                sample.extend_from_slice(&data.channel_quality);
-                                sample.push(data.battery_percent as f32);
-                                sample.push(data.signal_strength);
-                                sample.push(data.overall_quality);
-                                if sample_tx.send(sample).await.is_err() {
-                                    tracing::warn!("Device Quality outlet worker stopped");
-                                    break;
-                                }
-                                counter.fetch_add(1, Ordering::Relaxed);
-                            }
-                            _ = shutdown_rx.recv() => break,
-                        }
-                    }
-                }));
-            }
-
-            LslStream::EegQuality => {
-                let mut stream =
-                    streams::subscribe_eq(client, token, session_id, model.num_channels()).await?;
-                let sample_tx = register_outlet(
-                    &mut active_outlets,
-                    &mut outlet_workers,
-                    outlet_meta(LslStream::EegQuality, model),
-                    source_id,
-                    model,
-                )?;
-
-                tasks.push(tokio::spawn(async move {
-                    loop {
-                        tokio::select! {
-                            item = stream.next() => {
-                                let Some(data) = item else { break };
-                                let mut sample = Vec::with_capacity(data.sensor_quality.len() + 3);
-                                // Push in API cols order: batteryPercent, overall,
-                                // sampleRateQuality, then per-sensor values.
-                                sample.push(data.battery_percent as f32);
-                                sample.push(data.overall);
-                                sample.push(data.sample_rate_quality);
-                                sample.extend_from_slice(&data.sensor_quality);
-                                if sample_tx.send(sample).await.is_err() {
-                                    tracing::warn!("EEG Quality outlet worker stopped");
-                                    break;
-                                }
-                                counter.fetch_add(1, Ordering::Relaxed);
-                            }
-                            _ = shutdown_rx.recv() => break,
-                        }
-                    }
-                }));
-            }
-        }
+        let samples = subscribe_samples(client, token, session_id, model, *stream_type).await?;
+
+        let meta = outlet_meta(*stream_type, model);
+        active_outlets.push(format_outlet_summary(&meta));
+        let worker = spawn_outlet_worker(meta.clone(), source_id.to_string(), model.clone())?;
+
+        let forwarder = Forwarder {
+            label: stream_type.label(),
+            meta,
+            source_id: source_id.to_string(),
+            model: model.clone(),
+            counter: Arc::clone(&sample_counts[idx].1),
+            health: Arc::clone(&forwarder_health[idx].1),
+        };
+        tasks.push(tokio::spawn(run_forwarder(
+            forwarder,
+            samples,
+            worker,
+            shutdown_tx.subscribe(),
+        )));
     }
 
     tracing::info!("LSL streaming active: {}", active_outlets.join(", "));
@@ -923,8 +1004,8 @@ pub async fn start_lsl_streaming(
     Ok(LslStreamingHandle {
         shutdown_tx,
         tasks,
-        outlet_workers,
         sample_counts,
+        forwarder_health,
         started_at: Instant::now(),
         active_streams: active_outlets,
         subscribed: selected.to_vec(),
@@ -934,20 +1015,23 @@ pub async fn start_lsl_streaming(
 
 /// Stop a running LSL streaming session.
 ///
-/// Signals all forwarding tasks to shut down, waits for cleanup, and
-/// unsubscribes from the Cortex streams.
+/// Signals all forwarding tasks to shut down, waits for them to push the
+/// samples already received and close their outlets, and unsubscribes
+/// from the Cortex streams. Returns one report per outlet: samples handed
+/// to the outlet as written, samples discarded while it was down as
+/// dropped.
 pub async fn stop_lsl_streaming(
     handle: LslStreamingHandle,
     client: &CortexClient,
     token: &str,
     session_id: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<Vec<SinkReport>, Box<dyn std::error::Error>> {
     tracing::info!("Shutting down LSL streaming...");
     let LslStreamingHandle {
         shutdown_tx,
         tasks,
-        outlet_workers,
-        sample_counts: _,
+        sample_counts,
+        forwarder_health,
         started_at: _,
         active_streams: _,
         subscribed,
@@ -957,7 +1041,7 @@ pub async fn stop_lsl_streaming(
     // Signal all tasks to stop
     let _ = shutdown_tx.send(());
 
-    // Wait for all tasks to complete with timeout
+    // Wait for all tasks (and the outlet threads they own) to complete
     let shutdown_timeout = tokio::time::timeout(Duration::from_secs(5), async {
         for task in tasks {
             let _ = task.await;
@@ -969,13 +1053,18 @@ pub async fn stop_lsl_streaming(
         tracing::warn!("Some tasks did not shut down cleanly");
     }
 
-    // Drop worker senders and join outlet threads.
-    for worker in outlet_workers {
-        drop(worker.sample_tx);
-        if worker.thread_handle.join().is_err() {
-            tracing::warn!("An LSL outlet thread panicked during shutdown");
-        }
-    }
+    let reports = sample_counts
+        .iter()
+        .zip(forwarder_health.iter())
+        .map(|((label, counter), (_, health))| SinkReport {
+            written: counter.load(Ordering::Relaxed),
+            dropped: health.dropped_while_down(),
+            error: shutdown_timeout
+                .is_err()
+                .then(|| "outlet did not close within 5s".to_string()),
+            ..SinkReport::new(format!("lsl:{label}"))
+        })
+        .collect();
 
     // Unsubscribe from all streams
     let stream_names: Vec<&str> = subscribed
@@ -997,7 +1086,7 @@ pub async fn stop_lsl_streaming(
     }
 
     tracing::info!("LSL streaming stopped.");
-    Ok(())
+    Ok(reports)
 }
 
 #[cfg(all(test, feature = "lsl"))]
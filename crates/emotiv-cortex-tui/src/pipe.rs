@@ -0,0 +1,325 @@
+//! Headless `stream` subcommand — writes typed stream samples to stdout.
+//!
+//! Output is newline-delimited JSON (one object per sample) with no
+//! banners, colours, or progress text, so it can be piped straight into
+//! `jq`, a file, or a Python consumer:
+//!
+//! ```text
+//! emotiv-cortex-tui stream eeg --format jsonl --stdout | jq .channels
+//! ```
+//!
+//! All diagnostics (authentication progress, warnings) go to stderr.
+
+use std::io::Write;
+use std::pin::Pin;
+
+use clap::{Args, ValueEnum};
+use emotiv_cortex_v2::headset::HeadsetModel;
+use emotiv_cortex_v2::protocol::constants::Streams;
+use emotiv_cortex_v2::protocol::headset::QueryHeadsetsOptions;
+use emotiv_cortex_v2::stream_stats::{StreamStats, StreamStatsSnapshot};
+use emotiv_cortex_v2::streams::{SampleStreamExt, StreamSample};
+use emotiv_cortex_v2::{CortexClient, CortexConfig, streams};
+use futures_core::Stream;
+use futures_util::StreamExt;
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use crate::bridge;
+use crate::event::{AppEvent, LogLevel};
+
+pub(crate) type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Streams that can be piped by the `stream` subcommand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PipeStream {
+    /// Raw EEG samples.
+    Eeg,
+    /// Device contact quality / battery / signal.
+    Dev,
+    /// Motion / IMU samples.
+    Mot,
+    /// Per-sensor EEG quality.
+    Eq,
+    /// Per-channel band power.
+    Pow,
+    /// Performance metrics.
+    Met,
+    /// Mental commands.
+    Com,
+    /// Facial expressions.
+    Fac,
+    /// System / training events.
+    Sys,
+}
+
+impl PipeStream {
+    /// Cortex stream name used in `subscribe` / `unsubscribe`.
+    fn cortex_name(self) -> &'static str {
+        match self {
+            Self::Eeg => Streams::EEG,
+            Self::Dev => Streams::DEV,
+            Self::Mot => Streams::MOT,
+            Self::Eq => Streams::EQ,
+            Self::Pow => Streams::POW,
+            Self::Met => Streams::MET,
+            Self::Com => Streams::COM,
+            Self::Fac => Streams::FAC,
+            Self::Sys => Streams::SYS,
+        }
+    }
+}
+
+/// Output encodings supported by the `stream` subcommand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Newline-delimited JSON, one object per sample.
+    Jsonl,
+}
+
+/// Arguments for `emotiv-cortex-tui stream`.
+#[derive(Debug, Args)]
+pub struct StreamArgs {
+    /// Stream to subscribe to
+    #[arg(value_enum)]
+    pub stream: PipeStream,
+
+    /// Output encoding
+    #[arg(long, value_enum, default_value_t = OutputFormat::Jsonl)]
+    pub format: OutputFormat,
+
+    /// Write samples to stdout (the default; accepted for explicit scripts)
+    #[arg(long)]
+    pub stdout: bool,
+
+    /// Headset ID to stream from (defaults to the first discovered headset)
+    #[arg(long)]
+    pub headset: Option<String>,
+
+    /// Exit after writing this many samples
+    #[arg(long)]
+    pub count: Option<u64>,
+}
+
+/// Run the headless stream pipe until Ctrl-C, `--count` samples, or the
+/// reader closes stdout (e.g. `| head`).
+///
+/// Authenticates, connects the selected headset, creates a session,
+/// subscribes to the requested stream, and closes the session on exit.
+pub async fn run(
+    client: &CortexClient,
+    config: &CortexConfig,
+    args: &StreamArgs,
+) -> Result<(), BoxError> {
+    let (token, connected) = start_session(client, config, args.headset.as_deref()).await?;
+
+    let result = pipe_stream(
+        client,
+        &token,
+        &connected.session_id,
+        &connected.model,
+        args,
+    )
+    .await;
+
+    end_session(
+        client,
+        &token,
+        &connected.session_id,
+        &[args.stream.cortex_name()],
+    )
+    .await;
+
+    result
+}
+
+/// Authenticate, connect `headset` (or the first discovered one), and
+/// create a session. Progress goes to stderr.
+pub(crate) async fn start_session(
+    client: &CortexClient,
+    config: &CortexConfig,
+    headset: Option<&str>,
+) -> Result<(String, bridge::ConnectResult), BoxError> {
+    // Reuse the TUI bridge for bring-up; its progress log goes to stderr.
+    let (tx, rx) = mpsc::unbounded_channel::<AppEvent>();
+    let log_task = tokio::spawn(forward_logs_to_stderr(rx));
+
+    let token = client
+        .authenticate(&config.client_id, &config.client_secret)
+        .await?;
+
+    let headsets = client
+        .query_headsets(QueryHeadsetsOptions::default())
+        .await?;
+    let selected = match headset {
+        Some(id) => headsets.iter().find(|h| h.id == id),
+        None => headsets.first(),
+    }
+    .ok_or_else(|| match headset {
+        Some(id) => format!("Headset {id} not found"),
+        None => "No headsets found. Make sure your headset is turned on.".to_string(),
+    })?;
+
+    let connected = bridge::connect_headset_and_create_session(
+        client,
+        &token,
+        selected,
+        config.session_cleanup,
+        &tx,
+    )
+    .await?;
+    drop(tx);
+    let _ = log_task.await;
+
+    Ok((token, connected))
+}
+
+/// Best-effort teardown so the next run doesn't hit a busy headset.
+pub(crate) async fn end_session(
+    client: &CortexClient,
+    token: &str,
+    session_id: &str,
+    stream_names: &[&str],
+) {
+    let _ = streams::unsubscribe(client, token, session_id, stream_names).await;
+    if let Err(e) = client.close_session(token, session_id).await {
+        eprintln!("warning: failed to close session: {e}");
+    }
+}
+
+async fn pipe_stream(
+    client: &CortexClient,
+    token: &str,
+    session_id: &str,
+    model: &HeadsetModel,
+    args: &StreamArgs,
+) -> Result<(), BoxError> {
+    let num_ch = model.num_channels();
+    match args.stream {
+        PipeStream::Eeg => {
+            let stream = streams::subscribe_eeg(client, token, session_id, num_ch).await?;
+            write_samples(stream, args).await
+        }
+        PipeStream::Dev => {
+            let stream = streams::subscribe_dev(client, token, session_id, num_ch).await?;
+            write_samples(stream, args).await
+        }
+        PipeStream::Mot => {
+            let stream = streams::subscribe_motion(client, token, session_id).await?;
+            write_samples(stream, args).await
+        }
+        PipeStream::Eq => {
+            let stream = streams::subscribe_eq(client, token, session_id, num_ch).await?;
+            write_samples(stream, args).await
+        }
+        PipeStream::Pow => {
+            let stream = streams::subscribe_band_power(client, token, session_id, num_ch).await?;
+            write_samples(stream, args).await
+        }
+        PipeStream::Met => {
+            let stream = streams::subscribe_metrics(client, token, session_id).await?;
+            write_samples(stream, args).await
+        }
+        PipeStream::Com => {
+            let stream = streams::subscribe_mental_commands(client, token, session_id).await?;
+            write_samples(stream, args).await
+        }
+        PipeStream::Fac => {
+            let stream = streams::subscribe_facial_expressions(client, token, session_id).await?;
+            write_samples(stream, args).await
+        }
+        PipeStream::Sys => {
+            let stream = streams::subscribe_sys(client, token, session_id).await?;
+            write_samples(stream, args).await
+        }
+    }
+}
+
+/// Write each sample as one line of JSON, flushing per line so
+/// downstream consumers see data as soon as it arrives. A stream
+/// statistics summary goes to stderr on exit.
+async fn write_samples<T: Serialize + StreamSample>(
+    stream: Pin<Box<dyn Stream<Item = T> + Send>>,
+    args: &StreamArgs,
+) -> Result<(), BoxError> {
+    let stats = StreamStats::new();
+    let result = write_lines(stream.with_stats(stats.clone()), args).await;
+    eprintln!("{}", stats_summary(&stats.snapshot()));
+    result
+}
+
+async fn write_lines<T: Serialize>(
+    mut stream: impl Stream<Item = T> + Unpin,
+    args: &StreamArgs,
+) -> Result<(), BoxError> {
+    let OutputFormat::Jsonl = args.format;
+    let mut written: u64 = 0;
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    loop {
+        if args.count.is_some_and(|limit| written >= limit) {
+            return Ok(());
+        }
+
+        tokio::select! {
+            item = stream.next() => {
+                let Some(sample) = item else { return Ok(()) };
+                if !write_json_line(&sample)? {
+                    return Ok(());
+                }
+                written += 1;
+            }
+            _ = &mut ctrl_c => return Ok(()),
+        }
+    }
+}
+
+/// One-line summary of a stream's statistics for stderr.
+fn stats_summary(stats: &StreamStatsSnapshot) -> String {
+    let mut parts = vec![format!(
+        "{} {} samples",
+        stats.kind.unwrap_or("stream"),
+        stats.samples
+    )];
+    if let Some(hz) = stats.effective_rate_hz {
+        parts.push(format!("{hz:.1} Hz effective"));
+    }
+    if let Some(ms) = stats.jitter_ms {
+        parts.push(format!("jitter {ms:.2} ms"));
+    }
+    if stats.counter_gaps > 0 {
+        parts.push(format!(
+            "{} lost in {} gaps",
+            stats.lost_samples, stats.counter_gaps
+        ));
+    }
+    format!("stats: {}", parts.join(", "))
+}
+
+/// Write `value` to stdout as one line of JSON and flush it.
+///
+/// Returns `false` if the reader went away (`| head`, `jq` exited), so
+/// the caller can stop quietly.
+pub(crate) fn write_json_line<T: Serialize>(value: &T) -> Result<bool, BoxError> {
+    let line = serde_json::to_string(value)?;
+    let mut out = std::io::stdout().lock();
+    match writeln!(out, "{line}").and_then(|()| out.flush()) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn forward_logs_to_stderr(mut rx: mpsc::UnboundedReceiver<AppEvent>) {
+    while let Some(event) = rx.recv().await {
+        if let AppEvent::Log(entry) = event {
+            let level = match entry.level {
+                LogLevel::Info => "info",
+                LogLevel::Warn => "warning",
+                LogLevel::Error => "error",
+            };
+            eprintln!("{level}: {}", entry.message);
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! Persisted operator preferences.
+//!
+//! [`Preferences`] holds the bits of TUI state worth keeping across runs:
+//! the last connected headset (pre-selected in the Device tab), which
+//! streams to subscribe after connecting, the Streams tab view, chart
+//! window lengths, the EEG chart's time window and gain, the quality
+//! colour thresholds, and the record title and marker labels used by the
+//! Records tab.
+//!
+//! The file is JSON, stored next to the per-user `cortex.toml`
+//! (`~/.config/emotiv-cortex/tui-state.json`, or `%APPDATA%` on Windows).
+//! It is loaded at startup and written on exit; unknown or missing fields
+//! fall back to defaults, so the file can be edited by hand or deleted to
+//! reset.
+
+use std::path::{Path, PathBuf};
+
+use emotiv_cortex_v2::CortexConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::app::{StreamType, StreamView};
+
+/// File name of the preferences file inside the config directory.
+const PREFS_FILE_NAME: &str = "tui-state.json";
+
+/// Ring buffer length (in samples) used when no preference is set.
+const DEFAULT_CHART_WINDOW: usize = 256;
+
+/// Upper bound for a chart window, to keep a typo from exhausting memory.
+const MAX_CHART_WINDOW: usize = 16_384;
+
+/// Samples kept per chart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChartWindows {
+    /// EEG samples per channel.
+    pub eeg: usize,
+    /// Motion samples.
+    pub motion: usize,
+    /// Band-power samples per channel.
+    pub band_power: usize,
+}
+
+impl Default for ChartWindows {
+    fn default() -> Self {
+        Self {
+            eeg: DEFAULT_CHART_WINDOW,
+            motion: DEFAULT_CHART_WINDOW,
+            band_power: DEFAULT_CHART_WINDOW,
+        }
+    }
+}
+
+/// EEG chart time windows offered by `[` / `]`, in seconds.
+pub const EEG_WINDOWS_SECS: [f64; 5] = [1.0, 2.0, 4.0, 8.0, 16.0];
+
+/// EEG chart vertical ranges offered by `+` / `-`, in ±µV.
+pub const EEG_SCALES_UV: [f64; 8] = [10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 2000.0];
+
+/// How the EEG waveforms are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EegChart {
+    /// Seconds of signal shown, newest at the right edge.
+    pub window_secs: f64,
+    /// Half of the vertical range, in µV around each channel's mean.
+    pub scale_uv: f64,
+}
+
+impl Default for EegChart {
+    fn default() -> Self {
+        Self {
+            window_secs: 4.0,
+            scale_uv: 100.0,
+        }
+    }
+}
+
+impl EegChart {
+    /// Show the next longer (`longer`) or shorter time window.
+    pub fn step_window(&mut self, longer: bool) {
+        self.window_secs = step(&EEG_WINDOWS_SECS, self.window_secs, longer);
+    }
+
+    /// Zoom out (`wider`) or in to the next vertical range.
+    pub fn step_scale(&mut self, wider: bool) {
+        self.scale_uv = step(&EEG_SCALES_UV, self.scale_uv, wider);
+    }
+}
+
+/// The first option above (`up`) or below `current` in ascending
+/// `options`, staying at the ends.
+fn step(options: &[f64], current: f64, up: bool) -> f64 {
+    let next = if up {
+        options.iter().find(|&&option| option > current)
+    } else {
+        options.iter().rev().find(|&&option| option < current)
+    };
+    next.copied().unwrap_or(current)
+}
+
+/// Colour thresholds for contact quality, signal, and battery.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QualityThresholds {
+    /// Quality (0.0–1.0) above which a channel is shown green.
+    pub good: f32,
+    /// Quality (0.0–1.0) above which a channel is shown yellow.
+    pub fair: f32,
+    /// Battery percentage at or below which the battery is shown red.
+    pub battery_low: u8,
+    /// Battery percentage at or below which the battery is shown yellow.
+    pub battery_warn: u8,
+}
+
+impl Default for QualityThresholds {
+    fn default() -> Self {
+        Self {
+            good: 0.7,
+            fair: 0.3,
+            battery_low: 15,
+            battery_warn: 40,
+        }
+    }
+}
+
+/// TUI state persisted across runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    /// Headset connected in the previous run.
+    pub last_headset: Option<String>,
+    /// Streams subscribed after connecting. `dev` is always subscribed
+    /// because the status bar needs it.
+    pub streams: Vec<StreamType>,
+    /// View shown in the Streams tab.
+    pub stream_view: StreamView,
+    /// Chart window lengths.
+    pub chart_windows: ChartWindows,
+    /// EEG chart time window and gain.
+    pub eeg_chart: EegChart,
+    /// Colour thresholds.
+    pub quality: QualityThresholds,
+    /// Title given to records started with `R`.
+    pub record_title: String,
+    /// Labels of the markers injected with keys 1–9 on the Records tab,
+    /// in key order. Keys without a label use `marker`.
+    pub marker_labels: Vec<String>,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            last_headset: None,
+            streams: vec![
+                StreamType::Metrics,
+                StreamType::Eeg,
+                StreamType::Motion,
+                StreamType::BandPower,
+            ],
+            stream_view: StreamView::Eeg,
+            chart_windows: ChartWindows::default(),
+            eeg_chart: EegChart::default(),
+            quality: QualityThresholds::default(),
+            record_title: "emotiv-cortex-tui".to_string(),
+            marker_labels: Vec::new(),
+        }
+    }
+}
+
+impl Preferences {
+    /// Default location of the preferences file, if the config directory
+    /// is known.
+    pub fn default_path() -> Option<PathBuf> {
+        CortexConfig::user_config_path()
+            .and_then(|p| p.parent().map(|dir| dir.join(PREFS_FILE_NAME)))
+    }
+
+    /// Load preferences from `path`. A missing file yields defaults; an
+    /// unreadable or malformed one yields defaults plus a warning.
+    pub fn load(path: &Path) -> (Self, Option<String>) {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return (Self::default(), None),
+            Err(e) => {
+                return (
+                    Self::default(),
+                    Some(format!("Could not read {}: {e}", path.display())),
+                );
+            }
+        };
+        match serde_json::from_str::<Self>(&contents) {
+            Ok(prefs) => (prefs.sanitized(), None),
+            Err(e) => (
+                Self::default(),
+                Some(format!(
+                    "Ignoring malformed preferences in {}: {e}",
+                    path.display()
+                )),
+            ),
+        }
+    }
+
+    /// Write preferences to `path`, creating the parent directory.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, json + "\n")
+    }
+
+    /// Label of the marker injected with key `number` (1–9).
+    pub fn marker_label(&self, number: usize) -> &str {
+        number
+            .checked_sub(1)
+            .and_then(|i| self.marker_labels.get(i))
+            .filter(|label| !label.is_empty())
+            .map_or("marker", String::as_str)
+    }
+
+    /// Clamp hand-edited values into usable ranges.
+    fn sanitized(mut self) -> Self {
+        for window in [
+            &mut self.chart_windows.eeg,
+            &mut self.chart_windows.motion,
+            &mut self.chart_windows.band_power,
+        ] {
+            *window = (*window).clamp(2, MAX_CHART_WINDOW);
+        }
+        let eeg = &mut self.eeg_chart;
+        eeg.window_secs = eeg
+            .window_secs
+            .clamp(EEG_WINDOWS_SECS[0], EEG_WINDOWS_SECS[4]);
+        eeg.scale_uv = eeg.scale_uv.clamp(EEG_SCALES_UV[0], EEG_SCALES_UV[7]);
+        let q = &mut self.quality;
+        q.good = q.good.clamp(0.0, 1.0);
+        q.fair = q.fair.clamp(0.0, q.good);
+        q.battery_warn = q.battery_warn.min(100);
+        q.battery_low = q.battery_low.min(q.battery_warn);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_file_fills_defaults_and_clamps() {
+        let dir = std::env::temp_dir().join(format!("emotiv-tui-prefs-{}", std::process::id()));
+        let path = dir.join(PREFS_FILE_NAME);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            &path,
+            r#"{"last_headset": "INSIGHT-1", "streams": ["eeg"], "chart_windows": {"eeg": 0}}"#,
+        )
+        .unwrap();
+
+        let (prefs, warning) = Preferences::load(&path);
+        assert!(warning.is_none());
+        assert_eq!(prefs.last_headset.as_deref(), Some("INSIGHT-1"));
+        assert_eq!(prefs.streams, vec![StreamType::Eeg]);
+        assert_eq!(prefs.chart_windows.eeg, 2);
+        assert_eq!(prefs.chart_windows.motion, DEFAULT_CHART_WINDOW);
+        assert_eq!(prefs.marker_label(1), "marker");
+
+        prefs.save(&path).unwrap();
+        assert_eq!(Preferences::load(&path).0, prefs);
+
+        std::fs::write(&path, "not json").unwrap();
+        let (prefs, warning) = Preferences::load(&path);
+        assert_eq!(prefs, Preferences::default());
+        assert!(warning.is_some());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn marker_labels_fall_back_per_key() {
+        let prefs = Preferences {
+            marker_labels: vec!["stimulus".into(), String::new()],
+            ..Preferences::default()
+        };
+        assert_eq!(prefs.marker_label(1), "stimulus");
+        assert_eq!(prefs.marker_label(2), "marker");
+        assert_eq!(prefs.marker_label(9), "marker");
+    }
+
+    #[test]
+    fn eeg_chart_steps_through_options() {
+        let mut chart = EegChart::default();
+        chart.step_window(true);
+        assert!((chart.window_secs - 8.0).abs() < f64::EPSILON);
+        chart.step_window(true);
+        chart.step_window(true);
+        assert!((chart.window_secs - 16.0).abs() < f64::EPSILON);
+
+        chart.step_scale(false);
+        assert!((chart.scale_uv - 50.0).abs() < f64::EPSILON);
+        // A hand-edited value between options snaps to a neighbour.
+        chart.scale_uv = 150.0;
+        chart.step_scale(true);
+        assert!((chart.scale_uv - 200.0).abs() < f64::EPSILON);
+    }
+}
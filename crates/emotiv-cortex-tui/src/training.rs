@@ -0,0 +1,358 @@
+//! Training tab state — the mental command / facial expression training
+//! lifecycle driven from the TUI.
+//!
+//! Cortex reports training progress on the `sys` stream as
+//! `[detection, event]` pairs such as `["mentalCommand", "MC_Succeeded"]`.
+//! [`TrainingEvent::parse`] turns those into events and
+//! [`Training::apply`] advances the tab's [`TrainingPhase`]: Enter moves
+//! `Idle` to `Starting`, `Started` to `Running`, `Succeeded` to
+//! `AwaitingDecision`, `y` / `n` to `Deciding`, and `Completed` or
+//! `Rejected` back to `Idle`. `Failed` and `Reset` return to `Idle` from
+//! any phase.
+
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use emotiv_cortex_v2::protocol::streams::SysEvent;
+use emotiv_cortex_v2::protocol::training::{DetectionType, TrainedSignatureActions};
+
+/// Maximum number of `sys` events listed on the Training tab.
+const EVENT_CAP: usize = 20;
+
+/// Mental command actions offered before `getDetectionInfo` answers.
+const MENTAL_COMMAND_ACTIONS: &[&str] =
+    &["neutral", "push", "pull", "lift", "drop", "left", "right"];
+
+/// Facial expression actions offered before `getDetectionInfo` answers.
+const FACIAL_EXPRESSION_ACTIONS: &[&str] = &["neutral", "smile", "frown", "surprise", "clench"];
+
+// ─── Detection ───────────────────────────────────────────────────────────
+
+/// Detection being trained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Detection {
+    MentalCommand,
+    FacialExpression,
+}
+
+impl Detection {
+    pub fn label(self) -> &'static str {
+        match self {
+            Detection::MentalCommand => "Mental command",
+            Detection::FacialExpression => "Facial expression",
+        }
+    }
+
+    pub fn toggle(self) -> Self {
+        match self {
+            Detection::MentalCommand => Detection::FacialExpression,
+            Detection::FacialExpression => Detection::MentalCommand,
+        }
+    }
+
+    pub fn protocol(self) -> DetectionType {
+        match self {
+            Detection::MentalCommand => DetectionType::MentalCommand,
+            Detection::FacialExpression => DetectionType::FacialExpression,
+        }
+    }
+
+    fn default_actions(self) -> Vec<String> {
+        let actions = match self {
+            Detection::MentalCommand => MENTAL_COMMAND_ACTIONS,
+            Detection::FacialExpression => FACIAL_EXPRESSION_ACTIONS,
+        };
+        actions.iter().map(ToString::to_string).collect()
+    }
+
+    /// Prefix of this detection's `sys` event names.
+    fn event_prefix(self) -> &'static str {
+        match self {
+            Detection::MentalCommand => "MC_",
+            Detection::FacialExpression => "FE_",
+        }
+    }
+}
+
+// ─── Events ──────────────────────────────────────────────────────────────
+
+/// A training event from the `sys` stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrainingEvent {
+    Started,
+    Succeeded,
+    Failed,
+    Completed,
+    Rejected,
+    Reset,
+    DataErased,
+    /// Any other event of the detection, e.g. `AutoReject`.
+    Other(String),
+}
+
+impl TrainingEvent {
+    /// Parse a `sys` event into its detection and training event.
+    ///
+    /// Returns `None` for `sys` events that are not about training.
+    pub fn parse(event: &SysEvent) -> Option<(Detection, Self)> {
+        let name = event.sys.get(1)?.as_str()?;
+        let detection = [Detection::MentalCommand, Detection::FacialExpression]
+            .into_iter()
+            .find(|d| name.starts_with(d.event_prefix()))?;
+        let event = match &name[detection.event_prefix().len()..] {
+            "Started" => Self::Started,
+            "Succeeded" => Self::Succeeded,
+            "Failed" => Self::Failed,
+            "Completed" => Self::Completed,
+            "Rejected" => Self::Rejected,
+            "Reset" => Self::Reset,
+            "DataErased" => Self::DataErased,
+            other => Self::Other(other.to_string()),
+        };
+        Some((detection, event))
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            Self::Started => "Started",
+            Self::Succeeded => "Succeeded",
+            Self::Failed => "Failed",
+            Self::Completed => "Completed",
+            Self::Rejected => "Rejected",
+            Self::Reset => "Reset",
+            Self::DataErased => "DataErased",
+            Self::Other(name) => name,
+        }
+    }
+}
+
+// ─── State ───────────────────────────────────────────────────────────────
+
+/// Where the current training is in its lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrainingPhase {
+    /// No training; Enter starts one.
+    Idle,
+    /// `training start` sent, waiting for `Started`.
+    Starting,
+    /// Recording the action (about 8 s).
+    Running { since: Instant },
+    /// `Succeeded` received; waiting for the user to accept or reject.
+    AwaitingDecision,
+    /// `accept` / `reject` sent, waiting for `Completed` / `Rejected`.
+    Deciding,
+}
+
+/// A `sys` training event as listed on the tab.
+#[derive(Debug, Clone)]
+pub struct TrainingLogEntry {
+    pub at: Instant,
+    pub detection: Detection,
+    pub event: TrainingEvent,
+}
+
+/// All Training tab state.
+#[derive(Debug)]
+pub struct Training {
+    pub detection: Detection,
+    /// Actions of `detection`, from `getDetectionInfo` once fetched.
+    pub actions: Vec<String>,
+    pub selected: usize,
+    pub phase: TrainingPhase,
+    /// Action of the training in progress.
+    pub action: Option<String>,
+    /// Trained actions of `detection` in the session's profile.
+    pub trained: Option<TrainedSignatureActions>,
+    /// Recent `sys` training events, newest last.
+    pub events: VecDeque<TrainingLogEntry>,
+    /// Whether the `sys` stream is subscribed on the current session.
+    pub sys_subscribed: bool,
+}
+
+impl Default for Training {
+    fn default() -> Self {
+        Self::new(Detection::MentalCommand)
+    }
+}
+
+impl Training {
+    pub fn new(detection: Detection) -> Self {
+        Self {
+            detection,
+            actions: detection.default_actions(),
+            selected: 0,
+            phase: TrainingPhase::Idle,
+            action: None,
+            trained: None,
+            events: VecDeque::with_capacity(EVENT_CAP),
+            sys_subscribed: false,
+        }
+    }
+
+    /// Reset everything tied to the session, keeping the detection.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.detection);
+    }
+
+    /// Switch to the other detection type. Only allowed while idle.
+    pub fn toggle_detection(&mut self) -> bool {
+        if self.phase != TrainingPhase::Idle {
+            return false;
+        }
+        let sys_subscribed = self.sys_subscribed;
+        let events = std::mem::take(&mut self.events);
+        *self = Self::new(self.detection.toggle());
+        self.sys_subscribed = sys_subscribed;
+        self.events = events;
+        true
+    }
+
+    pub fn selected_action(&self) -> Option<&str> {
+        self.actions.get(self.selected).map(String::as_str)
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.actions.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Times `action` has been trained, if the counts are known.
+    pub fn times(&self, action: &str) -> Option<u32> {
+        let trained = self.trained.as_ref()?;
+        Some(
+            trained
+                .trained_actions
+                .iter()
+                .find(|a| a.action == action)
+                .map_or(0, |a| a.times),
+        )
+    }
+
+    /// Apply fetched actions and trained counts for `detection`.
+    ///
+    /// Ignored if the user has switched detection since the request.
+    pub fn set_info(
+        &mut self,
+        detection: Detection,
+        actions: Vec<String>,
+        trained: TrainedSignatureActions,
+    ) {
+        if detection != self.detection {
+            return;
+        }
+        if !actions.is_empty() && self.phase == TrainingPhase::Idle {
+            let selected = self.selected_action().map(ToString::to_string);
+            self.selected = selected
+                .and_then(|s| actions.iter().position(|a| *a == s))
+                .unwrap_or(0);
+            self.actions = actions;
+        }
+        self.trained = Some(trained);
+    }
+
+    /// Record a `sys` training event and advance the phase.
+    ///
+    /// Returns `true` when the profile's trained counts may have changed.
+    pub fn apply(&mut self, detection: Detection, event: TrainingEvent) -> bool {
+        let changed = detection == self.detection && self.advance(&event);
+        if self.events.len() >= EVENT_CAP {
+            self.events.pop_front();
+        }
+        self.events.push_back(TrainingLogEntry {
+            at: Instant::now(),
+            detection,
+            event,
+        });
+        changed
+    }
+
+    fn advance(&mut self, event: &TrainingEvent) -> bool {
+        match event {
+            TrainingEvent::Started => {
+                self.phase = TrainingPhase::Running {
+                    since: Instant::now(),
+                };
+                false
+            }
+            TrainingEvent::Succeeded => {
+                self.phase = TrainingPhase::AwaitingDecision;
+                false
+            }
+            TrainingEvent::Failed | TrainingEvent::Rejected | TrainingEvent::Reset => {
+                self.finish();
+                false
+            }
+            TrainingEvent::Completed | TrainingEvent::DataErased => {
+                self.finish();
+                true
+            }
+            TrainingEvent::Other(_) => false,
+        }
+    }
+
+    /// Return to idle after the training ended or a request failed.
+    pub fn finish(&mut self) {
+        self.phase = TrainingPhase::Idle;
+        self.action = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sys(detection: &str, event: &str) -> SysEvent {
+        SysEvent {
+            sid: "session-1".into(),
+            time: 0.0,
+            sys: vec![detection.into(), event.into()],
+        }
+    }
+
+    #[test]
+    fn parses_training_events_of_both_detections() {
+        assert_eq!(
+            TrainingEvent::parse(&sys("mentalCommand", "MC_Succeeded")),
+            Some((Detection::MentalCommand, TrainingEvent::Succeeded))
+        );
+        assert_eq!(
+            TrainingEvent::parse(&sys("facialExpression", "FE_AutoReject")),
+            Some((
+                Detection::FacialExpression,
+                TrainingEvent::Other("AutoReject".into())
+            ))
+        );
+        assert_eq!(
+            TrainingEvent::parse(&sys("headset", "HeadsetConnected")),
+            None
+        );
+    }
+
+    #[test]
+    fn lifecycle_returns_to_idle_and_requests_counts() {
+        let mut training = Training {
+            phase: TrainingPhase::Starting,
+            ..Training::default()
+        };
+
+        assert!(!training.apply(Detection::MentalCommand, TrainingEvent::Started));
+        assert!(matches!(training.phase, TrainingPhase::Running { .. }));
+        // Events of the other detection are listed but do not move the phase.
+        training.apply(Detection::FacialExpression, TrainingEvent::Failed);
+        assert!(matches!(training.phase, TrainingPhase::Running { .. }));
+
+        training.apply(Detection::MentalCommand, TrainingEvent::Succeeded);
+        assert_eq!(training.phase, TrainingPhase::AwaitingDecision);
+        assert!(!training.toggle_detection());
+
+        training.phase = TrainingPhase::Deciding;
+        assert!(training.apply(Detection::MentalCommand, TrainingEvent::Completed));
+        assert_eq!(training.phase, TrainingPhase::Idle);
+        assert_eq!(training.events.len(), 4);
+    }
+}
@@ -6,6 +6,8 @@ use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Gauge, Paragraph};
 
+use emotiv_cortex_v2::stream_stats::StreamStatsSnapshot;
+
 use crate::app::{App, ConnectionPhase};
 
 /// Render the dashboard tab content.
@@ -83,6 +85,18 @@ fn draw_session_info(frame: &mut Frame, app: &App, area: Rect) {
         }
     }
 
+    // Per-stream rate, jitter and loss
+    if !app.stream_stats.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            " Stream Stats:",
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+        for (kind, stats) in &app.stream_stats {
+            lines.push(stream_stats_line(kind, &stats.snapshot()));
+        }
+    }
+
     lines.push(Line::from(""));
 
     // Mental command (latest)
@@ -92,7 +106,7 @@ fn draw_session_info(frame: &mut Frame, app: &App, area: Rect) {
                 " Mental Cmd: ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::styled(&mc.action, Style::default().fg(Color::Magenta)),
+            Span::styled(mc.action.as_str(), Style::default().fg(Color::Magenta)),
             Span::raw(format!(" ({:.2})", mc.power)),
         ]));
     }
@@ -120,6 +134,37 @@ fn draw_session_info(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(paragraph, area);
 }
 
+/// One stream's rate, jitter, loss, and idle time.
+fn stream_stats_line(kind: &str, stats: &StreamStatsSnapshot) -> Line<'static> {
+    let rate = stats
+        .effective_rate_hz
+        .map_or_else(|| "—".to_string(), |hz| format!("{hz:.1} Hz"));
+    let jitter = stats
+        .jitter_ms
+        .map_or_else(|| "—".to_string(), |ms| format!("±{ms:.1} ms"));
+    let idle = stats.idle_for().unwrap_or_default();
+    let idle_style = if idle.as_secs() >= 2 {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+    let mut spans = vec![
+        Span::raw(format!("   {kind:<4} {rate:>9}  {jitter:>9}")),
+        Span::styled(format!("  {:.1}s ago", idle.as_secs_f64()), idle_style),
+    ];
+    if stats.lost_samples > 0 {
+        spans.push(Span::styled(
+            format!(
+                "  lost {} ({:.1}%)",
+                stats.lost_samples,
+                stats.loss_ratio() * 100.0
+            ),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    Line::from(spans)
+}
+
 /// Right panel: performance metrics as horizontal gauge bars.
 fn draw_metrics_panel(frame: &mut Frame, app: &App, area: Rect) {
     let block = Block::default()
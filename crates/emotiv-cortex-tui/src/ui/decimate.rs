@@ -0,0 +1,83 @@
+//! Min/max decimation for line charts.
+//!
+//! A 16 s EEG window at 256 Hz is 4096 samples per channel, far more than
+//! a terminal row can show. [`min_max`] reduces a series to two points per
+//! horizontal bucket (the bucket's minimum and maximum, in time order), so
+//! the chart draws the same envelope, spikes included, while the work per
+//! frame stays proportional to the chart width rather than the window
+//! length.
+
+use crate::util::usize_to_f64;
+
+/// Reduce `values` to at most `2 * buckets` `(index, value)` points.
+///
+/// Indices are positions in `values`, so callers can map them to time.
+/// Series that already fit are returned point for point.
+pub fn min_max<I>(values: I, buckets: usize) -> Vec<(f64, f64)>
+where
+    I: ExactSizeIterator<Item = f64>,
+{
+    let len = values.len();
+    let buckets = buckets.max(1);
+    if len <= 2 * buckets {
+        return values
+            .enumerate()
+            .map(|(i, value)| (usize_to_f64(i), value))
+            .collect();
+    }
+
+    let mut points = Vec::with_capacity(2 * buckets);
+    let mut bucket = 0;
+    let mut low = (0, f64::INFINITY);
+    let mut high = (0, f64::NEG_INFINITY);
+    for (i, value) in values.enumerate() {
+        // Bucket `b` covers indices [b * len / buckets, (b + 1) * len / buckets).
+        if i * buckets >= (bucket + 1) * len {
+            push_extremes(&mut points, low, high);
+            bucket += 1;
+            low = (i, f64::INFINITY);
+            high = (i, f64::NEG_INFINITY);
+        }
+        if value < low.1 {
+            low = (i, value);
+        }
+        if value > high.1 {
+            high = (i, value);
+        }
+    }
+    push_extremes(&mut points, low, high);
+    points
+}
+
+/// Append a bucket's extremes in time order.
+fn push_extremes(points: &mut Vec<(f64, f64)>, low: (usize, f64), high: (usize, f64)) {
+    let (first, second) = if low.0 <= high.0 {
+        (low, high)
+    } else {
+        (high, low)
+    };
+    points.push((usize_to_f64(first.0), first.1));
+    if second.0 != first.0 {
+        points.push((usize_to_f64(second.0), second.1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_short_series_and_each_buckets_extremes() {
+        let short = min_max([1.0, 2.0, 3.0].into_iter(), 2);
+        assert_eq!(short, [(0.0, 1.0), (1.0, 2.0), (2.0, 3.0)]);
+
+        // Two buckets of five: a spike in each must survive.
+        let values = [0.0, 0.0, 9.0, 0.0, 1.0, 0.0, -7.0, 0.0, 0.0, 2.0];
+        let points = min_max(values.into_iter(), 2);
+        assert_eq!(points, [(0.0, 0.0), (2.0, 9.0), (6.0, -7.0), (9.0, 2.0)]);
+
+        let long = min_max((0..4096).map(f64::from), 100);
+        assert!(long.len() <= 200);
+        assert_eq!(long.last(), Some(&(4095.0, 4095.0)));
+    }
+}
@@ -11,6 +11,7 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Gauge, List, ListItem, Paragraph};
 
 use crate::app::{App, ConnectionPhase};
+use crate::prefs::QualityThresholds;
 
 /// Render the device tab.
 pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
@@ -228,7 +229,7 @@ fn draw_contact_quality(frame: &mut Frame, app: &App, area: Rect) {
 
     for (i, &quality) in dq.channel_quality.iter().enumerate() {
         let label = channel_names.get(i).map_or("?", |s| s.as_str());
-        let color = quality_color(quality);
+        let color = quality_color(quality, &app.prefs.quality);
 
         let label_line = Line::from(vec![
             Span::raw("  "),
@@ -252,11 +253,12 @@ fn draw_contact_quality(frame: &mut Frame, app: &App, area: Rect) {
     }
 }
 
-/// Map contact quality (0.0–1.0) to a color.
-fn quality_color(q: f32) -> Color {
-    if q > 0.7 {
+/// Map contact / signal quality (0.0–1.0) to a color using the
+/// operator's thresholds.
+pub(crate) fn quality_color(q: f32, thresholds: &QualityThresholds) -> Color {
+    if q > thresholds.good {
         Color::Green
-    } else if q > 0.3 {
+    } else if q > thresholds.fair {
         Color::Yellow
     } else {
         Color::Red
@@ -26,10 +26,18 @@ pub fn draw(frame: &mut Frame, _app: &App) {
         key_line("q / Ctrl+C", "Quit the application"),
         key_line("Tab", "Next tab"),
         key_line("Shift+Tab", "Previous tab"),
-        key_line("1-5", "Jump to tab by number"),
+        key_line("1-9", "Jump to tab by number (marker on Records tab)"),
         key_line("↑ / k", "Scroll up"),
         key_line("↓ / j", "Scroll down"),
         key_line("v", "Cycle stream view (Streams tab)"),
+        key_line("[ / ]", "Shorter / longer EEG time window"),
+        key_line("- / +", "Lower / higher EEG gain"),
+        key_line("R", "Start / stop recording"),
+        key_line("1-9", "Inject numbered marker (Records tab)"),
+        key_line("t", "Switch mental command / facial (Training tab)"),
+        key_line("Enter", "Start training selected action (Training tab)"),
+        key_line("y / n", "Accept / reject succeeded training"),
+        key_line("c", "Cancel running training"),
         key_line("Enter", "Connect to selected headset (Device tab)"),
         key_line("r", "Refresh headset list (Device tab)"),
         key_line("l", "Toggle LSL streaming (LSL tab)"),
@@ -142,7 +142,14 @@ fn draw_status_summary(
     // Status + uptime
     lines.push(Line::from(vec![
         Span::styled("  Status: ", Style::default().add_modifier(Modifier::BOLD)),
-        Span::styled("Active ▶", Style::default().fg(Color::Green)),
+        if handle.forwarders_down() == 0 {
+            Span::styled("Active ▶", Style::default().fg(Color::Green))
+        } else {
+            Span::styled(
+                format!("Degraded ({} down)", handle.forwarders_down()),
+                Style::default().fg(Color::Red),
+            )
+        },
         Span::raw("   "),
         Span::styled("Uptime: ", Style::default().add_modifier(Modifier::BOLD)),
         Span::raw(time_str),
@@ -166,9 +173,39 @@ fn draw_status_summary(
         "  Sample Counts:",
         Style::default().add_modifier(Modifier::BOLD),
     )));
-    for (name, count) in handle.sample_counts.iter() {
+    for (i, (name, count)) in handle.sample_counts.iter().enumerate() {
         let n = count.load(std::sync::atomic::Ordering::Relaxed);
-        lines.push(Line::from(format!("    {name:<25} {n:>12} samples")));
+        let mut spans = vec![Span::raw(format!("    {name:<25} {n:>12} samples"))];
+        if let Some((_, health)) = handle.forwarder_health.get(i) {
+            if health.is_up() {
+                spans.push(Span::styled("  ● up", Style::default().fg(Color::Green)));
+            } else {
+                spans.push(Span::styled(
+                    format!(
+                        "  ▼ down, source still flowing ({} dropped)",
+                        health.dropped_while_down()
+                    ),
+                    Style::default().fg(Color::Red),
+                ));
+            }
+            if health.restarts() > 0 {
+                spans.push(Span::styled(
+                    format!("  restarts: {}", health.restarts()),
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+        }
+        lines.push(Line::from(spans));
+        if let Some((_, health)) = handle.forwarder_health.get(i) {
+            if !health.is_up() {
+                if let Some(err) = health.last_error() {
+                    lines.push(Line::from(Span::styled(
+                        format!("      {err}"),
+                        Style::default().fg(Color::DarkGray),
+                    )));
+                }
+            }
+        }
     }
 
     lines.push(Line::from(""));
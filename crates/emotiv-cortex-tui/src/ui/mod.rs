@@ -4,12 +4,15 @@
 //! footer into the full-screen layout drawn each frame.
 
 pub mod dashboard;
+mod decimate;
 pub mod device;
 pub mod help;
 pub mod log;
+pub mod records;
 pub mod status_bar;
 pub mod streams;
 pub mod tabs;
+pub mod training;
 
 #[cfg(all(feature = "lsl", not(target_os = "linux")))]
 pub mod lsl;
@@ -45,6 +48,8 @@ pub fn draw(frame: &mut Frame, app: &App) {
     match app.active_tab {
         crate::app::Tab::Dashboard => dashboard::draw(frame, app, chunks[2]),
         crate::app::Tab::Streams => streams::draw(frame, app, chunks[2]),
+        crate::app::Tab::Records => records::draw(frame, app, chunks[2]),
+        crate::app::Tab::Training => training::draw(frame, app, chunks[2]),
         #[cfg(all(feature = "lsl", not(target_os = "linux")))]
         crate::app::Tab::Lsl => lsl::draw(frame, app, chunks[2]),
         crate::app::Tab::Device => device::draw(frame, app, chunks[2]),
@@ -71,15 +76,48 @@ fn draw_key_help(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
         Span::raw(" Quit  "),
         Span::styled("Tab", Style::default().fg(Color::Yellow)),
         Span::raw(" Switch  "),
-        Span::styled("1-5", Style::default().fg(Color::Yellow)),
-        Span::raw(" Jump  "),
-        Span::styled("↑↓", Style::default().fg(Color::Yellow)),
-        Span::raw(" Scroll  "),
     ];
 
+    if app.active_tab == crate::app::Tab::Records {
+        spans.push(Span::styled("1-9", Style::default().fg(Color::Yellow)));
+        spans.push(Span::raw(" Marker  "));
+    } else {
+        spans.push(Span::styled(
+            format!("1-{}", crate::app::Tab::all().len()),
+            Style::default().fg(Color::Yellow),
+        ));
+        spans.push(Span::raw(" Jump  "));
+    }
+    spans.push(Span::styled("↑↓", Style::default().fg(Color::Yellow)));
+    spans.push(Span::raw(" Scroll  "));
+
+    if app.phase == crate::app::ConnectionPhase::Ready {
+        spans.push(Span::styled("R", Style::default().fg(Color::Yellow)));
+        spans.push(Span::raw(if app.recording.active.is_some() {
+            " Stop rec  "
+        } else {
+            " Record  "
+        }));
+    }
+
     if app.active_tab == crate::app::Tab::Streams {
         spans.push(Span::styled("v", Style::default().fg(Color::Yellow)));
         spans.push(Span::raw(" View  "));
+        if app.stream_view == crate::app::StreamView::Eeg {
+            spans.push(Span::styled("[ ]", Style::default().fg(Color::Yellow)));
+            spans.push(Span::raw(" Window  "));
+            spans.push(Span::styled("- +", Style::default().fg(Color::Yellow)));
+            spans.push(Span::raw(" Gain  "));
+        }
+    }
+
+    if app.active_tab == crate::app::Tab::Training {
+        spans.push(Span::styled("Enter", Style::default().fg(Color::Yellow)));
+        spans.push(Span::raw(" Train  "));
+        spans.push(Span::styled("y/n", Style::default().fg(Color::Yellow)));
+        spans.push(Span::raw(" Accept/Reject  "));
+        spans.push(Span::styled("t", Style::default().fg(Color::Yellow)));
+        spans.push(Span::raw(" Detection  "));
     }
 
     if app.active_tab == crate::app::Tab::Device {
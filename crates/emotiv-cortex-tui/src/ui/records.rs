@@ -0,0 +1,133 @@
+//! Records tab — recording state, marker key bindings, and the markers
+//! injected into the active record.
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use crate::app::{App, ConnectionPhase};
+
+/// Render the records tab.
+pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(6), Constraint::Min(4)])
+        .split(area);
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(30), Constraint::Min(20)])
+        .split(chunks[1]);
+
+    draw_state(frame, app, chunks[0]);
+    draw_bindings(frame, app, bottom[0]);
+    draw_markers(frame, app, bottom[1]);
+}
+
+/// Recording state: UUID, title, and elapsed time.
+fn draw_state(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default().title(" Recording ").borders(Borders::ALL);
+    let label = Style::default().fg(Color::DarkGray);
+
+    let lines = if let Some(record) = &app.recording.active {
+        vec![
+            Line::from(vec![
+                Span::styled(
+                    " ● REC ",
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(super::status_bar::format_duration(record.started.elapsed())),
+            ]),
+            Line::from(vec![
+                Span::styled(" UUID   ", label),
+                Span::styled(record.uuid.as_str(), Style::default().fg(Color::Cyan)),
+            ]),
+            Line::from(vec![
+                Span::styled(" Title  ", label),
+                Span::raw(record.title.as_str()),
+            ]),
+            Line::from(vec![
+                Span::styled(" R", Style::default().fg(Color::Yellow)),
+                Span::styled(" to stop", label),
+            ]),
+        ]
+    } else if app.recording.pending {
+        vec![Line::styled(" Waiting for Cortex…", label)]
+    } else if app.phase == ConnectionPhase::Ready {
+        vec![
+            Line::styled(" Not recording", label),
+            Line::from(vec![
+                Span::styled(" R", Style::default().fg(Color::Yellow)),
+                Span::styled(format!(" to start \"{}\"", app.prefs.record_title), label),
+            ]),
+        ]
+    } else {
+        vec![Line::styled(
+            " Connect a headset on the Device tab to record",
+            label,
+        )]
+    };
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Keys 1–9 and the marker label each injects.
+fn draw_bindings(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .title(" Marker keys ")
+        .borders(Borders::ALL);
+    let lines: Vec<Line<'_>> = (1..=9)
+        .map(|number| {
+            Line::from(vec![
+                Span::styled(format!(" {number} "), Style::default().fg(Color::Yellow)),
+                Span::raw(app.prefs.marker_label(number)),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Markers injected into the active record, newest first.
+fn draw_markers(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .title(format!(" Markers ({}) ", app.recording.markers.len()))
+        .borders(Borders::ALL);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    if app.recording.markers.is_empty() {
+        let msg = Paragraph::new("  No markers in this record.")
+            .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(msg, inner);
+        return;
+    }
+
+    let offset = app.recording.active.as_ref().map(|record| record.started);
+    let lines: Vec<Line<'_>> = app
+        .recording
+        .markers
+        .iter()
+        .rev()
+        .take(inner.height as usize)
+        .map(|marker| {
+            // Position within the record, like the record's own clock.
+            let at = offset.map_or_else(String::new, |started| {
+                super::status_bar::format_duration(marker.at.saturating_duration_since(started))
+            });
+            Line::from(vec![
+                Span::styled(format!(" {at:>8} "), Style::default().fg(Color::DarkGray)),
+                Span::styled(
+                    format!("{} ", marker.value),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::raw(marker.label.as_str()),
+                Span::styled(
+                    format!("  {}", marker.uuid),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+}
@@ -41,10 +41,13 @@ pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
             // Battery
             if let Some(ref dq) = app.device_quality {
                 let pct = dq.battery_percent;
-                let color = match pct {
-                    0..=15 => Color::Red,
-                    16..=40 => Color::Yellow,
-                    _ => Color::Green,
+                let thresholds = &app.prefs.quality;
+                let color = if pct <= thresholds.battery_low {
+                    Color::Red
+                } else if pct <= thresholds.battery_warn {
+                    Color::Yellow
+                } else {
+                    Color::Green
                 };
                 spans.push(Span::styled(
                     format!("🔋 {pct}%"),
@@ -60,13 +63,7 @@ pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
             if let Some(ref dq) = app.device_quality {
                 let level = dq.overall_quality;
                 let bars = signal_bars(level);
-                let color = if level > 0.7 {
-                    Color::Green
-                } else if level > 0.3 {
-                    Color::Yellow
-                } else {
-                    Color::Red
-                };
+                let color = super::device::quality_color(level, &app.prefs.quality);
                 spans.push(Span::styled(
                     format!("Signal: {bars}"),
                     Style::default().fg(color),
@@ -87,6 +84,19 @@ pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
                 spans.push(Span::styled("○", Style::default().fg(Color::DarkGray)));
             }
 
+            // Recording indicator
+            if let Some(ref record) = app.recording.active {
+                spans.push(Span::raw(" │ "));
+                spans.push(Span::styled(
+                    format!(
+                        "● REC {} {}",
+                        short_uuid(&record.uuid),
+                        format_duration(record.started.elapsed())
+                    ),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ));
+            }
+
             // LSL indicator
             #[cfg(all(feature = "lsl", not(target_os = "linux")))]
             if let Some(ref handle) = app.lsl_streaming {
@@ -114,6 +124,11 @@ pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(bar, area);
 }
 
+/// First block of a record UUID, enough to tell records apart.
+fn short_uuid(uuid: &str) -> &str {
+    uuid.split('-').next().unwrap_or(uuid)
+}
+
 /// Convert a 0.0–1.0 quality value to a simple bar string.
 fn signal_bars(level: f32) -> &'static str {
     if level > 0.8 {
@@ -130,7 +145,7 @@ fn signal_bars(level: f32) -> &'static str {
 }
 
 /// Format a duration into `HH:MM:SS` or `MM:SS`.
-fn format_duration(d: std::time::Duration) -> String {
+pub(crate) fn format_duration(d: std::time::Duration) -> String {
     let secs = d.as_secs();
     let h = secs / 3600;
     let m = (secs % 3600) / 60;
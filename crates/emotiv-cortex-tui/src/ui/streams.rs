@@ -1,15 +1,20 @@
-//! Streams tab — live sparkline / chart views for EEG, Motion, Band Power.
+//! Streams tab — live chart views for EEG, Motion, Band Power.
 
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{
-    Axis, Bar, BarChart, BarGroup, Block, Borders, Chart, Dataset, GraphType, Paragraph, Sparkline,
+    Axis, Bar, BarChart, BarGroup, Block, Borders, Chart, Dataset, GraphType, Paragraph,
 };
 
+use super::decimate;
 use crate::app::{App, StreamView};
 
+/// Band power samples averaged per bar (about one second at 8 Hz), so the
+/// bars follow the trend instead of flickering with every update.
+const BAND_POWER_AVERAGE: usize = 8;
+
 /// Render the streams tab content.
 pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
     // Header showing current view + how to switch
@@ -42,10 +47,21 @@ pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
     }
 }
 
-/// EEG sparklines — one per channel.
+/// EEG waveforms — one scrolling line chart per channel, newest sample
+/// at the right edge.
+///
+/// Each channel is drawn around its mean over the window, over
+/// ±[`EegChart::scale_uv`] µV, and decimated to the chart width so a long
+/// window costs no more to draw than a short one.
+///
+/// [`EegChart::scale_uv`]: crate::prefs::EegChart::scale_uv
 fn draw_eeg(frame: &mut Frame, app: &App, area: Rect) {
+    let view = app.prefs.eeg_chart;
     let block = Block::default()
-        .title(" EEG Channels ")
+        .title(format!(
+            " EEG Channels — {:.0} s, ±{:.0} µV ",
+            view.window_secs, view.scale_uv
+        ))
         .borders(Borders::ALL);
     let inner = block.inner(area);
     frame.render_widget(block, area);
@@ -89,34 +105,53 @@ fn draw_eeg(frame: &mut Frame, app: &App, area: Rect) {
         Color::Gray,
     ];
 
+    let rate = app.eeg_rate_hz();
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let window = (view.window_secs * rate).ceil() as usize;
+
     for (i, buf) in app.eeg_buffers.iter().enumerate() {
         let label = channel_names.get(i).map_or("?", |s| s.as_str());
-        let color = colors[i % colors.len()];
-
-        // Sparkline needs u64 data — normalize from f64 microvolts
-        // Shift so minimum maps to 0 to avoid negative values
-        let data: Vec<u64> = if buf.is_empty() {
-            vec![]
-        } else {
-            let min = buf.iter().copied().fold(f64::INFINITY, f64::min);
-            let max = buf.iter().copied().fold(f64::NEG_INFINITY, f64::max);
-            let range = (max - min).max(1.0);
-            buf.iter()
-                .map(|&v| {
-                    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-                    {
-                        ((v - min) / range * 100.0) as u64
-                    }
-                })
-                .collect()
+        let row = rows[i];
+        // Label column on the left, waveform in the rest of the row.
+        let label_width = 5.min(row.width);
+        let label_area = Rect {
+            width: label_width,
+            ..row
         };
+        let plot_area = Rect {
+            x: row.x + label_width,
+            width: row.width - label_width,
+            ..row
+        };
+        frame.render_widget(
+            Paragraph::new(label).style(Style::default().fg(colors[i % colors.len()])),
+            label_area,
+        );
 
-        let spark = Sparkline::default()
-            .block(Block::default().title(format!(" {label} ")))
-            .data(&data)
-            .style(Style::default().fg(color));
+        let shown = buf.len().min(window);
+        let start = buf.len() - shown;
+        #[allow(clippy::cast_precision_loss)]
+        let mean = buf.range(start..).sum::<f64>() / shown.max(1) as f64;
+        // Braille markers give two dots per cell horizontally.
+        let buckets = usize::from(plot_area.width) * 2;
+        // Right-align: a partly filled window starts part-way across.
+        #[allow(clippy::cast_precision_loss)]
+        let offset = (window - shown) as f64;
+        let points: Vec<(f64, f64)> =
+            decimate::min_max(buf.range(start..).map(|v| v - mean), buckets)
+                .into_iter()
+                .map(|(x, y)| ((offset + x) / rate, y))
+                .collect();
 
-        frame.render_widget(spark, rows[i]);
+        let dataset = Dataset::default()
+            .marker(ratatui::symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(colors[i % colors.len()]))
+            .data(&points);
+        let chart = Chart::new(vec![dataset])
+            .x_axis(Axis::default().bounds([0.0, view.window_secs]))
+            .y_axis(Axis::default().bounds([-view.scale_uv, view.scale_uv]));
+        frame.render_widget(chart, plot_area);
     }
 }
 
@@ -223,7 +258,8 @@ fn draw_motion_chart(
     frame.render_widget(chart, area);
 }
 
-/// Band power — grouped column chart with channels along the horizontal axis.
+/// Band power — grouped column chart with channels along the horizontal
+/// axis, each bar a band's share of the channel's total power.
 fn draw_band_power(frame: &mut Frame, app: &App, area: Rect) {
     let block = Block::default()
         .title(" Band Power (θ α βL βH γ) ")
@@ -283,7 +319,7 @@ fn draw_band_power(frame: &mut Frame, app: &App, area: Rect) {
                 .get(i)
                 .cloned()
                 .unwrap_or_else(|| format!("Ch{i}"));
-            let latest = buf.back().copied().unwrap_or([0.0; 5]);
+            let latest = recent_mean(buf, BAND_POWER_AVERAGE);
             let total: f32 = latest.iter().sum::<f32>().max(0.001);
 
             let bars: Vec<Bar<'_>> = latest
@@ -326,3 +362,21 @@ fn draw_band_power(frame: &mut Frame, app: &App, area: Rect) {
 
     frame.render_widget(chart, chunks[1]);
 }
+
+/// Per-band mean of the last `count` band power samples.
+fn recent_mean(buf: &std::collections::VecDeque<[f32; 5]>, count: usize) -> [f32; 5] {
+    let recent = buf.iter().rev().take(count);
+    let n = recent.len();
+    let mut mean = [0.0_f32; 5];
+    if n == 0 {
+        return mean;
+    }
+    for powers in recent {
+        for (sum, power) in mean.iter_mut().zip(powers) {
+            *sum += power;
+        }
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let n = n as f32;
+    mean.map(|sum| sum / n)
+}
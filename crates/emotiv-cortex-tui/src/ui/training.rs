@@ -0,0 +1,169 @@
+//! Training tab — action picker with trained counts, the current
+//! training's progress and accept/reject prompt, and recent `sys` events.
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Gauge, Paragraph};
+
+use crate::app::{App, ConnectionPhase};
+use crate::training::{TrainingEvent, TrainingPhase};
+
+/// Nominal length of a Cortex training, for the progress gauge.
+const TRAINING_SECS: f64 = 8.0;
+
+/// Render the training tab.
+pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(5), Constraint::Min(6)])
+        .split(area);
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(36), Constraint::Min(20)])
+        .split(chunks[1]);
+
+    draw_status(frame, app, chunks[0]);
+    draw_actions(frame, app, bottom[0]);
+    draw_events(frame, app, bottom[1]);
+}
+
+/// Current phase, with a progress gauge while recording and the
+/// accept/reject prompt once Cortex reports success.
+fn draw_status(frame: &mut Frame, app: &App, area: Rect) {
+    let training = &app.training;
+    let block = Block::default()
+        .title(format!(" {} training ", training.detection.label()))
+        .borders(Borders::ALL);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let key = Style::default().fg(Color::Yellow);
+    let dim = Style::default().fg(Color::DarkGray);
+    let action = training.action.as_deref().unwrap_or("");
+
+    if let TrainingPhase::Running { since } = training.phase {
+        let elapsed = since.elapsed().as_secs_f64();
+        let gauge = Gauge::default()
+            .gauge_style(Style::default().fg(Color::Cyan))
+            .ratio((elapsed / TRAINING_SECS).clamp(0.0, 1.0))
+            .label(format!(
+                "Training \"{action}\" — {elapsed:.0} s  (c to cancel)"
+            ));
+        frame.render_widget(gauge, inner);
+        return;
+    }
+
+    let line = match training.phase {
+        TrainingPhase::Idle if app.phase != ConnectionPhase::Ready => {
+            Line::styled(" Connect a headset on the Device tab to train", dim)
+        }
+        TrainingPhase::Idle => Line::from(vec![
+            Span::styled(" Enter", key),
+            Span::styled(
+                format!(
+                    " to train \"{}\"  ",
+                    training.selected_action().unwrap_or("")
+                ),
+                dim,
+            ),
+            Span::styled("t", key),
+            Span::styled(" switch detection", dim),
+        ]),
+        TrainingPhase::Starting => Line::styled(format!(" Starting \"{action}\"…"), dim),
+        TrainingPhase::AwaitingDecision => Line::from(vec![
+            Span::styled(
+                format!(" \"{action}\" succeeded. Keep it? "),
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("y", key),
+            Span::raw(" accept  "),
+            Span::styled("n", key),
+            Span::raw(" reject"),
+        ]),
+        TrainingPhase::Deciding => Line::styled(" Saving decision…", dim),
+        TrainingPhase::Running { .. } => Line::default(),
+    };
+    frame.render_widget(Paragraph::new(line), inner);
+}
+
+/// Actions of the detection with their trained counts.
+fn draw_actions(frame: &mut Frame, app: &App, area: Rect) {
+    let training = &app.training;
+    let total = training
+        .trained
+        .as_ref()
+        .map_or_else(|| "--".to_string(), |t| t.total_times_training.to_string());
+    let block = Block::default()
+        .title(format!(" Actions (trained {total}) "))
+        .borders(Borders::ALL);
+
+    let lines: Vec<Line<'_>> = training
+        .actions
+        .iter()
+        .enumerate()
+        .map(|(i, action)| {
+            let selected = i == training.selected;
+            let marker = if selected { "▸ " } else { "  " };
+            let style = if selected {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let times = training
+                .times(action)
+                .map_or_else(|| "--".to_string(), |n| format!("{n}×"));
+            Line::from(vec![
+                Span::styled(format!("{marker}{action:<24}"), style),
+                Span::styled(format!("{times:>5}"), Style::default().fg(Color::DarkGray)),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Recent training events from the `sys` stream, newest first.
+fn draw_events(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default().title(" Events ").borders(Borders::ALL);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    if app.training.events.is_empty() {
+        let msg =
+            Paragraph::new("  No training events yet.").style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(msg, inner);
+        return;
+    }
+
+    let lines: Vec<Line<'_>> = app
+        .training
+        .events
+        .iter()
+        .rev()
+        .take(inner.height as usize)
+        .map(|entry| {
+            let color = match entry.event {
+                TrainingEvent::Succeeded | TrainingEvent::Completed => Color::Green,
+                TrainingEvent::Failed | TrainingEvent::Rejected => Color::Red,
+                _ => Color::White,
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!(" {:>4}s ago ", entry.at.elapsed().as_secs()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    format!("{:<18}", entry.detection.label()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(entry.event.label(), Style::default().fg(color)),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+}
@@ -0,0 +1,21 @@
+//! Numeric conversions shared by the chart and watch renderers.
+//!
+//! The casts are lossy by design — sample indices and counts are far below
+//! the range where `f32`/`f64` lose precision — and keeping them here keeps
+//! the clippy allowances in one place.
+
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn usize_to_f64(value: usize) -> f64 {
+    value as f64
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn usize_to_f32(value: usize) -> f32 {
+    value as f32
+}
+
+/// Truncates toward zero; NaN and negative values become `0`.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub(crate) fn f32_to_usize(value: f32) -> usize {
+    value as usize
+}
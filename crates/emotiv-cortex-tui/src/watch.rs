@@ -0,0 +1,500 @@
+//! Headless `watch` subcommand — a refreshing one-screen text summary.
+//!
+//! For machines where the full-screen dashboard is unavailable or too
+//! heavy (SSH sessions, small SBCs), `watch` subscribes to a few
+//! low-rate streams and redraws a plain-text summary every `--interval`:
+//!
+//! ```text
+//! emotiv-cortex-tui watch --streams dev,met --interval 1s
+//! ```
+//!
+//! The screen is cleared with ANSI escapes only when stdout is a
+//! terminal; otherwise each refresh is appended, separated by a blank
+//! line, so the output can be logged. With the global `--json` flag each
+//! refresh is instead one line of JSON holding the session, the latest
+//! sample of every watched stream (`null` until one arrives), and its
+//! age. Diagnostics go to stderr.
+
+use std::fmt::Write as _;
+use std::io::{IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+use clap::{Args, ValueEnum};
+use crossterm::cursor::MoveTo;
+use crossterm::queue;
+use crossterm::terminal::{Clear, ClearType};
+use emotiv_cortex_v2::band_power::BAND_NAMES;
+use emotiv_cortex_v2::headset::HeadsetModel;
+use emotiv_cortex_v2::protocol::constants::Streams;
+use emotiv_cortex_v2::protocol::streams::{
+    BandPowerData, DeviceQuality, EegQuality, PerformanceMetrics,
+};
+use emotiv_cortex_v2::{CortexClient, CortexConfig, streams};
+use futures_core::Stream;
+use futures_util::StreamExt;
+use futures_util::stream::{BoxStream, select_all};
+use serde::Serialize;
+
+use crate::pipe::{self, BoxError, write_json_line};
+use crate::util::{f32_to_usize, usize_to_f32};
+
+/// Width of the bar graphs, in characters.
+const BAR_WIDTH: usize = 20;
+
+/// A section is marked stale after this many intervals without data.
+const STALE_INTERVALS: u32 = 3;
+
+/// Streams the `watch` summary can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum WatchStream {
+    /// Battery, wireless signal, and contact quality.
+    Dev,
+    /// Per-sensor EEG quality.
+    Eq,
+    /// Performance metrics.
+    Met,
+    /// Band power, averaged over channels.
+    Pow,
+}
+
+impl WatchStream {
+    fn cortex_name(self) -> &'static str {
+        match self {
+            Self::Dev => Streams::DEV,
+            Self::Eq => Streams::EQ,
+            Self::Met => Streams::MET,
+            Self::Pow => Streams::POW,
+        }
+    }
+}
+
+/// Arguments for `emotiv-cortex-tui watch`.
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    /// Comma-separated streams to summarize
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "dev,met")]
+    pub streams: Vec<WatchStream>,
+
+    /// Refresh interval, e.g. `1s`, `500ms`, or plain seconds
+    #[arg(long, value_parser = parse_interval, default_value = "1s")]
+    pub interval: Duration,
+
+    /// Headset ID to watch (defaults to the first discovered headset)
+    #[arg(long)]
+    pub headset: Option<String>,
+}
+
+/// Run the summary until Ctrl-C or every stream ends.
+///
+/// Authenticates, connects the selected headset, creates a session,
+/// subscribes to the requested streams, and closes the session on exit.
+/// With `json`, refreshes are written as JSON lines instead of text.
+pub async fn run(
+    client: &CortexClient,
+    config: &CortexConfig,
+    args: &WatchArgs,
+    json: bool,
+) -> Result<(), BoxError> {
+    let (token, connected) = pipe::start_session(client, config, args.headset.as_deref()).await?;
+
+    let mut selected: Vec<WatchStream> = Vec::new();
+    for stream in &args.streams {
+        if !selected.contains(stream) {
+            selected.push(*stream);
+        }
+    }
+    let result = watch(client, &token, &connected, &selected, args.interval, json).await;
+
+    let names: Vec<&str> = selected.iter().map(|s| s.cortex_name()).collect();
+    pipe::end_session(client, &token, &connected.session_id, &names).await;
+
+    result
+}
+
+/// One sample from any watched stream.
+enum Update {
+    Dev(DeviceQuality),
+    Eq(EegQuality),
+    Met(PerformanceMetrics),
+    Pow(BandPowerData),
+}
+
+async fn watch(
+    client: &CortexClient,
+    token: &str,
+    connected: &crate::bridge::ConnectResult,
+    selected: &[WatchStream],
+    interval: Duration,
+    json: bool,
+) -> Result<(), BoxError> {
+    let session_id = connected.session_id.as_str();
+    let num_ch = connected.model.num_channels();
+    let mut updates: Vec<BoxStream<'static, Update>> = Vec::new();
+    for stream in selected {
+        updates.push(match stream {
+            WatchStream::Dev => tag(
+                streams::subscribe_dev(client, token, session_id, num_ch).await?,
+                Update::Dev,
+            ),
+            WatchStream::Eq => tag(
+                streams::subscribe_eq(client, token, session_id, num_ch).await?,
+                Update::Eq,
+            ),
+            WatchStream::Met => tag(
+                streams::subscribe_metrics(client, token, session_id).await?,
+                Update::Met,
+            ),
+            WatchStream::Pow => tag(
+                streams::subscribe_band_power(client, token, session_id, num_ch).await?,
+                Update::Pow,
+            ),
+        });
+    }
+    let mut updates = select_all(updates);
+
+    let mut state = WatchState::new(
+        connected.session_id.clone(),
+        connected.headset_id.clone(),
+        connected.model.clone(),
+        selected.to_vec(),
+        interval,
+    );
+    let is_terminal = std::io::stdout().is_terminal();
+    let mut tick = tokio::time::interval(interval);
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    loop {
+        tokio::select! {
+            update = updates.next() => {
+                let Some(update) = update else { return Ok(()) };
+                state.apply(update, Instant::now());
+            }
+            _ = tick.tick() => {
+                if json {
+                    if !write_json_line(&state.snapshot(Instant::now()))? {
+                        return Ok(());
+                    }
+                    continue;
+                }
+                let screen = state.render(Instant::now());
+                let mut out = std::io::stdout().lock();
+                if is_terminal {
+                    queue!(out, MoveTo(0, 0), Clear(ClearType::All))?;
+                } else {
+                    writeln!(out)?;
+                }
+                match write!(out, "{screen}").and_then(|()| out.flush()) {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => return Ok(()),
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            _ = &mut ctrl_c => return Ok(()),
+        }
+    }
+}
+
+fn tag<T: 'static>(
+    stream: std::pin::Pin<Box<dyn Stream<Item = T> + Send>>,
+    wrap: fn(T) -> Update,
+) -> BoxStream<'static, Update> {
+    stream.map(wrap).boxed()
+}
+
+/// Latest sample of each watched stream and when it arrived.
+struct WatchState {
+    session_id: String,
+    headset_id: String,
+    model: HeadsetModel,
+    selected: Vec<WatchStream>,
+    interval: Duration,
+    started: Instant,
+    dev: Option<(DeviceQuality, Instant)>,
+    eq: Option<(EegQuality, Instant)>,
+    met: Option<(PerformanceMetrics, Instant)>,
+    pow: Option<(BandPowerData, Instant)>,
+}
+
+impl WatchState {
+    fn new(
+        session_id: String,
+        headset_id: String,
+        model: HeadsetModel,
+        selected: Vec<WatchStream>,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            session_id,
+            headset_id,
+            model,
+            selected,
+            interval,
+            started: Instant::now(),
+            dev: None,
+            eq: None,
+            met: None,
+            pow: None,
+        }
+    }
+
+    fn apply(&mut self, update: Update, now: Instant) {
+        match update {
+            Update::Dev(dev) => self.dev = Some((dev, now)),
+            Update::Eq(eq) => self.eq = Some((eq, now)),
+            Update::Met(met) => self.met = Some((met, now)),
+            Update::Pow(pow) => self.pow = Some((pow, now)),
+        }
+    }
+
+    /// The whole screen, one line per `\n`.
+    fn render(&self, now: Instant) -> String {
+        let mut out = String::new();
+        let elapsed = now.saturating_duration_since(self.started).as_secs();
+        let _ = writeln!(
+            out,
+            "{} ({})  up {:02}:{:02}:{:02}  Ctrl-C to quit",
+            self.headset_id,
+            self.model,
+            elapsed / 3600,
+            elapsed / 60 % 60,
+            elapsed % 60
+        );
+        for stream in &self.selected {
+            out.push('\n');
+            match stream {
+                WatchStream::Dev => self.render_dev(&mut out, now),
+                WatchStream::Eq => self.render_eq(&mut out, now),
+                WatchStream::Met => self.render_met(&mut out, now),
+                WatchStream::Pow => self.render_pow(&mut out, now),
+            }
+        }
+        out
+    }
+
+    /// The same content as [`Self::render`], as one JSON object.
+    fn snapshot(&self, now: Instant) -> serde_json::Value {
+        let mut streams = serde_json::Map::new();
+        for stream in &self.selected {
+            let section = match stream {
+                WatchStream::Dev => self.section(self.dev.as_ref(), now),
+                WatchStream::Eq => self.section(self.eq.as_ref(), now),
+                WatchStream::Met => self.section(self.met.as_ref(), now),
+                WatchStream::Pow => self.section(self.pow.as_ref(), now),
+            };
+            streams.insert(stream.cortex_name().to_string(), section);
+        }
+        serde_json::json!({
+            "session_id": self.session_id,
+            "headset_id": self.headset_id,
+            "model": self.model.to_string(),
+            "uptime_secs": now.saturating_duration_since(self.started).as_secs(),
+            "streams": streams,
+        })
+    }
+
+    /// A stream's latest sample with its age, or `null` before the first.
+    fn section<T: Serialize>(
+        &self,
+        latest: Option<&(T, Instant)>,
+        now: Instant,
+    ) -> serde_json::Value {
+        let Some((value, at)) = latest else {
+            return serde_json::Value::Null;
+        };
+        let age = now.saturating_duration_since(*at);
+        serde_json::json!({
+            "age_secs": age.as_secs_f64(),
+            "stale": age > self.interval * STALE_INTERVALS,
+            "sample": value,
+        })
+    }
+
+    /// Section heading, with the sample if there is one.
+    fn heading<'a, T>(
+        &self,
+        out: &mut String,
+        title: &str,
+        latest: Option<&'a (T, Instant)>,
+        now: Instant,
+    ) -> Option<&'a T> {
+        let Some((value, at)) = latest else {
+            let _ = writeln!(out, "{title}  (waiting for data)");
+            return None;
+        };
+        let age = now.saturating_duration_since(*at);
+        if age > self.interval * STALE_INTERVALS {
+            let _ = writeln!(out, "{title}  (stale, {}s old)", age.as_secs());
+        } else {
+            let _ = writeln!(out, "{title}");
+        }
+        Some(value)
+    }
+
+    fn render_dev(&self, out: &mut String, now: Instant) {
+        let Some(dev) = self.heading(out, "Device", self.dev.as_ref(), now) else {
+            return;
+        };
+        let _ = writeln!(
+            out,
+            "  Battery  {:>3}%  {}",
+            dev.battery_percent,
+            bar(f32::from(dev.battery_percent) / 100.0)
+        );
+        let _ = writeln!(
+            out,
+            "  Signal   {:>3.0}%  {}",
+            dev.signal_strength * 100.0,
+            bar(dev.signal_strength)
+        );
+        let _ = writeln!(
+            out,
+            "  Contact  {:>3.0}%  {}",
+            dev.overall_quality * 100.0,
+            bar(dev.overall_quality)
+        );
+        self.render_channels(out, &dev.channel_quality);
+    }
+
+    fn render_eq(&self, out: &mut String, now: Instant) {
+        let Some(eq) = self.heading(out, "EEG quality", self.eq.as_ref(), now) else {
+            return;
+        };
+        let _ = writeln!(
+            out,
+            "  Overall  {:>3.0}%  {}",
+            eq.overall * 100.0,
+            bar(eq.overall)
+        );
+        let _ = writeln!(out, "  Rate     {:>4.2}", eq.sample_rate_quality);
+        self.render_channels(out, &eq.sensor_quality);
+    }
+
+    fn render_met(&self, out: &mut String, now: Instant) {
+        let Some(met) = self.heading(out, "Metrics", self.met.as_ref(), now) else {
+            return;
+        };
+        for (name, value) in [
+            ("Engagement", met.engagement),
+            ("Excitement", met.excitement),
+            ("Stress", met.stress),
+            ("Relaxation", met.relaxation),
+            ("Interest", met.interest),
+            ("Attention", met.attention),
+            ("Focus", met.focus),
+        ] {
+            match value {
+                Some(value) => {
+                    let _ = writeln!(out, "  {name:<11}{value:>5.2}  {}", bar(value));
+                }
+                None => {
+                    let _ = writeln!(out, "  {name:<11}  n/a");
+                }
+            }
+        }
+    }
+
+    fn render_pow(&self, out: &mut String, now: Instant) {
+        let Some(pow) = self.heading(out, "Band power (channel mean)", self.pow.as_ref(), now)
+        else {
+            return;
+        };
+        let channels = pow.channel_powers.len().max(1);
+        let mut line = String::from(" ");
+        for (band, name) in BAND_NAMES.iter().enumerate() {
+            let sum: f32 = pow.channel_powers.iter().map(|p| p[band]).sum();
+            let _ = write!(line, " {name} {:.2}", sum / usize_to_f32(channels));
+        }
+        let _ = writeln!(out, "{line}");
+    }
+
+    /// Per-channel quality as `NAME value` pairs on one line.
+    fn render_channels(&self, out: &mut String, quality: &[f32]) {
+        let mut line = String::from("  Sensors ");
+        for (name, q) in self.model.channel_names().iter().zip(quality) {
+            let _ = write!(line, " {name} {:.0}", q * 4.0);
+        }
+        let _ = writeln!(out, "{line}  (0-4)");
+    }
+}
+
+/// A fixed-width bar for a 0.0–1.0 value.
+fn bar(fraction: f32) -> String {
+    let filled = f32_to_usize((fraction.clamp(0.0, 1.0) * usize_to_f32(BAR_WIDTH)).round());
+    let mut bar = "█".repeat(filled);
+    bar.push_str(&"░".repeat(BAR_WIDTH - filled.min(BAR_WIDTH)));
+    bar
+}
+
+/// Parse `1s`, `500ms`, `1.5s`, or plain seconds.
+fn parse_interval(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let (number, scale) = if let Some(ms) = text.strip_suffix("ms") {
+        (ms, 0.001)
+    } else if let Some(s) = text.strip_suffix('s') {
+        (s, 1.0)
+    } else {
+        (text, 1.0)
+    };
+    let value: f64 = number
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid interval '{text}' (expected e.g. 1s or 500ms)"))?;
+    let interval = Duration::try_from_secs_f64(value * scale)
+        .map_err(|_| format!("invalid interval '{text}'"))?;
+    if interval < Duration::from_millis(50) {
+        return Err(format!("interval '{text}' is too short (minimum 50ms)"));
+    }
+    Ok(interval)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_shows_latest_samples_and_staleness() {
+        let mut state = WatchState::new(
+            "session-1".into(),
+            "INSIGHT-1".into(),
+            HeadsetModel::Insight,
+            vec![WatchStream::Dev, WatchStream::Met],
+            Duration::from_secs(1),
+        );
+        let start = state.started;
+        assert!(state.render(start).contains("Device  (waiting for data)"));
+
+        state.apply(
+            Update::Dev(DeviceQuality {
+                battery_level: 3,
+                signal_strength: 1.0,
+                channel_quality: vec![1.0, 0.5, 0.0, 1.0, 1.0],
+                overall_quality: 0.8,
+                battery_percent: 75,
+            }),
+            start,
+        );
+        let screen = state.render(start + Duration::from_secs(61));
+        assert!(screen.starts_with("INSIGHT-1 (Emotiv Insight)  up 00:01:01"));
+        assert!(screen.contains("Device  (stale, 61s old)"));
+        assert!(screen.contains("  Battery   75%  ███████████████░░░░░"));
+        assert!(screen.contains("  Sensors  AF3 4 AF4 2 T7 0 T8 4 Pz 4  (0-4)"));
+        assert!(screen.contains("Metrics  (waiting for data)"));
+
+        let snapshot = state.snapshot(start + Duration::from_secs(61));
+        assert_eq!(snapshot["session_id"], "session-1");
+        assert_eq!(snapshot["uptime_secs"], 61);
+        assert_eq!(snapshot["streams"]["dev"]["stale"], true);
+        assert_eq!(snapshot["streams"]["dev"]["sample"]["battery_percent"], 75);
+        assert!(snapshot["streams"]["met"].is_null());
+    }
+
+    #[test]
+    fn intervals_accept_units() {
+        assert_eq!(parse_interval("1s"), Ok(Duration::from_secs(1)));
+        assert_eq!(parse_interval("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_interval("2"), Ok(Duration::from_secs(2)));
+        assert!(parse_interval("10ms").is_err());
+        assert!(parse_interval("soon").is_err());
+    }
+}
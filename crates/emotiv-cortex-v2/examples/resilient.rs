@@ -34,6 +34,39 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     attempts,
                     last_error,
                 } => println!("[event] Reconnect failed after {attempts} attempts: {last_error}"),
+                ConnectionEvent::SessionReactivated { session_id } => {
+                    println!("[event] Session {session_id} re-activated");
+                }
+                ConnectionEvent::Resubscribed {
+                    session_id,
+                    streams,
+                    ..
+                } => {
+                    println!("[event] Re-subscribed {streams:?} on session {session_id}");
+                }
+                ConnectionEvent::ProfileUnloaded {
+                    headset_id,
+                    profile,
+                    ..
+                } => println!(
+                    "[event] Profile {} unloaded from {}",
+                    profile.as_deref().unwrap_or("?"),
+                    headset_id.as_deref().unwrap_or("?")
+                ),
+                ConnectionEvent::ProfileReloaded {
+                    headset_id,
+                    profile,
+                } => println!("[event] Profile {profile} reloaded on {headset_id}"),
+                ConnectionEvent::CredentialsRotated { client_id } => {
+                    println!("[event] Now authorized as client {client_id}");
+                }
+                ConnectionEvent::IdleSession { session_id, .. } => {
+                    println!("[event] Session {session_id} is idle; start recording?");
+                }
+                ConnectionEvent::SessionClosed { session_id, reason } => {
+                    println!("[event] Session {session_id} closed by Cortex: {reason}");
+                }
+                other => println!("[event] {other:?}"),
             }
         }
     });
@@ -0,0 +1,334 @@
+//! # Capture Gap Annotations
+//!
+//! When local capture is paused — by the operator, a signal-quality gate,
+//! or a dropped connection — the skipped interval should be recorded
+//! explicitly rather than left as an unexplained hole in the timestamps.
+//! [`GapTracker`] turns pause/resume calls into [`GapAnnotation`]s, and
+//! [`bids_events_tsv`] renders them as rows of a BIDS `*_events.tsv` file.
+//!
+//! Timestamps are microseconds, the unit of
+//! [`StreamSample::timestamp`](crate::streams::StreamSample::timestamp).
+//!
+//! The local writers share their pause state through a [`CaptureGaps`]
+//! handle: [`FileRecorderOptions::gaps`](crate::recorder::FileRecorderOptions::gaps)
+//! and [`ExporterOptions::gaps`](crate::streams::exporter::ExporterOptions::gaps).
+//! While it is paused they skip samples, and each closed gap is written
+//! as an EDF+ annotation, a row of the CSV markers file, or a row of the
+//! exporter's `<name>_events.tsv`. Feed it from the operator
+//! ([`CaptureGaps::pause`]), a quality gate
+//! ([`QualityGated::report_gaps`](crate::streams::QualityGated::report_gaps)),
+//! or connection events; [`FileRecorder`](crate::recorder::FileRecorder)
+//! follows disconnects of its client by itself.
+//!
+//! ```
+//! use emotiv_cortex_v2::annotations::{GapCause, GapTracker, bids_events_tsv};
+//!
+//! let mut gaps = GapTracker::new();
+//! gaps.pause(2_000_000, GapCause::Paused);
+//! gaps.resume(3_500_000);
+//! let annotations = gaps.finish(10_000_000);
+//!
+//! let tsv = bids_events_tsv(&annotations, 0);
+//! assert_eq!(
+//!     tsv,
+//!     "onset\tduration\ttrial_type\tcause\n2.000000\t1.500000\tcapture_gap\tpaused\n"
+//! );
+//! ```
+
+use std::fmt;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::streams::micros_to_secs;
+use crate::util::now_micros;
+
+/// `trial_type` written for gap rows in BIDS events files.
+pub const BIDS_GAP_TRIAL_TYPE: &str = "capture_gap";
+
+/// Why capture stopped.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GapCause {
+    /// The operator paused capture.
+    Paused,
+    /// A signal-quality gate suppressed samples.
+    QualityGate,
+    /// The connection to Cortex or the headset dropped.
+    Disconnected,
+    /// Any other reason, as free text.
+    Other(String),
+}
+
+impl fmt::Display for GapCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GapCause::Paused => f.write_str("paused"),
+            GapCause::QualityGate => f.write_str("quality_gate"),
+            GapCause::Disconnected => f.write_str("disconnected"),
+            GapCause::Other(reason) => f.write_str(reason),
+        }
+    }
+}
+
+/// One interval in which capture was suspended.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GapAnnotation {
+    /// Start of the gap (microseconds).
+    pub start_us: i64,
+    /// End of the gap (microseconds).
+    pub end_us: i64,
+    /// Why capture was suspended.
+    pub cause: GapCause,
+}
+
+impl GapAnnotation {
+    /// Gap length in microseconds.
+    #[must_use]
+    pub fn duration_us(&self) -> i64 {
+        self.end_us.saturating_sub(self.start_us)
+    }
+
+    /// Label written for the gap in EDF+ annotations and CSV markers
+    /// files, e.g. `capture_gap:paused`.
+    #[must_use]
+    pub fn label(&self) -> String {
+        format!("{BIDS_GAP_TRIAL_TYPE}:{}", self.cause)
+    }
+}
+
+/// Tracks pause/resume transitions and collects the resulting gaps.
+///
+/// Pausing while already paused keeps the original start and cause, so
+/// a quality gate firing during an operator pause does not split the gap.
+#[derive(Debug, Clone, Default)]
+pub struct GapTracker {
+    open: Option<(i64, GapCause)>,
+    closed: Vec<GapAnnotation>,
+}
+
+impl GapTracker {
+    /// Create an empty tracker.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether capture is currently suspended.
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.open.is_some()
+    }
+
+    /// Mark capture as suspended from `timestamp_us`.
+    pub fn pause(&mut self, timestamp_us: i64, cause: GapCause) {
+        if self.open.is_none() {
+            self.open = Some((timestamp_us, cause));
+        }
+    }
+
+    /// Mark capture as resumed at `timestamp_us`, closing the open gap.
+    /// Returns the closed gap, or `None` if capture was not paused.
+    pub fn resume(&mut self, timestamp_us: i64) -> Option<&GapAnnotation> {
+        let (start_us, cause) = self.open.take()?;
+        self.closed.push(GapAnnotation {
+            start_us,
+            end_us: timestamp_us.max(start_us),
+            cause,
+        });
+        self.closed.last()
+    }
+
+    /// Gaps closed so far.
+    #[must_use]
+    pub fn annotations(&self) -> &[GapAnnotation] {
+        &self.closed
+    }
+
+    /// Close any open gap at `end_us` (the end of the capture) and return
+    /// all gaps in order.
+    #[must_use]
+    pub fn finish(mut self, end_us: i64) -> Vec<GapAnnotation> {
+        self.resume(end_us);
+        self.closed
+    }
+}
+
+/// Shared pause state of a local capture.
+///
+/// Clones share one state, so an operator control, a quality gate, and a
+/// connection watcher can all pause the same writers. Capture stays
+/// suspended while any cause is active; the gap opened by the first cause
+/// closes when the last one resumes. Times are taken from the system
+/// clock.
+#[derive(Debug, Clone, Default)]
+pub struct CaptureGaps {
+    shared: Arc<GapsShared>,
+}
+
+#[derive(Debug, Default)]
+struct GapsShared {
+    paused: AtomicBool,
+    state: Mutex<GapsState>,
+}
+
+#[derive(Debug, Default)]
+struct GapsState {
+    active: Vec<GapCause>,
+    tracker: GapTracker,
+    listeners: Vec<mpsc::UnboundedSender<GapAnnotation>>,
+}
+
+impl CaptureGaps {
+    /// Create a handle with capture running.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether capture is currently suspended.
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.shared.paused.load(Ordering::Acquire)
+    }
+
+    /// Suspend capture for `cause`. Pausing again for an active cause
+    /// does nothing.
+    pub fn pause(&self, cause: GapCause) {
+        let mut state = self.lock();
+        if state.active.contains(&cause) {
+            return;
+        }
+        state.tracker.pause(now_micros(), cause.clone());
+        state.active.push(cause);
+        self.shared.paused.store(true, Ordering::Release);
+    }
+
+    /// Clear `cause`. Capture resumes, and the gap is handed to the
+    /// writers, once no cause is left.
+    pub fn resume(&self, cause: &GapCause) {
+        let mut state = self.lock();
+        let before = state.active.len();
+        state.active.retain(|active| active != cause);
+        if state.active.len() == before || !state.active.is_empty() {
+            return;
+        }
+        self.shared.paused.store(false, Ordering::Release);
+        let Some(gap) = state.tracker.resume(now_micros()).cloned() else {
+            return;
+        };
+        state
+            .listeners
+            .retain(|listener| listener.send(gap.clone()).is_ok());
+    }
+
+    /// Gaps closed so far.
+    #[must_use]
+    pub fn annotations(&self) -> Vec<GapAnnotation> {
+        self.lock().tracker.annotations().to_vec()
+    }
+
+    /// The gap still open, as if it ended now.
+    pub(crate) fn open_gap(&self) -> Option<GapAnnotation> {
+        let (start_us, cause) = self.lock().tracker.open.clone()?;
+        Some(GapAnnotation {
+            start_us,
+            end_us: now_micros().max(start_us),
+            cause,
+        })
+    }
+
+    /// Receive every gap closed from now on.
+    pub(crate) fn listen(&self) -> mpsc::UnboundedReceiver<GapAnnotation> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.lock().listeners.push(tx);
+        rx
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, GapsState> {
+        self.shared
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Render gaps as a BIDS `*_events.tsv` body.
+///
+/// `onset` and `duration` are seconds relative to `recording_start_us`.
+/// Rows use [`BIDS_GAP_TRIAL_TYPE`] and carry the cause in an extra
+/// `cause` column, which should be described in the sidecar
+/// `*_events.json`.
+#[must_use]
+pub fn bids_events_tsv(annotations: &[GapAnnotation], recording_start_us: i64) -> String {
+    let mut out = String::from("onset\tduration\ttrial_type\tcause\n");
+    for gap in annotations {
+        let onset = micros_to_secs(gap.start_us.saturating_sub(recording_start_us));
+        let duration = micros_to_secs(gap.duration_us());
+        let cause = gap.cause.to_string().replace(['\t', '\n', '\r'], " ");
+        // Writing to a String cannot fail.
+        let _ = writeln!(
+            out,
+            "{onset:.6}\t{duration:.6}\t{BIDS_GAP_TRIAL_TYPE}\t{cause}"
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_pause_keeps_first_cause() {
+        let mut gaps = GapTracker::new();
+        gaps.pause(100, GapCause::Paused);
+        gaps.pause(150, GapCause::QualityGate);
+        assert!(gaps.is_paused());
+        assert_eq!(gaps.resume(400).map(GapAnnotation::duration_us), Some(300));
+        assert!(gaps.resume(500).is_none());
+
+        assert_eq!(
+            gaps.annotations(),
+            &[GapAnnotation {
+                start_us: 100,
+                end_us: 400,
+                cause: GapCause::Paused,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_capture_gaps_stay_paused_until_every_cause_resumes() {
+        let gaps = CaptureGaps::new();
+        let mut closed = gaps.listen();
+        gaps.pause(GapCause::Paused);
+        gaps.clone().pause(GapCause::QualityGate);
+        gaps.resume(&GapCause::Paused);
+        assert!(gaps.is_paused());
+        assert!(closed.try_recv().is_err());
+        assert_eq!(gaps.open_gap().map(|g| g.cause), Some(GapCause::Paused));
+
+        gaps.resume(&GapCause::QualityGate);
+        assert!(!gaps.is_paused());
+        assert!(gaps.open_gap().is_none());
+        let gap = closed.try_recv().unwrap();
+        assert_eq!(gap.label(), "capture_gap:paused");
+        assert_eq!(gaps.annotations(), [gap]);
+    }
+
+    #[test]
+    fn test_finish_closes_open_gap_and_tsv_escapes_cause() {
+        let mut gaps = GapTracker::new();
+        gaps.pause(1_000_000, GapCause::Other("battery\tlow".into()));
+        let annotations = gaps.finish(1_250_000);
+
+        assert_eq!(
+            bids_events_tsv(&annotations, 500_000),
+            "onset\tduration\ttrial_type\tcause\n0.500000\t0.250000\tcapture_gap\tbattery low\n"
+        );
+    }
+}
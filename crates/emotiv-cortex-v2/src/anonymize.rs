@@ -0,0 +1,372 @@
+//! # Export Anonymization
+//!
+//! De-identification applied at the export boundary, for clinical users
+//! who must not ship subject-identifying data. An [`Anonymizer`]:
+//!
+//! - replaces subject names with salted SHA-256 pseudonyms
+//!   (`sub-3f2a…`), stable for a given salt so one subject's recordings
+//!   stay linked;
+//! - shifts absolute sample timestamps back by a per-subject offset
+//!   derived from the salt, keeping relative timing within a subject
+//!   intact;
+//! - strips identifying metadata fields ([`DEFAULT_STRIPPED_FIELDS`]),
+//!   including absolute record datetimes, which cannot be shifted without
+//!   parsing their free-form strings.
+//!
+//! Keep the salt secret: anyone holding it can re-identify subjects by
+//! hashing candidate names.
+//!
+//! The local exporters take an optional anonymizer:
+//! [`FileRecorderOptions::anonymize`](crate::recorder::FileRecorderOptions::anonymize)
+//! and [`ExporterOptions::anonymize`](crate::streams::exporter::ExporterOptions::anonymize).
+//! With one set, sample and marker times are shifted for the configured
+//! subject, the EDF patient field carries the pseudonym, and the
+//! `<name>_metadata.json` sidecar is scrubbed.
+//!
+//! ```
+//! use emotiv_cortex_v2::anonymize::{AnonymizeOptions, Anonymizer};
+//! use serde_json::json;
+//!
+//! let anon = Anonymizer::new(AnonymizeOptions::with_salt("lab-secret"));
+//! let pseudonym = anon.pseudonym("Jane Doe");
+//! assert!(pseudonym.starts_with("sub-"));
+//!
+//! let meta = anon.anonymize_metadata(json!({
+//!     "subjectName": "Jane Doe",
+//!     "dateOfBirth": "1990-01-01",
+//!     "sex": "F",
+//! }));
+//! assert_eq!(meta, json!({"subjectName": pseudonym, "sex": "F"}));
+//!
+//! // Relative timing is preserved.
+//! let a = anon.shift_micros("Jane Doe", 1_700_000_000_000_000);
+//! let b = anon.shift_micros("Jane Doe", 1_700_000_001_000_000);
+//! assert_eq!(b - a, 1_000_000);
+//! ```
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use rand::RngCore;
+use serde_json::{Map, Value};
+
+use crate::annotations::GapAnnotation;
+use crate::streams::micros_to_secs;
+use sha2::{Digest, Sha256};
+
+/// Default upper bound for the per-subject timestamp shift.
+pub const DEFAULT_MAX_TIME_SHIFT: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+/// Metadata keys removed by default.
+pub const DEFAULT_STRIPPED_FIELDS: &[&str] = &[
+    "firstName",
+    "lastName",
+    "dateOfBirth",
+    "countryCode",
+    "countryName",
+    "state",
+    "city",
+    "attributes",
+    "email",
+    "phone",
+    "address",
+    "startDatetime",
+    "endDatetime",
+];
+
+/// Metadata keys whose values are subject names, replaced by the
+/// pseudonym.
+pub const SUBJECT_NAME_FIELDS: &[&str] = &["subjectName", "subject"];
+
+/// How exports are de-identified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnonymizeOptions {
+    /// Secret salt mixed into every hash. Reuse it across exports to keep
+    /// pseudonyms and offsets stable for the same subject.
+    pub salt: Vec<u8>,
+    /// Upper bound for the per-subject timestamp shift.
+    pub max_time_shift: Duration,
+    /// Metadata keys to remove (matched exactly, at any depth).
+    pub stripped_fields: Vec<String>,
+}
+
+impl AnonymizeOptions {
+    /// Options with a caller-provided salt and default settings.
+    pub fn with_salt(salt: impl Into<Vec<u8>>) -> Self {
+        Self {
+            salt: salt.into(),
+            max_time_shift: DEFAULT_MAX_TIME_SHIFT,
+            stripped_fields: DEFAULT_STRIPPED_FIELDS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+
+    /// Options with a fresh 32-byte salt from the thread-local CSPRNG.
+    /// Pseudonyms will not match those of any other export unless
+    /// [`Self::salt`] is saved and reused.
+    #[must_use]
+    pub fn with_random_salt() -> Self {
+        let mut salt = vec![0_u8; 32];
+        rand::rng().fill_bytes(&mut salt);
+        Self::with_salt(salt)
+    }
+}
+
+/// Applies [`AnonymizeOptions`] to subject names, timestamps, and
+/// metadata.
+#[derive(Debug, Clone)]
+pub struct Anonymizer {
+    options: AnonymizeOptions,
+}
+
+impl Anonymizer {
+    /// Create an anonymizer.
+    #[must_use]
+    pub fn new(options: AnonymizeOptions) -> Self {
+        Self { options }
+    }
+
+    /// Options in use.
+    #[must_use]
+    pub fn options(&self) -> &AnonymizeOptions {
+        &self.options
+    }
+
+    /// Salted pseudonym for `subject`, e.g. `sub-3f2a9c0d81e4b7a6`.
+    #[must_use]
+    pub fn pseudonym(&self, subject: &str) -> String {
+        let digest = self.digest("subject", subject);
+        let hex = u64::from_be_bytes(digest[..8].try_into().unwrap_or_default());
+        format!("sub-{hex:016x}")
+    }
+
+    /// Per-subject shift applied by [`Self::shift_micros`], in
+    /// `[0, max_time_shift)` whole seconds.
+    #[must_use]
+    pub fn time_offset(&self, subject: &str) -> Duration {
+        let max_secs = self.options.max_time_shift.as_secs();
+        if max_secs == 0 {
+            return Duration::ZERO;
+        }
+        let digest = self.digest("time-offset", subject);
+        let mut bytes = [0_u8; 8];
+        bytes.copy_from_slice(&digest[..8]);
+        Duration::from_secs(u64::from_le_bytes(bytes) % max_secs)
+    }
+
+    /// Shift an absolute timestamp in microseconds (the unit of
+    /// [`EegData::timestamp`](crate::protocol::streams::EegData::timestamp))
+    /// back by the subject's offset.
+    #[must_use]
+    pub fn shift_micros(&self, subject: &str, timestamp_us: i64) -> i64 {
+        let offset = i64::try_from(self.time_offset(subject).as_micros()).unwrap_or(i64::MAX);
+        timestamp_us.saturating_sub(offset)
+    }
+
+    /// Strip identifying fields from `metadata` (at any depth) and
+    /// replace each subject name ([`SUBJECT_NAME_FIELDS`]) with its
+    /// pseudonym.
+    #[must_use]
+    pub fn anonymize_metadata(&self, metadata: Value) -> Value {
+        match metadata {
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .filter(|(key, _)| !self.options.stripped_fields.iter().any(|f| f == key))
+                    .map(|(key, value)| {
+                        let value = match value {
+                            Value::String(name) if SUBJECT_NAME_FIELDS.contains(&key.as_str()) => {
+                                Value::String(self.pseudonym(&name))
+                            }
+                            other => self.anonymize_metadata(other),
+                        };
+                        (key, value)
+                    })
+                    .collect(),
+            ),
+            Value::Array(items) => Value::Array(
+                items
+                    .into_iter()
+                    .map(|item| self.anonymize_metadata(item))
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+
+    fn digest(&self, purpose: &str, subject: &str) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(&self.options.salt);
+        hasher.update([0]);
+        hasher.update(purpose.as_bytes());
+        hasher.update([0]);
+        hasher.update(subject.as_bytes());
+        hasher.finalize().into()
+    }
+}
+
+/// Subject identity as a local export writes it: pseudonymized and
+/// time-shifted when an [`Anonymizer`] is set, unchanged otherwise.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ExportIdentity {
+    anonymizer: Option<Anonymizer>,
+    subject: Option<String>,
+    /// [`Anonymizer::time_offset`] of the subject, computed once.
+    offset_us: i64,
+}
+
+impl ExportIdentity {
+    pub(crate) fn new(anonymizer: Option<Anonymizer>, subject: Option<String>) -> Self {
+        let key = subject.as_deref().unwrap_or_default();
+        let offset_us = anonymizer.as_ref().map_or(0, |a| {
+            i64::try_from(a.time_offset(key).as_micros()).unwrap_or(i64::MAX)
+        });
+        Self {
+            anonymizer,
+            subject,
+            offset_us,
+        }
+    }
+
+    /// Subject as written: its pseudonym when anonymizing.
+    pub(crate) fn subject(&self) -> Option<String> {
+        let subject = self.subject.as_deref()?;
+        Some(match &self.anonymizer {
+            Some(anonymizer) => anonymizer.pseudonym(subject),
+            None => subject.to_string(),
+        })
+    }
+
+    /// Shift a timestamp in microseconds.
+    pub(crate) fn shift_micros(&self, timestamp_us: i64) -> i64 {
+        timestamp_us.saturating_sub(self.offset_us)
+    }
+
+    /// Shift both ends of a capture gap.
+    pub(crate) fn shift_gap(&self, gap: GapAnnotation) -> GapAnnotation {
+        GapAnnotation {
+            start_us: self.shift_micros(gap.start_us),
+            end_us: self.shift_micros(gap.end_us),
+            ..gap
+        }
+    }
+
+    /// Shift a timestamp in seconds (Cortex event `time`).
+    pub(crate) fn shift_secs(&self, secs: f64) -> f64 {
+        secs - micros_to_secs(self.offset_us)
+    }
+
+    /// `metadata` with the subject name added, scrubbed when
+    /// anonymizing; `None` when there is neither.
+    pub(crate) fn metadata(&self, metadata: Option<&Value>) -> Option<Value> {
+        let mut value = match (metadata, &self.subject) {
+            (Some(metadata), _) => metadata.clone(),
+            (None, Some(_)) => Value::Object(Map::new()),
+            (None, None) => return None,
+        };
+        if let (Value::Object(map), Some(subject)) = (&mut value, &self.subject) {
+            map.entry(SUBJECT_NAME_FIELDS[0])
+                .or_insert_with(|| Value::String(subject.clone()));
+        }
+        Some(match &self.anonymizer {
+            Some(anonymizer) => anonymizer.anonymize_metadata(value),
+            None => value,
+        })
+    }
+
+    /// Write [`Self::metadata`] to `path` as pretty JSON. Returns whether
+    /// there was anything to write.
+    pub(crate) fn write_metadata(&self, path: &Path, metadata: Option<&Value>) -> io::Result<bool> {
+        let Some(value) = self.metadata(metadata) else {
+            return Ok(false);
+        };
+        let mut out = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut out, &value)?;
+        out.write_all(b"\n")?;
+        out.flush()?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_pseudonyms_depend_on_salt_and_subject() {
+        let a = Anonymizer::new(AnonymizeOptions::with_salt("one"));
+        let b = Anonymizer::new(AnonymizeOptions::with_salt("two"));
+
+        assert_eq!(a.pseudonym("alice"), a.pseudonym("alice"));
+        assert_ne!(a.pseudonym("alice"), a.pseudonym("bob"));
+        assert_ne!(a.pseudonym("alice"), b.pseudonym("alice"));
+        assert_eq!(a.pseudonym("alice").len(), "sub-".len() + 16);
+    }
+
+    #[test]
+    fn test_time_offset_is_bounded_and_per_subject() {
+        let mut options = AnonymizeOptions::with_salt("salt");
+        options.max_time_shift = Duration::from_secs(3600);
+        let anon = Anonymizer::new(options);
+
+        assert!(anon.time_offset("alice") < Duration::from_secs(3600));
+        assert_ne!(anon.time_offset("alice"), anon.time_offset("bob"));
+        let offset = i64::try_from(anon.time_offset("alice").as_micros()).unwrap();
+        assert_eq!(
+            anon.shift_micros("alice", 5_000_000_000),
+            5_000_000_000 - offset
+        );
+    }
+
+    #[test]
+    fn test_metadata_is_scrubbed_recursively() {
+        let anon = Anonymizer::new(AnonymizeOptions::with_salt("salt"));
+        let meta = json!({
+            "record": {"title": "run 1", "startDatetime": "2024-01-15T10:30:00Z"},
+            "subjects": [{"subjectName": "alice", "city": "Paris", "sex": "F"}],
+        });
+
+        assert_eq!(
+            anon.anonymize_metadata(meta),
+            json!({
+                "record": {"title": "run 1"},
+                "subjects": [{"subjectName": anon.pseudonym("alice"), "sex": "F"}],
+            })
+        );
+    }
+
+    #[test]
+    fn test_export_identity_passes_through_without_anonymizer() {
+        let plain = ExportIdentity::new(None, Some("alice".into()));
+        assert_eq!(plain.subject().as_deref(), Some("alice"));
+        assert_eq!(plain.shift_micros(42), 42);
+        assert_eq!(
+            plain.metadata(Some(&json!({"sex": "F"}))),
+            Some(json!({"subjectName": "alice", "sex": "F"}))
+        );
+        assert_eq!(ExportIdentity::default().metadata(None), None);
+
+        let anon = Anonymizer::new(AnonymizeOptions::with_salt("salt"));
+        let identity = ExportIdentity::new(Some(anon.clone()), Some("alice".into()));
+        assert_eq!(identity.subject(), Some(anon.pseudonym("alice")));
+        assert_eq!(
+            identity.shift_micros(9_000_000_000_000),
+            anon.shift_micros("alice", 9_000_000_000_000)
+        );
+        assert_eq!(
+            identity.metadata(Some(&json!({"city": "Paris"}))),
+            Some(json!({"subjectName": anon.pseudonym("alice")}))
+        );
+    }
+
+    #[test]
+    fn test_random_salts_differ() {
+        let salt = AnonymizeOptions::with_random_salt().salt;
+        assert_eq!(salt.len(), 32);
+        assert_ne!(salt, AnonymizeOptions::with_random_salt().salt);
+    }
+}
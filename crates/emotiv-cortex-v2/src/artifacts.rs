@@ -0,0 +1,470 @@
+//! # Artifact Detection
+//!
+//! Blinks, eye movements and muscle activity swamp the EEG they overlap,
+//! and analysis code usually drops the affected epochs rather than trying
+//! to correct them. [`ArtifactDetector`] splits an EEG stream into fixed
+//! windows aligned to its samples and flags each window by two per-channel
+//! criteria:
+//!
+//! - **amplitude**: peak-to-peak range above
+//!   [`ArtifactConfig::amplitude_threshold_uv`], typical of blinks and
+//!   movement;
+//! - **kurtosis**: excess kurtosis above
+//!   [`ArtifactConfig::kurtosis_threshold`], i.e. a few samples far from
+//!   the rest, typical of muscle bursts and electrode pops.
+//!
+//! Both are insensitive to the DC offset, so raw Cortex EEG can be fed
+//! directly. Blink events from the `fac` stream can be cross-referenced
+//! as a third criterion; they mark the window in progress when they
+//! arrive.
+//!
+//! [`SampleStreamExt::artifacts`] turns an EEG stream into a stream of
+//! [`ArtifactWindow`]s, and the detector remembers recent contaminated
+//! windows so epochs can be checked with
+//! [`ArtifactDetector::contaminated_between`]:
+//!
+//! ```no_run
+//! use futures_util::StreamExt;
+//! use emotiv_cortex_v2::{CortexClient, HeadsetModel, streams};
+//! use emotiv_cortex_v2::artifacts::{ArtifactConfig, ArtifactDetector};
+//! use emotiv_cortex_v2::streams::SampleStreamExt;
+//!
+//! # async fn demo(client: &CortexClient, token: &str, session_id: &str) -> emotiv_cortex_v2::CortexResult<()> {
+//! let model = HeadsetModel::EpocX;
+//! let eeg = streams::subscribe_eeg(client, token, session_id, model.num_channels()).await?;
+//! let fac = streams::subscribe_facial_expressions(client, token, session_id).await?;
+//!
+//! let detector = ArtifactDetector::new(ArtifactConfig::default(), model.sampling_rate_hz());
+//! let mut windows = eeg.artifacts(detector).with_facial(fac);
+//! while let Some(window) = windows.next().await {
+//!     if !window.is_clean() {
+//!         println!("{}..{}: {:?}", window.start_timestamp, window.end_timestamp, window.kinds);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`SampleStreamExt::artifacts`]: crate::streams::SampleStreamExt::artifacts
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::Stream;
+use serde::Serialize;
+
+use crate::protocol::streams::{EegData, FacialAction, FacialExpression};
+use crate::util::{duration_to_samples, f64_to_f32, usize_to_f64};
+
+/// Default window length.
+pub const DEFAULT_ARTIFACT_WINDOW: Duration = Duration::from_millis(500);
+
+/// Default peak-to-peak range that flags a window, in microvolts.
+pub const DEFAULT_AMPLITUDE_THRESHOLD_UV: f32 = 150.0;
+
+/// Default excess kurtosis that flags a window (0 for Gaussian noise).
+pub const DEFAULT_KURTOSIS_THRESHOLD: f32 = 5.0;
+
+/// Default span of contaminated windows kept for
+/// [`ArtifactDetector::contaminated_between`].
+pub const DEFAULT_ARTIFACT_HISTORY: Duration = Duration::from_secs(60);
+
+/// Settings for [`ArtifactDetector`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArtifactConfig {
+    /// Length of each analysed window.
+    pub window: Duration,
+    /// Peak-to-peak range above which a channel is flagged.
+    pub amplitude_threshold_uv: f32,
+    /// Excess kurtosis above which a channel is flagged.
+    pub kurtosis_threshold: f32,
+    /// How long contaminated windows are remembered.
+    pub history: Duration,
+}
+
+impl Default for ArtifactConfig {
+    fn default() -> Self {
+        Self {
+            window: DEFAULT_ARTIFACT_WINDOW,
+            amplitude_threshold_uv: DEFAULT_AMPLITUDE_THRESHOLD_UV,
+            kurtosis_threshold: DEFAULT_KURTOSIS_THRESHOLD,
+            history: DEFAULT_ARTIFACT_HISTORY,
+        }
+    }
+}
+
+/// Why a window was flagged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactKind {
+    /// A channel's peak-to-peak range exceeded the threshold.
+    Amplitude,
+    /// A channel's excess kurtosis exceeded the threshold.
+    Kurtosis,
+    /// The `fac` stream reported a blink during the window.
+    Blink,
+}
+
+/// One analysed window of EEG.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ArtifactWindow {
+    /// Timestamp of the first sample (µs).
+    pub start_timestamp: i64,
+    /// Timestamp of the last sample (µs).
+    pub end_timestamp: i64,
+    /// Samples in the window; fewer than configured only for the last
+    /// window of a stream.
+    pub samples: usize,
+    /// Criteria that flagged the window, sorted; empty when clean.
+    pub kinds: Vec<ArtifactKind>,
+    /// Channels flagged by amplitude or kurtosis, in channel order.
+    pub channels: Vec<usize>,
+    /// Peak-to-peak range per channel (µV).
+    pub peak_to_peak_uv: Vec<f32>,
+    /// Excess kurtosis per channel.
+    pub kurtosis: Vec<f32>,
+}
+
+impl ArtifactWindow {
+    /// Whether no criterion flagged the window.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.kinds.is_empty()
+    }
+}
+
+/// Windowed artifact detection with per-channel state. See the
+/// [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct ArtifactDetector {
+    config: ArtifactConfig,
+    window_samples: usize,
+    /// Values of the window in progress, one row per channel.
+    values: Vec<Vec<f32>>,
+    samples: usize,
+    start_timestamp: i64,
+    end_timestamp: i64,
+    blink: bool,
+    /// `(start, end)` of recent contaminated windows, oldest first.
+    contaminated: VecDeque<(i64, i64)>,
+}
+
+impl ArtifactDetector {
+    /// Detect with `config` on EEG at `sampling_rate_hz`.
+    #[must_use]
+    pub fn new(config: ArtifactConfig, sampling_rate_hz: f64) -> Self {
+        let window_samples = duration_to_samples(config.window, sampling_rate_hz).max(1);
+        Self {
+            config,
+            window_samples,
+            values: Vec::new(),
+            samples: 0,
+            start_timestamp: 0,
+            end_timestamp: 0,
+            blink: false,
+            contaminated: VecDeque::new(),
+        }
+    }
+
+    /// The settings in use.
+    #[must_use]
+    pub fn config(&self) -> &ArtifactConfig {
+        &self.config
+    }
+
+    /// Samples per window.
+    #[must_use]
+    pub fn window_samples(&self) -> usize {
+        self.window_samples
+    }
+
+    /// Add one EEG sample. Returns the window it completes, if any.
+    pub fn push(&mut self, sample: &EegData) -> Option<ArtifactWindow> {
+        if self.samples == 0 {
+            self.start_timestamp = sample.timestamp;
+        }
+        self.end_timestamp = sample.timestamp;
+        if self.values.len() < sample.channels.len() {
+            self.values.resize_with(sample.channels.len(), Vec::new);
+        }
+        for (row, value) in self.values.iter_mut().zip(&sample.channels) {
+            if value.is_finite() {
+                row.push(*value);
+            }
+        }
+        self.samples += 1;
+        (self.samples >= self.window_samples).then(|| self.finish())
+    }
+
+    /// Cross-reference a `fac` sample: a blink marks the window in
+    /// progress.
+    pub fn observe_facial(&mut self, expression: &FacialExpression) {
+        if expression.eye_action == FacialAction::Blink {
+            self.blink = true;
+        }
+    }
+
+    /// Close the window in progress early, e.g. at the end of a stream.
+    /// Returns `None` if it holds no samples.
+    pub fn flush(&mut self) -> Option<ArtifactWindow> {
+        (self.samples > 0).then(|| self.finish())
+    }
+
+    /// Whether a remembered contaminated window overlaps
+    /// `start_us..=end_us`.
+    #[must_use]
+    pub fn contaminated_between(&self, start_us: i64, end_us: i64) -> bool {
+        self.contaminated
+            .iter()
+            .any(|&(start, end)| start <= end_us && end >= start_us)
+    }
+
+    /// Drop the window in progress and the contamination history.
+    pub fn reset(&mut self) {
+        self.values.clear();
+        self.samples = 0;
+        self.blink = false;
+        self.contaminated.clear();
+    }
+
+    fn finish(&mut self) -> ArtifactWindow {
+        let mut kinds = Vec::new();
+        let mut channels = Vec::new();
+        let mut peak_to_peak_uv = Vec::with_capacity(self.values.len());
+        let mut kurtosis = Vec::with_capacity(self.values.len());
+        for (channel, row) in self.values.iter_mut().enumerate() {
+            let (range, excess) = channel_stats(row);
+            let loud = range > self.config.amplitude_threshold_uv;
+            let spiky = excess > self.config.kurtosis_threshold;
+            if loud {
+                kinds.push(ArtifactKind::Amplitude);
+            }
+            if spiky {
+                kinds.push(ArtifactKind::Kurtosis);
+            }
+            if loud || spiky {
+                channels.push(channel);
+            }
+            peak_to_peak_uv.push(range);
+            kurtosis.push(excess);
+            row.clear();
+        }
+        if std::mem::take(&mut self.blink) {
+            kinds.push(ArtifactKind::Blink);
+        }
+        kinds.sort_unstable();
+        kinds.dedup();
+
+        let window = ArtifactWindow {
+            start_timestamp: self.start_timestamp,
+            end_timestamp: self.end_timestamp,
+            samples: std::mem::take(&mut self.samples),
+            kinds,
+            channels,
+            peak_to_peak_uv,
+            kurtosis,
+        };
+        if !window.is_clean() {
+            self.contaminated
+                .push_back((window.start_timestamp, window.end_timestamp));
+        }
+        let horizon = window.end_timestamp
+            - i64::try_from(self.config.history.as_micros()).unwrap_or(i64::MAX);
+        while self
+            .contaminated
+            .front()
+            .is_some_and(|&(_, end)| end < horizon)
+        {
+            self.contaminated.pop_front();
+        }
+        window
+    }
+}
+
+/// Peak-to-peak range and excess kurtosis of one channel's window.
+fn channel_stats(values: &[f32]) -> (f32, f32) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let (min, max) = values
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &v| {
+            (lo.min(v), hi.max(v))
+        });
+    let n = usize_to_f64(values.len());
+    let mean = values.iter().map(|&v| f64::from(v)).sum::<f64>() / n;
+    let (m2, m4) = values.iter().fold((0.0, 0.0), |(m2, m4), &v| {
+        let d2 = (f64::from(v) - mean).powi(2);
+        (m2 + d2 / n, m4 + d2 * d2 / n)
+    });
+    let excess = if m2 > f64::EPSILON {
+        m4 / (m2 * m2) - 3.0
+    } else {
+        0.0
+    };
+    (max - min, f64_to_f32(excess))
+}
+
+/// Stream of [`ArtifactWindow`]s from an EEG stream, optionally
+/// cross-referenced with a `fac` stream. Ends with the EEG stream, after
+/// yielding its last partial window.
+pub struct ArtifactFlags<S> {
+    eeg: Option<S>,
+    facial: Option<Pin<Box<dyn Stream<Item = FacialExpression> + Send>>>,
+    detector: ArtifactDetector,
+}
+
+impl<S> ArtifactFlags<S>
+where
+    S: Stream<Item = EegData> + Unpin,
+{
+    /// Detect artifacts in `eeg` with `detector`.
+    pub fn new(eeg: S, detector: ArtifactDetector) -> Self {
+        Self {
+            eeg: Some(eeg),
+            facial: None,
+            detector,
+        }
+    }
+
+    /// Also flag windows in which `fac` reports a blink.
+    #[must_use]
+    pub fn with_facial<F>(mut self, fac: F) -> Self
+    where
+        F: Stream<Item = FacialExpression> + Send + 'static,
+    {
+        self.facial = Some(Box::pin(fac));
+        self
+    }
+
+    /// The detector, with its current state and contamination history.
+    #[must_use]
+    pub fn detector(&self) -> &ArtifactDetector {
+        &self.detector
+    }
+}
+
+impl<S> Stream for ArtifactFlags<S>
+where
+    S: Stream<Item = EegData> + Unpin,
+{
+    type Item = ArtifactWindow;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        while let Some(facial) = this.facial.as_mut() {
+            match facial.as_mut().poll_next(cx) {
+                Poll::Ready(Some(expression)) => this.detector.observe_facial(&expression),
+                Poll::Ready(None) => this.facial = None,
+                Poll::Pending => break,
+            }
+        }
+        while let Some(eeg) = this.eeg.as_mut() {
+            match Pin::new(eeg).poll_next(cx) {
+                Poll::Ready(Some(sample)) => {
+                    if let Some(window) = this.detector.push(&sample) {
+                        return Poll::Ready(Some(window));
+                    }
+                }
+                Poll::Ready(None) => {
+                    this.eeg = None;
+                    this.facial = None;
+                    return Poll::Ready(this.detector.flush());
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        Poll::Ready(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streams::SampleStreamExt;
+    use crate::testing::eeg_sample;
+    use futures_util::{StreamExt, stream};
+
+    const RATE: f64 = 128.0;
+
+    /// Sample `n`, timestamped `n` µs.
+    fn eeg(n: usize, channels: Vec<f32>) -> EegData {
+        eeg_sample(i64::try_from(n).unwrap(), 0, channels)
+    }
+
+    /// Low-level noise around a DC offset, deterministic.
+    fn background(n: usize) -> f32 {
+        4200.0 + [3.0, -2.0, 5.0, -4.0, 1.0, -3.0, 4.0, -5.0][n % 8]
+    }
+
+    fn detector() -> ArtifactDetector {
+        ArtifactDetector::new(ArtifactConfig::default(), RATE)
+    }
+
+    #[test]
+    fn test_clean_windows_are_not_flagged() {
+        let mut detector = detector();
+        assert_eq!(detector.window_samples(), 64);
+        let windows: Vec<_> = (0..128)
+            .filter_map(|n| detector.push(&eeg(n, vec![background(n); 2])))
+            .collect();
+        assert_eq!(windows.len(), 2);
+        assert!(windows.iter().all(ArtifactWindow::is_clean), "{windows:?}");
+        assert_eq!(
+            (windows[1].start_timestamp, windows[1].end_timestamp),
+            (64, 127)
+        );
+    }
+
+    #[test]
+    fn test_blink_deflection_flags_amplitude_on_its_channel() {
+        let mut detector = detector();
+        let mut window = None;
+        for i in 0..64 {
+            // A 200 µV, 250 ms bump on channel 1 only.
+            let blink = if (20..52).contains(&i) { 200.0 } else { 0.0 };
+            window = detector.push(&eeg(i, vec![background(i), background(i) + blink]));
+        }
+        let window = window.unwrap();
+        assert_eq!(window.kinds, vec![ArtifactKind::Amplitude]);
+        assert_eq!(window.channels, vec![1]);
+        assert!(detector.contaminated_between(10, 30));
+        assert!(!detector.contaminated_between(64, 100));
+    }
+
+    #[test]
+    fn test_isolated_spike_flags_kurtosis() {
+        let mut detector = detector();
+        let mut window = None;
+        for i in 0..64 {
+            let spike = if i == 30 { 60.0 } else { 0.0 };
+            window = detector.push(&eeg(i, vec![background(i) + spike]));
+        }
+        let window = window.unwrap();
+        assert_eq!(window.kinds, vec![ArtifactKind::Kurtosis]);
+        assert!(window.kurtosis[0] > DEFAULT_KURTOSIS_THRESHOLD);
+    }
+
+    #[tokio::test]
+    async fn test_facial_blink_marks_window_and_stream_flushes_tail() {
+        let samples = (0..100).map(|n| eeg(n, vec![background(n)]));
+        let blink = FacialExpression {
+            eye_action: FacialAction::Blink,
+            upper_face_action: FacialAction::Neutral,
+            upper_face_power: 0.0,
+            lower_face_action: FacialAction::Neutral,
+            lower_face_power: 0.0,
+        };
+        let windows: Vec<_> = stream::iter(samples)
+            .artifacts(detector())
+            .with_facial(stream::iter([blink]))
+            .collect()
+            .await;
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[0].kinds, vec![ArtifactKind::Blink]);
+        assert!(windows[1].is_clean());
+        assert_eq!(windows[1].samples, 36);
+    }
+}
@@ -0,0 +1,531 @@
+//! # Band Power Summaries
+//!
+//! The `pow` stream reports five band powers for every channel. Dashboards
+//! and neurofeedback targets usually want one number per band for the
+//! whole headset ("global alpha"), but a plain channel mean lets a single
+//! loose electrode, whose power is dominated by noise, drag the summary
+//! around.
+//!
+//! [`QualityWeightedBandPower`] averages the channels weighted by their
+//! latest contact quality from the `eq` stream, leaving out channels below
+//! [`min_quality`](QualityWeightedBandPower::with_min_quality) entirely.
+//! [`WeightedBandPower`] applies it to a pair of streams and yields one
+//! [`BandPowerSummary`] per `pow` sample (8 Hz):
+//!
+//! ```no_run
+//! use futures_util::StreamExt;
+//! use emotiv_cortex_v2::{CortexClient, HeadsetModel, streams};
+//! use emotiv_cortex_v2::band_power::WeightedBandPower;
+//!
+//! # async fn demo(client: &CortexClient, token: &str, session_id: &str) -> emotiv_cortex_v2::CortexResult<()> {
+//! let model = HeadsetModel::Insight;
+//! let pow = streams::subscribe_band_power(client, token, session_id, model.num_channels()).await?;
+//! let eq = streams::subscribe_eq(client, token, session_id, model.num_channels()).await?;
+//!
+//! let mut global = WeightedBandPower::new(pow, eq);
+//! while let Some(summary) = global.next().await {
+//!     println!("alpha {:.2} from {} channels", summary.alpha(), summary.channels_used);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Smoothing and Relative Power
+//!
+//! Raw band power jumps from one 8 Hz sample to the next, and its
+//! absolute level depends on the electrode. [`BandPowerSmoother`] keeps an
+//! exponential moving average of every channel's bands and reports each
+//! band's share of the channel's total power as a
+//! [`RelativeBandPower`]; [`SmoothedBandPower`] applies it to a `pow`
+//! stream, and [`streams::subscribe_relative_band_power`] subscribes and
+//! wraps in one call.
+//!
+//! [`streams::subscribe_relative_band_power`]: crate::streams::subscribe_relative_band_power
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::Stream;
+use serde::Serialize;
+
+use crate::protocol::streams::{BandPowerData, EegQuality};
+use crate::util::{f64_to_f32, usize_to_f64};
+
+/// Default quality (0.0–1.0) below which a channel is left out of the
+/// summary (Cortex contact quality 1 of 4).
+pub const DEFAULT_MIN_QUALITY: f32 = 0.25;
+
+/// Band names in [`BandPowerData::channel_powers`] order.
+pub const BAND_NAMES: [&str; 5] = ["theta", "alpha", "betaL", "betaH", "gamma"];
+
+/// Rate at which Cortex reports band power.
+pub const POW_RATE_HZ: f64 = 8.0;
+
+/// Default time constant of [`BandPowerSmoother`].
+pub const DEFAULT_SMOOTHING_TIME_CONSTANT: Duration = Duration::from_secs(1);
+
+/// Headset-wide band power for one `pow` sample.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BandPowerSummary {
+    /// Timestamp of the `pow` sample, in microseconds.
+    pub timestamp: i64,
+    /// Weighted mean power per band, in [`BAND_NAMES`] order (uV²/Hz).
+    pub powers: [f32; 5],
+    /// Channels that contributed (weight above zero).
+    pub channels_used: usize,
+    /// Sum of the channel weights; low values mean the summary rests on
+    /// few or poorly connected electrodes.
+    pub total_weight: f32,
+}
+
+impl BandPowerSummary {
+    /// Weighted theta power.
+    #[must_use]
+    pub fn theta(&self) -> f32 {
+        self.powers[0]
+    }
+
+    /// Weighted alpha power.
+    #[must_use]
+    pub fn alpha(&self) -> f32 {
+        self.powers[1]
+    }
+
+    /// Weighted low beta power.
+    #[must_use]
+    pub fn beta_low(&self) -> f32 {
+        self.powers[2]
+    }
+
+    /// Weighted high beta power.
+    #[must_use]
+    pub fn beta_high(&self) -> f32 {
+        self.powers[3]
+    }
+
+    /// Weighted gamma power.
+    #[must_use]
+    pub fn gamma(&self) -> f32 {
+        self.powers[4]
+    }
+}
+
+/// Contact-quality-weighted channel average of band power. See the
+/// [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct QualityWeightedBandPower {
+    min_quality: f32,
+    weights: Vec<f32>,
+}
+
+impl Default for QualityWeightedBandPower {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QualityWeightedBandPower {
+    /// Create an aggregator using [`DEFAULT_MIN_QUALITY`].
+    ///
+    /// Until the first [`update_quality`](Self::update_quality) every
+    /// channel has weight 1, i.e. the summary is a plain mean.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            min_quality: DEFAULT_MIN_QUALITY,
+            weights: Vec::new(),
+        }
+    }
+
+    /// Leave out channels whose quality is below `min_quality`
+    /// (0.0–1.0; 0.0 keeps every channel).
+    #[must_use]
+    pub fn with_min_quality(mut self, min_quality: f32) -> Self {
+        self.min_quality = min_quality;
+        self
+    }
+
+    /// Take the channel weights from the latest `eq` sample.
+    pub fn update_quality(&mut self, quality: &EegQuality) {
+        self.weights.clear();
+        self.weights.extend(
+            quality
+                .sensor_quality
+                .iter()
+                .map(|&q| if q >= self.min_quality { q } else { 0.0 }),
+        );
+    }
+
+    /// Current weight of each channel, in stream order. Channels beyond
+    /// the last quality sample have weight 1.
+    #[must_use]
+    pub fn weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    /// Summarize one `pow` sample.
+    ///
+    /// Returns `None` when no channel has a weight above zero (every
+    /// electrode is off the head).
+    #[must_use]
+    pub fn summarize(&self, sample: &BandPowerData) -> Option<BandPowerSummary> {
+        let mut sums = [0.0_f64; 5];
+        let mut total_weight = 0.0_f64;
+        let mut channels_used = 0;
+        for (channel, powers) in sample.channel_powers.iter().enumerate() {
+            let weight = f64::from(self.weights.get(channel).copied().unwrap_or(1.0));
+            if weight <= 0.0 || powers.iter().any(|p| !p.is_finite()) {
+                continue;
+            }
+            for (sum, power) in sums.iter_mut().zip(powers) {
+                *sum += weight * f64::from(*power);
+            }
+            total_weight += weight;
+            channels_used += 1;
+        }
+        if channels_used == 0 {
+            return None;
+        }
+
+        Some(BandPowerSummary {
+            timestamp: sample.timestamp,
+            powers: sums.map(|sum| f64_to_f32(sum / total_weight)),
+            channels_used,
+            total_weight: f64_to_f32(total_weight),
+        })
+    }
+}
+
+/// Stream of [`BandPowerSummary`]s from a `pow` stream weighted by an
+/// `eq` stream.
+///
+/// Quality samples are applied as they arrive; a `pow` sample is
+/// summarized with the latest weights. `pow` samples with no usable
+/// channel are skipped. The stream ends with the `pow` stream.
+pub struct WeightedBandPower<P, Q> {
+    pow: P,
+    eq: Option<Q>,
+    aggregator: QualityWeightedBandPower,
+}
+
+impl<P, Q> WeightedBandPower<P, Q>
+where
+    P: Stream<Item = BandPowerData> + Unpin,
+    Q: Stream<Item = EegQuality> + Unpin,
+{
+    /// Combine `pow` and `eq` with a default [`QualityWeightedBandPower`].
+    pub fn new(pow: P, eq: Q) -> Self {
+        Self::with_aggregator(pow, eq, QualityWeightedBandPower::new())
+    }
+
+    /// Combine `pow` and `eq` with a configured aggregator.
+    pub fn with_aggregator(pow: P, eq: Q, aggregator: QualityWeightedBandPower) -> Self {
+        Self {
+            pow,
+            eq: Some(eq),
+            aggregator,
+        }
+    }
+
+    /// The aggregator, with the current weights.
+    #[must_use]
+    pub fn aggregator(&self) -> &QualityWeightedBandPower {
+        &self.aggregator
+    }
+}
+
+impl<P, Q> Stream for WeightedBandPower<P, Q>
+where
+    P: Stream<Item = BandPowerData> + Unpin,
+    Q: Stream<Item = EegQuality> + Unpin,
+{
+    type Item = BandPowerSummary;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        while let Some(eq) = this.eq.as_mut() {
+            match Pin::new(eq).poll_next(cx) {
+                Poll::Ready(Some(quality)) => this.aggregator.update_quality(&quality),
+                Poll::Ready(None) => this.eq = None,
+                Poll::Pending => break,
+            }
+        }
+        loop {
+            match Pin::new(&mut this.pow).poll_next(cx) {
+                Poll::Ready(Some(sample)) => {
+                    if let Some(summary) = this.aggregator.summarize(&sample) {
+                        return Poll::Ready(Some(summary));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+// ─── Smoothing ───────────────────────────────────────────────────────────
+
+/// Smoothed and relative band power for one `pow` sample.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RelativeBandPower {
+    /// Timestamp of the `pow` sample, in microseconds.
+    pub timestamp: i64,
+    /// Exponentially smoothed power per channel, bands in [`BAND_NAMES`]
+    /// order (uV²/Hz).
+    pub smoothed: Vec<[f32; 5]>,
+    /// Each smoothed band divided by the channel's smoothed total, so a
+    /// channel's bands sum to 1 (all zero while its total is zero).
+    pub relative: Vec<[f32; 5]>,
+}
+
+impl RelativeBandPower {
+    /// Relative power per band averaged over the channels.
+    #[must_use]
+    pub fn mean_relative(&self) -> [f32; 5] {
+        let mut sums = [0.0_f64; 5];
+        for bands in &self.relative {
+            for (sum, value) in sums.iter_mut().zip(bands) {
+                *sum += f64::from(*value);
+            }
+        }
+        let n = usize_to_f64(self.relative.len().max(1));
+        sums.map(|sum| f64_to_f32(sum / n))
+    }
+}
+
+/// Exponential moving average of per-channel band power. See the
+/// [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct BandPowerSmoother {
+    smoothing: f64,
+    state: Vec<Option<[f64; 5]>>,
+}
+
+impl Default for BandPowerSmoother {
+    fn default() -> Self {
+        Self::with_time_constant(DEFAULT_SMOOTHING_TIME_CONSTANT)
+    }
+}
+
+impl BandPowerSmoother {
+    /// Smooth with weight `smoothing` (clamped to 0.0–1.0) for the newest
+    /// sample; 1.0 disables smoothing.
+    #[must_use]
+    pub fn new(smoothing: f32) -> Self {
+        Self {
+            smoothing: f64::from(smoothing).clamp(0.0, 1.0),
+            state: Vec::new(),
+        }
+    }
+
+    /// Smooth with time constant `tau` at the [`POW_RATE_HZ`] sample rate:
+    /// a step change is 63% through after `tau`.
+    #[must_use]
+    pub fn with_time_constant(tau: Duration) -> Self {
+        let tau = tau.as_secs_f64();
+        let smoothing = if tau > 0.0 {
+            1.0 - (-1.0 / (POW_RATE_HZ * tau)).exp()
+        } else {
+            1.0
+        };
+        Self {
+            smoothing,
+            state: Vec::new(),
+        }
+    }
+
+    /// Weight of the newest sample.
+    #[must_use]
+    pub fn smoothing(&self) -> f32 {
+        f64_to_f32(self.smoothing)
+    }
+
+    /// Fold in one `pow` sample. The first sample seeds each channel;
+    /// channels with a non-finite power keep their previous value.
+    pub fn process(&mut self, sample: &BandPowerData) -> RelativeBandPower {
+        self.state.resize(sample.channel_powers.len(), None);
+        let mut smoothed = Vec::with_capacity(sample.channel_powers.len());
+        let mut relative = Vec::with_capacity(sample.channel_powers.len());
+        for (state, powers) in self.state.iter_mut().zip(&sample.channel_powers) {
+            if powers.iter().all(|p| p.is_finite()) {
+                let powers = powers.map(f64::from);
+                *state = Some(match *state {
+                    Some(previous) => {
+                        let mut next = previous;
+                        for (value, power) in next.iter_mut().zip(powers) {
+                            *value += self.smoothing * (power - *value);
+                        }
+                        next
+                    }
+                    None => powers,
+                });
+            }
+            let bands = state.unwrap_or_default();
+            let total: f64 = bands.iter().sum();
+            smoothed.push(bands.map(f64_to_f32));
+            relative.push(if total > 0.0 {
+                bands.map(|band| f64_to_f32(band / total))
+            } else {
+                [0.0; 5]
+            });
+        }
+        RelativeBandPower {
+            timestamp: sample.timestamp,
+            smoothed,
+            relative,
+        }
+    }
+
+    /// Forget the smoothed values, e.g. after a gap in the stream.
+    pub fn reset(&mut self) {
+        self.state.clear();
+    }
+}
+
+/// Stream of [`RelativeBandPower`]s from a `pow` stream. Ends with it.
+pub struct SmoothedBandPower<S> {
+    pow: S,
+    smoother: BandPowerSmoother,
+}
+
+impl<S> SmoothedBandPower<S>
+where
+    S: Stream<Item = BandPowerData> + Unpin,
+{
+    /// Smooth `pow` with `smoother`.
+    pub fn new(pow: S, smoother: BandPowerSmoother) -> Self {
+        Self { pow, smoother }
+    }
+
+    /// The smoother, with its current state.
+    #[must_use]
+    pub fn smoother(&self) -> &BandPowerSmoother {
+        &self.smoother
+    }
+}
+
+impl<S> Stream for SmoothedBandPower<S>
+where
+    S: Stream<Item = BandPowerData> + Unpin,
+{
+    type Item = RelativeBandPower;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        Pin::new(&mut this.pow)
+            .poll_next(cx)
+            .map(|sample| sample.map(|sample| this.smoother.process(&sample)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::{StreamExt, stream};
+
+    fn pow(timestamp: i64, channel_powers: Vec<[f32; 5]>) -> BandPowerData {
+        BandPowerData {
+            timestamp,
+            channel_powers,
+        }
+    }
+
+    fn assert_bands(actual: [f32; 5], expected: [f32; 5]) {
+        assert!(
+            actual
+                .iter()
+                .zip(expected)
+                .all(|(a, e)| (a - e).abs() < 1e-6),
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    fn eq(sensor_quality: Vec<f32>) -> EegQuality {
+        EegQuality {
+            battery_percent: 100,
+            overall: 1.0,
+            sample_rate_quality: 1.0,
+            sensor_quality,
+        }
+    }
+
+    #[test]
+    fn test_bad_electrodes_are_weighted_down_or_dropped() {
+        let sample = pow(
+            1,
+            vec![
+                [1.0, 2.0, 0.0, 0.0, 0.0],
+                [3.0, 4.0, 0.0, 0.0, 0.0],
+                [99.0; 5],
+            ],
+        );
+        let mut aggregator = QualityWeightedBandPower::new();
+        let plain = aggregator.summarize(&sample).unwrap();
+        assert_eq!(plain.channels_used, 3);
+        assert!((plain.theta() - 103.0 / 3.0).abs() < 1e-4);
+
+        // Channel 1 at half quality, channel 2 off the head.
+        aggregator.update_quality(&eq(vec![1.0, 0.5, 0.0]));
+        let weighted = aggregator.summarize(&sample).unwrap();
+        assert_eq!(weighted.channels_used, 2);
+        assert!((weighted.total_weight - 1.5).abs() < 1e-6);
+        assert!((weighted.theta() - 2.5 / 1.5).abs() < 1e-4);
+        assert!((weighted.alpha() - 4.0 / 1.5).abs() < 1e-4);
+
+        aggregator.update_quality(&eq(vec![0.0, 0.0, 0.0]));
+        assert!(aggregator.summarize(&sample).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_applies_latest_quality() {
+        let pow_stream = stream::iter([
+            pow(1, vec![[1.0; 5], [5.0; 5]]),
+            pow(2, vec![[1.0; 5], [5.0; 5]]),
+        ]);
+        let eq_stream = stream::iter([eq(vec![1.0, 0.1])]);
+        let summaries: Vec<_> = WeightedBandPower::new(pow_stream, eq_stream)
+            .collect()
+            .await;
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].channels_used, 1);
+        assert!((summaries[1].gamma() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_smoother_averages_and_normalizes() {
+        let mut smoother = BandPowerSmoother::new(0.5);
+        let first = smoother.process(&pow(1, vec![[1.0, 3.0, 0.0, 0.0, 0.0], [0.0; 5]]));
+        assert_bands(first.smoothed[0], [1.0, 3.0, 0.0, 0.0, 0.0]);
+        assert_bands(first.relative[0], [0.25, 0.75, 0.0, 0.0, 0.0]);
+        assert_bands(first.relative[1], [0.0; 5]);
+
+        let second = smoother.process(&pow(2, vec![[3.0, 3.0, 0.0, 0.0, 0.0], [f32::NAN; 5]]));
+        assert_eq!(second.timestamp, 2);
+        assert_bands(second.smoothed[0], [2.0, 3.0, 0.0, 0.0, 0.0]);
+        assert!((second.relative[0][0] - 0.4).abs() < 1e-6);
+        // The NaN sample left channel 1 at its previous value.
+        assert_bands(second.smoothed[1], [0.0; 5]);
+        let mean = second.mean_relative();
+        assert!((mean[1] - 0.3).abs() < 1e-6);
+
+        let tau = BandPowerSmoother::with_time_constant(Duration::from_millis(125));
+        assert!((tau.smoothing() - 0.632_120_6).abs() < 1e-6);
+        assert!(
+            (BandPowerSmoother::with_time_constant(Duration::ZERO).smoothing() - 1.0).abs()
+                < f32::EPSILON
+        );
+    }
+
+    #[tokio::test]
+    async fn test_smoothed_stream_yields_one_item_per_sample() {
+        let pow_stream = stream::iter([pow(1, vec![[1.0; 5]]), pow(2, vec![[3.0; 5]])]);
+        let items: Vec<_> = SmoothedBandPower::new(pow_stream, BandPowerSmoother::new(1.0))
+            .collect()
+            .await;
+        assert_eq!(items.len(), 2);
+        assert_bands(items[1].smoothed[0], [3.0; 5]);
+        assert!((items[1].relative[0][2] - 0.2).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,552 @@
+//! # BrainFlow-style Board Data
+//!
+//! Analysis code written against BrainFlow's `BoardShim` reads a 2D array
+//! of `rows x samples` out of a ring buffer and finds each signal by its
+//! row index (`get_eeg_channels`, `get_timestamp_channel`, ...).
+//! [`BoardShim`] offers the same shape for Emotiv data, so such code can
+//! run on a Cortex session without LSL or a BrainFlow board driver.
+//!
+//! Enabled by the `brainflow` feature. Like BrainFlow, data is kept per
+//! preset, since EEG and motion arrive at different rates:
+//!
+//! | [`Preset`] | Rows |
+//! |------------|------|
+//! | `Default` | package number, EEG channels, contact quality, timestamp, marker |
+//! | `Auxiliary` | package number, accel x/y/z, gyro x/y/z, magnetometer x/y/z, rotation q0-q3, timestamp, marker |
+//!
+//! Row indices are listed in each preset's [`BoardDescription`].
+//! Timestamps are Unix seconds and EEG values microvolts, as in BrainFlow.
+//! Missing values (quaternion or gyroscope, depending on the headset's
+//! motion layout) are `NaN`.
+//!
+//! ```no_run
+//! use emotiv_cortex_v2::ResilientClient;
+//! use emotiv_cortex_v2::brainflow::{BoardShim, BoardShimOptions, Preset};
+//!
+//! # async fn demo(client: &ResilientClient, session_id: &str) -> emotiv_cortex_v2::CortexResult<()> {
+//! let mut board = BoardShim::start(client, session_id, BoardShimOptions::default()).await?;
+//! tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+//! let data = board.get_board_data(None, Preset::Default);
+//! let eeg = &board.get_board_descr(Preset::Default).eeg_channels;
+//! println!("{} samples of {}", data[eeg[0]].len(), eeg.len());
+//! board.stop_stream().await;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use serde_json::Value;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+
+use crate::error::{CortexError, CortexResult};
+use crate::protocol::constants::Streams;
+use crate::protocol::streams::{EegData, MotionData};
+use crate::reconnect::ResilientClient;
+use crate::streams::micros_to_secs;
+use crate::util::u64_to_f64;
+
+/// BrainFlow's default ring buffer size, in samples per preset.
+pub const DEFAULT_BUFFER_SIZE: usize = 450_000;
+
+/// EEG columns before the channel block: `COUNTER`, `INTERPOLATED`.
+const EEG_PREFIX: usize = 2;
+
+/// EEG columns after the channel block: `RAW_CQ`, `MARKER_HARDWARE`,
+/// `MARKERS`.
+const EEG_SUFFIX: usize = 3;
+
+/// Data preset, as in BrainFlow's `BrainFlowPresets`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Preset {
+    /// EEG.
+    #[default]
+    Default,
+    /// Motion sensors.
+    Auxiliary,
+}
+
+impl Preset {
+    fn index(self) -> usize {
+        match self {
+            Preset::Default => 0,
+            Preset::Auxiliary => 1,
+        }
+    }
+}
+
+/// Row layout of a preset, as BrainFlow's `get_board_descr` reports it.
+/// Unused channel kinds are empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoardDescription {
+    /// Samples per second.
+    pub sampling_rate: u32,
+    /// Rows in the board data.
+    pub num_rows: usize,
+    /// Row of the package number.
+    pub package_num_channel: usize,
+    /// Rows of EEG channels.
+    pub eeg_channels: Vec<usize>,
+    /// Labels of [`Self::eeg_channels`], e.g. `AF3`.
+    pub eeg_names: Vec<String>,
+    /// Rows of accelerometer x, y, z.
+    pub accel_channels: Vec<usize>,
+    /// Rows of gyroscope x, y, z (older EPOC+ firmware).
+    pub gyro_channels: Vec<usize>,
+    /// Rows of magnetometer x, y, z.
+    pub magnetometer_channels: Vec<usize>,
+    /// Rows of the orientation quaternion q0..q3.
+    pub rotation_channels: Vec<usize>,
+    /// Rows with no standard meaning (raw contact quality).
+    pub other_channels: Vec<usize>,
+    /// Row of the Unix timestamp in seconds.
+    pub timestamp_channel: usize,
+    /// Row of inserted markers; 0 where there is none.
+    pub marker_channel: usize,
+}
+
+impl BoardDescription {
+    fn eeg(names: Vec<String>, sampling_rate: u32) -> Self {
+        let channels = names.len();
+        Self {
+            sampling_rate,
+            num_rows: channels + 4,
+            package_num_channel: 0,
+            eeg_channels: (1..=channels).collect(),
+            eeg_names: names,
+            accel_channels: Vec::new(),
+            gyro_channels: Vec::new(),
+            magnetometer_channels: Vec::new(),
+            rotation_channels: Vec::new(),
+            other_channels: vec![channels + 1],
+            timestamp_channel: channels + 2,
+            marker_channel: channels + 3,
+        }
+    }
+
+    fn motion(sampling_rate: u32) -> Self {
+        Self {
+            sampling_rate,
+            num_rows: 16,
+            package_num_channel: 0,
+            eeg_channels: Vec::new(),
+            eeg_names: Vec::new(),
+            accel_channels: vec![1, 2, 3],
+            gyro_channels: vec![4, 5, 6],
+            magnetometer_channels: vec![7, 8, 9],
+            rotation_channels: vec![10, 11, 12, 13],
+            other_channels: Vec::new(),
+            timestamp_channel: 14,
+            marker_channel: 15,
+        }
+    }
+}
+
+/// Settings for [`BoardShim`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoardShimOptions {
+    /// Samples kept per preset; the oldest are dropped beyond this.
+    pub buffer_size: usize,
+    /// Reported EEG rate (128 or 256, depending on headset and license).
+    pub eeg_sampling_rate: u32,
+    /// Reported motion rate.
+    pub motion_sampling_rate: u32,
+    /// Subscribe `mot` as well as `eeg` in [`BoardShim::start`].
+    pub motion: bool,
+}
+
+impl Default for BoardShimOptions {
+    fn default() -> Self {
+        Self {
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            eeg_sampling_rate: 128,
+            motion_sampling_rate: 64,
+            motion: true,
+        }
+    }
+}
+
+/// Ring buffer of one preset; samples are stored as columns.
+#[derive(Debug)]
+struct Ring {
+    samples: VecDeque<Vec<f64>>,
+    capacity: usize,
+    marker_channel: usize,
+    /// Marker for the next sample.
+    pending_marker: f64,
+}
+
+impl Ring {
+    fn new(capacity: usize, marker_channel: usize) -> Self {
+        Self {
+            samples: VecDeque::new(),
+            capacity: capacity.max(1),
+            marker_channel,
+            pending_marker: 0.0,
+        }
+    }
+
+    fn push(&mut self, mut sample: Vec<f64>) {
+        sample[self.marker_channel] = std::mem::take(&mut self.pending_marker);
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+}
+
+/// Transpose sample columns into `rows x samples`.
+fn to_rows<'a>(rows: usize, samples: impl ExactSizeIterator<Item = &'a Vec<f64>>) -> Vec<Vec<f64>> {
+    let mut data = vec![Vec::with_capacity(samples.len()); rows];
+    for sample in samples {
+        for (row, value) in data.iter_mut().zip(sample) {
+            row.push(*value);
+        }
+    }
+    data
+}
+
+/// BrainFlow-style ring buffer fed from Cortex streams. See the
+/// [module documentation](self).
+pub struct BoardShim {
+    descriptions: [BoardDescription; 2],
+    rings: Arc<Mutex<[Ring; 2]>>,
+    motion_packages: Arc<Mutex<u64>>,
+    shutdown: watch::Sender<bool>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl BoardShim {
+    /// An empty board for EEG channels `eeg_names`, fed with
+    /// [`Self::push_eeg`] and [`Self::push_motion`].
+    #[must_use]
+    pub fn new(eeg_names: Vec<String>, options: &BoardShimOptions) -> Self {
+        let descriptions = [
+            BoardDescription::eeg(eeg_names, options.eeg_sampling_rate),
+            BoardDescription::motion(options.motion_sampling_rate),
+        ];
+        let rings = [
+            Ring::new(options.buffer_size, descriptions[0].marker_channel),
+            Ring::new(options.buffer_size, descriptions[1].marker_channel),
+        ];
+        Self {
+            descriptions,
+            rings: Arc::new(Mutex::new(rings)),
+            motion_packages: Arc::new(Mutex::new(0)),
+            shutdown: watch::channel(false).0,
+            tasks: Vec::new(),
+        }
+    }
+
+    /// Subscribe `eeg` (and `mot`, per [`BoardShimOptions::motion`]) on
+    /// `session_id` and feed the board until [`Self::stop_stream`]. EEG
+    /// channel labels come from the subscribe response.
+    ///
+    /// # Errors
+    /// Returns any error from subscribing, including per-stream subscribe
+    /// failures; streams that did subscribe are unsubscribed again.
+    pub async fn start(
+        client: &ResilientClient,
+        session_id: &str,
+        options: BoardShimOptions,
+    ) -> CortexResult<Self> {
+        let mut names = vec![Streams::EEG];
+        if options.motion {
+            names.push(Streams::MOT);
+        }
+        let mut receivers = client.create_stream_channels(&names).await;
+        let result = client.subscribe_streams(session_id, &names).await?;
+        if let Some(failure) = result.failure.first() {
+            let subscribed: Vec<&str> = result
+                .success
+                .iter()
+                .map(|s| s.stream_name.as_str())
+                .collect();
+            if !subscribed.is_empty() {
+                let _ = client.unsubscribe_streams(session_id, &subscribed).await;
+            }
+            return Err(CortexError::from_api_error(
+                failure.code,
+                format!(
+                    "board stream '{}': {}",
+                    failure.stream_name, failure.message
+                ),
+            ));
+        }
+
+        let cols = result
+            .subscription(Streams::EEG)
+            .map(|s| s.cols.clone())
+            .unwrap_or_default();
+        let eeg_names = cols
+            .get(EEG_PREFIX..cols.len().saturating_sub(EEG_SUFFIX))
+            .map(<[String]>::to_vec)
+            .unwrap_or_default();
+        let mut board = Self::new(eeg_names, &options);
+        for (stream, rx) in receivers.drain() {
+            let feeder = board.feeder();
+            board.tasks.push(tokio::spawn(feed(
+                stream,
+                rx,
+                board.shutdown.subscribe(),
+                feeder,
+            )));
+        }
+        tracing::info!(session_id, "BrainFlow board started");
+        Ok(board)
+    }
+
+    /// Stop feeding the board. Buffered data stays readable; the streams
+    /// stay subscribed.
+    pub async fn stop_stream(&mut self) {
+        let _ = self.shutdown.send(true);
+        for task in self.tasks.drain(..) {
+            let _ = task.await;
+        }
+    }
+
+    /// Row layout of `preset`.
+    #[must_use]
+    pub fn get_board_descr(&self, preset: Preset) -> &BoardDescription {
+        &self.descriptions[preset.index()]
+    }
+
+    /// Samples per second of `preset`.
+    #[must_use]
+    pub fn get_sampling_rate(&self, preset: Preset) -> u32 {
+        self.get_board_descr(preset).sampling_rate
+    }
+
+    /// Samples buffered for `preset`.
+    #[must_use]
+    pub fn get_board_data_count(&self, preset: Preset) -> usize {
+        self.rings()[preset.index()].samples.len()
+    }
+
+    /// Remove and return the oldest `num_samples` of `preset` (all if
+    /// `None`), as `rows x samples`.
+    #[must_use]
+    pub fn get_board_data(&self, num_samples: Option<usize>, preset: Preset) -> Vec<Vec<f64>> {
+        let rows = self.get_board_descr(preset).num_rows;
+        let mut rings = self.rings();
+        let ring = &mut rings[preset.index()];
+        let count = num_samples.map_or(ring.samples.len(), |n| n.min(ring.samples.len()));
+        let taken: Vec<Vec<f64>> = ring.samples.drain(..count).collect();
+        to_rows(rows, taken.iter())
+    }
+
+    /// The latest `num_samples` of `preset`, oldest first, as
+    /// `rows x samples`, leaving the buffer unchanged.
+    #[must_use]
+    pub fn get_current_board_data(&self, num_samples: usize, preset: Preset) -> Vec<Vec<f64>> {
+        let rows = self.get_board_descr(preset).num_rows;
+        let rings = self.rings();
+        let samples = &rings[preset.index()].samples;
+        let skip = samples.len().saturating_sub(num_samples);
+        to_rows(rows, samples.range(skip..))
+    }
+
+    /// Mark the next sample of `preset` with `value`.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ConfigError`] for 0, which BrainFlow reserves
+    /// for "no marker".
+    pub fn insert_marker(&self, value: f64, preset: Preset) -> CortexResult<()> {
+        if value == 0.0 {
+            return Err(CortexError::ConfigError {
+                reason: "board marker 0 is reserved for no marker".into(),
+            });
+        }
+        self.rings()[preset.index()].pending_marker = value;
+        Ok(())
+    }
+
+    /// Append an EEG sample to the `Default` preset. Channels beyond the
+    /// board's EEG rows are ignored; missing ones are `NaN`.
+    pub fn push_eeg(&self, sample: &EegData) {
+        self.feeder().push_eeg(sample);
+    }
+
+    /// Append a motion sample to the `Auxiliary` preset.
+    pub fn push_motion(&self, sample: &MotionData) {
+        self.feeder().push_motion(sample);
+    }
+
+    fn rings(&self) -> MutexGuard<'_, [Ring; 2]> {
+        self.rings.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn feeder(&self) -> Feeder {
+        Feeder {
+            eeg_rows: self.descriptions[0].num_rows,
+            rings: Arc::clone(&self.rings),
+            motion_packages: Arc::clone(&self.motion_packages),
+        }
+    }
+}
+
+impl std::fmt::Debug for BoardShim {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BoardShim")
+            .field("descriptions", &self.descriptions)
+            .field("tasks", &self.tasks.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Writes samples into a board's rings from a feed task.
+struct Feeder {
+    eeg_rows: usize,
+    rings: Arc<Mutex<[Ring; 2]>>,
+    motion_packages: Arc<Mutex<u64>>,
+}
+
+impl Feeder {
+    fn push_eeg(&self, sample: &EegData) {
+        let channels = self.eeg_rows - 4;
+        let mut column = vec![f64::NAN; self.eeg_rows];
+        column[0] = f64::from(sample.counter);
+        for (slot, value) in column[1..=channels].iter_mut().zip(&sample.channels) {
+            *slot = f64::from(*value);
+        }
+        column[channels + 1] = f64::from(sample.raw_cq);
+        column[channels + 2] = micros_to_secs(sample.timestamp);
+        self.rings.lock().unwrap_or_else(PoisonError::into_inner)[0].push(column);
+    }
+
+    fn push_motion(&self, sample: &MotionData) {
+        let package = {
+            let mut packages = self
+                .motion_packages
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            *packages = packages.wrapping_add(1);
+            *packages
+        };
+        let mut column = vec![f64::NAN; 16];
+        column[0] = u64_to_f64(package);
+        let mut fill = |at: usize, values: &[f32]| {
+            for (slot, value) in column[at..].iter_mut().zip(values) {
+                *slot = f64::from(*value);
+            }
+        };
+        fill(1, &sample.accelerometer);
+        fill(4, sample.gyroscope.as_ref().map_or(&[], |g| &g[..]));
+        fill(7, &sample.magnetometer);
+        fill(10, sample.quaternion.as_ref().map_or(&[], |q| &q[..]));
+        column[14] = micros_to_secs(sample.timestamp);
+        self.rings.lock().unwrap_or_else(PoisonError::into_inner)[1].push(column);
+    }
+
+    fn push_event(&self, stream: &str, event: &Value) {
+        let (Some(time), Some(array)) = (
+            event.get("time").and_then(Value::as_f64),
+            event.get(stream).and_then(Value::as_array),
+        ) else {
+            return;
+        };
+        if stream == Streams::EEG {
+            let channels = array.len().saturating_sub(EEG_PREFIX + EEG_SUFFIX);
+            if let Some(sample) = EegData::from_eeg_array(array, channels, time) {
+                self.push_eeg(&sample);
+            }
+        } else {
+            let values: Option<Vec<f64>> = array.iter().map(Value::as_f64).collect();
+            if let Some(sample) = values.and_then(|v| MotionData::from_mot_array(&v, time)) {
+                self.push_motion(&sample);
+            }
+        }
+    }
+}
+
+async fn feed(
+    stream: &'static str,
+    mut rx: mpsc::Receiver<Value>,
+    mut shutdown: watch::Receiver<bool>,
+    feeder: Feeder,
+) {
+    loop {
+        tokio::select! {
+            item = rx.recv() => {
+                let Some(event) = item else { break };
+                feeder.push_event(stream, &event);
+            }
+            _ = shutdown.changed() => {
+                while let Ok(event) = rx.try_recv() {
+                    feeder.push_event(stream, &event);
+                }
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::eeg_sample;
+
+    fn eeg(counter: u32, value: f32) -> EegData {
+        let timestamp = 1_700_000_000_000_000 + i64::from(counter) * 7_812;
+        eeg_sample(timestamp, counter, vec![value, -value])
+    }
+
+    #[test]
+    fn test_board_data_rows_and_ring_buffer() {
+        let options = BoardShimOptions {
+            buffer_size: 3,
+            ..BoardShimOptions::default()
+        };
+        let board = BoardShim::new(vec!["AF3".into(), "AF4".into()], &options);
+        let descr = board.get_board_descr(Preset::Default);
+        assert_eq!(descr.eeg_channels, [1, 2]);
+        assert_eq!(descr.timestamp_channel, 4);
+        assert_eq!(descr.num_rows, 6);
+
+        for counter in 0..4 {
+            board.push_eeg(&eeg(counter, f32::from(u8::try_from(counter).unwrap())));
+            if counter == 1 {
+                board.insert_marker(7.0, Preset::Default).unwrap();
+            }
+        }
+        // Oldest sample dropped at capacity.
+        assert_eq!(board.get_board_data_count(Preset::Default), 3);
+
+        let current = board.get_current_board_data(2, Preset::Default);
+        assert_eq!(current[0], [2.0, 3.0]);
+        assert_eq!(current[2], [-2.0, -3.0]);
+        assert_eq!(current[5], [7.0, 0.0]);
+        assert_eq!(board.get_board_data_count(Preset::Default), 3);
+
+        let data = board.get_board_data(Some(2), Preset::Default);
+        assert_eq!(data[0], [1.0, 2.0]);
+        assert_eq!(data[3], [4.0, 4.0]);
+        assert!((data[4][0] - 1_700_000_000.007_812).abs() < 1e-6);
+        assert_eq!(board.get_board_data_count(Preset::Default), 1);
+        assert_eq!(board.get_board_data(None, Preset::Default)[0], [3.0]);
+
+        assert!(board.insert_marker(0.0, Preset::Default).is_err());
+    }
+
+    #[test]
+    fn test_motion_rows_without_quaternion_are_nan() {
+        let board = BoardShim::new(Vec::new(), &BoardShimOptions::default());
+        board.push_motion(&MotionData {
+            timestamp: 2_000_000,
+            quaternion: None,
+            gyroscope: None,
+            accelerometer: [0.0, 0.5, 1.0],
+            magnetometer: [10.0, 20.0, 30.0],
+        });
+        let data = board.get_board_data(None, Preset::Auxiliary);
+        let descr = board.get_board_descr(Preset::Auxiliary);
+        assert_eq!(data[descr.accel_channels[2]], [1.0]);
+        assert_eq!(data[descr.magnetometer_channels[0]], [10.0]);
+        assert!(data[descr.rotation_channels[0]][0].is_nan());
+        assert!(data[descr.gyro_channels[0]][0].is_nan());
+        assert_eq!(data[descr.timestamp_channel], [2.0]);
+        assert_eq!(board.get_sampling_rate(Preset::Auxiliary), 64);
+    }
+}
@@ -14,7 +14,7 @@
 //! ┌─────────────────────────────────────────────────┐
 //! │                 CortexClient                     │
 //! │                                                  │
-//! │  writer: Arc<Mutex<SplitSink>>  ◄── call()       │
+//! │  writer: FrameWriter            ◄── call()       │
 //! │                                  ◄── subscribe() │
 //! │                                                  │
 //! │  reader_loop (spawned task):                     │
@@ -26,6 +26,15 @@
 //! └─────────────────────────────────────────────────┘
 //! ```
 //!
+//! The writer is either locked by each sender in turn or owned by a
+//! dedicated task; see [`crate::writer`].
+//! The reader loop looks up stream channels in a routing table that is
+//! swapped atomically on every channel change, so adding or removing a
+//! channel never blocks dispatch (or the runtime thread) on a lock.
+//!
+//! Binary frames are not part of the current Cortex protocol; they are
+//! counted and skipped unless a [`BinaryFrameHandler`] is installed.
+//!
 //! ## TLS Note
 //!
 //! The Emotiv Cortex service runs at `wss://localhost:6868` with a
@@ -41,12 +50,13 @@
 //! - return shape and parsing behavior
 //! - error propagation and retry/idempotency notes
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use futures_util::{SinkExt, StreamExt, stream::SplitSink, stream::SplitStream};
+use arc_swap::ArcSwap;
+use futures_util::{StreamExt, stream::SplitSink, stream::SplitStream};
 #[cfg(all(feature = "native-tls", not(feature = "rustls-tls")))]
 use native_tls::TlsConnector as NativeTlsConnector;
 #[cfg(feature = "rustls-tls")]
@@ -57,7 +67,7 @@ use rustls::{DigitallySignedStruct, Error as RustlsError, SignatureScheme};
 use rustls_pki_types::{CertificateDer, ServerName, UnixTime};
 use tokio::net::TcpStream;
 use tokio::sync::mpsc::error::TrySendError;
-use tokio::sync::{Mutex, mpsc, oneshot};
+use tokio::sync::{Mutex, broadcast, mpsc, oneshot};
 use tokio::task::JoinHandle;
 #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
 use tokio_tungstenite::connect_async_tls_with_config;
@@ -68,18 +78,28 @@ use tokio_tungstenite::{
     tungstenite::{Message, http},
 };
 
-use crate::config::CortexConfig;
+use crate::compat::ProtocolCompat;
+use crate::config::{CortexConfig, PingConfig, RequestIdStrategy, StrictProtocolMode};
 use crate::error::{CortexError, CortexResult};
-use crate::protocol::auth::UserLoginInfo;
+use crate::latency::{LatencyStats, LatencyTracker, SlowEndpoint};
+use crate::ownership::{CleanupScope, SessionOwner, SessionRegistry};
+use crate::protocol::auth::{LicenseInfo, UserInfo, UserLoginInfo};
 use crate::protocol::constants::{Methods, Streams};
 use crate::protocol::headset::{
     ConfigMappingListValue, ConfigMappingMode, ConfigMappingRequest, ConfigMappingResponse,
     ConfigMappingValue, HeadsetClockSyncResult, HeadsetInfo, QueryHeadsetsOptions,
+    VirtualHeadsetOptions,
+};
+use crate::protocol::profiles::{CurrentProfileInfo, ProfileAction, ProfileInfo, ProfileUnloaded};
+use crate::protocol::records::{
+    ExportFormat, ExportRecordOptions, ExportResult, MarkerInfo, QueryRecordsRequest, RecordInfo,
+    UpdateRecordRequest,
+};
+use crate::protocol::rpc::{
+    ConnectionPrefixedIds, CortexRequest, CortexResponse, CounterIds, RequestIdGenerator,
 };
-use crate::protocol::profiles::{CurrentProfileInfo, ProfileAction, ProfileInfo};
-use crate::protocol::records::{ExportFormat, MarkerInfo, RecordInfo, UpdateRecordRequest};
-use crate::protocol::rpc::{CortexRequest, CortexResponse};
-use crate::protocol::session::SessionInfo;
+use crate::protocol::session::{QuerySessionsRequest, SessionInfo, SessionStatus};
+use crate::protocol::streams::SubscriptionResult;
 use crate::protocol::subjects::{
     DemographicAttribute, QuerySubjectsRequest, SubjectInfo, SubjectRequest,
 };
@@ -88,6 +108,12 @@ use crate::protocol::training::{
     FacialExpressionThresholdRequest, MentalCommandTrainingThresholdRequest,
     TrainedSignatureActions, TrainingStatus, TrainingTime,
 };
+use crate::protocol::warnings::WarningEvent;
+use crate::rate_limit::{RateLimitStats, RateLimiter};
+use crate::replay::FrameCapture;
+use crate::retry::{RetryHooks, RetryPolicy, retry_with_policy};
+use crate::telemetry;
+use crate::writer::{FrameWriter, WriterStats};
 
 /// Connection timeout for the initial WebSocket handshake.
 const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
@@ -95,6 +121,10 @@ const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 /// Channel buffer size for data stream events.
 const STREAM_CHANNEL_BUFFER: usize = 1024;
 
+/// How long [`CortexClient::shutdown`] waits for the server to answer its
+/// Close frame before stopping the reader loop itself.
+const CLOSE_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(2);
+
 type ConnectOutput = Result<
     (
         WebSocketStream<MaybeTlsStream<TcpStream>>,
@@ -221,7 +251,7 @@ impl ServerCertVerifier for InsecureCertVerifier {
 }
 
 /// Type alias for the write half of the WebSocket connection.
-type WsWriter = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
+pub(crate) type WsWriter = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
 
 /// Type alias for the read half of the WebSocket connection.
 type WsReader = SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>;
@@ -229,8 +259,78 @@ type WsReader = SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>;
 /// A pending RPC response awaiting its matching JSON-RPC response by `id`.
 type PendingResponse = oneshot::Sender<CortexResult<serde_json::Value>>;
 
+/// RPC requests awaiting responses, keyed by request ID. Wakes
+/// [`CortexClient::shutdown`] whenever the last one is taken, so it can
+/// wait for in-flight calls without polling.
+#[derive(Default)]
+struct PendingResponses {
+    map: Mutex<HashMap<u64, PendingResponse>>,
+    drained: tokio::sync::Notify,
+}
+
+impl PendingResponses {
+    async fn insert(&self, id: u64, tx: PendingResponse) {
+        self.map.lock().await.insert(id, tx);
+    }
+
+    /// Remove the request `id`, signalling `drained` if none are left.
+    async fn take(&self, id: u64) -> Option<PendingResponse> {
+        let mut map = self.map.lock().await;
+        let tx = map.remove(&id);
+        if map.is_empty() {
+            self.drained.notify_waiters();
+        }
+        tx
+    }
+
+    /// Remove every pending request and signal `drained`.
+    async fn take_all(&self) -> Vec<PendingResponse> {
+        let taken = self.map.lock().await.drain().map(|(_, tx)| tx).collect();
+        self.drained.notify_waiters();
+        taken
+    }
+
+    async fn len(&self) -> usize {
+        self.map.lock().await.len()
+    }
+}
+
+/// Stream keys the reader loop recognizes in data events.
+const STREAM_KEYS: [&str; 9] = ["eeg", "mot", "dev", "eq", "pow", "met", "com", "fac", "sys"];
+
+/// What a stream channel receives: one stream's events from one session,
+/// or from any session without a channel of its own.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StreamRouteKey {
+    /// Session whose events the channel receives; `None` for the
+    /// wildcard channel.
+    pub session_id: Option<String>,
+    /// Stream key (`"eeg"`, `"mot"`, ...).
+    pub stream: &'static str,
+}
+
+impl StreamRouteKey {
+    /// The wildcard route of `stream`.
+    #[must_use]
+    pub fn any_session(stream: &'static str) -> Self {
+        Self {
+            session_id: None,
+            stream,
+        }
+    }
+
+    /// The route of `stream` for `session_id`.
+    #[must_use]
+    pub fn session(session_id: impl Into<String>, stream: &'static str) -> Self {
+        Self {
+            session_id: Some(session_id.into()),
+            stream,
+        }
+    }
+}
+
 /// Senders for dispatching stream data events to consumers.
-pub type StreamSenders = HashMap<&'static str, mpsc::Sender<serde_json::Value>>;
+pub type StreamSenders = HashMap<StreamRouteKey, mpsc::Sender<serde_json::Value>>;
 
 /// Receivers for consuming stream data events.
 pub type StreamReceivers = HashMap<&'static str, mpsc::Receiver<serde_json::Value>>;
@@ -265,27 +365,216 @@ impl StreamDispatchCounters {
 
 type StreamDispatchCounterMap = HashMap<&'static str, Arc<StreamDispatchCounters>>;
 
+/// Streams subscribed on one session, and the token they were subscribed
+/// with (needed to unsubscribe them on shutdown).
+///
+/// This is a per-connection record: it dies with the socket and only
+/// drives [`CortexClient::shutdown`]. What a
+/// [`ResilientClient`](crate::ResilientClient) restores after a reconnect
+/// comes from its own `OpenResources` registry, which is authoritative
+/// across connections.
+#[derive(Debug, Clone, Default)]
+struct ActiveSubscription {
+    cortex_token: String,
+    streams: BTreeSet<String>,
+}
+
+/// Outcome of [`CortexClient::shutdown`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// RPCs still unanswered when the grace period ran out. They fail
+    /// with [`CortexError::ConnectionLost`].
+    pub abandoned_requests: usize,
+    /// Streams unsubscribed, as `(session ID, stream)` pairs.
+    pub unsubscribed: Vec<(String, String)>,
+    /// Sessions whose streams could not be unsubscribed, with the error.
+    pub unsubscribe_errors: Vec<(String, String)>,
+    /// Whether the server answered the Close frame before the reader
+    /// loop was stopped.
+    pub clean_close: bool,
+}
+
+/// Stream channels by route and dispatch counters per stream key,
+/// published as a whole (see `CortexClient::stream_routes`).
+#[derive(Clone, Default)]
+struct StreamRoutes {
+    senders: StreamSenders,
+    counters: StreamDispatchCounterMap,
+}
+
+impl StreamRoutes {
+    /// The channel for an event of `stream` from `sid`: the session's own,
+    /// else the wildcard.
+    fn sender(
+        &self,
+        stream: &'static str,
+        sid: Option<&str>,
+    ) -> Option<&mpsc::Sender<serde_json::Value>> {
+        let session = sid.and_then(|sid| {
+            self.senders
+                .iter()
+                .find(|(key, _)| key.stream == stream && key.session_id.as_deref() == Some(sid))
+                .map(|(_, tx)| tx)
+        });
+        session.or_else(|| self.senders.get(&StreamRouteKey::any_session(stream)))
+    }
+
+    fn insert(&mut self, key: StreamRouteKey, tx: mpsc::Sender<serde_json::Value>) {
+        self.counters
+            .entry(key.stream)
+            .or_insert_with(|| Arc::new(StreamDispatchCounters::default()));
+        self.senders.insert(key, tx);
+    }
+
+    /// Remove a route, and the stream's counters with its last route.
+    fn remove(&mut self, key: &StreamRouteKey) {
+        self.senders.remove(key);
+        if !self.senders.keys().any(|k| k.stream == key.stream) {
+            self.counters.remove(key.stream);
+        }
+    }
+}
+
+/// Extension point for binary WebSocket frames.
+///
+/// Cortex currently sends only text (JSON) frames, so by default binary
+/// frames are counted, logged, and skipped. If Cortex starts sending
+/// compressed or binary-encoded payloads, install a handler with
+/// [`CortexClient::with_binary_frame_handler`] to decode them; decoded
+/// values are dispatched exactly like text frames (RPC responses by `id`,
+/// stream events by stream key).
+///
+/// Any `Fn(&[u8]) -> Option<serde_json::Value> + Send + Sync` closure is a
+/// handler. Handlers run on the reader loop and must not block.
+pub trait BinaryFrameHandler: Send + Sync {
+    /// Decode one binary frame.
+    ///
+    /// Return `Some(value)` to route the decoded message through normal
+    /// dispatch, or `None` if the handler consumed the frame itself.
+    fn handle_binary(&self, frame: &[u8]) -> Option<serde_json::Value>;
+}
+
+impl<F> BinaryFrameHandler for F
+where
+    F: Fn(&[u8]) -> Option<serde_json::Value> + Send + Sync,
+{
+    fn handle_binary(&self, frame: &[u8]) -> Option<serde_json::Value> {
+        self(frame)
+    }
+}
+
+/// Counters and hooks shared between the client and its reader loop.
+struct ReaderShared {
+    /// Number of times the reader loop has woken up.
+    wakeups: AtomicU64,
+    /// Binary frames received, handled or not.
+    binary_frames: AtomicU64,
+    /// Binary frames skipped because no handler was installed.
+    unhandled_binary_frames: AtomicU64,
+    /// Optional decoder for binary frames.
+    binary_handler: std::sync::RwLock<Option<Arc<dyn BinaryFrameHandler>>>,
+    /// Profile-unload warnings pushed by Cortex.
+    profile_unloaded: broadcast::Sender<ProfileUnloaded>,
+    /// Every warning pushed by Cortex.
+    warnings: broadcast::Sender<WarningEvent>,
+    /// Optional recorder of inbound frames.
+    capture: std::sync::RwLock<Option<Arc<FrameCapture>>>,
+    /// Set once the reader loop gave up on a silent connection.
+    liveness_failed: AtomicBool,
+    /// Raised when the reader loop gives up on a silent connection.
+    liveness_lost: broadcast::Sender<LivenessLost>,
+    /// Set once the reader loop has exited.
+    exited: tokio::sync::watch::Sender<bool>,
+}
+
+impl Default for ReaderShared {
+    fn default() -> Self {
+        let (profile_unloaded, _) = broadcast::channel(64);
+        let (warnings, _) = broadcast::channel(64);
+        let (liveness_lost, _) = broadcast::channel(4);
+        let (exited, _) = tokio::sync::watch::channel(false);
+        Self {
+            wakeups: AtomicU64::new(0),
+            binary_frames: AtomicU64::new(0),
+            unhandled_binary_frames: AtomicU64::new(0),
+            binary_handler: std::sync::RwLock::new(None),
+            profile_unloaded,
+            warnings,
+            capture: std::sync::RwLock::new(None),
+            liveness_failed: AtomicBool::new(false),
+            liveness_lost,
+            exited,
+        }
+    }
+}
+
+/// The service sent nothing, not even a pong, for longer than
+/// [`PingConfig::pong_timeout_secs`](crate::config::PingConfig::pong_timeout_secs).
+/// The reader loop has stopped and pending calls failed with
+/// [`CortexError::ConnectionLost`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LivenessLost {
+    /// How long the connection had been silent.
+    pub silent_for: Duration,
+}
+
+/// Everything the reader loop shares with its client.
+struct ReaderLoopState {
+    pending_responses: Arc<PendingResponses>,
+    running: Arc<AtomicBool>,
+    stream_routes: Arc<ArcSwap<StreamRoutes>>,
+    shared: Arc<ReaderShared>,
+    writer: Arc<FrameWriter>,
+    ping: Option<PingSchedule>,
+}
+
+/// Ping schedule handed to the reader loop.
+#[derive(Debug, Clone, Copy)]
+struct PingSchedule {
+    interval: Duration,
+    pong_timeout: Duration,
+}
+
+impl PingSchedule {
+    fn from_config(config: &PingConfig) -> Option<Self> {
+        config.enabled.then(|| Self {
+            interval: Duration::from_secs(config.interval_secs.max(1)),
+            pong_timeout: Duration::from_secs(config.pong_timeout_secs.max(1)),
+        })
+    }
+}
+
+impl ReaderShared {
+    fn capture(&self) -> Option<Arc<FrameCapture>> {
+        self.capture.read().ok().and_then(|slot| slot.clone())
+    }
+}
+
+/// Response fields not modeled by the typed protocol structs, grouped by
+/// Cortex method name. Populated when [`StrictProtocolMode`] is enabled.
+pub type UnmodeledFieldDigest = BTreeMap<&'static str, BTreeSet<String>>;
+
 /// WebSocket JSON-RPC client for the Emotiv Cortex API.
 ///
 /// This client manages a single WebSocket connection, split into reader
-/// and writer halves. The writer is shared (behind `Arc<Mutex>`) so that
-/// API calls can be made concurrently with data streaming. The reader
+/// and writer halves. The writer is shared (see [`crate::writer`]) so
+/// that API calls can be made concurrently with data streaming. The reader
 /// runs in a background task that dispatches:
 ///
 /// - **RPC responses** → matched by `id` to pending `oneshot` channels
 /// - **Data events** → routed by stream type to `mpsc` channels
 pub struct CortexClient {
-    /// Shared write half of the WebSocket.
-    writer: Arc<Mutex<WsWriter>>,
+    /// Shared write half of the WebSocket (the reader loop sends pings).
+    writer: Arc<FrameWriter>,
 
     /// Map of pending RPC requests awaiting responses, keyed by request ID.
-    pending_responses: Arc<Mutex<HashMap<u64, PendingResponse>>>,
+    pending_responses: Arc<PendingResponses>,
 
-    /// Auto-incrementing request ID counter.
-    next_id: AtomicU64,
+    /// Request ID source (counter by default, see [`RequestIdStrategy`]).
+    request_ids: Arc<dyn RequestIdGenerator>,
 
     /// Handle to the background reader loop task.
-    reader_handle: Option<JoinHandle<()>>,
+    reader_handle: std::sync::Mutex<Option<JoinHandle<()>>>,
 
     /// Whether the reader loop is currently running.
     reader_running: Arc<AtomicBool>,
@@ -293,19 +582,66 @@ pub struct CortexClient {
     /// Shutdown signal for the reader loop.
     reader_shutdown: tokio::sync::watch::Sender<bool>,
 
-    /// Shared stream senders, dynamically updatable without restarting
-    /// the reader loop. The reader holds a clone of this Arc and checks
-    /// it on each data message.
-    stream_senders: Arc<std::sync::Mutex<Option<StreamSenders>>>,
+    /// Reader loop counters and the binary frame hook.
+    reader_shared: Arc<ReaderShared>,
 
-    /// Per-stream dispatch counters for backpressure/drop observability.
-    stream_dispatch_counters: Arc<std::sync::Mutex<StreamDispatchCounterMap>>,
+    /// Stream routing table shared with the reader loop, updatable
+    /// without restarting it.
+    ///
+    /// Ordering model: a published table is never mutated. Updates build
+    /// a new table from the current one and swap it in atomically
+    /// (read-copy-update, retried if another update won the race), so
+    /// concurrent updates are never lost. The reader loads the current
+    /// table once per message without locking; each message is routed by
+    /// either the old or the new table, never a mix, and a channel's
+    /// sender and counters always change together. A message routed by a
+    /// table that was just replaced still reaches the channel it was
+    /// routed to, or counts as `dropped_closed` if that channel is gone.
+    stream_routes: Arc<ArcSwap<StreamRoutes>>,
 
     /// RPC call timeout (from config).
     rpc_timeout: Duration,
 
     /// Monotonic clock origin used for `syncWithHeadsetClock`.
     clock_origin: Instant,
+
+    /// Strict protocol auditing mode (from config).
+    strict_protocol: StrictProtocolMode,
+
+    /// License and session debit passed to `authorize` (from config).
+    license: Option<String>,
+    debit: Option<u32>,
+
+    /// Unmodeled response fields seen so far, per method.
+    unmodeled_fields: std::sync::Mutex<UnmodeledFieldDigest>,
+
+    /// Per-method-class token buckets, when rate limiting is enabled.
+    rate_limiter: Option<RateLimiter>,
+
+    /// Request-shape adaptation for the detected service version.
+    compat: std::sync::RwLock<ProtocolCompat>,
+
+    /// Latency tracking and the session registry. Shared with the
+    /// owning [`ResilientClient`](crate::ResilientClient) across reconnects.
+    tracking: SharedTracking,
+
+    /// Cleared by [`Self::shutdown`]; new RPCs are refused afterwards.
+    accepting_calls: AtomicBool,
+
+    /// Streams subscribed through this connection, by session ID. Only
+    /// consulted to unsubscribe on shutdown (see [`ActiveSubscription`]).
+    subscriptions: ArcSwap<BTreeMap<String, ActiveSubscription>>,
+}
+
+/// Client state that outlives one connection.
+#[derive(Clone)]
+pub(crate) struct SharedTracking {
+    /// Per-method latency window and budget alerts.
+    pub(crate) latency: Arc<LatencyTracker>,
+    /// Sessions created by this client.
+    pub(crate) sessions: Arc<SessionRegistry>,
+    /// Per-method retry settings applied in [`CortexClient::call`].
+    pub(crate) retry: Arc<ArcSwap<RetryHooks>>,
 }
 
 impl CortexClient {
@@ -360,38 +696,53 @@ impl CortexClient {
         // Split the WebSocket into reader and writer halves.
         let (writer, reader) = ws.split();
 
-        let pending_responses: Arc<Mutex<HashMap<u64, PendingResponse>>> =
-            Arc::new(Mutex::new(HashMap::new()));
+        let pending_responses = Arc::new(PendingResponses::default());
 
         let reader_running = Arc::new(AtomicBool::new(true));
         let (reader_shutdown, reader_shutdown_rx) = tokio::sync::watch::channel(false);
-        let stream_senders: Arc<std::sync::Mutex<Option<StreamSenders>>> =
-            Arc::new(std::sync::Mutex::new(None));
-        let stream_dispatch_counters: Arc<std::sync::Mutex<StreamDispatchCounterMap>> =
-            Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let reader_shared = Arc::new(ReaderShared::default());
+        let stream_routes = Arc::new(ArcSwap::from_pointee(StreamRoutes::default()));
+        let writer = Arc::new(FrameWriter::new(writer, &config.writer));
 
         // Start the reader loop immediately — it needs to be running before
         // any API calls so that responses can be dispatched.
         let reader_handle = Self::spawn_reader_loop(
             reader,
-            Arc::clone(&pending_responses),
-            Arc::clone(&reader_running),
-            Arc::clone(&stream_senders),
-            Arc::clone(&stream_dispatch_counters),
+            ReaderLoopState {
+                pending_responses: Arc::clone(&pending_responses),
+                running: Arc::clone(&reader_running),
+                stream_routes: Arc::clone(&stream_routes),
+                shared: Arc::clone(&reader_shared),
+                writer: Arc::clone(&writer),
+                ping: PingSchedule::from_config(&config.ping),
+            },
             reader_shutdown_rx,
         );
 
         Ok(Self {
-            writer: Arc::new(Mutex::new(writer)),
+            writer,
             pending_responses,
-            next_id: AtomicU64::new(1),
-            reader_handle: Some(reader_handle),
+            request_ids: Self::request_id_generator(config.request_ids),
+            reader_handle: std::sync::Mutex::new(Some(reader_handle)),
             reader_running,
             reader_shutdown,
-            stream_senders,
-            stream_dispatch_counters,
+            reader_shared,
+            stream_routes,
             rpc_timeout,
             clock_origin: Instant::now(),
+            strict_protocol: config.strict_protocol,
+            license: config.license.clone(),
+            debit: config.debit,
+            unmodeled_fields: std::sync::Mutex::new(BTreeMap::new()),
+            rate_limiter: RateLimiter::from_config(&config.rate_limit),
+            compat: std::sync::RwLock::new(ProtocolCompat::default()),
+            tracking: SharedTracking {
+                latency: Arc::new(LatencyTracker::from_config(&config.latency)),
+                sessions: Arc::new(SessionRegistry::new(config.app_id.clone())),
+                retry: Arc::new(ArcSwap::from_pointee(RetryHooks::new())),
+            },
+            accepting_calls: AtomicBool::new(true),
+            subscriptions: ArcSwap::from_pointee(BTreeMap::new()),
         })
     }
 
@@ -412,16 +763,84 @@ impl CortexClient {
         Self::connect(&config).await
     }
 
+    /// Replace the request ID generator for this connection.
+    ///
+    /// Use this to plug in a custom id scheme (for example one shared with
+    /// an application's own log correlation). The built-in strategies are
+    /// selected via [`CortexConfig::request_ids`]. Install the generator
+    /// before issuing any calls; ids must stay unique among in-flight
+    /// requests.
+    ///
+    /// A [`ResilientClient`](crate::ResilientClient) builds a new
+    /// `CortexClient` on every reconnect; pass the generator to
+    /// [`ResilientClient::connect_with_request_ids`](crate::ResilientClient::connect_with_request_ids)
+    /// so it is installed on each of them.
+    #[must_use]
+    pub fn with_request_id_generator(mut self, generator: Arc<dyn RequestIdGenerator>) -> Self {
+        self.request_ids = generator;
+        self
+    }
+
+    /// Install a decoder for binary WebSocket frames.
+    ///
+    /// See [`BinaryFrameHandler`]. Replaces any previously installed
+    /// handler; takes effect for the next frame the reader loop sees.
+    #[must_use]
+    pub fn with_binary_frame_handler(self, handler: Arc<dyn BinaryFrameHandler>) -> Self {
+        if let Ok(mut slot) = self.reader_shared.binary_handler.write() {
+            *slot = Some(handler);
+        }
+        self
+    }
+
+    /// Record every inbound frame to `capture`, for replay with
+    /// [`ReplayClient`](crate::replay::ReplayClient).
+    ///
+    /// Replaces any previously installed capture; frames already in
+    /// flight may be missed.
+    #[must_use]
+    pub fn with_capture(self, capture: Arc<FrameCapture>) -> Self {
+        if let Ok(mut slot) = self.reader_shared.capture.write() {
+            *slot = Some(capture);
+        }
+        self
+    }
+
+    fn request_id_generator(strategy: RequestIdStrategy) -> Arc<dyn RequestIdGenerator> {
+        match strategy {
+            RequestIdStrategy::Counter => Arc::new(CounterIds::default()),
+            RequestIdStrategy::ConnectionPrefixed => {
+                Arc::new(ConnectionPrefixedIds::per_connection())
+            }
+        }
+    }
+
     /// Spawn the background reader loop that dispatches WebSocket messages.
+    ///
+    /// The loop blocks on the socket, the shutdown watch channel and, when
+    /// pings are enabled, the ping timer — so an idle connection costs no
+    /// other wake-ups and a shutdown request is observed immediately.
     fn spawn_reader_loop(
         mut reader: WsReader,
-        pending_responses: Arc<Mutex<HashMap<u64, PendingResponse>>>,
-        running: Arc<AtomicBool>,
-        stream_senders: Arc<std::sync::Mutex<Option<StreamSenders>>>,
-        stream_dispatch_counters: Arc<std::sync::Mutex<StreamDispatchCounterMap>>,
+        state: ReaderLoopState,
         mut shutdown_rx: tokio::sync::watch::Receiver<bool>,
     ) -> JoinHandle<()> {
         tokio::spawn(async move {
+            let ReaderLoopState {
+                pending_responses,
+                running,
+                stream_routes,
+                shared,
+                writer,
+                ping,
+            } = state;
+            let mut ping_timer = ping.map(|ping| {
+                let mut timer = tokio::time::interval(ping.interval);
+                timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                timer
+            });
+            let mut last_frame = Instant::now();
+
             while running.load(Ordering::SeqCst) {
                 let msg = tokio::select! {
                     msg = reader.next() => msg,
@@ -432,15 +851,38 @@ impl CortexClient {
                             Err(_) => break,
                         }
                     },
+                    () = Self::next_ping(ping_timer.as_mut()) => {
+                        let silent_for = last_frame.elapsed();
+                        match ping {
+                            Some(ping) if silent_for >= ping.pong_timeout => {
+                                Self::declare_dead(&shared, &pending_responses, silent_for).await;
+                                break;
+                            }
+                            Some(ping) => Self::send_ping(&writer, ping.interval).await,
+                            None => {}
+                        }
+                        continue;
+                    },
                 };
+                shared.wakeups.fetch_add(1, Ordering::Relaxed);
+                last_frame = Instant::now();
 
                 match msg {
                     Some(Ok(Message::Text(text))) => {
                         Self::handle_text_message(
                             &text,
+                            &shared,
                             &pending_responses,
-                            &stream_senders,
-                            &stream_dispatch_counters,
+                            &stream_routes,
+                        )
+                        .await;
+                    }
+                    Some(Ok(Message::Binary(data))) => {
+                        Self::handle_binary_message(
+                            &data,
+                            &shared,
+                            &pending_responses,
+                            &stream_routes,
                         )
                         .await;
                     }
@@ -467,7 +909,7 @@ impl CortexClient {
                         break;
                     }
                     _ => {
-                        // Binary messages, pings, pongs — skip
+                        // Pings, pongs, raw frames — only count as liveness
                     }
                 }
             }
@@ -476,14 +918,54 @@ impl CortexClient {
 
             tracing::debug!("Reader loop exiting");
             running.store(false, Ordering::SeqCst);
+            shared.exited.send_replace(true);
         })
     }
 
+    /// Wait for the next ping tick, or forever when pings are disabled.
+    async fn next_ping(timer: Option<&mut tokio::time::Interval>) {
+        match timer {
+            Some(timer) => {
+                timer.tick().await;
+            }
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Send a ping, giving up after `limit` so a stalled socket cannot
+    /// block the reader loop.
+    async fn send_ping(writer: &FrameWriter, limit: Duration) {
+        match tokio::time::timeout(limit, writer.send(Message::Ping(Vec::new().into()))).await {
+            Ok(Ok(())) => tracing::trace!("Sent WebSocket ping"),
+            Ok(Err(e)) => tracing::debug!(error = %e, "Failed to send WebSocket ping"),
+            Err(_) => tracing::debug!("WebSocket ping send timed out"),
+        }
+    }
+
+    /// Give up on a connection that stopped answering pings.
+    async fn declare_dead(
+        shared: &ReaderShared,
+        pending_responses: &Arc<PendingResponses>,
+        silent_for: Duration,
+    ) {
+        tracing::warn!(
+            silent_ms = u64::try_from(silent_for.as_millis()).unwrap_or(u64::MAX),
+            "No frame from Cortex within the pong timeout; dropping connection"
+        );
+        shared.liveness_failed.store(true, Ordering::SeqCst);
+        let _ = shared.liveness_lost.send(LivenessLost { silent_for });
+        Self::drain_pending_connection_lost(
+            pending_responses,
+            &format!("No pong from Cortex for {}s", silent_for.as_secs()),
+        )
+        .await;
+    }
+
     async fn handle_text_message(
         text: &str,
-        pending_responses: &Arc<Mutex<HashMap<u64, PendingResponse>>>,
-        stream_senders: &Arc<std::sync::Mutex<Option<StreamSenders>>>,
-        stream_dispatch_counters: &Arc<std::sync::Mutex<StreamDispatchCounterMap>>,
+        shared: &ReaderShared,
+        pending_responses: &Arc<PendingResponses>,
+        stream_routes: &ArcSwap<StreamRoutes>,
     ) {
         tracing::debug!(raw = %text, "Reader loop received message");
 
@@ -495,6 +977,54 @@ impl CortexClient {
             }
         };
 
+        Self::dispatch_message(value, shared, pending_responses, stream_routes).await;
+    }
+
+    async fn handle_binary_message(
+        data: &[u8],
+        shared: &ReaderShared,
+        pending_responses: &Arc<PendingResponses>,
+        stream_routes: &ArcSwap<StreamRoutes>,
+    ) {
+        shared.binary_frames.fetch_add(1, Ordering::Relaxed);
+
+        let handler = shared
+            .binary_handler
+            .read()
+            .ok()
+            .and_then(|slot| slot.clone());
+        let Some(handler) = handler else {
+            let previous = shared
+                .unhandled_binary_frames
+                .fetch_add(1, Ordering::Relaxed);
+            if previous == 0 {
+                tracing::warn!(
+                    len = data.len(),
+                    "Received unexpected binary WebSocket frame; skipping \
+                     (install a BinaryFrameHandler to decode these)"
+                );
+            } else {
+                tracing::debug!(len = data.len(), "Skipping binary WebSocket frame");
+            }
+            return;
+        };
+
+        if let Some(value) = handler.handle_binary(data) {
+            Self::dispatch_message(value, shared, pending_responses, stream_routes).await;
+        }
+    }
+
+    /// Route a decoded message: RPC responses by `id`, warnings to their
+    /// channels, everything else as a stream event.
+    async fn dispatch_message(
+        value: serde_json::Value,
+        shared: &ReaderShared,
+        pending_responses: &Arc<PendingResponses>,
+        stream_routes: &ArcSwap<StreamRoutes>,
+    ) {
+        if let Some(capture) = shared.capture() {
+            capture.record(&value);
+        }
         if value
             .get("id")
             .and_then(serde_json::Value::as_u64)
@@ -503,13 +1033,33 @@ impl CortexClient {
             let _ = Self::dispatch_rpc_response(value, pending_responses).await;
             return;
         }
+        if value.get("warning").is_some() {
+            Self::dispatch_warning(&value, shared);
+            return;
+        }
 
-        Self::dispatch_stream_event(value, stream_senders, stream_dispatch_counters);
+        Self::dispatch_stream_event(value, stream_routes);
+    }
+
+    fn dispatch_warning(value: &serde_json::Value, shared: &ReaderShared) {
+        tracing::debug!(warning = %value["warning"], "Cortex warning");
+        if let Some(event) = ProfileUnloaded::from_warning(value) {
+            tracing::warn!(
+                headset = event.headset_id.as_deref().unwrap_or("unknown"),
+                profile = event.profile.as_deref().unwrap_or("unknown"),
+                automatic = event.automatic,
+                "Cortex unloaded a profile"
+            );
+            let _ = shared.profile_unloaded.send(event);
+        }
+        if let Some(warning) = WarningEvent::from_warning(value) {
+            let _ = shared.warnings.send(warning);
+        }
     }
 
     async fn dispatch_rpc_response(
         value: serde_json::Value,
-        pending_responses: &Arc<Mutex<HashMap<u64, PendingResponse>>>,
+        pending_responses: &Arc<PendingResponses>,
     ) -> bool {
         let Some(id) = value.get("id").and_then(serde_json::Value::as_u64) else {
             return false;
@@ -517,8 +1067,7 @@ impl CortexClient {
 
         let response: std::result::Result<CortexResponse, _> = serde_json::from_value(value);
 
-        let mut pending = pending_responses.lock().await;
-        if let Some(tx) = pending.remove(&id) {
+        if let Some(tx) = pending_responses.take(id).await {
             match response {
                 Ok(resp) => {
                     let result = if let Some(error) = resp.error {
@@ -549,39 +1098,34 @@ impl CortexClient {
         true
     }
 
-    fn dispatch_stream_event(
-        value: serde_json::Value,
-        stream_senders: &Arc<std::sync::Mutex<Option<StreamSenders>>>,
-        stream_dispatch_counters: &Arc<std::sync::Mutex<StreamDispatchCounterMap>>,
-    ) {
-        let target_sender = if let Ok(guard) = stream_senders.lock() {
-            guard.as_ref().and_then(|senders| {
-                senders
-                    .iter()
-                    .find_map(|(key, tx)| value.get(*key).is_some().then(|| (*key, tx.clone())))
-            })
-        } else {
-            None
+    fn dispatch_stream_event(value: serde_json::Value, stream_routes: &ArcSwap<StreamRoutes>) {
+        let routes = stream_routes.load();
+        let Some(stream_key) = STREAM_KEYS
+            .into_iter()
+            .find(|key| value.get(*key).is_some())
+        else {
+            return;
         };
+        let sid = value.get("sid").and_then(serde_json::Value::as_str);
 
-        if let Some((stream_key, tx)) = target_sender {
-            let counter = stream_dispatch_counters
-                .lock()
-                .ok()
-                .and_then(|counters| counters.get(stream_key).cloned());
+        if let Some(tx) = routes.sender(stream_key, sid) {
+            let counter = routes.counters.get(stream_key);
 
             match tx.try_send(value) {
                 Ok(()) => {
+                    telemetry::stream_sample_delivered(stream_key);
                     if let Some(counter) = counter {
                         counter.delivered.fetch_add(1, Ordering::Relaxed);
                     }
                 }
                 Err(TrySendError::Full(_)) => {
+                    telemetry::stream_sample_dropped(stream_key, "full");
                     if let Some(counter) = counter {
                         counter.dropped_full.fetch_add(1, Ordering::Relaxed);
                     }
                 }
                 Err(TrySendError::Closed(_)) => {
+                    telemetry::stream_sample_dropped(stream_key, "closed");
                     if let Some(counter) = counter {
                         counter.dropped_closed.fetch_add(1, Ordering::Relaxed);
                     }
@@ -591,36 +1135,76 @@ impl CortexClient {
     }
 
     async fn drain_pending_connection_lost(
-        pending_responses: &Arc<Mutex<HashMap<u64, PendingResponse>>>,
+        pending_responses: &Arc<PendingResponses>,
         reason: &str,
     ) {
-        let mut pending = pending_responses.lock().await;
-        for (_, tx) in pending.drain() {
+        for tx in pending_responses.take_all().await {
             let _ = tx.send(Err(CortexError::ConnectionLost {
                 reason: reason.to_string(),
             }));
         }
     }
 
-    async fn drain_pending_websocket(
-        pending_responses: &Arc<Mutex<HashMap<u64, PendingResponse>>>,
-        reason: String,
-    ) {
-        let mut pending = pending_responses.lock().await;
-        for (_, tx) in pending.drain() {
+    async fn drain_pending_websocket(pending_responses: &Arc<PendingResponses>, reason: String) {
+        for tx in pending_responses.take_all().await {
             let _ = tx.send(Err(CortexError::WebSocket(reason.clone())));
         }
     }
 
     // ─── Core RPC ───────────────────────────────────────────────────────
 
-    /// Send a JSON-RPC request and wait for the matching response.
-    async fn call(
+    /// Send a JSON-RPC request and wait for the matching response,
+    /// retrying it as the installed [`RetryHooks`] allow for `method`.
+    pub(crate) async fn call(
         &self,
         method: &'static str,
         params: serde_json::Value,
     ) -> CortexResult<serde_json::Value> {
-        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        if !self.accepting_calls.load(Ordering::SeqCst) {
+            return Err(CortexError::ConnectionLost {
+                reason: "Client is shutting down".into(),
+            });
+        }
+        // Fail fast so a resilient owner reconnects now rather than after
+        // an RPC timeout on the dead socket.
+        if self.reader_shared.liveness_failed.load(Ordering::SeqCst) {
+            return Err(CortexError::ConnectionLost {
+                reason: "Cortex stopped answering pings".into(),
+            });
+        }
+        let hooks = self.tracking.retry.load_full();
+        if matches!(hooks.policy_for(method), RetryPolicy::None) {
+            return self.call_once(method, params).await;
+        }
+        retry_with_policy(&hooks.for_method(method), || {
+            self.call_once(method, params.clone())
+        })
+        .await
+    }
+
+    async fn call_once(
+        &self,
+        method: &'static str,
+        params: serde_json::Value,
+    ) -> CortexResult<serde_json::Value> {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire(method).await;
+        }
+        let span = telemetry::rpc_started(method);
+        let started = Instant::now();
+        let result = self.call_uninstrumented(method, params).await;
+        self.tracking.latency.record(method, started.elapsed());
+        telemetry::rpc_finished(span, &result);
+        result
+    }
+
+    async fn call_uninstrumented(
+        &self,
+        method: &'static str,
+        params: serde_json::Value,
+    ) -> CortexResult<serde_json::Value> {
+        let id = self.request_ids.next_id();
+        let params = self.protocol_compat().adapt(method, params);
         let request = CortexRequest::new(id, method, params);
 
         let json = serde_json::to_string(&request).map_err(|e| CortexError::ProtocolError {
@@ -629,21 +1213,18 @@ impl CortexClient {
 
         tracing::debug!(method, id, json = %json, "Sending Cortex request");
 
+        if let Some(capture) = self.reader_shared.capture() {
+            capture.expect_response(id, method);
+        }
+
         // Register the pending response before sending
         let (tx, rx) = oneshot::channel();
-        {
-            let mut pending = self.pending_responses.lock().await;
-            pending.insert(id, tx);
-        }
+        self.pending_responses.insert(id, tx).await;
 
         // Send the request via the shared writer
-        let send_result = {
-            let mut writer = self.writer.lock().await;
-            writer.send(Message::Text(json.into())).await
-        };
+        let send_result = self.writer.send(Message::Text(json.into())).await;
         if let Err(e) = send_result {
-            let mut pending = self.pending_responses.lock().await;
-            pending.remove(&id);
+            self.pending_responses.take(id).await;
             return Err(CortexError::WebSocket(format!("Send error: {e}")));
         }
 
@@ -657,7 +1238,7 @@ impl CortexClient {
                 });
             }
             Err(_) => {
-                self.pending_responses.lock().await.remove(&id);
+                self.pending_responses.take(id).await;
                 return Err(CortexError::Timeout {
                     seconds: timeout_secs,
                 });
@@ -668,6 +1249,57 @@ impl CortexClient {
         Ok(result)
     }
 
+    /// Audit field names that a typed response kept in its `extra` map.
+    ///
+    /// Does nothing when strict protocol mode is off. Otherwise the fields
+    /// are added to the per-method digest; `Warn` logs newly seen fields
+    /// and `Error` fails the call with a `ProtocolError`.
+    fn audit_unmodeled_fields<'a>(
+        &self,
+        method: &'static str,
+        fields: impl IntoIterator<Item = &'a String>,
+    ) -> CortexResult<()> {
+        if self.strict_protocol == StrictProtocolMode::Off {
+            return Ok(());
+        }
+
+        let present: BTreeSet<String> = fields.into_iter().cloned().collect();
+        if present.is_empty() {
+            return Ok(());
+        }
+
+        let newly_seen: Vec<&String> = match self.unmodeled_fields.lock() {
+            Ok(mut digest) => {
+                let entry = digest.entry(method).or_default();
+                present
+                    .iter()
+                    .filter(|field| entry.insert((*field).clone()))
+                    .collect()
+            }
+            Err(_) => present.iter().collect(),
+        };
+
+        match self.strict_protocol {
+            StrictProtocolMode::Off => Ok(()),
+            StrictProtocolMode::Warn => {
+                if !newly_seen.is_empty() {
+                    tracing::warn!(
+                        method,
+                        fields = ?newly_seen,
+                        "Cortex response contains unmodeled fields"
+                    );
+                }
+                Ok(())
+            }
+            StrictProtocolMode::Error => Err(CortexError::ProtocolError {
+                reason: format!(
+                    "{method} response contains unmodeled fields: {}",
+                    present.into_iter().collect::<Vec<_>>().join(", ")
+                ),
+            }),
+        }
+    }
+
     fn query_headsets_params(options: QueryHeadsetsOptions) -> serde_json::Value {
         let mut params = serde_json::json!({});
         if let Some(id) = options.id {
@@ -805,8 +1437,6 @@ impl CortexClient {
         let mut params = serde_json::json!({
             "cortexToken": cortex_token,
             "headsetId": headset_id,
-            // Backward-compat for older deployments.
-            "headset": headset_id,
         });
 
         if let Some(pos) = headband_position {
@@ -818,6 +1448,21 @@ impl CortexClient {
         params
     }
 
+    fn create_virtual_headset_params(
+        cortex_token: &str,
+        options: &VirtualHeadsetOptions,
+    ) -> serde_json::Value {
+        let mut params = serde_json::json!({
+            "cortexToken": cortex_token,
+            "channelCount": options.channel_count,
+            "sampleRate": options.sampling_rate_hz,
+        });
+        if let Some(name) = &options.custom_name {
+            params["customName"] = serde_json::json!(name);
+        }
+        params
+    }
+
     fn mental_command_training_threshold_params(
         cortex_token: &str,
         session_id: Option<&str>,
@@ -886,20 +1531,23 @@ impl CortexClient {
         params
     }
 
-    fn query_subjects_params(
+    fn query_params(
         cortex_token: &str,
-        request: &QuerySubjectsRequest,
+        query: &serde_json::Value,
+        order_by: &serde_json::Value,
+        limit: Option<u32>,
+        offset: Option<u32>,
     ) -> serde_json::Value {
         let mut params = serde_json::json!({
             "cortexToken": cortex_token,
-            "query": request.query.clone(),
-            "orderBy": request.order_by.clone(),
+            "query": query,
+            "orderBy": order_by,
         });
 
-        if let Some(limit) = request.limit {
+        if let Some(limit) = limit {
             params["limit"] = serde_json::json!(limit);
         }
-        if let Some(offset) = request.offset {
+        if let Some(offset) = offset {
             params["offset"] = serde_json::json!(offset);
         }
 
@@ -954,7 +1602,7 @@ impl CortexClient {
     // ─── Streaming ──────────────────────────────────────────────────────
 
     /// Stream name validation and mapping to static keys.
-    fn stream_key(name: &str) -> &'static str {
+    pub(crate) fn stream_key(name: &str) -> &'static str {
         match name {
             Streams::EEG => "eeg",
             Streams::DEV => "dev",
@@ -974,103 +1622,281 @@ impl CortexClient {
 
     /// Create data stream channels for the specified streams.
     ///
+    /// The channels receive the streams' events from every session that
+    /// has no channel of its own (see
+    /// [`create_stream_channels_for_session`](Self::create_stream_channels_for_session)).
     /// This replaces ALL existing stream channels. Call before
     /// [`subscribe_streams`](Self::subscribe_streams).
     pub fn create_stream_channels(&self, streams: &[&str]) -> StreamReceivers {
-        let mut senders = StreamSenders::new();
+        let mut routes = StreamRoutes::default();
         let mut receivers = StreamReceivers::new();
-        let mut counters = StreamDispatchCounterMap::new();
 
         for &stream in streams {
             let stream_key = Self::stream_key(stream);
             let (tx, rx) = mpsc::channel(STREAM_CHANNEL_BUFFER);
-            senders.insert(stream_key, tx);
+            routes.insert(StreamRouteKey::any_session(stream_key), tx);
             receivers.insert(stream_key, rx);
-            counters.insert(stream_key, Arc::new(StreamDispatchCounters::default()));
         }
 
-        if let Ok(mut guard) = self.stream_senders.lock() {
-            *guard = Some(senders);
-        }
-        if let Ok(mut guard) = self.stream_dispatch_counters.lock() {
-            *guard = counters;
-        }
+        self.stream_routes.store(Arc::new(routes));
+
+        receivers
+    }
 
+    /// Create data stream channels that receive only `session_id`'s events
+    /// of `streams`, e.g. to subscribe the same stream on two headsets.
+    ///
+    /// Channels of other sessions and the wildcard channels are left in
+    /// place; an existing channel for the same session and stream is
+    /// replaced. Call before [`subscribe_streams`](Self::subscribe_streams).
+    pub fn create_stream_channels_for_session(
+        &self,
+        session_id: &str,
+        streams: &[&str],
+    ) -> StreamReceivers {
+        let mut senders = Vec::new();
+        let mut receivers = StreamReceivers::new();
+        for &stream in streams {
+            let stream_key = Self::stream_key(stream);
+            let (tx, rx) = mpsc::channel(STREAM_CHANNEL_BUFFER);
+            senders.push((StreamRouteKey::session(session_id, stream_key), tx));
+            receivers.insert(stream_key, rx);
+        }
+        self.stream_routes.rcu(|routes| {
+            let mut routes = StreamRoutes::clone(routes);
+            for (key, tx) in &senders {
+                routes.insert(key.clone(), tx.clone());
+            }
+            routes
+        });
         receivers
     }
 
     /// Add a single stream channel without disturbing existing ones.
     ///
-    /// Returns a receiver for the new channel.
+    /// Returns a receiver for the new channel. Replaces an existing
+    /// channel for the same stream, keeping its dispatch counters.
     pub fn add_stream_channel(&self, stream: &str) -> Option<mpsc::Receiver<serde_json::Value>> {
         let stream_key = Self::stream_key(stream);
         let (tx, rx) = mpsc::channel(STREAM_CHANNEL_BUFFER);
-        if let Ok(mut guard) = self.stream_senders.lock() {
-            let senders = guard.get_or_insert_with(StreamSenders::new);
-            senders.insert(stream_key, tx);
-            if let Ok(mut counters) = self.stream_dispatch_counters.lock() {
-                counters
-                    .entry(stream_key)
-                    .or_insert_with(|| Arc::new(StreamDispatchCounters::default()));
+        self.stream_routes.rcu(|routes| {
+            let mut routes = StreamRoutes::clone(routes);
+            routes.insert(StreamRouteKey::any_session(stream_key), tx.clone());
+            routes
+        });
+        Some(rx)
+    }
+
+    /// Route `previous`'s channels to `current`, after a session was
+    /// recreated under a new ID.
+    pub(crate) fn rekey_stream_routes(&self, previous: &str, current: &str) {
+        self.stream_routes.rcu(|routes| {
+            let mut routes = StreamRoutes::clone(routes);
+            let moved: Vec<StreamRouteKey> = routes
+                .senders
+                .keys()
+                .filter(|key| key.session_id.as_deref() == Some(previous))
+                .cloned()
+                .collect();
+            for key in moved {
+                if let Some(tx) = routes.senders.remove(&key) {
+                    routes
+                        .senders
+                        .insert(StreamRouteKey::session(current, key.stream), tx);
+                }
             }
-            Some(rx)
-        } else {
-            None
-        }
+            routes
+        });
+    }
+
+    /// Deliver stream events to the channels of `previous` from now on,
+    /// so receivers handed out by it outlive its connection.
+    pub(crate) fn adopt_stream_routes(&self, previous: &CortexClient) {
+        self.stream_routes.store(previous.stream_routes.load_full());
     }
 
-    /// Remove a single stream channel sender.
+    /// Remove a single (wildcard) stream channel sender.
     pub fn remove_stream_channel(&self, stream: &str) {
-        if let Ok(mut guard) = self.stream_senders.lock() {
-            if let Some(ref mut senders) = *guard {
-                let stream_key = Self::stream_key(stream);
-                senders.remove(stream_key);
-                if let Ok(mut counters) = self.stream_dispatch_counters.lock() {
-                    counters.remove(stream_key);
-                }
+        let key = StreamRouteKey::any_session(Self::stream_key(stream));
+        self.stream_routes.rcu(|routes| {
+            let mut routes = StreamRoutes::clone(routes);
+            routes.remove(&key);
+            routes
+        });
+    }
+
+    /// Remove `session_id`'s own channels for `streams`. Its events of
+    /// those streams go to the wildcard channels again, if any.
+    pub fn remove_session_stream_channels(&self, session_id: &str, streams: &[&str]) {
+        self.stream_routes.rcu(|routes| {
+            let mut routes = StreamRoutes::clone(routes);
+            for &stream in streams {
+                routes.remove(&StreamRouteKey::session(
+                    session_id,
+                    Self::stream_key(stream),
+                ));
             }
-        }
+            routes
+        });
     }
 
     /// Clear all stream senders.
     pub fn clear_stream_channels(&self) {
-        if let Ok(mut guard) = self.stream_senders.lock() {
-            *guard = None;
-        }
-        if let Ok(mut counters) = self.stream_dispatch_counters.lock() {
-            counters.clear();
-        }
+        self.stream_routes.store(Arc::new(StreamRoutes::default()));
     }
 
     /// Returns the current stream dispatch stats keyed by stream type (`"eeg"`, `"mot"`, ...).
     pub fn stream_dispatch_stats(&self) -> HashMap<&'static str, StreamDispatchStats> {
-        if let Ok(counters) = self.stream_dispatch_counters.lock() {
-            counters
-                .iter()
-                .map(|(stream, counter)| (*stream, counter.snapshot()))
-                .collect()
-        } else {
-            HashMap::new()
-        }
+        self.stream_routes
+            .load()
+            .counters
+            .iter()
+            .map(|(stream, counter)| (*stream, counter.snapshot()))
+            .collect()
+    }
+
+    /// Digest of response fields not modeled by the typed protocol structs,
+    /// grouped by Cortex method.
+    ///
+    /// Always empty when [`CortexConfig::strict_protocol`] is
+    /// [`StrictProtocolMode::Off`].
+    #[must_use]
+    pub fn unmodeled_fields(&self) -> UnmodeledFieldDigest {
+        self.unmodeled_fields
+            .lock()
+            .map(|digest| digest.clone())
+            .unwrap_or_default()
+    }
+
+    /// Subscribe to profile-unload warnings pushed by Cortex on this
+    /// connection. See [`ProfileUnloaded`].
+    #[must_use]
+    pub fn profile_unloaded_receiver(&self) -> broadcast::Receiver<ProfileUnloaded> {
+        self.reader_shared.profile_unloaded.subscribe()
+    }
+
+    /// Subscribe to every warning pushed by Cortex on this connection,
+    /// e.g. [`WarningEvent::HeadsetDisconnected`] when a headset is
+    /// unplugged.
+    #[must_use]
+    pub fn warning_receiver(&self) -> broadcast::Receiver<WarningEvent> {
+        self.reader_shared.warnings.subscribe()
+    }
+
+    /// Subscribe to [`LivenessLost`], raised when
+    /// [`CortexConfig::ping`] is enabled and the service goes silent.
+    #[must_use]
+    pub fn liveness_receiver(&self) -> broadcast::Receiver<LivenessLost> {
+        self.reader_shared.liveness_lost.subscribe()
+    }
+
+    /// Request-shape compatibility for the connected service version.
+    ///
+    /// The version is unknown until `getCortexInfo` has succeeded.
+    #[must_use]
+    pub fn protocol_compat(&self) -> ProtocolCompat {
+        self.compat.read().map(|compat| *compat).unwrap_or_default()
+    }
+
+    /// Number of times the reader loop has woken up to handle a frame.
+    ///
+    /// Each inbound WebSocket frame costs one wake-up; an idle connection
+    /// costs none. Test and diagnostics instrumentation only; not part of
+    /// the stable API.
+    #[doc(hidden)]
+    #[must_use]
+    pub fn reader_wakeups(&self) -> u64 {
+        self.reader_shared.wakeups.load(Ordering::Relaxed)
+    }
+
+    /// Number of binary WebSocket frames received, handled or not.
+    #[must_use]
+    pub fn binary_frames_received(&self) -> u64 {
+        self.reader_shared.binary_frames.load(Ordering::Relaxed)
+    }
+
+    /// Number of binary frames skipped because no
+    /// [`BinaryFrameHandler`] was installed.
+    #[must_use]
+    pub fn unhandled_binary_frames(&self) -> u64 {
+        self.reader_shared
+            .unhandled_binary_frames
+            .load(Ordering::Relaxed)
+    }
+
+    /// Contention and wait-time counters of the outgoing frame path.
+    /// See [`crate::writer`].
+    #[must_use]
+    pub fn writer_stats(&self) -> WriterStats {
+        self.writer.stats()
+    }
+
+    /// Per-method-class rate limiter counters.
+    ///
+    /// Empty unless [`CortexConfig::rate_limit`] is enabled.
+    #[must_use]
+    pub fn rate_limit_stats(&self) -> Vec<RateLimitStats> {
+        self.rate_limiter
+            .as_ref()
+            .map(RateLimiter::stats)
+            .unwrap_or_default()
+    }
+
+    /// Per-method call latency percentiles, sorted by method name.
+    /// See [`crate::latency`].
+    #[must_use]
+    pub fn latency_stats(&self) -> Vec<LatencyStats> {
+        self.tracking.latency.stats()
+    }
+
+    /// Subscribe to [`SlowEndpoint`] alerts for calls that exceed their
+    /// [`CortexConfig::latency`] budget.
+    #[must_use]
+    pub fn slow_endpoint_receiver(&self) -> broadcast::Receiver<SlowEndpoint> {
+        self.tracking.latency.subscribe()
+    }
+
+    /// Use `tracking` instead of this client's own, so latency stats,
+    /// alert subscriptions, and session ownership survive replacing the
+    /// client.
+    pub(crate) fn share_tracking(&mut self, tracking: SharedTracking) {
+        self.tracking = tracking;
+    }
+
+    pub(crate) fn tracking(&self) -> SharedTracking {
+        self.tracking.clone()
     }
 
     /// Returns the number of currently pending RPC responses.
     pub async fn pending_response_count(&self) -> usize {
-        self.pending_responses.lock().await.len()
+        self.pending_responses.len().await
     }
 
     // ─── Authentication ─────────────────────────────────────────────────
 
     /// Query Cortex service version and build info.
     ///
-    /// No authentication required. Useful as a health check.
+    /// No authentication required. Useful as a health check. The reported
+    /// version selects the request shapes used from then on; see
+    /// [`crate::compat`].
     ///
     /// # Errors
     /// Returns any error produced by the underlying Cortex API call,
     /// including connection, authentication, protocol, timeout, and configuration errors.
     pub async fn get_cortex_info(&self) -> CortexResult<serde_json::Value> {
-        self.call(Methods::GET_CORTEX_INFO, serde_json::json!({}))
-            .await
+        let info = self
+            .call(Methods::GET_CORTEX_INFO, serde_json::json!({}))
+            .await?;
+        let compat = ProtocolCompat::from_cortex_info(&info);
+        if let Ok(mut current) = self.compat.write() {
+            if *current != compat {
+                if let Some(version) = compat.version() {
+                    tracing::debug!(%version, "Detected Cortex service version");
+                }
+                *current = compat;
+            }
+        }
+        Ok(info)
     }
 
     /// Check if the application has been granted access rights.
@@ -1099,6 +1925,30 @@ impl CortexClient {
             .unwrap_or(false))
     }
 
+    /// Ask the user to approve this application in the EMOTIV Launcher.
+    ///
+    /// Returns the raw result (`accessGranted`, `message`). Approval is
+    /// asynchronous; poll [`Self::has_access_right`] to wait for it.
+    ///
+    /// # Errors
+    /// Returns any error produced by the underlying Cortex API call,
+    /// including connection, authentication, protocol, timeout, and configuration errors.
+    /// Cortex versions without this method return [`CortexError::MethodNotFound`].
+    pub async fn request_access(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+    ) -> CortexResult<serde_json::Value> {
+        self.call(
+            Methods::REQUEST_ACCESS,
+            serde_json::json!({
+                "clientId": client_id,
+                "clientSecret": client_secret,
+            }),
+        )
+        .await
+    }
+
     /// Get the currently logged-in Emotiv user.
     ///
     /// # Errors
@@ -1117,9 +1967,15 @@ impl CortexClient {
     /// Authenticate with the Cortex API.
     ///
     /// Performs: `getCortexInfo` → `requestAccess` → `authorize`.
+    /// `authorize` carries the config's [`license`] and [`debit`] when set;
+    /// each call debits again, so prefer [`Self::generate_new_token`] to
+    /// renew a token that is still known to Cortex.
     ///
     /// Returns the cortex token needed for all subsequent operations.
     ///
+    /// [`license`]: CortexConfig::license
+    /// [`debit`]: CortexConfig::debit
+    ///
     /// # Errors
     /// Returns any error produced by the underlying Cortex API call,
     /// including connection, authentication, protocol, timeout, and configuration errors.
@@ -1137,16 +1993,7 @@ impl CortexClient {
         };
 
         // Step 1: requestAccess — gracefully skip if method doesn't exist
-        match self
-            .call(
-                Methods::REQUEST_ACCESS,
-                serde_json::json!({
-                    "clientId": client_id,
-                    "clientSecret": client_secret,
-                }),
-            )
-            .await
-        {
+        match self.request_access(client_id, client_secret).await {
             Ok(_) => tracing::debug!("Cortex access requested"),
             Err(CortexError::MethodNotFound { .. }) => {
                 tracing::info!(
@@ -1158,16 +2005,18 @@ impl CortexClient {
         }
 
         // Step 2: authorize and get a cortex token
-        let auth_result = match self
-            .call(
-                Methods::AUTHORIZE,
-                serde_json::json!({
-                    "clientId": client_id,
-                    "clientSecret": client_secret,
-                }),
-            )
-            .await
-        {
+        let mut params = serde_json::json!({
+            "clientId": client_id,
+            "clientSecret": client_secret,
+        });
+        if let Some(license) = &self.license {
+            params["license"] = serde_json::json!(license);
+        }
+        if let Some(debit) = self.debit {
+            tracing::info!(debit, "Debiting sessions from the license");
+            params["debit"] = serde_json::json!(debit);
+        }
+        let auth_result = match self.call(Methods::AUTHORIZE, params).await {
             Ok(result) => result,
             Err(CortexError::MethodNotFound { .. }) => {
                 if !cortex_info_ok {
@@ -1236,29 +2085,52 @@ impl CortexClient {
     /// # Errors
     /// Returns any error produced by the underlying Cortex API call,
     /// including connection, authentication, protocol, timeout, and configuration errors.
-    pub async fn get_user_info(&self, cortex_token: &str) -> CortexResult<serde_json::Value> {
-        self.call(
-            Methods::GET_USER_INFO,
-            serde_json::json!({
-                "cortexToken": cortex_token,
-            }),
-        )
-        .await
+    pub async fn get_user_info(&self, cortex_token: &str) -> CortexResult<UserInfo> {
+        let result = self
+            .call(
+                Methods::GET_USER_INFO,
+                serde_json::json!({
+                    "cortexToken": cortex_token,
+                }),
+            )
+            .await?;
+
+        let info: UserInfo =
+            serde_json::from_value(result).map_err(|e| CortexError::ProtocolError {
+                reason: format!("Failed to parse user info: {e}"),
+            })?;
+        self.audit_unmodeled_fields(Methods::GET_USER_INFO, info.extra.keys())?;
+        Ok(info)
     }
 
-    /// Get information about the license used by the application.
+    /// Get information about the license used by the application: scopes,
+    /// validity, and session quota. See [`LicenseInfo::has_eeg_access`].
     ///
     /// # Errors
     /// Returns any error produced by the underlying Cortex API call,
     /// including connection, authentication, protocol, timeout, and configuration errors.
-    pub async fn get_license_info(&self, cortex_token: &str) -> CortexResult<serde_json::Value> {
-        self.call(
+    pub async fn get_license_info(&self, cortex_token: &str) -> CortexResult<LicenseInfo> {
+        let result = self
+            .call(
+                Methods::GET_LICENSE_INFO,
+                serde_json::json!({
+                    "cortexToken": cortex_token,
+                }),
+            )
+            .await?;
+
+        let info: LicenseInfo =
+            serde_json::from_value(result).map_err(|e| CortexError::ProtocolError {
+                reason: format!("Failed to parse license info: {e}"),
+            })?;
+        self.audit_unmodeled_fields(
             Methods::GET_LICENSE_INFO,
-            serde_json::json!({
-                "cortexToken": cortex_token,
-            }),
-        )
-        .await
+            info.extra
+                .keys()
+                .chain(info.license.extra.keys())
+                .chain(info.license.device_info.iter().flat_map(|d| d.extra.keys())),
+        )?;
+        Ok(info)
     }
 
     // ─── Headset Management ─────────────────────────────────────────────
@@ -1284,6 +2156,11 @@ impl CortexClient {
                 reason: format!("Failed to parse headset list: {e}"),
             })?;
 
+        self.audit_unmodeled_fields(
+            Methods::QUERY_HEADSETS,
+            headsets.iter().flat_map(|h| h.extra.keys()),
+        )?;
+
         tracing::info!(count = headsets.len(), "Queried headsets");
 
         Ok(headsets)
@@ -1397,6 +2274,7 @@ impl CortexClient {
                     serde_json::from_value(result).map_err(|e| CortexError::ProtocolError {
                         reason: format!("Failed to parse configMapping value response: {e}"),
                     })?;
+                self.audit_unmodeled_fields(Methods::CONFIG_MAPPING, parsed.value.extra.keys())?;
                 Ok(ConfigMappingResponse::Value {
                     message: parsed.message,
                     value: parsed.value,
@@ -1412,6 +2290,14 @@ impl CortexClient {
                     serde_json::from_value(result).map_err(|e| CortexError::ProtocolError {
                         reason: format!("Failed to parse configMapping get response: {e}"),
                     })?;
+                self.audit_unmodeled_fields(
+                    Methods::CONFIG_MAPPING,
+                    parsed
+                        .value
+                        .extra
+                        .keys()
+                        .chain(parsed.value.config.iter().flat_map(|c| c.extra.keys())),
+                )?;
                 Ok(ConfigMappingResponse::List {
                     message: parsed.message,
                     value: parsed.value,
@@ -1482,33 +2368,102 @@ impl CortexClient {
     ///
     /// Returns: raw JSON-RPC result payload from Cortex.
     /// Errors: validation/headset/auth errors are propagated.
-    /// Related methods: [`Self::update_headset`], [`Self::query_headsets`].
+    /// Related methods: [`Self::update_headset`], [`Self::query_headsets`].
+    ///
+    /// # Errors
+    /// Returns any error produced by the underlying Cortex API call,
+    /// including connection, authentication, protocol, timeout, and configuration errors.
+    pub async fn update_headset_custom_info(
+        &self,
+        cortex_token: &str,
+        headset_id: &str,
+        headband_position: Option<&str>,
+        custom_name: Option<&str>,
+    ) -> CortexResult<serde_json::Value> {
+        self.call(
+            Methods::UPDATE_HEADSET_CUSTOM_INFO,
+            Self::update_headset_custom_info_params(
+                cortex_token,
+                headset_id,
+                headband_position,
+                custom_name,
+            ),
+        )
+        .await
+    }
+
+    /// Create a virtual headset, which Cortex then reports from
+    /// `queryHeadsets` (with `isVirtual: true`) and streams simulated data
+    /// from like a real device.
+    ///
+    /// Cortex method: `createVirtualHeadset`
+    /// Required state: authenticated token.
+    /// Parameters:
+    ///
+    /// - `options`: channel count, sampling rate, and optional display name
+    ///
+    /// Returns: the created headset.
+    /// Errors: validation/license/auth errors are propagated.
+    /// Retry/idempotency: not idempotent; each call creates a new device.
+    /// Related methods: [`Self::delete_virtual_headset`], [`Self::query_headsets`].
+    ///
+    /// # Errors
+    /// Returns any error produced by the underlying Cortex API call,
+    /// including connection, authentication, protocol, timeout, and configuration errors.
+    pub async fn create_virtual_headset(
+        &self,
+        cortex_token: &str,
+        options: &VirtualHeadsetOptions,
+    ) -> CortexResult<HeadsetInfo> {
+        let result = self
+            .call(
+                Methods::CREATE_VIRTUAL_HEADSET,
+                Self::create_virtual_headset_params(cortex_token, options),
+            )
+            .await?;
+
+        let headset: HeadsetInfo =
+            serde_json::from_value(result).map_err(|e| CortexError::ProtocolError {
+                reason: format!("Failed to parse virtual headset: {e}"),
+            })?;
+
+        tracing::info!(headset_id = %headset.id, "Virtual headset created");
+        Ok(headset)
+    }
+
+    /// Delete a virtual headset.
+    ///
+    /// Cortex method: `deleteVirtualHeadset`
+    /// Required state: authenticated token.
+    /// Parameters:
+    ///
+    /// - `headset_id`: id returned by [`Self::create_virtual_headset`]
+    ///
+    /// Returns: raw JSON-RPC result payload from Cortex.
+    /// Errors: validation/headset/auth errors are propagated.
+    /// Related methods: [`Self::create_virtual_headset`].
     ///
     /// # Errors
     /// Returns any error produced by the underlying Cortex API call,
     /// including connection, authentication, protocol, timeout, and configuration errors.
-    pub async fn update_headset_custom_info(
+    pub async fn delete_virtual_headset(
         &self,
         cortex_token: &str,
         headset_id: &str,
-        headband_position: Option<&str>,
-        custom_name: Option<&str>,
     ) -> CortexResult<serde_json::Value> {
         self.call(
-            Methods::UPDATE_HEADSET_CUSTOM_INFO,
-            Self::update_headset_custom_info_params(
-                cortex_token,
-                headset_id,
-                headband_position,
-                custom_name,
-            ),
+            Methods::DELETE_VIRTUAL_HEADSET,
+            serde_json::json!({
+                "cortexToken": cortex_token,
+                "headsetId": headset_id,
+            }),
         )
         .await
     }
 
     // ─── Session Management ─────────────────────────────────────────────
 
-    /// Create a session for a headset.
+    /// Create an active session for a headset.
     ///
     /// # Errors
     /// Returns any error produced by the underlying Cortex API call,
@@ -1517,6 +2472,23 @@ impl CortexClient {
         &self,
         cortex_token: &str,
         headset_id: &str,
+    ) -> CortexResult<SessionInfo> {
+        self.create_session_with_status(cortex_token, headset_id, SessionStatus::Active)
+            .await
+    }
+
+    /// Create a session for a headset with the given initial `status`.
+    /// An [`SessionStatus::Open`] session must be activated with
+    /// [`Self::activate_session`] before data calls.
+    ///
+    /// # Errors
+    /// Returns any error produced by the underlying Cortex API call,
+    /// including connection, authentication, protocol, timeout, and configuration errors.
+    pub async fn create_session_with_status(
+        &self,
+        cortex_token: &str,
+        headset_id: &str,
+        status: SessionStatus,
     ) -> CortexResult<SessionInfo> {
         let result = self
             .call(
@@ -1524,7 +2496,7 @@ impl CortexClient {
                 serde_json::json!({
                     "cortexToken": cortex_token,
                     "headset": headset_id,
-                    "status": "active",
+                    "status": status.as_str(),
                 }),
             )
             .await?;
@@ -1535,15 +2507,91 @@ impl CortexClient {
             })?;
 
         tracing::info!(session_id = %session.id, "Session created");
+        self.tracking.sessions.created(&session);
         Ok(session)
     }
 
+    /// IDs of sessions created by this client and not yet closed through
+    /// it. See [`crate::ownership`].
+    #[must_use]
+    pub fn owned_sessions(&self) -> Vec<String> {
+        self.tracking.sessions.created_ids()
+    }
+
+    /// This client's Cortex application ID, from
+    /// [`CortexConfig::app_id`] or learned from the first session it
+    /// created.
+    #[must_use]
+    pub fn app_id(&self) -> Option<String> {
+        self.tracking.sessions.app_id()
+    }
+
+    /// Classify who opened `session`.
+    #[must_use]
+    pub fn session_owner(&self, session: &SessionInfo) -> SessionOwner {
+        self.tracking.sessions.owner(session)
+    }
+
+    /// Close every open session in `scope`, optionally only those bound
+    /// to `headset_id`.
+    ///
+    /// Sessions of other applications are skipped unless `scope` is
+    /// [`CleanupScope::All`]. Returns each attempted session ID with its
+    /// outcome; a failed close does not stop the others.
+    ///
+    /// # Errors
+    /// Returns any error produced by `querySessions`.
+    pub async fn close_sessions(
+        &self,
+        cortex_token: &str,
+        scope: CleanupScope,
+        headset_id: Option<&str>,
+    ) -> CortexResult<Vec<(String, CortexResult<()>)>> {
+        let sessions = self.query_sessions(cortex_token).await?;
+        let mut closed = Vec::new();
+        for session in sessions {
+            if !session.is_open() {
+                continue;
+            }
+            if let Some(headset_id) = headset_id {
+                if session.headset.as_ref().is_none_or(|h| h.id != headset_id) {
+                    continue;
+                }
+            }
+            let owner = self.session_owner(&session);
+            if !scope.allows(owner) {
+                tracing::debug!(session_id = %session.id, app_id = %session.app_id, "Leaving session of another application open");
+                continue;
+            }
+            let result = self.close_session(cortex_token, &session.id).await;
+            closed.push((session.id, result));
+        }
+        Ok(closed)
+    }
+
     /// Query existing sessions.
     ///
     /// # Errors
     /// Returns any error produced by the underlying Cortex API call,
     /// including connection, authentication, protocol, timeout, and configuration errors.
     pub async fn query_sessions(&self, cortex_token: &str) -> CortexResult<Vec<SessionInfo>> {
+        self.query_sessions_with(cortex_token, &QuerySessionsRequest::default())
+            .await
+    }
+
+    /// Query existing sessions, filtered and sorted client-side.
+    ///
+    /// Cortex's `querySessions` takes no query, so `request` is applied to
+    /// the raw results before parsing; see [`QuerySessionsRequest`].
+    ///
+    /// # Errors
+    /// Returns any error produced by the underlying Cortex API call,
+    /// including connection, authentication, protocol, timeout, and configuration errors.
+    pub async fn query_sessions_with(
+        &self,
+        cortex_token: &str,
+        request: &QuerySessionsRequest,
+    ) -> CortexResult<Vec<SessionInfo>> {
         let result = self
             .call(
                 Methods::QUERY_SESSIONS,
@@ -1553,11 +2601,45 @@ impl CortexClient {
             )
             .await?;
 
-        serde_json::from_value(result).map_err(|e| CortexError::ProtocolError {
-            reason: format!("Failed to parse sessions: {e}"),
+        let sessions: Vec<serde_json::Value> =
+            serde_json::from_value(result).map_err(|e| CortexError::ProtocolError {
+                reason: format!("Failed to parse sessions: {e}"),
+            })?;
+
+        serde_json::from_value(serde_json::Value::Array(request.apply(sessions))).map_err(|e| {
+            CortexError::ProtocolError {
+                reason: format!("Failed to parse sessions: {e}"),
+            }
         })
     }
 
+    /// Re-activate an existing session.
+    ///
+    /// Cortex method: `updateSession` with `status = "active"`.
+    /// Required state: authenticated token and a valid `session_id`.
+    /// Returns: `Ok(())` once Cortex confirms the session update call.
+    /// Errors: session/auth/transport errors are propagated.
+    /// Retry/idempotency: activating an already active session is harmless.
+    /// Related methods: [`Self::create_session`], [`Self::close_session`].
+    ///
+    /// # Errors
+    /// Returns any error produced by the underlying Cortex API call,
+    /// including connection, authentication, protocol, timeout, and configuration errors.
+    pub async fn activate_session(&self, cortex_token: &str, session_id: &str) -> CortexResult<()> {
+        self.call(
+            Methods::UPDATE_SESSION,
+            serde_json::json!({
+                "cortexToken": cortex_token,
+                "session": session_id,
+                "status": "active",
+            }),
+        )
+        .await?;
+
+        tracing::info!(session_id, "Session activated");
+        Ok(())
+    }
+
     /// Close an active session.
     ///
     /// Cortex method: `updateSession` with `status = "close"`.
@@ -1582,6 +2664,12 @@ impl CortexClient {
         .await?;
 
         tracing::info!(session_id, "Session closed");
+        self.tracking.sessions.closed(session_id);
+        self.subscriptions.rcu(|subscriptions| {
+            let mut subscriptions = BTreeMap::clone(subscriptions);
+            subscriptions.remove(session_id);
+            subscriptions
+        });
         Ok(())
     }
 
@@ -1589,6 +2677,11 @@ impl CortexClient {
 
     /// Subscribe to one or more data streams.
     ///
+    /// Cortex may accept some streams and reject others (e.g. `pow`
+    /// without a license); the call still succeeds and the per-stream
+    /// outcome is returned. Channels created for rejected streams are
+    /// removed, so their receivers end instead of waiting forever.
+    ///
     /// # Errors
     /// Returns any error produced by the underlying Cortex API call,
     /// including connection, authentication, protocol, timeout, and configuration errors.
@@ -1597,7 +2690,57 @@ impl CortexClient {
         cortex_token: &str,
         session_id: &str,
         streams: &[&str],
-    ) -> CortexResult<serde_json::Value> {
+    ) -> CortexResult<SubscriptionResult> {
+        let result = self
+            .subscribe_streams_once(cortex_token, session_id, streams)
+            .await?;
+        self.remove_failed_stream_channels(session_id, &result);
+        Ok(result)
+    }
+
+    /// Like [`Self::subscribe_streams`], but re-subscribes the rejected
+    /// streams once before giving up on them.
+    ///
+    /// Useful when a stream is briefly unavailable right after session
+    /// activation.
+    ///
+    /// # Errors
+    /// Returns any error produced by the underlying Cortex API calls,
+    /// including connection, authentication, protocol, timeout, and configuration errors.
+    pub async fn subscribe_streams_with_retry(
+        &self,
+        cortex_token: &str,
+        session_id: &str,
+        streams: &[&str],
+    ) -> CortexResult<SubscriptionResult> {
+        let mut result = self
+            .subscribe_streams_once(cortex_token, session_id, streams)
+            .await?;
+
+        if !result.is_complete() {
+            let failed: Vec<String> = result
+                .failed_streams()
+                .into_iter()
+                .map(String::from)
+                .collect();
+            let failed_refs: Vec<&str> = failed.iter().map(String::as_str).collect();
+            tracing::debug!(session_id, streams = ?failed_refs, "Retrying failed subscriptions");
+            let retry = self
+                .subscribe_streams_once(cortex_token, session_id, &failed_refs)
+                .await?;
+            result.merge_retry(retry);
+        }
+
+        self.remove_failed_stream_channels(session_id, &result);
+        Ok(result)
+    }
+
+    async fn subscribe_streams_once(
+        &self,
+        cortex_token: &str,
+        session_id: &str,
+        streams: &[&str],
+    ) -> CortexResult<SubscriptionResult> {
         let resp = self
             .call(
                 Methods::SUBSCRIBE,
@@ -1609,8 +2752,51 @@ impl CortexClient {
             )
             .await?;
 
-        tracing::info!(session_id, ?streams, "Subscribed to data streams");
-        Ok(resp)
+        let result = SubscriptionResult::from_response(&resp);
+        for subscription in &result.success {
+            telemetry::stream_subscribed(&subscription.stream_name);
+        }
+        if !result.success.is_empty() {
+            self.subscriptions.rcu(|subscriptions| {
+                let mut subscriptions = BTreeMap::clone(subscriptions);
+                let active = subscriptions.entry(session_id.to_string()).or_default();
+                active.cortex_token = cortex_token.to_string();
+                active.streams.extend(
+                    result
+                        .success
+                        .iter()
+                        .map(|subscription| subscription.stream_name.clone()),
+                );
+                subscriptions
+            });
+        }
+        if result.is_complete() {
+            tracing::info!(session_id, ?streams, "Subscribed to data streams");
+        } else {
+            for failure in &result.failure {
+                tracing::warn!(
+                    session_id,
+                    stream = %failure.stream_name,
+                    code = failure.code,
+                    message = %failure.message,
+                    "Stream subscription rejected"
+                );
+            }
+        }
+        Ok(result)
+    }
+
+    /// Drop the channels of streams that failed to subscribe: the
+    /// session's own channel if it has one, else the wildcard channel.
+    fn remove_failed_stream_channels(&self, session_id: &str, result: &SubscriptionResult) {
+        for stream in result.failed_streams() {
+            let key = StreamRouteKey::session(session_id, Self::stream_key(stream));
+            if self.stream_routes.load().senders.contains_key(&key) {
+                self.remove_session_stream_channels(session_id, &[stream]);
+            } else {
+                self.remove_stream_channel(stream);
+            }
+        }
     }
 
     /// Unsubscribe from one or more data streams.
@@ -1634,10 +2820,29 @@ impl CortexClient {
         )
         .await?;
 
+        for stream in streams {
+            telemetry::stream_unsubscribed(stream);
+        }
+        self.forget_subscriptions(session_id, streams);
         tracing::info!(session_id, ?streams, "Unsubscribed from data streams");
         Ok(())
     }
 
+    fn forget_subscriptions(&self, session_id: &str, streams: &[&str]) {
+        self.subscriptions.rcu(|subscriptions| {
+            let mut subscriptions = BTreeMap::clone(subscriptions);
+            if let Some(active) = subscriptions.get_mut(session_id) {
+                for stream in streams {
+                    active.streams.remove(*stream);
+                }
+                if active.streams.is_empty() {
+                    subscriptions.remove(session_id);
+                }
+            }
+            subscriptions
+        });
+    }
+
     // ─── Records ────────────────────────────────────────────────────────
 
     /// Start a new recording.
@@ -1727,20 +2932,36 @@ impl CortexClient {
         limit: Option<u32>,
         offset: Option<u32>,
     ) -> CortexResult<Vec<RecordInfo>> {
-        let mut params = serde_json::json!({
-            "cortexToken": cortex_token,
-            "query": {},
-            "orderBy": [{ "startDatetime": "DESC" }],
-        });
-
-        if let Some(limit) = limit {
-            params["limit"] = serde_json::json!(limit);
-        }
-        if let Some(offset) = offset {
-            params["offset"] = serde_json::json!(offset);
-        }
+        let request = QueryRecordsRequest {
+            limit,
+            offset,
+            ..QueryRecordsRequest::default()
+        };
+        self.query_records_with(cortex_token, &request).await
+    }
 
-        let result = self.call(Methods::QUERY_RECORDS, params).await?;
+    /// Query recorded sessions with filtering, sorting, and pagination.
+    ///
+    /// # Errors
+    /// Returns any error produced by the underlying Cortex API call,
+    /// including connection, authentication, protocol, timeout, and configuration errors.
+    pub async fn query_records_with(
+        &self,
+        cortex_token: &str,
+        request: &QueryRecordsRequest,
+    ) -> CortexResult<Vec<RecordInfo>> {
+        let result = self
+            .call(
+                Methods::QUERY_RECORDS,
+                Self::query_params(
+                    cortex_token,
+                    &request.query,
+                    &request.order_by,
+                    request.limit,
+                    request.offset,
+                ),
+            )
+            .await?;
 
         let records = result
             .get("records")
@@ -1754,6 +2975,9 @@ impl CortexClient {
 
     /// Export a recording to CSV or EDF format.
     ///
+    /// Shorthand for [`Self::export_record_with`] without optional
+    /// parameters.
+    ///
     /// # Errors
     /// Returns any error produced by the underlying Cortex API call,
     /// including connection, authentication, protocol, timeout, and configuration errors.
@@ -1763,25 +2987,89 @@ impl CortexClient {
         record_ids: &[String],
         folder: &str,
         format: ExportFormat,
-    ) -> CortexResult<()> {
-        self.call(
-            Methods::EXPORT_RECORD,
-            serde_json::json!({
-                "cortexToken": cortex_token,
-                "recordIds": record_ids,
-                "folder": folder,
-                "format": format.as_str(),
-            }),
+    ) -> CortexResult<ExportResult> {
+        self.export_record_with(
+            cortex_token,
+            &ExportRecordOptions::new(record_ids, folder, format),
         )
-        .await?;
+        .await
+    }
+
+    /// Export recordings as described by `options`.
+    ///
+    /// Cortex method: `exportRecord`
+    /// Required state: authenticated token; the records must be stopped.
+    /// Returns: which records were exported and which failed, with reasons.
+    /// A partial failure is not an error; check [`ExportResult::is_complete`].
+    /// Related methods: [`Self::query_records`], [`Self::stop_record`].
+    ///
+    /// # Errors
+    /// Returns any error produced by the underlying Cortex API call,
+    /// including connection, authentication, protocol, timeout, and configuration errors.
+    pub async fn export_record_with(
+        &self,
+        cortex_token: &str,
+        options: &ExportRecordOptions,
+    ) -> CortexResult<ExportResult> {
+        let result = self
+            .call(
+                Methods::EXPORT_RECORD,
+                Self::export_record_params(cortex_token, options),
+            )
+            .await?;
+
+        let exported: ExportResult =
+            serde_json::from_value(result).map_err(|e| CortexError::ProtocolError {
+                reason: format!("Failed to parse export result: {e}"),
+            })?;
 
         tracing::info!(
-            ?record_ids,
-            folder,
-            format = format.as_str(),
-            "Export initiated"
+            exported = exported.success.len(),
+            failed = exported.failure.len(),
+            folder = %options.folder,
+            format = options.format.as_str(),
+            "Export finished"
         );
-        Ok(())
+        for failure in &exported.failure {
+            tracing::warn!(record_id = %failure.record_id, reason = %failure.message, "Record not exported");
+        }
+        Ok(exported)
+    }
+
+    fn export_record_params(
+        cortex_token: &str,
+        options: &ExportRecordOptions,
+    ) -> serde_json::Value {
+        let mut params = serde_json::json!({
+            "cortexToken": cortex_token,
+            "recordIds": options.record_ids,
+            "folder": options.folder,
+            "format": options.format.as_str(),
+        });
+        if let Some(stream_types) = &options.stream_types {
+            params["streamTypes"] = serde_json::json!(stream_types);
+        }
+        if let Some(version) = &options.version {
+            params["version"] = serde_json::json!(version);
+        }
+        if let Some(license_ids) = &options.license_ids {
+            params["licenseIds"] = serde_json::json!(license_ids);
+        }
+        let flags = [
+            ("includeDemographics", options.include_demographics),
+            ("includeSurvey", options.include_survey),
+            (
+                "includeMarkerExtraInfos",
+                options.include_marker_extra_infos,
+            ),
+            ("includeDeprecatedPM", options.include_deprecated_pm),
+        ];
+        for (name, value) in flags {
+            if let Some(value) = value {
+                params[name] = serde_json::json!(value);
+            }
+        }
+        params
     }
 
     /// Update a recording's metadata (title, description, tags).
@@ -2154,7 +3442,13 @@ impl CortexClient {
         let result = self
             .call(
                 Methods::QUERY_SUBJECTS,
-                Self::query_subjects_params(cortex_token, request),
+                Self::query_params(
+                    cortex_token,
+                    &request.query,
+                    &request.order_by,
+                    request.limit,
+                    request.offset,
+                ),
             )
             .await?;
 
@@ -2272,6 +3566,7 @@ impl CortexClient {
             serde_json::from_value(result).map_err(|e| CortexError::ProtocolError {
                 reason: format!("Failed to parse current profile info: {e}"),
             })?;
+        self.audit_unmodeled_fields(Methods::GET_CURRENT_PROFILE, profile.extra.keys())?;
 
         Ok(profile)
     }
@@ -2735,11 +4030,141 @@ impl CortexClient {
     pub async fn stop_reader(&mut self) {
         self.reader_running.store(false, Ordering::SeqCst);
         let _ = self.reader_shutdown.send(true);
-        if let Some(handle) = self.reader_handle.take() {
+        if let Some(handle) = self.take_reader_handle() {
             let _ = tokio::time::timeout(Duration::from_secs(2), handle).await;
         }
     }
 
+    /// Hand the reader loop's handle to an owner that tracks its tasks.
+    /// [`Self::stop_reader`] no longer waits for the loop afterwards.
+    pub(crate) fn take_reader_handle(&self) -> Option<JoinHandle<()>> {
+        self.reader_handle
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .take()
+    }
+
+    /// Stop the reader loop and send a WebSocket Close frame through a
+    /// shared reference, for owners that cannot get `&mut self` (the
+    /// client may be held in several `Arc`s). Unlike [`Self::disconnect`]
+    /// this does not wait for the reader task to exit.
+    pub(crate) async fn close_connection(&self) -> CortexResult<()> {
+        self.reader_running.store(false, Ordering::SeqCst);
+        let _ = self.reader_shutdown.send(true);
+        self.writer
+            .close()
+            .await
+            .map_err(|e| CortexError::WebSocket(format!("Close error: {e}")))
+    }
+
+    /// Shut the connection down without cutting off work in flight.
+    ///
+    /// In order:
+    ///
+    /// 1. New RPCs are refused with [`CortexError::ConnectionLost`].
+    /// 2. RPCs already sent get up to `grace` to be answered; the rest are
+    ///    counted in [`ShutdownReport::abandoned_requests`].
+    /// 3. Streams subscribed through this client are unsubscribed.
+    /// 4. A WebSocket Close frame is sent, and the reader loop is joined
+    ///    once the server answers it (or after a short timeout).
+    ///
+    /// Prefer this over [`Self::disconnect`] when a record is running or
+    /// responses still matter, e.g. a `stopRecord` issued by another task
+    /// just before. It takes `&self`, so a client shared in an `Arc` can be
+    /// shut down while other tasks still hold it.
+    pub async fn shutdown(&self, grace: Duration) -> ShutdownReport {
+        self.accepting_calls.store(false, Ordering::SeqCst);
+        let mut report = ShutdownReport {
+            abandoned_requests: self.drain_pending(grace).await,
+            ..ShutdownReport::default()
+        };
+
+        // Subscriptions die with a connection that is already gone.
+        let subscriptions = Arc::unwrap_or_clone(self.subscriptions.swap(Arc::default()));
+        let subscriptions = subscriptions.into_iter().filter(|_| self.is_connected());
+        for (session_id, active) in subscriptions {
+            let streams: Vec<&str> = active.streams.iter().map(String::as_str).collect();
+            let params = serde_json::json!({
+                "cortexToken": active.cortex_token,
+                "session": session_id,
+                "streams": streams,
+            });
+            match self.call_once(Methods::UNSUBSCRIBE, params).await {
+                Ok(_) => {
+                    for stream in &streams {
+                        telemetry::stream_unsubscribed(stream);
+                    }
+                    report.unsubscribed.extend(
+                        streams
+                            .iter()
+                            .map(|stream| (session_id.clone(), (*stream).to_string())),
+                    );
+                }
+                Err(e) => {
+                    tracing::warn!(session_id, error = %e, "Failed to unsubscribe on shutdown");
+                    report.unsubscribe_errors.push((session_id, e.to_string()));
+                }
+            }
+        }
+
+        if let Err(e) = self.writer.close().await {
+            tracing::debug!(error = %e, "Failed to send Close frame");
+        }
+        report.clean_close = self.join_reader(CLOSE_HANDSHAKE_TIMEOUT).await;
+        if !report.clean_close {
+            self.reader_running.store(false, Ordering::SeqCst);
+            let _ = self.reader_shutdown.send(true);
+            self.join_reader(CLOSE_HANDSHAKE_TIMEOUT).await;
+        }
+
+        tracing::info!(
+            abandoned = report.abandoned_requests,
+            unsubscribed = report.unsubscribed.len(),
+            clean_close = report.clean_close,
+            "Cortex client shut down"
+        );
+        report
+    }
+
+    /// Wait up to `timeout` for the reader loop to exit. Joins its task
+    /// unless an owner took the handle, in which case the loop's exit
+    /// signal is awaited instead.
+    async fn join_reader(&self, timeout: Duration) -> bool {
+        let Some(mut handle) = self.take_reader_handle() else {
+            let mut exited = self.reader_shared.exited.subscribe();
+            return tokio::time::timeout(timeout, exited.wait_for(|exited| *exited))
+                .await
+                .is_ok();
+        };
+        if tokio::time::timeout(timeout, &mut handle).await.is_ok() {
+            return true;
+        }
+        if let Ok(mut slot) = self.reader_handle.lock() {
+            *slot = Some(handle);
+        }
+        false
+    }
+
+    /// Wait up to `grace` for pending RPCs to be answered; returns how
+    /// many are still pending.
+    async fn drain_pending(&self, grace: Duration) -> usize {
+        let deadline = tokio::time::Instant::now() + grace;
+        loop {
+            // Register for the wakeup before counting so a response that
+            // lands in between is not missed.
+            let drained = self.pending_responses.drained.notified();
+            tokio::pin!(drained);
+            drained.as_mut().enable();
+            let pending = self.pending_response_count().await;
+            if pending == 0 || !self.is_connected() {
+                return pending;
+            }
+            if tokio::time::timeout_at(deadline, drained).await.is_err() {
+                return self.pending_response_count().await;
+            }
+        }
+    }
+
     /// Close the WebSocket connection.
     ///
     /// # Errors
@@ -2748,8 +4173,7 @@ impl CortexClient {
     pub async fn disconnect(&mut self) -> CortexResult<()> {
         self.stop_reader().await;
 
-        let mut writer = self.writer.lock().await;
-        let _ = writer.close().await;
+        let _ = self.writer.close().await;
 
         Ok(())
     }
@@ -2758,6 +4182,7 @@ impl CortexClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::protocol::query::Filter;
 
     #[test]
     fn test_query_headsets_params_default_is_empty() {
@@ -2765,6 +4190,60 @@ mod tests {
         assert_eq!(params, serde_json::json!({}));
     }
 
+    #[test]
+    fn test_query_params_from_typed_records_request() {
+        let request = QueryRecordsRequest::default()
+            .filter(Filter::eq("applicationId", "com.example.app"))
+            .limit(10);
+        let params = CortexClient::query_params(
+            "token",
+            &request.query,
+            &request.order_by,
+            request.limit,
+            request.offset,
+        );
+        assert_eq!(
+            params["query"],
+            serde_json::json!({ "applicationId": "com.example.app" })
+        );
+        assert_eq!(
+            params["orderBy"],
+            serde_json::json!([{ "startDatetime": "DESC" }])
+        );
+        assert_eq!(params["limit"], 10);
+        assert!(params.get("offset").is_none());
+    }
+
+    #[test]
+    fn test_stream_events_route_by_session_then_wildcard() {
+        let routes = ArcSwap::from_pointee(StreamRoutes::default());
+        let mut state = StreamRoutes::default();
+        let (tx_a, mut rx_a) = mpsc::channel(4);
+        let (tx_b, mut rx_b) = mpsc::channel(4);
+        let (tx_any, mut rx_any) = mpsc::channel(4);
+        state.insert(StreamRouteKey::session("sid-a", "eeg"), tx_a);
+        state.insert(StreamRouteKey::session("sid-b", "eeg"), tx_b);
+        state.insert(StreamRouteKey::any_session("eeg"), tx_any);
+        routes.store(Arc::new(state));
+
+        for sid in ["sid-a", "sid-b", "sid-c"] {
+            let event = serde_json::json!({ "sid": sid, "eeg": [1.0], "time": 0.0 });
+            CortexClient::dispatch_stream_event(event, &routes);
+        }
+
+        assert_eq!(rx_a.try_recv().unwrap()["sid"], "sid-a");
+        assert_eq!(rx_b.try_recv().unwrap()["sid"], "sid-b");
+        assert_eq!(rx_any.try_recv().unwrap()["sid"], "sid-c");
+        assert!(rx_a.try_recv().is_err());
+        assert!(rx_any.try_recv().is_err());
+        assert_eq!(
+            routes.load().counters["eeg"]
+                .delivered
+                .load(std::sync::atomic::Ordering::Relaxed),
+            3
+        );
+    }
+
     #[test]
     fn test_query_headsets_params_with_id() {
         let params = CortexClient::query_headsets_params(QueryHeadsetsOptions {
@@ -2814,6 +4293,11 @@ mod tests {
             Some("My Headset"),
         );
         assert_eq!(params["headsetId"], "HS-123");
+        assert!(params.get("headset").is_none());
+        // The legacy name is added by the compat layer until the service
+        // version is known.
+        let params = ProtocolCompat::default().adapt(Methods::UPDATE_HEADSET_CUSTOM_INFO, params);
+        assert_eq!(params["headsetId"], "HS-123");
         assert_eq!(params["headset"], "HS-123");
         assert_eq!(params["headbandPosition"], "front");
         assert_eq!(params["customName"], "My Headset");
@@ -2821,13 +4305,54 @@ mod tests {
 
     #[test]
     fn test_update_headset_custom_info_omits_optional_fields_when_none() {
-        let params = CortexClient::update_headset_custom_info_params("token", "HS-123", None, None);
+        let params = ProtocolCompat::default().adapt(
+            Methods::UPDATE_HEADSET_CUSTOM_INFO,
+            CortexClient::update_headset_custom_info_params("token", "HS-123", None, None),
+        );
         assert_eq!(params["headsetId"], "HS-123");
         assert_eq!(params["headset"], "HS-123");
         assert!(params.get("headbandPosition").is_none());
         assert!(params.get("customName").is_none());
     }
 
+    #[test]
+    fn test_export_record_params_include_only_set_options() {
+        let options = ExportRecordOptions::new(["r1"], "/tmp/out", ExportFormat::Edf);
+        let params = CortexClient::export_record_params("token", &options);
+        assert_eq!(params["recordIds"], serde_json::json!(["r1"]));
+        assert_eq!(params["format"], "EDF");
+        assert!(params.get("streamTypes").is_none());
+        assert!(params.get("includeDemographics").is_none());
+
+        let options = options
+            .stream_types(["EEG", "MOTION"])
+            .version("V2")
+            .license_ids(["lic-1"])
+            .include_demographics(true)
+            .include_deprecated_pm(false);
+        let params = CortexClient::export_record_params("token", &options);
+        assert_eq!(params["streamTypes"], serde_json::json!(["EEG", "MOTION"]));
+        assert_eq!(params["version"], "V2");
+        assert_eq!(params["licenseIds"], serde_json::json!(["lic-1"]));
+        assert_eq!(params["includeDemographics"], true);
+        assert_eq!(params["includeDeprecatedPM"], false);
+        assert!(params.get("includeSurvey").is_none());
+    }
+
+    #[test]
+    fn test_create_virtual_headset_params() {
+        let mut options = VirtualHeadsetOptions::new(5);
+        let params = CortexClient::create_virtual_headset_params("token", &options);
+        assert_eq!(params["cortexToken"], "token");
+        assert_eq!(params["channelCount"], 5);
+        assert_eq!(params["sampleRate"], 128);
+        assert!(params.get("customName").is_none());
+
+        options.custom_name = Some("CI".into());
+        let params = CortexClient::create_virtual_headset_params("token", &options);
+        assert_eq!(params["customName"], "CI");
+    }
+
     #[test]
     fn test_config_mapping_create_params_validation() {
         let empty_name = CortexClient::config_mapping_params(
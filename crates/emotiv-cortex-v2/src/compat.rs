@@ -0,0 +1,253 @@
+//! # Protocol Compatibility
+//!
+//! Cortex services shipped with different EMOTIV Launcher releases do not
+//! accept identical request shapes: parameters get renamed, and newer
+//! parameters are rejected by older services. The client always builds
+//! requests in the current documented shape; [`ProtocolCompat`] then
+//! rewrites them for the service version reported by `getCortexInfo`,
+//! following [`COMPAT_TABLE`].
+//!
+//! [`CortexClient`](crate::CortexClient) detects the version whenever
+//! `getCortexInfo` succeeds (including during `authenticate`). Until then
+//! the version is unknown and every renamed parameter is sent under both
+//! names, which older and newer services both accept.
+
+use std::fmt;
+
+use crate::protocol::constants::Methods;
+
+/// A Cortex service version, e.g. `3.7.5`.
+///
+/// Build suffixes (`3.7.5.512`, `2.7.0-beta`) are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CortexVersion {
+    /// Major version.
+    pub major: u32,
+    /// Minor version.
+    pub minor: u32,
+    /// Patch version.
+    pub patch: u32,
+}
+
+impl CortexVersion {
+    /// Create a version from its components.
+    #[must_use]
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parse the leading `major[.minor[.patch]]` of a version string.
+    ///
+    /// Returns `None` if the string does not start with a number.
+    #[must_use]
+    pub fn parse(version: &str) -> Option<Self> {
+        let mut parts = version
+            .trim()
+            .trim_start_matches('v')
+            .split('.')
+            .map(|part| {
+                let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+                digits.parse::<u32>().ok()
+            });
+        let major = parts.next().flatten()?;
+        let minor = parts.next().flatten().unwrap_or(0);
+        let patch = parts.next().flatten().unwrap_or(0);
+        Some(Self::new(major, minor, patch))
+    }
+}
+
+impl fmt::Display for CortexVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// How a request parameter is adapted for an older service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shim {
+    /// Send the parameter under its older name.
+    Rename {
+        /// Current documented name, as built by the client.
+        from: &'static str,
+        /// Name the older service expects.
+        to: &'static str,
+    },
+    /// Leave out a parameter the older service rejects.
+    Drop(&'static str),
+}
+
+/// One row of [`COMPAT_TABLE`]: services older than `before` need `shim`
+/// applied to `method` requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompatRule {
+    /// Cortex method the rule applies to.
+    pub method: &'static str,
+    /// First service version that accepts the current shape.
+    pub before: CortexVersion,
+    /// Adaptation for older services.
+    pub shim: Shim,
+}
+
+/// Known request-shape differences between Cortex service versions.
+pub const COMPAT_TABLE: &[CompatRule] = &[
+    CompatRule {
+        method: Methods::UPDATE_HEADSET_CUSTOM_INFO,
+        before: CortexVersion::new(3, 0, 0),
+        shim: Shim::Rename {
+            from: "headsetId",
+            to: "headset",
+        },
+    },
+    CompatRule {
+        method: Methods::QUERY_HEADSETS,
+        before: CortexVersion::new(3, 7, 0),
+        shim: Shim::Drop("includeFlexMappings"),
+    },
+];
+
+/// Adapts request parameters to the connected service version.
+///
+/// See the [module documentation](self).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProtocolCompat {
+    version: Option<CortexVersion>,
+}
+
+impl ProtocolCompat {
+    /// Compatibility for a known (or unknown) service version.
+    #[must_use]
+    pub const fn new(version: Option<CortexVersion>) -> Self {
+        Self { version }
+    }
+
+    /// Read the service version from a `getCortexInfo` result.
+    #[must_use]
+    pub fn from_cortex_info(info: &serde_json::Value) -> Self {
+        let version = info
+            .get("version")
+            .and_then(serde_json::Value::as_str)
+            .and_then(CortexVersion::parse);
+        Self { version }
+    }
+
+    /// The detected service version, if any.
+    #[must_use]
+    pub const fn version(&self) -> Option<CortexVersion> {
+        self.version
+    }
+
+    /// The rules that apply to `method` for this version.
+    ///
+    /// With an unknown version every rule for `method` is returned, and
+    /// [`adapt`](Self::adapt) applies them conservatively.
+    pub fn rules_for(&self, method: &str) -> impl Iterator<Item = &'static CompatRule> {
+        let version = self.version;
+        COMPAT_TABLE
+            .iter()
+            .filter(move |rule| rule.method == method && version.is_none_or(|v| v < rule.before))
+    }
+
+    /// Rewrite `params` of a `method` request for this service version.
+    ///
+    /// When the version is unknown, renamed parameters are sent under both
+    /// names and nothing is dropped.
+    #[must_use]
+    pub fn adapt(&self, method: &str, mut params: serde_json::Value) -> serde_json::Value {
+        let Some(object) = params.as_object_mut() else {
+            return params;
+        };
+        for rule in self.rules_for(method) {
+            match (rule.shim, self.version) {
+                (Shim::Rename { from, to }, Some(_)) => {
+                    if let Some(value) = object.remove(from) {
+                        object.insert(to.to_string(), value);
+                    }
+                }
+                (Shim::Rename { from, to }, None) => {
+                    if let Some(value) = object.get(from).cloned() {
+                        object.entry(to).or_insert(value);
+                    }
+                }
+                (Shim::Drop(name), Some(_)) => {
+                    object.remove(name);
+                }
+                (Shim::Drop(_), None) => {}
+            }
+        }
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_versions_with_build_suffixes() {
+        assert_eq!(
+            CortexVersion::parse("3.7.5.512"),
+            Some(CortexVersion::new(3, 7, 5))
+        );
+        assert_eq!(
+            CortexVersion::parse("2.7.0-beta"),
+            Some(CortexVersion::new(2, 7, 0))
+        );
+        assert_eq!(
+            CortexVersion::parse("v3"),
+            Some(CortexVersion::new(3, 0, 0))
+        );
+        assert_eq!(CortexVersion::parse("mock"), None);
+        assert_eq!(CortexVersion::new(3, 7, 5).to_string(), "3.7.5");
+    }
+
+    #[test]
+    fn test_unknown_version_sends_both_names() {
+        let params = ProtocolCompat::default().adapt(
+            Methods::UPDATE_HEADSET_CUSTOM_INFO,
+            json!({"cortexToken": "t", "headsetId": "HS-1"}),
+        );
+        assert_eq!(params["headsetId"], "HS-1");
+        assert_eq!(params["headset"], "HS-1");
+
+        let params = ProtocolCompat::default().adapt(
+            Methods::QUERY_HEADSETS,
+            json!({"includeFlexMappings": true}),
+        );
+        assert_eq!(params["includeFlexMappings"], true);
+    }
+
+    #[test]
+    fn test_old_service_gets_legacy_shape() {
+        let compat = ProtocolCompat::from_cortex_info(&json!({"version": "2.7.3.100"}));
+        assert_eq!(compat.version(), Some(CortexVersion::new(2, 7, 3)));
+
+        let params = compat.adapt(
+            Methods::UPDATE_HEADSET_CUSTOM_INFO,
+            json!({"cortexToken": "t", "headsetId": "HS-1"}),
+        );
+        assert_eq!(params["headset"], "HS-1");
+        assert!(params.get("headsetId").is_none());
+
+        let params = compat.adapt(
+            Methods::QUERY_HEADSETS,
+            json!({"id": "HS-1", "includeFlexMappings": true}),
+        );
+        assert_eq!(params, json!({"id": "HS-1"}));
+    }
+
+    #[test]
+    fn test_current_service_keeps_documented_shape() {
+        let compat = ProtocolCompat::new(Some(CortexVersion::new(3, 7, 5)));
+        let original = json!({"cortexToken": "t", "headsetId": "HS-1"});
+        assert_eq!(
+            compat.adapt(Methods::UPDATE_HEADSET_CUSTOM_INFO, original.clone()),
+            original
+        );
+        assert_eq!(compat.rules_for(Methods::QUERY_HEADSETS).count(), 0);
+    }
+}
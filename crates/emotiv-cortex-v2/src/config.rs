@@ -16,13 +16,46 @@
 //! even when loading from a file.
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+use crate::client::CortexClient;
 use crate::error::{CortexError, CortexResult};
+use crate::ownership::CleanupScope;
+use crate::rate_limit::MethodClass;
+use crate::routes::StreamRoute;
 
 /// Default Cortex WebSocket URL (localhost, self-signed TLS).
 pub const DEFAULT_CORTEX_URL: &str = "wss://localhost:6868";
 
+/// Default number of calls per method kept for latency percentiles.
+const DEFAULT_LATENCY_WINDOW: usize = 256;
+
+/// Default time allowed for each teardown step, in seconds.
+const DEFAULT_TEARDOWN_STEP_TIMEOUT_SECS: u64 = 5;
+
+/// Default capacity of the dedicated writer task's send queue.
+const DEFAULT_WRITER_QUEUE_CAPACITY: usize = 256;
+
+/// Default time a session may sit without streams or a record before an
+/// idle alert, in seconds.
+const DEFAULT_IDLE_AFTER_SECS: u64 = 120;
+
+/// Default title of the record started by
+/// [`ResilientClient::start_default_recording`](crate::ResilientClient::start_default_recording).
+const DEFAULT_IDLE_RECORD_TITLE: &str = "Untitled recording";
+
+/// Default interval between session keep-alive probes, in seconds.
+const DEFAULT_KEEP_ALIVE_INTERVAL_SECS: u64 = 30;
+
+/// Default interval between WebSocket pings, in seconds.
+const DEFAULT_PING_INTERVAL_SECS: u64 = 5;
+
+/// Default silence after which a pinged connection is declared dead, in
+/// seconds.
+const DEFAULT_PONG_TIMEOUT_SECS: u64 = 10;
+
 /// Default RPC call timeout in seconds.
 const DEFAULT_RPC_TIMEOUT_SECS: u64 = 10;
 
@@ -47,6 +80,9 @@ const DEFAULT_HEALTH_INTERVAL_SECS: u64 = 30;
 /// Default max consecutive health check failures before reconnect.
 const DEFAULT_HEALTH_MAX_FAILURES: u32 = 3;
 
+/// Default per-candidate probe timeout for endpoint autodiscovery, in seconds.
+const DEFAULT_DISCOVERY_PROBE_TIMEOUT_SECS: u64 = 2;
+
 /// Configuration for connecting to the Emotiv Cortex API.
 ///
 /// # Examples
@@ -87,10 +123,17 @@ pub struct CortexConfig {
     #[serde(default = "default_cortex_url")]
     pub cortex_url: String,
 
-    /// Emotiv license key for commercial/premium features.
+    /// Emotiv license key for commercial/premium features, passed to
+    /// `authorize`.
     #[serde(default)]
     pub license: Option<String>,
 
+    /// Sessions to debit from the license (`license`, or the user's own)
+    /// when authorizing, so they can be spent offline later. Every `authorize` debits again; see
+    /// [`TokenCacheConfig`] to reuse tokens instead.
+    #[serde(default)]
+    pub debit: Option<u32>,
+
     /// Request decontaminated EEG data (motion artifact removal).
     #[serde(default = "default_true")]
     pub decontaminated: bool,
@@ -111,6 +154,160 @@ pub struct CortexConfig {
     /// Health monitoring configuration.
     #[serde(default)]
     pub health: HealthConfig,
+
+    /// How to treat response fields that the typed protocol structs do
+    /// not model (they land in `extra` flatten maps).
+    #[serde(default)]
+    pub strict_protocol: StrictProtocolMode,
+
+    /// How JSON-RPC request ids are generated for each connection.
+    #[serde(default)]
+    pub request_ids: RequestIdStrategy,
+
+    /// Candidate endpoints probed by [`CortexConfig::autodiscover`].
+    #[serde(default)]
+    pub discovery: DiscoveryConfig,
+
+    /// Client-side rate limiting of RPC calls (off by default).
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+
+    /// Per-method latency budgets and percentile tracking.
+    #[serde(default)]
+    pub latency: LatencyConfig,
+
+    /// Ordered teardown of sessions, subscriptions, and records.
+    #[serde(default)]
+    pub teardown: TeardownConfig,
+
+    /// How outgoing WebSocket frames reach the socket.
+    #[serde(default)]
+    pub writer: WriterConfig,
+
+    /// Alerts for sessions left without streams or a record.
+    #[serde(default)]
+    pub idle: IdleConfig,
+
+    /// Periodic probing of sessions for server-side expiry (off by
+    /// default).
+    #[serde(default)]
+    pub keep_alive: KeepAliveConfig,
+
+    /// WebSocket ping/pong liveness checks.
+    #[serde(default)]
+    pub ping: PingConfig,
+
+    /// On-disk cache of the Cortex token, reused across restarts (off by
+    /// default). See [`crate::token_cache`].
+    #[serde(default)]
+    pub token_cache: TokenCacheConfig,
+
+    /// Cortex application ID (e.g. `com.example.myapp`), used to
+    /// recognise this app's sessions from earlier runs. Learned from the
+    /// first created session when unset. See [`crate::ownership`].
+    #[serde(default)]
+    pub app_id: Option<String>,
+
+    /// Scope for stale-session cleanup done on the application's behalf,
+    /// to pass to [`CortexClient::close_sessions`] (default: `"owned"`).
+    /// `"all"` also closes other applications' sessions.
+    #[serde(default)]
+    pub session_cleanup: CleanupScope,
+
+    /// Reload a profile loaded through [`ResilientClient`] when Cortex
+    /// reports it unloaded (another application took the headset, or the
+    /// user unloaded it in the Launcher). Default: `false`.
+    ///
+    /// [`ResilientClient`]: crate::ResilientClient
+    #[serde(default)]
+    pub auto_reload_profile: bool,
+
+    /// Declarative `<stream> -> <sink>` routes started by
+    /// [`ResilientClient::start_routes`](crate::ResilientClient::start_routes).
+    /// See [`crate::routes`].
+    #[serde(default)]
+    pub routes: Vec<StreamRoute>,
+}
+
+/// Endpoint autodiscovery settings.
+///
+/// Candidates are tried in order; put the most likely endpoint first.
+/// Remote hosts use Cortex's self-signed certificate, so probing them
+/// also requires [`CortexConfig::allow_insecure_tls`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveryConfig {
+    /// WebSocket URLs to probe, e.g. `"wss://lab-pc.local:6868"`.
+    #[serde(default = "default_discovery_candidates")]
+    pub candidates: Vec<String>,
+
+    /// Time allowed per candidate for connect + `getCortexInfo`, in seconds.
+    #[serde(default = "default_discovery_probe_timeout")]
+    pub probe_timeout_secs: u64,
+}
+
+/// Outcome of [`CortexConfig::autodiscover`], kept for diagnostics.
+#[derive(Debug, Clone)]
+pub struct DiscoveryReport {
+    /// The endpoint that answered `getCortexInfo`.
+    pub selected_url: String,
+
+    /// The `getCortexInfo` result from the selected endpoint.
+    pub cortex_info: serde_json::Value,
+
+    /// Every probe made, in order, including the successful one.
+    pub probes: Vec<EndpointProbe>,
+}
+
+/// A single autodiscovery probe.
+#[derive(Debug, Clone)]
+pub struct EndpointProbe {
+    /// Candidate URL that was probed.
+    pub url: String,
+
+    /// How long the probe took.
+    pub elapsed: Duration,
+
+    /// Why the candidate was rejected, or `None` if it was healthy.
+    pub error: Option<String>,
+}
+
+/// Built-in JSON-RPC request id strategies.
+///
+/// Custom strategies can be installed on a connected client with
+/// [`CortexClient::with_request_id_generator`].
+///
+/// [`CortexClient::with_request_id_generator`]: crate::CortexClient::with_request_id_generator
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestIdStrategy {
+    /// Counter starting at 1 on every connection (default).
+    #[default]
+    Counter,
+    /// Counter prefixed with a per-connection value, so ids stay unique
+    /// across reconnects and clients (see
+    /// [`ConnectionPrefixedIds`](crate::protocol::rpc::ConnectionPrefixedIds)).
+    ConnectionPrefixed,
+}
+
+/// Strict protocol auditing of unmodeled response fields.
+///
+/// Typed response structs keep unknown fields in a forward-compatible
+/// `extra` map. With auditing enabled, the client records those field
+/// names per Cortex method (see [`CortexClient::unmodeled_fields`]) so
+/// gaps in the typed model surface early.
+///
+/// [`CortexClient::unmodeled_fields`]: crate::CortexClient::unmodeled_fields
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StrictProtocolMode {
+    /// No auditing (default).
+    #[default]
+    Off,
+    /// Record unmodeled fields and log each newly seen one at `warn`.
+    Warn,
+    /// Record unmodeled fields and fail the call with
+    /// [`CortexError::ProtocolError`]. Intended for tests and CI.
+    Error,
 }
 
 /// Timeout settings for various Cortex operations.
@@ -147,6 +344,12 @@ pub struct ReconnectConfig {
     /// Maximum number of reconnect attempts. 0 means unlimited.
     #[serde(default = "default_reconnect_max_attempts")]
     pub max_attempts: u32,
+
+    /// Re-subscribe the streams subscribed through the client after a
+    /// reconnect, recreating the session on the same headset if Cortex
+    /// no longer knows it. Existing stream receivers keep delivering.
+    #[serde(default)]
+    pub resubscribe: bool,
 }
 
 /// Health monitoring configuration (periodic heartbeat).
@@ -165,6 +368,169 @@ pub struct HealthConfig {
     pub max_consecutive_failures: u32,
 }
 
+/// Token bucket settings for one [`MethodClass`].
+///
+/// A `rate_per_sec` of zero or less disables limiting for that class.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitRule {
+    /// Sustained calls per second.
+    pub rate_per_sec: f64,
+    /// Calls allowed back to back before pacing starts.
+    pub burst: u32,
+}
+
+/// Client-side token-bucket rate limiting, one bucket per
+/// [`MethodClass`]. See [`crate::rate_limit`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// Apply rate limiting to every RPC call.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Read-only queries (default: 10/s, burst 20).
+    #[serde(default = "default_query_rate_limit")]
+    pub query: RateLimitRule,
+
+    /// Session lifecycle and subscriptions (default: 5/s, burst 10).
+    #[serde(default = "default_write_rate_limit")]
+    pub session: RateLimitRule,
+
+    /// Records, markers, and exports (default: 5/s, burst 10).
+    #[serde(default = "default_write_rate_limit")]
+    pub records: RateLimitRule,
+
+    /// Training and detection tuning (default: 5/s, burst 10).
+    #[serde(default = "default_write_rate_limit")]
+    pub training: RateLimitRule,
+
+    /// All other methods (default: 10/s, burst 20).
+    #[serde(default = "default_query_rate_limit")]
+    pub other: RateLimitRule,
+}
+
+/// Per-method latency budgets. See [`crate::latency`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyConfig {
+    /// Calls per method kept for percentile reporting (default: 256).
+    #[serde(default = "default_latency_window")]
+    pub window: usize,
+
+    /// Budget in milliseconds, keyed by Cortex method name
+    /// (e.g. `subscribe = 1000`). Methods without a budget are tracked
+    /// but never alert.
+    #[serde(default)]
+    pub budgets_ms: HashMap<String, u64>,
+}
+
+/// Teardown settings. See [`crate::teardown`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeardownConfig {
+    /// Time allowed for each teardown step (default: 5).
+    #[serde(default = "default_teardown_step_timeout")]
+    pub step_timeout_secs: u64,
+}
+
+/// Idle-session alerts. See
+/// [`ConnectionEvent::IdleSession`](crate::reconnect::ConnectionEvent::IdleSession).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdleConfig {
+    /// Watch sessions created through the client (default: `true`).
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Seconds a session may have no subscribed streams and no running
+    /// record before the alert (default: 120).
+    #[serde(default = "default_idle_after")]
+    pub after_secs: u64,
+
+    /// Title of the record started by
+    /// [`ResilientClient::start_default_recording`](crate::ResilientClient::start_default_recording)
+    /// (default: `"Untitled recording"`).
+    #[serde(default = "default_idle_record_title")]
+    pub default_record_title: String,
+}
+
+/// Session keep-alive. See
+/// [`ConnectionEvent::SessionClosed`](crate::reconnect::ConnectionEvent::SessionClosed).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeepAliveConfig {
+    /// Probe active sessions created through the client with
+    /// `updateSession` (default: `false`).
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Seconds between probes (default: 30).
+    #[serde(default = "default_keep_alive_interval")]
+    pub interval_secs: u64,
+}
+
+/// WebSocket ping/pong liveness checks. See
+/// [`CortexClient::liveness_receiver`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PingConfig {
+    /// Ping the service and drop the connection when it goes silent
+    /// (default: `false`).
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Seconds between pings (default: 5).
+    #[serde(default = "default_ping_interval")]
+    pub interval_secs: u64,
+
+    /// Seconds without any frame from the service, pongs included,
+    /// before the connection is declared dead (default: 10).
+    #[serde(default = "default_pong_timeout")]
+    pub pong_timeout_secs: u64,
+}
+
+/// Token cache. See [`crate::token_cache`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TokenCacheConfig {
+    /// Save the token on disk and renew it with `generateNewToken` on the
+    /// next start instead of authorizing again (default: `false`).
+    /// Reconnects and token refreshes renew the current token the same
+    /// way.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Cache file (default: `token.json` beside the per-user config file,
+    /// see [`CortexConfig::user_config_path`]).
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+
+    /// Encrypt the cached token with a key derived from the client
+    /// secret (default: `false`). Requires the `token-encryption` feature.
+    #[serde(default)]
+    pub encrypt: bool,
+}
+
+/// Outgoing frame path. See [`crate::writer`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WriterConfig {
+    /// Hand frames to a dedicated writer task through a queue instead of
+    /// locking the shared socket sink (default: `false`).
+    #[serde(default)]
+    pub dedicated_task: bool,
+
+    /// Frames the writer task queues before senders wait (default: 256).
+    #[serde(default = "default_writer_queue_capacity")]
+    pub queue_capacity: usize,
+}
+
+impl RateLimitConfig {
+    /// Rule for a method class.
+    #[must_use]
+    pub fn rule(&self, class: MethodClass) -> RateLimitRule {
+        match class {
+            MethodClass::Query => self.query,
+            MethodClass::Session => self.session,
+            MethodClass::Records => self.records,
+            MethodClass::Training => self.training,
+            MethodClass::Other => self.other,
+        }
+    }
+}
+
 // ─── Defaults ───────────────────────────────────────────────────────────
 
 fn default_cortex_url() -> String {
@@ -207,6 +573,17 @@ fn default_health_max_failures() -> u32 {
     DEFAULT_HEALTH_MAX_FAILURES
 }
 
+fn default_discovery_candidates() -> Vec<String> {
+    vec![
+        DEFAULT_CORTEX_URL.to_string(),
+        "wss://127.0.0.1:6868".to_string(),
+    ]
+}
+
+fn default_discovery_probe_timeout() -> u64 {
+    DEFAULT_DISCOVERY_PROBE_TIMEOUT_SECS
+}
+
 // ─── Default impls ──────────────────────────────────────────────────────
 
 impl Default for TimeoutConfig {
@@ -226,6 +603,130 @@ impl Default for ReconnectConfig {
             base_delay_secs: DEFAULT_RECONNECT_BASE_DELAY_SECS,
             max_delay_secs: DEFAULT_RECONNECT_MAX_DELAY_SECS,
             max_attempts: DEFAULT_RECONNECT_MAX_ATTEMPTS,
+            resubscribe: false,
+        }
+    }
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            candidates: default_discovery_candidates(),
+            probe_timeout_secs: DEFAULT_DISCOVERY_PROBE_TIMEOUT_SECS,
+        }
+    }
+}
+
+fn default_query_rate_limit() -> RateLimitRule {
+    RateLimitRule {
+        rate_per_sec: 10.0,
+        burst: 20,
+    }
+}
+
+fn default_write_rate_limit() -> RateLimitRule {
+    RateLimitRule {
+        rate_per_sec: 5.0,
+        burst: 10,
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            query: default_query_rate_limit(),
+            session: default_write_rate_limit(),
+            records: default_write_rate_limit(),
+            training: default_write_rate_limit(),
+            other: default_query_rate_limit(),
+        }
+    }
+}
+
+fn default_latency_window() -> usize {
+    DEFAULT_LATENCY_WINDOW
+}
+
+impl Default for LatencyConfig {
+    fn default() -> Self {
+        Self {
+            window: DEFAULT_LATENCY_WINDOW,
+            budgets_ms: HashMap::new(),
+        }
+    }
+}
+
+fn default_writer_queue_capacity() -> usize {
+    DEFAULT_WRITER_QUEUE_CAPACITY
+}
+
+impl Default for WriterConfig {
+    fn default() -> Self {
+        Self {
+            dedicated_task: false,
+            queue_capacity: DEFAULT_WRITER_QUEUE_CAPACITY,
+        }
+    }
+}
+
+fn default_idle_after() -> u64 {
+    DEFAULT_IDLE_AFTER_SECS
+}
+
+fn default_idle_record_title() -> String {
+    DEFAULT_IDLE_RECORD_TITLE.to_string()
+}
+
+impl Default for IdleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            after_secs: DEFAULT_IDLE_AFTER_SECS,
+            default_record_title: DEFAULT_IDLE_RECORD_TITLE.to_string(),
+        }
+    }
+}
+
+fn default_keep_alive_interval() -> u64 {
+    DEFAULT_KEEP_ALIVE_INTERVAL_SECS
+}
+
+impl Default for KeepAliveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: DEFAULT_KEEP_ALIVE_INTERVAL_SECS,
+        }
+    }
+}
+
+fn default_ping_interval() -> u64 {
+    DEFAULT_PING_INTERVAL_SECS
+}
+
+fn default_pong_timeout() -> u64 {
+    DEFAULT_PONG_TIMEOUT_SECS
+}
+
+impl Default for PingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: DEFAULT_PING_INTERVAL_SECS,
+            pong_timeout_secs: DEFAULT_PONG_TIMEOUT_SECS,
+        }
+    }
+}
+
+fn default_teardown_step_timeout() -> u64 {
+    DEFAULT_TEARDOWN_STEP_TIMEOUT_SECS
+}
+
+impl Default for TeardownConfig {
+    fn default() -> Self {
+        Self {
+            step_timeout_secs: DEFAULT_TEARDOWN_STEP_TIMEOUT_SECS,
         }
     }
 }
@@ -260,11 +761,27 @@ impl CortexConfig {
             client_secret: client_secret.into(),
             cortex_url: default_cortex_url(),
             license: None,
+            debit: None,
             decontaminated: true,
             allow_insecure_tls: false,
             timeouts: TimeoutConfig::default(),
             reconnect: ReconnectConfig::default(),
             health: HealthConfig::default(),
+            strict_protocol: StrictProtocolMode::default(),
+            request_ids: RequestIdStrategy::default(),
+            discovery: DiscoveryConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+            latency: LatencyConfig::default(),
+            teardown: TeardownConfig::default(),
+            writer: WriterConfig::default(),
+            idle: IdleConfig::default(),
+            keep_alive: KeepAliveConfig::default(),
+            ping: PingConfig::default(),
+            token_cache: TokenCacheConfig::default(),
+            app_id: None,
+            session_cleanup: CleanupScope::default(),
+            auto_reload_profile: false,
+            routes: Vec::new(),
         }
     }
 
@@ -312,7 +829,7 @@ impl CortexConfig {
         let contents = std::fs::read_to_string(path).map_err(|e| CortexError::ConfigError {
             reason: format!("Failed to read config file '{}': {}", path.display(), e),
         })?;
-        let mut config: Self = parse_toml_config(&contents)?;
+        let mut config: Self = parse_toml_config(path, &contents)?;
 
         // Environment variable overrides
         if let Ok(id) = std::env::var("EMOTIV_CLIENT_ID") {
@@ -331,6 +848,16 @@ impl CortexConfig {
         Ok(config)
     }
 
+    /// Per-user config file location searched by [`Self::discover`]
+    /// (`~/.config/emotiv-cortex/cortex.toml`, or
+    /// `%APPDATA%\emotiv-cortex\cortex.toml` on Windows).
+    ///
+    /// Returns `None` when the home / app-data directory is unknown.
+    #[must_use]
+    pub fn user_config_path() -> Option<PathBuf> {
+        dirs_config_path()
+    }
+
     /// Discover and load config from the standard search path:
     ///
     /// 1. Explicit path (if `Some`)
@@ -399,17 +926,587 @@ impl CortexConfig {
         }
         self.allow_insecure_tls
     }
+
+    /// Probe [`DiscoveryConfig::candidates`] in order and point
+    /// [`cortex_url`](Self::cortex_url) at the first one that accepts a
+    /// WebSocket connection and answers `getCortexInfo`.
+    ///
+    /// Useful when Cortex runs on a non-default port or on another machine
+    /// (remote-desktop rigs). Only the configured list is probed; there is
+    /// no mDNS/zeroconf lookup. The returned report records every probe
+    /// and the selected endpoint for diagnostics.
+    ///
+    /// ```no_run
+    /// use emotiv_cortex_v2::CortexConfig;
+    ///
+    /// # async fn demo() -> emotiv_cortex_v2::CortexResult<()> {
+    /// let mut config = CortexConfig::discover(None)?;
+    /// config.discovery.candidates.push("wss://lab-pc.local:6868".into());
+    /// config.allow_insecure_tls = true;
+    /// let report = config.autodiscover().await?;
+    /// println!("using {}", report.selected_url);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ConnectionFailed`] listing every candidate's
+    /// failure when none of them is healthy, and [`CortexError::ConfigError`]
+    /// when the candidate list is empty.
+    pub async fn autodiscover(&mut self) -> CortexResult<DiscoveryReport> {
+        if self.discovery.candidates.is_empty() {
+            return Err(CortexError::ConfigError {
+                reason: "discovery.candidates is empty".into(),
+            });
+        }
+
+        let probe_timeout = Duration::from_secs(self.discovery.probe_timeout_secs.max(1));
+        let mut probes = Vec::with_capacity(self.discovery.candidates.len());
+
+        for url in &self.discovery.candidates {
+            let mut probe_config = self.clone();
+            probe_config.cortex_url.clone_from(url);
+            probe_config.timeouts.rpc_timeout_secs = probe_timeout.as_secs();
+
+            let started = std::time::Instant::now();
+            let outcome = tokio::time::timeout(probe_timeout, probe_endpoint(&probe_config))
+                .await
+                .unwrap_or(Err(CortexError::Timeout {
+                    seconds: probe_timeout.as_secs(),
+                }));
+            let elapsed = started.elapsed();
+
+            match outcome {
+                Ok(cortex_info) => {
+                    probes.push(EndpointProbe {
+                        url: url.clone(),
+                        elapsed,
+                        error: None,
+                    });
+                    tracing::info!(url = %url, ?elapsed, "Autodiscovered Cortex endpoint");
+                    self.cortex_url.clone_from(url);
+                    return Ok(DiscoveryReport {
+                        selected_url: url.clone(),
+                        cortex_info,
+                        probes,
+                    });
+                }
+                Err(e) => {
+                    tracing::debug!(url = %url, error = %e, "Cortex endpoint probe failed");
+                    probes.push(EndpointProbe {
+                        url: url.clone(),
+                        elapsed,
+                        error: Some(e.to_string()),
+                    });
+                }
+            }
+        }
+
+        let reason = probes
+            .iter()
+            .map(|p| format!("{}: {}", p.url, p.error.as_deref().unwrap_or("unknown")))
+            .collect::<Vec<_>>()
+            .join("; ");
+        Err(CortexError::ConnectionFailed {
+            url: self.discovery.candidates.join(", "),
+            reason: format!("no healthy Cortex endpoint ({reason})"),
+        })
+    }
+}
+
+// ─── Validation ─────────────────────────────────────────────────────────
+
+/// How serious a [`ConfigIssue`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IssueSeverity {
+    /// The config cannot be used as written.
+    Error,
+    /// The config loads, but probably not the way it was meant to
+    /// (e.g. a misspelled key that is silently ignored).
+    Warning,
+}
+
+impl std::fmt::Display for IssueSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Error => f.write_str("error"),
+            Self::Warning => f.write_str("warning"),
+        }
+    }
+}
+
+/// A 1-based line and column in a config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SourceLocation {
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Column (in characters), starting at 1.
+    pub column: usize,
+}
+
+impl SourceLocation {
+    /// Location of the byte `offset` in `source`.
+    #[must_use]
+    pub fn from_offset(source: &str, offset: usize) -> Self {
+        let mut offset = offset.min(source.len());
+        while !source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+impl std::fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// One problem found by [`CortexConfig::validate`] or
+/// [`CortexConfig::check_toml`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    /// Error or warning.
+    pub severity: IssueSeverity,
+    /// Dotted path of the offending key (e.g. `timeouts.rpc_timeout_secs`);
+    /// empty for problems with the file as a whole.
+    pub key: String,
+    /// What is wrong, without the location.
+    pub message: String,
+    /// Where in the file, when validating TOML source.
+    pub location: Option<SourceLocation>,
+}
+
+impl ConfigIssue {
+    fn error(key: &str, message: impl Into<String>) -> Self {
+        Self {
+            severity: IssueSeverity::Error,
+            key: key.to_string(),
+            message: message.into(),
+            location: None,
+        }
+    }
+
+    fn warning(key: &str, message: impl Into<String>) -> Self {
+        Self {
+            severity: IssueSeverity::Warning,
+            ..Self::error(key, message)
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(location) = self.location {
+            write!(f, "{location}: ")?;
+        }
+        write!(f, "{}: ", self.severity)?;
+        if !self.key.is_empty() {
+            write!(f, "`{}` ", self.key)?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+/// Every issue found in a config, in file order where locations are known.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigReport {
+    /// The issues found; empty for a clean config.
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl ConfigReport {
+    /// `true` when there are no issues at all.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// `true` when at least one issue is an [`IssueSeverity::Error`].
+    #[must_use]
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    /// The [`IssueSeverity::Error`] issues.
+    pub fn errors(&self) -> impl Iterator<Item = &ConfigIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == IssueSeverity::Error)
+    }
+
+    /// The [`IssueSeverity::Warning`] issues.
+    pub fn warnings(&self) -> impl Iterator<Item = &ConfigIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == IssueSeverity::Warning)
+    }
+}
+
+impl std::fmt::Display for ConfigReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, issue) in self.issues.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+            write!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+impl CortexConfig {
+    /// Check value ranges and combinations that deserialize fine but
+    /// cannot work, such as zero timeouts or a reconnect base delay above
+    /// the maximum delay.
+    ///
+    /// Issues carry no [`location`](ConfigIssue::location); use
+    /// [`check_toml`](Self::check_toml) to validate file contents with
+    /// line/column information.
+    ///
+    /// ```
+    /// use emotiv_cortex_v2::CortexConfig;
+    ///
+    /// let mut config = CortexConfig::new("id", "secret");
+    /// assert!(config.validate().is_clean());
+    ///
+    /// config.timeouts.rpc_timeout_secs = 0;
+    /// let report = config.validate();
+    /// assert!(report.has_errors());
+    /// assert_eq!(report.issues[0].key, "timeouts.rpc_timeout_secs");
+    /// ```
+    #[must_use]
+    pub fn validate(&self) -> ConfigReport {
+        let mut issues = self.zero_value_issues();
+
+        if !self.cortex_url.starts_with("wss://") && !self.cortex_url.starts_with("ws://") {
+            issues.push(ConfigIssue::error(
+                "cortex_url",
+                format!("must be a ws:// or wss:// URL, got '{}'", self.cortex_url),
+            ));
+        }
+        if self.reconnect.enabled && self.reconnect.base_delay_secs > self.reconnect.max_delay_secs
+        {
+            issues.push(ConfigIssue::error(
+                "reconnect.base_delay_secs",
+                format!(
+                    "({}) must not exceed reconnect.max_delay_secs ({})",
+                    self.reconnect.base_delay_secs, self.reconnect.max_delay_secs
+                ),
+            ));
+        }
+        for (name, class) in [
+            ("query", MethodClass::Query),
+            ("session", MethodClass::Session),
+            ("records", MethodClass::Records),
+            ("training", MethodClass::Training),
+            ("other", MethodClass::Other),
+        ] {
+            if !self.rate_limit.rule(class).rate_per_sec.is_finite() {
+                issues.push(ConfigIssue::error(
+                    &format!("rate_limit.{name}.rate_per_sec"),
+                    "must be a finite number",
+                ));
+            }
+        }
+        if self.token_cache.encrypt && !cfg!(feature = "token-encryption") {
+            issues.push(ConfigIssue::error(
+                "token_cache.encrypt",
+                "requires the `token-encryption` feature",
+            ));
+        }
+        if self.client_id.is_empty() {
+            issues.push(ConfigIssue::warning(
+                "client_id",
+                "is empty; authentication will fail",
+            ));
+        }
+        if self.client_secret.is_empty() {
+            issues.push(ConfigIssue::warning(
+                "client_secret",
+                "is empty; authentication will fail",
+            ));
+        }
+
+        ConfigReport { issues }
+    }
+
+    /// Settings that must be greater than zero (when their section is
+    /// enabled).
+    fn zero_value_issues(&self) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+        let mut positive = |key: &str, value: u64| {
+            if value == 0 {
+                issues.push(ConfigIssue::error(key, "must be greater than 0"));
+            }
+        };
+
+        positive("timeouts.rpc_timeout_secs", self.timeouts.rpc_timeout_secs);
+        positive(
+            "timeouts.subscribe_timeout_secs",
+            self.timeouts.subscribe_timeout_secs,
+        );
+        positive(
+            "timeouts.headset_connect_timeout_secs",
+            self.timeouts.headset_connect_timeout_secs,
+        );
+        positive(
+            "discovery.probe_timeout_secs",
+            self.discovery.probe_timeout_secs,
+        );
+        positive(
+            "teardown.step_timeout_secs",
+            self.teardown.step_timeout_secs,
+        );
+        positive(
+            "latency.window",
+            u64::try_from(self.latency.window).unwrap_or(u64::MAX),
+        );
+        if self.reconnect.enabled {
+            positive("reconnect.base_delay_secs", self.reconnect.base_delay_secs);
+        }
+        if self.writer.dedicated_task {
+            positive(
+                "writer.queue_capacity",
+                u64::try_from(self.writer.queue_capacity).unwrap_or(u64::MAX),
+            );
+        }
+        if self.idle.enabled {
+            positive("idle.after_secs", self.idle.after_secs);
+        }
+        if self.keep_alive.enabled {
+            positive("keep_alive.interval_secs", self.keep_alive.interval_secs);
+        }
+        if self.ping.enabled {
+            positive("ping.interval_secs", self.ping.interval_secs);
+            positive("ping.pong_timeout_secs", self.ping.pong_timeout_secs);
+        }
+        if self.health.enabled {
+            positive("health.interval_secs", self.health.interval_secs);
+            positive(
+                "health.max_consecutive_failures",
+                u64::from(self.health.max_consecutive_failures),
+            );
+        }
+        issues
+    }
+
+    /// Validate the contents of a TOML config file.
+    ///
+    /// Reports syntax and type errors, unknown keys (as warnings, with a
+    /// suggestion for likely typos such as `recconnect`), and everything
+    /// [`validate`](Self::validate) checks, each with the line and column
+    /// it refers to. [`from_file`](Self::from_file) runs the same checks,
+    /// fails on errors, and logs warnings.
+    ///
+    /// ```
+    /// use emotiv_cortex_v2::CortexConfig;
+    ///
+    /// let report = CortexConfig::check_toml(
+    ///     "client_id = \"id\"\nclient_secret = \"secret\"\n\n[recconnect]\nenabled = false\n",
+    /// );
+    /// assert!(!report.has_errors());
+    /// let typo = &report.issues[0];
+    /// assert_eq!(typo.key, "recconnect");
+    /// assert_eq!(typo.location.map(|l| l.line), Some(4));
+    /// assert!(typo.message.contains("did you mean `reconnect`"));
+    /// ```
+    #[cfg(feature = "config-toml")]
+    #[must_use]
+    pub fn check_toml(contents: &str) -> ConfigReport {
+        check_toml_config(contents).0
+    }
+}
+
+/// Parse and check `contents`, returning the config when it deserialized.
+#[cfg(feature = "config-toml")]
+fn check_toml_config(contents: &str) -> (ConfigReport, Option<CortexConfig>) {
+    use std::ops::Range;
+
+    let document = match toml::de::DeTable::parse(contents) {
+        Ok(document) => document,
+        Err(e) => {
+            let mut issue = ConfigIssue::error("", e.message().trim_end());
+            issue.location = e
+                .span()
+                .map(|span| SourceLocation::from_offset(contents, span.start));
+            return (
+                ConfigReport {
+                    issues: vec![issue],
+                },
+                None,
+            );
+        }
+    };
+
+    let schema = serde_json::to_value(CortexConfig::new("", "")).unwrap_or_default();
+    let mut spans: HashMap<String, Range<usize>> = HashMap::new();
+    let mut issues = Vec::new();
+    check_toml_keys(
+        contents,
+        document.get_ref(),
+        schema.as_object(),
+        "",
+        &mut spans,
+        &mut issues,
+    );
+
+    let locate = |key: &str| {
+        let mut key = key;
+        loop {
+            if let Some(span) = spans.get(key) {
+                return Some(SourceLocation::from_offset(contents, span.start));
+            }
+            key = key.rsplit_once('.')?.0;
+        }
+    };
+
+    let config = match toml::from_str::<CortexConfig>(contents) {
+        Ok(config) => {
+            issues.extend(config.validate().issues.into_iter().map(|mut issue| {
+                issue.location = locate(&issue.key);
+                issue
+            }));
+            Some(config)
+        }
+        Err(e) => {
+            // Attribute the error to the innermost key whose value contains it.
+            let span = e.span();
+            let key = span
+                .as_ref()
+                .and_then(|span| {
+                    spans
+                        .iter()
+                        .filter(|(_, value)| value.start <= span.start && span.end <= value.end)
+                        .min_by_key(|(_, value)| value.len())
+                        .map(|(key, _)| key.clone())
+                })
+                .unwrap_or_default();
+            let mut issue = ConfigIssue::error(&key, e.message().trim_end());
+            issue.location = span.map(|span| SourceLocation::from_offset(contents, span.start));
+            issues.push(issue);
+            None
+        }
+    };
+
+    issues.sort_by_key(|issue| issue.location.map(|l| (l.line, l.column)));
+    (ConfigReport { issues }, config)
+}
+
+/// Record the value span of every key and flag keys missing from
+/// `schema`. Tables that are empty in the schema (such as
+/// `latency.budgets_ms`) are free-form and not checked.
+#[cfg(feature = "config-toml")]
+fn check_toml_keys(
+    source: &str,
+    table: &toml::de::DeTable<'_>,
+    schema: Option<&serde_json::Map<String, serde_json::Value>>,
+    prefix: &str,
+    spans: &mut HashMap<String, std::ops::Range<usize>>,
+    issues: &mut Vec<ConfigIssue>,
+) {
+    for (key, value) in table {
+        let name = key.get_ref().as_ref();
+        let path = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{prefix}.{name}")
+        };
+        spans.insert(path.clone(), value.span());
+
+        let mut expected = None;
+        if let Some(schema) = schema {
+            let Some(fields) = schema.get(name) else {
+                let message = match closest_key(name, schema.keys()) {
+                    Some(suggestion) => {
+                        format!("is not a known setting (did you mean `{suggestion}`?)")
+                    }
+                    None => "is not a known setting".to_string(),
+                };
+                let mut issue = ConfigIssue::warning(&path, message);
+                issue.location = Some(SourceLocation::from_offset(source, key.span().start));
+                issues.push(issue);
+                continue;
+            };
+            expected = fields.as_object().filter(|fields| !fields.is_empty());
+        }
+        if let toml::de::DeValue::Table(inner) = value.get_ref() {
+            check_toml_keys(source, inner, expected, &path, spans, issues);
+        }
+    }
+}
+
+/// The closest candidate within edit distance 2 of `key`, or one that
+/// `key` is a prefix of (`rpc_timeout` for `rpc_timeout_secs`).
+#[cfg(feature = "config-toml")]
+fn closest_key<'a>(key: &str, candidates: impl Iterator<Item = &'a String>) -> Option<&'a str> {
+    candidates
+        .map(|candidate| (edit_distance(key, candidate), candidate))
+        .filter(|(distance, candidate)| {
+            *distance <= 2 || (!key.is_empty() && candidate.starts_with(key))
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+/// Levenshtein distance between two strings, by character.
+#[cfg(feature = "config-toml")]
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
 }
 
 // ─── Helpers ────────────────────────────────────────────────────────────
 
+/// Connect to `config.cortex_url`, ask for `getCortexInfo`, and hang up.
+async fn probe_endpoint(config: &CortexConfig) -> CortexResult<serde_json::Value> {
+    let mut client = CortexClient::connect(config).await?;
+    let info = client.get_cortex_info().await;
+    let _ = client.disconnect().await;
+    info
+}
+
+/// Parse a config file, failing on any [`IssueSeverity::Error`] found by
+/// [`CortexConfig::check_toml`] and logging its warnings.
 #[cfg(feature = "config-toml")]
-fn parse_toml_config(contents: &str) -> CortexResult<CortexConfig> {
-    Ok(toml::from_str(contents)?)
+fn parse_toml_config(path: &Path, contents: &str) -> CortexResult<CortexConfig> {
+    let (report, config) = check_toml_config(contents);
+    for warning in report.warnings() {
+        tracing::warn!("{}: {warning}", path.display());
+    }
+    match config {
+        Some(config) if !report.has_errors() => Ok(config),
+        _ => Err(CortexError::ConfigError {
+            reason: format!(
+                "invalid config file '{}':\n{}",
+                path.display(),
+                report
+                    .errors()
+                    .map(|issue| format!("  {issue}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ),
+        }),
+    }
 }
 
 #[cfg(not(feature = "config-toml"))]
-fn parse_toml_config(_contents: &str) -> CortexResult<CortexConfig> {
+fn parse_toml_config(_path: &Path, _contents: &str) -> CortexResult<CortexConfig> {
     Err(CortexError::ConfigError {
         reason: "TOML config parsing is disabled. Enable the `config-toml` feature on `emotiv-cortex-v2` to use CortexConfig::from_file/discover file loading.".into(),
     })
@@ -568,6 +1665,9 @@ cortex_url = "{url}"
         assert_eq!(config.timeouts.rpc_timeout_secs, DEFAULT_RPC_TIMEOUT_SECS);
         assert!(config.reconnect.enabled);
         assert!(config.health.enabled);
+        assert_eq!(config.strict_protocol, StrictProtocolMode::Off);
+        assert_eq!(config.request_ids, RequestIdStrategy::Counter);
+        assert_eq!(config.discovery.candidates[0], DEFAULT_CORTEX_URL);
     }
 
     #[test]
@@ -604,6 +1704,12 @@ cortex_url = "{url}"
             cortex_url = "wss://localhost:9999"
             license = "ABCD-1234"
             decontaminated = false
+            strict_protocol = "warn"
+            request_ids = "connection_prefixed"
+            app_id = "com.example.app"
+            session_cleanup = "all"
+            auto_reload_profile = true
+            routes = ["met -> csv:/data/met.csv", "pow->osc:127.0.0.1:9000"]
 
             [timeouts]
             rpc_timeout_secs = 30
@@ -614,6 +1720,26 @@ cortex_url = "{url}"
 
             [health]
             interval_secs = 60
+
+            [rate_limit]
+            enabled = true
+            records = { rate_per_sec = 2.0, burst = 4 }
+
+            [latency.budgets_ms]
+            subscribe = 1000
+
+            [writer]
+            dedicated_task = true
+
+            [idle]
+            after_secs = 300
+
+            [keep_alive]
+            enabled = true
+
+            [ping]
+            enabled = true
+            pong_timeout_secs = 4
         "#;
 
         let config: CortexConfig = toml::from_str(toml_str).unwrap();
@@ -625,6 +1751,28 @@ cortex_url = "{url}"
         assert!(!config.reconnect.enabled);
         assert_eq!(config.reconnect.max_attempts, 5);
         assert_eq!(config.health.interval_secs, 60);
+        assert_eq!(config.strict_protocol, StrictProtocolMode::Warn);
+        assert_eq!(config.request_ids, RequestIdStrategy::ConnectionPrefixed);
+        assert!(config.rate_limit.enabled);
+        assert_eq!(config.rate_limit.records.burst, 4);
+        assert_eq!(config.rate_limit.query.burst, 20);
+        assert_eq!(config.latency.budgets_ms.get("subscribe"), Some(&1000));
+        assert_eq!(config.latency.window, 256);
+        assert!(config.writer.dedicated_task);
+        assert_eq!(config.writer.queue_capacity, 256);
+        assert!(config.idle.enabled);
+        assert_eq!(config.idle.after_secs, 300);
+        assert_eq!(config.idle.default_record_title, "Untitled recording");
+        assert!(config.keep_alive.enabled);
+        assert_eq!(config.keep_alive.interval_secs, 30);
+        assert!(config.ping.enabled);
+        assert_eq!(config.ping.interval_secs, 5);
+        assert_eq!(config.ping.pong_timeout_secs, 4);
+        assert_eq!(config.app_id.as_deref(), Some("com.example.app"));
+        assert_eq!(config.session_cleanup, CleanupScope::All);
+        assert!(config.auto_reload_profile);
+        assert_eq!(config.routes.len(), 2);
+        assert_eq!(config.routes[1].to_string(), "pow -> osc:127.0.0.1:9000");
     }
 
     #[cfg(not(feature = "config-toml"))]
@@ -844,7 +1992,102 @@ license = "FILE-LICENSE"
         fs::write(&invalid_path, "client_id = [").unwrap();
         let invalid = CortexConfig::from_file(&invalid_path).unwrap_err();
         assert!(matches!(invalid, CortexError::ConfigError { .. }));
+        assert!(
+            invalid.to_string().contains("line 1, column"),
+            "unexpected error: {invalid}"
+        );
+
+        let out_of_range = dir.join("out-of-range.toml");
+        fs::write(
+            &out_of_range,
+            "client_id = \"id\"\nclient_secret = \"secret\"\n[timeouts]\nrpc_timeout_secs = 0\n",
+        )
+        .unwrap();
+        let err = CortexConfig::from_file(&out_of_range).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("line 4, column 20: error: `timeouts.rpc_timeout_secs`"),
+            "unexpected error: {err}"
+        );
 
         fs::remove_dir_all(dir).unwrap();
     }
+
+    #[test]
+    fn test_validate_reports_out_of_range_values() {
+        let mut config = CortexConfig::new("id", "secret");
+        assert!(config.validate().is_clean());
+
+        config.cortex_url = "https://localhost:6868".into();
+        config.timeouts.subscribe_timeout_secs = 0;
+        config.reconnect.base_delay_secs = 120;
+        config.health.interval_secs = 0;
+        config.client_secret.clear();
+        let report = config.validate();
+        let errors: Vec<&str> = report.errors().map(|issue| issue.key.as_str()).collect();
+        assert_eq!(
+            errors,
+            [
+                "timeouts.subscribe_timeout_secs",
+                "health.interval_secs",
+                "cortex_url",
+                "reconnect.base_delay_secs",
+            ]
+        );
+        let warnings: Vec<&str> = report.warnings().map(|issue| issue.key.as_str()).collect();
+        assert_eq!(warnings, ["client_secret"]);
+
+        config.health.enabled = false;
+        config.reconnect.enabled = false;
+        assert_eq!(config.validate().errors().count(), 2);
+    }
+
+    #[cfg(feature = "config-toml")]
+    #[test]
+    fn test_check_toml_locates_issues() {
+        let report = CortexConfig::check_toml(
+            r#"client_id = "id"
+client_secret = "secret"
+
+[recconnect]
+enabled = false
+
+[timeouts]
+rpc_timeout_secs = 0
+subscribe_timeout = 5
+
+[latency.budgets_ms]
+subscribe = 1000
+"#,
+        );
+        let rendered: Vec<String> = report.issues.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            rendered,
+            [
+                "line 4, column 2: warning: `recconnect` is not a known setting \
+                 (did you mean `reconnect`?)",
+                "line 8, column 20: error: `timeouts.rpc_timeout_secs` must be greater than 0",
+                "line 9, column 1: warning: `timeouts.subscribe_timeout` is not a known \
+                 setting (did you mean `subscribe_timeout_secs`?)",
+            ]
+        );
+
+        let report = CortexConfig::check_toml(
+            "client_id = \"id\"\nclient_secret = \"secret\"\n[health]\ninterval_secs = \"soon\"\n",
+        );
+        let issue = &report.issues[0];
+        assert_eq!(issue.severity, IssueSeverity::Error);
+        assert_eq!(issue.key, "health.interval_secs");
+        assert_eq!(
+            issue.location,
+            Some(SourceLocation {
+                line: 4,
+                column: 17
+            })
+        );
+
+        let report = CortexConfig::check_toml("client_id = \"id\"\nclient_secret = ");
+        assert!(report.has_errors());
+        assert_eq!(report.issues[0].location.map(|l| l.line), Some(2));
+    }
 }
@@ -0,0 +1,278 @@
+//! # Call Deadlines
+//!
+//! Every layer of [`ResilientClient`] bounds its own work: an RPC waits up
+//! to [`TimeoutConfig::rpc_timeout_secs`], a reconnect makes up to
+//! [`ReconnectConfig::max_attempts`] attempts with backoff in between, and
+//! a call that fails on a dead connection runs again after the reconnect.
+//! The worst case of one call is the product of those limits, which is
+//! rarely what a caller with a frame budget or a UI timeout wants.
+//!
+//! [`ResilientClient::with_deadline`] bounds the whole operation instead,
+//! whatever it ends up doing. When the budget runs out the operation is
+//! cancelled and [`CortexError::DeadlineExceeded`] carries a
+//! [`DeadlineBreakdown`] of where the time went:
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use emotiv_cortex_v2::CortexError;
+//! use emotiv_cortex_v2::protocol::headset::QueryHeadsetsOptions;
+//!
+//! # async fn demo(client: &emotiv_cortex_v2::ResilientClient) {
+//! let result = client
+//!     .with_deadline(Duration::from_secs(5), async |c| {
+//!         c.query_headsets(QueryHeadsetsOptions::default()).await
+//!     })
+//!     .await;
+//! if let Err(CortexError::DeadlineExceeded { breakdown }) = result {
+//!     eprintln!("gave up: {breakdown}");
+//! }
+//! # }
+//! ```
+//!
+//! A reconnect interrupted by the deadline is abandoned; the next call
+//! that finds the connection dead starts a new one.
+//!
+//! [`ResilientClient`]: crate::ResilientClient
+//! [`ResilientClient::with_deadline`]: crate::ResilientClient::with_deadline
+//! [`TimeoutConfig::rpc_timeout_secs`]: crate::config::TimeoutConfig::rpc_timeout_secs
+//! [`ReconnectConfig::max_attempts`]: crate::config::ReconnectConfig::max_attempts
+
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::time::Instant;
+
+use crate::error::{CortexError, CortexResult};
+
+/// What a [`ResilientClient`](crate::ResilientClient) call was doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeadlinePhase {
+    /// Waiting for Cortex to answer an RPC.
+    Rpc,
+    /// Checking or refreshing the Cortex token.
+    TokenRefresh,
+    /// Connecting and re-authenticating after a connection loss, or
+    /// waiting for another task's reconnect.
+    Reconnect,
+    /// Sleeping between reconnect attempts.
+    Backoff,
+}
+
+/// Where the time of a deadline-bounded operation went.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DeadlineBreakdown {
+    /// The budget given to [`ResilientClient::with_deadline`](crate::ResilientClient::with_deadline).
+    pub budget: Duration,
+    /// Time from start until the operation finished or was cancelled.
+    pub elapsed: Duration,
+    /// Time waiting for RPC responses.
+    pub rpc: Duration,
+    /// Time checking and refreshing the token.
+    pub token_refresh: Duration,
+    /// Time spent on reconnect attempts.
+    pub reconnect: Duration,
+    /// Time sleeping between reconnect attempts.
+    pub backoff: Duration,
+    /// RPC attempts made, including retries after a reconnect.
+    pub rpc_attempts: u32,
+    /// Reconnect attempts made.
+    pub reconnect_attempts: u32,
+    /// Phase that was running when the deadline passed.
+    pub interrupted: Option<DeadlinePhase>,
+}
+
+impl DeadlineBreakdown {
+    /// Time not spent in any client phase: the caller's own work between
+    /// calls.
+    #[must_use]
+    pub fn other(&self) -> Duration {
+        self.elapsed
+            .saturating_sub(self.rpc + self.token_refresh + self.reconnect + self.backoff)
+    }
+
+    fn add(&mut self, phase: DeadlinePhase, spent: Duration) {
+        match phase {
+            DeadlinePhase::Rpc => self.rpc += spent,
+            DeadlinePhase::TokenRefresh => self.token_refresh += spent,
+            DeadlinePhase::Reconnect => self.reconnect += spent,
+            DeadlinePhase::Backoff => self.backoff += spent,
+        }
+    }
+}
+
+impl fmt::Display for DeadlineBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} of {:?} used: rpc {:?} ({} attempts), token {:?}, reconnect {:?} ({} attempts), backoff {:?}, other {:?}",
+            self.elapsed,
+            self.budget,
+            self.rpc,
+            self.rpc_attempts,
+            self.token_refresh,
+            self.reconnect,
+            self.reconnect_attempts,
+            self.backoff,
+            self.other(),
+        )?;
+        if let Some(phase) = self.interrupted {
+            write!(f, "; interrupted during {phase:?}")?;
+        }
+        Ok(())
+    }
+}
+
+struct DeadlineState {
+    started: Instant,
+    expires: Instant,
+    breakdown: DeadlineBreakdown,
+    /// A phase is being timed; nested phases are not counted twice.
+    active: bool,
+}
+
+tokio::task_local! {
+    static DEADLINE: Arc<Mutex<DeadlineState>>;
+}
+
+/// Run `op` with at most `budget` for all of it. See the
+/// [module documentation](self).
+pub(crate) async fn run<T>(
+    budget: Duration,
+    op: impl Future<Output = CortexResult<T>>,
+) -> CortexResult<T> {
+    let started = Instant::now();
+    let expires = started + budget;
+    let state = Arc::new(Mutex::new(DeadlineState {
+        started,
+        expires,
+        breakdown: DeadlineBreakdown {
+            budget,
+            ..DeadlineBreakdown::default()
+        },
+        active: false,
+    }));
+
+    let result = tokio::time::timeout_at(expires, DEADLINE.scope(Arc::clone(&state), op)).await;
+    if let Ok(result) = result {
+        return result;
+    }
+
+    // The cancelled operation's phase guards have recorded their time.
+    let breakdown = state.lock().map_or_else(
+        |_| DeadlineBreakdown {
+            budget,
+            elapsed: started.elapsed(),
+            ..DeadlineBreakdown::default()
+        },
+        |s| DeadlineBreakdown {
+            elapsed: s.started.elapsed(),
+            ..s.breakdown.clone()
+        },
+    );
+    tracing::warn!(%breakdown, "Operation deadline exceeded");
+    Err(CortexError::DeadlineExceeded {
+        breakdown: Box::new(breakdown),
+    })
+}
+
+/// Run `fut` as `phase` of the current deadline, if there is one.
+pub(crate) async fn phase<T>(phase: DeadlinePhase, fut: impl Future<Output = T>) -> T {
+    let _guard = PhaseGuard::enter(phase);
+    fut.await
+}
+
+/// Records the time of one phase when dropped, including when the
+/// deadline cancels it.
+struct PhaseGuard {
+    state: Option<Arc<Mutex<DeadlineState>>>,
+    phase: DeadlinePhase,
+    entered: Instant,
+}
+
+impl PhaseGuard {
+    fn enter(phase: DeadlinePhase) -> Self {
+        let state = DEADLINE
+            .try_with(Arc::clone)
+            .ok()
+            .filter(|state| match state.lock() {
+                Ok(mut s) if !s.active => {
+                    s.active = true;
+                    match phase {
+                        DeadlinePhase::Rpc => s.breakdown.rpc_attempts += 1,
+                        DeadlinePhase::Reconnect => s.breakdown.reconnect_attempts += 1,
+                        DeadlinePhase::TokenRefresh | DeadlinePhase::Backoff => {}
+                    }
+                    true
+                }
+                _ => false,
+            });
+        Self {
+            state,
+            phase,
+            entered: Instant::now(),
+        }
+    }
+}
+
+impl Drop for PhaseGuard {
+    fn drop(&mut self) {
+        let Some(state) = self.state.take() else {
+            return;
+        };
+        if let Ok(mut s) = state.lock() {
+            s.active = false;
+            s.breakdown.add(self.phase, self.entered.elapsed());
+            if Instant::now() >= s.expires {
+                s.breakdown.interrupted = Some(self.phase);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_deadline_cancels_and_attributes_time() {
+        let ms = Duration::from_millis;
+        let result: CortexResult<()> = run(ms(300), async {
+            phase(DeadlinePhase::Rpc, tokio::time::sleep(ms(50))).await;
+            phase(DeadlinePhase::Reconnect, async {
+                // Nested phases are counted once.
+                phase(DeadlinePhase::Rpc, tokio::time::sleep(ms(50))).await;
+                tokio::time::sleep(Duration::from_secs(10)).await;
+            })
+            .await;
+            Ok(())
+        })
+        .await;
+
+        let Err(CortexError::DeadlineExceeded { breakdown }) = result else {
+            panic!("expected DeadlineExceeded, got {result:?}");
+        };
+        assert!(breakdown.elapsed >= ms(300), "{breakdown}");
+        assert!(breakdown.elapsed < ms(2000), "{breakdown}");
+        assert!(
+            breakdown.rpc >= ms(50) && breakdown.rpc < ms(100),
+            "{breakdown}"
+        );
+        assert_eq!(breakdown.rpc_attempts, 1);
+        assert!(breakdown.reconnect >= ms(200), "{breakdown}");
+        assert_eq!(breakdown.reconnect_attempts, 1);
+        assert!(breakdown.rpc + breakdown.reconnect <= breakdown.elapsed);
+        assert_eq!(breakdown.interrupted, Some(DeadlinePhase::Reconnect));
+    }
+
+    #[tokio::test]
+    async fn test_operation_within_budget_returns_its_result() {
+        let value = run(Duration::from_secs(1), async { Ok(7) }).await.unwrap();
+        assert_eq!(value, 7);
+        // Outside a deadline, phases are plain awaits.
+        assert_eq!(phase(DeadlinePhase::Rpc, async { 1 }).await, 1);
+    }
+}
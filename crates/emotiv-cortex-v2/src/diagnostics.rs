@@ -0,0 +1,395 @@
+//! # Diagnostics
+//!
+//! [`collect_support_bundle`] gathers the information usually requested in
+//! a bug report into one zip file that users can attach to an issue:
+//!
+//! | Entry | Contents |
+//! |-------|----------|
+//! | `manifest.json` | crate version, enabled features, OS/arch, creation time |
+//! | `cortex_info.json` | `getCortexInfo` result |
+//! | `license_info.json` | `getLicenseInfo` result, redacted |
+//! | `headsets.json` | `queryHeadsets` result |
+//! | `client_stats.json` | stream dispatch stats, unmodeled fields, reader wake-ups, rate limiter counters |
+//! | `connection_events.json` | caller-supplied [`ConnectionEvent`] history |
+//! | `health_history.json` | caller-supplied [`HealthStatus`] history |
+//! | `wire_frames.jsonl` | the last N caller-supplied wire frames, redacted |
+//!
+//! A failing Cortex call does not abort the bundle; its entry records the
+//! error instead. Values under keys that look like credentials (tokens,
+//! secrets, license keys, e-mail addresses) are replaced with
+//! [`REDACTED`] before anything is written.
+//!
+//! Writing the zip requires the opt-in `support-bundle` feature;
+//! [`SupportBundle::gather`] works without it.
+//!
+//! ```no_run
+//! use emotiv_cortex_v2::CortexClient;
+//! use emotiv_cortex_v2::diagnostics::{SupportBundleInputs, collect_support_bundle};
+//!
+//! # async fn demo(client: &CortexClient, token: &str) -> emotiv_cortex_v2::CortexResult<()> {
+//! let inputs = SupportBundleInputs {
+//!     cortex_token: Some(token.to_string()),
+//!     ..SupportBundleInputs::default()
+//! };
+//! let bundle = collect_support_bundle(client, "cortex-support.zip", inputs).await?;
+//! println!("wrote {} entries", bundle.entries.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::Path;
+
+use serde_json::{Value, json};
+
+use crate::client::CortexClient;
+use crate::error::{CortexError, CortexResult};
+use crate::health::HealthStatus;
+use crate::protocol::constants::Methods;
+use crate::protocol::headset::QueryHeadsetsOptions;
+use crate::reconnect::ConnectionEvent;
+
+/// Placeholder written in place of redacted values.
+pub const REDACTED: &str = "<redacted>";
+
+/// Default number of trailing wire frames kept in a bundle.
+pub const DEFAULT_MAX_WIRE_FRAMES: usize = 200;
+
+/// Key fragments (lowercase) whose values are redacted.
+const SENSITIVE_KEY_FRAGMENTS: &[&str] = &[
+    "token", "secret", "password", "license", "email", "username", "owner",
+];
+
+/// Caller-held state to include in a support bundle.
+///
+/// The client does not keep connection-event, health, or wire histories
+/// itself; collect them from [`ResilientClient::event_receiver`], the
+/// health monitor, or your own transport logging and pass them here.
+///
+/// [`ResilientClient::event_receiver`]: crate::ResilientClient::event_receiver
+#[derive(Debug, Clone)]
+pub struct SupportBundleInputs {
+    /// Token used for `getLicenseInfo`; the license entry records an
+    /// error when `None`.
+    pub cortex_token: Option<String>,
+
+    /// Recent connection lifecycle events, oldest first.
+    pub connection_events: Vec<ConnectionEvent>,
+
+    /// Recent health monitor signals, oldest first.
+    pub health_history: Vec<HealthStatus>,
+
+    /// Captured raw WebSocket text frames, oldest first.
+    pub wire_frames: Vec<String>,
+
+    /// How many trailing wire frames to keep.
+    pub max_wire_frames: usize,
+}
+
+impl Default for SupportBundleInputs {
+    fn default() -> Self {
+        Self {
+            cortex_token: None,
+            connection_events: Vec::new(),
+            health_history: Vec::new(),
+            wire_frames: Vec::new(),
+            max_wire_frames: DEFAULT_MAX_WIRE_FRAMES,
+        }
+    }
+}
+
+/// One file inside a support bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleEntry {
+    /// File name inside the archive.
+    pub name: String,
+    /// UTF-8 file contents.
+    pub contents: String,
+}
+
+/// The redacted contents of a support bundle.
+#[derive(Debug, Clone, Default)]
+pub struct SupportBundle {
+    /// Archive entries in write order.
+    pub entries: Vec<BundleEntry>,
+}
+
+impl SupportBundle {
+    /// Query `client` and assemble the bundle contents without writing
+    /// anything to disk.
+    pub async fn gather(client: &CortexClient, inputs: SupportBundleInputs) -> Self {
+        let mut bundle = Self::default();
+
+        let cortex_info = client.get_cortex_info().await;
+        bundle.push_json("cortex_info.json", &result_value(cortex_info));
+
+        let license_info = match inputs.cortex_token.as_deref() {
+            // Raw, so the bundle shows a response the typed
+            // `LicenseInfo` cannot parse.
+            Some(token) => result_value(
+                client
+                    .call(Methods::GET_LICENSE_INFO, json!({ "cortexToken": token }))
+                    .await,
+            ),
+            None => json!({ "error": "no cortex token supplied" }),
+        };
+        bundle.push_json("license_info.json", &redact(license_info));
+
+        let headsets = client
+            .query_headsets(QueryHeadsetsOptions::default())
+            .await
+            .and_then(|headsets| Ok(serde_json::to_value(headsets)?));
+        bundle.push_json("headsets.json", &result_value(headsets));
+
+        bundle.push_json("client_stats.json", &client_stats(client).await);
+        bundle.push_json(
+            "connection_events.json",
+            &serde_json::to_value(&inputs.connection_events).unwrap_or(Value::Null),
+        );
+        bundle.push_json(
+            "health_history.json",
+            &serde_json::to_value(&inputs.health_history).unwrap_or(Value::Null),
+        );
+
+        let skip = inputs
+            .wire_frames
+            .len()
+            .saturating_sub(inputs.max_wire_frames);
+        let frames: Vec<String> = inputs.wire_frames[skip..]
+            .iter()
+            .map(|frame| redact_frame(frame))
+            .collect();
+        bundle.entries.push(BundleEntry {
+            name: "wire_frames.jsonl".into(),
+            contents: frames.join("\n"),
+        });
+
+        let manifest = manifest(&bundle.entries);
+        bundle.entries.insert(
+            0,
+            BundleEntry {
+                name: "manifest.json".into(),
+                contents: pretty(&manifest),
+            },
+        );
+
+        bundle
+    }
+
+    /// Entry named `name`, if present.
+    #[must_use]
+    pub fn entry(&self, name: &str) -> Option<&BundleEntry> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+
+    /// Write the bundle as a deflate-compressed zip archive.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::Io`] if the file cannot be written, or
+    /// [`CortexError::ConfigError`] when the `support-bundle` feature is
+    /// disabled.
+    pub fn write_zip(&self, path: impl AsRef<Path>) -> CortexResult<()> {
+        write_zip(&self.entries, path.as_ref())
+    }
+
+    fn push_json(&mut self, name: &str, value: &Value) {
+        self.entries.push(BundleEntry {
+            name: name.into(),
+            contents: pretty(value),
+        });
+    }
+}
+
+/// Gather a [`SupportBundle`] from `client` and write it to `path` as a
+/// zip archive.
+///
+/// # Errors
+/// Returns [`CortexError::Io`] if the archive cannot be written, or
+/// [`CortexError::ConfigError`] when the `support-bundle` feature is
+/// disabled. Failing Cortex calls are recorded in the bundle instead.
+pub async fn collect_support_bundle(
+    client: &CortexClient,
+    path: impl AsRef<Path>,
+    inputs: SupportBundleInputs,
+) -> CortexResult<SupportBundle> {
+    let bundle = SupportBundle::gather(client, inputs).await;
+    bundle.write_zip(path)?;
+    Ok(bundle)
+}
+
+/// Recursively replace values stored under credential-like keys with
+/// [`REDACTED`].
+#[must_use]
+pub fn redact(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let lower = key.to_ascii_lowercase();
+                    if SENSITIVE_KEY_FRAGMENTS.iter().any(|f| lower.contains(f)) {
+                        (key, Value::String(REDACTED.into()))
+                    } else {
+                        (key, redact(value))
+                    }
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(redact).collect()),
+        other => other,
+    }
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────────
+
+fn redact_frame(frame: &str) -> String {
+    match serde_json::from_str::<Value>(frame) {
+        Ok(value) => redact(value).to_string(),
+        // Unparseable frames could hold anything; keep only their size.
+        Err(_) => json!({ "unparsed_frame_bytes": frame.len() }).to_string(),
+    }
+}
+
+fn result_value(result: CortexResult<Value>) -> Value {
+    match result {
+        Ok(value) => value,
+        Err(e) => json!({ "error": e.to_string() }),
+    }
+}
+
+async fn client_stats(client: &CortexClient) -> Value {
+    let dispatch: serde_json::Map<String, Value> = client
+        .stream_dispatch_stats()
+        .into_iter()
+        .map(|(stream, stats)| {
+            (
+                stream.to_string(),
+                json!({
+                    "delivered": stats.delivered,
+                    "dropped_full": stats.dropped_full,
+                    "dropped_closed": stats.dropped_closed,
+                }),
+            )
+        })
+        .collect();
+
+    json!({
+        "connected": client.is_connected(),
+        "pending_responses": client.pending_response_count().await,
+        "reader_wakeups": client.reader_wakeups(),
+        "binary_frames_received": client.binary_frames_received(),
+        "unhandled_binary_frames": client.unhandled_binary_frames(),
+        "stream_dispatch": dispatch,
+        "unmodeled_fields": client.unmodeled_fields(),
+        "rate_limit": client.rate_limit_stats(),
+        "latency": client.latency_stats(),
+    })
+}
+
+fn manifest(entries: &[BundleEntry]) -> Value {
+    let mut features = Vec::new();
+    if cfg!(feature = "rustls-tls") {
+        features.push("rustls-tls");
+    }
+    if cfg!(feature = "native-tls") {
+        features.push("native-tls");
+    }
+    if cfg!(feature = "config-toml") {
+        features.push("config-toml");
+    }
+    if cfg!(feature = "support-bundle") {
+        features.push("support-bundle");
+    }
+
+    let created_unix_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis());
+
+    json!({
+        "crate": env!("CARGO_PKG_NAME"),
+        "version": env!("CARGO_PKG_VERSION"),
+        "features": features,
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "created_unix_ms": created_unix_ms,
+        "entries": entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+    })
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+#[cfg(feature = "support-bundle")]
+fn write_zip(entries: &[BundleEntry], path: &Path) -> CortexResult<()> {
+    use std::io::Write;
+
+    let file = std::fs::File::create(path)?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    for entry in entries {
+        zip.start_file(entry.name.as_str(), options)
+            .map_err(zip_error)?;
+        zip.write_all(entry.contents.as_bytes())?;
+    }
+    zip.finish().map_err(zip_error)?;
+    Ok(())
+}
+
+#[cfg(feature = "support-bundle")]
+fn zip_error(e: zip::result::ZipError) -> CortexError {
+    match e {
+        zip::result::ZipError::Io(io) => CortexError::Io(io),
+        other => CortexError::Io(std::io::Error::other(other)),
+    }
+}
+
+#[cfg(not(feature = "support-bundle"))]
+fn write_zip(_entries: &[BundleEntry], _path: &Path) -> CortexResult<()> {
+    Err(CortexError::ConfigError {
+        reason: "Support bundle archives are disabled. Enable the `support-bundle` feature on `emotiv-cortex-v2` to use diagnostics::collect_support_bundle.".into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_nested_credentials() {
+        let value = json!({
+            "cortexToken": "abc",
+            "params": {"clientSecret": "s", "streams": ["eeg"]},
+            "result": [{"licenseId": "L-1", "validTo": "2026-01-01"}],
+            "emailAddress": "a@b.c"
+        });
+
+        let redacted = redact(value);
+        assert_eq!(redacted["cortexToken"], REDACTED);
+        assert_eq!(redacted["params"]["clientSecret"], REDACTED);
+        assert_eq!(redacted["params"]["streams"], json!(["eeg"]));
+        assert_eq!(redacted["result"][0]["licenseId"], REDACTED);
+        assert_eq!(redacted["result"][0]["validTo"], "2026-01-01");
+        assert_eq!(redacted["emailAddress"], REDACTED);
+    }
+
+    #[test]
+    fn test_redact_frame_drops_unparseable_text() {
+        assert_eq!(
+            redact_frame(r#"{"params":{"cortexToken":"t"}}"#),
+            r#"{"params":{"cortexToken":"<redacted>"}}"#
+        );
+        assert_eq!(redact_frame("token=abc"), r#"{"unparsed_frame_bytes":9}"#);
+    }
+
+    #[test]
+    fn test_manifest_lists_entries_and_version() {
+        let entries = vec![BundleEntry {
+            name: "cortex_info.json".into(),
+            contents: "{}".into(),
+        }];
+        let manifest = manifest(&entries);
+        assert_eq!(manifest["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(manifest["entries"], json!(["cortex_info.json"]));
+    }
+}
@@ -0,0 +1,472 @@
+//! # Streaming Filters
+//!
+//! Neurofeedback and display code usually wants EEG without mains hum
+//! and with the slow drift and high-frequency noise cut away. This module
+//! provides the two filters that cover it, as causal IIR filters that run
+//! sample by sample with per-channel state:
+//!
+//! - a notch at the mains frequency ([`MainsFrequency`], 50 or 60 Hz),
+//! - a Butterworth bandpass of configurable order.
+//!
+//! [`FilterSpec`]s are combined into an [`EegFilter`], which checks them
+//! against the sampling rate once. [`SampleStreamExt::filtered`] applies
+//! it to an EEG stream.
+//!
+//! Enabled by the `dsp` feature.
+//!
+//! ```no_run
+//! use futures_util::StreamExt;
+//! use emotiv_cortex_v2::{CortexClient, HeadsetModel, streams};
+//! use emotiv_cortex_v2::dsp::{EegFilter, FilterSpec, MainsFrequency};
+//! use emotiv_cortex_v2::streams::SampleStreamExt;
+//!
+//! # async fn demo(client: &CortexClient, token: &str, session_id: &str) -> emotiv_cortex_v2::CortexResult<()> {
+//! let model = HeadsetModel::Insight;
+//! let filter = EegFilter::new(
+//!     model.sampling_rate_hz(),
+//!     &[
+//!         FilterSpec::notch(MainsFrequency::Hz50),
+//!         FilterSpec::bandpass(1.0, 40.0, 4),
+//!     ],
+//! )?;
+//!
+//! let eeg = streams::subscribe_eeg(client, token, session_id, model.num_channels()).await?;
+//! let mut eeg = eeg.filtered(filter);
+//! while let Some(sample) = eeg.next().await {
+//!     println!("{:?}", sample.channels);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`SampleStreamExt::filtered`]: crate::streams::SampleStreamExt::filtered
+
+use std::f64::consts::PI;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CortexError, CortexResult};
+use crate::protocol::streams::EegData;
+use crate::util::{f64_to_f32, usize_to_f64};
+
+/// Default quality factor of [`FilterSpec::notch`]: about 1.7 Hz wide at
+/// 50 Hz.
+pub const DEFAULT_NOTCH_Q: f64 = 30.0;
+
+/// Highest accepted [`FilterSpec::Bandpass`] order.
+pub const MAX_BANDPASS_ORDER: usize = 8;
+
+/// Mains frequency to notch out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MainsFrequency {
+    /// Europe, Asia, Africa, most of Oceania.
+    #[serde(rename = "50hz")]
+    Hz50,
+    /// The Americas, parts of Japan.
+    #[serde(rename = "60hz")]
+    Hz60,
+}
+
+impl MainsFrequency {
+    /// The frequency in Hz.
+    #[must_use]
+    pub fn hz(self) -> f64 {
+        match self {
+            Self::Hz50 => 50.0,
+            Self::Hz60 => 60.0,
+        }
+    }
+}
+
+/// One filter stage of an [`EegFilter`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FilterSpec {
+    /// Second-order notch.
+    Notch {
+        /// Centre frequency.
+        freq_hz: f64,
+        /// Centre frequency over -3 dB bandwidth.
+        q: f64,
+    },
+    /// Butterworth highpass at `low_hz` followed by a Butterworth lowpass
+    /// at `high_hz`.
+    Bandpass {
+        /// Lower -3 dB edge.
+        low_hz: f64,
+        /// Upper -3 dB edge.
+        high_hz: f64,
+        /// Order of each edge, 1 to [`MAX_BANDPASS_ORDER`].
+        order: usize,
+    },
+}
+
+impl FilterSpec {
+    /// A notch at `mains` with [`DEFAULT_NOTCH_Q`].
+    #[must_use]
+    pub fn notch(mains: MainsFrequency) -> Self {
+        Self::Notch {
+            freq_hz: mains.hz(),
+            q: DEFAULT_NOTCH_Q,
+        }
+    }
+
+    /// A bandpass from `low_hz` to `high_hz` of `order`.
+    #[must_use]
+    pub fn bandpass(low_hz: f64, high_hz: f64, order: usize) -> Self {
+        Self::Bandpass {
+            low_hz,
+            high_hz,
+            order,
+        }
+    }
+
+    fn design(&self, sampling_rate_hz: f64) -> CortexResult<Vec<Biquad>> {
+        let nyquist = sampling_rate_hz / 2.0;
+        let invalid = |reason: String| CortexError::ConfigError {
+            reason: format!("{self:?} at {sampling_rate_hz} Hz: {reason}"),
+        };
+        let in_band = |hz: f64| hz > 0.0 && hz < nyquist;
+        match *self {
+            Self::Notch { freq_hz, q } => {
+                if !in_band(freq_hz) {
+                    return Err(invalid(format!("frequency must lie in (0, {nyquist}) Hz")));
+                }
+                if !(q > 0.0 && q.is_finite()) {
+                    return Err(invalid("q must be positive".into()));
+                }
+                Ok(vec![Biquad::notch(freq_hz / sampling_rate_hz, q)])
+            }
+            Self::Bandpass {
+                low_hz,
+                high_hz,
+                order,
+            } => {
+                if !in_band(low_hz) || !in_band(high_hz) || low_hz >= high_hz {
+                    return Err(invalid(format!(
+                        "edges must satisfy 0 < low < high < {nyquist} Hz"
+                    )));
+                }
+                if !(1..=MAX_BANDPASS_ORDER).contains(&order) {
+                    return Err(invalid(format!("order must be 1 to {MAX_BANDPASS_ORDER}")));
+                }
+                let mut sections = butterworth(low_hz / sampling_rate_hz, order, Edge::Highpass);
+                sections.extend(butterworth(
+                    high_hz / sampling_rate_hz,
+                    order,
+                    Edge::Lowpass,
+                ));
+                Ok(sections)
+            }
+        }
+    }
+}
+
+// ─── Biquads ─────────────────────────────────────────────────────────────
+
+/// Normalised second-order section (`a0 == 1`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Biquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edge {
+    Lowpass,
+    Highpass,
+}
+
+impl Biquad {
+    fn normalised(b: [f64; 3], a: [f64; 3]) -> Self {
+        Self {
+            b0: b[0] / a[0],
+            b1: b[1] / a[0],
+            b2: b[2] / a[0],
+            a1: a[1] / a[0],
+            a2: a[2] / a[0],
+        }
+    }
+
+    /// Notch at `freq` cycles per sample (RBJ cookbook).
+    fn notch(freq: f64, q: f64) -> Self {
+        let w0 = 2.0 * PI * freq;
+        let alpha = w0.sin() / (2.0 * q);
+        let cos = w0.cos();
+        Self::normalised(
+            [1.0, -2.0 * cos, 1.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    /// Second-order low- or highpass at `freq` cycles per sample (RBJ
+    /// cookbook; the bilinear transform is prewarped at `freq`).
+    fn second_order(freq: f64, q: f64, edge: Edge) -> Self {
+        let w0 = 2.0 * PI * freq;
+        let alpha = w0.sin() / (2.0 * q);
+        let cos = w0.cos();
+        let a = [1.0 + alpha, -2.0 * cos, 1.0 - alpha];
+        match edge {
+            Edge::Lowpass => {
+                let b = (1.0 - cos) / 2.0;
+                Self::normalised([b, 2.0 * b, b], a)
+            }
+            Edge::Highpass => {
+                let b = f64::midpoint(1.0, cos);
+                Self::normalised([b, -2.0 * b, b], a)
+            }
+        }
+    }
+
+    /// First-order low- or highpass at `freq` cycles per sample.
+    fn first_order(freq: f64, edge: Edge) -> Self {
+        let k = (PI * freq).tan();
+        let a = [k + 1.0, k - 1.0, 0.0];
+        match edge {
+            Edge::Lowpass => Self::normalised([k, k, 0.0], a),
+            Edge::Highpass => Self::normalised([1.0, -1.0, 0.0], a),
+        }
+    }
+}
+
+/// Butterworth filter of `order` as cascaded sections: one second-order
+/// section per conjugate pole pair, plus a first-order section for odd
+/// orders.
+fn butterworth(freq: f64, order: usize, edge: Edge) -> Vec<Biquad> {
+    let n = usize_to_f64(order);
+    let mut sections: Vec<Biquad> = (0..order / 2)
+        .map(|k| {
+            let angle = usize_to_f64(2 * k + 1) * PI / (2.0 * n);
+            Biquad::second_order(freq, 1.0 / (2.0 * angle.sin()), edge)
+        })
+        .collect();
+    if order % 2 == 1 {
+        sections.push(Biquad::first_order(freq, edge));
+    }
+    sections
+}
+
+/// Transposed direct form II state of one section on one channel.
+#[derive(Debug, Clone, Copy, Default)]
+struct SectionState {
+    s1: f64,
+    s2: f64,
+}
+
+impl SectionState {
+    fn step(&mut self, section: &Biquad, x: f64) -> f64 {
+        let y = section.b0 * x + self.s1;
+        self.s1 = section.b1 * x - section.a1 * y + self.s2;
+        self.s2 = section.b2 * x - section.a2 * y;
+        y
+    }
+}
+
+// ─── EEG Filter ──────────────────────────────────────────────────────────
+
+/// A chain of [`FilterSpec`]s with per-channel state. See the
+/// [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct EegFilter {
+    specs: Vec<FilterSpec>,
+    sections: Vec<Biquad>,
+    /// `sections.len()` states per channel.
+    channels: Vec<Vec<SectionState>>,
+}
+
+impl EegFilter {
+    /// Filter with `specs`, in order, at `sampling_rate_hz`.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ConfigError`] if the sampling rate is not
+    /// positive, or a spec's frequencies are not below the Nyquist
+    /// frequency or its order is out of range.
+    pub fn new(sampling_rate_hz: f64, specs: &[FilterSpec]) -> CortexResult<Self> {
+        if !(sampling_rate_hz > 0.0 && sampling_rate_hz.is_finite()) {
+            return Err(CortexError::ConfigError {
+                reason: format!("filter sampling rate must be positive, got {sampling_rate_hz}"),
+            });
+        }
+        let mut sections = Vec::new();
+        for spec in specs {
+            sections.extend(spec.design(sampling_rate_hz)?);
+        }
+        Ok(Self {
+            specs: specs.to_vec(),
+            sections,
+            channels: Vec::new(),
+        })
+    }
+
+    /// The stages, in order.
+    #[must_use]
+    pub fn specs(&self) -> &[FilterSpec] {
+        &self.specs
+    }
+
+    /// Filter one sample. Channel state is created on first use; a
+    /// non-finite value passes through and resets its channel.
+    #[must_use]
+    pub fn process(&mut self, mut sample: EegData) -> EegData {
+        let sections = self.sections.len();
+        self.channels.resize_with(sample.channels.len(), || {
+            vec![SectionState::default(); sections]
+        });
+        for (value, states) in sample.channels.iter_mut().zip(&mut self.channels) {
+            if !value.is_finite() {
+                states.fill(SectionState::default());
+                continue;
+            }
+            let mut x = f64::from(*value);
+            for (state, section) in states.iter_mut().zip(&self.sections) {
+                x = state.step(section, x);
+            }
+            *value = f64_to_f32(x);
+        }
+        sample
+    }
+
+    /// Clear the filter state, e.g. after a gap in the stream.
+    pub fn reset(&mut self) {
+        self.channels.clear();
+    }
+}
+
+/// Stream of filtered [`EegData`] from an EEG stream. Ends with it.
+pub struct Filtered<S> {
+    eeg: S,
+    filter: EegFilter,
+}
+
+impl<S> Filtered<S>
+where
+    S: Stream<Item = EegData> + Unpin,
+{
+    /// Filter `eeg` with `filter`.
+    pub fn new(eeg: S, filter: EegFilter) -> Self {
+        Self { eeg, filter }
+    }
+
+    /// The filter, with its current state.
+    #[must_use]
+    pub fn filter(&self) -> &EegFilter {
+        &self.filter
+    }
+}
+
+impl<S> Stream for Filtered<S>
+where
+    S: Stream<Item = EegData> + Unpin,
+{
+    type Item = EegData;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        Pin::new(&mut this.eeg)
+            .poll_next(cx)
+            .map(|sample| sample.map(|sample| this.filter.process(sample)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streams::SampleStreamExt;
+    use crate::testing::eeg_sample;
+    use futures_util::{StreamExt, stream};
+
+    const RATE: f64 = 256.0;
+    /// One second of samples at `RATE`.
+    const SECOND: u32 = 256;
+
+    /// Peak output amplitude of a unit sine at `freq_hz` after the
+    /// filter has settled.
+    fn gain(specs: &[FilterSpec], freq_hz: f64) -> f64 {
+        let mut filter = EegFilter::new(RATE, specs).unwrap();
+        let mut peak = 0.0_f64;
+        for n in 0..SECOND * 8 {
+            let t = f64::from(n) / RATE;
+            let x = f64_to_f32((2.0 * PI * freq_hz * t).sin());
+            let y = filter.process(eeg_sample(0, 0, vec![x])).channels[0];
+            if n > SECOND * 4 {
+                peak = peak.max(f64::from(y.abs()));
+            }
+        }
+        peak
+    }
+
+    #[test]
+    fn test_notch_removes_mains_and_keeps_alpha() {
+        let specs = [FilterSpec::notch(MainsFrequency::Hz50)];
+        assert!(gain(&specs, 50.0) < 0.01);
+        assert!(gain(&specs, 10.0) > 0.99);
+
+        let specs = [FilterSpec::notch(MainsFrequency::Hz60)];
+        assert!(gain(&specs, 60.0) < 0.01);
+        assert!(gain(&specs, 50.0) > 0.9);
+    }
+
+    #[test]
+    fn test_bandpass_passes_band_and_attenuates_outside() {
+        for order in [1, 2, 4, 5] {
+            let specs = [FilterSpec::bandpass(4.0, 30.0, order)];
+            assert!(gain(&specs, 12.0) > 0.85, "order {order}");
+            // Butterworth edges sit at -3 dB.
+            assert!((gain(&specs, 30.0) - 0.707).abs() < 0.05, "order {order}");
+        }
+        let specs = [FilterSpec::bandpass(4.0, 30.0, 4)];
+        assert!(gain(&specs, 80.0) < 0.01);
+        assert!(gain(&specs, 0.5) < 0.01);
+    }
+
+    #[test]
+    fn test_channels_keep_independent_state() {
+        let mut filter = EegFilter::new(RATE, &[FilterSpec::bandpass(1.0, 40.0, 2)]).unwrap();
+        // A constant offset on channel 1 must not leak into channel 0.
+        let mut last = eeg_sample(0, 0, vec![]);
+        for _ in 0..SECOND * 10 {
+            last = filter.process(eeg_sample(0, 0, vec![0.0, 4200.0]));
+        }
+        assert_eq!(last.channels[0].to_bits(), 0.0_f32.to_bits());
+        assert!(last.channels[1].abs() < 1.0, "{:?}", last.channels);
+    }
+
+    #[test]
+    fn test_invalid_specs_are_rejected() {
+        for spec in [
+            FilterSpec::bandpass(30.0, 4.0, 4),
+            FilterSpec::bandpass(1.0, 200.0, 4),
+            FilterSpec::bandpass(1.0, 40.0, 0),
+            FilterSpec::bandpass(1.0, 40.0, MAX_BANDPASS_ORDER + 1),
+            FilterSpec::Notch {
+                freq_hz: 50.0,
+                q: 0.0,
+            },
+        ] {
+            assert!(
+                matches!(
+                    EegFilter::new(RATE, &[spec]),
+                    Err(CortexError::ConfigError { .. })
+                ),
+                "{spec:?}"
+            );
+        }
+        // 60 Hz lies above Nyquist at 100 Hz.
+        assert!(EegFilter::new(100.0, &[FilterSpec::notch(MainsFrequency::Hz60)]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_filtered_combinator_filters_stream() {
+        let filter = EegFilter::new(RATE, &[FilterSpec::bandpass(1.0, 40.0, 2)]).unwrap();
+        let samples = (0..SECOND * 10).map(|_| eeg_sample(0, 0, vec![4200.0]));
+        let filtered = stream::iter(samples).filtered(filter);
+        let last = filtered.collect::<Vec<_>>().await.pop().unwrap();
+        assert!(last.channels[0].abs() < 1.0);
+    }
+}
@@ -0,0 +1,287 @@
+//! # EEG Units and DC Offset
+//!
+//! Cortex reports EEG in microvolts riding on a large DC offset (around
+//! 4200 µV on EPOC and Insight), and the offset drifts slowly with
+//! electrode contact. Filters and thresholds written for zero-centred
+//! signals misbehave on that input.
+//!
+//! [`EegUnitConverter`] is an opt-in processing step that scales each
+//! channel to physical microvolts and removes the DC offset, with
+//! per-channel state. [`EegUnitConfig::for_model`] gives the settings for
+//! each [`HeadsetModel`]; [`EegUnitConfig::raw`] leaves samples untouched.
+//! [`SampleStreamExt::microvolts`] applies it to an EEG stream:
+//!
+//! ```no_run
+//! use futures_util::StreamExt;
+//! use emotiv_cortex_v2::{CortexClient, HeadsetModel, streams};
+//! use emotiv_cortex_v2::eeg_units::EegUnitConverter;
+//! use emotiv_cortex_v2::streams::SampleStreamExt;
+//!
+//! # async fn demo(client: &CortexClient, token: &str, session_id: &str) -> emotiv_cortex_v2::CortexResult<()> {
+//! let model = HeadsetModel::EpocX;
+//! let eeg = streams::subscribe_eeg(client, token, session_id, model.num_channels()).await?;
+//!
+//! let mut eeg = eeg.microvolts(EegUnitConverter::for_model(&model));
+//! while let Some(sample) = eeg.next().await {
+//!     println!("AF3 {:+.1} µV", sample.channels[0]);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`SampleStreamExt::microvolts`]: crate::streams::SampleStreamExt::microvolts
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::Stream;
+use serde::{Deserialize, Serialize};
+
+use crate::headset::HeadsetModel;
+use crate::protocol::streams::EegData;
+use crate::util::f64_to_f32;
+
+/// Default time constant of [`DcRemoval::Running`]: slow enough to leave
+/// delta activity (above ~0.1 Hz) in place.
+pub const DEFAULT_DC_TIME_CONSTANT: Duration = Duration::from_secs(2);
+
+/// How the DC offset is removed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DcRemoval {
+    /// Keep the offset.
+    None,
+    /// Subtract a constant, in microvolts.
+    Fixed {
+        /// Offset subtracted from every channel.
+        offset_uv: f32,
+    },
+    /// Subtract each channel's exponential moving average, seeded with
+    /// its first sample; follows electrode drift.
+    Running {
+        /// A step in the offset is 63% removed after this long.
+        time_constant: Duration,
+    },
+}
+
+/// Unit conversion settings for one headset model.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EegUnitConfig {
+    /// Microvolts per reported unit.
+    pub scale_uv: f32,
+    /// DC offset removal, applied after scaling.
+    pub dc: DcRemoval,
+}
+
+impl EegUnitConfig {
+    /// Leave samples exactly as Cortex sent them.
+    #[must_use]
+    pub fn raw() -> Self {
+        Self {
+            scale_uv: 1.0,
+            dc: DcRemoval::None,
+        }
+    }
+
+    /// Settings for `model`. Cortex already reports every current model
+    /// in microvolts, so only the offset is removed.
+    #[must_use]
+    pub fn for_model(model: &HeadsetModel) -> Self {
+        let scale_uv = match model {
+            HeadsetModel::Insight
+            | HeadsetModel::EpocPlus
+            | HeadsetModel::EpocX
+            | HeadsetModel::EpocFlex
+            | HeadsetModel::Virtual { .. }
+            | HeadsetModel::Unknown(_) => 1.0,
+        };
+        Self {
+            scale_uv,
+            dc: DcRemoval::Running {
+                time_constant: DEFAULT_DC_TIME_CONSTANT,
+            },
+        }
+    }
+
+    fn is_raw(&self) -> bool {
+        self.dc == DcRemoval::None && (self.scale_uv - 1.0).abs() < f32::EPSILON
+    }
+}
+
+/// Per-channel unit conversion and DC removal. See the
+/// [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct EegUnitConverter {
+    config: EegUnitConfig,
+    /// Weight of the newest sample in the running mean.
+    smoothing: f64,
+    means: Vec<Option<f64>>,
+}
+
+impl EegUnitConverter {
+    /// Convert with `config` at `sampling_rate_hz`.
+    #[must_use]
+    pub fn new(config: EegUnitConfig, sampling_rate_hz: f64) -> Self {
+        let smoothing = match config.dc {
+            DcRemoval::Running { time_constant } => {
+                let tau = time_constant.as_secs_f64();
+                if tau > 0.0 && sampling_rate_hz > 0.0 {
+                    1.0 - (-1.0 / (sampling_rate_hz * tau)).exp()
+                } else {
+                    1.0
+                }
+            }
+            DcRemoval::None | DcRemoval::Fixed { .. } => 0.0,
+        };
+        Self {
+            config,
+            smoothing,
+            means: Vec::new(),
+        }
+    }
+
+    /// The default settings and sampling rate of `model`.
+    #[must_use]
+    pub fn for_model(model: &HeadsetModel) -> Self {
+        Self::new(EegUnitConfig::for_model(model), model.sampling_rate_hz())
+    }
+
+    /// The settings in use.
+    #[must_use]
+    pub fn config(&self) -> &EegUnitConfig {
+        &self.config
+    }
+
+    /// Convert one sample. Non-finite values pass through and leave the
+    /// running mean alone.
+    #[must_use]
+    pub fn process(&mut self, mut sample: EegData) -> EegData {
+        if self.config.is_raw() {
+            return sample;
+        }
+        self.means.resize(sample.channels.len(), None);
+        let scale = f64::from(self.config.scale_uv);
+        for (value, mean) in sample.channels.iter_mut().zip(&mut self.means) {
+            if !value.is_finite() {
+                continue;
+            }
+            let uv = f64::from(*value) * scale;
+            let offset = match self.config.dc {
+                DcRemoval::None => 0.0,
+                DcRemoval::Fixed { offset_uv } => f64::from(offset_uv),
+                DcRemoval::Running { .. } => {
+                    let next = mean.map_or(uv, |m| m + self.smoothing * (uv - m));
+                    *mean = Some(next);
+                    next
+                }
+            };
+            *value = f64_to_f32(uv - offset);
+        }
+        sample
+    }
+
+    /// Forget the running means, e.g. after the headset was refitted.
+    pub fn reset(&mut self) {
+        self.means.clear();
+    }
+}
+
+/// Stream of converted [`EegData`] from an EEG stream. Ends with it.
+pub struct EegInMicrovolts<S> {
+    eeg: S,
+    converter: EegUnitConverter,
+}
+
+impl<S> EegInMicrovolts<S>
+where
+    S: Stream<Item = EegData> + Unpin,
+{
+    /// Convert `eeg` with `converter`.
+    pub fn new(eeg: S, converter: EegUnitConverter) -> Self {
+        Self { eeg, converter }
+    }
+
+    /// The converter, with its current state.
+    #[must_use]
+    pub fn converter(&self) -> &EegUnitConverter {
+        &self.converter
+    }
+}
+
+impl<S> Stream for EegInMicrovolts<S>
+where
+    S: Stream<Item = EegData> + Unpin,
+{
+    type Item = EegData;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        Pin::new(&mut this.eeg)
+            .poll_next(cx)
+            .map(|sample| sample.map(|sample| this.converter.process(sample)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streams::SampleStreamExt;
+    use crate::testing::eeg_sample;
+    use futures_util::{StreamExt, stream};
+
+    #[test]
+    fn test_raw_config_leaves_samples_untouched() {
+        let mut converter = EegUnitConverter::new(EegUnitConfig::raw(), 128.0);
+        let sample = converter.process(eeg_sample(0, 0, vec![4201.5, f32::NAN]));
+        assert_eq!(sample.channels[0].to_bits(), 4201.5_f32.to_bits());
+        assert!(sample.channels[1].is_nan());
+    }
+
+    #[test]
+    fn test_fixed_offset_and_scale() {
+        let config = EegUnitConfig {
+            scale_uv: 0.5,
+            dc: DcRemoval::Fixed { offset_uv: 2100.0 },
+        };
+        let mut converter = EegUnitConverter::new(config, 128.0);
+        let sample = converter.process(eeg_sample(0, 0, vec![4200.0, 4210.0]));
+        assert_eq!(sample.channels, vec![0.0, 5.0]);
+    }
+
+    #[test]
+    fn test_running_removal_tracks_each_channel_offset() {
+        let mut converter = EegUnitConverter::for_model(&HeadsetModel::Insight);
+
+        // The first sample seeds the mean, so it comes out centred.
+        let first = converter.process(eeg_sample(0, 0, vec![4200.0, 3900.0]));
+        assert_eq!(first.channels, vec![0.0, 0.0]);
+
+        // A 10 µV step decays towards zero at the 2 s time constant.
+        let mut last = first;
+        for _ in 0..(128 * 10) {
+            last = converter.process(eeg_sample(0, 0, vec![4210.0, 3890.0]));
+        }
+        assert!(last.channels[0].abs() < 0.1, "{:?}", last.channels);
+        assert!(last.channels[1].abs() < 0.1, "{:?}", last.channels);
+
+        converter.reset();
+        let reseeded = converter.process(eeg_sample(0, 0, vec![4000.0, 4000.0]));
+        assert_eq!(reseeded.channels, vec![0.0, 0.0]);
+    }
+
+    #[tokio::test]
+    async fn test_microvolts_combinator_converts_stream() {
+        let config = EegUnitConfig {
+            scale_uv: 1.0,
+            dc: DcRemoval::Fixed { offset_uv: 4200.0 },
+        };
+        let samples = stream::iter([
+            eeg_sample(0, 0, vec![4200.0]),
+            eeg_sample(0, 0, vec![4195.0]),
+        ])
+        .microvolts(EegUnitConverter::new(config, 128.0));
+        let channels: Vec<Vec<f32>> = samples.map(|s| s.channels).collect().await;
+        assert_eq!(channels, vec![vec![0.0], vec![-5.0]]);
+    }
+}
@@ -0,0 +1,736 @@
+//! # EEG Replay Buffer & Epoch Extraction
+//!
+//! [`EegReplayBuffer`] keeps the most recent EEG samples from a
+//! subscribed `eeg` stream so that fixed-length epochs can be cut out
+//! around marker times after the fact — the core primitive for
+//! event-related (ERP) analysis.
+//!
+//! Marker times are expressed in the same unit as [`EegData::timestamp`]
+//! (microseconds since the Unix epoch). The `time` value passed to
+//! [`CortexClient::inject_marker`] is in milliseconds, so multiply it by
+//! 1000 before calling [`EegReplayBuffer::epoch_around`].
+//!
+//! ```
+//! use std::time::Duration;
+//! use emotiv_cortex_v2::epochs::EegReplayBuffer;
+//! use emotiv_cortex_v2::protocol::streams::EegData;
+//!
+//! let mut buffer = EegReplayBuffer::new(128.0, 1024);
+//! for i in 0..256_i64 {
+//!     buffer.push(EegData {
+//!         timestamp: i * 7_812, // ~128 Hz
+//!         counter: 0,
+//!         interpolated: false,
+//!         channels: vec![0.0; 5],
+//!         raw_cq: 4.0,
+//!     });
+//! }
+//!
+//! let marker_us = 100 * 7_812;
+//! let epoch = buffer
+//!     .epoch_around(marker_us, Duration::from_millis(250), Duration::from_millis(500))
+//!     .unwrap();
+//! assert_eq!(epoch.num_channels(), 5);
+//! assert_eq!(epoch.num_samples(), 32 + 64);
+//! ```
+//!
+//! ## Shared Buffer
+//!
+//! [`EegReplayBuffer`] needs `&mut` access to push, so the task reading
+//! the stream and the code cutting epochs have to share it behind a lock.
+//! [`EpochBuffer::new`] instead returns a single [`EpochWriter`] and any
+//! number of cloneable [`EpochBuffer`] readers over one fixed-size ring
+//! of atomics: the writer never waits for readers, and a reader that
+//! races the writer over a slot sees the slot's sequence number change
+//! and reports the epoch as unavailable rather than returning torn data.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use emotiv_cortex_v2::epochs::{EpochBuffer, marker_time_us};
+//! use emotiv_cortex_v2::{CortexClient, HeadsetModel, streams};
+//!
+//! # async fn demo(client: &CortexClient, token: &str, session_id: &str, marker_ms: f64) -> emotiv_cortex_v2::CortexResult<()> {
+//! let model = HeadsetModel::EpocX;
+//! let (writer, buffer) = EpochBuffer::new(
+//!     model.num_channels(), model.sampling_rate_hz(), Duration::from_secs(10),
+//! );
+//! let eeg = streams::subscribe_eeg(client, token, session_id, model.num_channels()).await?;
+//! tokio::spawn(writer.feed(eeg));
+//!
+//! // Later, once the post-marker window has arrived:
+//! let epoch = buffer.epoch_around(
+//!     marker_time_us(marker_ms), Duration::from_millis(200), Duration::from_millis(800),
+//! );
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Choosing a Buffer
+//!
+//! Both buffers cut identical epochs from the same samples; they share
+//! the alignment code, so only storage differs.
+//!
+//! - [`EegReplayBuffer`] stores whole [`EegData`] samples, so channel
+//!   counts may differ between sessions and nothing is fixed up front.
+//!   Pick it when one task both feeds the stream and cuts epochs.
+//! - [`EpochBuffer`] stores a fixed channel count in preallocated slots.
+//!   Pick it when epochs are cut on another task or thread than the one
+//!   reading the stream, e.g. from a UI or a marker handler, so neither
+//!   side takes a lock.
+//!
+//! [`CortexClient::inject_marker`]: crate::CortexClient::inject_marker
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, AtomicU32, AtomicU64, Ordering, fence};
+use std::time::Duration;
+
+use futures_core::Stream;
+use futures_util::StreamExt;
+
+use crate::protocol::streams::EegData;
+use crate::util::{duration_to_samples, u64_to_usize, usize_to_u64};
+
+/// Bounded FIFO of recent EEG samples used for epoch extraction.
+///
+/// Once `capacity` samples are held, pushing a new sample evicts the
+/// oldest one. Samples are expected to arrive in timestamp order, as
+/// they do from the Cortex `eeg` stream.
+#[derive(Debug, Clone)]
+pub struct EegReplayBuffer {
+    sampling_rate_hz: f64,
+    capacity: usize,
+    samples: VecDeque<EegData>,
+}
+
+impl EegReplayBuffer {
+    /// Create a buffer holding at most `capacity` samples recorded at
+    /// `sampling_rate_hz` (see [`HeadsetModel::sampling_rate_hz`]).
+    ///
+    /// [`HeadsetModel::sampling_rate_hz`]: crate::headset::HeadsetModel::sampling_rate_hz
+    #[must_use]
+    pub fn new(sampling_rate_hz: f64, capacity: usize) -> Self {
+        Self {
+            sampling_rate_hz,
+            capacity: capacity.max(1),
+            samples: VecDeque::with_capacity(capacity.max(1)),
+        }
+    }
+
+    /// Create a buffer sized to hold `window` worth of samples at
+    /// `sampling_rate_hz`.
+    #[must_use]
+    pub fn with_window(sampling_rate_hz: f64, window: Duration) -> Self {
+        Self::new(
+            sampling_rate_hz,
+            duration_to_samples(window, sampling_rate_hz),
+        )
+    }
+
+    /// Append a sample, evicting the oldest one when full.
+    pub fn push(&mut self, sample: EegData) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Number of samples currently buffered.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Whether the buffer holds no samples.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Maximum number of samples retained.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Nominal sampling rate used to convert windows to sample counts.
+    #[must_use]
+    pub fn sampling_rate_hz(&self) -> f64 {
+        self.sampling_rate_hz
+    }
+
+    /// Timestamp (µs) of the newest buffered sample.
+    #[must_use]
+    pub fn latest_timestamp(&self) -> Option<i64> {
+        self.samples.back().map(|s| s.timestamp)
+    }
+
+    /// Remove all buffered samples.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Extract an epoch time-locked to `marker_time_us`.
+    ///
+    /// The epoch is anchored on the first sample at or after the marker
+    /// and spans `pre` before and `post` after it, converted to sample
+    /// counts at the nominal rate. Every epoch for the same `pre`/`post`
+    /// therefore has the same shape, regardless of timestamp jitter.
+    ///
+    /// Returns `None` when the window is not fully buffered — either the
+    /// pre-marker samples were already evicted or the post-marker samples
+    /// have not arrived yet (poll again after pushing more data).
+    #[must_use]
+    pub fn epoch_around(
+        &self,
+        marker_time_us: i64,
+        pre: Duration,
+        post: Duration,
+    ) -> Option<Epoch> {
+        let pre_samples = duration_to_samples(pre, self.sampling_rate_hz);
+        let post_samples = duration_to_samples(post, self.sampling_rate_hz);
+        let (start, end) = epoch_window(
+            0,
+            usize_to_u64(self.samples.len()),
+            |index| Some(self.samples.get(u64_to_usize(index))?.timestamp),
+            marker_time_us,
+            pre_samples,
+            post_samples,
+        )?;
+        let (start, end) = (u64_to_usize(start), u64_to_usize(end));
+
+        let num_channels = self.samples.get(start)?.channels.len();
+        let num_samples = end - start;
+        let mut data = vec![0.0_f32; num_channels * num_samples];
+        let mut timestamps = Vec::with_capacity(num_samples);
+
+        for (col, sample) in self.samples.range(start..end).enumerate() {
+            if sample.channels.len() != num_channels {
+                return None;
+            }
+            timestamps.push(sample.timestamp);
+            for (ch, value) in sample.channels.iter().enumerate() {
+                data[ch * num_samples + col] = *value;
+            }
+        }
+
+        Some(Epoch {
+            marker_time_us,
+            pre_samples,
+            num_channels,
+            num_samples,
+            timestamps,
+            data,
+        })
+    }
+}
+
+/// A fixed-length, channel-major block of EEG around a marker.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Epoch {
+    /// Marker time the epoch was extracted around (µs).
+    pub marker_time_us: i64,
+    /// Index of the anchor sample (first sample at or after the marker).
+    pub pre_samples: usize,
+    num_channels: usize,
+    num_samples: usize,
+    timestamps: Vec<i64>,
+    data: Vec<f32>,
+}
+
+impl Epoch {
+    /// Number of EEG channels (matrix rows).
+    #[must_use]
+    pub fn num_channels(&self) -> usize {
+        self.num_channels
+    }
+
+    /// Number of samples per channel (matrix columns).
+    #[must_use]
+    pub fn num_samples(&self) -> usize {
+        self.num_samples
+    }
+
+    /// Per-sample timestamps (µs), one per column.
+    #[must_use]
+    pub fn timestamps(&self) -> &[i64] {
+        &self.timestamps
+    }
+
+    /// Samples for one channel, or `None` if `channel` is out of range.
+    #[must_use]
+    pub fn channel(&self, channel: usize) -> Option<&[f32]> {
+        if channel >= self.num_channels {
+            return None;
+        }
+        let start = channel * self.num_samples;
+        self.data.get(start..start + self.num_samples)
+    }
+
+    /// Value at `(channel, sample)`.
+    #[must_use]
+    pub fn get(&self, channel: usize, sample: usize) -> Option<f32> {
+        if sample >= self.num_samples {
+            return None;
+        }
+        self.channel(channel)?.get(sample).copied()
+    }
+
+    /// Row-major `[channel][sample]` matrix data.
+    #[must_use]
+    pub fn as_slice(&self) -> &[f32] {
+        &self.data
+    }
+}
+
+// ─── Shared Ring Buffer ──────────────────────────────────────────────────
+
+/// One ring slot. `seq` is `2k + 1` while sample `k` is being written and
+/// `2k + 2` once it is complete.
+#[derive(Debug)]
+struct Slot {
+    seq: AtomicU64,
+    timestamp: AtomicI64,
+    values: Box<[AtomicU32]>,
+}
+
+#[derive(Debug)]
+struct Ring {
+    sampling_rate_hz: f64,
+    num_channels: usize,
+    slots: Box<[Slot]>,
+    /// Samples written so far.
+    written: AtomicU64,
+}
+
+impl Ring {
+    fn capacity(&self) -> u64 {
+        usize_to_u64(self.slots.len())
+    }
+
+    fn slot(&self, index: u64) -> &Slot {
+        &self.slots[u64_to_usize(index % self.capacity())]
+    }
+
+    /// Indices of the samples still held: `[oldest, written)`.
+    fn held(&self) -> (u64, u64) {
+        let written = self.written.load(Ordering::Acquire);
+        (written.saturating_sub(self.capacity()), written)
+    }
+
+    /// Timestamp of sample `index`, or `None` if it has been overwritten.
+    fn timestamp(&self, index: u64) -> Option<i64> {
+        let slot = self.slot(index);
+        let seq = slot.seq.load(Ordering::Acquire);
+        let timestamp = slot.timestamp.load(Ordering::Relaxed);
+        fence(Ordering::Acquire);
+        (seq == 2 * index + 2 && slot.seq.load(Ordering::Relaxed) == seq).then_some(timestamp)
+    }
+
+    /// Copy sample `index` into column `col` of a channel-major matrix
+    /// with `columns` columns. Returns its timestamp, or `None` if it has
+    /// been overwritten.
+    fn copy(&self, index: u64, data: &mut [f32], col: usize, columns: usize) -> Option<i64> {
+        let slot = self.slot(index);
+        let seq = slot.seq.load(Ordering::Acquire);
+        if seq != 2 * index + 2 {
+            return None;
+        }
+        let timestamp = slot.timestamp.load(Ordering::Relaxed);
+        for (ch, value) in slot.values.iter().enumerate() {
+            data[ch * columns + col] = f32::from_bits(value.load(Ordering::Relaxed));
+        }
+        fence(Ordering::Acquire);
+        (slot.seq.load(Ordering::Relaxed) == seq).then_some(timestamp)
+    }
+}
+
+/// Reader side of a shared EEG ring buffer. See the
+/// [module documentation](self#shared-buffer).
+///
+/// Clones read the same buffer.
+#[derive(Debug, Clone)]
+pub struct EpochBuffer {
+    ring: Arc<Ring>,
+}
+
+/// Writer side of an [`EpochBuffer`]; there is exactly one per buffer.
+#[derive(Debug)]
+pub struct EpochWriter {
+    ring: Arc<Ring>,
+}
+
+impl EpochBuffer {
+    /// Create a buffer holding the last `window` of `num_channels`-channel
+    /// EEG at `sampling_rate_hz`.
+    #[must_use]
+    pub fn new(
+        num_channels: usize,
+        sampling_rate_hz: f64,
+        window: Duration,
+    ) -> (EpochWriter, EpochBuffer) {
+        let capacity = duration_to_samples(window, sampling_rate_hz).max(1);
+        let slots = (0..capacity)
+            .map(|_| Slot {
+                seq: AtomicU64::new(0),
+                timestamp: AtomicI64::new(0),
+                values: (0..num_channels).map(|_| AtomicU32::new(0)).collect(),
+            })
+            .collect();
+        let ring = Arc::new(Ring {
+            sampling_rate_hz,
+            num_channels,
+            slots,
+            written: AtomicU64::new(0),
+        });
+        (
+            EpochWriter {
+                ring: Arc::clone(&ring),
+            },
+            EpochBuffer { ring },
+        )
+    }
+
+    /// Number of samples currently buffered.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        let (oldest, written) = self.ring.held();
+        u64_to_usize(written - oldest)
+    }
+
+    /// Whether no sample has been written yet.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Maximum number of samples retained.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.ring.slots.len()
+    }
+
+    /// EEG channels per sample.
+    #[must_use]
+    pub fn num_channels(&self) -> usize {
+        self.ring.num_channels
+    }
+
+    /// Nominal sampling rate used to convert windows to sample counts.
+    #[must_use]
+    pub fn sampling_rate_hz(&self) -> f64 {
+        self.ring.sampling_rate_hz
+    }
+
+    /// Timestamp (µs) of the newest buffered sample.
+    #[must_use]
+    pub fn latest_timestamp(&self) -> Option<i64> {
+        let (_, written) = self.ring.held();
+        self.ring.timestamp(written.checked_sub(1)?)
+    }
+
+    /// Extract an epoch time-locked to `marker_time_us`, with the same
+    /// alignment as [`EegReplayBuffer::epoch_around`].
+    ///
+    /// Returns `None` when the window is not fully buffered (the
+    /// pre-marker samples were overwritten, or the post-marker samples
+    /// have not arrived yet), or when the writer overwrote part of it
+    /// while it was being copied.
+    #[must_use]
+    pub fn epoch_around(
+        &self,
+        marker_time_us: i64,
+        pre: Duration,
+        post: Duration,
+    ) -> Option<Epoch> {
+        let ring = &self.ring;
+        let pre_samples = duration_to_samples(pre, ring.sampling_rate_hz);
+        let post_samples = duration_to_samples(post, ring.sampling_rate_hz);
+        let (oldest, written) = ring.held();
+        let (start, end) = epoch_window(
+            oldest,
+            written,
+            |index| ring.timestamp(index),
+            marker_time_us,
+            pre_samples,
+            post_samples,
+        )?;
+
+        let num_samples = pre_samples + post_samples;
+        let mut data = vec![0.0_f32; ring.num_channels * num_samples];
+        let mut timestamps = Vec::with_capacity(num_samples);
+        for (col, index) in (start..end).enumerate() {
+            timestamps.push(ring.copy(index, &mut data, col, num_samples)?);
+        }
+
+        Some(Epoch {
+            marker_time_us,
+            pre_samples,
+            num_channels: ring.num_channels,
+            num_samples,
+            timestamps,
+            data,
+        })
+    }
+}
+
+impl EpochWriter {
+    /// Append a sample, overwriting the oldest one when full.
+    ///
+    /// Returns `false` (and drops the sample) if its channel count does
+    /// not match the buffer's.
+    pub fn push(&mut self, sample: &EegData) -> bool {
+        let ring = &self.ring;
+        if sample.channels.len() != ring.num_channels {
+            return false;
+        }
+        let index = ring.written.load(Ordering::Relaxed);
+        let slot = ring.slot(index);
+        slot.seq.store(2 * index + 1, Ordering::Relaxed);
+        fence(Ordering::Release);
+        slot.timestamp.store(sample.timestamp, Ordering::Relaxed);
+        for (cell, value) in slot.values.iter().zip(&sample.channels) {
+            cell.store(value.to_bits(), Ordering::Relaxed);
+        }
+        slot.seq.store(2 * index + 2, Ordering::Release);
+        ring.written.store(index + 1, Ordering::Release);
+        true
+    }
+
+    /// Push every sample of `stream` until it ends.
+    pub async fn feed<S>(mut self, stream: S)
+    where
+        S: Stream<Item = EegData> + Unpin,
+    {
+        let mut stream = stream;
+        while let Some(sample) = stream.next().await {
+            if !self.push(&sample) {
+                tracing::warn!(
+                    expected = self.ring.num_channels,
+                    got = sample.channels.len(),
+                    "EEG sample with unexpected channel count dropped from epoch buffer"
+                );
+            }
+        }
+    }
+}
+
+/// Sample indices `[start, end)` of the epoch around `marker_time_us`,
+/// given the samples held at `[oldest, end_held)` and their timestamps
+/// (non-decreasing). The epoch is anchored on the first sample at or
+/// after the marker. `None` when the window is not fully held or a
+/// timestamp could not be read.
+fn epoch_window(
+    oldest: u64,
+    end_held: u64,
+    timestamp: impl Fn(u64) -> Option<i64>,
+    marker_time_us: i64,
+    pre_samples: usize,
+    post_samples: usize,
+) -> Option<(u64, u64)> {
+    // A marker older than the whole buffer has lost its anchor sample.
+    if timestamp(oldest)? > marker_time_us {
+        return None;
+    }
+    let (mut lo, mut hi) = (oldest, end_held);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if timestamp(mid)? < marker_time_us {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    let anchor = lo;
+    if anchor == end_held {
+        return None;
+    }
+    let start = anchor.checked_sub(usize_to_u64(pre_samples))?;
+    let end = anchor.checked_add(usize_to_u64(post_samples))?;
+    (start >= oldest && end <= end_held).then_some((start, end))
+}
+
+/// Convert a marker time in epoch milliseconds (as passed to
+/// `injectMarker` and returned by [`MarkerBatcher::mark`]) to the
+/// microseconds used by EEG timestamps.
+///
+/// [`MarkerBatcher::mark`]: crate::markers::MarkerBatcher::mark
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub fn marker_time_us(millis: f64) -> i64 {
+    (millis * 1000.0).round() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(timestamp: i64, value: f32) -> EegData {
+        EegData {
+            timestamp,
+            counter: 0,
+            interpolated: false,
+            channels: vec![value, -value],
+            raw_cq: 4.0,
+        }
+    }
+
+    /// 100 Hz samples every 10 ms, value = sample index.
+    fn filled(count: i64, capacity: usize) -> EegReplayBuffer {
+        let mut buffer = EegReplayBuffer::new(100.0, capacity);
+        for i in 0..count {
+            #[allow(clippy::cast_precision_loss)]
+            buffer.push(sample(i * 10_000, i as f32));
+        }
+        buffer
+    }
+
+    #[test]
+    fn test_push_evicts_oldest_when_full() {
+        let buffer = filled(10, 4);
+        assert_eq!(buffer.len(), 4);
+        assert_eq!(buffer.latest_timestamp(), Some(90_000));
+        assert_eq!(buffer.samples.front().map(|s| s.timestamp), Some(60_000));
+    }
+
+    #[test]
+    fn test_epoch_is_aligned_on_marker() {
+        let buffer = filled(100, 100);
+        // Marker between samples 50 and 51 anchors on 51.
+        let epoch = buffer
+            .epoch_around(
+                505_000,
+                Duration::from_millis(50),
+                Duration::from_millis(100),
+            )
+            .unwrap();
+
+        assert_eq!(epoch.num_channels(), 2);
+        assert_eq!(epoch.num_samples(), 15);
+        assert_eq!(epoch.pre_samples, 5);
+        assert_eq!(epoch.timestamps()[epoch.pre_samples], 510_000);
+        assert_eq!(epoch.get(0, 0), Some(46.0));
+        assert_eq!(epoch.get(1, 0), Some(-46.0));
+        assert_eq!(epoch.channel(0).unwrap().last().copied(), Some(60.0));
+        assert_eq!(epoch.channel(2), None);
+    }
+
+    #[test]
+    fn test_epoch_waits_for_post_window() {
+        let buffer = filled(55, 100);
+        assert!(
+            buffer
+                .epoch_around(
+                    500_000,
+                    Duration::from_millis(50),
+                    Duration::from_millis(100)
+                )
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_epoch_rejects_evicted_pre_window() {
+        let buffer = filled(100, 20);
+        // Buffer now starts at sample 80.
+        assert!(
+            buffer
+                .epoch_around(
+                    820_000,
+                    Duration::from_millis(50),
+                    Duration::from_millis(10)
+                )
+                .is_none()
+        );
+        assert!(
+            buffer
+                .epoch_around(
+                    500_000,
+                    Duration::from_millis(50),
+                    Duration::from_millis(10)
+                )
+                .is_none()
+        );
+    }
+
+    /// Push `count` samples every `step_us`, value = sample index.
+    fn push_indexed(writer: &mut EpochWriter, count: i64, step_us: i64) {
+        for i in 0..count {
+            #[allow(clippy::cast_precision_loss)]
+            let value = i as f32;
+            assert!(writer.push(&sample(i * step_us, value)));
+        }
+    }
+
+    #[test]
+    fn test_shared_buffer_matches_replay_buffer() {
+        let replay = filled(100, 100);
+        let (mut writer, buffer) = EpochBuffer::new(2, 100.0, Duration::from_secs(1));
+        assert_eq!(buffer.capacity(), 100);
+        assert!(buffer.is_empty());
+        push_indexed(&mut writer, 100, 10_000);
+        assert!(!writer.push(&EegData {
+            channels: vec![0.0; 3],
+            ..sample(1_000_000, 0.0)
+        }));
+        assert_eq!(buffer.len(), 100);
+        assert_eq!(buffer.latest_timestamp(), Some(990_000));
+
+        let (pre, post) = (Duration::from_millis(50), Duration::from_millis(100));
+        let epoch = buffer.epoch_around(505_000, pre, post);
+        assert!(epoch.is_some());
+        assert_eq!(epoch, replay.epoch_around(505_000, pre, post));
+        // Post window not yet recorded.
+        assert!(buffer.epoch_around(950_000, pre, post).is_none());
+    }
+
+    #[test]
+    fn test_shared_buffer_rejects_overwritten_samples() {
+        let (mut writer, buffer) = EpochBuffer::new(2, 100.0, Duration::from_millis(200));
+        push_indexed(&mut writer, 100, 10_000);
+        assert_eq!(buffer.len(), 20);
+        let (pre, post) = (Duration::from_millis(50), Duration::from_millis(10));
+        assert!(buffer.epoch_around(820_000, pre, post).is_none());
+        let epoch = buffer.epoch_around(900_000, pre, post).unwrap();
+        assert_eq!(epoch.timestamps()[0], 850_000);
+    }
+
+    #[test]
+    fn test_shared_buffer_epochs_are_never_torn() {
+        let (mut writer, buffer) = EpochBuffer::new(2, 1000.0, Duration::from_millis(64));
+        let reader = std::thread::spawn(move || {
+            let (pre, post) = (Duration::from_millis(8), Duration::from_millis(8));
+            while buffer.latest_timestamp().unwrap_or(0) < 19_000_000 {
+                let Some(latest) = buffer.latest_timestamp() else {
+                    continue;
+                };
+                let Some(epoch) = buffer.epoch_around(latest - 20_000, pre, post) else {
+                    continue;
+                };
+                let first = epoch.timestamps()[0];
+                for (col, ts) in epoch.timestamps().iter().enumerate() {
+                    let index = first / 1_000 + i64::try_from(col).unwrap();
+                    assert_eq!(*ts, index * 1_000);
+                    #[allow(clippy::cast_precision_loss)]
+                    let expected = index as f32;
+                    assert!((epoch.get(0, col).unwrap() - expected).abs() < f32::EPSILON);
+                    assert!((epoch.get(1, col).unwrap() + expected).abs() < f32::EPSILON);
+                }
+            }
+        });
+        push_indexed(&mut writer, 20_000, 1_000);
+        reader.join().unwrap();
+    }
+
+    #[test]
+    fn test_marker_time_us_converts_millis() {
+        assert_eq!(marker_time_us(1_700_000_000_123.456), 1_700_000_000_123_456);
+    }
+
+    #[test]
+    fn test_with_window_sizes_capacity() {
+        let buffer = EegReplayBuffer::with_window(128.0, Duration::from_secs(2));
+        assert_eq!(buffer.capacity(), 256);
+        assert!(buffer.is_empty());
+    }
+}
@@ -7,15 +7,23 @@
 //!
 //! The Cortex API returns numeric error codes in JSON-RPC error responses.
 //! [`CortexError::from_api_error`] maps known codes to semantic variants
-//! with actionable error messages.
+//! with actionable error messages, and [`CortexError::error_code`] maps a
+//! variant back to its code. Codes without a variant of their own stay
+//! available as [`CortexError::ApiError`].
 
 use thiserror::Error;
 
+use crate::deadline::DeadlineBreakdown;
+use crate::protocol::constants::ErrorCodes;
+
 /// Convenient Result alias for Cortex operations.
 pub type CortexResult<T> = std::result::Result<T, CortexError>;
 
 /// All errors that can occur when interacting with the Emotiv Cortex API.
+///
+/// New variants may be added in minor releases; match with a wildcard arm.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum CortexError {
     // ─── Connection ─────────────────────────────────────────────────
     /// Failed to establish a WebSocket connection to the Cortex service.
@@ -53,11 +61,20 @@ pub enum CortexError {
     #[error("Application not approved. Open the EMOTIV Launcher and approve access for your app.")]
     NotApproved,
 
+    /// The user has not accepted the EMOTIV end-user license agreement.
+    #[error("EULA not accepted. Open the EMOTIV Launcher and accept the license agreement.")]
+    EulaNotAccepted,
+
     // ─── License ────────────────────────────────────────────────────
     /// License expired, invalid, or missing for the requested operation.
     #[error("Emotiv license error: {reason}")]
     LicenseError { reason: String },
 
+    /// The license does not include the requested stream (`-32029`), e.g.
+    /// `eeg` without a paid license.
+    #[error("Stream not available on this license: {reason}")]
+    StreamNotLicensed { reason: String },
+
     // ─── Headset ────────────────────────────────────────────────────
     /// No headset found (either not paired or not powered on).
     #[error("No headset found. Ensure the headset is powered on and within range.")]
@@ -71,16 +88,58 @@ pub enum CortexError {
     #[error("Headset connection error: {reason}")]
     HeadsetError { reason: String },
 
+    /// The headset is connected but not ready yet (`-32152`), e.g. while it
+    /// is still calibrating. Retryable.
+    #[error("Headset not ready: {reason}")]
+    HeadsetNotReady { reason: String },
+
     // ─── Session ────────────────────────────────────────────────────
     /// Session-related error (create, update, close failed).
     #[error("Session error: {reason}")]
     SessionError { reason: String },
 
+    /// The session does not exist (`-32007`), usually because it was closed
+    /// or Cortex restarted.
+    #[error("Session not found: {reason}")]
+    SessionNotFound { reason: String },
+
+    /// Cortex refused to open another session on this device (`-32019`).
+    #[error("Session limit reached: {reason}. Close unused sessions and try again.")]
+    SessionLimitReached { reason: String },
+
+    /// The session exists but is not active (`-32012`). Re-activate it with
+    /// `updateSession` (`status = "active"`); `ResilientClient` does this
+    /// automatically once per call.
+    #[error("Session must be activated: {reason}")]
+    SessionNotActivated { reason: String },
+
     // ─── Streams ────────────────────────────────────────────────────
     /// Subscribe/unsubscribe failed for the requested streams.
     #[error("Stream error: {reason}")]
     StreamError { reason: String },
 
+    // ─── Records ────────────────────────────────────────────────────
+    /// Cortex could not export a record.
+    #[error("Export of record {record_id} failed: {reason}")]
+    ExportFailed { record_id: String, reason: String },
+
+    /// The record does not exist (`-32104`).
+    #[error("Record not found: {reason}")]
+    RecordNotFound { reason: String },
+
+    /// A marker's time falls outside its record (`-32107`).
+    #[error("Marker outside record: {reason}")]
+    MarkerOutsideRecord { reason: String },
+
+    // ─── Subjects & Profiles ────────────────────────────────────────
+    /// A subject with the same name already exists (`-32110`).
+    #[error("Subject already exists: {reason}")]
+    SubjectAlreadyExists { reason: String },
+
+    /// The profile is loaded by another application (`-32045`).
+    #[error("Profile in use by another application: {reason}")]
+    ProfileInUse { reason: String },
+
     // ─── API ────────────────────────────────────────────────────────
     /// Raw Cortex API error that doesn't map to a more specific variant.
     #[error("Cortex API error {code}: {message}")]
@@ -94,11 +153,21 @@ pub enum CortexError {
     #[error("API method not found: {method}")]
     MethodNotFound { method: String },
 
+    /// Cortex rejected the method parameters (`-32602`).
+    #[error("Invalid parameters: {reason}")]
+    InvalidParams { reason: String },
+
     // ─── Timeout ────────────────────────────────────────────────────
     /// An operation timed out waiting for a response.
     #[error("Operation timed out after {seconds}s")]
     Timeout { seconds: u64 },
 
+    /// An operation bounded by
+    /// [`ResilientClient::with_deadline`](crate::ResilientClient::with_deadline)
+    /// ran out of budget and was cancelled.
+    #[error("Deadline exceeded: {breakdown}")]
+    DeadlineExceeded { breakdown: Box<DeadlineBreakdown> },
+
     // ─── Retry ──────────────────────────────────────────────────────
     /// All retry attempts have been exhausted.
     #[error("Operation failed after {attempts} attempts: {last_error}")]
@@ -139,24 +208,38 @@ pub enum CortexError {
 impl CortexError {
     /// Map a Cortex API error code + message to the most specific error variant.
     ///
-    /// Known error codes from the Cortex v2 API docs (2026-02-12):
+    /// Known error codes from the Cortex v2 API docs, by [`ErrorCodes`]
+    /// constant:
     /// - `-32601`: Method not found
+    /// - `-32602`: Invalid params
     /// - `-32001`: No headset connected
     /// - `-32002`: Invalid license ID
     /// - `-32004`: Headset unavailable
     /// - `-32005`: Session already exists
+    /// - `-32007`: Session does not exist
     /// - `-32012`: Session must be activated
     /// - `-32014`: Invalid cortex token
     /// - `-32015`: Cortex token expired
     /// - `-32016`: Invalid stream
+    /// - `-32019`: Session limit reached
     /// - `-32021`: Invalid client credentials
     /// - `-32024`: License expired
+    /// - `-32027`: Invalid debit number
+    /// - `-32029`: Stream not available on the license
+    /// - `-32032`: No access rights
     /// - `-32033`: User not logged in
+    /// - `-32034`: EULA not accepted
+    /// - `-32045`: Profile loaded by another application
+    /// - `-32104`: Record not found
+    /// - `-32107`: Marker outside record
+    /// - `-32110`: Subject already exists
     /// - `-32142`: Unpublished/unapproved application
     /// - `-32152`: Headset not ready
     ///
     /// Legacy Cortex deployments may also return older codes such as
-    /// `-32102` and `-32122`.
+    /// `-32102` and `-32122`. Other codes, including the remaining JSON-RPC
+    /// errors (`-32700`, `-32600`, `-32603`), become
+    /// [`CortexError::ApiError`].
     ///
     /// # Examples
     ///
@@ -170,29 +253,98 @@ impl CortexError {
     /// assert!(matches!(err, CortexError::NoHeadsetFound));
     /// ```
     pub fn from_api_error(code: i32, message: impl Into<String>) -> Self {
-        let message = message.into();
+        let reason = message.into();
         match code {
-            -32601 => CortexError::MethodNotFound {
-                method: message.clone(),
+            ErrorCodes::METHOD_NOT_FOUND => CortexError::MethodNotFound { method: reason },
+            ErrorCodes::INVALID_PARAMS => CortexError::InvalidParams { reason },
+            ErrorCodes::NO_HEADSET_CONNECTED | ErrorCodes::HEADSET_UNAVAILABLE => {
+                CortexError::NoHeadsetFound
+            }
+            ErrorCodes::INVALID_LICENSE_ID
+            | ErrorCodes::LICENSE_EXPIRED
+            | ErrorCodes::INVALID_DEBIT => CortexError::LicenseError { reason },
+            ErrorCodes::SESSION_ALREADY_EXISTS => CortexError::SessionError { reason },
+            ErrorCodes::SESSION_NOT_FOUND => CortexError::SessionNotFound { reason },
+            ErrorCodes::SESSION_MUST_BE_ACTIVATED => CortexError::SessionNotActivated { reason },
+            ErrorCodes::SESSION_LIMIT_REACHED => CortexError::SessionLimitReached { reason },
+            ErrorCodes::INVALID_CORTEX_TOKEN | ErrorCodes::INVALID_CLIENT_CREDENTIALS => {
+                CortexError::AuthenticationFailed { reason }
+            }
+            ErrorCodes::TOKEN_EXPIRED => CortexError::TokenExpired,
+            ErrorCodes::INVALID_STREAM => CortexError::StreamError { reason },
+            ErrorCodes::STREAM_NOT_LICENSED => CortexError::StreamNotLicensed { reason },
+            ErrorCodes::NO_ACCESS_RIGHTS => CortexError::AccessDenied { reason },
+            ErrorCodes::USER_NOT_LOGGED_IN => CortexError::UserNotLoggedIn,
+            ErrorCodes::EULA_NOT_ACCEPTED => CortexError::EulaNotAccepted,
+            ErrorCodes::PROFILE_IN_USE => CortexError::ProfileInUse { reason },
+            ErrorCodes::RECORD_NOT_FOUND => CortexError::RecordNotFound { reason },
+            ErrorCodes::MARKER_OUTSIDE_RECORD => CortexError::MarkerOutsideRecord { reason },
+            ErrorCodes::SUBJECT_ALREADY_EXISTS => CortexError::SubjectAlreadyExists { reason },
+            ErrorCodes::UNPUBLISHED_APPLICATION | ErrorCodes::LEGACY_NOT_APPROVED => {
+                CortexError::NotApproved
+            }
+            ErrorCodes::HEADSET_NOT_READY => CortexError::HeadsetNotReady { reason },
+            ErrorCodes::LEGACY_CORTEX_STARTING => CortexError::CortexStarting,
+            _ => CortexError::ApiError {
+                code,
+                message: reason,
             },
-            -32001 | -32004 => CortexError::NoHeadsetFound,
-            -32002 | -32024 => CortexError::LicenseError { reason: message },
-            -32005 | -32012 => CortexError::SessionError { reason: message },
-            -32014 | -32021 => CortexError::AuthenticationFailed { reason: message },
-            -32015 => CortexError::TokenExpired,
-            -32016 => CortexError::StreamError { reason: message },
-            -32033 => CortexError::UserNotLoggedIn,
-            -32142 => CortexError::NotApproved,
-            -32152 => CortexError::HeadsetError { reason: message },
-            // Legacy/older documented mappings.
-            -32102 => CortexError::NotApproved,
-            -32122 => CortexError::CortexStarting,
-            _ => CortexError::ApiError { code, message },
         }
     }
 
+    /// The Cortex error code behind this error, if any.
+    ///
+    /// [`CortexError::ApiError`] reports its own code. Variants that only
+    /// come from a Cortex error report the code [`from_api_error`] maps to
+    /// them, or the first one listed there when several codes share a
+    /// variant. Variants the client also raises on its own, such as
+    /// [`CortexError::SessionError`], return `None`.
+    /// [`CortexError::RetriesExhausted`] reports its last error's code.
+    ///
+    /// [`from_api_error`]: CortexError::from_api_error
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use emotiv_cortex_v2::CortexError;
+    ///
+    /// let err = CortexError::from_api_error(-32104, "record not found");
+    /// assert_eq!(err.error_code(), Some(-32104));
+    /// assert_eq!(CortexError::NotConnected.error_code(), None);
+    /// ```
+    #[must_use]
+    pub fn error_code(&self) -> Option<i32> {
+        let code = match self {
+            CortexError::ApiError { code, .. } => *code,
+            CortexError::MethodNotFound { .. } => ErrorCodes::METHOD_NOT_FOUND,
+            CortexError::InvalidParams { .. } => ErrorCodes::INVALID_PARAMS,
+            CortexError::NoHeadsetFound => ErrorCodes::NO_HEADSET_CONNECTED,
+            CortexError::SessionNotFound { .. } => ErrorCodes::SESSION_NOT_FOUND,
+            CortexError::SessionNotActivated { .. } => ErrorCodes::SESSION_MUST_BE_ACTIVATED,
+            CortexError::SessionLimitReached { .. } => ErrorCodes::SESSION_LIMIT_REACHED,
+            CortexError::TokenExpired => ErrorCodes::TOKEN_EXPIRED,
+            CortexError::StreamNotLicensed { .. } => ErrorCodes::STREAM_NOT_LICENSED,
+            CortexError::UserNotLoggedIn => ErrorCodes::USER_NOT_LOGGED_IN,
+            CortexError::EulaNotAccepted => ErrorCodes::EULA_NOT_ACCEPTED,
+            CortexError::ProfileInUse { .. } => ErrorCodes::PROFILE_IN_USE,
+            CortexError::RecordNotFound { .. } => ErrorCodes::RECORD_NOT_FOUND,
+            CortexError::MarkerOutsideRecord { .. } => ErrorCodes::MARKER_OUTSIDE_RECORD,
+            CortexError::SubjectAlreadyExists { .. } => ErrorCodes::SUBJECT_ALREADY_EXISTS,
+            CortexError::NotApproved => ErrorCodes::UNPUBLISHED_APPLICATION,
+            CortexError::HeadsetNotReady { .. } => ErrorCodes::HEADSET_NOT_READY,
+            CortexError::CortexStarting => ErrorCodes::LEGACY_CORTEX_STARTING,
+            CortexError::RetriesExhausted { last_error, .. } => return last_error.error_code(),
+            _ => return None,
+        };
+        Some(code)
+    }
+
     /// Returns `true` if this error is transient and the operation can be retried.
     ///
+    /// Besides dropped connections and timeouts this covers Cortex codes
+    /// that clear up on their own: the service starting, the headset not
+    /// being ready yet, and internal errors.
+    ///
     /// # Examples
     ///
     /// ```
@@ -200,17 +352,50 @@ impl CortexError {
     ///
     /// assert!(CortexError::Timeout { seconds: 10 }.is_retryable());
     /// assert!(CortexError::CortexStarting.is_retryable());
+    /// assert!(CortexError::from_api_error(-32603, "internal error").is_retryable());
     /// assert!(!CortexError::NoHeadsetFound.is_retryable());
+    /// assert!(!CortexError::from_api_error(-32104, "record not found").is_retryable());
     /// ```
     #[must_use]
     pub fn is_retryable(&self) -> bool {
-        matches!(
-            self,
+        match self {
             CortexError::ConnectionLost { .. }
-                | CortexError::Timeout { .. }
-                | CortexError::CortexStarting
-                | CortexError::WebSocket(_)
-        )
+            | CortexError::Timeout { .. }
+            | CortexError::CortexStarting
+            | CortexError::HeadsetNotReady { .. }
+            | CortexError::WebSocket(_) => true,
+            CortexError::ApiError { code, .. } => *code == ErrorCodes::INTERNAL_ERROR,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the service rejected the request for being sent
+    /// too often.
+    ///
+    /// Cortex has no dedicated error code for throttling, so this matches
+    /// [`CortexError::ApiError`] messages mentioning a rate limit or too many
+    /// requests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use emotiv_cortex_v2::CortexError;
+    ///
+    /// let err = CortexError::from_api_error(-32999, "Too many requests, slow down");
+    /// assert!(err.is_rate_limited());
+    /// assert!(!CortexError::Timeout { seconds: 1 }.is_rate_limited());
+    /// ```
+    #[must_use]
+    pub fn is_rate_limited(&self) -> bool {
+        match self {
+            CortexError::ApiError { message, .. } => {
+                let message = message.to_ascii_lowercase();
+                message.contains("too many requests")
+                    || message.contains("rate limit")
+                    || message.contains("rate-limit")
+            }
+            _ => false,
+        }
     }
 
     /// Returns `true` if this error indicates the connection is dead
@@ -284,7 +469,7 @@ mod tests {
         ));
         assert!(matches!(
             CortexError::from_api_error(-32012, "session must be activated"),
-            CortexError::SessionError { .. }
+            CortexError::SessionNotActivated { .. }
         ));
         assert!(matches!(
             CortexError::from_api_error(-32014, "invalid token"),
@@ -316,7 +501,7 @@ mod tests {
         ));
         assert!(matches!(
             CortexError::from_api_error(-32152, "headset not ready"),
-            CortexError::HeadsetError { .. }
+            CortexError::HeadsetNotReady { .. }
         ));
         assert!(matches!(
             CortexError::from_api_error(-32601, "unknown"),
@@ -324,6 +509,91 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_from_api_error_extended_codes() {
+        assert!(matches!(
+            CortexError::from_api_error(-32602, "missing cortexToken"),
+            CortexError::InvalidParams { .. }
+        ));
+        assert!(matches!(
+            CortexError::from_api_error(-32007, "session does not exist"),
+            CortexError::SessionNotFound { .. }
+        ));
+        assert!(matches!(
+            CortexError::from_api_error(-32019, "session limit reached"),
+            CortexError::SessionLimitReached { .. }
+        ));
+        assert!(matches!(
+            CortexError::from_api_error(-32027, "invalid debit"),
+            CortexError::LicenseError { .. }
+        ));
+        assert!(matches!(
+            CortexError::from_api_error(-32029, "eeg not in license"),
+            CortexError::StreamNotLicensed { .. }
+        ));
+        assert!(matches!(
+            CortexError::from_api_error(-32032, "no access rights"),
+            CortexError::AccessDenied { .. }
+        ));
+        assert!(matches!(
+            CortexError::from_api_error(-32034, "eula"),
+            CortexError::EulaNotAccepted
+        ));
+        assert!(matches!(
+            CortexError::from_api_error(-32045, "profile in use"),
+            CortexError::ProfileInUse { .. }
+        ));
+        assert!(matches!(
+            CortexError::from_api_error(-32104, "record not found"),
+            CortexError::RecordNotFound { .. }
+        ));
+        assert!(matches!(
+            CortexError::from_api_error(-32107, "marker outside record"),
+            CortexError::MarkerOutsideRecord { .. }
+        ));
+        assert!(matches!(
+            CortexError::from_api_error(-32110, "subject exists"),
+            CortexError::SubjectAlreadyExists { .. }
+        ));
+        assert!(matches!(
+            CortexError::from_api_error(-32603, "internal"),
+            CortexError::ApiError { code: -32603, .. }
+        ));
+    }
+
+    #[test]
+    fn test_error_code_round_trips_dedicated_variants() {
+        for code in [
+            -32601, -32602, -32001, -32007, -32012, -32015, -32019, -32029, -32033, -32034, -32045,
+            -32104, -32107, -32110, -32142, -32152, -32122, -32603, -12345,
+        ] {
+            let err = CortexError::from_api_error(code, "x");
+            assert_eq!(err.error_code(), Some(code), "{err:?}");
+        }
+        // Shared variants report their first code; local errors have none.
+        assert_eq!(
+            CortexError::from_api_error(-32004, "x").error_code(),
+            Some(-32001)
+        );
+        assert_eq!(CortexError::from_api_error(-32005, "x").error_code(), None);
+        assert_eq!(CortexError::Timeout { seconds: 1 }.error_code(), None);
+        let exhausted = CortexError::RetriesExhausted {
+            attempts: 3,
+            last_error: Box::new(CortexError::from_api_error(-32104, "x")),
+        };
+        assert_eq!(exhausted.error_code(), Some(-32104));
+    }
+
+    #[test]
+    fn test_is_retryable_by_error_code() {
+        assert!(CortexError::from_api_error(-32603, "internal").is_retryable());
+        assert!(CortexError::from_api_error(-32152, "headset not ready").is_retryable());
+        assert!(CortexError::from_api_error(-32122, "starting").is_retryable());
+        assert!(!CortexError::from_api_error(-32019, "session limit").is_retryable());
+        assert!(!CortexError::from_api_error(-32107, "outside").is_retryable());
+        assert!(!CortexError::from_api_error(-32700, "parse").is_retryable());
+    }
+
     #[test]
     fn test_from_api_error_legacy_codes() {
         assert!(matches!(
@@ -0,0 +1,181 @@
+//! # Fan-out
+//!
+//! Relay core shared by the [`supervisor`](crate::supervisor) and the
+//! [`ipc`](crate::ipc) server: relaying a stream's events into a bounded
+//! broadcast of encoded lines, accepting socket connections, and reading
+//! the broadcast with lagging receivers counted as drops.
+
+use std::future::Future;
+use std::os::unix::fs::FileTypeExt;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use serde_json::Value;
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio::task::JoinHandle;
+
+use crate::error::{CortexError, CortexResult};
+
+/// Default number of lines buffered per consumer.
+pub(crate) const DEFAULT_CONSUMER_BUFFER: usize = 1024;
+
+/// Counters shared by a fan-out's relays and consumers.
+#[derive(Default)]
+pub(crate) struct Counters {
+    /// Consumers currently attached.
+    pub(crate) consumers: AtomicUsize,
+    /// Consumers attached since start.
+    pub(crate) connections: AtomicU64,
+    /// Lines broadcast by the relays.
+    pub(crate) relayed: AtomicU64,
+    /// Lines skipped by consumers that fell behind.
+    pub(crate) dropped: AtomicU64,
+}
+
+/// Bind `path`, replacing a socket file nobody is listening on.
+///
+/// Anything at `path` that is not a socket is left alone.
+pub(crate) async fn bind(path: &Path) -> CortexResult<UnixListener> {
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            return Err(CortexError::ConfigError {
+                reason: format!("{} exists and is not a socket", path.display()),
+            });
+        }
+        if UnixStream::connect(path).await.is_ok() {
+            return Err(CortexError::ConfigError {
+                reason: format!("{} is already served by another process", path.display()),
+            });
+        }
+        std::fs::remove_file(path)?;
+    }
+    Ok(UnixListener::bind(path)?)
+}
+
+/// Encode each event of `stream` with `encode` and broadcast it on
+/// `lines`. Events `encode` rejects are skipped. On `shutdown`, the events
+/// already queued are relayed before returning.
+pub(crate) async fn relay<E>(
+    stream: String,
+    mut rx: mpsc::Receiver<Value>,
+    lines: broadcast::Sender<Arc<str>>,
+    counters: Arc<Counters>,
+    mut shutdown: watch::Receiver<bool>,
+    encode: E,
+) where
+    E: Fn(&str, Value) -> Option<String>,
+{
+    let send = |event: Value| {
+        if let Some(line) = encode(&stream, event) {
+            counters.relayed.fetch_add(1, Ordering::Relaxed);
+            // No receivers just means no consumer is attached.
+            let _ = lines.send(Arc::from(line + "\n"));
+        }
+    };
+    loop {
+        tokio::select! {
+            item = rx.recv() => {
+                let Some(event) = item else { break };
+                send(event);
+            }
+            _ = shutdown.changed() => {
+                while let Ok(event) = rx.try_recv() {
+                    send(event);
+                }
+                break;
+            }
+        }
+    }
+}
+
+/// Accept connections on `listener` and spawn `serve` for each until
+/// `shutdown`, then wait for the served connections to finish.
+pub(crate) async fn accept<S, F>(
+    listener: UnixListener,
+    counters: Arc<Counters>,
+    mut shutdown: watch::Receiver<bool>,
+    mut serve: S,
+) where
+    S: FnMut(UnixStream, watch::Receiver<bool>) -> F,
+    F: Future<Output = ()> + Send + 'static,
+{
+    let mut connections = Vec::new();
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((socket, _)) => {
+                    counters.connections.fetch_add(1, Ordering::Relaxed);
+                    connections.retain(|task: &JoinHandle<()>| !task.is_finished());
+                    connections.push(tokio::spawn(serve(socket, shutdown.clone())));
+                }
+                Err(e) => tracing::warn!(error = %e, "Accept failed"),
+            },
+            _ = shutdown.changed() => break,
+        }
+    }
+    for task in connections {
+        let _ = task.await;
+    }
+}
+
+/// Next line from `lines`, counting lines skipped by lagging as dropped.
+/// `None` once the relay is gone.
+pub(crate) async fn recv(
+    lines: &mut broadcast::Receiver<Arc<str>>,
+    counters: &Counters,
+) -> Option<Arc<str>> {
+    loop {
+        match lines.recv().await {
+            Ok(line) => return Some(line),
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                counters.dropped.fetch_add(skipped, Ordering::Relaxed);
+            }
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
+
+/// Like [`recv`], without waiting. `None` once nothing is queued.
+pub(crate) fn try_recv(
+    lines: &mut broadcast::Receiver<Arc<str>>,
+    counters: &Counters,
+) -> Option<Arc<str>> {
+    loop {
+        match lines.try_recv() {
+            Ok(line) => return Some(line),
+            Err(broadcast::error::TryRecvError::Lagged(skipped)) => {
+                counters.dropped.fetch_add(skipped, Ordering::Relaxed);
+            }
+            Err(_) => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bind_refuses_to_replace_regular_file() {
+        let path = std::env::temp_dir().join(format!("emotiv-fanout-{}.txt", std::process::id()));
+        std::fs::write(&path, "keep me").unwrap();
+
+        let err = bind(&path).await.unwrap_err();
+        assert!(matches!(err, CortexError::ConfigError { .. }), "{err:?}");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "keep me");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bind_replaces_stale_socket() {
+        let path = std::env::temp_dir().join(format!("emotiv-fanout-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        drop(UnixListener::bind(&path).unwrap());
+
+        let listener = bind(&path).await.unwrap();
+        drop(listener);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,317 @@
+//! # Headset Fit Check
+//!
+//! Before a recording starts, an experimenter wants a yes/no answer to
+//! "is the headset seated well enough?". [`FitCheck`] collects contact
+//! quality from the `dev` or `eq` stream for a few seconds and produces a
+//! [`FitReport`]: per sensor, the median quality, how often the sensor
+//! dropped out, and how steady the reading was, each checked against
+//! [`FitCheckConfig`].
+//!
+//! ```no_run
+//! use emotiv_cortex_v2::{CortexClient, HeadsetModel};
+//! use emotiv_cortex_v2::fitcheck::{self, FitCheckConfig};
+//!
+//! # async fn demo(client: &CortexClient, token: &str, session_id: &str) -> emotiv_cortex_v2::CortexResult<()> {
+//! let report = fitcheck::run(
+//!     client, token, session_id, &HeadsetModel::EpocX, &FitCheckConfig::default(),
+//! ).await?;
+//! if !report.passed {
+//!     for sensor in report.failing_sensors() {
+//!         eprintln!("re-seat {}: median {:.2}", sensor.name, sensor.median_quality);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! For a stream that is already subscribed, feed samples to
+//! [`FitCheck::observe`] or hand the stream to [`check_stream`].
+
+use std::time::{Duration, Instant};
+
+use futures_core::Stream;
+use futures_util::StreamExt;
+use serde::Serialize;
+
+use crate::client::CortexClient;
+use crate::error::CortexResult;
+use crate::headset::HeadsetModel;
+use crate::protocol::constants::Streams;
+use crate::streams::{self, StreamSample};
+use crate::util::usize_to_f32;
+
+/// Default time quality is collected for.
+pub const DEFAULT_FIT_DURATION: Duration = Duration::from_secs(10);
+
+/// Thresholds a [`FitReport`] is judged against. Quality values are the
+/// normalized 0.0–1.0 values of the `dev` and `eq` streams (Cortex's 0–4
+/// scale divided by four).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FitCheckConfig {
+    /// How long [`run`] and [`check_stream`] collect quality for.
+    pub duration: Duration,
+    /// Lowest median quality a sensor may have.
+    pub min_median_quality: f32,
+    /// A reading at or below this counts as a dropout.
+    pub dropout_quality: f32,
+    /// Highest share of readings that may be dropouts.
+    pub max_dropout_ratio: f32,
+    /// Lowest share of readings that must lie within one Cortex quality
+    /// step (0.25) of the sensor's median.
+    pub min_stability: f32,
+}
+
+impl Default for FitCheckConfig {
+    fn default() -> Self {
+        Self {
+            duration: DEFAULT_FIT_DURATION,
+            min_median_quality: 0.75,
+            dropout_quality: 0.25,
+            max_dropout_ratio: 0.1,
+            min_stability: 0.8,
+        }
+    }
+}
+
+/// Fit of one sensor over the check.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SensorFit {
+    /// Channel index.
+    pub channel: usize,
+    /// Sensor name (e.g. `"AF3"`).
+    pub name: String,
+    /// Median quality 0.0–1.0.
+    pub median_quality: f32,
+    /// Share of readings at or below [`FitCheckConfig::dropout_quality`].
+    pub dropout_ratio: f32,
+    /// Share of readings within one quality step of the median.
+    pub stability: f32,
+    /// Whether the sensor met every threshold.
+    pub passed: bool,
+}
+
+/// Outcome of a fit check.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FitReport {
+    /// Quality readings collected.
+    pub samples: usize,
+    /// Per-sensor results, in stream order.
+    pub sensors: Vec<SensorFit>,
+    /// Whether readings were collected and every sensor passed.
+    pub passed: bool,
+}
+
+impl FitReport {
+    /// Sensors that did not pass.
+    pub fn failing_sensors(&self) -> impl Iterator<Item = &SensorFit> {
+        self.sensors.iter().filter(|sensor| !sensor.passed)
+    }
+}
+
+/// Collects contact quality and judges it. See the
+/// [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct FitCheck {
+    config: FitCheckConfig,
+    names: Vec<String>,
+    readings: Vec<Vec<f32>>,
+    samples: usize,
+}
+
+impl FitCheck {
+    /// Check the sensors named `channel_names`, in stream order.
+    #[must_use]
+    pub fn new<S: AsRef<str>>(channel_names: &[S], config: FitCheckConfig) -> Self {
+        Self {
+            config,
+            names: channel_names
+                .iter()
+                .map(|name| name.as_ref().to_string())
+                .collect(),
+            readings: vec![Vec::new(); channel_names.len()],
+            samples: 0,
+        }
+    }
+
+    /// Record a `dev` or `eq` sample.
+    pub fn observe(&mut self, sample: &impl StreamSample) {
+        if let Some(values) = sample.as_f32_slice() {
+            self.push(values);
+        }
+    }
+
+    /// Record per-sensor quality values (0.0–1.0). Extra values are
+    /// ignored.
+    pub fn push(&mut self, values: &[f32]) {
+        self.samples += 1;
+        for (readings, value) in self.readings.iter_mut().zip(values) {
+            readings.push(*value);
+        }
+    }
+
+    /// Judge the readings so far.
+    #[must_use]
+    pub fn report(&self) -> FitReport {
+        let sensors: Vec<SensorFit> = self
+            .names
+            .iter()
+            .zip(&self.readings)
+            .enumerate()
+            .map(|(channel, (name, readings))| self.sensor_fit(channel, name, readings))
+            .collect();
+        FitReport {
+            samples: self.samples,
+            passed: self.samples > 0 && sensors.iter().all(|sensor| sensor.passed),
+            sensors,
+        }
+    }
+
+    fn sensor_fit(&self, channel: usize, name: &str, readings: &[f32]) -> SensorFit {
+        let config = &self.config;
+        let median_quality = median(readings).unwrap_or(0.0);
+        let share = |pred: &dyn Fn(f32) -> bool| {
+            if readings.is_empty() {
+                return 0.0;
+            }
+            let count = readings.iter().filter(|q| pred(**q)).count();
+            usize_to_f32(count) / usize_to_f32(readings.len())
+        };
+        let dropout_ratio = if readings.is_empty() {
+            1.0
+        } else {
+            share(&|q| q <= config.dropout_quality)
+        };
+        let stability = share(&|q| (q - median_quality).abs() <= QUALITY_STEP + f32::EPSILON);
+        SensorFit {
+            channel,
+            name: name.to_string(),
+            median_quality,
+            dropout_ratio,
+            stability,
+            passed: !readings.is_empty()
+                && median_quality >= config.min_median_quality
+                && dropout_ratio <= config.max_dropout_ratio
+                && stability >= config.min_stability,
+        }
+    }
+}
+
+/// One step of Cortex's 0–4 contact quality scale, normalized.
+const QUALITY_STEP: f32 = 0.25;
+
+fn median(values: &[f32]) -> Option<f32> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f32::total_cmp);
+    let mid = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        n if n % 2 == 0 => Some(f32::midpoint(sorted[mid - 1], sorted[mid])),
+        _ => Some(sorted[mid]),
+    }
+}
+
+/// Collect quality samples from `stream` for
+/// [`FitCheckConfig::duration`] (or until it ends) and judge them.
+pub async fn check_stream<S, T>(
+    stream: S,
+    channel_names: &[impl AsRef<str>],
+    config: &FitCheckConfig,
+) -> FitReport
+where
+    S: Stream<Item = T> + Unpin,
+    T: StreamSample,
+{
+    let mut check = FitCheck::new(channel_names, *config);
+    let mut stream = stream;
+    let deadline = Instant::now() + config.duration;
+    while let Ok(Some(sample)) = tokio::time::timeout(
+        deadline.saturating_duration_since(Instant::now()),
+        stream.next(),
+    )
+    .await
+    {
+        check.observe(&sample);
+    }
+    let report = check.report();
+    tracing::info!(
+        samples = report.samples,
+        passed = report.passed,
+        failing = report.failing_sensors().count(),
+        "Fit check finished"
+    );
+    report
+}
+
+/// Subscribe to the `dev` stream of `session_id`, run a fit check for
+/// `model`'s sensors, and unsubscribe again.
+///
+/// Use [`check_stream`] instead if the session is already subscribed to
+/// `dev`: subscribing again here would take over its channel.
+///
+/// # Errors
+/// Returns any error produced by the `subscribe` or `unsubscribe` calls.
+pub async fn run(
+    client: &CortexClient,
+    cortex_token: &str,
+    session_id: &str,
+    model: &HeadsetModel,
+    config: &FitCheckConfig,
+) -> CortexResult<FitReport> {
+    let dev =
+        streams::subscribe_dev(client, cortex_token, session_id, model.num_channels()).await?;
+    let report = check_stream(dev, model.channel_names(), config).await;
+    streams::unsubscribe(client, cortex_token, session_id, &[Streams::DEV]).await?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_judges_each_sensor() {
+        let mut check = FitCheck::new(&["AF3", "T7", "Pz"], FitCheckConfig::default());
+        assert!(!check.report().passed);
+
+        for i in 0..10 {
+            // AF3 solid, T7 drops out now and then, Pz jumps around.
+            let t7 = if i % 3 == 0 { 0.0 } else { 1.0 };
+            let pz = if i % 5 < 2 { 0.5 } else { 1.0 };
+            check.push(&[1.0, t7, pz]);
+        }
+        let report = check.report();
+        assert_eq!(report.samples, 10);
+        assert!(!report.passed);
+
+        let af3 = &report.sensors[0];
+        assert!(af3.passed);
+        assert!((af3.median_quality - 1.0).abs() < f32::EPSILON);
+        assert!(af3.dropout_ratio.abs() < f32::EPSILON);
+        assert!((af3.stability - 1.0).abs() < f32::EPSILON);
+
+        let t7 = &report.sensors[1];
+        assert!(!t7.passed);
+        assert!((t7.dropout_ratio - 0.4).abs() < 1e-6);
+
+        let pz = &report.sensors[2];
+        assert!(!pz.passed);
+        assert!((pz.median_quality - 1.0).abs() < f32::EPSILON);
+        assert!(pz.dropout_ratio.abs() < f32::EPSILON);
+        assert!((pz.stability - 0.6).abs() < 1e-6);
+
+        let failing: Vec<&str> = report.failing_sensors().map(|s| s.name.as_str()).collect();
+        assert_eq!(failing, ["T7", "Pz"]);
+    }
+
+    #[test]
+    fn test_stability_counts_readings_near_median() {
+        let mut check = FitCheck::new(&["AF3"], FitCheckConfig::default());
+        for value in [1.0, 1.0, 1.0, 1.0, 0.25] {
+            check.push(&[value]);
+        }
+        let sensor = &check.report().sensors[0];
+        assert!((sensor.stability - 0.8).abs() < 1e-6);
+        assert!((sensor.dropout_ratio - 0.2).abs() < 1e-6);
+        assert!(!sensor.passed);
+    }
+}
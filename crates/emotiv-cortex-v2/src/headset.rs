@@ -51,6 +51,14 @@ pub enum HeadsetModel {
     /// Default configuration uses the same 14-channel EPOC+ layout.
     EpocFlex,
 
+    /// Cortex virtual (simulated) headset with a configurable number of
+    /// EEG channels at 128 Hz. Channel names follow the EPOC layout, then
+    /// further 10-20 positions, up to 32 channels.
+    Virtual {
+        /// Number of simulated EEG channels.
+        channel_count: usize,
+    },
+
     /// Unknown or unrecognized Emotiv headset.
     Unknown(String),
 }
@@ -89,6 +97,14 @@ const EPOC_CHANNELS: &[&str] = &[
     "AF3", "F7", "F3", "FC5", "T7", "P7", "O1", "O2", "P8", "T8", "FC6", "F4", "F8", "AF4",
 ];
 
+// ─── Virtual headset layout (EPOC layout, then extra 10-20 sites) ───────
+
+const VIRTUAL_CHANNELS: &[&str] = &[
+    "AF3", "F7", "F3", "FC5", "T7", "P7", "O1", "O2", "P8", "T8", "FC6", "F4", "F8", "AF4", "Fp1",
+    "Fp2", "Fz", "FC1", "FC2", "C3", "Cz", "C4", "CP5", "CP1", "CP2", "CP6", "P3", "Pz", "P4",
+    "PO3", "PO4", "Oz",
+];
+
 // ─── HeadsetModel impl ─────────────────────────────────────────────────
 
 impl HeadsetModel {
@@ -108,7 +124,11 @@ impl HeadsetModel {
     pub fn from_headset_id(headset_id: &str) -> Self {
         let id_upper = headset_id.to_uppercase();
 
-        if id_upper.starts_with("INSIGHT") {
+        if id_upper.starts_with("VIRTUAL") {
+            HeadsetModel::Virtual {
+                channel_count: EPOC_CHANNELS.len(),
+            }
+        } else if id_upper.starts_with("INSIGHT") {
             HeadsetModel::Insight
         } else if id_upper.starts_with("EPOCX") || id_upper.starts_with("EPOC-X") {
             HeadsetModel::EpocX
@@ -126,8 +146,15 @@ impl HeadsetModel {
     }
 
     /// Infer the headset model from a [`HeadsetInfo`] response.
+    ///
+    /// Headsets flagged `isVirtual` map to [`HeadsetModel::Virtual`] with
+    /// one channel per reported sensor.
     #[must_use]
     pub fn from_headset_info(info: &HeadsetInfo) -> Self {
+        if info.is_virtual == Some(true) {
+            let channel_count = info.sensors.as_ref().map_or(EPOC_CHANNELS.len(), Vec::len);
+            return HeadsetModel::Virtual { channel_count };
+        }
         Self::from_headset_id(&info.id)
     }
 
@@ -144,11 +171,8 @@ impl HeadsetModel {
     /// ```
     #[must_use]
     pub fn channel_config(&self) -> HeadsetChannelConfig {
-        let (names, rate): (&[&str], f64) = match self {
-            HeadsetModel::Insight | HeadsetModel::Unknown(_) => (INSIGHT_CHANNELS, 128.0),
-            HeadsetModel::EpocPlus | HeadsetModel::EpocFlex => (EPOC_CHANNELS, 128.0),
-            HeadsetModel::EpocX => (EPOC_CHANNELS, 256.0),
-        };
+        let names = self.channel_names();
+        let rate = self.sampling_rate_hz();
 
         HeadsetChannelConfig {
             channels: names
@@ -175,12 +199,7 @@ impl HeadsetModel {
     /// ```
     #[must_use]
     pub fn num_channels(&self) -> usize {
-        match self {
-            HeadsetModel::Insight | HeadsetModel::Unknown(_) => INSIGHT_CHANNELS.len(),
-            HeadsetModel::EpocPlus | HeadsetModel::EpocX | HeadsetModel::EpocFlex => {
-                EPOC_CHANNELS.len()
-            }
-        }
+        self.channel_names().len()
     }
 
     /// Sampling rate in Hz for this headset model.
@@ -207,6 +226,9 @@ impl HeadsetModel {
         match self {
             HeadsetModel::Insight | HeadsetModel::Unknown(_) => INSIGHT_CHANNELS,
             HeadsetModel::EpocPlus | HeadsetModel::EpocX | HeadsetModel::EpocFlex => EPOC_CHANNELS,
+            HeadsetModel::Virtual { channel_count } => {
+                &VIRTUAL_CHANNELS[..(*channel_count).min(VIRTUAL_CHANNELS.len())]
+            }
         }
     }
 }
@@ -218,12 +240,16 @@ impl std::fmt::Display for HeadsetModel {
             HeadsetModel::EpocPlus => write!(f, "Emotiv EPOC+"),
             HeadsetModel::EpocX => write!(f, "Emotiv EPOC X"),
             HeadsetModel::EpocFlex => write!(f, "Emotiv EPOC Flex"),
+            HeadsetModel::Virtual { channel_count } => {
+                write!(f, "Virtual headset ({channel_count} channels)")
+            }
             HeadsetModel::Unknown(id) => write!(f, "Unknown Emotiv ({id})"),
         }
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::float_cmp)]
 mod tests {
     use super::*;
 
@@ -390,6 +416,30 @@ mod tests {
         assert_eq!(model.sampling_rate_hz(), 128.0);
     }
 
+    #[test]
+    fn test_virtual_channels() {
+        let model = HeadsetModel::Virtual { channel_count: 8 };
+        assert_eq!(model.num_channels(), 8);
+        assert_eq!(model.channel_names()[..2], ["AF3", "F7"]);
+        assert_eq!(model.sampling_rate_hz(), 128.0);
+
+        let all = HeadsetModel::Virtual { channel_count: 64 };
+        assert_eq!(all.num_channels(), 32);
+        assert_eq!(all.channel_config().channels[31].name, "Oz");
+
+        let info: HeadsetInfo = serde_json::from_value(serde_json::json!({
+            "id": "EPOCX-VIRTUAL01",
+            "status": "connected",
+            "isVirtual": true,
+            "sensors": ["AF3", "F7", "F3", "FC5", "T7", "P7"]
+        }))
+        .unwrap();
+        assert_eq!(
+            HeadsetModel::from_headset_info(&info),
+            HeadsetModel::Virtual { channel_count: 6 }
+        );
+    }
+
     // ─── Channel names ──────────────────────────────────────────────────
 
     #[test]
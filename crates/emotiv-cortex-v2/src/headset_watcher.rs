@@ -0,0 +1,498 @@
+//! # Headset Watcher
+//!
+//! [`HeadsetWatcher`] polls `queryHeadsets` in the background and turns
+//! what it sees into typed [`HeadsetEvent`]s on a broadcast channel, so
+//! applications do not each reimplement headset polling. Cortex headset
+//! warnings (connected, disconnected, connect failed) trigger an extra
+//! poll right away.
+//!
+//! Each headset moves between `discovered`, `connecting`, `connected`
+//! and gone ([`HeadsetEvent::Lost`]). A change is only reported once it
+//! has been seen for [`HeadsetWatcherConfig::debounce`], so a headset
+//! flickering between polls does not flood subscribers.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use emotiv_cortex_v2::{CortexConfig, ResilientClient};
+//! use emotiv_cortex_v2::headset_watcher::{HeadsetEvent, HeadsetWatcher, HeadsetWatcherConfig};
+//!
+//! # async fn demo() -> emotiv_cortex_v2::CortexResult<()> {
+//! let client = Arc::new(ResilientClient::connect(CortexConfig::discover(None)?).await?);
+//! let watcher = HeadsetWatcher::spawn(client, HeadsetWatcherConfig::default());
+//! let mut events = watcher.subscribe();
+//! while let Ok(event) = events.recv().await {
+//!     if let HeadsetEvent::BatteryLow { headset_id, battery_percent } = event {
+//!         eprintln!("{headset_id}: battery at {battery_percent}%");
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use crate::protocol::headset::{HeadsetInfo, QueryHeadsetsOptions};
+use crate::protocol::warnings::WarningEvent;
+use crate::reconnect::ResilientClient;
+
+/// Default interval between `queryHeadsets` polls.
+pub const DEFAULT_WATCH_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Default time a state change must persist before it is reported.
+pub const DEFAULT_WATCH_DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// Default battery percentage at or below which
+/// [`HeadsetEvent::BatteryLow`] is emitted.
+pub const DEFAULT_BATTERY_LOW_PERCENT: u32 = 15;
+
+/// Capacity of the event channel.
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+
+/// Connection state Cortex reports for a headset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeadsetState {
+    /// Found by scanning, not connected.
+    Discovered,
+    /// Connection in progress.
+    Connecting,
+    /// Connected and usable for sessions.
+    Connected,
+}
+
+impl HeadsetState {
+    /// Parse a `queryHeadsets` `status` value. Unknown values are treated
+    /// as `Discovered`.
+    #[must_use]
+    pub fn from_status(status: &str) -> Self {
+        match status {
+            "connected" => Self::Connected,
+            "connecting" => Self::Connecting,
+            _ => Self::Discovered,
+        }
+    }
+}
+
+/// A headset transition seen by a [`HeadsetWatcher`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HeadsetEvent {
+    /// A headset appeared, not (yet) connected.
+    Discovered {
+        /// The headset as reported.
+        headset: HeadsetInfo,
+    },
+    /// A headset started connecting.
+    Connecting {
+        /// The headset as reported.
+        headset: HeadsetInfo,
+    },
+    /// A headset is connected.
+    Connected {
+        /// The headset as reported.
+        headset: HeadsetInfo,
+    },
+    /// A headset disappeared from `queryHeadsets`.
+    Lost {
+        /// Headset that disappeared.
+        headset_id: String,
+        /// Last state it was reported in.
+        last_state: HeadsetState,
+    },
+    /// A headset's battery dropped to
+    /// [`HeadsetWatcherConfig::battery_low_percent`] or below. Reported
+    /// again only after it recovers above the threshold.
+    BatteryLow {
+        /// Headset with a low battery.
+        headset_id: String,
+        /// Battery percentage reported.
+        battery_percent: u32,
+    },
+    /// Cortex lists `firmware` among the headset's `dfuTypes`. Reported
+    /// once per headset.
+    FirmwareUpdateAvailable {
+        /// Headset with an update available.
+        headset_id: String,
+        /// Current firmware, as displayed by Cortex.
+        firmware: Option<String>,
+    },
+}
+
+impl HeadsetEvent {
+    /// Headset the event concerns.
+    #[must_use]
+    pub fn headset_id(&self) -> &str {
+        match self {
+            Self::Discovered { headset }
+            | Self::Connecting { headset }
+            | Self::Connected { headset } => &headset.id,
+            Self::Lost { headset_id, .. }
+            | Self::BatteryLow { headset_id, .. }
+            | Self::FirmwareUpdateAvailable { headset_id, .. } => headset_id,
+        }
+    }
+}
+
+/// Tuning for [`HeadsetWatcher`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeadsetWatcherConfig {
+    /// Interval between `queryHeadsets` polls.
+    pub poll_interval: Duration,
+    /// Time a state change must persist before it is reported. Zero
+    /// reports every change on the poll that sees it.
+    pub debounce: Duration,
+    /// Battery percentage at or below which
+    /// [`HeadsetEvent::BatteryLow`] is emitted.
+    pub battery_low_percent: u32,
+}
+
+impl Default for HeadsetWatcherConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: DEFAULT_WATCH_POLL_INTERVAL,
+            debounce: DEFAULT_WATCH_DEBOUNCE,
+            battery_low_percent: DEFAULT_BATTERY_LOW_PERCENT,
+        }
+    }
+}
+
+/// Background headset poller. See the [module documentation](self).
+///
+/// Polling stops when the watcher is dropped.
+pub struct HeadsetWatcher {
+    events: broadcast::Sender<HeadsetEvent>,
+    shutdown: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+impl HeadsetWatcher {
+    /// Start polling `client`.
+    #[must_use]
+    pub fn spawn(client: Arc<ResilientClient>, config: HeadsetWatcherConfig) -> Self {
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        let task = tokio::spawn(watch_headsets(client, config, events.clone(), shutdown_rx));
+        Self {
+            events,
+            shutdown,
+            task,
+        }
+    }
+
+    /// Receive events from now on.
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<HeadsetEvent> {
+        self.events.subscribe()
+    }
+
+    /// Stop polling and wait for the task to finish.
+    pub async fn stop(mut self) {
+        let _ = self.shutdown.send(true);
+        let _ = (&mut self.task).await;
+    }
+}
+
+impl Drop for HeadsetWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn watch_headsets(
+    client: Arc<ResilientClient>,
+    config: HeadsetWatcherConfig,
+    events: broadcast::Sender<HeadsetEvent>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let mut warnings = client.warning_receiver();
+    let mut tracker = Tracker::new(config);
+    let mut poll = tokio::time::interval(config.poll_interval);
+    poll.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = poll.tick() => {}
+            warning = warnings.recv() => match warning {
+                Ok(
+                    WarningEvent::HeadsetConnected { .. }
+                    | WarningEvent::HeadsetDisconnected { .. }
+                    | WarningEvent::HeadsetConnectFailed { .. },
+                )
+                | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Closed) => {
+                    warnings = client.warning_receiver();
+                    continue;
+                }
+            },
+            _ = shutdown.changed() => break,
+        }
+
+        match client.query_headsets(QueryHeadsetsOptions::default()).await {
+            Ok(headsets) => {
+                for event in tracker.observe(&headsets, Instant::now()) {
+                    tracing::debug!(?event, "Headset event");
+                    let _ = events.send(event);
+                }
+            }
+            Err(e) => tracing::debug!(error = %e, "Headset watcher poll failed"),
+        }
+    }
+}
+
+// ─── State machine ───────────────────────────────────────────────────────
+
+/// What the watcher knows about one headset.
+#[derive(Debug, Default)]
+struct Tracked {
+    /// State last reported; `None` before the first report and after
+    /// [`HeadsetEvent::Lost`].
+    reported: Option<HeadsetState>,
+    /// A different state seen since, and when it was first seen.
+    pending: Option<(Option<HeadsetState>, Instant)>,
+    battery_low: bool,
+    firmware_reported: bool,
+}
+
+/// Debounced per-headset state machine.
+struct Tracker {
+    config: HeadsetWatcherConfig,
+    headsets: HashMap<String, Tracked>,
+}
+
+impl Tracker {
+    fn new(config: HeadsetWatcherConfig) -> Self {
+        Self {
+            config,
+            headsets: HashMap::new(),
+        }
+    }
+
+    /// Events for one `queryHeadsets` result seen at `now`.
+    fn observe(&mut self, headsets: &[HeadsetInfo], now: Instant) -> Vec<HeadsetEvent> {
+        let mut events = Vec::new();
+        for headset in headsets {
+            let state = HeadsetState::from_status(&headset.status);
+            let tracked = self.headsets.entry(headset.id.clone()).or_default();
+            if let Some(committed) = commit(tracked, Some(state), now, self.config.debounce) {
+                tracked.reported = committed;
+                events.push(match state {
+                    HeadsetState::Discovered => HeadsetEvent::Discovered {
+                        headset: headset.clone(),
+                    },
+                    HeadsetState::Connecting => HeadsetEvent::Connecting {
+                        headset: headset.clone(),
+                    },
+                    HeadsetState::Connected => HeadsetEvent::Connected {
+                        headset: headset.clone(),
+                    },
+                });
+            }
+            extras(
+                tracked,
+                headset,
+                self.config.battery_low_percent,
+                &mut events,
+            );
+        }
+
+        let gone: Vec<String> = self
+            .headsets
+            .keys()
+            .filter(|id| !headsets.iter().any(|h| h.id == **id))
+            .cloned()
+            .collect();
+        for id in gone {
+            let Some(tracked) = self.headsets.get_mut(&id) else {
+                continue;
+            };
+            let Some(last_state) = tracked.reported else {
+                // Never reported, so nothing to retract.
+                self.headsets.remove(&id);
+                continue;
+            };
+            if commit(tracked, None, now, self.config.debounce).is_some() {
+                self.headsets.remove(&id);
+                events.push(HeadsetEvent::Lost {
+                    headset_id: id,
+                    last_state,
+                });
+            }
+        }
+        events
+    }
+}
+
+/// Whether `observed` should now be reported for `tracked`: returns the
+/// state to commit once it has persisted for `debounce`.
+#[allow(clippy::option_option)]
+fn commit(
+    tracked: &mut Tracked,
+    observed: Option<HeadsetState>,
+    now: Instant,
+    debounce: Duration,
+) -> Option<Option<HeadsetState>> {
+    if tracked.reported == observed {
+        tracked.pending = None;
+        return None;
+    }
+    let since = match tracked.pending {
+        Some((pending, since)) if pending == observed => since,
+        _ => {
+            tracked.pending = Some((observed, now));
+            now
+        }
+    };
+    if now.duration_since(since) >= debounce {
+        tracked.pending = None;
+        Some(observed)
+    } else {
+        None
+    }
+}
+
+/// Battery and firmware events, which are not debounced.
+fn extras(
+    tracked: &mut Tracked,
+    headset: &HeadsetInfo,
+    battery_low_percent: u32,
+    events: &mut Vec<HeadsetEvent>,
+) {
+    if let Some(battery_percent) = headset.battery_percent {
+        let low = battery_percent <= battery_low_percent;
+        if low && !tracked.battery_low {
+            events.push(HeadsetEvent::BatteryLow {
+                headset_id: headset.id.clone(),
+                battery_percent,
+            });
+        }
+        tracked.battery_low = low;
+    }
+    let update = headset
+        .dfu_types
+        .as_ref()
+        .is_some_and(|types| types.iter().any(|t| t == "firmware"));
+    if update && !tracked.firmware_reported {
+        tracked.firmware_reported = true;
+        events.push(HeadsetEvent::FirmwareUpdateAvailable {
+            headset_id: headset.id.clone(),
+            firmware: headset
+                .firmware_display
+                .clone()
+                .or_else(|| headset.firmware.clone()),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headset(id: &str, status: &str, battery: u32) -> HeadsetInfo {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "status": status,
+            "batteryPercent": battery,
+        }))
+        .unwrap()
+    }
+
+    fn kinds(events: &[HeadsetEvent]) -> Vec<String> {
+        events
+            .iter()
+            .map(|e| {
+                let value = serde_json::to_value(e).unwrap();
+                format!("{}:{}", value["kind"].as_str().unwrap(), e.headset_id())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_tracker_debounces_transitions() {
+        let config = HeadsetWatcherConfig {
+            debounce: Duration::from_secs(1),
+            battery_low_percent: 20,
+            ..HeadsetWatcherConfig::default()
+        };
+        let mut tracker = Tracker::new(config);
+        let t0 = Instant::now();
+        let at = |secs: u64| t0 + Duration::from_secs(secs);
+
+        // Seen once: pending until it persists for the debounce.
+        assert!(
+            tracker
+                .observe(&[headset("A", "discovered", 80)], at(0))
+                .is_empty()
+        );
+        assert_eq!(
+            kinds(&tracker.observe(&[headset("A", "discovered", 80)], at(1))),
+            ["discovered:A"]
+        );
+
+        // A flicker to `connecting` that does not persist is not reported.
+        assert!(
+            tracker
+                .observe(&[headset("A", "connecting", 80)], at(2))
+                .is_empty()
+        );
+        assert!(
+            tracker
+                .observe(&[headset("A", "discovered", 80)], at(3))
+                .is_empty()
+        );
+
+        assert_eq!(
+            kinds(&tracker.observe(&[headset("A", "connected", 10)], at(4))),
+            ["battery_low:A"]
+        );
+        let events = tracker.observe(&[headset("A", "connected", 10)], at(5));
+        assert_eq!(kinds(&events), ["connected:A"]);
+
+        assert!(tracker.observe(&[], at(6)).is_empty());
+        assert_eq!(
+            tracker.observe(&[], at(7)),
+            [HeadsetEvent::Lost {
+                headset_id: "A".into(),
+                last_state: HeadsetState::Connected,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_tracker_reports_battery_and_firmware_once() {
+        let mut tracker = Tracker::new(HeadsetWatcherConfig {
+            debounce: Duration::ZERO,
+            battery_low_percent: 20,
+            ..HeadsetWatcherConfig::default()
+        });
+        let now = Instant::now();
+        let mut low: HeadsetInfo = headset("B", "connected", 15);
+        low.dfu_types = Some(vec!["firmware".into()]);
+        low.firmware_display = Some("3.7.1".into());
+
+        assert_eq!(
+            kinds(&tracker.observe(std::slice::from_ref(&low), now)),
+            [
+                "connected:B",
+                "battery_low:B",
+                "firmware_update_available:B"
+            ]
+        );
+        assert!(tracker.observe(std::slice::from_ref(&low), now).is_empty());
+
+        low.battery_percent = Some(50);
+        assert!(tracker.observe(std::slice::from_ref(&low), now).is_empty());
+        low.battery_percent = Some(12);
+        assert_eq!(
+            kinds(&tracker.observe(std::slice::from_ref(&low), now)),
+            ["battery_low:B"]
+        );
+    }
+}
@@ -0,0 +1,300 @@
+//! # Analysis Interop
+//!
+//! Conversions from collected stream samples into the containers analysis
+//! code usually starts from, so a batch of [`EegData`] or
+//! [`BandPowerData`] does not need to be reshaped by hand.
+//!
+//! | Feature | Functions | Output |
+//! |---------|-----------|--------|
+//! | `ndarray` | [`eeg_to_array`], [`band_power_to_array`] | `Array2<f32>`, one row per sample |
+//! | `polars` | [`eeg_to_dataframe`], [`band_power_to_dataframe`] | `DataFrame` with a `timestamp` column and one column per channel |
+//!
+//! Band power columns are named like Cortex's `pow` labels,
+//! `"<channel>/<band>"` with bands in [`BAND_NAMES`] order, and arrays use
+//! the same column order. Timestamps are microseconds since the Unix
+//! epoch, as on the samples.
+//!
+//! Every sample of a batch must have the same number of channels;
+//! otherwise the conversion fails with [`CortexError::StreamError`].
+//!
+//! [`BAND_NAMES`]: crate::band_power::BAND_NAMES
+
+use crate::error::{CortexError, CortexResult};
+use crate::protocol::streams::{BandPowerData, EegData};
+
+#[cfg(feature = "polars")]
+use crate::band_power::BAND_NAMES;
+
+/// Bands per channel in a [`BandPowerData`] sample.
+const BANDS: usize = 5;
+
+/// Channel count shared by every sample in `widths`, or 0 for an empty
+/// batch.
+fn uniform_width(widths: impl IntoIterator<Item = usize>) -> CortexResult<usize> {
+    let mut widths = widths.into_iter().enumerate();
+    let Some((_, expected)) = widths.next() else {
+        return Ok(0);
+    };
+    match widths.find(|(_, width)| *width != expected) {
+        Some((index, width)) => Err(CortexError::StreamError {
+            reason: format!("sample {index} has {width} channels, expected {expected}"),
+        }),
+        None => Ok(expected),
+    }
+}
+
+/// EEG values in sample order, row-major, and the channel count.
+fn eeg_values(samples: &[EegData]) -> CortexResult<(Vec<f32>, usize)> {
+    let width = uniform_width(samples.iter().map(|s| s.channels.len()))?;
+    let values = samples
+        .iter()
+        .flat_map(|s| s.channels.iter().copied())
+        .collect();
+    Ok((values, width))
+}
+
+/// Band powers in sample order, row-major, and the channel count.
+fn band_power_values(samples: &[BandPowerData]) -> CortexResult<(Vec<f32>, usize)> {
+    let width = uniform_width(samples.iter().map(|s| s.channel_powers.len()))?;
+    let values = samples
+        .iter()
+        .flat_map(|s| s.channel_powers.iter().flatten().copied())
+        .collect();
+    Ok((values, width))
+}
+
+// ─── ndarray ─────────────────────────────────────────────────────────────
+
+/// EEG samples as a `(samples, channels)` array of microvolts.
+///
+/// ```no_run
+/// # fn demo(samples: &[emotiv_cortex_v2::protocol::streams::EegData]) -> emotiv_cortex_v2::CortexResult<()> {
+/// let eeg = emotiv_cortex_v2::interop::eeg_to_array(samples)?;
+/// let mean_af3 = eeg.column(0).mean();
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns [`CortexError::StreamError`] if the samples do not all have
+/// the same number of channels.
+#[cfg(feature = "ndarray")]
+pub fn eeg_to_array(samples: &[EegData]) -> CortexResult<ndarray::Array2<f32>> {
+    let (values, width) = eeg_values(samples)?;
+    to_array(values, samples.len(), width)
+}
+
+/// Band power samples as a `(samples, channels * 5)` array in uV²/Hz,
+/// each channel's bands in [`BAND_NAMES`](crate::band_power::BAND_NAMES)
+/// order.
+///
+/// # Errors
+/// Returns [`CortexError::StreamError`] if the samples do not all have
+/// the same number of channels.
+#[cfg(feature = "ndarray")]
+pub fn band_power_to_array(samples: &[BandPowerData]) -> CortexResult<ndarray::Array2<f32>> {
+    let (values, width) = band_power_values(samples)?;
+    to_array(values, samples.len(), width * BANDS)
+}
+
+/// Timestamps of EEG samples, microseconds since the Unix epoch.
+#[cfg(feature = "ndarray")]
+#[must_use]
+pub fn eeg_timestamps(samples: &[EegData]) -> ndarray::Array1<i64> {
+    samples.iter().map(|s| s.timestamp).collect()
+}
+
+/// Timestamps of band power samples, microseconds since the Unix epoch.
+#[cfg(feature = "ndarray")]
+#[must_use]
+pub fn band_power_timestamps(samples: &[BandPowerData]) -> ndarray::Array1<i64> {
+    samples.iter().map(|s| s.timestamp).collect()
+}
+
+#[cfg(feature = "ndarray")]
+fn to_array(values: Vec<f32>, rows: usize, columns: usize) -> CortexResult<ndarray::Array2<f32>> {
+    ndarray::Array2::from_shape_vec((rows, columns), values).map_err(|e| CortexError::StreamError {
+        reason: format!("cannot shape samples as {rows}x{columns}: {e}"),
+    })
+}
+
+// ─── polars ──────────────────────────────────────────────────────────────
+
+/// EEG samples as a `DataFrame` with an `i64` `timestamp` column followed
+/// by one `f32` column per name in `channel_names`.
+///
+/// ```no_run
+/// use emotiv_cortex_v2::{HeadsetModel, interop};
+///
+/// # fn demo(samples: &[emotiv_cortex_v2::protocol::streams::EegData]) -> emotiv_cortex_v2::CortexResult<()> {
+/// let df = interop::eeg_to_dataframe(samples, HeadsetModel::EpocX.channel_names())?;
+/// println!("{}", df.head(Some(5)));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns [`CortexError::StreamError`] if the samples do not all have
+/// `channel_names.len()` channels, or the names are not unique.
+#[cfg(feature = "polars")]
+pub fn eeg_to_dataframe(
+    samples: &[EegData],
+    channel_names: &[impl AsRef<str>],
+) -> CortexResult<polars::frame::DataFrame> {
+    let (values, width) = eeg_values(samples)?;
+    let names: Vec<String> = channel_names
+        .iter()
+        .map(|name| name.as_ref().to_string())
+        .collect();
+    let timestamps = samples.iter().map(|s| s.timestamp).collect();
+    to_dataframe(timestamps, &values, width, &names)
+}
+
+/// Band power samples as a `DataFrame` with an `i64` `timestamp` column
+/// followed by one `f32` column per channel and band, named
+/// `"<channel>/<band>"` (e.g. `"AF3/theta"`).
+///
+/// # Errors
+/// Returns [`CortexError::StreamError`] if the samples do not all have
+/// `channel_names.len()` channels, or the names are not unique.
+#[cfg(feature = "polars")]
+pub fn band_power_to_dataframe(
+    samples: &[BandPowerData],
+    channel_names: &[impl AsRef<str>],
+) -> CortexResult<polars::frame::DataFrame> {
+    let (values, width) = band_power_values(samples)?;
+    let names: Vec<String> = channel_names
+        .iter()
+        .flat_map(|name| {
+            BAND_NAMES
+                .iter()
+                .map(move |band| format!("{}/{band}", name.as_ref()))
+        })
+        .collect();
+    let timestamps = samples.iter().map(|s| s.timestamp).collect();
+    to_dataframe(timestamps, &values, width * BANDS, &names)
+}
+
+#[cfg(feature = "polars")]
+fn to_dataframe(
+    timestamps: Vec<i64>,
+    values: &[f32],
+    width: usize,
+    names: &[String],
+) -> CortexResult<polars::frame::DataFrame> {
+    use polars::prelude::{Column, DataFrame};
+
+    if !timestamps.is_empty() && width != names.len() {
+        return Err(CortexError::StreamError {
+            reason: format!(
+                "samples have {width} columns but {} column names were given",
+                names.len()
+            ),
+        });
+    }
+    let mut columns = Vec::with_capacity(names.len() + 1);
+    columns.push(Column::new("timestamp".into(), timestamps));
+    for (index, name) in names.iter().enumerate() {
+        let column: Vec<f32> = values
+            .iter()
+            .skip(index)
+            .step_by(width.max(1))
+            .copied()
+            .collect();
+        columns.push(Column::new(name.into(), column));
+    }
+    DataFrame::new(columns).map_err(|e| CortexError::StreamError {
+        reason: format!("cannot build DataFrame: {e}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::eeg_sample;
+
+    fn pow(timestamp: i64, base: f32) -> BandPowerData {
+        BandPowerData {
+            timestamp,
+            channel_powers: vec![
+                [base, base + 1.0, base + 2.0, base + 3.0, base + 4.0],
+                [base + 5.0, base + 6.0, base + 7.0, base + 8.0, base + 9.0],
+            ],
+        }
+    }
+
+    #[test]
+    fn test_ragged_batch_is_rejected() {
+        let samples = [
+            eeg_sample(0, 0, vec![1.0, 2.0]),
+            eeg_sample(1, 0, vec![1.0]),
+        ];
+        let err = eeg_values(&samples).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("sample 1 has 1 channels, expected 2")
+        );
+        assert_eq!(eeg_values(&[]).unwrap(), (Vec::new(), 0));
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_arrays_have_one_row_per_sample() {
+        let samples = [
+            eeg_sample(10, 0, vec![1.0, -1.0]),
+            eeg_sample(20, 0, vec![2.0, -2.0]),
+            eeg_sample(30, 0, vec![3.0, -3.0]),
+        ];
+        let array = eeg_to_array(&samples).unwrap();
+        assert_eq!(array.shape(), [3, 2]);
+        assert!((array[[2, 1]] + 3.0).abs() < f32::EPSILON);
+        assert_eq!(eeg_timestamps(&samples).to_vec(), [10, 20, 30]);
+
+        let powers = [pow(10, 0.0), pow(20, 100.0)];
+        let array = band_power_to_array(&powers).unwrap();
+        assert_eq!(array.shape(), [2, 10]);
+        // Second sample, second channel, alpha.
+        assert!((array[[1, 6]] - 106.0).abs() < f32::EPSILON);
+        assert_eq!(band_power_timestamps(&powers).to_vec(), [10, 20]);
+    }
+
+    #[cfg(feature = "polars")]
+    #[test]
+    fn test_dataframes_name_columns_by_channel() {
+        let samples = [
+            eeg_sample(10, 0, vec![1.0, -1.0]),
+            eeg_sample(20, 0, vec![2.0, -2.0]),
+        ];
+        let df = eeg_to_dataframe(&samples, &["AF3", "AF4"]).unwrap();
+        assert_eq!(df.get_column_names_str(), ["timestamp", "AF3", "AF4"]);
+        let af4: Vec<Option<f32>> = df
+            .column("AF4")
+            .unwrap()
+            .f32()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(af4, [Some(-1.0), Some(-2.0)]);
+        assert!(eeg_to_dataframe(&samples, &["AF3"]).is_err());
+
+        let df = band_power_to_dataframe(&[pow(10, 0.0), pow(20, 100.0)], &["AF3", "AF4"]).unwrap();
+        assert_eq!(df.width(), 11);
+        assert_eq!(df.get_column_names_str()[1], "AF3/theta");
+        let alpha: Vec<Option<f32>> = df
+            .column("AF4/alpha")
+            .unwrap()
+            .f32()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(alpha, [Some(6.0), Some(106.0)]);
+        let timestamps: Vec<Option<i64>> = df
+            .column("timestamp")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(timestamps, [Some(10), Some(20)]);
+    }
+}
@@ -0,0 +1,541 @@
+//! # IPC Server
+//!
+//! Exposes one authenticated [`ResilientClient`] to other local processes
+//! over a Unix domain socket, so Python or ML consumers can list headsets,
+//! open sessions, and receive typed stream samples without their own
+//! Cortex credentials or connection.
+//!
+//! Unlike the [`supervisor`](crate::supervisor), which relays a fixed set
+//! of streams from a session its owner chose, the IPC server takes
+//! requests: each connected process picks a headset, opens (or joins) its
+//! session, and subscribes the streams it wants. Each stream is subscribed
+//! on Cortex once, by the first process that asks for it, and fanned out
+//! to every process that does.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use emotiv_cortex_v2::{CortexConfig, ResilientClient};
+//! use emotiv_cortex_v2::ipc::IpcServer;
+//!
+//! # async fn demo(stop: impl std::future::Future<Output = ()>) -> emotiv_cortex_v2::CortexResult<()> {
+//! let client = Arc::new(ResilientClient::connect(CortexConfig::discover(None)?).await?);
+//! let server = IpcServer::start(Arc::clone(&client), "/tmp/emotiv-cortex-api.sock").await?;
+//! stop.await;
+//!
+//! let report = server.stop().await;
+//! println!("{} samples relayed, {} dropped", report.written, report.dropped);
+//! if let Some(client) = Arc::into_inner(client) {
+//!     client.shutdown().await;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Wire Format
+//!
+//! Newline-delimited JSON in both directions. Requests carry an `id` that
+//! is echoed in the response, as in Cortex's own JSON-RPC:
+//!
+//! ```text
+//! → {"id":1,"method":"queryHeadsets"}
+//! ← {"id":1,"result":[{"id":"INSIGHT-A1B2C3D4","status":"connected",...}]}
+//! → {"id":2,"method":"createSession","params":{"headset":"INSIGHT-A1B2C3D4"}}
+//! ← {"id":2,"result":{"id":"f8cb7289-...","status":"activated",...}}
+//! → {"id":3,"method":"subscribe","params":{"session":"f8cb7289-...","streams":["eeg","met"]}}
+//! ← {"id":3,"result":{"streams":["eeg","met"]}}
+//! ← {"stream":"eeg","sample":{"timestamp":1700000000000000,"counter":12,...}}
+//! ```
+//!
+//! | Method | Params | Result |
+//! |--------|--------|--------|
+//! | `queryHeadsets` | — | [`HeadsetInfo`] list |
+//! | `connectHeadset` | `headset` | `{}` |
+//! | `querySessions` | — | [`SessionInfo`] list |
+//! | `createSession` | `headset` | [`SessionInfo`], shared by every process asking for that headset |
+//! | `subscribe` | `session`, `streams` | `{"streams": [...]}` |
+//! | `unsubscribe` | `streams` | `{"streams": [...]}` (those this process had) |
+//!
+//! A process may close its sending half once subscribed and keep
+//! reading samples. Failures are answered with `{"id":...,"error":{"message":"..."}}`.
+//! Lines without an `id` are samples: a [`ParsedSample`] serialized as
+//! `{"stream":...,"sample":...}`. A process that falls more than
+//! [`IpcServerConfig::consumer_buffer`] samples behind skips the backlog,
+//! counted in [`IpcStats::dropped`].
+//!
+//! Cortex subscriptions stay up until the server stops, even while no
+//! process listens, and a stream is relayed from one session at a time.
+//! Sessions and subscriptions are made through the [`ResilientClient`],
+//! so [`ResilientClient::shutdown`] releases them.
+//!
+//! Only Unix platforms are supported.
+//!
+//! [`HeadsetInfo`]: crate::protocol::headset::HeadsetInfo
+//! [`ParsedSample`]: crate::streams::ParsedSample
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+use tokio::sync::{Mutex, broadcast, mpsc, watch};
+use tokio::task::JoinHandle;
+
+use crate::error::{CortexError, CortexResult};
+use crate::fanout::{self, Counters};
+use crate::protocol::headset::QueryHeadsetsOptions;
+use crate::protocol::session::SessionInfo;
+use crate::reconnect::ResilientClient;
+use crate::sink::SinkReport;
+use crate::streams::parse_sample_value;
+
+/// IPC server tuning; the same per-consumer buffer as the supervisor's.
+pub type IpcServerConfig = crate::supervisor::SupervisorConfig;
+
+/// Counters for a running [`IpcServer`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct IpcStats {
+    /// Processes currently connected.
+    pub clients: usize,
+    /// Connections accepted since start.
+    pub connections: u64,
+    /// Requests handled.
+    pub requests: u64,
+    /// Samples relayed from Cortex.
+    pub samples: u64,
+    /// Samples skipped by processes that fell behind.
+    pub dropped: u64,
+}
+
+/// One Cortex stream fanned out to connected processes.
+struct Relay {
+    session_id: String,
+    lines: broadcast::Sender<Arc<str>>,
+    task: JoinHandle<()>,
+}
+
+/// State shared by the connection tasks.
+struct Shared {
+    client: Arc<ResilientClient>,
+    consumer_buffer: usize,
+    /// Sessions opened through the server, by headset.
+    sessions: Mutex<HashMap<String, SessionInfo>>,
+    /// Relayed streams, by stream name.
+    relays: Mutex<HashMap<String, Relay>>,
+    counters: Arc<Counters>,
+    requests: AtomicU64,
+    /// Stops the relay tasks.
+    shutdown: watch::Receiver<bool>,
+}
+
+// ─── Server ──────────────────────────────────────────────────────────────
+
+/// Serves a [`ResilientClient`] to local processes over a Unix socket.
+pub struct IpcServer {
+    path: PathBuf,
+    shared: Arc<Shared>,
+    shutdown: watch::Sender<bool>,
+    acceptor: JoinHandle<()>,
+}
+
+impl IpcServer {
+    /// Serve `client` at `path` with the default [`IpcServerConfig`].
+    ///
+    /// # Errors
+    /// See [`Self::start_with_config`].
+    pub async fn start(client: Arc<ResilientClient>, path: impl AsRef<Path>) -> CortexResult<Self> {
+        Self::start_with_config(client, path, IpcServerConfig::default()).await
+    }
+
+    /// Serve `client` at `path`.
+    ///
+    /// A stale socket file left by a crashed server is replaced; a socket
+    /// another process is still serving is not.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ConfigError`] if `path` is not a socket or is
+    /// served by another process and [`CortexError::Io`] if the socket cannot be bound.
+    pub async fn start_with_config(
+        client: Arc<ResilientClient>,
+        path: impl AsRef<Path>,
+        config: IpcServerConfig,
+    ) -> CortexResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let listener = fanout::bind(&path).await?;
+
+        let (shutdown, _) = watch::channel(false);
+        let shared = Arc::new(Shared {
+            client,
+            consumer_buffer: config.consumer_buffer.max(1),
+            sessions: Mutex::new(HashMap::new()),
+            relays: Mutex::new(HashMap::new()),
+            counters: Arc::new(Counters::default()),
+            requests: AtomicU64::new(0),
+            shutdown: shutdown.subscribe(),
+        });
+        let acceptor = {
+            let shared = Arc::clone(&shared);
+            tokio::spawn(fanout::accept(
+                listener,
+                Arc::clone(&shared.counters),
+                shutdown.subscribe(),
+                move |socket, shutdown| serve(socket, Arc::clone(&shared), shutdown),
+            ))
+        };
+
+        tracing::info!(path = %path.display(), "IPC server started");
+        Ok(Self {
+            path,
+            shared,
+            shutdown,
+            acceptor,
+        })
+    }
+
+    /// Socket path processes connect to.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Current counters.
+    #[must_use]
+    pub fn stats(&self) -> IpcStats {
+        let counters = &self.shared.counters;
+        IpcStats {
+            clients: counters.consumers.load(Ordering::Relaxed),
+            connections: counters.connections.load(Ordering::Relaxed),
+            requests: self.shared.requests.load(Ordering::Relaxed),
+            samples: counters.relayed.load(Ordering::Relaxed),
+            dropped: counters.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Disconnect every process, stop relaying, and remove the socket
+    /// file. Sessions and subscriptions stay open; release them with
+    /// [`ResilientClient::shutdown`], which the returned server no longer
+    /// keeps a reference to.
+    ///
+    /// The report counts relayed samples as written and samples skipped
+    /// by lagging processes as dropped.
+    pub async fn stop(self) -> SinkReport {
+        let mut report = SinkReport::new(format!("ipc:{}", self.path.display()));
+        let _ = self.shutdown.send(true);
+        if self.acceptor.await.is_err() {
+            report.error = Some("accept task panicked".into());
+        }
+        for (_, relay) in self.shared.relays.lock().await.drain() {
+            relay.task.abort();
+            let _ = relay.task.await;
+        }
+        let _ = std::fs::remove_file(&self.path);
+
+        report.written = self.shared.counters.relayed.load(Ordering::Relaxed);
+        report.dropped = self.shared.counters.dropped.load(Ordering::Relaxed);
+        report
+    }
+}
+
+// ─── Connections ─────────────────────────────────────────────────────────
+
+/// One request line.
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct HeadsetParams {
+    headset: String,
+}
+
+#[derive(Deserialize)]
+struct SubscribeParams {
+    session: String,
+    streams: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct UnsubscribeParams {
+    streams: Vec<String>,
+}
+
+/// Per-connection forwarding tasks, by stream name.
+type Forwards = HashMap<String, JoinHandle<()>>;
+
+async fn serve(socket: UnixStream, shared: Arc<Shared>, mut shutdown: watch::Receiver<bool>) {
+    shared.counters.consumers.fetch_add(1, Ordering::Relaxed);
+    let (reader, mut writer) = socket.into_split();
+    let mut requests = BufReader::new(reader).lines();
+    let (samples_tx, mut samples) = mpsc::channel::<Arc<str>>(shared.consumer_buffer);
+    let mut forwards = Forwards::new();
+    let mut reading = true;
+
+    loop {
+        tokio::select! {
+            line = requests.next_line(), if reading => {
+                let Ok(Some(line)) = line else {
+                    // A process that closed its write half after
+                    // subscribing still receives samples.
+                    if forwards.is_empty() {
+                        break;
+                    }
+                    reading = false;
+                    continue;
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let response = handle(&shared, &line, &samples_tx, &mut forwards).await;
+                if writer.write_all(response.as_bytes()).await.is_err() {
+                    break;
+                }
+            }
+            Some(line) = samples.recv() => {
+                if writer.write_all(line.as_bytes()).await.is_err() {
+                    break;
+                }
+            }
+            _ = shutdown.changed() => break,
+        }
+    }
+
+    for task in forwards.into_values() {
+        task.abort();
+    }
+    let _ = writer.shutdown().await;
+    shared.counters.consumers.fetch_sub(1, Ordering::Relaxed);
+}
+
+/// Handle one request line and encode the response line.
+async fn handle(
+    shared: &Shared,
+    line: &str,
+    samples: &mpsc::Sender<Arc<str>>,
+    forwards: &mut Forwards,
+) -> String {
+    shared.requests.fetch_add(1, Ordering::Relaxed);
+    let (id, result) = match serde_json::from_str::<Request>(line) {
+        Ok(request) => {
+            let result = dispatch(shared, &request.method, request.params, samples, forwards).await;
+            (request.id, result)
+        }
+        Err(e) => (
+            Value::Null,
+            Err(CortexError::ProtocolError {
+                reason: format!("invalid request: {e}"),
+            }),
+        ),
+    };
+    let response = match result {
+        Ok(result) => json!({"id": id, "result": result}),
+        Err(e) => json!({"id": id, "error": {"message": e.to_string()}}),
+    };
+    response.to_string() + "\n"
+}
+
+async fn dispatch(
+    shared: &Shared,
+    method: &str,
+    params: Value,
+    samples: &mpsc::Sender<Arc<str>>,
+    forwards: &mut Forwards,
+) -> CortexResult<Value> {
+    match method {
+        "queryHeadsets" => {
+            let headsets = shared
+                .client
+                .query_headsets(QueryHeadsetsOptions::default())
+                .await?;
+            Ok(serde_json::to_value(headsets)?)
+        }
+        "connectHeadset" => {
+            let params: HeadsetParams = parse_params(params)?;
+            shared.client.connect_headset(&params.headset).await?;
+            Ok(json!({}))
+        }
+        "querySessions" => Ok(serde_json::to_value(shared.client.query_sessions().await?)?),
+        "createSession" => {
+            let params: HeadsetParams = parse_params(params)?;
+            Ok(serde_json::to_value(
+                shared.session_for(&params.headset).await?,
+            )?)
+        }
+        "subscribe" => {
+            let params: SubscribeParams = parse_params(params)?;
+            let receivers = shared.subscribe(&params.session, &params.streams).await?;
+            let mut streams = Vec::with_capacity(receivers.len());
+            for (stream, lines) in receivers {
+                if !forwards.contains_key(&stream) {
+                    let task = tokio::spawn(forward(
+                        lines,
+                        samples.clone(),
+                        Arc::clone(&shared.counters),
+                    ));
+                    forwards.insert(stream.clone(), task);
+                }
+                streams.push(stream);
+            }
+            Ok(json!({ "streams": streams }))
+        }
+        "unsubscribe" => {
+            let params: UnsubscribeParams = parse_params(params)?;
+            let mut streams = Vec::new();
+            for stream in params.streams {
+                if let Some(task) = forwards.remove(&stream) {
+                    task.abort();
+                    streams.push(stream);
+                }
+            }
+            Ok(json!({ "streams": streams }))
+        }
+        other => Err(CortexError::ProtocolError {
+            reason: format!("unknown method '{other}'"),
+        }),
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> CortexResult<T> {
+    serde_json::from_value(params).map_err(|e| CortexError::ProtocolError {
+        reason: format!("invalid params: {e}"),
+    })
+}
+
+impl Shared {
+    /// The session opened through the server on `headset`, creating it
+    /// on first use.
+    async fn session_for(&self, headset: &str) -> CortexResult<SessionInfo> {
+        let mut sessions = self.sessions.lock().await;
+        if let Some(session) = sessions.get(headset) {
+            return Ok(session.clone());
+        }
+        let session = self.client.create_session(headset).await?;
+        sessions.insert(headset.to_string(), session.clone());
+        Ok(session)
+    }
+
+    /// Receivers for `streams` on `session_id`, subscribing the streams
+    /// not relayed yet.
+    async fn subscribe(
+        &self,
+        session_id: &str,
+        streams: &[String],
+    ) -> CortexResult<Vec<(String, broadcast::Receiver<Arc<str>>)>> {
+        let mut relays = self.relays.lock().await;
+        let mut missing: Vec<&str> = Vec::new();
+        for stream in streams {
+            match relays.get(stream) {
+                Some(relay) if relay.session_id != session_id => {
+                    return Err(CortexError::StreamError {
+                        reason: format!(
+                            "stream '{stream}' is already relayed from session {}",
+                            relay.session_id
+                        ),
+                    });
+                }
+                Some(_) => {}
+                None if missing.contains(&stream.as_str()) => {}
+                None => missing.push(stream),
+            }
+        }
+
+        if !missing.is_empty() {
+            let mut receivers = self
+                .client
+                .create_stream_channels_for_session(session_id, &missing)
+                .await;
+            let result = match self.client.subscribe_streams(session_id, &missing).await {
+                Ok(result) => result,
+                Err(e) => {
+                    self.client
+                        .remove_session_stream_channels(session_id, &missing)
+                        .await;
+                    return Err(e);
+                }
+            };
+            if let Some(failure) = result.failure.first() {
+                self.client
+                    .remove_session_stream_channels(session_id, &missing)
+                    .await;
+                let subscribed: Vec<&str> = result
+                    .success
+                    .iter()
+                    .map(|s| s.stream_name.as_str())
+                    .collect();
+                if !subscribed.is_empty() {
+                    let _ = self
+                        .client
+                        .unsubscribe_streams(session_id, &subscribed)
+                        .await;
+                }
+                return Err(CortexError::from_api_error(
+                    failure.code,
+                    format!("stream '{}': {}", failure.stream_name, failure.message),
+                ));
+            }
+            for stream in missing {
+                let Some(rx) = receivers.remove(stream) else {
+                    continue;
+                };
+                let (lines, _) = broadcast::channel(self.consumer_buffer);
+                let task = tokio::spawn(fanout::relay(
+                    stream.to_string(),
+                    rx,
+                    lines.clone(),
+                    Arc::clone(&self.counters),
+                    self.shutdown.clone(),
+                    encode_sample,
+                ));
+                relays.insert(
+                    stream.to_string(),
+                    Relay {
+                        session_id: session_id.to_string(),
+                        lines,
+                        task,
+                    },
+                );
+            }
+        }
+
+        Ok(streams
+            .iter()
+            .filter_map(|stream| {
+                let relay = relays.get(stream)?;
+                Some((stream.clone(), relay.lines.subscribe()))
+            })
+            .collect())
+    }
+}
+
+/// Parse one Cortex event and encode it as a sample line.
+#[allow(clippy::needless_pass_by_value)] // `fanout::relay` hands events over by value.
+fn encode_sample(stream: &str, event: Value) -> Option<String> {
+    let Some(sample) = parse_sample_value(stream, &event) else {
+        tracing::debug!(stream = %stream, "Skipping unparseable event");
+        return None;
+    };
+    serde_json::to_string(&sample)
+        .inspect_err(|e| tracing::warn!(stream = %stream, error = %e, "Failed to encode sample"))
+        .ok()
+}
+
+/// Copy one stream's sample lines into a connection's queue.
+async fn forward(
+    mut lines: broadcast::Receiver<Arc<str>>,
+    samples: mpsc::Sender<Arc<str>>,
+    counters: Arc<Counters>,
+) {
+    while let Some(line) = fanout::recv(&mut lines, &counters).await {
+        if samples.send(line).await.is_err() {
+            break;
+        }
+    }
+}
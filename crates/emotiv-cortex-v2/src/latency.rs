@@ -0,0 +1,234 @@
+//! # Endpoint Latency Budgets
+//!
+//! A Launcher that is starting to struggle usually gets slow well before
+//! calls time out. [`CortexClient`] times every JSON-RPC call, keeps a
+//! sliding window of recent latencies per method for percentile
+//! reporting, and compares each call against the budget configured for
+//! its method in [`LatencyConfig::budgets_ms`]. Calls over budget are
+//! logged and broadcast as [`SlowEndpoint`] alerts.
+//!
+//! ```toml
+//! [latency]
+//! window = 256
+//!
+//! [latency.budgets_ms]
+//! subscribe = 1000
+//! createSession = 3000
+//! ```
+//!
+//! Alerts are available from [`CortexClient::slow_endpoint_receiver`] and
+//! percentiles from [`CortexClient::latency_stats`]; [`ResilientClient`]
+//! offers the same methods, which keep working across reconnects.
+//!
+//! [`CortexClient`]: crate::CortexClient
+//! [`CortexClient::slow_endpoint_receiver`]: crate::CortexClient::slow_endpoint_receiver
+//! [`CortexClient::latency_stats`]: crate::CortexClient::latency_stats
+//! [`ResilientClient`]: crate::ResilientClient
+//! [`LatencyConfig::budgets_ms`]: crate::config::LatencyConfig::budgets_ms
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::config::LatencyConfig;
+
+/// Capacity of the [`SlowEndpoint`] broadcast channel.
+const ALERT_CHANNEL_CAPACITY: usize = 64;
+
+/// A call that took longer than its method's budget.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SlowEndpoint {
+    /// Cortex method name.
+    pub method: String,
+    /// How long the call took (including failed calls and timeouts).
+    pub elapsed: Duration,
+    /// The configured budget.
+    pub budget: Duration,
+}
+
+/// Latency summary for one method.
+///
+/// Percentiles cover the most recent [`LatencyConfig::window`] calls;
+/// `calls` and `slow` count every call since the client connected.
+///
+/// [`LatencyConfig::window`]: crate::config::LatencyConfig::window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LatencyStats {
+    /// Cortex method name.
+    pub method: String,
+    /// Calls made.
+    pub calls: u64,
+    /// Calls over budget.
+    pub slow: u64,
+    /// Configured budget, if any.
+    pub budget: Option<Duration>,
+    /// Median latency.
+    pub p50: Duration,
+    /// 90th percentile latency.
+    pub p90: Duration,
+    /// 99th percentile latency.
+    pub p99: Duration,
+    /// Slowest call in the window.
+    pub max: Duration,
+}
+
+#[derive(Default)]
+struct MethodLatency {
+    recent: VecDeque<Duration>,
+    calls: u64,
+    slow: u64,
+}
+
+/// Per-method latency window and budget checks.
+pub(crate) struct LatencyTracker {
+    budgets: HashMap<String, Duration>,
+    window: usize,
+    methods: Mutex<HashMap<&'static str, MethodLatency>>,
+    alerts: broadcast::Sender<SlowEndpoint>,
+}
+
+impl LatencyTracker {
+    pub(crate) fn from_config(config: &LatencyConfig) -> Self {
+        let (alerts, _) = broadcast::channel(ALERT_CHANNEL_CAPACITY);
+        Self {
+            budgets: config
+                .budgets_ms
+                .iter()
+                .map(|(method, ms)| (method.clone(), Duration::from_millis(*ms)))
+                .collect(),
+            window: config.window.max(1),
+            methods: Mutex::new(HashMap::new()),
+            alerts,
+        }
+    }
+
+    /// Record one call and alert if it was over budget.
+    pub(crate) fn record(&self, method: &'static str, elapsed: Duration) {
+        let budget = self.budgets.get(method).copied();
+        let over_budget = budget.filter(|b| elapsed > *b);
+
+        if let Ok(mut methods) = self.methods.lock() {
+            let entry = methods.entry(method).or_default();
+            entry.calls += 1;
+            if over_budget.is_some() {
+                entry.slow += 1;
+            }
+            if entry.recent.len() == self.window {
+                entry.recent.pop_front();
+            }
+            entry.recent.push_back(elapsed);
+        }
+
+        if let Some(budget) = over_budget {
+            tracing::warn!(
+                method,
+                elapsed_ms = elapsed.as_millis(),
+                budget_ms = budget.as_millis(),
+                "Cortex call exceeded its latency budget"
+            );
+            let _ = self.alerts.send(SlowEndpoint {
+                method: method.to_string(),
+                elapsed,
+                budget,
+            });
+        }
+    }
+
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<SlowEndpoint> {
+        self.alerts.subscribe()
+    }
+
+    /// Stats for every method called so far, sorted by method name.
+    pub(crate) fn stats(&self) -> Vec<LatencyStats> {
+        let Ok(methods) = self.methods.lock() else {
+            return Vec::new();
+        };
+        let mut stats: Vec<LatencyStats> = methods
+            .iter()
+            .map(|(method, m)| {
+                let mut sorted: Vec<Duration> = m.recent.iter().copied().collect();
+                sorted.sort_unstable();
+                LatencyStats {
+                    method: (*method).to_string(),
+                    calls: m.calls,
+                    slow: m.slow,
+                    budget: self.budgets.get(*method).copied(),
+                    p50: percentile(&sorted, 50),
+                    p90: percentile(&sorted, 90),
+                    p99: percentile(&sorted, 99),
+                    max: sorted.last().copied().unwrap_or_default(),
+                }
+            })
+            .collect();
+        stats.sort_by(|a, b| a.method.cmp(&b.method));
+        stats
+    }
+}
+
+/// Nearest-rank percentile of an ascending slice.
+fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank.min(sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker(window: usize, budgets: &[(&str, u64)]) -> LatencyTracker {
+        LatencyTracker::from_config(&LatencyConfig {
+            window,
+            budgets_ms: budgets
+                .iter()
+                .map(|(m, ms)| ((*m).to_string(), *ms))
+                .collect(),
+        })
+    }
+
+    #[test]
+    fn test_over_budget_calls_alert() {
+        let tracker = tracker(16, &[("subscribe", 100)]);
+        let mut alerts = tracker.subscribe();
+
+        tracker.record("subscribe", Duration::from_millis(40));
+        tracker.record("subscribe", Duration::from_millis(250));
+        tracker.record("queryHeadsets", Duration::from_secs(5));
+
+        assert_eq!(
+            alerts.try_recv().unwrap(),
+            SlowEndpoint {
+                method: "subscribe".into(),
+                elapsed: Duration::from_millis(250),
+                budget: Duration::from_millis(100),
+            }
+        );
+        assert!(alerts.try_recv().is_err(), "unbudgeted methods never alert");
+
+        let stats = tracker.stats();
+        assert_eq!(stats[0].method, "queryHeadsets");
+        assert_eq!(stats[0].budget, None);
+        assert_eq!((stats[1].calls, stats[1].slow), (2, 1));
+    }
+
+    #[test]
+    fn test_percentiles_use_recent_window() {
+        let tracker = tracker(100, &[]);
+        tracker.record("getCortexInfo", Duration::from_secs(60));
+        for ms in 1..=100 {
+            tracker.record("getCortexInfo", Duration::from_millis(ms));
+        }
+
+        let stats = &tracker.stats()[0];
+        assert_eq!(stats.calls, 101);
+        assert_eq!(stats.p50, Duration::from_millis(50));
+        assert_eq!(stats.p90, Duration::from_millis(90));
+        assert_eq!(stats.p99, Duration::from_millis(99));
+        assert_eq!(stats.max, Duration::from_millis(100));
+    }
+}
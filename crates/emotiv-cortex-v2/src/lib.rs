@@ -70,6 +70,20 @@
 //! Exactly one TLS backend feature must be enabled.
 //! `config-toml` (default) controls TOML parsing support in [`CortexConfig`];
 //! when disabled, file-based config loading returns [`CortexError::ConfigError`].
+//! `support-bundle` (opt-in) enables zip output for
+//! [`diagnostics::collect_support_bundle`].
+//! `otel` (opt-in) reports RPC traces and stream/reconnect metrics through
+//! the OpenTelemetry API, and `metrics` (opt-in) the same metrics through
+//! the `metrics` facade; see [`telemetry`].
+//! `brainflow` (opt-in) adds a BrainFlow-style `BoardShim` ring buffer for
+//! analysis code written against BrainFlow; see `brainflow`.
+//! `dsp` (opt-in) adds streaming notch and bandpass filters for EEG; see
+//! `dsp`.
+//! `gzip` (opt-in) lets [`streams::exporter`] compress its segments.
+//! `ndarray` and `polars` (opt-in) convert collected EEG and band power
+//! samples to arrays and data frames; see `interop`.
+//! `token-encryption` (opt-in) encrypts the on-disk token cache; see
+//! [`token_cache`].
 //!
 //! ## Protocol Modules
 //!
@@ -84,6 +98,8 @@
 //! - `protocol::training`
 //! - `protocol::auth`
 //! - `protocol::subjects`
+//! - `protocol::query`
+//! - `protocol::warnings`
 
 #[cfg(all(not(feature = "rustls-tls"), not(feature = "native-tls")))]
 compile_error!(
@@ -95,15 +111,63 @@ compile_error!(
     "emotiv-cortex-v2 requires exactly one TLS backend feature: `rustls-tls` and `native-tls` are mutually exclusive."
 );
 
+pub mod annotations;
+pub mod anonymize;
+pub mod artifacts;
+pub mod band_power;
+#[cfg(feature = "brainflow")]
+pub mod brainflow;
 pub mod client;
+pub mod compat;
 pub mod config;
+pub mod deadline;
+pub mod diagnostics;
+#[cfg(feature = "dsp")]
+pub mod dsp;
+pub mod eeg_units;
+pub mod epochs;
 pub mod error;
+#[cfg(unix)]
+mod fanout;
+pub mod fitcheck;
 pub mod headset;
+pub mod headset_watcher;
 pub mod health;
+#[cfg(any(feature = "ndarray", feature = "polars"))]
+pub mod interop;
+#[cfg(unix)]
+pub mod ipc;
+pub mod latency;
+pub mod markers;
+pub mod multi_session;
+pub mod ownership;
 pub mod protocol;
+pub mod quality;
+pub mod rate_limit;
 pub mod reconnect;
+pub mod record_template;
+pub mod recorder;
+pub mod replay;
+pub mod resample;
 pub mod retry;
+pub mod routes;
+pub mod self_test;
+pub mod session_handle;
+pub mod session_manager;
+pub mod session_pool;
+pub mod sink;
+pub mod stream_stats;
 pub mod streams;
+#[cfg(unix)]
+pub mod supervisor;
+pub mod teardown;
+pub mod telemetry;
+pub mod testing;
+pub mod timesync;
+pub mod token_cache;
+pub mod training;
+mod util;
+pub mod writer;
 
 // ─── Public re-exports ──────────────────────────────────────────────────
 
@@ -0,0 +1,317 @@
+//! # Marker Batching
+//!
+//! [`MarkerBatcher`] takes `injectMarker` calls off the experiment's hot
+//! path. [`MarkerBatcher::mark`] stamps the marker with the client clock
+//! when it is called and queues it; a background task sends queued
+//! markers in order, passing that stamp as the explicit `time`
+//! parameter, so a marker lands where the event happened however late it
+//! is delivered.
+//!
+//! When Cortex cannot be reached the markers stay queued (up to
+//! [`MarkerBatcherConfig::max_buffered`]) and are retried with backoff,
+//! and straight away after [`ConnectionEvent::Reconnected`]. A marker
+//! Cortex rejects outright (e.g. the record was stopped) is dropped and
+//! counted, so one bad marker does not hold up the rest.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use emotiv_cortex_v2::{CortexConfig, ResilientClient};
+//! use emotiv_cortex_v2::markers::{MarkerBatcher, MarkerBatcherConfig};
+//!
+//! # async fn demo(session_id: &str) -> emotiv_cortex_v2::CortexResult<()> {
+//! let client = Arc::new(ResilientClient::connect(CortexConfig::discover(None)?).await?);
+//! let markers = MarkerBatcher::spawn(client, session_id, MarkerBatcherConfig::default());
+//! markers.mark("stimulus", 1)?;
+//! // ...
+//! let report = markers.stop().await;
+//! assert!(report.is_lossless());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`ConnectionEvent::Reconnected`]: crate::reconnect::ConnectionEvent::Reconnected
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio::task::JoinHandle;
+
+use crate::error::{CortexError, CortexResult};
+use crate::reconnect::{ConnectionEvent, ResilientClient};
+use crate::sink::{Sink, SinkReport};
+use crate::util::epoch_millis;
+
+/// Port reported on batched markers unless configured otherwise.
+pub const DEFAULT_MARKER_PORT: &str = "emotiv-cortex-v2";
+
+/// How a [`MarkerBatcher`] sends and buffers markers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkerBatcherConfig {
+    /// Port reported on every marker.
+    pub port: String,
+    /// Markers held while they cannot be delivered; [`MarkerBatcher::mark`]
+    /// fails beyond this.
+    pub max_buffered: usize,
+    /// Delay before the first retry after a failed send; doubles up to
+    /// `max_retry_delay`.
+    pub retry_delay: Duration,
+    /// Cap on the retry delay.
+    pub max_retry_delay: Duration,
+}
+
+impl Default for MarkerBatcherConfig {
+    fn default() -> Self {
+        Self {
+            port: DEFAULT_MARKER_PORT.to_string(),
+            max_buffered: 10_000,
+            retry_delay: Duration::from_millis(250),
+            max_retry_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+/// A marker waiting to be sent.
+#[derive(Debug, Clone)]
+struct PendingMarker {
+    label: String,
+    value: i32,
+    /// Unix epoch milliseconds.
+    time: f64,
+}
+
+/// Queues markers for one session and delivers them in order. See the
+/// [module documentation](self).
+pub struct MarkerBatcher {
+    session_id: String,
+    queue: mpsc::UnboundedSender<PendingMarker>,
+    /// Markers accepted and not yet delivered or dropped.
+    pending: Arc<AtomicUsize>,
+    max_buffered: usize,
+    shutdown: watch::Sender<bool>,
+    task: JoinHandle<SinkReport>,
+}
+
+impl MarkerBatcher {
+    /// Start delivering markers to `session_id` through `client`.
+    #[must_use]
+    pub fn spawn(
+        client: Arc<ResilientClient>,
+        session_id: impl Into<String>,
+        config: MarkerBatcherConfig,
+    ) -> Self {
+        let session_id = session_id.into();
+        let (queue, queue_rx) = mpsc::unbounded_channel();
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        let pending = Arc::new(AtomicUsize::new(0));
+        let max_buffered = config.max_buffered;
+        let delivery = Delivery {
+            events: client.event_receiver(),
+            client,
+            session_id: session_id.clone(),
+            config,
+            pending: Arc::clone(&pending),
+            report: SinkReport::new(format!("markers -> {session_id}")),
+        };
+        let task = tokio::spawn(delivery.run(queue_rx, shutdown_rx));
+        Self {
+            session_id,
+            queue,
+            pending,
+            max_buffered,
+            shutdown,
+            task,
+        }
+    }
+
+    /// Queue a marker stamped with the current time. Returns the stamp,
+    /// in Unix epoch milliseconds.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::StreamError`] if the buffer is full or the
+    /// batcher has stopped.
+    pub fn mark(&self, label: impl Into<String>, value: i32) -> CortexResult<f64> {
+        let time = epoch_millis();
+        self.mark_at(label, value, time)?;
+        Ok(time)
+    }
+
+    /// Queue a marker with an explicit `time` in Unix epoch milliseconds.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::StreamError`] if the buffer is full or the
+    /// batcher has stopped.
+    pub fn mark_at(&self, label: impl Into<String>, value: i32, time: f64) -> CortexResult<()> {
+        if self.pending.fetch_add(1, Ordering::SeqCst) >= self.max_buffered {
+            self.pending.fetch_sub(1, Ordering::SeqCst);
+            return Err(CortexError::StreamError {
+                reason: format!(
+                    "marker buffer for session {} is full ({} markers)",
+                    self.session_id, self.max_buffered
+                ),
+            });
+        }
+        let marker = PendingMarker {
+            label: label.into(),
+            value,
+            time,
+        };
+        self.queue.send(marker).map_err(|_| {
+            self.pending.fetch_sub(1, Ordering::SeqCst);
+            CortexError::StreamError {
+                reason: format!("marker batcher for session {} stopped", self.session_id),
+            }
+        })
+    }
+
+    /// Markers accepted and not yet delivered or dropped.
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::SeqCst)
+    }
+
+    /// Stop accepting markers, try once more to send what is queued, and
+    /// report: `written` markers were delivered, `dropped` were rejected
+    /// by Cortex, and `unflushed` were still queued.
+    pub async fn stop(self) -> SinkReport {
+        let label = format!("markers -> {}", self.session_id);
+        let _ = self.shutdown.send(true);
+        self.task.await.unwrap_or_else(|e| SinkReport {
+            error: Some(format!("marker task failed: {e}")),
+            ..SinkReport::new(label)
+        })
+    }
+}
+
+impl Sink for MarkerBatcher {
+    fn name(&self) -> String {
+        format!("markers -> {}", self.session_id)
+    }
+
+    fn close(self: Box<Self>) -> BoxFuture<'static, Vec<SinkReport>> {
+        Box::pin(async move { vec![self.stop().await] })
+    }
+}
+
+// ─── Delivery ────────────────────────────────────────────────────────────
+
+/// State of the delivery task.
+struct Delivery {
+    client: Arc<ResilientClient>,
+    events: broadcast::Receiver<ConnectionEvent>,
+    session_id: String,
+    config: MarkerBatcherConfig,
+    pending: Arc<AtomicUsize>,
+    report: SinkReport,
+}
+
+/// Result of one send attempt.
+enum Sent {
+    Delivered,
+    Rejected,
+    Failed,
+}
+
+impl Delivery {
+    async fn run(
+        mut self,
+        mut queue_rx: mpsc::UnboundedReceiver<PendingMarker>,
+        mut shutdown: watch::Receiver<bool>,
+    ) -> SinkReport {
+        let mut queue = VecDeque::new();
+        let mut delay = self.config.retry_delay;
+
+        loop {
+            while let Ok(marker) = queue_rx.try_recv() {
+                queue.push_back(marker);
+            }
+            let Some(marker) = queue.front() else {
+                tokio::select! {
+                    marker = queue_rx.recv() => match marker {
+                        Some(marker) => {
+                            queue.push_back(marker);
+                            continue;
+                        }
+                        None => break,
+                    },
+                    _ = shutdown.changed() => break,
+                }
+            };
+
+            match self.send(marker).await {
+                Sent::Delivered | Sent::Rejected => {
+                    queue.pop_front();
+                    delay = self.config.retry_delay;
+                }
+                Sent::Failed => {
+                    tokio::select! {
+                        () = tokio::time::sleep(delay) => {}
+                        () = reconnected(&mut self.events) => {}
+                        _ = shutdown.changed() => break,
+                    }
+                    delay = (delay * 2).min(self.config.max_retry_delay);
+                }
+            }
+        }
+
+        // Final pass: one attempt each, giving up once Cortex is unreachable.
+        queue_rx.close();
+        while let Some(marker) = queue_rx.recv().await {
+            queue.push_back(marker);
+        }
+        while let Some(marker) = queue.pop_front() {
+            if let Sent::Failed = self.send(&marker).await {
+                queue.push_front(marker);
+                break;
+            }
+        }
+        self.report.unflushed = queue.len() as u64;
+        self.report
+    }
+
+    async fn send(&mut self, marker: &PendingMarker) -> Sent {
+        let result = self
+            .client
+            .inject_marker(
+                &self.session_id,
+                &marker.label,
+                marker.value,
+                &self.config.port,
+                Some(marker.time),
+            )
+            .await;
+        match result {
+            Ok(_) => {
+                self.pending.fetch_sub(1, Ordering::SeqCst);
+                self.report.written += 1;
+                Sent::Delivered
+            }
+            Err(e) if e.is_retryable() || e.is_connection_error() || e.is_rate_limited() => {
+                tracing::debug!(label = %marker.label, error = %e, "Marker not sent; will retry");
+                Sent::Failed
+            }
+            Err(e) => {
+                tracing::warn!(label = %marker.label, error = %e, "Cortex rejected marker");
+                self.pending.fetch_sub(1, Ordering::SeqCst);
+                self.report.dropped += 1;
+                self.report.error = Some(e.to_string());
+                Sent::Rejected
+            }
+        }
+    }
+}
+
+/// Resolves on the next [`ConnectionEvent::Reconnected`]; never resolves
+/// once the event channel is closed.
+async fn reconnected(events: &mut broadcast::Receiver<ConnectionEvent>) {
+    loop {
+        match events.recv().await {
+            Ok(ConnectionEvent::Reconnected) => return,
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+            Err(broadcast::error::RecvError::Closed) => std::future::pending().await,
+        }
+    }
+}
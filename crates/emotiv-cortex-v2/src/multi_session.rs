@@ -0,0 +1,341 @@
+//! # Multi-Headset Sessions
+//!
+//! Hyperscanning experiments record from several headsets at once, e.g.
+//! two EPOC X units on one machine. Cortex allows one session per headset
+//! on a single connection. [`MultiSessionClient`] opens one session per
+//! headset on a shared [`ResilientClient`] and subscribes through
+//! per-session stream channels (see
+//! [`CortexClient::create_stream_channels_for_session`]), so each headset
+//! gets receivers of its own.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use emotiv_cortex_v2::{CortexConfig, ResilientClient};
+//! use emotiv_cortex_v2::multi_session::MultiSessionClient;
+//! use emotiv_cortex_v2::protocol::constants::Streams;
+//!
+//! # async fn demo() -> emotiv_cortex_v2::CortexResult<()> {
+//! let client = Arc::new(ResilientClient::connect(CortexConfig::discover(None)?).await?);
+//! let multi = MultiSessionClient::new(client);
+//!
+//! multi.open("EPOCX-A1B2C3D4").await?;
+//! multi.open("EPOCX-E5F6A7B8").await?;
+//! let mut left = multi.subscribe("EPOCX-A1B2C3D4", &[Streams::EEG]).await?;
+//! let mut right = multi.subscribe("EPOCX-E5F6A7B8", &[Streams::EEG]).await?;
+//!
+//! if let (Some(left), Some(right)) = (left.get_mut("eeg"), right.get_mut("eeg")) {
+//!     let (a, b) = tokio::join!(left.recv(), right.recv());
+//!     println!("{a:?} {b:?}");
+//! }
+//! multi.close_all().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! With [`ReconnectConfig::resubscribe`] set, a session Cortex recreated
+//! after a reconnect keeps its receivers: events from the new session id
+//! are routed to them.
+//!
+//! [`ReconnectConfig::resubscribe`]: crate::config::ReconnectConfig::resubscribe
+//! [`CortexClient::create_stream_channels_for_session`]: crate::client::CortexClient::create_stream_channels_for_session
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::client::{CortexClient, StreamReceivers};
+use crate::error::{CortexError, CortexResult};
+use crate::protocol::session::SessionInfo;
+use crate::reconnect::{ConnectionEvent, ResilientClient};
+
+/// A session opened on one headset and the streams subscribed on it.
+#[derive(Debug, Clone)]
+struct HeadsetSession {
+    session: SessionInfo,
+    streams: Vec<&'static str>,
+}
+
+#[derive(Default)]
+struct Shared {
+    /// Sessions by headset ID.
+    sessions: Mutex<HashMap<String, HeadsetSession>>,
+}
+
+impl Shared {
+    fn sessions(&self) -> MutexGuard<'_, HashMap<String, HeadsetSession>> {
+        self.sessions
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Track `current` in place of `previous` after Cortex recreated a
+    /// session. The client moves the session's stream channels itself.
+    fn rekey(&self, previous: &str, current: &str) {
+        let mut sessions = self.sessions();
+        let Some(entry) = sessions
+            .values_mut()
+            .find(|entry| entry.session.id == previous)
+        else {
+            return;
+        };
+        entry.session.id = current.to_string();
+        tracing::info!(previous, current, "Tracking recreated session");
+    }
+}
+
+/// Concurrent sessions on several headsets over one [`ResilientClient`].
+/// See the [module docs](self).
+pub struct MultiSessionClient {
+    client: Arc<ResilientClient>,
+    shared: Arc<Shared>,
+    watcher: JoinHandle<()>,
+}
+
+impl MultiSessionClient {
+    /// Manage sessions opened through `client`.
+    ///
+    /// Must be called within a Tokio runtime.
+    #[must_use]
+    pub fn new(client: Arc<ResilientClient>) -> Self {
+        let shared = Arc::new(Shared::default());
+        let watcher = tokio::spawn(watch_resubscribes(
+            client.event_receiver(),
+            Arc::downgrade(&shared),
+        ));
+        Self {
+            client,
+            shared,
+            watcher,
+        }
+    }
+
+    /// The underlying client.
+    #[must_use]
+    pub fn client(&self) -> &Arc<ResilientClient> {
+        &self.client
+    }
+
+    /// Open a session on `headset_id`, or return the one already open.
+    ///
+    /// The headset must be connected; see
+    /// [`ResilientClient::connect_headset`].
+    ///
+    /// # Errors
+    /// Returns any error from `createSession`.
+    pub async fn open(&self, headset_id: &str) -> CortexResult<SessionInfo> {
+        if let Some(existing) = self.session(headset_id) {
+            return Ok(existing);
+        }
+        let session = self.client.create_session(headset_id).await?;
+        self.shared.sessions().insert(
+            headset_id.to_string(),
+            HeadsetSession {
+                session: session.clone(),
+                streams: Vec::new(),
+            },
+        );
+        Ok(session)
+    }
+
+    /// The session open on `headset_id`.
+    #[must_use]
+    pub fn session(&self, headset_id: &str) -> Option<SessionInfo> {
+        self.shared
+            .sessions()
+            .get(headset_id)
+            .map(|entry| entry.session.clone())
+    }
+
+    /// Open sessions as `(headset ID, session)` pairs.
+    #[must_use]
+    pub fn sessions(&self) -> Vec<(String, SessionInfo)> {
+        let mut sessions: Vec<(String, SessionInfo)> = self
+            .shared
+            .sessions()
+            .iter()
+            .map(|(headset, entry)| (headset.clone(), entry.session.clone()))
+            .collect();
+        sessions.sort_by(|a, b| a.0.cmp(&b.0));
+        sessions
+    }
+
+    /// Subscribe `streams` on the session open on `headset_id` and return
+    /// receivers that only see that session's events.
+    ///
+    /// Streams Cortex refused are left out of the returned receivers; the
+    /// call fails only if none could be subscribed.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::SessionError`] if no session is open on
+    /// `headset_id`, the first per-stream failure if every stream was
+    /// refused, or any error from `subscribe`.
+    pub async fn subscribe(
+        &self,
+        headset_id: &str,
+        streams: &[&str],
+    ) -> CortexResult<StreamReceivers> {
+        let session_id = self.require(headset_id)?.session.id;
+
+        let mut receivers = self
+            .client
+            .create_stream_channels_for_session(&session_id, streams)
+            .await;
+        let result = match self.client.subscribe_streams(&session_id, streams).await {
+            Ok(result) => result,
+            Err(e) => {
+                self.client
+                    .remove_session_stream_channels(&session_id, streams)
+                    .await;
+                return Err(e);
+            }
+        };
+        // The client already dropped the channels of refused streams.
+        for failure in &result.failure {
+            receivers.remove(CortexClient::stream_key(&failure.stream_name));
+        }
+        if let (true, Some(failure)) = (receivers.is_empty(), result.failure.first()) {
+            return Err(CortexError::from_api_error(
+                failure.code,
+                format!("stream '{}': {}", failure.stream_name, failure.message),
+            ));
+        }
+
+        if let Some(entry) = self.shared.sessions().get_mut(headset_id) {
+            for stream in &result.success {
+                let key = CortexClient::stream_key(&stream.stream_name);
+                if !entry.streams.contains(&key) {
+                    entry.streams.push(key);
+                }
+            }
+        }
+        Ok(receivers)
+    }
+
+    /// Unsubscribe `streams` on the session open on `headset_id`. Their
+    /// receivers end.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::SessionError`] if no session is open on
+    /// `headset_id`, or any error from `unsubscribe`.
+    pub async fn unsubscribe(&self, headset_id: &str, streams: &[&str]) -> CortexResult<()> {
+        let session_id = self.require(headset_id)?.session.id;
+        self.client
+            .unsubscribe_streams(&session_id, streams)
+            .await?;
+        self.client
+            .remove_session_stream_channels(&session_id, streams)
+            .await;
+        if let Some(entry) = self.shared.sessions().get_mut(headset_id) {
+            entry
+                .streams
+                .retain(|key| !streams.iter().any(|s| CortexClient::stream_key(s) == *key));
+        }
+        Ok(())
+    }
+
+    /// Unsubscribe everything on the session open on `headset_id` and
+    /// close it.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::SessionError`] if no session is open on
+    /// `headset_id`, or any error from `unsubscribe` or `updateSession`.
+    pub async fn close(&self, headset_id: &str) -> CortexResult<()> {
+        let entry = self.require(headset_id)?;
+        let session_id = entry.session.id;
+        if !entry.streams.is_empty() {
+            self.client
+                .unsubscribe_streams(&session_id, &entry.streams)
+                .await?;
+            self.client
+                .remove_session_stream_channels(&session_id, &entry.streams)
+                .await;
+        }
+        self.client.close_session(&session_id).await?;
+        self.shared.sessions().remove(headset_id);
+        Ok(())
+    }
+
+    /// Close every open session, continuing past failures.
+    ///
+    /// # Errors
+    /// Returns the first error encountered.
+    pub async fn close_all(&self) -> CortexResult<()> {
+        let headsets: Vec<String> = self.shared.sessions().keys().cloned().collect();
+        let mut first_error = None;
+        for headset in headsets {
+            if let Err(e) = self.close(&headset).await {
+                tracing::warn!(headset, error = %e, "Failed to close session");
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    fn require(&self, headset_id: &str) -> CortexResult<HeadsetSession> {
+        self.shared
+            .sessions()
+            .get(headset_id)
+            .cloned()
+            .ok_or_else(|| CortexError::SessionError {
+                reason: format!("No session open on headset {headset_id}"),
+            })
+    }
+}
+
+impl Drop for MultiSessionClient {
+    fn drop(&mut self) {
+        self.watcher.abort();
+    }
+}
+
+/// Follow sessions Cortex recreated while re-subscribing after a
+/// reconnect.
+async fn watch_resubscribes(
+    mut events: broadcast::Receiver<ConnectionEvent>,
+    shared: std::sync::Weak<Shared>,
+) {
+    loop {
+        match events.recv().await {
+            Ok(ConnectionEvent::Resubscribed {
+                previous_session_id,
+                session_id,
+                ..
+            }) if previous_session_id != session_id => {
+                let Some(shared) = shared.upgrade() else {
+                    break;
+                };
+                shared.rekey(&previous_session_id, &session_id);
+            }
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rekey_follows_recreated_session() {
+        let shared = Shared::default();
+        shared.sessions().insert(
+            "EPOCX-1".into(),
+            HeadsetSession {
+                session: serde_json::from_value(serde_json::json!({
+                    "id": "old", "status": "activated", "owner": "user", "license": "",
+                    "appId": "app", "started": "", "streams": [], "recordIds": [],
+                    "recording": false
+                }))
+                .unwrap(),
+                streams: vec!["eeg"],
+            },
+        );
+        shared.rekey("old", "new");
+
+        assert_eq!(shared.sessions()["EPOCX-1"].session.id, "new");
+        assert_eq!(shared.sessions()["EPOCX-1"].streams, vec!["eeg"]);
+    }
+}
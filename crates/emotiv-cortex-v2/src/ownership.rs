@@ -0,0 +1,158 @@
+//! # Session Ownership
+//!
+//! `querySessions` returns every session of the logged-in user, including
+//! sessions opened by other applications. Cleanup that closes all of them
+//! can stop another app's recording.
+//!
+//! A client remembers the sessions it created, and learns its Cortex
+//! application ID from them (or from [`CortexConfig::app_id`]). Every
+//! session is classified as a [`SessionOwner`], and cleanup helpers such
+//! as [`CortexClient::close_sessions`] only touch sessions allowed by a
+//! [`CleanupScope`] — by default those created by this client or by the
+//! same application, e.g. left over from a previous run.
+//! [`CleanupScope::All`] is the explicit override for admin tooling.
+//!
+//! [`CortexConfig::app_id`]: crate::CortexConfig::app_id
+//! [`CortexClient::close_sessions`]: crate::CortexClient::close_sessions
+
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::protocol::session::SessionInfo;
+
+/// Who opened a session, from this client's point of view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionOwner {
+    /// Created by this client.
+    ThisClient,
+    /// Created by another client of the same Cortex application.
+    ThisApp,
+    /// Created by another application, or the application is unknown.
+    OtherApp,
+}
+
+/// Which sessions a cleanup helper may close.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanupScope {
+    /// Sessions of this client or this application.
+    #[default]
+    Owned,
+    /// Every session of the user, including other applications'.
+    All,
+}
+
+impl CleanupScope {
+    /// Whether a session with this owner is in scope.
+    #[must_use]
+    pub fn allows(self, owner: SessionOwner) -> bool {
+        match self {
+            CleanupScope::Owned => owner != SessionOwner::OtherApp,
+            CleanupScope::All => true,
+        }
+    }
+}
+
+/// Sessions created by one client, and the application they belong to.
+#[derive(Debug, Default)]
+pub(crate) struct SessionRegistry {
+    app_id: Mutex<Option<String>>,
+    created: Mutex<BTreeSet<String>>,
+}
+
+impl SessionRegistry {
+    pub(crate) fn new(app_id: Option<String>) -> Self {
+        Self {
+            app_id: Mutex::new(app_id.filter(|id| !id.is_empty())),
+            created: Mutex::new(BTreeSet::new()),
+        }
+    }
+
+    pub(crate) fn created(&self, session: &SessionInfo) {
+        if let Ok(mut created) = self.created.lock() {
+            created.insert(session.id.clone());
+        }
+        if !session.app_id.is_empty() {
+            if let Ok(mut app_id) = self.app_id.lock() {
+                app_id.get_or_insert_with(|| session.app_id.clone());
+            }
+        }
+    }
+
+    pub(crate) fn closed(&self, session_id: &str) {
+        if let Ok(mut created) = self.created.lock() {
+            created.remove(session_id);
+        }
+    }
+
+    pub(crate) fn app_id(&self) -> Option<String> {
+        self.app_id.lock().ok().and_then(|id| id.clone())
+    }
+
+    pub(crate) fn created_ids(&self) -> Vec<String> {
+        self.created
+            .lock()
+            .map(|c| c.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn owner(&self, session: &SessionInfo) -> SessionOwner {
+        let created = self.created.lock().is_ok_and(|c| c.contains(&session.id));
+        if created {
+            SessionOwner::ThisClient
+        } else if self
+            .app_id()
+            .is_some_and(|id| !session.app_id.is_empty() && id == session.app_id)
+        {
+            SessionOwner::ThisApp
+        } else {
+            SessionOwner::OtherApp
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str, app_id: &str) -> SessionInfo {
+        serde_json::from_value(serde_json::json!({
+            "id": id, "status": "activated", "owner": "user", "license": "",
+            "appId": app_id, "started": "", "streams": [], "recordIds": [],
+            "recording": false
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_owner_from_registry_and_learned_app_id() {
+        let registry = SessionRegistry::new(None);
+        let other = session("s-2", "com.other.app");
+        assert_eq!(registry.owner(&other), SessionOwner::OtherApp);
+
+        registry.created(&session("s-1", "com.example.app"));
+        assert_eq!(registry.app_id().as_deref(), Some("com.example.app"));
+        assert_eq!(
+            registry.owner(&session("s-1", "com.example.app")),
+            SessionOwner::ThisClient
+        );
+        assert_eq!(
+            registry.owner(&session("s-0", "com.example.app")),
+            SessionOwner::ThisApp
+        );
+        assert_eq!(registry.owner(&other), SessionOwner::OtherApp);
+
+        registry.closed("s-1");
+        assert!(registry.created_ids().is_empty());
+    }
+
+    #[test]
+    fn test_scope_never_includes_other_apps_by_default() {
+        assert!(!CleanupScope::default().allows(SessionOwner::OtherApp));
+        assert!(CleanupScope::Owned.allows(SessionOwner::ThisApp));
+        assert!(CleanupScope::All.allows(SessionOwner::OtherApp));
+    }
+}
@@ -1,6 +1,9 @@
 //! Authentication-related protocol types.
 
-use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
 
 /// User login info from `getUserLogin`.
 #[derive(Debug, Clone, Deserialize)]
@@ -14,3 +17,257 @@ pub struct UserLoginInfo {
     #[serde(rename = "lastLoginTime")]
     pub last_login_time: Option<String>,
 }
+
+/// Authorized user from `getUserInformation`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserInfo {
+    /// Emotiv account username.
+    pub username: String,
+    /// Account email address.
+    #[serde(alias = "emailAddress")]
+    pub email: Option<String>,
+    /// Given name.
+    pub first_name: Option<String>,
+    /// Family name.
+    pub last_name: Option<String>,
+    /// Forward-compatible storage for new optional fields.
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Result of `getLicenseInfo`: the license the application is using.
+///
+/// Check it before subscribing, since Cortex refuses `eeg` and other
+/// raw-data streams without a license that grants them:
+///
+/// ```
+/// use emotiv_cortex_v2::protocol::auth::LicenseInfo;
+///
+/// let info: LicenseInfo = serde_json::from_str(r#"{
+///     "isOnline": true,
+///     "license": {
+///         "licenseId": "lic-1",
+///         "scopes": ["pm", "eeg"],
+///         "validTo": "2099-01-01T00:00:00.000+07:00",
+///         "expired": false,
+///         "localQuota": 5
+///     }
+/// }"#).unwrap();
+/// assert!(!info.is_expired());
+/// assert!(info.has_eeg_access());
+/// assert_eq!(info.license.local_quota, Some(5));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseInfo {
+    /// Whether Cortex could reach the Emotiv license server.
+    pub is_online: Option<bool>,
+    /// The license itself.
+    #[serde(default)]
+    pub license: License,
+    /// Forward-compatible storage for new optional fields.
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// License details within [`LicenseInfo`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct License {
+    /// License ID, as passed to `authorize`.
+    pub license_id: Option<String>,
+    /// Human-readable license name.
+    pub license_name: Option<String>,
+    /// Data the license grants, e.g. `"eeg"` and `"pm"` (performance
+    /// metrics).
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// Application IDs the license is valid for.
+    #[serde(default)]
+    pub applications: Vec<String>,
+    /// Start of validity, ISO 8601.
+    pub valid_from: Option<String>,
+    /// End of validity, ISO 8601.
+    pub valid_to: Option<String>,
+    /// Whether Cortex considers the license expired.
+    #[serde(default)]
+    pub expired: bool,
+    /// Commercial rather than personal license.
+    pub is_commercial: Option<bool>,
+    /// Trial license.
+    pub is_trial: Option<bool>,
+    /// Debited sessions still available on this machine.
+    pub local_quota: Option<u32>,
+    /// Sessions debited so far.
+    pub total_debit: Option<u32>,
+    /// Most sessions one `authorize` may debit.
+    pub max_debit: Option<u32>,
+    /// Sessions used.
+    pub session_count: Option<u32>,
+    /// Seats (users) the license covers.
+    pub seat_count: Option<u32>,
+    /// Session count at which Cortex refuses new sessions.
+    pub hard_limit: Option<u32>,
+    /// Session count at which Cortex starts warning.
+    pub soft_limit: Option<u32>,
+    /// Headset limits.
+    pub device_info: Option<LicenseDeviceInfo>,
+    /// Forward-compatible storage for new optional fields.
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Headset limits of a [`License`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseDeviceInfo {
+    /// Headsets the license may be used with.
+    pub device_limit: Option<u32>,
+    /// Headsets per user.
+    pub device_per_user: Option<u32>,
+    /// Session limits per period (`day`, `month`, `year`); `null` means
+    /// unlimited.
+    pub session_limit: Option<serde_json::Value>,
+    /// Forward-compatible storage for new optional fields.
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl LicenseInfo {
+    /// End of validity, if Cortex reported a parseable `validTo`.
+    #[must_use]
+    pub fn valid_until(&self) -> Option<SystemTime> {
+        parse_timestamp(self.license.valid_to.as_deref()?)
+    }
+
+    /// Whether the license has expired: Cortex says so, or `validTo` has
+    /// passed.
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(SystemTime::now())
+    }
+
+    /// [`Self::is_expired`] as of `now`.
+    #[must_use]
+    pub fn is_expired_at(&self, now: SystemTime) -> bool {
+        self.license.expired || self.valid_until().is_some_and(|until| until <= now)
+    }
+
+    /// Whether the license grants `scope` (e.g. `"eeg"`, `"pm"`).
+    #[must_use]
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.license
+            .scopes
+            .iter()
+            .any(|s| s.eq_ignore_ascii_case(scope))
+    }
+
+    /// Whether raw EEG (and the other raw-data streams) can be
+    /// subscribed: the license is current and grants the `eeg` scope.
+    #[must_use]
+    pub fn has_eeg_access(&self) -> bool {
+        !self.is_expired() && self.has_scope("eeg")
+    }
+}
+
+/// Parse an ISO 8601 timestamp such as `2024-03-15T08:30:00.000+07:00`.
+/// A missing offset is read as UTC.
+fn parse_timestamp(text: &str) -> Option<SystemTime> {
+    let field = |range: std::ops::Range<usize>| text.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (field(0..4)?, field(5..7)?, field(8..10)?);
+    let (hour, minute, second) = if text.len() >= 19 {
+        (field(11..13)?, field(14..16)?, field(17..19)?)
+    } else {
+        (0, 0, 0)
+    };
+
+    // Offset: the last `Z`, `+HH:MM`, or `-HH:MM` after the time.
+    let rest = text.get(19..).unwrap_or("");
+    let offset = match rest.rfind(['+', '-']) {
+        Some(i) => {
+            let sign = if rest[i..].starts_with('-') { -1 } else { 1 };
+            let hours = rest.get(i + 1..i + 3)?.parse::<i64>().ok()?;
+            let minutes = rest.get(i + 4..i + 6).map_or(Some(0), |m| m.parse().ok())?;
+            sign * (hours * 3600 + minutes * 60)
+        }
+        None => 0,
+    };
+
+    let secs =
+        days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second - offset;
+    Some(UNIX_EPOCH + Duration::from_secs(u64::try_from(secs).ok()?))
+}
+
+/// Days since the Unix epoch of a proleptic Gregorian date, after Howard
+/// Hinnant's `days_from_civil`.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn license(valid_to: &str, expired: bool) -> LicenseInfo {
+        serde_json::from_value(serde_json::json!({
+            "isOnline": true,
+            "license": {
+                "licenseId": "lic-1",
+                "scopes": ["pm"],
+                "validTo": valid_to,
+                "expired": expired,
+                "deviceInfo": {"deviceLimit": 3, "sessionLimit": {"day": null}},
+                "extenderLimit": 15
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_timestamp_applies_offset() {
+        let utc = parse_timestamp("2024-03-15T08:30:00Z").unwrap();
+        assert_eq!(utc, UNIX_EPOCH + Duration::from_secs(1_710_491_400));
+        let offset = parse_timestamp("2024-03-15T15:30:00.000+07:00").unwrap();
+        assert_eq!(offset, utc);
+        assert_eq!(
+            parse_timestamp("1970-01-02"),
+            Some(UNIX_EPOCH + Duration::from_secs(86_400))
+        );
+        assert_eq!(parse_timestamp("soon"), None);
+    }
+
+    #[test]
+    fn test_license_expiry_and_scopes() {
+        let current = license("2024-03-15T08:30:00Z", false);
+        let before = UNIX_EPOCH + Duration::from_secs(1_710_000_000);
+        assert!(!current.is_expired_at(before));
+        assert!(current.is_expired_at(before + Duration::from_secs(1_000_000)));
+        assert!(license("2099-01-01T00:00:00Z", true).is_expired_at(before));
+
+        assert!(current.has_scope("PM"));
+        assert!(!current.has_eeg_access());
+        assert_eq!(
+            current.license.device_info.as_ref().unwrap().device_limit,
+            Some(3)
+        );
+        assert!(current.license.extra.contains_key("extenderLimit"));
+    }
+
+    #[test]
+    fn test_deserialize_user_info() {
+        let info: UserInfo = serde_json::from_str(
+            r#"{"username": "jdoe", "emailAddress": "j@example.com", "firstName": "J"}"#,
+        )
+        .unwrap();
+        assert_eq!(info.username, "jdoe");
+        assert_eq!(info.email.as_deref(), Some("j@example.com"));
+        assert!(info.extra.is_empty());
+    }
+}
@@ -53,6 +53,12 @@ impl Methods {
     /// Synchronize system time with headset clock.
     pub const SYNC_WITH_HEADSET_CLOCK: &'static str = "syncWithHeadsetClock";
 
+    /// Create a virtual (simulated) headset for development.
+    pub const CREATE_VIRTUAL_HEADSET: &'static str = "createVirtualHeadset";
+
+    /// Delete a virtual headset created with `createVirtualHeadset`.
+    pub const DELETE_VIRTUAL_HEADSET: &'static str = "deleteVirtualHeadset";
+
     // ─── Session Management ─────────────────────────────────────────
     /// Create a session (associates a headset with a cortex token).
     pub const CREATE_SESSION: &'static str = "createSession";
@@ -177,9 +183,21 @@ pub struct ErrorCodes;
 
 impl ErrorCodes {
     // ─── JSON-RPC standard errors ────────────────────────────────────
+    /// The request was not valid JSON.
+    pub const PARSE_ERROR: i32 = -32700;
+
+    /// The request was not a valid JSON-RPC request object.
+    pub const INVALID_REQUEST: i32 = -32600;
+
     /// Method not found (unknown or deprecated method name).
     pub const METHOD_NOT_FOUND: i32 = -32601;
 
+    /// Missing or malformed method parameters.
+    pub const INVALID_PARAMS: i32 = -32602;
+
+    /// Internal Cortex error while handling the request.
+    pub const INTERNAL_ERROR: i32 = -32603;
+
     // ─── Cortex-specific errors ──────────────────────────────────────
     /// No headset connected.
     pub const NO_HEADSET_CONNECTED: i32 = -32001;
@@ -193,6 +211,9 @@ impl ErrorCodes {
     /// Session already exists.
     pub const SESSION_ALREADY_EXISTS: i32 = -32005;
 
+    /// Session does not exist (closed, or never created).
+    pub const SESSION_NOT_FOUND: i32 = -32007;
+
     /// Session must be activated before this operation.
     pub const SESSION_MUST_BE_ACTIVATED: i32 = -32012;
 
@@ -205,21 +226,58 @@ impl ErrorCodes {
     /// Invalid stream for subscribe/unsubscribe.
     pub const INVALID_STREAM: i32 = -32016;
 
+    /// Session limit on this device has been reached.
+    pub const SESSION_LIMIT_REACHED: i32 = -32019;
+
     /// Invalid client credentials.
     pub const INVALID_CLIENT_CREDENTIALS: i32 = -32021;
 
     /// License expired or unavailable.
     pub const LICENSE_EXPIRED: i32 = -32024;
 
+    /// Invalid debit number passed to `authorize`.
+    pub const INVALID_DEBIT: i32 = -32027;
+
+    /// The license does not include the requested stream.
+    pub const STREAM_NOT_LICENSED: i32 = -32029;
+
+    /// The user has not granted the application access rights.
+    pub const NO_ACCESS_RIGHTS: i32 = -32032;
+
     /// User not logged in to `EmotivID` in the Launcher.
     pub const USER_NOT_LOGGED_IN: i32 = -32033;
 
+    /// The user has not accepted the EMOTIV end-user license agreement.
+    pub const EULA_NOT_ACCEPTED: i32 = -32034;
+
+    /// The profile is loaded by another application.
+    pub const PROFILE_IN_USE: i32 = -32045;
+
+    /// No profile is loaded for the headset.
+    pub const PROFILE_NOT_LOADED: i32 = -32046;
+
+    /// Record does not exist.
+    pub const RECORD_NOT_FOUND: i32 = -32104;
+
+    /// Marker time falls outside the record.
+    pub const MARKER_OUTSIDE_RECORD: i32 = -32107;
+
+    /// A subject with this name already exists.
+    pub const SUBJECT_ALREADY_EXISTS: i32 = -32110;
+
     /// Application is unpublished/unapproved for this account.
     pub const UNPUBLISHED_APPLICATION: i32 = -32142;
 
     /// Headset not ready yet.
     pub const HEADSET_NOT_READY: i32 = -32152;
 
+    // ─── Legacy errors ───────────────────────────────────────────────
+    /// Application not approved (older Cortex releases).
+    pub const LEGACY_NOT_APPROVED: i32 = -32102;
+
+    /// Cortex service still starting (older Cortex releases).
+    pub const LEGACY_CORTEX_STARTING: i32 = -32122;
+
     // Backward-compatible aliases for older naming.
     pub const ACCESS_DENIED: i32 = Self::INVALID_LICENSE_ID;
     pub const HEADSET_IN_USE: i32 = Self::SESSION_MUST_BE_ACTIVATED;
@@ -227,6 +285,58 @@ impl ErrorCodes {
     pub const CORTEX_STARTING: i32 = Self::HEADSET_NOT_READY;
 }
 
+// ─── Warning Codes ──────────────────────────────────────────────────────
+
+/// Codes of the unsolicited `{"warning": {"code": ..., "message": ...}}`
+/// objects Cortex pushes on the WebSocket.
+pub struct WarningCodes;
+
+impl WarningCodes {
+    /// Cortex stopped every stream of a session.
+    pub const CORTEX_STOP_ALL_STREAMS: i32 = 0;
+    /// Cortex closed a session.
+    pub const CORTEX_CLOSE_SESSION: i32 = 1;
+    /// A user logged in to the Launcher.
+    pub const USER_LOGIN: i32 = 2;
+    /// The user logged out of the Launcher.
+    pub const USER_LOGOUT: i32 = 3;
+    /// The user approved the application's access request.
+    pub const ACCESS_RIGHT_GRANTED: i32 = 9;
+    /// The user rejected the application's access request.
+    pub const ACCESS_RIGHT_REJECTED: i32 = 10;
+    /// A profile was loaded for a headset.
+    pub const PROFILE_LOADED: i32 = 13;
+    /// A profile was unloaded from a headset.
+    pub const PROFILE_UNLOADED: i32 = 14;
+    /// Cortex unloaded a profile on its own, e.g. when the headset
+    /// disconnected or another application took it.
+    pub const CORTEX_AUTO_UNLOAD_PROFILE: i32 = 15;
+    /// The user accepted the EULA.
+    pub const EULA_ACCEPTED: i32 = 17;
+    /// Disk space for records is running low.
+    pub const DISKSPACE_LOW: i32 = 19;
+    /// Disk space for records is critically low.
+    pub const DISKSPACE_CRITICAL: i32 = 20;
+    /// Progress of a record export started with `exportRecord`. The
+    /// message names the record and its progress in percent.
+    pub const RECORD_EXPORT_PROGRESS: i32 = 31;
+    /// A record export finished. The message names the record and either
+    /// the files written or an error.
+    pub const RECORD_EXPORT_DONE: i32 = 32;
+    /// A headset could not connect within the timeout.
+    pub const HEADSET_CANNOT_CONNECT_TIMEOUT: i32 = 102;
+    /// A headset was disconnected for too long.
+    pub const HEADSET_DISCONNECTED_TIMEOUT: i32 = 103;
+    /// A headset connected.
+    pub const HEADSET_CONNECTED: i32 = 104;
+    /// A headset cannot work over Bluetooth Low Energy.
+    pub const HEADSET_CANNOT_WORK_WITH_BTLE: i32 = 112;
+    /// A headset cannot connect while motion is disabled.
+    pub const HEADSET_CANNOT_CONNECT_DISABLE_MOTION: i32 = 113;
+    /// Headset scanning (`refreshHeadsetList`) finished.
+    pub const HEADSET_SCANNING_FINISHED: i32 = 142;
+}
+
 // ─── Stream Names ───────────────────────────────────────────────────────
 
 /// Known Cortex data stream names for subscribe/unsubscribe.
@@ -2,7 +2,7 @@
 
 use std::collections::HashMap;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// Options for the `queryHeadsets` method.
 #[derive(Debug, Clone, Default)]
@@ -14,7 +14,7 @@ pub struct QueryHeadsetsOptions {
 }
 
 /// Headset info returned by `queryHeadsets`.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HeadsetInfo {
     /// Headset ID (e.g., "INSIGHT-A1B2C3D4").
     pub id: String,
@@ -120,6 +120,36 @@ pub struct HeadsetClockSyncResult {
     pub headset: String,
 }
 
+/// Options for the `createVirtualHeadset` method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VirtualHeadsetOptions {
+    /// Number of simulated EEG channels (clamped by Cortex to what it
+    /// supports; 5 mimics an Insight, 14 an EPOC X).
+    pub channel_count: usize,
+    /// Simulated EEG sampling rate in Hz.
+    pub sampling_rate_hz: u32,
+    /// Optional display name for the virtual device.
+    pub custom_name: Option<String>,
+}
+
+impl VirtualHeadsetOptions {
+    /// Options for a 128 Hz virtual headset with `channel_count` channels.
+    #[must_use]
+    pub fn new(channel_count: usize) -> Self {
+        Self {
+            channel_count,
+            sampling_rate_hz: 128,
+            custom_name: None,
+        }
+    }
+}
+
+impl Default for VirtualHeadsetOptions {
+    fn default() -> Self {
+        Self::new(14)
+    }
+}
+
 /// Type of operation requested for `configMapping`.
 #[derive(Debug, Clone, Copy)]
 pub enum ConfigMappingMode {
@@ -322,6 +352,7 @@ mod tests {
         assert_eq!(sync.headset, "INSIGHT-A1B2C3D4");
     }
     #[test]
+    #[allow(clippy::items_after_statements)]
     fn test_deserialize_config_mapping_value_response_shapes() {
         let value_json = r#"{
             "message": "Create flex mapping config successful",
@@ -11,14 +11,18 @@
 //! - [`training`]: detection/training and advanced BCI payloads.
 //! - [`auth`]: authentication/user-login payloads.
 //! - [`subjects`]: subject/demographic payloads.
+//! - [`query`]: typed filters and sort orders shared by the query requests.
+//! - [`warnings`]: unsolicited warning pushes.
 
 pub mod auth;
 pub mod constants;
 pub mod headset;
 pub mod profiles;
+pub mod query;
 pub mod records;
 pub mod rpc;
 pub mod session;
 pub mod streams;
 pub mod subjects;
 pub mod training;
+pub mod warnings;
@@ -2,7 +2,9 @@
 
 use std::collections::HashMap;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use super::constants::WarningCodes;
 
 /// Profile information from `queryProfile`.
 #[derive(Debug, Clone, Deserialize)]
@@ -67,6 +69,57 @@ impl ProfileAction {
     }
 }
 
+/// A profile was unloaded from a headset, as reported by a Cortex
+/// `PROFILE_UNLOADED` or `CORTEX_AUTO_UNLOAD_PROFILE` warning.
+///
+/// Mental-command and facial-expression detections stop producing
+/// meaningful data until a profile is loaded again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProfileUnloaded {
+    /// Headset the profile was unloaded from, when the warning names it.
+    pub headset_id: Option<String>,
+    /// Unloaded profile, when the warning names it.
+    pub profile: Option<String>,
+    /// `true` when Cortex unloaded the profile on its own rather than at
+    /// an application's or the user's request.
+    pub automatic: bool,
+}
+
+impl ProfileUnloaded {
+    /// Recognise a profile-unload warning in a raw WebSocket message.
+    ///
+    /// Returns `None` for any other message. The warning `message` may be
+    /// an object (`headsetId`/`headset`, `profileName`/`profile`) or plain
+    /// text, in which case only `automatic` is known.
+    #[must_use]
+    pub fn from_warning(value: &serde_json::Value) -> Option<Self> {
+        let warning = value.get("warning")?;
+        let code = warning.get("code").and_then(serde_json::Value::as_i64)?;
+        let automatic = if code == i64::from(WarningCodes::CORTEX_AUTO_UNLOAD_PROFILE) {
+            true
+        } else if code == i64::from(WarningCodes::PROFILE_UNLOADED) {
+            false
+        } else {
+            return None;
+        };
+
+        let message = warning.get("message");
+        let field = |keys: &[&str]| {
+            keys.iter().find_map(|key| {
+                message
+                    .and_then(|m| m.get(*key))
+                    .and_then(serde_json::Value::as_str)
+                    .map(str::to_string)
+            })
+        };
+        Some(Self {
+            headset_id: field(&["headsetId", "headset"]),
+            profile: field(&["profileName", "profile"]),
+            automatic,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -127,4 +180,35 @@ mod tests {
         assert_eq!(ProfileAction::Rename.as_str(), "rename");
         assert_eq!(ProfileAction::Delete.as_str(), "delete");
     }
+
+    #[test]
+    fn test_profile_unloaded_from_warning() {
+        let event = ProfileUnloaded::from_warning(&serde_json::json!({
+            "warning": {
+                "code": WarningCodes::CORTEX_AUTO_UNLOAD_PROFILE,
+                "message": {"headsetId": "INSIGHT-1", "profileName": "alice"}
+            }
+        }))
+        .unwrap();
+        assert_eq!(event.headset_id.as_deref(), Some("INSIGHT-1"));
+        assert_eq!(event.profile.as_deref(), Some("alice"));
+        assert!(event.automatic);
+
+        let event = ProfileUnloaded::from_warning(&serde_json::json!({
+            "warning": {"code": WarningCodes::PROFILE_UNLOADED, "message": "Profile unloaded"}
+        }))
+        .unwrap();
+        assert_eq!(event.headset_id, None);
+        assert!(!event.automatic);
+
+        assert!(
+            ProfileUnloaded::from_warning(&serde_json::json!({
+                "warning": {"code": WarningCodes::PROFILE_LOADED, "message": {}}
+            }))
+            .is_none()
+        );
+        assert!(
+            ProfileUnloaded::from_warning(&serde_json::json!({"com": ["neutral", 0.0]})).is_none()
+        );
+    }
 }
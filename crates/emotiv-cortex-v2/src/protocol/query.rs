@@ -0,0 +1,439 @@
+//! Typed query filters and sort orders.
+//!
+//! `querySubjects` and `queryRecords` take a MongoDB-style `query` object
+//! and an `orderBy` array keyed by Cortex's camelCase field names.
+//! [`Filter`] and [`Order`] build those fragments so callers don't write
+//! the JSON by hand:
+//!
+//! ```
+//! use emotiv_cortex_v2::protocol::query::{Filter, Order};
+//! use emotiv_cortex_v2::protocol::subjects::QuerySubjectsRequest;
+//!
+//! let request = QuerySubjectsRequest::default()
+//!     .filter(Filter::eq("sex", "M").and(Filter::gt("experimentsCount", 0)))
+//!     .order(Order::asc("subjectName"))
+//!     .limit(20);
+//!
+//! assert_eq!(request.query, serde_json::json!({
+//!     "sex": "M",
+//!     "experimentsCount": { "$gt": 0 },
+//! }));
+//! assert_eq!(request.order_by, serde_json::json!([{ "subjectName": "ASC" }]));
+//! ```
+//!
+//! `querySessions` has no server-side query, so
+//! [`QuerySessionsRequest`](super::session::QuerySessionsRequest) applies
+//! the same types locally with [`Filter::matches`] and [`Order::sort`].
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
+
+/// Comparison operators understood by Cortex queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Op {
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    In,
+}
+
+impl Op {
+    fn key(self) -> &'static str {
+        match self {
+            Self::Ne => "$ne",
+            Self::Gt => "$gt",
+            Self::Gte => "$gte",
+            Self::Lt => "$lt",
+            Self::Lte => "$lte",
+            Self::In => "$in",
+        }
+    }
+
+    fn test(self, actual: &Value, operand: &Value) -> bool {
+        match self {
+            Self::Ne => actual != operand,
+            Self::Gt => compare(actual, operand) == Some(Ordering::Greater),
+            Self::Gte => matches!(
+                compare(actual, operand),
+                Some(Ordering::Greater | Ordering::Equal)
+            ),
+            Self::Lt => compare(actual, operand) == Some(Ordering::Less),
+            Self::Lte => matches!(
+                compare(actual, operand),
+                Some(Ordering::Less | Ordering::Equal)
+            ),
+            Self::In => operand
+                .as_array()
+                .is_some_and(|values| values.contains(actual)),
+        }
+    }
+}
+
+/// The condition on one field.
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    /// Field equals the value; serialized as the bare value.
+    Eq(Value),
+    /// Field satisfies every operator; serialized as `{"$op": value}`.
+    Ops(BTreeMap<Op, Value>),
+}
+
+impl Condition {
+    fn to_value(&self) -> Value {
+        match self {
+            Self::Eq(value) => value.clone(),
+            Self::Ops(ops) => Value::Object(
+                ops.iter()
+                    .map(|(op, value)| (op.key().to_string(), value.clone()))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn test(&self, actual: Option<&Value>) -> bool {
+        let actual = actual.unwrap_or(&Value::Null);
+        match self {
+            Self::Eq(value) => actual == value,
+            Self::Ops(ops) => ops.iter().all(|(op, operand)| op.test(actual, operand)),
+        }
+    }
+}
+
+/// A query filter: a conjunction of per-field conditions.
+///
+/// Serializes to the object Cortex expects as `query`. The empty filter
+/// (the [`Default`]) matches everything.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Filter {
+    fields: BTreeMap<String, Condition>,
+}
+
+impl Filter {
+    /// A filter that matches everything.
+    #[must_use]
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// `field` equals `value`.
+    pub fn eq(field: impl Into<String>, value: impl Into<Value>) -> Self {
+        Self::single(field, Condition::Eq(value.into()))
+    }
+
+    /// `field` does not equal `value`.
+    pub fn ne(field: impl Into<String>, value: impl Into<Value>) -> Self {
+        Self::op(field, Op::Ne, value.into())
+    }
+
+    /// `field` is greater than `value`.
+    pub fn gt(field: impl Into<String>, value: impl Into<Value>) -> Self {
+        Self::op(field, Op::Gt, value.into())
+    }
+
+    /// `field` is greater than or equal to `value`.
+    pub fn gte(field: impl Into<String>, value: impl Into<Value>) -> Self {
+        Self::op(field, Op::Gte, value.into())
+    }
+
+    /// `field` is less than `value`.
+    pub fn lt(field: impl Into<String>, value: impl Into<Value>) -> Self {
+        Self::op(field, Op::Lt, value.into())
+    }
+
+    /// `field` is less than or equal to `value`.
+    pub fn lte(field: impl Into<String>, value: impl Into<Value>) -> Self {
+        Self::op(field, Op::Lte, value.into())
+    }
+
+    /// `field` equals one of `values`.
+    pub fn one_of<V: Into<Value>>(
+        field: impl Into<String>,
+        values: impl IntoIterator<Item = V>,
+    ) -> Self {
+        let values = values.into_iter().map(Into::into).collect();
+        Self::op(field, Op::In, Value::Array(values))
+    }
+
+    /// Both `self` and `other` must match.
+    ///
+    /// Operators on the same field combine, so
+    /// `Filter::gte("startDatetime", a).and(Filter::lt("startDatetime", b))`
+    /// is a range. A later condition replaces an earlier equality, or an
+    /// earlier use of the same operator, on the same field.
+    #[must_use]
+    pub fn and(mut self, other: Filter) -> Self {
+        for (field, condition) in other.fields {
+            match (self.fields.get_mut(&field), condition) {
+                (Some(Condition::Ops(existing)), Condition::Ops(ops)) => existing.extend(ops),
+                (_, condition) => {
+                    self.fields.insert(field, condition);
+                }
+            }
+        }
+        self
+    }
+
+    /// Whether the filter has no conditions.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// The `query` object to send to Cortex.
+    #[must_use]
+    pub fn to_value(&self) -> Value {
+        Value::Object(
+            self.fields
+                .iter()
+                .map(|(field, condition)| (field.clone(), condition.to_value()))
+                .collect(),
+        )
+    }
+
+    /// Whether `item` (a JSON object as Cortex returns it) satisfies the
+    /// filter. Dotted field names such as `headset.id` address nested
+    /// objects.
+    ///
+    /// Used for queries Cortex cannot run server-side. Ordering operators
+    /// compare numbers numerically and strings lexicographically, which is
+    /// chronological for Cortex's ISO 8601 timestamps.
+    #[must_use]
+    pub fn matches(&self, item: &Value) -> bool {
+        self.fields
+            .iter()
+            .all(|(field, condition)| condition.test(lookup(item, field)))
+    }
+
+    fn single(field: impl Into<String>, condition: Condition) -> Self {
+        Self {
+            fields: BTreeMap::from([(field.into(), condition)]),
+        }
+    }
+
+    fn op(field: impl Into<String>, op: Op, value: Value) -> Self {
+        Self::single(field, Condition::Ops(BTreeMap::from([(op, value)])))
+    }
+}
+
+impl Serialize for Filter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_value().serialize(serializer)
+    }
+}
+
+impl From<Filter> for Value {
+    fn from(filter: Filter) -> Self {
+        filter.to_value()
+    }
+}
+
+/// Sort direction of one [`Order`] key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Smallest first.
+    Asc,
+    /// Largest first.
+    Desc,
+}
+
+impl Direction {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC",
+        }
+    }
+}
+
+/// A sort order: one or more fields, each ascending or descending.
+///
+/// Serializes to the array Cortex expects as `orderBy`, e.g.
+/// `[{"startDatetime": "DESC"}]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Order {
+    keys: Vec<(String, Direction)>,
+}
+
+impl Order {
+    /// Sort by `field` ascending.
+    pub fn asc(field: impl Into<String>) -> Self {
+        Self::default().then_asc(field)
+    }
+
+    /// Sort by `field` descending.
+    pub fn desc(field: impl Into<String>) -> Self {
+        Self::default().then_desc(field)
+    }
+
+    /// Break ties by `field` ascending.
+    #[must_use]
+    pub fn then_asc(mut self, field: impl Into<String>) -> Self {
+        self.keys.push((field.into(), Direction::Asc));
+        self
+    }
+
+    /// Break ties by `field` descending.
+    #[must_use]
+    pub fn then_desc(mut self, field: impl Into<String>) -> Self {
+        self.keys.push((field.into(), Direction::Desc));
+        self
+    }
+
+    /// The sort keys in priority order.
+    #[must_use]
+    pub fn keys(&self) -> &[(String, Direction)] {
+        &self.keys
+    }
+
+    /// The `orderBy` array to send to Cortex.
+    #[must_use]
+    pub fn to_value(&self) -> Value {
+        Value::Array(
+            self.keys
+                .iter()
+                .map(|(field, direction)| {
+                    let mut key = Map::new();
+                    key.insert(field.clone(), Value::from(direction.as_str()));
+                    Value::Object(key)
+                })
+                .collect(),
+        )
+    }
+
+    /// Compare two JSON objects by this order. Missing and incomparable
+    /// values sort last in either direction.
+    #[must_use]
+    pub fn compare(&self, a: &Value, b: &Value) -> Ordering {
+        for (field, direction) in &self.keys {
+            let ordering = match (lookup(a, field), lookup(b, field)) {
+                (Some(x), Some(y)) if !x.is_null() && !y.is_null() => {
+                    let ordering = compare(x, y).unwrap_or(Ordering::Equal);
+                    match direction {
+                        Direction::Asc => ordering,
+                        Direction::Desc => ordering.reverse(),
+                    }
+                }
+                (Some(x), _) if !x.is_null() => Ordering::Less,
+                (_, Some(y)) if !y.is_null() => Ordering::Greater,
+                _ => Ordering::Equal,
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        Ordering::Equal
+    }
+
+    /// Stable-sort JSON objects by this order.
+    pub fn sort(&self, items: &mut [Value]) {
+        items.sort_by(|a, b| self.compare(a, b));
+    }
+}
+
+impl Serialize for Order {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_value().serialize(serializer)
+    }
+}
+
+impl From<Order> for Value {
+    fn from(order: Order) -> Self {
+        order.to_value()
+    }
+}
+
+/// Resolve a dotted field path in a JSON object.
+fn lookup<'a>(item: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(item, |value, key| value.get(key))
+}
+
+fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64()?.partial_cmp(&y.as_f64()?),
+        (Value::String(x), Value::String(y)) => Some(x.cmp(y)),
+        (Value::Bool(x), Value::Bool(y)) => Some(x.cmp(y)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_filter_serializes_to_cortex_query() {
+        let filter = Filter::eq("sex", "M")
+            .and(Filter::gte("startDatetime", "2024-01-01"))
+            .and(Filter::lt("startDatetime", "2024-02-01"))
+            .and(Filter::one_of("countryCode", ["US", "GB"]));
+
+        assert_eq!(
+            filter.to_value(),
+            json!({
+                "sex": "M",
+                "startDatetime": { "$gte": "2024-01-01", "$lt": "2024-02-01" },
+                "countryCode": { "$in": ["US", "GB"] },
+            })
+        );
+        assert_eq!(serde_json::to_value(&filter).unwrap(), filter.to_value());
+        assert_eq!(Filter::all().to_value(), json!({}));
+    }
+
+    #[test]
+    fn test_filter_and_replaces_conflicting_conditions() {
+        let filter = Filter::eq("sex", "M").and(Filter::eq("sex", "F"));
+        assert_eq!(filter.to_value(), json!({ "sex": "F" }));
+
+        let filter = Filter::gt("experimentsCount", 1).and(Filter::gt("experimentsCount", 5));
+        assert_eq!(
+            filter.to_value(),
+            json!({ "experimentsCount": { "$gt": 5 } })
+        );
+    }
+
+    #[test]
+    fn test_filter_matches_locally() {
+        let session = json!({
+            "status": "activated",
+            "started": "2024-03-01T10:00:00Z",
+            "headset": { "id": "INSIGHT-1" },
+        });
+
+        assert!(Filter::all().matches(&session));
+        assert!(Filter::eq("headset.id", "INSIGHT-1").matches(&session));
+        assert!(
+            Filter::one_of("status", ["opened", "activated"])
+                .and(Filter::gte("started", "2024-01-01"))
+                .matches(&session)
+        );
+        assert!(!Filter::ne("status", "activated").matches(&session));
+        assert!(!Filter::lt("started", "2024-01-01").matches(&session));
+        assert!(!Filter::eq("stopped", "2024-03-01").matches(&session));
+        assert!(Filter::gt("count", 1).matches(&json!({ "count": 2.5 })));
+    }
+
+    #[test]
+    fn test_order_serializes_and_sorts() {
+        let order = Order::desc("started").then_asc("id");
+        assert_eq!(
+            order.to_value(),
+            json!([{ "started": "DESC" }, { "id": "ASC" }])
+        );
+
+        let mut items = vec![
+            json!({ "id": "c" }),
+            json!({ "id": "b", "started": "2024-01-01" }),
+            json!({ "id": "a", "started": "2024-01-01" }),
+            json!({ "id": "d", "started": "2024-06-01" }),
+        ];
+        order.sort(&mut items);
+        let ids: Vec<_> = items.iter().map(|item| item["id"].clone()).collect();
+        assert_eq!(ids, [json!("d"), json!("a"), json!("b"), json!("c")]);
+    }
+}
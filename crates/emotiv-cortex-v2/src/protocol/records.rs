@@ -2,8 +2,10 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::protocol::query::{Filter, Order};
+
 /// Record information from `createRecord` / `queryRecords`.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecordInfo {
     /// Record UUID.
     pub uuid: String,
@@ -20,6 +22,73 @@ pub struct RecordInfo {
     pub end_datetime: Option<String>,
 }
 
+/// Request payload for `queryRecords`.
+///
+/// Defaults to every record, newest first. Filter on Cortex record
+/// fields such as `applicationId`, `licenseId`, `startDatetime`, or
+/// `keyword`:
+///
+/// ```
+/// use emotiv_cortex_v2::protocol::query::Filter;
+/// use emotiv_cortex_v2::protocol::records::QueryRecordsRequest;
+///
+/// let request = QueryRecordsRequest::default()
+///     .filter(Filter::gte("startDatetime", "2024-01-01T00:00:00Z"))
+///     .limit(50);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryRecordsRequest {
+    /// Query expression object.
+    pub query: serde_json::Value,
+    /// Sort order expression array.
+    pub order_by: serde_json::Value,
+    /// Optional pagination limit.
+    pub limit: Option<u32>,
+    /// Optional pagination offset.
+    pub offset: Option<u32>,
+}
+
+impl Default for QueryRecordsRequest {
+    fn default() -> Self {
+        Self {
+            query: serde_json::json!({}),
+            order_by: Order::desc("startDatetime").into(),
+            limit: None,
+            offset: None,
+        }
+    }
+}
+
+impl QueryRecordsRequest {
+    /// Replace the query with `filter`.
+    #[must_use]
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.query = filter.into();
+        self
+    }
+
+    /// Replace the sort order with `order`.
+    #[must_use]
+    pub fn order(mut self, order: Order) -> Self {
+        self.order_by = order.into();
+        self
+    }
+
+    /// Return at most `limit` records.
+    #[must_use]
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Skip the first `offset` matching records.
+    #[must_use]
+    pub fn offset(mut self, offset: u32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+}
+
 /// Marker information from `injectMarker`.
 #[derive(Debug, Clone, Deserialize)]
 pub struct MarkerInfo {
@@ -51,6 +120,163 @@ impl ExportFormat {
     }
 }
 
+/// Request payload for `exportRecord`.
+///
+/// Only the record IDs, folder, and format are required; the other
+/// parameters are omitted unless set:
+///
+/// ```
+/// use emotiv_cortex_v2::protocol::records::{ExportFormat, ExportRecordOptions};
+///
+/// let options = ExportRecordOptions::new(["record-1"], "/data/exports", ExportFormat::Csv)
+///     .stream_types(["EEG", "MOTION", "PM", "BP"])
+///     .version("V2")
+///     .include_demographics(true);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ExportRecordOptions {
+    /// Records to export.
+    pub record_ids: Vec<String>,
+    /// Destination folder, on the machine running Cortex.
+    pub folder: String,
+    /// Output format.
+    pub format: ExportFormat,
+    /// Data streams to include (`EEG`, `MOTION`, `PM`, `BP`, ...).
+    pub stream_types: Option<Vec<String>>,
+    /// CSV layout version (`V1` or `V2`).
+    pub version: Option<String>,
+    /// Only export records created under these licenses.
+    pub license_ids: Option<Vec<String>>,
+    /// Include the subject's demographic data.
+    pub include_demographics: Option<bool>,
+    /// Include the subject's survey answers.
+    pub include_survey: Option<bool>,
+    /// Include the extra information of markers.
+    pub include_marker_extra_infos: Option<bool>,
+    /// Include deprecated performance-metric columns.
+    pub include_deprecated_pm: Option<bool>,
+}
+
+impl ExportRecordOptions {
+    /// Export `record_ids` to `folder` as `format`.
+    pub fn new<I, S>(record_ids: I, folder: impl Into<String>, format: ExportFormat) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            record_ids: record_ids.into_iter().map(Into::into).collect(),
+            folder: folder.into(),
+            format,
+            stream_types: None,
+            version: None,
+            license_ids: None,
+            include_demographics: None,
+            include_survey: None,
+            include_marker_extra_infos: None,
+            include_deprecated_pm: None,
+        }
+    }
+
+    /// Export only these data streams.
+    #[must_use]
+    pub fn stream_types<I, S>(mut self, stream_types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.stream_types = Some(stream_types.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set the CSV layout version.
+    #[must_use]
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Only export records created under these licenses.
+    #[must_use]
+    pub fn license_ids<I, S>(mut self, license_ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.license_ids = Some(license_ids.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Include the subject's demographic data.
+    #[must_use]
+    pub fn include_demographics(mut self, include: bool) -> Self {
+        self.include_demographics = Some(include);
+        self
+    }
+
+    /// Include the subject's survey answers.
+    #[must_use]
+    pub fn include_survey(mut self, include: bool) -> Self {
+        self.include_survey = Some(include);
+        self
+    }
+
+    /// Include the extra information of markers.
+    #[must_use]
+    pub fn include_marker_extra_infos(mut self, include: bool) -> Self {
+        self.include_marker_extra_infos = Some(include);
+        self
+    }
+
+    /// Include deprecated performance-metric columns.
+    #[must_use]
+    pub fn include_deprecated_pm(mut self, include: bool) -> Self {
+        self.include_deprecated_pm = Some(include);
+        self
+    }
+}
+
+/// A record `exportRecord` exported.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ExportSuccess {
+    /// Record UUID.
+    #[serde(rename = "recordId")]
+    pub record_id: String,
+}
+
+/// A record `exportRecord` could not export.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ExportFailure {
+    /// Record UUID.
+    #[serde(rename = "recordId")]
+    pub record_id: String,
+    /// Cortex error code.
+    pub code: Option<i32>,
+    /// Why the export failed.
+    #[serde(default)]
+    pub message: String,
+}
+
+/// Result of `exportRecord`: which records were exported and which were
+/// not.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct ExportResult {
+    /// Records exported.
+    #[serde(default)]
+    pub success: Vec<ExportSuccess>,
+    /// Records not exported, with reasons.
+    #[serde(default)]
+    pub failure: Vec<ExportFailure>,
+}
+
+impl ExportResult {
+    /// Whether every requested record was exported.
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.failure.is_empty()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -79,6 +305,20 @@ mod tests {
         let marker: MarkerInfo = serde_json::from_str(json).unwrap();
         assert_eq!(marker.uuid, "marker-uuid-abc");
     }
+    #[test]
+    fn test_deserialize_export_result() {
+        let json = r#"{
+            "success": [{"recordId": "record-1"}],
+            "failure": [{"recordId": "record-2", "code": -32001, "message": "Record not found"}]
+        }"#;
+
+        let result: ExportResult = serde_json::from_str(json).unwrap();
+        assert_eq!(result.success[0].record_id, "record-1");
+        assert_eq!(result.failure[0].record_id, "record-2");
+        assert_eq!(result.failure[0].code, Some(-32001));
+        assert!(!result.is_complete());
+    }
+
     #[test]
     fn test_export_format_strings() {
         assert_eq!(ExportFormat::Csv.as_str(), "CSV");
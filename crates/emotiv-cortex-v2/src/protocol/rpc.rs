@@ -1,5 +1,8 @@
 //! JSON-RPC request/response protocol structures.
 
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
 use serde::{Deserialize, Serialize};
 
 /// A JSON-RPC 2.0 request to the Cortex API.
@@ -68,6 +71,123 @@ impl std::fmt::Display for RpcError {
     }
 }
 
+// ─── Request IDs ────────────────────────────────────────────────────────
+
+/// Source of JSON-RPC request ids for one client connection.
+///
+/// Cortex echoes the numeric `id` back in each response, so ids must be
+/// unique among in-flight requests on a connection. Implementations are
+/// shared across concurrent callers and must be thread-safe.
+pub trait RequestIdGenerator: Send + Sync {
+    /// Return the next request id.
+    fn next_id(&self) -> u64;
+}
+
+/// Per-connection counter starting at 1 (the default strategy).
+#[derive(Debug)]
+pub struct CounterIds {
+    next: AtomicU64,
+}
+
+impl CounterIds {
+    /// Create a counter whose first id is `first`.
+    #[must_use]
+    pub fn starting_at(first: u64) -> Self {
+        Self {
+            next: AtomicU64::new(first),
+        }
+    }
+}
+
+impl Default for CounterIds {
+    fn default() -> Self {
+        Self::starting_at(1)
+    }
+}
+
+impl RequestIdGenerator for CounterIds {
+    fn next_id(&self) -> u64 {
+        self.next.fetch_add(1, Ordering::SeqCst)
+    }
+}
+
+/// Ids of the form `prefix * 1_000_000 + n`, where `prefix` identifies
+/// the connection and `n` counts from 1.
+///
+/// [`ConnectionPrefixedIds::per_connection`] (the prefix the client uses)
+/// hands out a different prefix to every generator in the process,
+/// starting from a random value drawn once per process. Ids from
+/// different connections and reconnects therefore never overlap within
+/// a process, and overlap across processes only if two random starting
+/// points land within a few prefixes of each other. The connection a
+/// request belongs to can be read straight off its id.
+///
+/// When a prefix's [`Self::COUNTER_SPAN`] ids are used up the generator
+/// moves on to a fresh prefix instead of wrapping. Prefixes stay below
+/// 2^32, so ids stay below 2^53 and survive JSON number handling in
+/// JavaScript-based tooling.
+#[derive(Debug)]
+pub struct ConnectionPrefixedIds {
+    /// The next id to hand out.
+    next: AtomicU64,
+}
+
+/// Prefix handed to the next generator in this process.
+static NEXT_PREFIX: OnceLock<AtomicU32> = OnceLock::new();
+
+fn fresh_prefix() -> u64 {
+    let next = NEXT_PREFIX.get_or_init(|| AtomicU32::new(rand::random()));
+    u64::from(next.fetch_add(1, Ordering::Relaxed))
+}
+
+impl ConnectionPrefixedIds {
+    /// Number of ids per prefix; the counter runs from 1 to
+    /// `COUNTER_SPAN - 1`.
+    pub const COUNTER_SPAN: u64 = 1_000_000;
+
+    /// Create a generator with an explicit connection prefix. Once its ids
+    /// are used up the generator continues on a fresh prefix.
+    #[must_use]
+    pub fn new(prefix: u64) -> Self {
+        Self {
+            next: AtomicU64::new(prefix.wrapping_mul(Self::COUNTER_SPAN).wrapping_add(1)),
+        }
+    }
+
+    /// Create a generator with a prefix no other generator in this
+    /// process has.
+    #[must_use]
+    pub fn per_connection() -> Self {
+        Self::new(fresh_prefix())
+    }
+
+    /// The connection prefix of the next id.
+    #[must_use]
+    pub fn prefix(&self) -> u64 {
+        self.next.load(Ordering::SeqCst).saturating_sub(1) / Self::COUNTER_SPAN
+    }
+}
+
+impl RequestIdGenerator for ConnectionPrefixedIds {
+    fn next_id(&self) -> u64 {
+        let mut current = self.next.load(Ordering::SeqCst);
+        loop {
+            let id = if current % Self::COUNTER_SPAN == 0 {
+                fresh_prefix() * Self::COUNTER_SPAN + 1
+            } else {
+                current
+            };
+            match self
+                .next
+                .compare_exchange(current, id + 1, Ordering::SeqCst, Ordering::SeqCst)
+            {
+                Ok(_) => return id,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,4 +237,43 @@ mod tests {
         assert!(resp.error.is_some());
         assert_eq!(resp.error.unwrap().code, ErrorCodes::ACCESS_DENIED);
     }
+
+    #[test]
+    fn test_counter_ids_start_at_one() {
+        let ids = CounterIds::default();
+        assert_eq!(ids.next_id(), 1);
+        assert_eq!(ids.next_id(), 2);
+        assert_eq!(CounterIds::starting_at(10).next_id(), 10);
+    }
+
+    #[test]
+    fn test_connection_prefixed_ids_embed_prefix() {
+        let ids = ConnectionPrefixedIds::new(1_700_000_000);
+        assert_eq!(ids.next_id(), 1_700_000_000_000_001);
+        assert_eq!(ids.next_id(), 1_700_000_000_000_002);
+        assert_eq!(ids.prefix(), 1_700_000_000);
+        assert!(ids.next_id() < (1_u64 << 53));
+    }
+
+    #[test]
+    fn test_connection_prefixed_ids_move_to_fresh_prefix_when_used_up() {
+        let ids = ConnectionPrefixedIds::new(7);
+        ids.next.store(7_999_999, Ordering::SeqCst);
+        assert_eq!(ids.next_id(), 7_999_999);
+        let id = ids.next_id();
+        assert_ne!(id / ConnectionPrefixedIds::COUNTER_SPAN, 7);
+        assert_eq!(id % ConnectionPrefixedIds::COUNTER_SPAN, 1);
+        assert_eq!(ids.prefix(), id / ConnectionPrefixedIds::COUNTER_SPAN);
+    }
+
+    #[test]
+    fn test_per_connection_ids_do_not_overlap() {
+        let first = ConnectionPrefixedIds::per_connection();
+        let second = ConnectionPrefixedIds::per_connection();
+        assert_ne!(first.prefix(), second.prefix());
+        let a: Vec<u64> = (0..1_000).map(|_| first.next_id()).collect();
+        let b: Vec<u64> = (0..1_000).map(|_| second.next_id()).collect();
+        assert!(a.iter().all(|id| !b.contains(id)));
+        assert!(a.iter().chain(&b).all(|id| *id < (1_u64 << 53)));
+    }
 }
@@ -1,11 +1,12 @@
 //! Session management protocol types.
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::protocol::headset::HeadsetInfo;
+use crate::protocol::query::{Filter, Order};
 
 /// Session information from `createSession` / `querySessions`.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionInfo {
     /// Session ID (UUID).
     pub id: String,
@@ -43,6 +44,103 @@ pub struct SessionInfo {
     pub headset: Option<HeadsetInfo>,
 }
 
+impl SessionInfo {
+    /// Whether the session is still open (`opened`, `activated`, or
+    /// `active`).
+    #[must_use]
+    pub fn is_open(&self) -> bool {
+        matches!(self.status.as_str(), "opened" | "activated" | "active")
+    }
+}
+
+/// Status requested from `createSession`.
+///
+/// An `Open` session reserves the headset without starting data
+/// acquisition; it must be activated (`updateSession`, `status =
+/// "active"`) before subscribing or recording. Session-scoped
+/// [`ResilientClient`](crate::ResilientClient) calls do that
+/// automatically on first use.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SessionStatus {
+    /// Reserve the headset only (`"open"`).
+    Open,
+    /// Reserve the headset and start data acquisition (`"active"`).
+    #[default]
+    Active,
+}
+
+impl SessionStatus {
+    /// Returns the Cortex API status string.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Active => "active",
+        }
+    }
+}
+
+/// Options for `querySessions`.
+///
+/// Cortex returns every session of the application, so the filter and
+/// order are applied client-side to the raw session objects, using
+/// Cortex's field names (`status`, `started`, `headset.id`, ...):
+///
+/// ```
+/// use emotiv_cortex_v2::protocol::query::{Filter, Order};
+/// use emotiv_cortex_v2::protocol::session::QuerySessionsRequest;
+///
+/// let request = QuerySessionsRequest::default()
+///     .filter(Filter::one_of("status", ["opened", "activated"]))
+///     .order(Order::desc("started"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuerySessionsRequest {
+    /// Sessions to keep; the default keeps all.
+    pub query: Filter,
+    /// Sort order; the default keeps Cortex's order.
+    pub order_by: Order,
+    /// Keep at most this many sessions after sorting.
+    pub limit: Option<u32>,
+}
+
+impl QuerySessionsRequest {
+    /// Replace the filter with `filter`.
+    #[must_use]
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.query = filter;
+        self
+    }
+
+    /// Replace the sort order with `order`.
+    #[must_use]
+    pub fn order(mut self, order: Order) -> Self {
+        self.order_by = order;
+        self
+    }
+
+    /// Keep at most `limit` sessions.
+    #[must_use]
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Filter, sort, and truncate raw `querySessions` results.
+    #[must_use]
+    pub fn apply(&self, sessions: Vec<serde_json::Value>) -> Vec<serde_json::Value> {
+        let mut sessions: Vec<_> = sessions
+            .into_iter()
+            .filter(|session| self.query.matches(session))
+            .collect();
+        self.order_by.sort(&mut sessions);
+        if let Some(limit) = self.limit {
+            sessions.truncate(usize::try_from(limit).unwrap_or(usize::MAX));
+        }
+        sessions
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,6 +1,8 @@
 //! Stream event and parsed stream payload protocol types.
 
-use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
 
 fn f64_to_f32(value: f64) -> Option<f32> {
     if !value.is_finite() {
@@ -9,7 +11,7 @@ fn f64_to_f32(value: f64) -> Option<f32> {
     value.to_string().parse::<f32>().ok()
 }
 
-fn seconds_to_micros_i64(timestamp_secs: f64) -> Option<i64> {
+pub(crate) fn seconds_to_micros_i64(timestamp_secs: f64) -> Option<i64> {
     if !timestamp_secs.is_finite() {
         return None;
     }
@@ -47,7 +49,7 @@ pub struct EegEvent {
 ///
 /// Produced by [`EegData::from_eeg_array`], which mirrors the pattern
 /// used by [`DeviceQuality::from_dev_array`] for the `"dev"` stream.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EegData {
     /// Timestamp in microseconds (converted from Cortex f64 seconds).
     pub timestamp: i64,
@@ -122,7 +124,7 @@ pub struct DevEvent {
 ///
 /// Cortex reports contact quality per-channel as integers 0–4 (None/Poor/Fair/Good/Excellent)
 /// and overall quality as 0–100. We normalize these to 0.0–1.0 for consistency.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DeviceQuality {
     /// Battery level 0–4 (coarse indicator).
     pub battery_level: u8,
@@ -197,13 +199,78 @@ pub struct MotEvent {
     pub mot: Vec<f64>,
 }
 
+/// Column layout of the `mot` stream.
+///
+/// Headsets report orientation either as a quaternion (Insight, EPOC X,
+/// current EPOC+ firmware) or, on older EPOC+ firmware, as raw gyroscope
+/// rates:
+///
+/// - `Quaternion`: `[COUNTER, INTERPOLATED, Q0, Q1, Q2, Q3, ACCX, ACCY, ACCZ, MAGX, MAGY, MAGZ]`
+/// - `Gyroscope`: `[COUNTER, INTERPOLATED, GYROX, GYROY, GYROZ, ACCX, ACCY, ACCZ, MAGX, MAGY, MAGZ]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum MotionLayout {
+    /// Orientation quaternion, 12 columns.
+    #[default]
+    Quaternion,
+    /// Gyroscope x/y/z, 11 columns.
+    Gyroscope,
+}
+
+impl MotionLayout {
+    /// Layout named by the `cols` of a `subscribe` response, or `None` if
+    /// they name neither `Q0` nor `GYROX`.
+    #[must_use]
+    pub fn from_cols(cols: &[String]) -> Option<Self> {
+        if cols.iter().any(|c| c == "GYROX") {
+            Some(Self::Gyroscope)
+        } else if cols.iter().any(|c| c == "Q0") {
+            Some(Self::Quaternion)
+        } else {
+            None
+        }
+    }
+
+    /// Layout implied by the length of a `mot` array, or `None` for an
+    /// unknown length.
+    #[must_use]
+    pub fn from_len(len: usize) -> Option<Self> {
+        match len {
+            12 => Some(Self::Quaternion),
+            11 => Some(Self::Gyroscope),
+            _ => None,
+        }
+    }
+
+    /// Best guess for `model` when neither `cols` nor a sample is at hand:
+    /// [`Gyroscope`](Self::Gyroscope) for EPOC+, whose older firmware
+    /// lacks quaternions, otherwise [`Quaternion`](Self::Quaternion).
+    #[must_use]
+    pub fn for_model(model: &crate::headset::HeadsetModel) -> Self {
+        match model {
+            crate::headset::HeadsetModel::EpocPlus => Self::Gyroscope,
+            _ => Self::Quaternion,
+        }
+    }
+
+    /// Number of columns in a `mot` array of this layout.
+    #[must_use]
+    pub fn column_count(self) -> usize {
+        match self {
+            Self::Quaternion => 12,
+            Self::Gyroscope => 11,
+        }
+    }
+}
+
 /// Parsed motion/IMU data from a "mot" stream event.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct MotionData {
     /// Timestamp in microseconds.
     pub timestamp: i64,
     /// Quaternion orientation [Q0, Q1, Q2, Q3] (newer headsets).
     pub quaternion: Option<[f32; 4]>,
+    /// Gyroscope readings [x, y, z] (older EPOC+ firmware).
+    pub gyroscope: Option<[f32; 3]>,
     /// Accelerometer readings [x, y, z] in g.
     pub accelerometer: [f32; 3],
     /// Magnetometer readings [x, y, z] in microtesla.
@@ -211,35 +278,52 @@ pub struct MotionData {
 }
 
 impl MotionData {
-    /// Parse a `MotEvent.mot` array into structured motion data.
+    /// Parse a `MotEvent.mot` array into structured motion data, telling
+    /// the [`MotionLayout`] from the array length.
     ///
-    /// Expected format (Insight/EPOC X):
-    /// `[COUNTER, INTERPOLATED, Q0, Q1, Q2, Q3, ACCX, ACCY, ACCZ, MAGX, MAGY, MAGZ]`
+    /// Returns `None` for an array of neither layout's length.
     #[must_use]
     pub fn from_mot_array(mot: &[f64], timestamp: f64) -> Option<Self> {
-        if mot.len() < 12 {
+        Self::from_mot_array_with(mot, timestamp, MotionLayout::from_len(mot.len())?)
+    }
+
+    /// Parse a `MotEvent.mot` array of a known [`MotionLayout`], e.g. one
+    /// from [`MotionLayout::from_cols`].
+    ///
+    /// Returns `None` if the array is shorter than the layout.
+    #[must_use]
+    pub fn from_mot_array_with(mot: &[f64], timestamp: f64, layout: MotionLayout) -> Option<Self> {
+        if mot.len() < layout.column_count() {
             return None;
         }
-
-        // Skip COUNTER (0) and INTERPOLATED (1), then Q0-Q3, then ACC, then MAG
+        let axes = |at: usize| -> Option<[f32; 3]> {
+            Some([
+                f64_to_f32(mot[at])?,
+                f64_to_f32(mot[at + 1])?,
+                f64_to_f32(mot[at + 2])?,
+            ])
+        };
+
+        // Skip COUNTER (0) and INTERPOLATED (1); orientation, then ACC, then MAG
+        let (quaternion, gyroscope, acc) = match layout {
+            MotionLayout::Quaternion => (
+                Some([
+                    f64_to_f32(mot[2])?,
+                    f64_to_f32(mot[3])?,
+                    f64_to_f32(mot[4])?,
+                    f64_to_f32(mot[5])?,
+                ]),
+                None,
+                6,
+            ),
+            MotionLayout::Gyroscope => (None, Some(axes(2)?), 5),
+        };
         Some(Self {
             timestamp: seconds_to_micros_i64(timestamp)?,
-            quaternion: Some([
-                f64_to_f32(mot[2])?,
-                f64_to_f32(mot[3])?,
-                f64_to_f32(mot[4])?,
-                f64_to_f32(mot[5])?,
-            ]),
-            accelerometer: [
-                f64_to_f32(mot[6])?,
-                f64_to_f32(mot[7])?,
-                f64_to_f32(mot[8])?,
-            ],
-            magnetometer: [
-                f64_to_f32(mot[9])?,
-                f64_to_f32(mot[10])?,
-                f64_to_f32(mot[11])?,
-            ],
+            quaternion,
+            gyroscope,
+            accelerometer: axes(acc)?,
+            magnetometer: axes(acc + 3)?,
         })
     }
 }
@@ -260,7 +344,7 @@ pub struct EqEvent {
 }
 
 /// Parsed EEG quality data from an "eq" stream event.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EegQuality {
     /// Battery percentage 0–100.
     pub battery_percent: u8,
@@ -325,7 +409,7 @@ pub struct PowEvent {
 }
 
 /// Parsed band power data from a "pow" stream event.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct BandPowerData {
     /// Timestamp in microseconds.
     pub timestamp: i64,
@@ -384,7 +468,7 @@ pub struct MetEvent {
 }
 
 /// Parsed performance metrics from a "met" stream event.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PerformanceMetrics {
     /// Timestamp in microseconds.
     pub timestamp: i64,
@@ -404,6 +488,177 @@ pub struct PerformanceMetrics {
     pub attention: Option<f32>,
     /// Focus sustainability (0.0–1.0).
     pub focus: Option<f32>,
+    /// Columns this crate does not model, by label, e.g. metrics of a
+    /// newer Cortex release or their `.isActive` flags.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+/// Default `met` column layout (Insight/EPOC with a standard license).
+///
+/// The authoritative layout is the `cols` list in the `subscribe`
+/// response; this is only a fallback for captures that lack it.
+pub const MET_COLUMNS: &[&str] = &[
+    "eng.isActive",
+    "eng",
+    "exc.isActive",
+    "exc",
+    "lex",
+    "str.isActive",
+    "str",
+    "rel.isActive",
+    "rel",
+    "int.isActive",
+    "int",
+    "foc.isActive",
+    "foc",
+];
+
+/// `met` column layout for Cortex versions that report `attention` first.
+pub const MET_COLUMNS_WITH_ATTENTION: &[&str] = &[
+    "attention.isActive",
+    "attention",
+    "eng.isActive",
+    "eng",
+    "exc.isActive",
+    "exc",
+    "lex",
+    "str.isActive",
+    "str",
+    "rel.isActive",
+    "rel",
+    "int.isActive",
+    "int",
+    "foc.isActive",
+    "foc",
+];
+
+/// Position of each metric in a `met` array, built once from the column
+/// labels of the `subscribe` response.
+///
+/// Which columns Cortex sends depends on the headset, license, and service
+/// version (`attention` may be missing, `.isActive` flags are interleaved),
+/// so metrics are looked up by label rather than position. Columns that
+/// are neither a known metric nor its `.isActive` flag are kept in
+/// [`PerformanceMetrics::extra`].
+///
+/// ```
+/// use emotiv_cortex_v2::protocol::streams::MetColumnMap;
+/// use serde_json::json;
+///
+/// let map = MetColumnMap::new(&["foc.isActive", "foc", "att.new"]);
+/// let metrics = map.parse(&[json!(true), json!(0.4), json!(0.9)], 1.0).unwrap();
+/// assert_eq!(metrics.focus, Some(0.4));
+/// assert_eq!(metrics.engagement, None);
+/// assert_eq!(metrics.extra["att.new"], json!(0.9));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetColumnMap {
+    engagement: Option<usize>,
+    excitement: Option<usize>,
+    long_excitement: Option<usize>,
+    stress: Option<usize>,
+    relaxation: Option<usize>,
+    interest: Option<usize>,
+    attention: Option<usize>,
+    focus: Option<usize>,
+    extra: Vec<(String, usize)>,
+}
+
+/// Labels of the metrics [`PerformanceMetrics`] models.
+const KNOWN_MET_LABELS: [&str; 8] = ["eng", "exc", "lex", "str", "rel", "int", "attention", "foc"];
+
+impl MetColumnMap {
+    /// Map the column labels of a `met` subscription.
+    #[must_use]
+    pub fn new<S: AsRef<str>>(cols: &[S]) -> Self {
+        let find = |name: &str| cols.iter().position(|c| c.as_ref() == name);
+        let extra = cols
+            .iter()
+            .enumerate()
+            .filter(|(_, col)| {
+                let col = col.as_ref();
+                let metric = col.strip_suffix(".isActive").unwrap_or(col);
+                !KNOWN_MET_LABELS.contains(&metric)
+            })
+            .map(|(idx, col)| (col.as_ref().to_string(), idx))
+            .collect();
+        Self {
+            engagement: find("eng"),
+            excitement: find("exc"),
+            long_excitement: find("lex"),
+            stress: find("str"),
+            relaxation: find("rel"),
+            interest: find("int"),
+            attention: find("attention"),
+            focus: find("foc"),
+            extra,
+        }
+    }
+
+    /// Map the documented default layout for a `met` array of `len`
+    /// values: [`MET_COLUMNS_WITH_ATTENTION`] or [`MET_COLUMNS`]. For
+    /// captures that lack the `subscribe` response.
+    #[must_use]
+    pub fn for_len(len: usize) -> Self {
+        if len == MET_COLUMNS_WITH_ATTENTION.len() {
+            Self::new(MET_COLUMNS_WITH_ATTENTION)
+        } else {
+            Self::new(MET_COLUMNS)
+        }
+    }
+
+    /// Parse a `MetEvent.met` array.
+    ///
+    /// Metrics whose column is missing, or whose value is `null`
+    /// (inactive detection), are `None`.
+    #[must_use]
+    pub fn parse(&self, met: &[serde_json::Value], timestamp: f64) -> Option<PerformanceMetrics> {
+        let val = |idx: Option<usize>| -> Option<f32> {
+            met.get(idx?)
+                .and_then(serde_json::Value::as_f64)
+                .and_then(f64_to_f32)
+        };
+
+        Some(PerformanceMetrics {
+            timestamp: seconds_to_micros_i64(timestamp)?,
+            engagement: val(self.engagement),
+            excitement: val(self.excitement),
+            long_excitement: val(self.long_excitement),
+            stress: val(self.stress),
+            relaxation: val(self.relaxation),
+            interest: val(self.interest),
+            attention: val(self.attention),
+            focus: val(self.focus),
+            extra: self
+                .extra
+                .iter()
+                .filter_map(|(name, idx)| Some((name.clone(), met.get(*idx)?.clone())))
+                .collect(),
+        })
+    }
+}
+
+impl PerformanceMetrics {
+    /// Parse a `MetEvent.met` array using the column labels from the
+    /// `subscribe` response. Falls back to the documented default layout
+    /// when `cols` is empty.
+    ///
+    /// Builds a [`MetColumnMap`] on every call; build one once to parse a
+    /// whole stream.
+    #[must_use]
+    pub fn from_met_array<S: AsRef<str>>(
+        met: &[serde_json::Value],
+        cols: &[S],
+        timestamp: f64,
+    ) -> Option<Self> {
+        let map = if cols.is_empty() {
+            MetColumnMap::for_len(met.len())
+        } else {
+            MetColumnMap::new(cols)
+        };
+        map.parse(met, timestamp)
+    }
 }
 
 /// A mental command event from the "com" stream.
@@ -421,15 +676,161 @@ pub struct ComEvent {
     pub com: Vec<serde_json::Value>,
 }
 
+// ─── Detection Actions ──────────────────────────────────────────────────
+
+/// Defines a string-backed action enum: each variant maps to its Cortex
+/// name, and unrecognised names are kept in `Other`. (De)serializes as
+/// the plain Cortex string.
+macro_rules! action_enum {
+    (
+        $(#[$meta:meta])*
+        $name:ident { $($(#[$vmeta:meta])* $variant:ident => $wire:literal,)+ }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$vmeta])* $variant,)+
+            /// An action name this crate does not know yet.
+            Other(String),
+        }
+
+        impl $name {
+            /// The Cortex name of this action.
+            #[must_use]
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $wire,)+
+                    Self::Other(name) => name,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(name: &str) -> Self {
+                match name {
+                    $($wire => Self::$variant,)+
+                    other => Self::Other(other.to_string()),
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.pad(self.as_str())
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.as_str() == *other
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let name = String::deserialize(deserializer)?;
+                Ok(Self::from(name.as_str()))
+            }
+        }
+    };
+}
+
+action_enum! {
+    /// A mental command action, as reported by the "com" stream.
+    MentalCommandAction {
+        /// No command (`neutral`).
+        Neutral => "neutral",
+        /// `push`.
+        Push => "push",
+        /// `pull`.
+        Pull => "pull",
+        /// `lift`.
+        Lift => "lift",
+        /// `drop`.
+        Drop => "drop",
+        /// `left`.
+        Left => "left",
+        /// `right`.
+        Right => "right",
+        /// `rotateLeft`.
+        RotateLeft => "rotateLeft",
+        /// `rotateRight`.
+        RotateRight => "rotateRight",
+        /// `rotateClockwise`.
+        RotateClockwise => "rotateClockwise",
+        /// `rotateCounterClockwise`.
+        RotateCounterClockwise => "rotateCounterClockwise",
+        /// `rotateForwards`.
+        RotateForwards => "rotateForwards",
+        /// `rotateReverse`.
+        RotateReverse => "rotateReverse",
+        /// `disappear`.
+        Disappear => "disappear",
+    }
+}
+
+action_enum! {
+    /// A facial expression action, as reported by the "fac" stream for
+    /// the eyes, upper face, or lower face.
+    FacialAction {
+        /// No expression (`neutral`).
+        Neutral => "neutral",
+        /// `blink`.
+        Blink => "blink",
+        /// `winkL`.
+        WinkLeft => "winkL",
+        /// `winkR`.
+        WinkRight => "winkR",
+        /// `lookL`.
+        LookLeft => "lookL",
+        /// `lookR`.
+        LookRight => "lookR",
+        /// Horizontal eye movement (`horiEye`), the trainable form of
+        /// `lookL`/`lookR`.
+        HorizontalEye => "horiEye",
+        /// `surprise`.
+        Surprise => "surprise",
+        /// `frown`.
+        Frown => "frown",
+        /// `smile`.
+        Smile => "smile",
+        /// `clench`.
+        Clench => "clench",
+        /// `laugh`.
+        Laugh => "laugh",
+        /// `smirkLeft`.
+        SmirkLeft => "smirkLeft",
+        /// `smirkRight`.
+        SmirkRight => "smirkRight",
+    }
+}
+
 /// Parsed mental command data from a "com" stream event.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct MentalCommand {
-    /// The detected action name (e.g., "push", "pull", "neutral").
-    pub action: String,
+    /// The detected action (e.g., push, pull, neutral).
+    pub action: MentalCommandAction,
     /// Action intensity 0.0–1.0.
     pub power: f32,
 }
 
+impl MentalCommand {
+    /// Parse a `ComEvent.com` array: `[action, power]`.
+    #[must_use]
+    pub fn from_com_array(com: &[serde_json::Value]) -> Option<Self> {
+        Some(Self {
+            action: MentalCommandAction::from(com.first()?.as_str()?),
+            power: f64_to_f32(com.get(1)?.as_f64()?)?,
+        })
+    }
+}
+
 /// A facial expression event from the "fac" stream.
 #[derive(Debug, Deserialize)]
 pub struct FacEvent {
@@ -444,24 +845,39 @@ pub struct FacEvent {
 }
 
 /// Parsed facial expression data from a "fac" stream event.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct FacialExpression {
-    /// Eye action (e.g., "blink", "winkL", "winkR", "lookL", "lookR").
-    pub eye_action: String,
-    /// Upper face action (e.g., "surprise", "frown").
-    pub upper_face_action: String,
+    /// Eye action (e.g., blink, winkL, winkR, lookL, lookR).
+    pub eye_action: FacialAction,
+    /// Upper face action (e.g., surprise, frown).
+    pub upper_face_action: FacialAction,
     /// Upper face action power 0.0–1.0.
     pub upper_face_power: f32,
-    /// Lower face action (e.g., "smile", "clench").
-    pub lower_face_action: String,
+    /// Lower face action (e.g., smile, clench).
+    pub lower_face_action: FacialAction,
     /// Lower face action power 0.0–1.0.
     pub lower_face_power: f32,
 }
 
+impl FacialExpression {
+    /// Parse a `FacEvent.fac` array:
+    /// `[eyeAct, uAct, uPow, lAct, lPow]`.
+    #[must_use]
+    pub fn from_fac_array(fac: &[serde_json::Value]) -> Option<Self> {
+        Some(Self {
+            eye_action: FacialAction::from(fac.first()?.as_str()?),
+            upper_face_action: FacialAction::from(fac.get(1)?.as_str()?),
+            upper_face_power: f64_to_f32(fac.get(2)?.as_f64()?)?,
+            lower_face_action: FacialAction::from(fac.get(3)?.as_str()?),
+            lower_face_power: f64_to_f32(fac.get(4)?.as_f64()?)?,
+        })
+    }
+}
+
 /// A system event from the "sys" stream.
 ///
 /// Used during training for mental commands and facial expressions.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SysEvent {
     /// Session ID.
     pub sid: String,
@@ -513,7 +929,127 @@ pub struct StreamEvent {
     pub sys: Option<Vec<serde_json::Value>>,
 }
 
+// ─── Subscribe Response ─────────────────────────────────────────────────
+
+/// A stream that Cortex subscribed successfully.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct StreamSubscription {
+    /// Stream name (`"eeg"`, `"met"`, ...).
+    #[serde(rename = "streamName")]
+    pub stream_name: String,
+
+    /// Column labels for the stream's data arrays.
+    #[serde(default)]
+    pub cols: Vec<String>,
+
+    /// Session ID the stream is bound to.
+    #[serde(default)]
+    pub sid: Option<String>,
+}
+
+/// A stream that Cortex refused to subscribe (missing license, unknown
+/// stream name, headset not supporting the stream, ...).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct StreamSubscriptionFailure {
+    /// Stream name as requested.
+    #[serde(rename = "streamName")]
+    pub stream_name: String,
+
+    /// Cortex error code for this stream.
+    pub code: i32,
+
+    /// Human-readable reason.
+    #[serde(default)]
+    pub message: String,
+}
+
+/// Per-stream outcome of a `subscribe` call.
+///
+/// Cortex answers `subscribe` with a successful JSON-RPC result even when
+/// some of the requested streams were rejected; the rejected ones are
+/// listed under `failure`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubscriptionResult {
+    /// Streams that are now subscribed.
+    pub success: Vec<StreamSubscription>,
+
+    /// Streams that were rejected.
+    pub failure: Vec<StreamSubscriptionFailure>,
+}
+
+impl SubscriptionResult {
+    /// Parse the raw `subscribe` result.
+    ///
+    /// Success entries given as bare stream names are accepted alongside
+    /// the documented `{ streamName, cols, sid }` objects. Entries that
+    /// match neither shape are ignored.
+    #[must_use]
+    pub fn from_response(value: &serde_json::Value) -> Self {
+        let entries = |key: &str| {
+            value
+                .get(key)
+                .and_then(serde_json::Value::as_array)
+                .cloned()
+                .unwrap_or_default()
+        };
+
+        let success = entries("success")
+            .into_iter()
+            .filter_map(|entry| match entry {
+                serde_json::Value::String(stream_name) => Some(StreamSubscription {
+                    stream_name,
+                    ..StreamSubscription::default()
+                }),
+                other => serde_json::from_value(other).ok(),
+            })
+            .collect();
+        let failure = entries("failure")
+            .into_iter()
+            .filter_map(|entry| serde_json::from_value(entry).ok())
+            .collect();
+
+        Self { success, failure }
+    }
+
+    /// Whether every requested stream was subscribed.
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.failure.is_empty()
+    }
+
+    /// Success entry for `stream`, if it was subscribed.
+    #[must_use]
+    pub fn subscription(&self, stream: &str) -> Option<&StreamSubscription> {
+        self.success.iter().find(|s| s.stream_name == stream)
+    }
+
+    /// Failure entry for `stream`, if it was rejected.
+    #[must_use]
+    pub fn failure_for(&self, stream: &str) -> Option<&StreamSubscriptionFailure> {
+        self.failure.iter().find(|f| f.stream_name == stream)
+    }
+
+    /// Names of the rejected streams.
+    #[must_use]
+    pub fn failed_streams(&self) -> Vec<&str> {
+        self.failure
+            .iter()
+            .map(|f| f.stream_name.as_str())
+            .collect()
+    }
+
+    /// Fold the outcome of re-subscribing the failed streams into `self`.
+    ///
+    /// Streams that succeeded on retry move to `success`; `failure` is
+    /// replaced by whatever still failed.
+    pub fn merge_retry(&mut self, retry: SubscriptionResult) {
+        self.success.extend(retry.success);
+        self.failure = retry.failure;
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::float_cmp, clippy::unreadable_literal)]
 mod tests {
     use super::*;
 
@@ -550,6 +1086,29 @@ mod tests {
         assert!((data.raw_cq - 0.0).abs() < f32::EPSILON);
     }
 
+    #[test]
+    fn test_serialize_eeg_data_is_flat_json_object() {
+        let data = EegData {
+            timestamp: 1_609_459_200_000_000,
+            counter: 7,
+            interpolated: false,
+            channels: vec![1.5, 2.5],
+            raw_cq: 4.0,
+        };
+
+        let value = serde_json::to_value(&data).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "timestamp": 1_609_459_200_000_000_i64,
+                "counter": 7,
+                "interpolated": false,
+                "channels": [1.5, 2.5],
+                "raw_cq": 4.0
+            })
+        );
+    }
+
     #[test]
     fn test_parse_eeg_data_too_short() {
         let eeg: Vec<serde_json::Value> = serde_json::from_str(r"[29, 0, 4262.564]").unwrap();
@@ -631,6 +1190,36 @@ mod tests {
         assert!((q[0] - 0.707).abs() < 0.001);
         assert!((motion.accelerometer[1] - -9.81).abs() < 0.01);
         assert!((motion.magnetometer[2] - 45.0).abs() < 0.01);
+        assert!(motion.gyroscope.is_none());
+    }
+
+    #[test]
+    fn test_parse_gyroscope_motion_data() {
+        // [COUNTER, INTERPOLATED, GYROX, GYROY, GYROZ, ACCX, ACCY, ACCZ, MAGX, MAGY, MAGZ]
+        let mot = vec![
+            7.0, 0.0, 1.5, -2.5, 0.25, 0.01, -9.81, 0.02, 30.0, -15.0, 45.0,
+        ];
+        let motion = MotionData::from_mot_array(&mot, 1_609_459_200.0).unwrap();
+        assert!(motion.quaternion.is_none());
+        assert_eq!(motion.gyroscope, Some([1.5, -2.5, 0.25]));
+        assert!((motion.accelerometer[1] - -9.81).abs() < 0.01);
+        assert!((motion.magnetometer[2] - 45.0).abs() < 0.01);
+
+        let cols: Vec<String> = [
+            "COUNTER_MEMS",
+            "INTERPOLATED_MEMS",
+            "GYROX",
+            "GYROY",
+            "GYROZ",
+        ]
+        .map(String::from)
+        .to_vec();
+        assert_eq!(
+            MotionLayout::from_cols(&cols),
+            Some(MotionLayout::Gyroscope)
+        );
+        assert!(MotionData::from_mot_array_with(&mot, 0.0, MotionLayout::Quaternion).is_none());
+        assert!(MotionData::from_mot_array(&mot[..10], 0.0).is_none());
     }
 
     #[test]
@@ -698,6 +1287,57 @@ mod tests {
         assert_eq!(event.met.len(), 4);
     }
 
+    #[test]
+    fn test_met_column_map_follows_subscribe_cols() {
+        // Attention missing, flags interleaved, one column not modeled.
+        let cols = [
+            "foc.isActive",
+            "foc",
+            "eng.isActive",
+            "eng",
+            "cognitiveStress.isActive",
+            "cognitiveStress",
+            "rel",
+        ];
+        let met = [
+            serde_json::json!(true),
+            serde_json::json!(0.7),
+            serde_json::json!(false),
+            serde_json::Value::Null,
+            serde_json::json!(true),
+            serde_json::json!(0.25),
+            serde_json::json!(0.5),
+        ];
+
+        let metrics = MetColumnMap::new(&cols).parse(&met, 1.5).unwrap();
+
+        assert_eq!(metrics.timestamp, 1_500_000);
+        assert_eq!(metrics.focus, Some(0.7));
+        assert_eq!(metrics.engagement, None);
+        assert_eq!(metrics.relaxation, Some(0.5));
+        assert_eq!(metrics.attention, None);
+        assert_eq!(metrics.extra.len(), 2);
+        assert_eq!(metrics.extra["cognitiveStress"], serde_json::json!(0.25));
+        assert_eq!(
+            metrics.extra["cognitiveStress.isActive"],
+            serde_json::json!(true)
+        );
+    }
+
+    #[test]
+    fn test_met_column_map_defaults_by_length() {
+        let mut met = vec![serde_json::json!(true); MET_COLUMNS_WITH_ATTENTION.len()];
+        met[1] = serde_json::json!(0.9);
+        let with_attention = PerformanceMetrics::from_met_array::<&str>(&met, &[], 0.0).unwrap();
+        assert_eq!(with_attention.attention, Some(0.9));
+        assert!(with_attention.extra.is_empty());
+
+        met.truncate(MET_COLUMNS.len());
+        let standard = PerformanceMetrics::from_met_array::<&str>(&met, &[], 0.0).unwrap();
+        assert_eq!(standard.attention, None);
+        assert_eq!(standard.engagement, Some(0.9));
+    }
+
     #[test]
     fn test_deserialize_com_event() {
         let json = r#"{
@@ -724,6 +1364,36 @@ mod tests {
         assert_eq!(event.fac[0].as_str(), Some("blink"));
     }
 
+    #[test]
+    fn test_action_enums_parse_known_and_keep_unknown_names() {
+        let cmd = MentalCommand::from_com_array(&[
+            serde_json::json!("rotateLeft"),
+            serde_json::json!(0.5),
+        ])
+        .unwrap();
+        assert_eq!(cmd.action, MentalCommandAction::RotateLeft);
+        assert_eq!(cmd.action, "rotateLeft");
+
+        let fac = FacialExpression::from_fac_array(&[
+            serde_json::json!("winkL"),
+            serde_json::json!("frown"),
+            serde_json::json!(0.25),
+            serde_json::json!("wiggle"),
+            serde_json::json!(0.5),
+        ])
+        .unwrap();
+        assert_eq!(fac.eye_action, FacialAction::WinkLeft);
+        assert_eq!(fac.upper_face_action, FacialAction::Frown);
+        assert_eq!(fac.lower_face_action, FacialAction::Other("wiggle".into()));
+        assert_eq!(format!("{:<7}|", fac.eye_action), "winkL  |");
+
+        let json = serde_json::to_value(&fac).unwrap();
+        assert_eq!(json["eye_action"], "winkL");
+        assert_eq!(json["lower_face_action"], "wiggle");
+        let back: FacialAction = serde_json::from_value(json["upper_face_action"].clone()).unwrap();
+        assert_eq!(back, FacialAction::Frown);
+    }
+
     #[test]
     fn test_deserialize_sys_event() {
         let json = r#"{
@@ -764,4 +1434,44 @@ mod tests {
         assert!(event.eeg.is_none());
         assert!(event.dev.is_some());
     }
+
+    #[test]
+    fn test_subscription_result_splits_success_and_failure() {
+        let json = serde_json::json!({
+            "success": [
+                {"streamName": "met", "cols": ["eng.isActive", "eng"], "sid": "s-1"},
+                "eeg"
+            ],
+            "failure": [
+                {"streamName": "pow", "code": -32016, "message": "Invalid stream"},
+                {"unexpected": true}
+            ]
+        });
+
+        let result = SubscriptionResult::from_response(&json);
+        assert!(!result.is_complete());
+        assert_eq!(result.success.len(), 2);
+        assert_eq!(result.subscription("met").map(|s| s.cols.len()), Some(2));
+        assert!(result.subscription("eeg").is_some());
+        assert_eq!(result.failed_streams(), vec!["pow"]);
+        assert_eq!(result.failure_for("pow").map(|f| f.code), Some(-32016));
+    }
+
+    #[test]
+    fn test_subscription_result_merge_retry() {
+        let mut result = SubscriptionResult::from_response(&serde_json::json!({
+            "success": ["eeg"],
+            "failure": [
+                {"streamName": "pow", "code": -32016, "message": "x"},
+                {"streamName": "fac", "code": -32016, "message": "y"}
+            ]
+        }));
+        result.merge_retry(SubscriptionResult::from_response(&serde_json::json!({
+            "success": ["pow"],
+            "failure": [{"streamName": "fac", "code": -32016, "message": "y"}]
+        })));
+
+        assert!(result.subscription("pow").is_some());
+        assert_eq!(result.failed_streams(), vec!["fac"]);
+    }
 }
@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::protocol::query::{Filter, Order};
+
 /// Subject info from `createSubject` / `updateSubject` / `querySubjects`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubjectInfo {
@@ -84,6 +86,9 @@ impl SubjectRequest {
 }
 
 /// Request payload for `querySubjects`.
+///
+/// Build the `query` and `order_by` fields with [`Filter`] and [`Order`]
+/// rather than raw JSON; see [`query`](super::query).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuerySubjectsRequest {
     /// Query expression object.
@@ -107,6 +112,36 @@ impl Default for QuerySubjectsRequest {
     }
 }
 
+impl QuerySubjectsRequest {
+    /// Replace the query with `filter`.
+    #[must_use]
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.query = filter.into();
+        self
+    }
+
+    /// Replace the sort order with `order`.
+    #[must_use]
+    pub fn order(mut self, order: Order) -> Self {
+        self.order_by = order.into();
+        self
+    }
+
+    /// Return at most `limit` subjects.
+    #[must_use]
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Skip the first `offset` matching subjects.
+    #[must_use]
+    pub fn offset(mut self, offset: u32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
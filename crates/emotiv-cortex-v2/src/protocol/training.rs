@@ -3,7 +3,7 @@
 use serde::{Deserialize, Serialize};
 
 /// Detection type for the `training` and `getDetectionInfo` methods.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DetectionType {
     /// Mental command detection.
     MentalCommand,
@@ -74,7 +74,7 @@ pub struct TrainedSignatureActions {
 }
 
 /// A single trained action within a profile.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TrainedAction {
     /// Action name (e.g. "neutral", "push", "pull").
     pub action: String,
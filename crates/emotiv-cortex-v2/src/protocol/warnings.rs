@@ -0,0 +1,269 @@
+//! Unsolicited warning objects Cortex pushes on the WebSocket.
+
+use serde::Serialize;
+
+use super::constants::WarningCodes;
+use super::profiles::ProfileUnloaded;
+
+/// A Cortex `{"warning": {"code": ..., "message": ...}}` push, decoded by
+/// [`WarningCodes`] code.
+///
+/// Names Cortex puts in the `message` object are extracted where the
+/// warning concerns a headset or session; they are `None` when the
+/// message is plain text.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WarningEvent {
+    /// Cortex stopped every stream of a session.
+    StreamsStopped {
+        /// Affected session.
+        session_id: Option<String>,
+    },
+    /// Cortex closed a session.
+    SessionClosed {
+        /// Closed session.
+        session_id: Option<String>,
+    },
+    /// A user logged in to the Launcher.
+    UserLogin,
+    /// The user logged out of the Launcher.
+    UserLogout,
+    /// The user approved the application's access request.
+    AccessGranted,
+    /// The user rejected the application's access request.
+    AccessRejected,
+    /// A profile was loaded for a headset.
+    ProfileLoaded {
+        /// Headset the profile was loaded on.
+        headset_id: Option<String>,
+    },
+    /// A profile was unloaded from a headset.
+    ProfileUnloaded(ProfileUnloaded),
+    /// The user accepted the EULA.
+    EulaAccepted,
+    /// Disk space for records is running low.
+    DiskSpaceLow,
+    /// Disk space for records is critically low.
+    DiskSpaceCritical,
+    /// Progress of a record export.
+    ExportProgress {
+        /// Record being exported.
+        record_id: Option<String>,
+        /// Progress in percent, when Cortex reports it.
+        percent: Option<f64>,
+    },
+    /// A record export finished; `error` is set if it failed.
+    ExportFinished {
+        /// Exported record.
+        record_id: Option<String>,
+        /// Paths of the files written.
+        files: Vec<String>,
+        /// Why the export failed.
+        error: Option<String>,
+    },
+    /// A headset could not connect within the timeout.
+    HeadsetConnectFailed {
+        /// Headset that failed to connect.
+        headset_id: Option<String>,
+    },
+    /// A headset was disconnected (unplugged, switched off, out of
+    /// range) for longer than Cortex waits for it to come back.
+    HeadsetDisconnected {
+        /// Disconnected headset.
+        headset_id: Option<String>,
+    },
+    /// A headset connected.
+    HeadsetConnected {
+        /// Connected headset.
+        headset_id: Option<String>,
+    },
+    /// Headset scanning (`refreshHeadsetList`) finished.
+    HeadsetScanFinished,
+    /// Any other warning code.
+    Other {
+        /// Warning code.
+        code: i64,
+        /// Warning message as sent.
+        message: serde_json::Value,
+    },
+}
+
+impl WarningEvent {
+    /// Decode the warning in a raw WebSocket message.
+    ///
+    /// Returns `None` if the message carries no warning with a numeric
+    /// code.
+    #[must_use]
+    pub fn from_warning(value: &serde_json::Value) -> Option<Self> {
+        let warning = value.get("warning")?;
+        let code = warning.get("code").and_then(serde_json::Value::as_i64)?;
+        let message = warning.get("message");
+        let field = |keys: &[&str]| {
+            keys.iter().find_map(|key| {
+                message
+                    .and_then(|m| m.get(*key))
+                    .and_then(serde_json::Value::as_str)
+                    .map(str::to_string)
+            })
+        };
+        let session_id = || field(&["sessionId", "session"]);
+        let headset_id = || field(&["headsetId", "headset"]);
+        let record_id = || field(&["recordId", "record"]);
+
+        let Ok(known) = i32::try_from(code) else {
+            return Some(Self::other(code, message));
+        };
+        Some(match known {
+            WarningCodes::CORTEX_STOP_ALL_STREAMS => Self::StreamsStopped {
+                session_id: session_id(),
+            },
+            WarningCodes::CORTEX_CLOSE_SESSION => Self::SessionClosed {
+                session_id: session_id(),
+            },
+            WarningCodes::USER_LOGIN => Self::UserLogin,
+            WarningCodes::USER_LOGOUT => Self::UserLogout,
+            WarningCodes::ACCESS_RIGHT_GRANTED => Self::AccessGranted,
+            WarningCodes::ACCESS_RIGHT_REJECTED => Self::AccessRejected,
+            WarningCodes::PROFILE_LOADED => Self::ProfileLoaded {
+                headset_id: headset_id(),
+            },
+            WarningCodes::PROFILE_UNLOADED | WarningCodes::CORTEX_AUTO_UNLOAD_PROFILE => {
+                Self::ProfileUnloaded(ProfileUnloaded::from_warning(value)?)
+            }
+            WarningCodes::EULA_ACCEPTED => Self::EulaAccepted,
+            WarningCodes::DISKSPACE_LOW => Self::DiskSpaceLow,
+            WarningCodes::DISKSPACE_CRITICAL => Self::DiskSpaceCritical,
+            WarningCodes::RECORD_EXPORT_PROGRESS => Self::ExportProgress {
+                record_id: record_id(),
+                percent: message
+                    .and_then(|m| m.get("progress"))
+                    .and_then(serde_json::Value::as_f64),
+            },
+            WarningCodes::RECORD_EXPORT_DONE => Self::ExportFinished {
+                record_id: record_id(),
+                files: message
+                    .and_then(|m| m.get("files"))
+                    .and_then(serde_json::Value::as_array)
+                    .map(|files| {
+                        files
+                            .iter()
+                            .filter_map(serde_json::Value::as_str)
+                            .map(str::to_string)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                error: field(&["error"]),
+            },
+            WarningCodes::HEADSET_CANNOT_CONNECT_TIMEOUT => Self::HeadsetConnectFailed {
+                headset_id: headset_id(),
+            },
+            WarningCodes::HEADSET_DISCONNECTED_TIMEOUT => Self::HeadsetDisconnected {
+                headset_id: headset_id(),
+            },
+            WarningCodes::HEADSET_CONNECTED => Self::HeadsetConnected {
+                headset_id: headset_id(),
+            },
+            WarningCodes::HEADSET_SCANNING_FINISHED => Self::HeadsetScanFinished,
+            _ => Self::other(code, message),
+        })
+    }
+
+    fn other(code: i64, message: Option<&serde_json::Value>) -> Self {
+        Self::Other {
+            code,
+            message: message.cloned().unwrap_or_default(),
+        }
+    }
+
+    /// Headset the warning concerns, if it names one.
+    #[must_use]
+    pub fn headset_id(&self) -> Option<&str> {
+        match self {
+            Self::ProfileLoaded { headset_id }
+            | Self::HeadsetConnectFailed { headset_id }
+            | Self::HeadsetDisconnected { headset_id }
+            | Self::HeadsetConnected { headset_id } => headset_id.as_deref(),
+            Self::ProfileUnloaded(event) => event.headset_id.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_warning_codes() {
+        let warning = |code: i32, message: serde_json::Value| {
+            WarningEvent::from_warning(&serde_json::json!({
+                "warning": {"code": code, "message": message}
+            }))
+        };
+
+        let unplugged = warning(
+            WarningCodes::HEADSET_DISCONNECTED_TIMEOUT,
+            serde_json::json!({"headsetId": "INSIGHT-A1B2C3D4", "behavior": "disconnected"}),
+        )
+        .unwrap();
+        assert_eq!(
+            unplugged,
+            WarningEvent::HeadsetDisconnected {
+                headset_id: Some("INSIGHT-A1B2C3D4".into())
+            }
+        );
+        assert_eq!(unplugged.headset_id(), Some("INSIGHT-A1B2C3D4"));
+
+        assert_eq!(
+            warning(
+                WarningCodes::CORTEX_CLOSE_SESSION,
+                serde_json::json!({"sessionId": "s-1"})
+            ),
+            Some(WarningEvent::SessionClosed {
+                session_id: Some("s-1".into())
+            })
+        );
+        assert!(matches!(
+            warning(
+                WarningCodes::CORTEX_AUTO_UNLOAD_PROFILE,
+                serde_json::json!("unloaded")
+            ),
+            Some(WarningEvent::ProfileUnloaded(ProfileUnloaded {
+                automatic: true,
+                ..
+            }))
+        ));
+        assert_eq!(
+            warning(
+                WarningCodes::RECORD_EXPORT_PROGRESS,
+                serde_json::json!({"recordId": "r-1", "progress": 40})
+            ),
+            Some(WarningEvent::ExportProgress {
+                record_id: Some("r-1".into()),
+                percent: Some(40.0)
+            })
+        );
+        assert_eq!(
+            warning(
+                WarningCodes::RECORD_EXPORT_DONE,
+                serde_json::json!({"recordId": "r-1", "files": ["/tmp/r-1.csv", "/tmp/r-1_md.csv"]})
+            ),
+            Some(WarningEvent::ExportFinished {
+                record_id: Some("r-1".into()),
+                files: vec!["/tmp/r-1.csv".into(), "/tmp/r-1_md.csv".into()],
+                error: None
+            })
+        );
+        assert_eq!(
+            warning(999, serde_json::json!("new")),
+            Some(WarningEvent::Other {
+                code: 999,
+                message: serde_json::json!("new")
+            })
+        );
+        assert_eq!(
+            WarningEvent::from_warning(&serde_json::json!({"sid": "s-1"})),
+            None
+        );
+    }
+}
@@ -0,0 +1,452 @@
+//! # Contact Quality Trends
+//!
+//! A contact-quality reading says whether an electrode is good right now.
+//! Saline sensors dry out and electrodes work loose over tens of minutes,
+//! and a channel's quality sinks gradually long before the data becomes
+//! unusable. [`ChannelTrend`] fits a line to each channel's recent
+//! quality, classifies the trend, estimates when the channel will drop to
+//! [`TrendConfig::failure_threshold`], and raises [`QualityAlert`]s on its
+//! alerts channel so an operator can re-wet or reseat the sensor in time.
+//!
+//! Feed it samples from the `dev` or `eq` stream:
+//!
+//! ```no_run
+//! use futures_util::StreamExt;
+//! use emotiv_cortex_v2::{CortexClient, HeadsetModel, streams};
+//! use emotiv_cortex_v2::quality::{ChannelTrend, TrendConfig};
+//!
+//! # async fn demo(client: &CortexClient, token: &str, session_id: &str) -> emotiv_cortex_v2::CortexResult<()> {
+//! let model = HeadsetModel::EpocX;
+//! let mut trend = ChannelTrend::new(model.channel_names(), TrendConfig::default());
+//! let mut alerts = trend.subscribe();
+//! tokio::spawn(async move {
+//!     while let Ok(alert) = alerts.recv().await {
+//!         eprintln!("{}: {:?}", alert.name, alert.kind);
+//!     }
+//! });
+//!
+//! let mut dev = streams::subscribe_dev(client, token, session_id, model.num_channels()).await?;
+//! while let Some(quality) = dev.next().await {
+//!     trend.observe(&quality);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::streams::{StreamSample, micros_to_secs};
+use crate::util::{f64_to_f32, now_micros, usize_to_f64};
+
+/// Capacity of the [`QualityAlert`] broadcast channel.
+const ALERT_CHANNEL_CAPACITY: usize = 64;
+
+/// Default span of quality history each trend is fitted over.
+pub const DEFAULT_TREND_WINDOW: Duration = Duration::from_secs(120);
+
+/// Default history needed before a trend is reported.
+pub const DEFAULT_TREND_MIN_SPAN: Duration = Duration::from_secs(30);
+
+/// Default slope (quality per minute) that counts as degrading.
+pub const DEFAULT_DEGRADING_PER_MIN: f32 = 0.05;
+
+/// Default quality at or below which a channel counts as failed
+/// (Cortex contact quality 1 of 4).
+pub const DEFAULT_FAILURE_THRESHOLD: f32 = 0.25;
+
+/// Default time-to-failure that raises a [`QualityAlertKind::Degrading`].
+pub const DEFAULT_ALERT_HORIZON: Duration = Duration::from_secs(600);
+
+/// Default margin above the failure threshold needed to recover.
+pub const DEFAULT_RECOVERY_MARGIN: f32 = 0.1;
+
+/// Tuning for [`ChannelTrend`]. Quality values are the normalized
+/// 0.0–1.0 values of [`DeviceQuality`] and [`EegQuality`].
+///
+/// [`DeviceQuality`]: crate::protocol::streams::DeviceQuality
+/// [`EegQuality`]: crate::protocol::streams::EegQuality
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrendConfig {
+    /// Span of history each trend is fitted over.
+    pub window: Duration,
+    /// History needed before a trend is reported or alerts are raised.
+    pub min_span: Duration,
+    /// Slope (quality per minute) beyond which a channel is degrading or
+    /// improving.
+    pub degrading_per_min: f32,
+    /// Quality at or below which a channel has failed.
+    pub failure_threshold: f32,
+    /// Raise a degrading alert once the estimated time to failure is
+    /// within this horizon.
+    pub alert_horizon: Duration,
+    /// A failed channel recovers once its quality exceeds
+    /// `failure_threshold + recovery_margin`.
+    pub recovery_margin: f32,
+}
+
+impl Default for TrendConfig {
+    fn default() -> Self {
+        Self {
+            window: DEFAULT_TREND_WINDOW,
+            min_span: DEFAULT_TREND_MIN_SPAN,
+            degrading_per_min: DEFAULT_DEGRADING_PER_MIN,
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            alert_horizon: DEFAULT_ALERT_HORIZON,
+            recovery_margin: DEFAULT_RECOVERY_MARGIN,
+        }
+    }
+}
+
+/// Direction of a channel's quality.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trend {
+    /// Not enough history yet.
+    Unknown,
+    /// Quality is rising.
+    Improving,
+    /// Quality is steady.
+    Stable,
+    /// Quality is falling.
+    Degrading,
+}
+
+/// Current view of one channel.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChannelStatus {
+    /// Channel index.
+    pub channel: usize,
+    /// Channel name (e.g. `"AF3"`).
+    pub name: String,
+    /// Smoothed quality 0.0–1.0.
+    pub quality: f32,
+    /// Fitted change in quality per minute.
+    pub slope_per_min: f32,
+    /// Trend classification.
+    pub trend: Trend,
+    /// Estimated time until quality reaches the failure threshold, when
+    /// degrading (zero once it has).
+    pub time_to_failure: Option<Duration>,
+}
+
+/// What a [`QualityAlert`] reports.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum QualityAlertKind {
+    /// Quality is falling and will reach the failure threshold within the
+    /// alert horizon.
+    Degrading {
+        /// Fitted change in quality per minute.
+        slope_per_min: f32,
+        /// Estimated time until failure.
+        time_to_failure: Duration,
+    },
+    /// Quality is at or below the failure threshold.
+    Failed,
+    /// A degrading or failed channel is healthy again.
+    Recovered,
+}
+
+/// A change in a channel's condition.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QualityAlert {
+    /// Channel index.
+    pub channel: usize,
+    /// Channel name.
+    pub name: String,
+    /// Time of the sample that raised the alert (microseconds).
+    pub timestamp: i64,
+    /// Smoothed quality at that time.
+    pub quality: f32,
+    /// What happened.
+    pub kind: QualityAlertKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Condition {
+    Ok,
+    AtRisk,
+    Failed,
+}
+
+#[derive(Debug)]
+struct ChannelHistory {
+    name: String,
+    /// `(seconds since the first sample, quality)`.
+    points: VecDeque<(f64, f64)>,
+    condition: Condition,
+}
+
+/// Per-channel contact-quality trend tracker. See the
+/// [module documentation](self).
+pub struct ChannelTrend {
+    config: TrendConfig,
+    channels: Vec<ChannelHistory>,
+    origin_us: Option<i64>,
+    alerts: broadcast::Sender<QualityAlert>,
+}
+
+impl ChannelTrend {
+    /// Track the channels named `channel_names`, in stream order.
+    #[must_use]
+    pub fn new<S: AsRef<str>>(channel_names: &[S], config: TrendConfig) -> Self {
+        let (alerts, _) = broadcast::channel(ALERT_CHANNEL_CAPACITY);
+        Self {
+            config,
+            channels: channel_names
+                .iter()
+                .map(|name| ChannelHistory {
+                    name: name.as_ref().to_string(),
+                    points: VecDeque::new(),
+                    condition: Condition::Ok,
+                })
+                .collect(),
+            origin_us: None,
+            alerts,
+        }
+    }
+
+    /// Subscribe to [`QualityAlert`]s.
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<QualityAlert> {
+        self.alerts.subscribe()
+    }
+
+    /// Record a quality sample (`dev` or `eq`), timestamped now unless
+    /// the sample carries its own time. Returns the alerts it raised.
+    pub fn observe(&mut self, sample: &impl StreamSample) -> Vec<QualityAlert> {
+        let Some(values) = sample.as_f32_slice() else {
+            return Vec::new();
+        };
+        let timestamp = sample.timestamp().unwrap_or_else(now_micros);
+        self.push(timestamp, values)
+    }
+
+    /// Record per-channel quality values (0.0–1.0) taken at `timestamp`
+    /// (microseconds). Extra values are ignored. Returns the alerts it
+    /// raised; they are also broadcast to [`subscribers`](Self::subscribe).
+    pub fn push(&mut self, timestamp: i64, values: &[f32]) -> Vec<QualityAlert> {
+        let origin = *self.origin_us.get_or_insert(timestamp);
+        let time = micros_to_secs(timestamp - origin);
+        let window = self.config.window.as_secs_f64();
+
+        let mut raised = Vec::new();
+        for (channel, value) in values.iter().enumerate().take(self.channels.len()) {
+            let history = &mut self.channels[channel];
+            history.points.push_back((time, f64::from(*value)));
+            while history
+                .points
+                .front()
+                .is_some_and(|(t, _)| time - t > window)
+            {
+                history.points.pop_front();
+            }
+
+            let status = status_of(channel, history, &self.config);
+            if let Some(kind) = transition(history, &status, &self.config) {
+                raised.push(QualityAlert {
+                    channel,
+                    name: status.name,
+                    timestamp,
+                    quality: status.quality,
+                    kind,
+                });
+            }
+        }
+
+        for alert in &raised {
+            tracing::warn!(
+                channel = %alert.name,
+                quality = alert.quality,
+                kind = ?alert.kind,
+                "Contact quality alert"
+            );
+            let _ = self.alerts.send(alert.clone());
+        }
+        raised
+    }
+
+    /// Current status of every channel.
+    #[must_use]
+    pub fn statuses(&self) -> Vec<ChannelStatus> {
+        self.channels
+            .iter()
+            .enumerate()
+            .map(|(channel, history)| status_of(channel, history, &self.config))
+            .collect()
+    }
+
+    /// Forget all history, e.g. after the headset is refitted.
+    pub fn reset(&mut self) {
+        self.origin_us = None;
+        for history in &mut self.channels {
+            history.points.clear();
+            history.condition = Condition::Ok;
+        }
+    }
+}
+
+/// Fit the channel's history and classify it.
+fn status_of(channel: usize, history: &ChannelHistory, config: &TrendConfig) -> ChannelStatus {
+    let points = &history.points;
+    let span = match (points.front(), points.back()) {
+        (Some(first), Some(last)) => last.0 - first.0,
+        _ => 0.0,
+    };
+    let n = usize_to_f64(points.len().max(1));
+    let mean_t = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_q = points.iter().map(|p| p.1).sum::<f64>() / n;
+
+    let mut status = ChannelStatus {
+        channel,
+        name: history.name.clone(),
+        quality: f64_to_f32(mean_q.clamp(0.0, 1.0)),
+        slope_per_min: 0.0,
+        trend: Trend::Unknown,
+        time_to_failure: None,
+    };
+    if span < config.min_span.as_secs_f64() {
+        return status;
+    }
+
+    let (stt, stq) = points.iter().fold((0.0, 0.0), |(stt, stq), (t, q)| {
+        let dt = t - mean_t;
+        (stt + dt * dt, stq + dt * (q - mean_q))
+    });
+    let slope = if stt > 0.0 { stq / stt } else { 0.0 };
+    let latest_t = points.back().map_or(mean_t, |p| p.0);
+    let quality = (mean_q + slope * (latest_t - mean_t)).clamp(0.0, 1.0);
+    let slope_per_min = slope * 60.0;
+    let threshold = f64::from(config.degrading_per_min);
+
+    status.quality = f64_to_f32(quality);
+    status.slope_per_min = f64_to_f32(slope_per_min);
+    status.trend = if slope_per_min <= -threshold {
+        Trend::Degrading
+    } else if slope_per_min >= threshold {
+        Trend::Improving
+    } else {
+        Trend::Stable
+    };
+    let margin = quality - f64::from(config.failure_threshold);
+    status.time_to_failure = if margin <= 0.0 {
+        Some(Duration::ZERO)
+    } else if status.trend == Trend::Degrading {
+        Duration::try_from_secs_f64(margin / -slope).ok()
+    } else {
+        None
+    };
+    status
+}
+
+/// Update the channel's condition; returns the alert for a change.
+fn transition(
+    history: &mut ChannelHistory,
+    status: &ChannelStatus,
+    config: &TrendConfig,
+) -> Option<QualityAlertKind> {
+    if status.trend == Trend::Unknown {
+        return None;
+    }
+    let failed = status.quality <= config.failure_threshold
+        || (history.condition == Condition::Failed
+            && status.quality <= config.failure_threshold + config.recovery_margin);
+    let at_risk = status.trend == Trend::Degrading
+        && status
+            .time_to_failure
+            .is_some_and(|ttf| ttf <= config.alert_horizon);
+    let next = if failed {
+        Condition::Failed
+    } else if at_risk {
+        Condition::AtRisk
+    } else {
+        Condition::Ok
+    };
+
+    let previous = std::mem::replace(&mut history.condition, next);
+    match (previous, next) {
+        (Condition::Ok | Condition::AtRisk, Condition::Failed) => Some(QualityAlertKind::Failed),
+        (Condition::Ok, Condition::AtRisk) => Some(QualityAlertKind::Degrading {
+            slope_per_min: status.slope_per_min,
+            time_to_failure: status.time_to_failure.unwrap_or_default(),
+        }),
+        (Condition::AtRisk | Condition::Failed, Condition::Ok) => Some(QualityAlertKind::Recovered),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: i64 = 1_000_000;
+
+    fn trend() -> ChannelTrend {
+        ChannelTrend::new(&["AF3", "AF4"], TrendConfig::default())
+    }
+
+    #[test]
+    fn test_drying_channel_warns_before_failing() {
+        let mut trend = trend();
+        let mut alerts = trend.subscribe();
+        let mut raised = Vec::new();
+        // AF3 loses 0.1 per minute; AF4 stays good.
+        for s in 0..480 {
+            let af3 = 1.0 - 0.1 * f64::from(s) / 60.0;
+            raised.extend(trend.push(i64::from(s) * SECOND, &[f64_to_f32(af3), 1.0]));
+        }
+
+        assert!(raised.iter().all(|a| a.name == "AF3"));
+        assert_eq!(raised.len(), 2, "{raised:?}");
+        let QualityAlertKind::Degrading {
+            slope_per_min,
+            time_to_failure,
+        } = raised[0].kind
+        else {
+            panic!("expected a degrading alert first: {raised:?}");
+        };
+        assert!((slope_per_min + 0.1).abs() < 1e-3);
+        // Warned at 30 s, 7 minutes before reaching 0.25.
+        assert!((time_to_failure.as_secs_f64() - 420.0).abs() < 5.0);
+        assert_eq!(raised[1].kind, QualityAlertKind::Failed);
+        assert!(raised[1].timestamp >= 449 * SECOND);
+
+        assert_eq!(alerts.try_recv().unwrap(), raised[0]);
+        let statuses = trend.statuses();
+        assert_eq!(statuses[0].time_to_failure, Some(Duration::ZERO));
+        assert_eq!(statuses[1].trend, Trend::Stable);
+    }
+
+    #[test]
+    fn test_quantized_noise_is_stable() {
+        let mut trend = trend();
+        for s in 0..300 {
+            let value = if s % 2 == 0 { 0.75 } else { 1.0 };
+            assert!(
+                trend
+                    .push(i64::from(s) * SECOND, &[value, value])
+                    .is_empty()
+            );
+        }
+        assert!(trend.statuses().iter().all(|s| s.trend == Trend::Stable));
+    }
+
+    #[test]
+    fn test_failed_channel_recovers_after_reseat() {
+        let mut trend = trend();
+        let mut kinds = Vec::new();
+        for s in 0..400 {
+            let value = if s < 60 { 0.0 } else { 1.0 };
+            let alerts = trend.push(i64::from(s) * SECOND, &[value, 1.0]);
+            kinds.extend(alerts.into_iter().map(|a| a.kind));
+        }
+        assert_eq!(
+            kinds,
+            [QualityAlertKind::Failed, QualityAlertKind::Recovered]
+        );
+    }
+}
@@ -0,0 +1,290 @@
+//! # Client-Side Rate Limiting
+//!
+//! Cortex throttles clients that send bursts of requests. When
+//! [`RateLimitConfig::enabled`] is set, [`CortexClient`] passes every
+//! JSON-RPC call through a token bucket for its [`MethodClass`] before
+//! sending it. Callers that find the bucket empty wait in FIFO order
+//! instead of failing, so UI refresh bursts are smoothed out rather than
+//! rejected by the server.
+//!
+//! Per-class counters are available from
+//! [`CortexClient::rate_limit_stats`].
+//!
+//! ```toml
+//! [rate_limit]
+//! enabled = true
+//! query = { rate_per_sec = 10.0, burst = 20 }
+//! records = { rate_per_sec = 2.0, burst = 4 }
+//! ```
+//!
+//! [`CortexClient`]: crate::CortexClient
+//! [`CortexClient::rate_limit_stats`]: crate::CortexClient::rate_limit_stats
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tokio::sync::Mutex;
+
+use crate::config::{RateLimitConfig, RateLimitRule};
+use crate::protocol::constants::Methods;
+
+/// Groups of Cortex methods that share a token bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MethodClass {
+    /// Read-only queries (`query*`, `get*`, `hasAccessRight`, ...).
+    Query,
+    /// Session lifecycle and stream subscriptions.
+    Session,
+    /// Records, markers, and exports.
+    Records,
+    /// Training and detection tuning.
+    Training,
+    /// Everything else (auth, device control, profiles, subjects).
+    Other,
+}
+
+impl MethodClass {
+    /// All classes, in stats order.
+    pub const ALL: [MethodClass; 5] = [
+        MethodClass::Query,
+        MethodClass::Session,
+        MethodClass::Records,
+        MethodClass::Training,
+        MethodClass::Other,
+    ];
+
+    /// Classify a Cortex method name.
+    #[must_use]
+    pub fn of(method: &str) -> Self {
+        match method {
+            Methods::CREATE_SESSION
+            | Methods::UPDATE_SESSION
+            | Methods::SUBSCRIBE
+            | Methods::UNSUBSCRIBE => MethodClass::Session,
+            Methods::CREATE_RECORD
+            | Methods::STOP_RECORD
+            | Methods::UPDATE_RECORD
+            | Methods::DELETE_RECORD
+            | Methods::EXPORT_RECORD
+            | Methods::CONFIG_OPT_OUT
+            | Methods::DOWNLOAD_RECORD
+            | Methods::INJECT_MARKER
+            | Methods::UPDATE_MARKER => MethodClass::Records,
+            Methods::TRAINING
+            | Methods::FACIAL_EXPRESSION_SIGNATURE_TYPE
+            | Methods::FACIAL_EXPRESSION_THRESHOLD
+            | Methods::MENTAL_COMMAND_ACTIVE_ACTION
+            | Methods::MENTAL_COMMAND_BRAIN_MAP
+            | Methods::MENTAL_COMMAND_TRAINING_THRESHOLD
+            | Methods::MENTAL_COMMAND_ACTION_SENSITIVITY => MethodClass::Training,
+            m if m.starts_with("query")
+                || m.starts_with("get")
+                || m == Methods::HAS_ACCESS_RIGHT =>
+            {
+                MethodClass::Query
+            }
+            _ => MethodClass::Other,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            MethodClass::Query => 0,
+            MethodClass::Session => 1,
+            MethodClass::Records => 2,
+            MethodClass::Training => 3,
+            MethodClass::Other => 4,
+        }
+    }
+}
+
+/// Counters for one method class.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RateLimitStats {
+    /// Method class.
+    pub class: MethodClass,
+    /// Calls let through.
+    pub acquired: u64,
+    /// Calls that had to wait for a token.
+    pub delayed: u64,
+    /// Total time spent waiting.
+    pub total_wait: Duration,
+    /// Calls currently waiting.
+    pub queued: usize,
+}
+
+struct BucketState {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// Keeps the `queued` gauge right when a waiting call is cancelled.
+struct QueuedGuard<'a>(&'a AtomicUsize);
+
+impl<'a> QueuedGuard<'a> {
+    fn enter(queued: &'a AtomicUsize) -> Self {
+        queued.fetch_add(1, Ordering::Relaxed);
+        Self(queued)
+    }
+}
+
+impl Drop for QueuedGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+struct TokenBucket {
+    rule: RateLimitRule,
+    /// Held across the wait so callers are served in FIFO order.
+    state: Mutex<BucketState>,
+    acquired: AtomicU64,
+    delayed: AtomicU64,
+    total_wait_micros: AtomicU64,
+    queued: AtomicUsize,
+}
+
+impl TokenBucket {
+    fn new(rule: RateLimitRule) -> Self {
+        Self {
+            state: Mutex::new(BucketState {
+                tokens: f64::from(rule.burst.max(1)),
+                refilled_at: Instant::now(),
+            }),
+            rule,
+            acquired: AtomicU64::new(0),
+            delayed: AtomicU64::new(0),
+            total_wait_micros: AtomicU64::new(0),
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    async fn acquire(&self) -> Duration {
+        let started = Instant::now();
+        let queued = QueuedGuard::enter(&self.queued);
+        let mut state = self.state.lock().await;
+
+        let capacity = f64::from(self.rule.burst.max(1));
+        let rate = self.rule.rate_per_sec;
+        loop {
+            let now = Instant::now();
+            let elapsed = now.duration_since(state.refilled_at).as_secs_f64();
+            state.tokens = (state.tokens + elapsed * rate).min(capacity);
+            state.refilled_at = now;
+            if state.tokens >= 1.0 || rate <= 0.0 || !rate.is_finite() {
+                break;
+            }
+            let wait = Duration::from_secs_f64((1.0 - state.tokens) / rate);
+            tokio::time::sleep(wait).await;
+        }
+        state.tokens = (state.tokens - 1.0).max(0.0);
+        drop(state);
+        drop(queued);
+
+        self.acquired.fetch_add(1, Ordering::Relaxed);
+        let waited = started.elapsed();
+        if waited >= Duration::from_millis(1) {
+            self.delayed.fetch_add(1, Ordering::Relaxed);
+            self.total_wait_micros.fetch_add(
+                u64::try_from(waited.as_micros()).unwrap_or(u64::MAX),
+                Ordering::Relaxed,
+            );
+        }
+        waited
+    }
+
+    fn stats(&self, class: MethodClass) -> RateLimitStats {
+        RateLimitStats {
+            class,
+            acquired: self.acquired.load(Ordering::Relaxed),
+            delayed: self.delayed.load(Ordering::Relaxed),
+            total_wait: Duration::from_micros(self.total_wait_micros.load(Ordering::Relaxed)),
+            queued: self.queued.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// One token bucket per [`MethodClass`].
+pub(crate) struct RateLimiter {
+    buckets: [TokenBucket; 5],
+}
+
+impl RateLimiter {
+    /// Build a limiter from config, or `None` when rate limiting is off.
+    pub(crate) fn from_config(config: &RateLimitConfig) -> Option<Self> {
+        config.enabled.then(|| Self {
+            buckets: MethodClass::ALL.map(|class| TokenBucket::new(config.rule(class))),
+        })
+    }
+
+    /// Wait for a token for `method`'s class.
+    pub(crate) async fn acquire(&self, method: &str) {
+        let class = MethodClass::of(method);
+        let waited = self.buckets[class.index()].acquire().await;
+        if waited >= Duration::from_millis(1) {
+            tracing::debug!(method, ?class, ?waited, "RPC delayed by rate limiter");
+        }
+    }
+
+    pub(crate) fn stats(&self) -> Vec<RateLimitStats> {
+        MethodClass::ALL
+            .iter()
+            .map(|class| self.buckets[class.index()].stats(*class))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_method_classification() {
+        assert_eq!(MethodClass::of(Methods::QUERY_HEADSETS), MethodClass::Query);
+        assert_eq!(
+            MethodClass::of(Methods::GET_CORTEX_INFO),
+            MethodClass::Query
+        );
+        assert_eq!(MethodClass::of(Methods::SUBSCRIBE), MethodClass::Session);
+        assert_eq!(
+            MethodClass::of(Methods::INJECT_MARKER),
+            MethodClass::Records
+        );
+        assert_eq!(MethodClass::of(Methods::TRAINING), MethodClass::Training);
+        assert_eq!(MethodClass::of(Methods::AUTHORIZE), MethodClass::Other);
+        assert_eq!(MethodClass::of(Methods::CONTROL_DEVICE), MethodClass::Other);
+    }
+
+    #[tokio::test]
+    async fn test_bucket_allows_burst_then_paces() {
+        let bucket = TokenBucket::new(RateLimitRule {
+            rate_per_sec: 50.0,
+            burst: 2,
+        });
+
+        assert!(bucket.acquire().await < Duration::from_millis(1));
+        assert!(bucket.acquire().await < Duration::from_millis(1));
+        let waited = bucket.acquire().await;
+        assert!(waited >= Duration::from_millis(15), "waited {waited:?}");
+
+        let stats = bucket.stats(MethodClass::Query);
+        assert_eq!(stats.acquired, 3);
+        assert_eq!(stats.delayed, 1);
+        assert_eq!(stats.queued, 0);
+    }
+
+    #[test]
+    fn test_disabled_config_builds_no_limiter() {
+        assert!(RateLimiter::from_config(&RateLimitConfig::default()).is_none());
+        let enabled = RateLimitConfig {
+            enabled: true,
+            ..RateLimitConfig::default()
+        };
+        assert_eq!(
+            RateLimiter::from_config(&enabled).map(|l| l.stats().len()),
+            Some(5)
+        );
+    }
+}
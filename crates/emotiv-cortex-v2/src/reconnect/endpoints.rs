@@ -1,12 +1,18 @@
+use crate::compat::ProtocolCompat;
 use crate::error::CortexResult;
-use crate::protocol::auth::UserLoginInfo;
+use crate::ownership::{CleanupScope, SessionOwner};
+use crate::protocol::auth::{LicenseInfo, UserInfo, UserLoginInfo};
 use crate::protocol::headset::{
     ConfigMappingRequest, ConfigMappingResponse, HeadsetClockSyncResult, HeadsetInfo,
-    QueryHeadsetsOptions,
+    QueryHeadsetsOptions, VirtualHeadsetOptions,
 };
 use crate::protocol::profiles::{CurrentProfileInfo, ProfileAction, ProfileInfo};
-use crate::protocol::records::{ExportFormat, MarkerInfo, RecordInfo, UpdateRecordRequest};
-use crate::protocol::session::SessionInfo;
+use crate::protocol::records::{
+    ExportFormat, ExportRecordOptions, ExportResult, MarkerInfo, QueryRecordsRequest, RecordInfo,
+    UpdateRecordRequest,
+};
+use crate::protocol::session::{QuerySessionsRequest, SessionInfo, SessionStatus};
+use crate::protocol::streams::SubscriptionResult;
 use crate::protocol::subjects::{
     DemographicAttribute, QuerySubjectsRequest, SubjectInfo, SubjectRequest,
 };
@@ -15,6 +21,7 @@ use crate::protocol::training::{
     FacialExpressionThresholdRequest, MentalCommandTrainingThresholdRequest,
     TrainedSignatureActions, TrainingStatus, TrainingTime,
 };
+use crate::self_test::{SelfTestOptions, SelfTestReport};
 
 use super::ResilientClient;
 
@@ -31,17 +38,22 @@ impl ResilientClient {
             .await
     }
 
+    /// Request-shape compatibility of the current connection. See
+    /// [`crate::compat`].
+    pub async fn protocol_compat(&self) -> ProtocolCompat {
+        self.client().await.protocol_compat()
+    }
+
     /// Check if the application has access rights.
     ///
     /// # Errors
     /// Returns any error produced by the underlying Cortex API call,
     /// including connection, authentication, protocol, and timeout errors.
     pub async fn has_access_right(&self) -> CortexResult<bool> {
-        let client_id = self.config.client_id.clone();
-        let client_secret = self.config.client_secret.clone();
+        let credentials = self.state.read().await.credentials.clone();
         self.exec(move |c| {
-            let id = client_id.clone();
-            let secret = client_secret.clone();
+            let id = credentials.client_id.clone();
+            let secret = credentials.client_secret.clone();
             async move { c.has_access_right(&id, &secret).await }
         })
         .await
@@ -61,17 +73,18 @@ impl ResilientClient {
     /// # Errors
     /// Returns any error produced by the underlying Cortex API call,
     /// including connection, authentication, protocol, and timeout errors.
-    pub async fn get_user_info(&self) -> CortexResult<serde_json::Value> {
+    pub async fn get_user_info(&self) -> CortexResult<UserInfo> {
         self.exec_with_token(|c, token| async move { c.get_user_info(&token).await })
             .await
     }
 
-    /// Get information about the license used by the application.
+    /// Get information about the license used by the application: scopes,
+    /// validity, and session quota. See [`LicenseInfo::has_eeg_access`].
     ///
     /// # Errors
     /// Returns any error produced by the underlying Cortex API call,
     /// including connection, authentication, protocol, and timeout errors.
-    pub async fn get_license_info(&self) -> CortexResult<serde_json::Value> {
+    pub async fn get_license_info(&self) -> CortexResult<LicenseInfo> {
         self.exec_with_token(|c, token| async move { c.get_license_info(&token).await })
             .await
     }
@@ -210,20 +223,107 @@ impl ResilientClient {
         .await
     }
 
+    /// Create a virtual headset.
+    ///
+    /// # Errors
+    /// Returns any error produced by the underlying Cortex API call,
+    /// including connection, authentication, protocol, timeout, and configuration errors.
+    pub async fn create_virtual_headset(
+        &self,
+        options: &VirtualHeadsetOptions,
+    ) -> CortexResult<HeadsetInfo> {
+        let options = options.clone();
+        self.exec_with_token(move |c, token| {
+            let options = options.clone();
+            async move { c.create_virtual_headset(&token, &options).await }
+        })
+        .await
+    }
+
+    /// Delete a virtual headset.
+    ///
+    /// # Errors
+    /// Returns any error produced by the underlying Cortex API call,
+    /// including connection, authentication, protocol, timeout, and configuration errors.
+    pub async fn delete_virtual_headset(
+        &self,
+        headset_id: &str,
+    ) -> CortexResult<serde_json::Value> {
+        let id = headset_id.to_string();
+        self.exec_with_token(move |c, token| {
+            let id = id.clone();
+            async move { c.delete_virtual_headset(&token, &id).await }
+        })
+        .await
+    }
+
+    /// A virtual headset with `options.channel_count` channels: an existing
+    /// one reported by `queryHeadsets` if there is one, otherwise a newly
+    /// created one. Lets CI and demos run the same code path as hardware.
+    ///
+    /// # Errors
+    /// Returns any error produced by the underlying Cortex API calls,
+    /// including connection, authentication, protocol, timeout, and configuration errors.
+    pub async fn ensure_virtual_headset(
+        &self,
+        options: &VirtualHeadsetOptions,
+    ) -> CortexResult<HeadsetInfo> {
+        let existing = self
+            .query_headsets(QueryHeadsetsOptions::default())
+            .await?
+            .into_iter()
+            .find(|h| {
+                h.is_virtual == Some(true)
+                    && h.sensors
+                        .as_ref()
+                        .is_some_and(|s| s.len() == options.channel_count)
+            });
+        match existing {
+            Some(headset) => Ok(headset),
+            None => self.create_virtual_headset(options).await,
+        }
+    }
+
     // ─── Session Management ─────────────────────────────────────────────
 
-    /// Create a session for a headset.
+    /// Create an active session for a headset.
     ///
     /// # Errors
     /// Returns any error produced by the underlying Cortex API call,
     /// including connection, authentication, protocol, and timeout errors.
     pub async fn create_session(&self, headset_id: &str) -> CortexResult<SessionInfo> {
+        self.create_session_with_status(headset_id, SessionStatus::Active)
+            .await
+    }
+
+    /// Create a session for a headset with the given initial `status`.
+    ///
+    /// An [`SessionStatus::Open`] session is activated by the first
+    /// session-scoped call (subscribe, record, marker, training), which
+    /// emits [`ConnectionEvent::SessionReactivated`](super::ConnectionEvent::SessionReactivated),
+    /// or explicitly with [`Self::activate_session`].
+    ///
+    /// # Errors
+    /// Returns any error produced by the underlying Cortex API call,
+    /// including connection, authentication, protocol, and timeout errors.
+    pub async fn create_session_with_status(
+        &self,
+        headset_id: &str,
+        status: SessionStatus,
+    ) -> CortexResult<SessionInfo> {
         let id = headset_id.to_string();
         self.exec_with_token(move |c, token| {
             let id = id.clone();
-            async move { c.create_session(&token, &id).await }
+            async move { c.create_session_with_status(&token, &id, status).await }
         })
         .await
+        .inspect(|session| {
+            self.resources.session_created(
+                &session.id,
+                headset_id,
+                status == SessionStatus::Active,
+            );
+        })
     }
 
     /// Query existing sessions.
@@ -236,6 +336,42 @@ impl ResilientClient {
             .await
     }
 
+    /// Query existing sessions, filtered and sorted client-side.
+    ///
+    /// # Errors
+    /// Returns any error produced by the underlying Cortex API call,
+    /// including connection, authentication, protocol, and timeout errors.
+    pub async fn query_sessions_with(
+        &self,
+        request: &QuerySessionsRequest,
+    ) -> CortexResult<Vec<SessionInfo>> {
+        let request = request.clone();
+        self.exec_with_token(move |c, token| {
+            let request = request.clone();
+            async move { c.query_sessions_with(&token, &request).await }
+        })
+        .await
+    }
+
+    /// Re-activate an existing session.
+    ///
+    /// Session-scoped calls already do this automatically when Cortex
+    /// reports `-32012`; see
+    /// [`ConnectionEvent::SessionReactivated`](super::ConnectionEvent::SessionReactivated).
+    ///
+    /// # Errors
+    /// Returns any error produced by the underlying Cortex API call,
+    /// including connection, authentication, protocol, and timeout errors.
+    pub async fn activate_session(&self, session_id: &str) -> CortexResult<()> {
+        let id = session_id.to_string();
+        self.exec_with_token(move |c, token| {
+            let id = id.clone();
+            async move { c.activate_session(&token, &id).await }
+        })
+        .await
+        .inspect(|()| self.resources.session_activated(session_id))
+    }
+
     /// Close a session.
     ///
     /// # Errors
@@ -248,6 +384,53 @@ impl ResilientClient {
             async move { c.close_session(&token, &id).await }
         })
         .await
+        .inspect(|()| self.resources.session_closed(session_id))
+    }
+
+    /// Close every open session in `scope`, optionally only those bound
+    /// to `headset_id`. See [`CortexClient::close_sessions`].
+    ///
+    /// # Errors
+    /// Returns any error produced by `querySessions`, including
+    /// connection, authentication, protocol, and timeout errors.
+    ///
+    /// [`CortexClient::close_sessions`]: crate::CortexClient::close_sessions
+    pub async fn close_sessions(
+        &self,
+        scope: CleanupScope,
+        headset_id: Option<&str>,
+    ) -> CortexResult<Vec<(String, CortexResult<()>)>> {
+        let headset = headset_id.map(str::to_string);
+        let closed = self
+            .exec_with_token(move |c, token| {
+                let headset = headset.clone();
+                async move { c.close_sessions(&token, scope, headset.as_deref()).await }
+            })
+            .await?;
+        for (session_id, result) in &closed {
+            if result.is_ok() {
+                self.resources.session_closed(session_id);
+            }
+        }
+        Ok(closed)
+    }
+
+    /// IDs of sessions created through this client and not yet closed.
+    #[must_use]
+    pub fn owned_sessions(&self) -> Vec<String> {
+        self.tracking.sessions.created_ids()
+    }
+
+    /// This client's Cortex application ID, if configured or learned.
+    #[must_use]
+    pub fn app_id(&self) -> Option<String> {
+        self.tracking.sessions.app_id()
+    }
+
+    /// Classify who opened `session`. See [`crate::ownership`].
+    #[must_use]
+    pub fn session_owner(&self, session: &SessionInfo) -> SessionOwner {
+        self.tracking.sessions.owner(session)
     }
 
     // ─── Data Streams ───────────────────────────────────────────────────
@@ -260,26 +443,74 @@ impl ResilientClient {
         self.client().await.create_stream_channels(streams)
     }
 
+    /// Create data stream channels that receive only one session's events.
+    ///
+    /// This delegates to the underlying
+    /// [`crate::client::CortexClient::create_stream_channels_for_session`].
+    pub async fn create_stream_channels_for_session(
+        &self,
+        session_id: &str,
+        streams: &[&str],
+    ) -> crate::client::StreamReceivers {
+        self.client()
+            .await
+            .create_stream_channels_for_session(session_id, streams)
+    }
+
+    /// Remove one session's own stream channels.
+    ///
+    /// This delegates to the underlying
+    /// [`crate::client::CortexClient::remove_session_stream_channels`].
+    pub async fn remove_session_stream_channels(&self, session_id: &str, streams: &[&str]) {
+        self.client()
+            .await
+            .remove_session_stream_channels(session_id, streams);
+    }
+
+    /// Add a single stream channel without disturbing existing ones.
+    ///
+    /// This delegates to the underlying
+    /// [`crate::client::CortexClient::add_stream_channel`].
+    pub async fn add_stream_channel(
+        &self,
+        stream: &str,
+    ) -> Option<tokio::sync::mpsc::Receiver<serde_json::Value>> {
+        self.client().await.add_stream_channel(stream)
+    }
+
     /// Subscribe to data streams.
     ///
+    /// Returns the per-stream outcome; see
+    /// [`crate::client::CortexClient::subscribe_streams`].
+    ///
     /// # Errors
     /// Returns any error produced by the underlying Cortex API call,
     /// including connection, authentication, protocol, and timeout errors.
-    pub async fn subscribe_streams(&self, session_id: &str, streams: &[&str]) -> CortexResult<()> {
+    pub async fn subscribe_streams(
+        &self,
+        session_id: &str,
+        streams: &[&str],
+    ) -> CortexResult<SubscriptionResult> {
         let sid = session_id.to_string();
         let stream_names: Vec<String> = streams
             .iter()
             .map(std::string::ToString::to_string)
             .collect();
-        self.exec_with_token(move |c, token| {
+        self.exec_with_session(session_id, move |c, token| {
             let sid = sid.clone();
             let names = stream_names.clone();
             async move {
                 let refs: Vec<&str> = names.iter().map(std::string::String::as_str).collect();
-                c.subscribe_streams(&token, &sid, &refs).await.map(|_| ())
+                c.subscribe_streams(&token, &sid, &refs).await
             }
         })
         .await
+        .inspect(|result| {
+            self.resources.subscribed(
+                session_id,
+                result.success.iter().map(|s| s.stream_name.as_str()),
+            );
+        })
     }
 
     /// Unsubscribe from data streams.
@@ -297,7 +528,7 @@ impl ResilientClient {
             .iter()
             .map(std::string::ToString::to_string)
             .collect();
-        self.exec_with_token(move |c, token| {
+        self.exec_with_session(session_id, move |c, token| {
             let sid = sid.clone();
             let names = stream_names.clone();
             async move {
@@ -306,6 +537,39 @@ impl ResilientClient {
             }
         })
         .await
+        .inspect(|()| self.resources.unsubscribed(session_id, streams))
+    }
+
+    /// Subscribe the streams named in [`CortexConfig::routes`] and start
+    /// forwarding them to their sinks. See [`crate::routes`].
+    ///
+    /// Routes are bound to `session_id`; after a reconnect creates a new
+    /// session, stop the handle and start the routes again.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ConfigError`](crate::CortexError::ConfigError)
+    /// if a route uses the `lsl` sink or a sink cannot be opened, and any
+    /// error from subscribing, including per-stream subscribe failures.
+    ///
+    /// [`CortexConfig::routes`]: crate::CortexConfig::routes
+    pub async fn start_routes(&self, session_id: &str) -> CortexResult<crate::routes::RouteHandle> {
+        crate::routes::start(self, &self.config.routes, session_id).await
+    }
+
+    // ─── Self-Test ──────────────────────────────────────────────────────
+
+    /// Check the deployment with a simulated acquisition: build features,
+    /// runtime, Cortex reachability, and a generated stream dispatched,
+    /// parsed, and recorded. No headset is involved. See
+    /// [`crate::self_test`].
+    pub async fn self_test(&self) -> SelfTestReport {
+        self.self_test_with(&SelfTestOptions::default()).await
+    }
+
+    /// [`Self::self_test`] with a chosen headset model, duration, and
+    /// scratch directory.
+    pub async fn self_test_with(&self, options: &SelfTestOptions) -> SelfTestReport {
+        crate::self_test::run(self, &self.config, options).await
     }
 
     // ─── Records ────────────────────────────────────────────────────────
@@ -318,12 +582,13 @@ impl ResilientClient {
     pub async fn create_record(&self, session_id: &str, title: &str) -> CortexResult<RecordInfo> {
         let sid = session_id.to_string();
         let t = title.to_string();
-        self.exec_with_token(move |c, token| {
+        self.exec_with_session(session_id, move |c, token| {
             let sid = sid.clone();
             let t = t.clone();
             async move { c.create_record(&token, &sid, &t).await }
         })
         .await
+        .inspect(|_| self.resources.record_started(session_id))
     }
 
     /// Stop an active recording.
@@ -333,11 +598,12 @@ impl ResilientClient {
     /// including connection, authentication, protocol, and timeout errors.
     pub async fn stop_record(&self, session_id: &str) -> CortexResult<RecordInfo> {
         let sid = session_id.to_string();
-        self.exec_with_token(move |c, token| {
+        self.exec_with_session(session_id, move |c, token| {
             let sid = sid.clone();
             async move { c.stop_record(&token, &sid).await }
         })
         .await
+        .inspect(|_| self.resources.record_stopped(session_id))
     }
 
     /// Query recorded sessions.
@@ -356,6 +622,23 @@ impl ResilientClient {
         .await
     }
 
+    /// Query recorded sessions with filtering, sorting, and pagination.
+    ///
+    /// # Errors
+    /// Returns any error produced by the underlying Cortex API call,
+    /// including connection, authentication, protocol, timeout, and configuration errors.
+    pub async fn query_records_with(
+        &self,
+        request: &QueryRecordsRequest,
+    ) -> CortexResult<Vec<RecordInfo>> {
+        let request = request.clone();
+        self.exec_with_token(move |c, token| {
+            let request = request.clone();
+            async move { c.query_records_with(&token, &request).await }
+        })
+        .await
+    }
+
     /// Export a recording to CSV or EDF format.
     ///
     /// # Errors
@@ -366,13 +649,25 @@ impl ResilientClient {
         record_ids: &[String],
         folder: &str,
         format: ExportFormat,
-    ) -> CortexResult<()> {
-        let ids = record_ids.to_vec();
-        let f = folder.to_string();
+    ) -> CortexResult<ExportResult> {
+        self.export_record_with(&ExportRecordOptions::new(record_ids, folder, format))
+            .await
+    }
+
+    /// Export recordings as described by `options`, reporting which
+    /// records were exported and which failed.
+    ///
+    /// # Errors
+    /// Returns any error produced by the underlying Cortex API call,
+    /// including connection, authentication, protocol, timeout, and configuration errors.
+    pub async fn export_record_with(
+        &self,
+        options: &ExportRecordOptions,
+    ) -> CortexResult<ExportResult> {
+        let options = options.clone();
         self.exec_with_token(move |c, token| {
-            let ids = ids.clone();
-            let f = f.clone();
-            async move { c.export_record(&token, &ids, &f, format).await }
+            let options = options.clone();
+            async move { c.export_record_with(&token, &options).await }
         })
         .await
     }
@@ -494,7 +789,7 @@ impl ResilientClient {
         let sid = session_id.to_string();
         let l = label.to_string();
         let p = port.to_string();
-        self.exec_with_token(move |c, token| {
+        self.exec_with_session(session_id, move |c, token| {
             let sid = sid.clone();
             let l = l.clone();
             let p = p.clone();
@@ -516,7 +811,7 @@ impl ResilientClient {
     ) -> CortexResult<()> {
         let sid = session_id.to_string();
         let mid = marker_id.to_string();
-        self.exec_with_token(move |c, token| {
+        self.exec_with_session(session_id, move |c, token| {
             let sid = sid.clone();
             let mid = mid.clone();
             async move { c.update_marker(&token, &sid, &mid, time).await }
@@ -715,6 +1010,9 @@ impl ResilientClient {
         profile_name: &str,
         action: ProfileAction,
     ) -> CortexResult<()> {
+        if !matches!(action, ProfileAction::Load) {
+            self.track_profile(headset_id, profile_name, action);
+        }
         let hid = headset_id.to_string();
         let pname = profile_name.to_string();
         self.exec_with_token(move |c, token| {
@@ -723,6 +1021,11 @@ impl ResilientClient {
             async move { c.setup_profile(&token, &hid, &pname, action).await }
         })
         .await
+        .inspect(|()| {
+            if matches!(action, ProfileAction::Load) {
+                self.track_profile(headset_id, profile_name, action);
+            }
+        })
     }
 
     /// Load an empty guest profile for a headset.
@@ -731,6 +1034,7 @@ impl ResilientClient {
     /// Returns any error produced by the underlying Cortex API call,
     /// including connection, authentication, protocol, and timeout errors.
     pub async fn load_guest_profile(&self, headset_id: &str) -> CortexResult<()> {
+        self.track_profile(headset_id, "", ProfileAction::Unload);
         let id = headset_id.to_string();
         self.exec_with_token(move |c, token| {
             let id = id.clone();
@@ -768,7 +1072,7 @@ impl ResilientClient {
     ) -> CortexResult<serde_json::Value> {
         let sid = session_id.to_string();
         let act = action.to_string();
-        self.exec_with_token(move |c, token| {
+        self.exec_with_session(session_id, move |c, token| {
             let sid = sid.clone();
             let act = act.clone();
             async move { c.training(&token, &sid, detection, status, &act).await }
@@ -789,7 +1093,7 @@ impl ResilientClient {
         let sid = session_id.to_string();
         let owned_actions: Option<Vec<String>> =
             actions.map(|a| a.iter().map(std::string::ToString::to_string).collect());
-        self.exec_with_token(move |c, token| {
+        self.exec_with_session(session_id, move |c, token| {
             let sid = sid.clone();
             let owned_actions = owned_actions.clone();
             async move {
@@ -815,7 +1119,7 @@ impl ResilientClient {
     ) -> CortexResult<serde_json::Value> {
         let sid = session_id.to_string();
         let owned_values: Option<Vec<i32>> = values.map(<[i32]>::to_vec);
-        self.exec_with_token(move |c, token| {
+        self.exec_with_session(session_id, move |c, token| {
             let sid = sid.clone();
             let owned_values = owned_values.clone();
             async move {
@@ -836,7 +1140,7 @@ impl ResilientClient {
         session_id: &str,
     ) -> CortexResult<serde_json::Value> {
         let sid = session_id.to_string();
-        self.exec_with_token(move |c, token| {
+        self.exec_with_session(session_id, move |c, token| {
             let sid = sid.clone();
             async move { c.mental_command_brain_map(&token, &sid).await }
         })
@@ -853,7 +1157,7 @@ impl ResilientClient {
         session_id: &str,
     ) -> CortexResult<serde_json::Value> {
         let sid = session_id.to_string();
-        self.exec_with_token(move |c, token| {
+        self.exec_with_session(session_id, move |c, token| {
             let sid = sid.clone();
             async move { c.mental_command_training_threshold(&token, &sid).await }
         })
@@ -970,7 +1274,7 @@ impl ResilientClient {
         session_id: &str,
     ) -> CortexResult<TrainingTime> {
         let sid = session_id.to_string();
-        self.exec_with_token(move |c, token| {
+        self.exec_with_session(session_id, move |c, token| {
             let sid = sid.clone();
             async move { c.get_training_time(&token, detection, &sid).await }
         })
@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::error::{CortexError, CortexResult};
+use crate::protocol::records::ExportRecordOptions;
+use crate::protocol::warnings::WarningEvent;
+
+use super::ResilientClient;
+
+impl ResilientClient {
+    /// Export recordings and wait until Cortex has written them.
+    ///
+    /// `exportRecord` only starts the export; Cortex reports progress and
+    /// completion per record with [`WarningEvent::ExportProgress`] and
+    /// [`WarningEvent::ExportFinished`] warnings. This resolves once every
+    /// record Cortex accepted has finished, with the paths of all files
+    /// written.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ExportFailed`] for the first record Cortex
+    /// refuses or fails to export, [`CortexError::Timeout`] if the exports
+    /// have not all finished within `timeout`, and any error of
+    /// [`Self::export_record_with`].
+    pub async fn export_record_and_wait(
+        &self,
+        options: &ExportRecordOptions,
+        timeout: Duration,
+    ) -> CortexResult<Vec<PathBuf>> {
+        // Subscribe first: completion may arrive before the reply.
+        let mut warnings = self.warning_receiver();
+        let result = self.export_record_with(options).await?;
+        if let Some(failure) = result.failure.into_iter().next() {
+            return Err(CortexError::ExportFailed {
+                record_id: failure.record_id,
+                reason: failure.message,
+            });
+        }
+
+        let mut waiting: HashSet<String> =
+            result.success.into_iter().map(|s| s.record_id).collect();
+        let mut files = Vec::new();
+        let finished = async {
+            while !waiting.is_empty() {
+                match warnings.recv().await {
+                    Ok(WarningEvent::ExportProgress {
+                        record_id: Some(record_id),
+                        percent,
+                    }) if waiting.contains(&record_id) => {
+                        tracing::debug!(record_id, ?percent, "Export progress");
+                    }
+                    Ok(WarningEvent::ExportFinished {
+                        record_id: Some(record_id),
+                        files: written,
+                        error,
+                    }) if waiting.remove(&record_id) => {
+                        if let Some(reason) = error {
+                            return Err(CortexError::ExportFailed { record_id, reason });
+                        }
+                        tracing::info!(record_id, files = written.len(), "Record exported");
+                        files.extend(written.into_iter().map(PathBuf::from));
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "Cortex warnings dropped while awaiting export");
+                    }
+                    Err(RecvError::Closed) => {
+                        return Err(CortexError::ConnectionLost {
+                            reason: "warning channel closed while awaiting export".into(),
+                        });
+                    }
+                }
+            }
+            Ok(())
+        };
+        tokio::time::timeout(timeout, finished)
+            .await
+            .map_err(|_| CortexError::Timeout {
+                seconds: timeout.as_secs(),
+            })??;
+        Ok(files)
+    }
+}
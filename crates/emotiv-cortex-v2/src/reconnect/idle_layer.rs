@@ -0,0 +1,52 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::CortexResult;
+use crate::protocol::records::RecordInfo;
+
+use super::{ConnectionEvent, ResilientClient};
+
+impl ResilientClient {
+    /// Start a record titled
+    /// [`IdleConfig::default_record_title`](crate::config::IdleConfig::default_record_title)
+    /// on `session_id`: the one-call answer to
+    /// [`ConnectionEvent::IdleSession`].
+    ///
+    /// # Errors
+    /// Returns any error produced by the underlying Cortex API call,
+    /// including connection, authentication, protocol, and timeout errors.
+    pub async fn start_default_recording(&self, session_id: &str) -> CortexResult<RecordInfo> {
+        self.create_record(session_id, &self.config.idle.default_record_title)
+            .await
+    }
+
+    /// Raise [`ConnectionEvent::IdleSession`] for sessions created through
+    /// the client that sit without streams or a record for
+    /// [`IdleConfig::after_secs`](crate::config::IdleConfig::after_secs).
+    ///
+    /// The task ends when the client is dropped.
+    pub(super) fn watch_idle_sessions(&self) {
+        let resources = Arc::downgrade(&self.resources);
+        let event_tx = self.event_tx.clone();
+        let after = Duration::from_secs(self.config.idle.after_secs);
+        let period = (after / 4).clamp(Duration::from_millis(250), Duration::from_secs(15));
+
+        self.tasks.spawn("idle session watcher", async move {
+            let mut ticks = tokio::time::interval(period);
+            loop {
+                ticks.tick().await;
+                let Some(resources) = resources.upgrade() else {
+                    break;
+                };
+                for (session_id, since) in resources.take_idle(after) {
+                    tracing::warn!(
+                        session_id,
+                        idle_secs = after.as_secs(),
+                        "Session has no streams and no record; was recording forgotten?"
+                    );
+                    let _ = event_tx.send(ConnectionEvent::IdleSession { session_id, since });
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use crate::error::CortexError;
+
+use super::{ConnectionEvent, ResilientClient};
+
+impl ResilientClient {
+    /// Probe active sessions created through the client every
+    /// [`KeepAliveConfig::interval_secs`](crate::config::KeepAliveConfig::interval_secs)
+    /// and raise [`ConnectionEvent::SessionClosed`] for those Cortex
+    /// rejects.
+    ///
+    /// Probes go straight to the current connection: connection errors
+    /// and timeouts are left to the health monitor and reconnect logic.
+    /// The task ends when the client is dropped.
+    pub(super) fn keep_sessions_alive(&self) {
+        let resources = Arc::downgrade(&self.resources);
+        let state = Arc::downgrade(&self.state);
+        let reconnecting = Arc::clone(&self.reconnecting);
+        let event_tx = self.event_tx.clone();
+        let period = Duration::from_secs(self.config.keep_alive.interval_secs);
+
+        self.tasks.spawn("session keep-alive", async move {
+            let mut ticks = tokio::time::interval(period);
+            ticks.tick().await;
+            loop {
+                ticks.tick().await;
+                let (Some(resources), Some(state)) = (resources.upgrade(), state.upgrade()) else {
+                    break;
+                };
+                if reconnecting.load(Ordering::SeqCst) {
+                    continue;
+                }
+                let (client, token) = {
+                    let state = state.read().await;
+                    (Arc::clone(&state.client), state.cortex_token.clone())
+                };
+                for session_id in resources.activated_sessions() {
+                    let Err(e) = client.activate_session(&token, &session_id).await else {
+                        continue;
+                    };
+                    if !session_gone(&e) {
+                        tracing::debug!(session_id, error = %e, "Session keep-alive probe failed");
+                        continue;
+                    }
+                    tracing::warn!(session_id, error = %e, "Session closed by Cortex");
+                    resources.session_closed(&session_id);
+                    let _ = event_tx.send(ConnectionEvent::SessionClosed {
+                        session_id,
+                        reason: e.to_string(),
+                    });
+                }
+            }
+        });
+    }
+}
+
+/// Whether a failed `updateSession` means Cortex no longer has the
+/// session (`-32007`), as opposed to a connection, token, or other API
+/// problem that a later probe may get past.
+fn session_gone(error: &CortexError) -> bool {
+    matches!(error, CortexError::SessionNotFound { .. })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_gone_only_for_missing_session() {
+        assert!(session_gone(&CortexError::from_api_error(
+            -32007,
+            "session does not exist"
+        )));
+        assert!(!session_gone(&CortexError::from_api_error(
+            -32005,
+            "session already exists"
+        )));
+        assert!(!session_gone(&CortexError::from_api_error(
+            -32012,
+            "session must be activated"
+        )));
+        assert!(!session_gone(&CortexError::from_api_error(-32999, "other")));
+    }
+}
@@ -6,6 +6,9 @@
 //! - **Token management** — internal tracking with proactive refresh
 //! - **Connection events** — broadcast channel for lifecycle notifications
 //! - **Health monitoring** — optional background liveness checks
+//! - **Ping/pong** — with [`PingConfig`](crate::config::PingConfig)
+//!   enabled, a silent connection is dropped within seconds and reported
+//!   as [`ConnectionEvent::Disconnected`]; the next call reconnects
 //!
 //! ## Usage
 //!
@@ -42,8 +45,104 @@
 //! 4. On success: re-authenticates, emits `ConnectionEvent::Reconnected`
 //! 5. On exhaustion: emits `ConnectionEvent::ReconnectFailed`
 //!
-//! **Streams are NOT auto-re-subscribed.** Consumers must listen for
-//! `Reconnected` events and re-subscribe, since the session ID changes.
+//! Stream subscriptions do not survive the connection. By default
+//! consumers must listen for `Reconnected` events and re-subscribe.
+//!
+//! With [`ReconnectConfig::resubscribe`] set, the client does it: stream
+//! channels carry over to the new connection, so existing receivers and
+//! [`TypedStream`]s keep delivering, and every stream subscribed through
+//! the client is subscribed again. A session Cortex no longer knows is
+//! recreated on the headset it was created for. Each re-subscribed
+//! session emits `ConnectionEvent::Resubscribed`.
+//!
+//! [`ReconnectConfig::resubscribe`]: crate::config::ReconnectConfig::resubscribe
+//! [`TypedStream`]: crate::streams::TypedStream
+//!
+//! ## Session Re-activation
+//!
+//! Session-scoped calls (subscribe, records, markers, training) that fail
+//! with "session must be activated" (`-32012`) re-activate the session
+//! via `updateSession` once, emit `ConnectionEvent::SessionReactivated`,
+//! and retry the original call.
+//!
+//! ## Profile Unloads
+//!
+//! When Cortex reports that a profile was unloaded (another application
+//! took the headset, or the user unloaded it in the Launcher), the client
+//! emits `ConnectionEvent::ProfileUnloaded`. With
+//! [`CortexConfig::auto_reload_profile`] set, a profile that was loaded
+//! through [`ResilientClient::setup_profile`] is loaded again and
+//! `ConnectionEvent::ProfileReloaded` follows.
+//!
+//! ## Warnings
+//!
+//! [`ResilientClient::warning_receiver`] yields every warning Cortex
+//! pushes, decoded as a [`WarningEvent`], from whichever connection is
+//! current; e.g. [`WarningEvent::HeadsetDisconnected`] when a headset is
+//! unplugged.
+//!
+//! [`ResilientClient::export_record_and_wait`] uses them to follow an
+//! export to completion and returns the files Cortex wrote.
+//!
+//! ## Credential Rotation
+//!
+//! [`ResilientClient::rotate_credentials`] authorizes a new client
+//! ID/secret pair on the live connection and swaps it in together with
+//! its token. Calls keep using the old token until the swap, and later
+//! token refreshes and reconnects use the new pair.
+//!
+//! ## Token Reuse
+//!
+//! With [`TokenCacheConfig::enabled`] set, the token is saved to disk and
+//! renewed with `generateNewToken` on the next start, and on reconnects
+//! and refreshes, instead of authorizing again, which would debit
+//! [`CortexConfig::debit`] sessions each time. See [`crate::token_cache`].
+//!
+//! [`TokenCacheConfig::enabled`]: crate::config::TokenCacheConfig::enabled
+//!
+//! ## Idle Sessions
+//!
+//! A session created through the client that has no subscribed streams
+//! and no running record for [`IdleConfig::after_secs`] raises
+//! `ConnectionEvent::IdleSession`; [`ResilientClient::start_default_recording`]
+//! starts a record on it in one call.
+//!
+//! [`IdleConfig::after_secs`]: crate::config::IdleConfig::after_secs
+//!
+//! ## Session Keep-Alive
+//!
+//! With [`KeepAliveConfig::enabled`] set, active sessions created through
+//! the client are probed with `updateSession` every
+//! [`KeepAliveConfig::interval_secs`]. A session Cortex has expired raises
+//! `ConnectionEvent::SessionClosed` right away, rather than a `-32012`
+//! error in the middle of a stream. Sessions created with
+//! [`SessionStatus::Open`] join once activated.
+//!
+//! [`KeepAliveConfig::enabled`]: crate::config::KeepAliveConfig::enabled
+//! [`KeepAliveConfig::interval_secs`]: crate::config::KeepAliveConfig::interval_secs
+//! [`SessionStatus::Open`]: crate::protocol::session::SessionStatus::Open
+//!
+//! ## Teardown
+//!
+//! Sessions created, streams subscribed, and records started through the
+//! client are tracked. [`ResilientClient::shutdown`], `disconnect`, and
+//! dropping the client release them in dependency order — stop records,
+//! unsubscribe, close owned sessions, then disconnect. See
+//! [`crate::teardown`].
+//!
+//! ## Retries
+//!
+//! No RPC is retried by default. [`ResilientClient::set_retry_hooks`]
+//! installs per-method retry budgets, error classification, jitter, and an
+//! on-retry callback; see [`crate::retry`]. Retries happen per RPC, inside
+//! the reconnect handling: a call that fails with a connection error is
+//! still repeated once after reconnecting.
+//!
+//! ## Deadlines
+//!
+//! [`ResilientClient::with_deadline`] bounds the total time of an
+//! operation, including any reconnect and retry it triggers, and reports
+//! where the time went if the budget runs out. See [`crate::deadline`].
 //!
 //! ## Method Contract Template
 //!
@@ -55,26 +154,46 @@
 
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
-use tokio::sync::{RwLock, broadcast};
+use serde::Serialize;
+use tokio::sync::{Mutex, RwLock, broadcast};
 use tokio::time::Instant;
 
-use crate::client::CortexClient;
+use crate::client::{CortexClient, SharedTracking};
 use crate::config::CortexConfig;
 use crate::error::CortexResult;
 use crate::health::HealthMonitor;
+use crate::latency::{LatencyStats, SlowEndpoint};
+use crate::protocol::rpc::RequestIdGenerator;
+use crate::protocol::warnings::WarningEvent;
+use crate::retry::RetryHooks;
+use crate::sink::Sink;
+use crate::teardown::{OpenResources, TaskRegistry};
+use crate::token_cache::TokenCache;
 
 mod endpoints;
+mod export_layer;
+mod idle_layer;
+mod keep_alive_layer;
 mod operation_layer;
+mod profile_layer;
 mod reconnect_layer;
+mod resubscribe_layer;
+mod snapshot;
 mod token_layer;
+mod warning_layer;
+
+pub use snapshot::SystemSnapshot;
 
 /// Token refresh interval — re-authenticate before the token expires.
 const TOKEN_REFRESH_INTERVAL: Duration = Duration::from_secs(55 * 60); // 55 minutes
 
 /// Connection lifecycle events emitted by [`ResilientClient`].
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// New variants may be added in minor releases; match with a wildcard arm.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
 pub enum ConnectionEvent {
     /// Successfully connected and authenticated.
     Connected,
@@ -90,6 +209,54 @@ pub enum ConnectionEvent {
 
     /// All reconnection attempts exhausted.
     ReconnectFailed { attempts: u32, last_error: String },
+
+    /// A call failed with "session must be activated" (`-32012`); the
+    /// session was re-activated and the call retried.
+    SessionReactivated { session_id: String },
+
+    /// After a reconnect, `streams` were subscribed again because
+    /// [`ReconnectConfig::resubscribe`](crate::config::ReconnectConfig::resubscribe)
+    /// is set. `session_id` differs from `previous_session_id` when the
+    /// session had to be recreated on its headset.
+    Resubscribed {
+        previous_session_id: String,
+        session_id: String,
+        streams: Vec<String>,
+    },
+
+    /// Cortex unloaded a profile from a headset; mental-command and
+    /// facial-expression data are meaningless until one is loaded again.
+    /// See [`ProfileUnloaded`](crate::protocol::profiles::ProfileUnloaded).
+    ProfileUnloaded {
+        headset_id: Option<String>,
+        profile: Option<String>,
+        automatic: bool,
+    },
+
+    /// A profile unloaded by Cortex was loaded again because
+    /// [`CortexConfig::auto_reload_profile`] is set.
+    ProfileReloaded { headset_id: String, profile: String },
+
+    /// [`ResilientClient::rotate_credentials`] authorized the new client
+    /// ID and the client switched to its token.
+    CredentialsRotated { client_id: String },
+
+    /// A session created through the client has had no subscribed
+    /// streams and no running record since `since`, for at least
+    /// [`IdleConfig::after_secs`](crate::config::IdleConfig::after_secs).
+    /// The operator probably forgot to start recording; see
+    /// [`ResilientClient::start_default_recording`]. Raised once per idle
+    /// period.
+    IdleSession {
+        session_id: String,
+        since: SystemTime,
+    },
+
+    /// The session keep-alive found that Cortex no longer accepts a
+    /// session created through the client (it expired or was closed
+    /// server-side). Raised once; the session is forgotten afterwards.
+    /// See [`KeepAliveConfig`](crate::config::KeepAliveConfig).
+    SessionClosed { session_id: String, reason: String },
 }
 
 /// Internal state holding the active client and authentication info.
@@ -97,6 +264,15 @@ struct ClientState {
     client: Arc<CortexClient>,
     cortex_token: String,
     token_obtained_at: Instant,
+    credentials: Credentials,
+}
+
+/// API credentials the current token was issued for. Starts as the
+/// config's pair; replaced by [`ResilientClient::rotate_credentials`].
+#[derive(Clone)]
+struct Credentials {
+    client_id: String,
+    client_secret: String,
 }
 
 /// Production-grade Cortex API client with automatic reconnection
@@ -109,10 +285,25 @@ struct ClientState {
 /// See [module docs](self) for usage examples.
 pub struct ResilientClient {
     config: CortexConfig,
-    state: RwLock<ClientState>,
+    state: Arc<RwLock<ClientState>>,
     event_tx: broadcast::Sender<ConnectionEvent>,
+    /// Cortex warnings, forwarded from each connection in turn.
+    warning_tx: broadcast::Sender<WarningEvent>,
     reconnecting: Arc<AtomicBool>,
-    health_monitor: std::sync::Mutex<Option<HealthMonitor>>,
+    /// Running health monitor. Only swapped under the lock; monitors are
+    /// stopped after the guard is released.
+    health_monitor: Mutex<Option<HealthMonitor>>,
+    tracking: SharedTracking,
+    resources: Arc<OpenResources>,
+    /// Sinks closed first on shutdown, in attach order.
+    sinks: Mutex<Vec<Box<dyn Sink>>>,
+    loaded_profiles: profile_layer::LoadedProfiles,
+    /// Background tasks, joined at the end of shutdown.
+    tasks: TaskRegistry,
+    /// Where tokens are saved for reuse, when enabled.
+    token_cache: Option<TokenCache>,
+    /// Custom request id generator installed on every connection.
+    request_ids: Option<Arc<dyn RequestIdGenerator>>,
 }
 
 impl ResilientClient {
@@ -125,27 +316,80 @@ impl ResilientClient {
     /// Returns any error produced by the underlying Cortex API call,
     /// including connection, authentication, protocol, timeout, and configuration errors.
     pub async fn connect(config: CortexConfig) -> CortexResult<Self> {
+        Self::connect_inner(config, None).await
+    }
+
+    /// Like [`Self::connect`], but every connection, including each one
+    /// made by a reconnect, uses `generator` for its request ids instead
+    /// of the [`CortexConfig::request_ids`] strategy. See
+    /// [`CortexClient::with_request_id_generator`].
+    ///
+    /// # Errors
+    /// Same as [`Self::connect`].
+    pub async fn connect_with_request_ids(
+        config: CortexConfig,
+        generator: Arc<dyn RequestIdGenerator>,
+    ) -> CortexResult<Self> {
+        Self::connect_inner(config, Some(generator)).await
+    }
+
+    async fn connect_inner(
+        config: CortexConfig,
+        request_ids: Option<Arc<dyn RequestIdGenerator>>,
+    ) -> CortexResult<Self> {
         let client = CortexClient::connect(&config).await?;
-        let cortex_token = client
-            .authenticate(&config.client_id, &config.client_secret)
-            .await?;
+        let client = match &request_ids {
+            Some(generator) => client.with_request_id_generator(Arc::clone(generator)),
+            None => client,
+        };
+        let reader = client.take_reader_handle();
+        let token_cache = TokenCache::from_config(&config);
+        let credentials = Credentials {
+            client_id: config.client_id.clone(),
+            client_secret: config.client_secret.clone(),
+        };
+        let cortex_token =
+            token_layer::obtain_token(&client, &credentials, token_cache.as_ref(), None).await?;
 
         let (event_tx, _) = broadcast::channel(64);
         let _ = event_tx.send(ConnectionEvent::Connected);
+        let tracking = client.tracking();
 
+        let client = Arc::new(client);
         let state = ClientState {
-            client: Arc::new(client),
+            client: Arc::clone(&client),
             cortex_token,
             token_obtained_at: Instant::now(),
+            credentials,
         };
 
         let resilient = Self {
             config,
-            state: RwLock::new(state),
+            state: Arc::new(RwLock::new(state)),
             event_tx,
+            warning_tx: broadcast::channel(64).0,
             reconnecting: Arc::new(AtomicBool::new(false)),
-            health_monitor: std::sync::Mutex::new(None),
+            health_monitor: Mutex::new(None),
+            tracking,
+            resources: Arc::new(OpenResources::default()),
+            sinks: Mutex::new(Vec::new()),
+            loaded_profiles: profile_layer::LoadedProfiles::default(),
+            tasks: TaskRegistry::default(),
+            token_cache,
+            request_ids,
         };
+        if let Some(reader) = reader {
+            resilient.tasks.adopt("reader loop", reader);
+        }
+        resilient.watch_profiles(&client);
+        resilient.watch_warnings(&client);
+        resilient.watch_liveness(&client);
+        if resilient.config.idle.enabled {
+            resilient.watch_idle_sessions();
+        }
+        if resilient.config.keep_alive.enabled {
+            resilient.keep_sessions_alive();
+        }
 
         // Start health monitor if enabled
         if resilient.config.health.enabled {
@@ -159,6 +403,50 @@ impl ResilientClient {
     pub fn event_receiver(&self) -> broadcast::Receiver<ConnectionEvent> {
         self.event_tx.subscribe()
     }
+
+    /// Per-method call latency percentiles, accumulated across
+    /// reconnects. See [`crate::latency`].
+    #[must_use]
+    pub fn latency_stats(&self) -> Vec<LatencyStats> {
+        self.tracking.latency.stats()
+    }
+
+    /// Subscribe to [`SlowEndpoint`] alerts. The receiver keeps working
+    /// across reconnects.
+    #[must_use]
+    pub fn slow_endpoint_receiver(&self) -> broadcast::Receiver<SlowEndpoint> {
+        self.tracking.latency.subscribe()
+    }
+
+    /// Replace the retry settings for the RPCs this client sends. They
+    /// carry over reconnects and take effect for calls made afterwards.
+    /// See [`crate::retry`].
+    pub fn set_retry_hooks(&self, hooks: RetryHooks) {
+        self.tracking.retry.store(Arc::new(hooks));
+    }
+
+    /// The retry settings in effect.
+    #[must_use]
+    pub fn retry_hooks(&self) -> Arc<RetryHooks> {
+        self.tracking.retry.load_full()
+    }
+
+    /// Names of the background tasks this client is running (reader
+    /// loop, watchers, health event forwarder). [`Self::shutdown`] stops
+    /// and awaits all of them; see [`crate::teardown`].
+    #[must_use]
+    pub fn background_tasks(&self) -> Vec<String> {
+        self.tasks.running()
+    }
+
+    /// Spawn `task` as `name` among the client's background tasks, so
+    /// [`Self::shutdown`] stops it with the others.
+    pub(crate) fn spawn_task<F>(&self, name: &'static str, task: F)
+    where
+        F: std::future::Future<Output = ()> + Send + 'static,
+    {
+        self.tasks.spawn(name, task);
+    }
 }
 
 #[cfg(test)]
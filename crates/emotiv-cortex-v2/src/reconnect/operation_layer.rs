@@ -1,9 +1,10 @@
 use std::sync::Arc;
 
 use crate::client::CortexClient;
-use crate::error::CortexResult;
+use crate::deadline::{self, DeadlinePhase};
+use crate::error::{CortexError, CortexResult};
 
-use super::ResilientClient;
+use super::{ConnectionEvent, ResilientClient};
 
 impl ResilientClient {
     /// Get a clone of the Arc<CortexClient> and the current token.
@@ -24,12 +25,12 @@ impl ResilientClient {
         Fut: std::future::Future<Output = CortexResult<T>>,
     {
         let client = self.client().await;
-        match f(client).await {
+        match deadline::phase(DeadlinePhase::Rpc, f(client)).await {
             Ok(result) => Ok(result),
             Err(e) if e.is_connection_error() && self.config.reconnect.enabled => {
                 self.reconnect().await?;
                 let client = self.client().await;
-                f(client).await
+                deadline::phase(DeadlinePhase::Rpc, f(client)).await
             }
             Err(e) => Err(e),
         }
@@ -42,17 +43,101 @@ impl ResilientClient {
         F: Fn(Arc<CortexClient>, String) -> Fut,
         Fut: std::future::Future<Output = CortexResult<T>>,
     {
-        self.maybe_refresh_token().await?;
+        deadline::phase(DeadlinePhase::TokenRefresh, self.maybe_refresh_token()).await?;
 
         let (client, token) = self.client_and_token().await;
-        match f(client, token).await {
+        match deadline::phase(DeadlinePhase::Rpc, f(client, token)).await {
             Ok(result) => Ok(result),
             Err(e) if e.is_connection_error() && self.config.reconnect.enabled => {
                 self.reconnect().await?;
                 let (client, token) = self.client_and_token().await;
-                f(client, token).await
+                deadline::phase(DeadlinePhase::Rpc, f(client, token)).await
             }
             Err(e) => Err(e),
         }
     }
+
+    /// Execute a session-scoped operation like [`Self::exec_with_token`],
+    /// re-activating the session once if Cortex reports it must be
+    /// activated (`-32012`) and then retrying the original call.
+    pub(super) async fn exec_with_session<F, Fut, T>(
+        &self,
+        session_id: &str,
+        f: F,
+    ) -> CortexResult<T>
+    where
+        F: Fn(Arc<CortexClient>, String) -> Fut,
+        Fut: std::future::Future<Output = CortexResult<T>>,
+    {
+        match self.exec_with_token(&f).await {
+            Err(CortexError::SessionNotActivated { reason }) => {
+                tracing::warn!(session_id, %reason, "Session not active; re-activating");
+                let sid = session_id.to_string();
+                self.exec_with_token(move |c, token| {
+                    let sid = sid.clone();
+                    async move { c.activate_session(&token, &sid).await }
+                })
+                .await?;
+                self.resources.session_activated(session_id);
+                let _ = self.event_tx.send(ConnectionEvent::SessionReactivated {
+                    session_id: session_id.to_string(),
+                });
+                self.exec_with_token(&f).await
+            }
+            other => other,
+        }
+    }
+
+    /// Run `op` with an overall time budget covering everything it does,
+    /// including reconnects, backoff, token refreshes, and retried calls.
+    /// See [`crate::deadline`].
+    ///
+    /// `op` can make any number of calls on the client. Time is
+    /// attributed to phases as it is spent, so the breakdown in the error
+    /// shows whether the budget went on slow RPCs or on reconnecting.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::DeadlineExceeded`] if `op` has not finished
+    /// within `budget`, otherwise whatever `op` returns.
+    pub async fn with_deadline<F, T>(&self, budget: std::time::Duration, op: F) -> CortexResult<T>
+    where
+        F: AsyncFnOnce(&Self) -> CortexResult<T>,
+    {
+        deadline::run(budget, op(self)).await
+    }
+
+    /// Run a low-level [`CortexClient`] call against the current
+    /// connection and token.
+    ///
+    /// The client and token are only lent for the duration of `f`, so
+    /// they cannot be stashed and reused after a reconnect has replaced
+    /// them. The connection state's read lock is held while `f` runs:
+    /// reconnects and token refreshes wait until it returns.
+    ///
+    /// Unlike the wrapper methods, errors from `f` are returned as-is and
+    /// never trigger a reconnect. Do not call other `ResilientClient`
+    /// methods from inside `f` — one that needs to reconnect would wait
+    /// on the lock held here.
+    ///
+    /// ```no_run
+    /// # async fn demo(client: &emotiv_cortex_v2::reconnect::ResilientClient) -> emotiv_cortex_v2::CortexResult<()> {
+    /// let info = client
+    ///     .with_raw_client(async |raw, _token| raw.get_cortex_info().await)
+    ///     .await?;
+    /// # let _ = info;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the proactive token refresh check fails.
+    pub async fn with_raw_client<F, T>(&self, f: F) -> CortexResult<T>
+    where
+        F: AsyncFnOnce(&CortexClient, &str) -> T,
+    {
+        self.maybe_refresh_token().await?;
+
+        let state = self.state.read().await;
+        Ok(f(&state.client, &state.cortex_token).await)
+    }
 }
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::client::CortexClient;
+use crate::protocol::profiles::{ProfileAction, ProfileUnloaded};
+
+use super::{ConnectionEvent, ResilientClient};
+
+/// Profile loaded through the client, keyed by headset ID.
+pub(super) type LoadedProfiles = Arc<Mutex<HashMap<String, String>>>;
+
+impl ResilientClient {
+    /// Profiles loaded through this client and not unloaded since, keyed
+    /// by headset ID.
+    ///
+    /// These are the profiles reloaded when
+    /// [`CortexConfig::auto_reload_profile`](crate::CortexConfig::auto_reload_profile)
+    /// is set.
+    #[must_use]
+    pub fn loaded_profiles(&self) -> HashMap<String, String> {
+        self.loaded_profiles
+            .lock()
+            .map(|profiles| profiles.clone())
+            .unwrap_or_default()
+    }
+
+    /// Record the effect of a `setupProfile` call on the loaded profiles.
+    ///
+    /// Called before the request for actions that remove the profile, so
+    /// the unload warning Cortex pushes in response is not mistaken for
+    /// one that needs a reload, and after success for `Load`.
+    pub(super) fn track_profile(&self, headset_id: &str, profile: &str, action: ProfileAction) {
+        let Ok(mut profiles) = self.loaded_profiles.lock() else {
+            return;
+        };
+        match action {
+            ProfileAction::Load => {
+                profiles.insert(headset_id.to_string(), profile.to_string());
+            }
+            ProfileAction::Unload | ProfileAction::Delete | ProfileAction::Rename => {
+                profiles.remove(headset_id);
+            }
+            ProfileAction::Create | ProfileAction::Save => {}
+        }
+    }
+
+    /// Forward profile-unload warnings from `client` as
+    /// [`ConnectionEvent::ProfileUnloaded`], reloading the tracked profile
+    /// when auto-reload is enabled.
+    ///
+    /// Started for every connection; the task ends with the connection.
+    pub(super) fn watch_profiles(&self, client: &CortexClient) {
+        let mut unloaded = client.profile_unloaded_receiver();
+        let event_tx = self.event_tx.clone();
+        let profiles = Arc::clone(&self.loaded_profiles);
+        let state = Arc::downgrade(&self.state);
+        let auto_reload = self.config.auto_reload_profile;
+
+        self.tasks.spawn("profile watcher", async move {
+            loop {
+                let event = match unloaded.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+                let _ = event_tx.send(ConnectionEvent::ProfileUnloaded {
+                    headset_id: event.headset_id.clone(),
+                    profile: event.profile.clone(),
+                    automatic: event.automatic,
+                });
+                if !auto_reload {
+                    continue;
+                }
+
+                let targets = reload_targets(&profiles, &event);
+                if targets.is_empty() {
+                    continue;
+                }
+                let Some(state) = state.upgrade() else {
+                    break;
+                };
+                let (client, token) = {
+                    let state = state.read().await;
+                    (Arc::clone(&state.client), state.cortex_token.clone())
+                };
+                drop(state);
+
+                for (headset_id, profile) in targets {
+                    match client
+                        .setup_profile(&token, &headset_id, &profile, ProfileAction::Load)
+                        .await
+                    {
+                        Ok(()) => {
+                            tracing::info!(headset = %headset_id, %profile, "Reloaded unloaded profile");
+                            let _ = event_tx.send(ConnectionEvent::ProfileReloaded {
+                                headset_id,
+                                profile,
+                            });
+                        }
+                        Err(e) => {
+                            tracing::warn!(
+                                headset = %headset_id,
+                                %profile,
+                                error = %e,
+                                "Failed to reload unloaded profile"
+                            );
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Tracked profiles affected by `event`: the one on the named headset,
+/// or every tracked profile when the warning does not name a headset.
+fn reload_targets(
+    profiles: &Mutex<HashMap<String, String>>,
+    event: &ProfileUnloaded,
+) -> Vec<(String, String)> {
+    let Ok(profiles) = profiles.lock() else {
+        return Vec::new();
+    };
+    profiles
+        .iter()
+        .filter(|(headset_id, profile)| {
+            event.headset_id.as_ref().is_none_or(|h| h == *headset_id)
+                && event.profile.as_ref().is_none_or(|p| p == *profile)
+        })
+        .map(|(headset_id, profile)| (headset_id.clone(), profile.clone()))
+        .collect()
+}
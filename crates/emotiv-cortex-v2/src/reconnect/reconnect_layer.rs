@@ -5,10 +5,13 @@ use std::time::Duration;
 use tokio::time::Instant;
 
 use crate::client::CortexClient;
+use crate::deadline::{self, DeadlinePhase};
 use crate::error::{CortexError, CortexResult};
 use crate::health::{HealthMonitor, HealthStatus};
+use crate::sink::Sink;
+use crate::teardown::{self, TeardownReport, TeardownStage};
 
-use super::{ClientState, ConnectionEvent, ResilientClient};
+use super::{ClientState, ConnectionEvent, ResilientClient, token_layer};
 
 impl ResilientClient {
     /// Start the background health monitor.
@@ -20,7 +23,7 @@ impl ResilientClient {
         let event_tx = self.event_tx.clone();
         let reconnecting = Arc::clone(&self.reconnecting);
 
-        tokio::spawn(async move {
+        self.tasks.spawn("health event forwarder", async move {
             while let Some(status) = rx.recv().await {
                 if let HealthStatus::Unhealthy { .. } = status {
                     if !reconnecting.load(Ordering::SeqCst) {
@@ -33,8 +36,41 @@ impl ResilientClient {
             }
         });
 
-        if let Ok(mut guard) = self.health_monitor.lock() {
-            *guard = Some(monitor);
+        // Swap under the lock, stop the old monitor outside it.
+        let previous = self.health_monitor.lock().await.replace(monitor);
+        if let Some(mut previous) = previous {
+            self.tasks
+                .spawn("health monitor stop", async move { previous.stop().await });
+        }
+    }
+
+    /// Report a connection that stopped answering pings (see
+    /// [`crate::config::PingConfig`]) as soon as the reader loop gives up
+    /// on it. Calls fail fast from then on, so the next one reconnects.
+    pub(super) fn watch_liveness(&self, client: &CortexClient) {
+        if !self.config.ping.enabled {
+            return;
+        }
+        let mut lost = client.liveness_receiver();
+        let event_tx = self.event_tx.clone();
+        let reconnecting = Arc::clone(&self.reconnecting);
+        self.tasks.spawn("liveness watcher", async move {
+            if let Ok(lost) = lost.recv().await {
+                if !reconnecting.load(Ordering::SeqCst) {
+                    let _ = event_tx.send(ConnectionEvent::Disconnected {
+                        reason: format!("No pong from Cortex for {}s", lost.silent_for.as_secs()),
+                    });
+                }
+            }
+        });
+    }
+
+    /// Stop the background health monitor, if running.
+    async fn stop_health_monitor(&self) {
+        let monitor = self.health_monitor.lock().await.take();
+        if let Some(mut monitor) = monitor {
+            self.tasks
+                .spawn("health monitor stop", async move { monitor.stop().await });
         }
     }
 
@@ -47,9 +83,12 @@ impl ResilientClient {
             .is_err()
         {
             // Another task is already reconnecting — wait for it
-            while self.reconnecting.load(Ordering::SeqCst) {
-                tokio::time::sleep(Duration::from_millis(100)).await;
-            }
+            deadline::phase(DeadlinePhase::Reconnect, async {
+                while self.reconnecting.load(Ordering::SeqCst) {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+            })
+            .await;
             // Check if the reconnection succeeded
             if self.client().await.is_connected() {
                 return Ok(());
@@ -66,11 +105,7 @@ impl ResilientClient {
         });
 
         // Stop health monitor during reconnection
-        if let Ok(mut guard) = self.health_monitor.lock() {
-            if let Some(mut monitor) = guard.take() {
-                tokio::spawn(async move { monitor.stop().await });
-            }
-        }
+        self.stop_health_monitor().await;
 
         let reconnect = &self.config.reconnect;
         let mut delay = Duration::from_secs(reconnect.base_delay_secs);
@@ -96,53 +131,16 @@ impl ResilientClient {
                 "Attempting reconnection"
             );
 
-            match CortexClient::connect(&self.config).await {
-                Ok(new_client) => {
-                    match new_client
-                        .authenticate(&self.config.client_id, &self.config.client_secret)
-                        .await
-                    {
-                        Ok(new_token) => {
-                            let new_client = Arc::new(new_client);
-
-                            // Update state
-                            {
-                                let mut state = self.state.write().await;
-                                *state = ClientState {
-                                    client: Arc::clone(&new_client),
-                                    cortex_token: new_token,
-                                    token_obtained_at: Instant::now(),
-                                };
-                            }
-
-                            let _ = self.event_tx.send(ConnectionEvent::Reconnected);
-                            tracing::info!(attempt, "Reconnected and re-authenticated");
-
-                            // Restart health monitor
-                            if self.config.health.enabled {
-                                self.start_health_monitor().await;
-                            }
-
-                            return Ok(());
-                        }
-                        Err(e) => {
-                            tracing::warn!(
-                                attempt,
-                                error = %e,
-                                "Connected but authentication failed"
-                            );
-                        }
-                    }
-                }
-                Err(e) => {
-                    tracing::warn!(attempt, error = %e, "Reconnection attempt failed");
-                }
+            crate::telemetry::reconnect_attempted();
+            let attempt_fut = Box::pin(self.replace_connection(attempt));
+            if deadline::phase(DeadlinePhase::Reconnect, attempt_fut).await {
+                return Ok(());
             }
 
             if attempt < max_attempts {
                 let delay_ms = u64::try_from(delay.as_millis()).unwrap_or(u64::MAX);
                 tracing::debug!(delay_ms, "Backing off before retry");
-                tokio::time::sleep(delay).await;
+                deadline::phase(DeadlinePhase::Backoff, tokio::time::sleep(delay)).await;
                 delay = std::cmp::min(delay * 2, max_delay);
             }
         }
@@ -160,6 +158,87 @@ impl ResilientClient {
         })
     }
 
+    /// One reconnect attempt: connect, authenticate with the current
+    /// credentials, and swap the new connection in. Returns `true` on
+    /// success.
+    async fn replace_connection(&self, attempt: u32) -> bool {
+        match CortexClient::connect(&self.config).await {
+            Ok(new_client) => {
+                let mut new_client = match &self.request_ids {
+                    Some(generator) => new_client.with_request_id_generator(Arc::clone(generator)),
+                    None => new_client,
+                };
+                new_client.share_tracking(self.tracking.clone());
+                if self.config.reconnect.resubscribe {
+                    new_client.adopt_stream_routes(&*self.client().await);
+                }
+                if let Some(reader) = new_client.take_reader_handle() {
+                    self.tasks.adopt("reader loop", reader);
+                }
+                let (credentials, previous) = {
+                    let state = self.state.read().await;
+                    (state.credentials.clone(), state.cortex_token.clone())
+                };
+                match token_layer::obtain_token(
+                    &new_client,
+                    &credentials,
+                    self.token_cache.as_ref(),
+                    Some(previous),
+                )
+                .await
+                {
+                    Ok(new_token) => {
+                        let new_client = Arc::new(new_client);
+
+                        // Update state
+                        {
+                            let mut state = self.state.write().await;
+                            *state = ClientState {
+                                client: Arc::clone(&new_client),
+                                cortex_token: new_token,
+                                token_obtained_at: Instant::now(),
+                                credentials,
+                            };
+                        }
+
+                        self.watch_profiles(&new_client);
+                        self.watch_warnings(&new_client);
+                        self.watch_liveness(&new_client);
+                        let lost = self.resources.connection_replaced();
+                        self.notify_reconnected();
+                        tracing::info!(attempt, "Reconnected and re-authenticated");
+                        if self.config.reconnect.resubscribe {
+                            self.resubscribe(&new_client, lost).await;
+                        }
+
+                        // Restart health monitor
+                        if self.config.health.enabled {
+                            self.start_health_monitor().await;
+                        }
+
+                        return true;
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            attempt,
+                            error = %e,
+                            "Connected but authentication failed"
+                        );
+                    }
+                }
+            }
+            Err(e) => {
+                tracing::warn!(attempt, error = %e, "Reconnection attempt failed");
+            }
+        }
+        false
+    }
+
+    fn notify_reconnected(&self) {
+        let _ = self.event_tx.send(ConnectionEvent::Reconnected);
+        crate::telemetry::reconnected();
+    }
+
     /// Returns whether the underlying connection is alive.
     pub async fn is_connected(&self) -> bool {
         self.client().await.is_connected()
@@ -175,30 +254,122 @@ impl ResilientClient {
 
     /// Gracefully disconnect from the Cortex service.
     ///
-    /// Stops the health monitor and drops the connection. The
+    /// Runs [`Self::shutdown`] and discards the report. The
     /// `ResilientClient` cannot be used after this call.
     ///
     /// # Errors
-    /// Returns any error produced by shutting down background health monitoring.
+    /// Currently always succeeds; teardown problems are logged. Use
+    /// [`Self::shutdown`] to inspect them.
     pub async fn disconnect(self) -> CortexResult<()> {
-        // Take the monitor out of the mutex, then drop the guard before awaiting
-        let monitor = self
-            .health_monitor
-            .lock()
-            .ok()
-            .and_then(|mut guard| guard.take());
+        let _ = self.shutdown().await;
+        Ok(())
+    }
+
+    /// Have [`Self::shutdown`] close `sink` before releasing anything, and
+    /// include its data-loss accounting in the [`TeardownReport`]. See
+    /// [`crate::sink`].
+    pub async fn attach_sink(&self, sink: impl Sink) {
+        self.sinks.lock().await.push(Box::new(sink));
+    }
+
+    /// Release everything this client opened, in dependency order, and
+    /// close the connection. See [`crate::teardown`].
+    ///
+    /// Attached sinks are closed and flushed first. Records are stopped
+    /// and streams unsubscribed before the sessions this client created
+    /// are closed; then the health monitor stops, the WebSocket closes,
+    /// and the client's background tasks are joined. Each step is bounded by
+    /// [`TeardownConfig::step_timeout_secs`](crate::config::TeardownConfig::step_timeout_secs)
+    /// and later steps run even if earlier ones fail.
+    pub async fn shutdown(self) -> TeardownReport {
+        let step_timeout = Duration::from_secs(self.config.teardown.step_timeout_secs);
+        let mut report = TeardownReport::default();
 
+        let sinks = std::mem::take(&mut *self.sinks.lock().await);
+        teardown::close_sinks(sinks, step_timeout, &mut report).await;
+
+        let (client, token) = {
+            let state = self.state.read().await;
+            (Arc::clone(&state.client), state.cortex_token.clone())
+        };
+        let sessions = self.resources.take();
+        teardown::release(
+            Arc::clone(&client),
+            &token,
+            &sessions,
+            step_timeout,
+            &mut report,
+        )
+        .await;
+
+        // Take the monitor out of the mutex, then drop the guard before awaiting
+        let monitor = self.health_monitor.lock().await.take();
         if let Some(mut monitor) = monitor {
-            monitor.stop().await;
+            report
+                .run(
+                    TeardownStage::StopHealthMonitor,
+                    "health monitor",
+                    step_timeout,
+                    async {
+                        monitor.stop().await;
+                        Ok(())
+                    },
+                )
+                .await;
         }
 
         let _ = self.event_tx.send(ConnectionEvent::Disconnected {
             reason: "Graceful disconnect".into(),
         });
+        report
+            .run(
+                TeardownStage::Disconnect,
+                "websocket",
+                step_timeout,
+                client.close_connection(),
+            )
+            .await;
+        teardown::join_tasks(&self.tasks, step_timeout, &mut report).await;
 
-        // Drop the state — the CortexClient's reader loop will stop
-        // when all Arc references are dropped.
-        Ok(())
+        report
+    }
+
+    /// Wait for `signal` (e.g. `tokio::signal::ctrl_c()`), then
+    /// [`shutdown`](Self::shutdown).
+    pub async fn shutdown_on<F: Future>(self, signal: F) -> TeardownReport {
+        signal.await;
+        self.shutdown().await
+    }
+}
+
+impl Drop for ResilientClient {
+    /// Close attached sinks and release outstanding records,
+    /// subscriptions, and owned sessions in a background task. Dropping
+    /// outside a Tokio runtime, or while the state is locked, only logs
+    /// what was left open.
+    fn drop(&mut self) {
+        let sinks = std::mem::take(self.sinks.get_mut());
+        if self.resources.is_empty() && sinks.is_empty() {
+            return;
+        }
+        let state = self
+            .state
+            .try_read()
+            .ok()
+            .map(|s| (Arc::clone(&s.client), s.cortex_token.clone()));
+        let runtime = tokio::runtime::Handle::try_current().ok();
+        let (Some((client, token)), Some(runtime)) = (state, runtime) else {
+            tracing::warn!("ResilientClient dropped with open Cortex resources; not released");
+            return;
+        };
+
+        let sessions = self.resources.take();
+        let step_timeout = Duration::from_secs(self.config.teardown.step_timeout_secs);
+        runtime.spawn(async move {
+            let mut report = TeardownReport::default();
+            teardown::close_sinks(sinks, step_timeout, &mut report).await;
+            teardown::release(client, &token, &sessions, step_timeout, &mut report).await;
+        });
     }
 }
 
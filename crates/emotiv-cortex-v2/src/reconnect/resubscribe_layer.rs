@@ -0,0 +1,88 @@
+use crate::client::CortexClient;
+use crate::error::{CortexError, CortexResult};
+use crate::protocol::streams::SubscriptionResult;
+use crate::teardown::LostSubscriptions;
+
+use super::{ConnectionEvent, ResilientClient};
+
+impl ResilientClient {
+    /// Subscribe the streams `lost` with the previous connection on
+    /// `client`. Failures are logged; the reconnect itself has succeeded.
+    pub(super) async fn resubscribe(&self, client: &CortexClient, lost: Vec<LostSubscriptions>) {
+        let token = self.state.read().await.cortex_token.clone();
+        for lost in lost {
+            match self.resubscribe_session(client, &token, &lost).await {
+                Ok((session_id, result)) => {
+                    let streams: Vec<String> = result
+                        .success
+                        .iter()
+                        .map(|s| s.stream_name.clone())
+                        .collect();
+                    if !result.is_complete() {
+                        tracing::warn!(
+                            session_id,
+                            failed = ?result.failed_streams(),
+                            "Some streams could not be re-subscribed"
+                        );
+                    }
+                    self.resources
+                        .subscribed(&session_id, streams.iter().map(String::as_str));
+                    tracing::info!(session_id, ?streams, "Re-subscribed streams");
+                    let _ = self.event_tx.send(ConnectionEvent::Resubscribed {
+                        previous_session_id: lost.session_id,
+                        session_id,
+                        streams,
+                    });
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        session_id = %lost.session_id,
+                        streams = ?lost.streams,
+                        error = %e,
+                        "Failed to re-subscribe streams after reconnect"
+                    );
+                }
+            }
+        }
+    }
+
+    /// Subscribe `lost.streams` on its session, re-activating it or
+    /// creating a replacement on the same headset if needed. Returns the
+    /// session subscribed on.
+    async fn resubscribe_session(
+        &self,
+        client: &CortexClient,
+        token: &str,
+        lost: &LostSubscriptions,
+    ) -> CortexResult<(String, SubscriptionResult)> {
+        let streams: Vec<&str> = lost.streams.iter().map(String::as_str).collect();
+        let session_id = &lost.session_id;
+
+        match client.subscribe_streams(token, session_id, &streams).await {
+            Ok(result) => Ok((session_id.clone(), result)),
+            Err(CortexError::SessionNotActivated { .. }) => {
+                client.activate_session(token, session_id).await?;
+                self.resources.session_activated(session_id);
+                let result = client
+                    .subscribe_streams(token, session_id, &streams)
+                    .await?;
+                Ok((session_id.clone(), result))
+            }
+            Err(CortexError::SessionError { reason } | CortexError::SessionNotFound { reason }) => {
+                let Some(headset) = &lost.headset else {
+                    return Err(CortexError::SessionError { reason });
+                };
+                tracing::info!(session_id, headset, %reason, "Recreating session");
+                let session = client.create_session(token, headset).await?;
+                self.resources.session_closed(session_id);
+                self.resources.session_created(&session.id, headset, true);
+                client.rekey_stream_routes(session_id, &session.id);
+                let result = client
+                    .subscribe_streams(token, &session.id, &streams)
+                    .await?;
+                Ok((session.id, result))
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
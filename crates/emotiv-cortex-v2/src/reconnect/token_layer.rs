@@ -1,8 +1,62 @@
 use tokio::time::Instant;
 
+use crate::client::CortexClient;
 use crate::error::CortexResult;
+use crate::token_cache::TokenCache;
 
-use super::{ResilientClient, TOKEN_REFRESH_INTERVAL};
+use super::{ConnectionEvent, Credentials, ResilientClient, TOKEN_REFRESH_INTERVAL};
+
+/// Get a token for `credentials` on `client`.
+///
+/// Without a cache this is a full `authorize`. With one, `previous` (or
+/// else the cached token) is renewed with `generateNewToken` first, so
+/// license sessions are not debited again, and the resulting token is
+/// saved for the next start.
+pub(super) async fn obtain_token(
+    client: &CortexClient,
+    credentials: &Credentials,
+    cache: Option<&TokenCache>,
+    previous: Option<String>,
+) -> CortexResult<String> {
+    let Credentials {
+        client_id,
+        client_secret,
+    } = credentials;
+    let Some(cache) = cache else {
+        return client.authenticate(client_id, client_secret).await;
+    };
+
+    let renewed = match previous.or_else(|| cache.load(client_id, client_secret)) {
+        Some(token) => match client
+            .generate_new_token(&token, client_id, client_secret)
+            .await
+        {
+            Ok(token) => {
+                tracing::info!("Renewed Cortex token without re-authorizing");
+                Some(token)
+            }
+            Err(e) => {
+                tracing::info!(error = %e, "Token renewal refused, authorizing");
+                None
+            }
+        },
+        None => None,
+    };
+    let token = match renewed {
+        Some(token) => token,
+        None => client.authenticate(client_id, client_secret).await?,
+    };
+    remember(cache, credentials, &token);
+    Ok(token)
+}
+
+/// Save `token` to `cache`, logging rather than failing: a cache that
+/// cannot be written only costs a debit on the next start.
+fn remember(cache: &TokenCache, credentials: &Credentials, token: &str) {
+    if let Err(e) = cache.store(&credentials.client_id, &credentials.client_secret, token) {
+        tracing::warn!(path = %cache.path().display(), error = %e, "Failed to save Cortex token");
+    }
+}
 
 impl ResilientClient {
     /// Returns the current Cortex token (for advanced use cases).
@@ -22,18 +76,24 @@ impl ResilientClient {
             let mut state = self.state.write().await;
             // Double-check after acquiring write lock
             if state.token_obtained_at.elapsed() > TOKEN_REFRESH_INTERVAL {
-                match state
-                    .client
-                    .authenticate(&self.config.client_id, &self.config.client_secret)
-                    .await
+                let previous = state.cortex_token.clone();
+                match obtain_token(
+                    &state.client,
+                    &state.credentials,
+                    self.token_cache.as_ref(),
+                    Some(previous),
+                )
+                .await
                 {
                     Ok(new_token) => {
                         state.cortex_token = new_token;
                         state.token_obtained_at = Instant::now();
                         tracing::info!("Token refreshed successfully");
+                        crate::telemetry::token_refreshed(true);
                     }
                     Err(e) => {
                         tracing::warn!(error = %e, "Token refresh failed, will retry on next call");
+                        crate::telemetry::token_refreshed(false);
                     }
                 }
             }
@@ -50,21 +110,75 @@ impl ResilientClient {
     /// Returns any error produced by the underlying Cortex API call,
     /// including connection, authentication, protocol, timeout, and configuration errors.
     pub async fn generate_new_token(&self) -> CortexResult<String> {
-        let client_id = self.config.client_id.clone();
-        let client_secret = self.config.client_secret.clone();
+        let credentials = self.state.read().await.credentials.clone();
         let new_token = self
             .exec_with_token(move |c, token| {
-                let id = client_id.clone();
-                let secret = client_secret.clone();
+                let id = credentials.client_id.clone();
+                let secret = credentials.client_secret.clone();
                 async move { c.generate_new_token(&token, &id, &secret).await }
             })
-            .await?;
+            .await;
+        crate::telemetry::token_refreshed(new_token.is_ok());
+        let new_token = new_token?;
 
         // Update internal token state
         let mut state = self.state.write().await;
         state.cortex_token.clone_from(&new_token);
         state.token_obtained_at = Instant::now();
+        if let Some(cache) = &self.token_cache {
+            remember(cache, &state.credentials, &new_token);
+        }
 
         Ok(new_token)
     }
+
+    /// Switch to a new client ID and secret without dropping the
+    /// connection, e.g. when a long-running rig rotates its API
+    /// credentials.
+    ///
+    /// The new pair is authorized on the live connection first; calls in
+    /// the meantime keep using the current token. Only once that succeeds
+    /// are the credentials and token swapped together and
+    /// [`ConnectionEvent::CredentialsRotated`] emitted. Token refreshes and
+    /// reconnects use the new pair from then on.
+    ///
+    /// # Errors
+    /// Returns the error from authorizing the new pair (for example
+    /// [`CortexError::AuthenticationFailed`](crate::CortexError::AuthenticationFailed)
+    /// for an unknown client ID); the current credentials and token are
+    /// then left in place.
+    pub async fn rotate_credentials(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+    ) -> CortexResult<()> {
+        let credentials = Credentials {
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+        };
+        let new_token = {
+            let credentials = credentials.clone();
+            self.exec(move |c| {
+                let id = credentials.client_id.clone();
+                let secret = credentials.client_secret.clone();
+                async move { c.authenticate(&id, &secret).await }
+            })
+            .await?
+        };
+
+        {
+            let mut state = self.state.write().await;
+            if let Some(cache) = &self.token_cache {
+                remember(cache, &credentials, &new_token);
+            }
+            state.credentials = credentials;
+            state.cortex_token = new_token;
+            state.token_obtained_at = Instant::now();
+        }
+        tracing::info!(client_id, "Rotated Cortex API credentials");
+        let _ = self.event_tx.send(ConnectionEvent::CredentialsRotated {
+            client_id: client_id.to_string(),
+        });
+        Ok(())
+    }
 }
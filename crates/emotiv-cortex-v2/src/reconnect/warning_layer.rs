@@ -0,0 +1,38 @@
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::client::CortexClient;
+use crate::protocol::warnings::WarningEvent;
+
+use super::ResilientClient;
+
+impl ResilientClient {
+    /// Subscribe to warnings Cortex pushes, across reconnects. See
+    /// [`WarningEvent`]; e.g. [`WarningEvent::HeadsetDisconnected`] when
+    /// a headset is unplugged.
+    #[must_use]
+    pub fn warning_receiver(&self) -> broadcast::Receiver<WarningEvent> {
+        self.warning_tx.subscribe()
+    }
+
+    /// Forward warnings from `client` to [`Self::warning_receiver`].
+    ///
+    /// Started for every connection; the task ends with the connection.
+    pub(super) fn watch_warnings(&self, client: &CortexClient) {
+        let mut warnings = client.warning_receiver();
+        let warning_tx = self.warning_tx.clone();
+        self.tasks.spawn("warning forwarder", async move {
+            loop {
+                match warnings.recv().await {
+                    Ok(warning) => {
+                        let _ = warning_tx.send(warning);
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "Cortex warnings dropped before forwarding");
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,342 @@
+//! # Record Naming Templates
+//!
+//! Studies name their recordings by convention, e.g.
+//! `SUBJ01_task-rest_run-02`, and expect the run number to go up by one
+//! for each new recording of the same subject and task. A
+//! [`RecordTemplate`] holds such a pattern; [`RecordTemplate::next_run`]
+//! finds the highest run already recorded in Cortex (via `queryRecords`)
+//! so the next title continues the sequence.
+//!
+//! Placeholders:
+//! - `{subject}`, `{task}`: from [`RecordFields`]
+//! - `{date}`: the UTC date as `YYYYMMDD`, fixed when the fields are made
+//! - `{run}`, or `{run:02}` for a zero-padded width
+//!
+//! Set [`SessionHandleOptions::record_template`] to have a
+//! [`SessionHandle`] title its records this way; use
+//! [`SessionHandle::record_file_name`] to give local files (stream
+//! recorder output, exports) the same name:
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use emotiv_cortex_v2::{CortexConfig, ResilientClient};
+//! use emotiv_cortex_v2::record_template::{RecordFields, RecordNaming, RecordTemplate};
+//! use emotiv_cortex_v2::session_handle::{SessionHandle, SessionHandleOptions};
+//!
+//! # async fn demo() -> emotiv_cortex_v2::CortexResult<()> {
+//! let client = Arc::new(ResilientClient::connect(CortexConfig::discover(None)?).await?);
+//! let options = SessionHandleOptions {
+//!     streams: vec!["eeg".into()],
+//!     record_template: Some(RecordNaming {
+//!         template: RecordTemplate::new("SUBJ{subject}_task-{task}_run-{run:02}")?,
+//!         fields: RecordFields::new("01", "rest"),
+//!     }),
+//!     ..SessionHandleOptions::default()
+//! };
+//! let (handle, _streams) = SessionHandle::open(client, "INSIGHT-A1B2C3D4", options).await?;
+//! // e.g. "SUBJ01_task-rest_run-03" when runs 1 and 2 exist.
+//! println!("{:?}", handle.record_title());
+//! let csv = handle.record_file_name("csv");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`SessionHandleOptions::record_template`]: crate::session_handle::SessionHandleOptions::record_template
+//! [`SessionHandle`]: crate::session_handle::SessionHandle
+//! [`SessionHandle::record_file_name`]: crate::session_handle::SessionHandle::record_file_name
+
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::{CortexError, CortexResult};
+use crate::protocol::records::QueryRecordsRequest;
+use crate::reconnect::ResilientClient;
+
+/// Records fetched per `queryRecords` page while looking for past runs.
+const RUN_QUERY_PAGE: u32 = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Subject,
+    Task,
+    Date,
+    Run { width: usize },
+}
+
+/// A record naming pattern. See the [module documentation](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordTemplate {
+    pattern: String,
+    segments: Vec<Segment>,
+}
+
+/// Values substituted into a [`RecordTemplate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordFields {
+    /// Subject identifier.
+    pub subject: String,
+    /// Task name.
+    pub task: String,
+    /// Date substituted for `{date}`.
+    pub date: String,
+}
+
+impl RecordFields {
+    /// Fields for `subject` and `task`, dated today (UTC).
+    pub fn new(subject: impl Into<String>, task: impl Into<String>) -> Self {
+        Self {
+            subject: subject.into(),
+            task: task.into(),
+            date: utc_date(SystemTime::now()),
+        }
+    }
+}
+
+/// A template together with the fields of one study block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordNaming {
+    /// Naming pattern.
+    pub template: RecordTemplate,
+    /// Subject, task, and date to fill in.
+    pub fields: RecordFields,
+}
+
+impl RecordTemplate {
+    /// Parse `pattern`.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ConfigError`] for an unknown or unclosed
+    /// placeholder, or more than one `{run}`.
+    pub fn new(pattern: impl Into<String>) -> CortexResult<Self> {
+        let pattern = pattern.into();
+        let segments = parse(&pattern)?;
+        Ok(Self { pattern, segments })
+    }
+
+    /// The pattern as given.
+    #[must_use]
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// The record title for `run`.
+    #[must_use]
+    pub fn render(&self, fields: &RecordFields, run: u32) -> String {
+        render(&self.segments, fields, run)
+    }
+
+    /// The run number of `title` if it was rendered from this template
+    /// with `fields`.
+    ///
+    /// A template without `{run}` reports run 1 for its one title.
+    #[must_use]
+    pub fn run_of(&self, fields: &RecordFields, title: &str) -> Option<u32> {
+        let Some(run_index) = self
+            .segments
+            .iter()
+            .position(|s| matches!(s, Segment::Run { .. }))
+        else {
+            return (self.render(fields, 1) == title).then_some(1);
+        };
+        let prefix = render(&self.segments[..run_index], fields, 0);
+        let suffix = render(&self.segments[run_index + 1..], fields, 0);
+        let digits = title.strip_prefix(&prefix)?.strip_suffix(&suffix)?;
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        digits.parse().ok()
+    }
+
+    /// One past the highest run of this template and `fields` among the
+    /// records Cortex holds, or 1 if there are none.
+    ///
+    /// # Errors
+    /// Returns any error from `queryRecords`.
+    pub async fn next_run(
+        &self,
+        client: &ResilientClient,
+        fields: &RecordFields,
+    ) -> CortexResult<u32> {
+        let mut highest = 0;
+        let mut offset = 0;
+        loop {
+            let request = QueryRecordsRequest::default()
+                .limit(RUN_QUERY_PAGE)
+                .offset(offset);
+            let page = client.query_records_with(&request).await?;
+            highest = page
+                .iter()
+                .filter_map(|record| record.title.as_deref())
+                .filter_map(|title| self.run_of(fields, title))
+                .fold(highest, u32::max);
+            if page.len() < RUN_QUERY_PAGE as usize {
+                return Ok(highest.saturating_add(1));
+            }
+            offset += RUN_QUERY_PAGE;
+        }
+    }
+}
+
+/// A file name for `title` with `extension`: characters other than ASCII
+/// letters, digits, `-`, `_`, and `.` become `_`.
+#[must_use]
+pub fn file_name(title: &str, extension: &str) -> String {
+    let stem: String = title
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let extension = extension.trim_start_matches('.');
+    if extension.is_empty() {
+        stem
+    } else {
+        format!("{stem}.{extension}")
+    }
+}
+
+fn render(segments: &[Segment], fields: &RecordFields, run: u32) -> String {
+    let mut title = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Literal(text) => title.push_str(text),
+            Segment::Subject => title.push_str(&fields.subject),
+            Segment::Task => title.push_str(&fields.task),
+            Segment::Date => title.push_str(&fields.date),
+            Segment::Run { width } => {
+                let _ = write!(title, "{run:0width$}");
+            }
+        }
+    }
+    title
+}
+
+fn parse(pattern: &str) -> CortexResult<Vec<Segment>> {
+    let invalid = |reason: String| CortexError::ConfigError {
+        reason: format!("record template '{pattern}': {reason}"),
+    };
+    let mut segments = Vec::new();
+    let mut rest = pattern;
+    while let Some(open) = rest.find('{') {
+        if open > 0 {
+            segments.push(Segment::Literal(rest[..open].to_string()));
+        }
+        let Some(close) = rest[open..].find('}') else {
+            return Err(invalid("unclosed placeholder".into()));
+        };
+        let name = &rest[open + 1..open + close];
+        segments.push(match name {
+            "subject" => Segment::Subject,
+            "task" => Segment::Task,
+            "date" => Segment::Date,
+            "run" => Segment::Run { width: 0 },
+            _ => match name.strip_prefix("run:").and_then(|w| w.parse().ok()) {
+                Some(width) => Segment::Run { width },
+                None => return Err(invalid(format!("unknown placeholder {{{name}}}"))),
+            },
+        });
+        rest = &rest[open + close + 1..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Literal(rest.to_string()));
+    }
+    let runs = segments
+        .iter()
+        .filter(|s| matches!(s, Segment::Run { .. }))
+        .count();
+    if runs > 1 {
+        return Err(invalid("more than one {run}".into()));
+    }
+    Ok(segments)
+}
+
+/// `YYYYMMDD` of `time` in UTC.
+fn utc_date(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (year, month, day) = civil_date(secs);
+    format!("{year:04}{month:02}{day:02}")
+}
+
+/// UTC `(year, month, day)` of `unix_secs`.
+pub(crate) fn civil_date(unix_secs: u64) -> (i64, i64, i64) {
+    // Days to civil date, after Howard Hinnant's `civil_from_days`.
+    let z = i64::try_from(unix_secs / 86_400).unwrap_or(0) + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn fields() -> RecordFields {
+        RecordFields {
+            subject: "01".into(),
+            task: "rest".into(),
+            date: "20240315".into(),
+        }
+    }
+
+    #[test]
+    fn test_render_and_parse_run() {
+        let template = RecordTemplate::new("SUBJ{subject}_task-{task}_run-{run:02}").unwrap();
+        assert_eq!(template.render(&fields(), 2), "SUBJ01_task-rest_run-02");
+        assert_eq!(template.render(&fields(), 123), "SUBJ01_task-rest_run-123");
+
+        assert_eq!(
+            template.run_of(&fields(), "SUBJ01_task-rest_run-07"),
+            Some(7)
+        );
+        assert_eq!(
+            template.run_of(&fields(), "SUBJ01_task-rest_run-7"),
+            Some(7)
+        );
+        assert_eq!(template.run_of(&fields(), "SUBJ02_task-rest_run-07"), None);
+        assert_eq!(template.run_of(&fields(), "SUBJ01_task-rest_run-"), None);
+        assert_eq!(template.run_of(&fields(), "SUBJ01_task-rest_run-x1"), None);
+
+        let dated = RecordTemplate::new("{date}-{subject}").unwrap();
+        assert_eq!(dated.render(&fields(), 5), "20240315-01");
+        assert_eq!(dated.run_of(&fields(), "20240315-01"), Some(1));
+    }
+
+    #[test]
+    fn test_invalid_patterns_are_rejected() {
+        for pattern in ["{subject", "{session}", "{run}-{run}", "{run:x}"] {
+            assert!(
+                matches!(
+                    RecordTemplate::new(pattern),
+                    Err(CortexError::ConfigError { .. })
+                ),
+                "{pattern}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_file_name_and_utc_date() {
+        assert_eq!(
+            file_name("SUBJ01 task/rest", ".csv"),
+            "SUBJ01_task_rest.csv"
+        );
+        assert_eq!(file_name("run-01", ""), "run-01");
+        assert_eq!(utc_date(UNIX_EPOCH), "19700101");
+        // 2024-02-29T12:00:00Z
+        let leap_day = UNIX_EPOCH + Duration::from_secs(1_709_208_000);
+        assert_eq!(utc_date(leap_day), "20240229");
+    }
+}
@@ -0,0 +1,432 @@
+//! EDF+ encoding for [`FileRecorder`](super::FileRecorder).
+//!
+//! One file holds the signals of one stream at a single sample rate, plus
+//! the `EDF Annotations` signal for markers and capture gaps. Data records last one second,
+//! or as many whole seconds as a slower stream needs for a whole number
+//! of samples.
+
+use std::collections::VecDeque;
+use std::io::{self, Seek, SeekFrom, Write};
+
+use crate::record_template::civil_date;
+use crate::util::{f64_to_i16, f64_to_u64, f64_to_usize};
+
+/// Bytes of the `EDF Annotations` signal in each data record.
+const ANNOTATION_BYTES: usize = 256;
+
+/// Offset of the "number of data records" header field.
+const RECORD_COUNT_OFFSET: u64 = 236;
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+/// One data signal of an EDF file.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct EdfSignal {
+    pub(crate) label: String,
+    pub(crate) unit: String,
+    pub(crate) physical_min: f64,
+    pub(crate) physical_max: f64,
+}
+
+/// What an [`EdfWriter`] wrote.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct EdfSummary {
+    pub(crate) records: u64,
+    /// Values outside the physical range, stored as the range limit.
+    pub(crate) clipped: u64,
+    /// Markers that did not fit in the annotation space of the last
+    /// data record.
+    pub(crate) dropped_annotations: u64,
+}
+
+/// Streaming EDF+ (`EDF+C`) writer.
+///
+/// The header is written with the first sample, with an unknown record
+/// count that [`finish`](Self::finish) fills in.
+pub(crate) struct EdfWriter<W: Write + Seek> {
+    out: W,
+    /// EDF+ local patient identification.
+    patient: String,
+    signals: Vec<EdfSignal>,
+    samples_per_record: usize,
+    record_secs: u32,
+    /// Unix time of the first sample, in seconds.
+    start: Option<f64>,
+    /// Digital values of the current record, per signal.
+    pending: Vec<Vec<i16>>,
+    /// Annotations not yet written: Unix time in seconds, duration in
+    /// seconds if any, and label.
+    annotations: VecDeque<(f64, Option<f64>, String)>,
+    summary: EdfSummary,
+}
+
+impl<W: Write + Seek> EdfWriter<W> {
+    pub(crate) fn new(out: W, signals: Vec<EdfSignal>, sample_rate: f64) -> Self {
+        let (record_secs, samples_per_record) = record_layout(sample_rate);
+        let pending = vec![Vec::with_capacity(samples_per_record); signals.len()];
+        Self {
+            out,
+            patient: "X X X X".into(),
+            signals,
+            samples_per_record,
+            record_secs,
+            start: None,
+            pending,
+            annotations: VecDeque::new(),
+            summary: EdfSummary::default(),
+        }
+    }
+
+    /// Set the patient code of the header (the other EDF+ patient
+    /// subfields stay unknown). Spaces become underscores, as EDF+
+    /// requires.
+    pub(crate) fn with_patient(mut self, code: &str) -> Self {
+        self.patient = format!("{} X X X", code.replace(' ', "_"));
+        self
+    }
+
+    /// Append one sample taken at `time` (Unix seconds). Missing and
+    /// non-finite values are stored as the digital minimum.
+    pub(crate) fn push(&mut self, time: f64, values: &[f64]) -> io::Result<()> {
+        if self.start.is_none() {
+            self.start = Some(time);
+            let header = self.header(time);
+            self.out.write_all(&header)?;
+        }
+        for (i, signal) in self.signals.iter().enumerate() {
+            let value = values.get(i).copied().unwrap_or(f64::NAN);
+            let (digital, clipped) = to_digital(signal, value);
+            self.summary.clipped += u64::from(clipped);
+            self.pending[i].push(digital);
+        }
+        if self.pending.first().map_or(0, Vec::len) >= self.samples_per_record {
+            self.write_record()?;
+        }
+        Ok(())
+    }
+
+    /// Add a marker at `time` (Unix seconds). It is written with the next
+    /// data record.
+    pub(crate) fn annotate(&mut self, time: f64, label: &str) {
+        self.annotations.push_back((time, None, tal_text(label)));
+    }
+
+    /// Add an annotation lasting `duration` seconds from `time` (Unix
+    /// seconds), such as a capture gap.
+    pub(crate) fn annotate_span(&mut self, time: f64, duration: f64, label: &str) {
+        self.annotations
+            .push_back((time, Some(duration.max(0.0)), tal_text(label)));
+    }
+
+    /// Pad and write the last data record, fill in the record count, and
+    /// return the output.
+    pub(crate) fn finish(mut self) -> io::Result<(W, EdfSummary)> {
+        if self.start.is_none() {
+            // No samples: a valid file with zero records.
+            let start = self.annotations.front().map_or(0.0, |(t, _, _)| *t);
+            self.start = Some(start);
+            let header = self.header(start);
+            self.out.write_all(&header)?;
+        }
+        if self.pending.first().is_some_and(|p| !p.is_empty()) {
+            for values in &mut self.pending {
+                let last = values.last().copied().unwrap_or(i16::MIN);
+                values.resize(self.samples_per_record, last);
+            }
+            self.write_record()?;
+        }
+        self.summary.dropped_annotations = self.annotations.len() as u64;
+
+        self.out.flush()?;
+        self.out.seek(SeekFrom::Start(RECORD_COUNT_OFFSET))?;
+        let mut count = Vec::with_capacity(8);
+        field(&mut count, &self.summary.records.to_string(), 8);
+        self.out.write_all(&count)?;
+        self.out.seek(SeekFrom::End(0))?;
+        self.out.flush()?;
+        Ok((self.out, self.summary))
+    }
+
+    fn write_record(&mut self) -> io::Result<()> {
+        let mut record =
+            Vec::with_capacity(self.signals.len() * self.samples_per_record * 2 + ANNOTATION_BYTES);
+        for values in &mut self.pending {
+            for value in values.drain(..) {
+                record.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+
+        // Time-keeping TAL first, then as many markers as fit.
+        let record_onset = self.summary.records * u64::from(self.record_secs);
+        let mut tals = format!("+{record_onset}\x14\x14\0").into_bytes();
+        let start = self.start.unwrap_or(0.0);
+        while let Some((time, duration, label)) = self.annotations.front() {
+            let duration = duration.map_or_else(String::new, |d| format!("\x15{}", onset(d)));
+            let tal = format!(
+                "+{}{duration}\x14{label}\x14\0",
+                onset((time - start).max(0.0))
+            );
+            if tals.len() + tal.len() > ANNOTATION_BYTES {
+                break;
+            }
+            tals.extend_from_slice(tal.as_bytes());
+            self.annotations.pop_front();
+        }
+        tals.resize(ANNOTATION_BYTES, 0);
+        record.extend_from_slice(&tals);
+
+        self.out.write_all(&record)?;
+        self.summary.records += 1;
+        Ok(())
+    }
+
+    fn header(&self, start: f64) -> Vec<u8> {
+        let secs = f64_to_u64(start);
+        let (year, month, day) = civil_date(secs);
+        let month_name = usize::try_from(month - 1)
+            .ok()
+            .and_then(|m| MONTHS.get(m))
+            .copied()
+            .unwrap_or("JAN");
+        let time_of_day = secs % 86_400;
+        let signal_count = self.signals.len() + 1;
+
+        let mut header = Vec::with_capacity(256 * (signal_count + 1));
+        field(&mut header, "0", 8);
+        field(&mut header, &self.patient, 80);
+        field(
+            &mut header,
+            &format!("Startdate {day:02}-{month_name}-{year:04} X X emotiv-cortex-v2"),
+            80,
+        );
+        field(
+            &mut header,
+            &format!("{day:02}.{month:02}.{:02}", year.rem_euclid(100)),
+            8,
+        );
+        field(
+            &mut header,
+            &format!(
+                "{:02}.{:02}.{:02}",
+                time_of_day / 3600,
+                time_of_day / 60 % 60,
+                time_of_day % 60
+            ),
+            8,
+        );
+        field(&mut header, &(256 * (signal_count + 1)).to_string(), 8);
+        field(&mut header, "EDF+C", 44);
+        field(&mut header, "-1", 8);
+        field(&mut header, &self.record_secs.to_string(), 8);
+        field(&mut header, &signal_count.to_string(), 4);
+
+        let annotation = EdfSignal {
+            label: "EDF Annotations".into(),
+            unit: String::new(),
+            physical_min: -1.0,
+            physical_max: 1.0,
+        };
+        let all: Vec<&EdfSignal> = self
+            .signals
+            .iter()
+            .chain(std::iter::once(&annotation))
+            .collect();
+        for s in &all {
+            field(&mut header, &s.label, 16);
+        }
+        for _ in &all {
+            field(&mut header, "", 80);
+        }
+        for s in &all {
+            field(&mut header, &s.unit, 8);
+        }
+        for s in &all {
+            field(&mut header, &number(s.physical_min), 8);
+        }
+        for s in &all {
+            field(&mut header, &number(s.physical_max), 8);
+        }
+        for _ in &all {
+            field(&mut header, &i16::MIN.to_string(), 8);
+        }
+        for _ in &all {
+            field(&mut header, &i16::MAX.to_string(), 8);
+        }
+        for _ in &all {
+            field(&mut header, "", 80);
+        }
+        for (i, _) in all.iter().enumerate() {
+            let samples = if i < self.signals.len() {
+                self.samples_per_record
+            } else {
+                ANNOTATION_BYTES / 2
+            };
+            field(&mut header, &samples.to_string(), 8);
+        }
+        for _ in &all {
+            field(&mut header, "", 32);
+        }
+        header
+    }
+}
+
+/// Record duration in whole seconds and samples per record for
+/// `sample_rate`: the shortest duration holding a whole number of
+/// samples, up to a minute.
+fn record_layout(sample_rate: f64) -> (u32, usize) {
+    let rate = if sample_rate.is_finite() && sample_rate > 0.0 {
+        sample_rate
+    } else {
+        1.0
+    };
+    let secs = (1..=60)
+        .find(|&secs| {
+            let samples = rate * f64::from(secs);
+            samples >= 1.0 && (samples - samples.round()).abs() < 1e-6
+        })
+        .unwrap_or(1);
+    (
+        secs,
+        f64_to_usize((rate * f64::from(secs)).round().max(1.0)),
+    )
+}
+
+/// `value` in the signal's digital range, and whether it was clipped.
+fn to_digital(signal: &EdfSignal, value: f64) -> (i16, bool) {
+    if !value.is_finite() {
+        return (i16::MIN, false);
+    }
+    let span = signal.physical_max - signal.physical_min;
+    let digital_span = f64::from(i16::MAX) - f64::from(i16::MIN);
+    let scaled =
+        ((value - signal.physical_min) / span * digital_span + f64::from(i16::MIN)).round();
+    let (low, high) = (f64::from(i16::MIN), f64::from(i16::MAX));
+    (
+        f64_to_i16(scaled.clamp(low, high)),
+        scaled < low || scaled > high,
+    )
+}
+
+/// `label` without the control characters that delimit TALs.
+fn tal_text(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect()
+}
+
+/// Onset in seconds as EDF+ writes it: no exponent, no trailing zeros.
+fn onset(secs: f64) -> String {
+    let text = format!("{secs:.6}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// `value` in at most 8 characters.
+fn number(value: f64) -> String {
+    let mut text = value.to_string();
+    let mut precision = 7;
+    while text.len() > 8 && precision > 0 {
+        precision -= 1;
+        text = format!("{value:.precision$}");
+    }
+    text
+}
+
+/// Append `value` as an ASCII field of `width` bytes, space padded.
+fn field(out: &mut Vec<u8>, value: &str, width: usize) {
+    let bytes: Vec<u8> = value
+        .bytes()
+        .map(|b| if b.is_ascii_graphic() { b } else { b' ' })
+        .take(width)
+        .collect();
+    out.extend_from_slice(&bytes);
+    out.extend(std::iter::repeat_n(b' ', width - bytes.len()));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn text(bytes: &[u8], start: usize, len: usize) -> String {
+        String::from_utf8_lossy(&bytes[start..start + len])
+            .trim_end()
+            .to_string()
+    }
+
+    #[test]
+    fn test_edf_header_records_and_annotations() {
+        let signals = vec![
+            EdfSignal {
+                label: "AF3".into(),
+                unit: "uV".into(),
+                physical_min: -100.0,
+                physical_max: 100.0,
+            },
+            EdfSignal {
+                label: "AF4".into(),
+                unit: "uV".into(),
+                physical_min: -100.0,
+                physical_max: 100.0,
+            },
+        ];
+        // 2024-03-15T10:20:30Z
+        let start = 1_710_498_030.0;
+        let mut edf =
+            EdfWriter::new(Cursor::new(Vec::new()), signals, 2.0).with_patient("sub-01 a");
+        edf.annotate(start + 0.5, "stim\u{1}on");
+        edf.annotate_span(start + 1.0, 2.25, "capture_gap:paused");
+        for i in 0..3 {
+            let t = start + f64::from(i) * 0.5;
+            edf.push(t, &[100.0, 1000.0]).unwrap();
+        }
+        let (out, summary) = edf.finish().unwrap();
+        let bytes = out.into_inner();
+
+        assert_eq!(text(&bytes, 0, 8), "0");
+        assert_eq!(text(&bytes, 8, 80), "sub-01_a X X X");
+        assert!(text(&bytes, 88, 80).starts_with("Startdate 15-MAR-2024"));
+        assert_eq!(text(&bytes, 168, 8), "15.03.24");
+        assert_eq!(text(&bytes, 176, 8), "10.20.30");
+        assert_eq!(text(&bytes, 184, 8), "1024");
+        assert_eq!(text(&bytes, 192, 44), "EDF+C");
+        assert_eq!(text(&bytes, 236, 8), "2");
+        assert_eq!(text(&bytes, 244, 8), "1");
+        assert_eq!(text(&bytes, 252, 4), "3");
+        assert_eq!(text(&bytes, 256 + 32, 16), "EDF Annotations");
+
+        // Two records: 2 signals x 2 samples x 2 bytes + annotations.
+        let record_len = 2 * 2 * 2 + ANNOTATION_BYTES;
+        assert_eq!(bytes.len(), 1024 + 2 * record_len);
+        let first = &bytes[1024..1024 + record_len];
+        assert_eq!(i16::from_le_bytes([first[0], first[1]]), i16::MAX);
+        assert_eq!(i16::from_le_bytes([first[4], first[5]]), i16::MAX);
+        let tals = String::from_utf8_lossy(&first[8..]);
+        assert!(
+            tals.starts_with(
+                "+0\x14\x14\0+0.5\x14stim on\x14\0+1\x152.25\x14capture_gap:paused\x14\0"
+            ),
+            "{tals:?}"
+        );
+        let second = &bytes[1024 + record_len..];
+        assert!(String::from_utf8_lossy(&second[8..]).starts_with("+1\x14\x14\0"));
+
+        assert_eq!(summary.records, 2);
+        assert_eq!(summary.clipped, 3);
+        assert_eq!(summary.dropped_annotations, 0);
+    }
+
+    #[test]
+    fn test_record_layout_for_slow_streams() {
+        assert_eq!(record_layout(128.0), (1, 128));
+        assert_eq!(record_layout(0.1), (10, 1));
+        assert_eq!(record_layout(2.5), (2, 5));
+        assert_eq!(number(-8400.0), "-8400");
+        assert_eq!(number(0.123_456_789), "0.123457");
+        assert_eq!(onset(12.5), "12.5");
+        assert_eq!(onset(3.0), "3");
+    }
+}
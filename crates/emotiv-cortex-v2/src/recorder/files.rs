@@ -0,0 +1,913 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use futures_util::future::BoxFuture;
+use serde_json::Value;
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio::task::JoinHandle;
+
+use crate::annotations::{CaptureGaps, GapAnnotation, GapCause};
+use crate::anonymize::{Anonymizer, ExportIdentity};
+use crate::error::{CortexError, CortexResult};
+use crate::protocol::constants::Streams;
+use crate::protocol::records::{ExportFormat, MarkerInfo};
+use crate::reconnect::{ConnectionEvent, ResilientClient};
+use crate::record_template;
+use crate::sink::{Sink, SinkReport};
+use crate::streams::{StreamMarker, micros_to_secs};
+use crate::util::{i64_to_f64, now_micros};
+
+use super::edf::{EdfSignal, EdfWriter};
+
+/// Streams a [`FileRecorder`] can write: those whose samples are rows of
+/// numbers.
+pub const FILE_RECORDER_STREAMS: [&str; 4] =
+    [Streams::EEG, Streams::MOT, Streams::POW, Streams::MET];
+
+/// Columns of the `eeg` and `mot` streams that are not measurements in
+/// the stream's unit.
+const BOOKKEEPING_COLUMNS: [&str; 6] = [
+    "COUNTER",
+    "INTERPOLATED",
+    "RAW_CQ",
+    "MARKER_HARDWARE",
+    "COUNTER_MEMS",
+    "INTERPOLATED_MEMS",
+];
+
+/// How one stream is stored in EDF.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdfStreamSettings {
+    /// Samples per second. EDF stores samples at a fixed rate, so this
+    /// must match what the headset delivers (e.g. 128 or 256 for `eeg`).
+    pub sample_rate: f64,
+    /// Lowest value stored; smaller values are clipped to it.
+    pub physical_min: f64,
+    /// Highest value stored; larger values are clipped to it.
+    pub physical_max: f64,
+    /// Physical dimension, e.g. `uV`.
+    pub unit: String,
+}
+
+impl EdfStreamSettings {
+    /// Defaults for `stream`: Cortex's documented rates for a standard
+    /// license and ranges that cover the stream's values.
+    #[must_use]
+    pub fn defaults_for(stream: &str) -> Option<Self> {
+        let (sample_rate, physical_min, physical_max, unit) = match stream {
+            Streams::EEG => (128.0, -8400.0, 8400.0, "uV"),
+            Streams::MOT => (64.0, -32768.0, 32767.0, ""),
+            Streams::POW => (8.0, 0.0, 10_000.0, "uV^2/Hz"),
+            Streams::MET => (0.1, 0.0, 1.0, ""),
+            _ => return None,
+        };
+        Some(Self {
+            sample_rate,
+            physical_min,
+            physical_max,
+            unit: unit.into(),
+        })
+    }
+}
+
+/// Where and how a [`FileRecorder`] writes.
+#[derive(Debug, Clone)]
+pub struct FileRecorderOptions {
+    /// [`ExportFormat::Csv`] or [`ExportFormat::Edf`] (EDF+).
+    pub format: ExportFormat,
+    /// Directory for the files; created if missing.
+    pub dir: PathBuf,
+    /// File name stem: streams go to `<name>_<stream>.<ext>`, CSV markers
+    /// to `<name>_markers.csv`.
+    pub name: String,
+    /// EDF settings per stream, overriding
+    /// [`EdfStreamSettings::defaults_for`].
+    pub edf: BTreeMap<String, EdfStreamSettings>,
+    /// Subject of the recording: the EDF patient code and the
+    /// `subjectName` of the metadata sidecar.
+    pub subject: Option<String>,
+    /// Written to `<name>_metadata.json` when set (or when
+    /// [`Self::subject`] is).
+    pub metadata: Option<Value>,
+    /// De-identify the files: the subject becomes its pseudonym, sample
+    /// and marker times are shifted by the subject's offset, and the
+    /// metadata is scrubbed. See [`crate::anonymize`].
+    pub anonymize: Option<Anonymizer>,
+    /// Pause state shared with other producers, such as a
+    /// [quality gate](crate::streams::QualityGated::report_gaps); the
+    /// recorder makes its own when `None`. See [`crate::annotations`].
+    pub gaps: Option<CaptureGaps>,
+}
+
+impl Default for FileRecorderOptions {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::Csv,
+            dir: PathBuf::from("."),
+            name: "recording".into(),
+            edf: BTreeMap::new(),
+            subject: None,
+            metadata: None,
+            anonymize: None,
+            gaps: None,
+        }
+    }
+}
+
+/// Records streams to local CSV or EDF+ files, independent of Cortex
+/// records and `exportRecord`. See the [module documentation](super).
+///
+/// Dropping the recorder leaves its tasks running until their streams
+/// end; call [`Self::stop`] to finish the files.
+pub struct FileRecorder {
+    session_id: String,
+    identity: ExportIdentity,
+    files: Vec<PathBuf>,
+    markers: Vec<mpsc::UnboundedSender<StreamMarker>>,
+    marker_file: Option<Arc<Mutex<MarkerFile>>>,
+    gaps: CaptureGaps,
+    shutdown: watch::Sender<bool>,
+    tasks: Vec<JoinHandle<SinkReport>>,
+    gap_task: JoinHandle<()>,
+}
+
+impl FileRecorder {
+    /// Create the files, subscribe `streams` on `session_id`, and start
+    /// writing.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ConfigError`] for a stream outside
+    /// [`FILE_RECORDER_STREAMS`], an EDF stream without settings, or a
+    /// file that cannot be created, and any error from subscribing,
+    /// including per-stream subscribe failures.
+    pub async fn start(
+        client: &ResilientClient,
+        session_id: &str,
+        streams: &[&str],
+        options: FileRecorderOptions,
+    ) -> CortexResult<Self> {
+        // Create every file before subscribing so mistakes fail fast.
+        let identity = ExportIdentity::new(options.anonymize.clone(), options.subject.clone());
+        let (outputs, marker_file, metadata_file) = open_files(streams, &options, &identity)?;
+        let files: Vec<PathBuf> = outputs
+            .iter()
+            .map(|(_, output)| output.path().to_path_buf())
+            .chain(marker_file.iter().map(|m| m.path.clone()))
+            .chain(metadata_file)
+            .collect();
+        let marker_file = marker_file.map(|file| Arc::new(Mutex::new(file)));
+        let gaps = options.gaps.clone().unwrap_or_default();
+
+        let names: Vec<&str> = outputs.iter().map(|(s, _)| s.as_str()).collect();
+        let mut receivers = client.create_stream_channels(&names).await;
+        let result = client.subscribe_streams(session_id, &names).await?;
+        if let Some(failure) = result.failure.first() {
+            let subscribed: Vec<&str> = result
+                .success
+                .iter()
+                .map(|s| s.stream_name.as_str())
+                .collect();
+            if !subscribed.is_empty() {
+                let _ = client.unsubscribe_streams(session_id, &subscribed).await;
+            }
+            return Err(CortexError::from_api_error(
+                failure.code,
+                format!(
+                    "recorded stream '{}': {}",
+                    failure.stream_name, failure.message
+                ),
+            ));
+        }
+
+        let (shutdown, _) = watch::channel(false);
+        let mut markers = Vec::with_capacity(outputs.len());
+        let mut tasks = Vec::with_capacity(outputs.len());
+        for (stream, output) in outputs {
+            let Some(rx) = receivers.remove(stream.as_str()) else {
+                continue;
+            };
+            let cols = result
+                .subscription(&stream)
+                .map(|s| s.cols.clone())
+                .unwrap_or_default();
+            let (marker_tx, marker_rx) = mpsc::unbounded_channel();
+            markers.push(marker_tx);
+            tasks.push(tokio::spawn(write_stream(
+                StreamInputs {
+                    stream,
+                    cols,
+                    rx,
+                    markers: marker_rx,
+                    gaps: gaps.clone(),
+                    closed_gaps: gaps.listen(),
+                    shutdown: shutdown.subscribe(),
+                },
+                output,
+                identity.clone(),
+            )));
+        }
+        let gap_task = tokio::spawn(follow_gaps(
+            client.event_receiver(),
+            gaps.clone(),
+            gaps.listen(),
+            marker_file.clone(),
+            identity.clone(),
+            shutdown.subscribe(),
+        ));
+
+        tracing::info!(session_id, ?files, "File recorder started");
+        Ok(Self {
+            session_id: session_id.to_string(),
+            identity,
+            files,
+            markers,
+            marker_file,
+            gaps,
+            shutdown,
+            tasks,
+            gap_task,
+        })
+    }
+
+    /// Session the recorded streams are subscribed on.
+    #[must_use]
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Files being written: streams, then the CSV markers file and the
+    /// metadata sidecar when there are any.
+    #[must_use]
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Pause state of the recording. Samples arriving while it is paused
+    /// are skipped, and each gap is written as an EDF+ annotation or a
+    /// `capture_gap:<cause>` row of the CSV markers file. Disconnects of
+    /// the client pause it by themselves.
+    #[must_use]
+    pub fn gaps(&self) -> &CaptureGaps {
+        &self.gaps
+    }
+
+    /// Pause recording on the operator's behalf
+    /// ([`GapCause::Paused`]).
+    pub fn pause(&self) {
+        self.gaps.pause(GapCause::Paused);
+    }
+
+    /// Undo [`Self::pause`]. Recording resumes once no other cause
+    /// (disconnect, quality gate) holds it paused.
+    pub fn resume(&self) {
+        self.gaps.resume(&GapCause::Paused);
+    }
+
+    /// Record `marker`: a row of the markers file for CSV, an annotation
+    /// in every stream file for EDF. Its time is shifted like the samples
+    /// when anonymizing.
+    pub fn add_marker(&self, marker: &StreamMarker) {
+        let marker = StreamMarker {
+            timestamp: self.identity.shift_micros(marker.timestamp),
+            ..marker.clone()
+        };
+        for tx in &self.markers {
+            let _ = tx.send(marker.clone());
+        }
+        if let Some(file) = &self.marker_file {
+            if let Ok(mut file) = file.lock() {
+                file.write_marker(&marker);
+            }
+        }
+    }
+
+    /// Inject a marker into the Cortex session at the current time and
+    /// [record it](Self::add_marker) in the files.
+    ///
+    /// # Errors
+    /// Returns any error from `injectMarker`; the marker is then not
+    /// recorded.
+    pub async fn inject_marker(
+        &self,
+        client: &ResilientClient,
+        label: &str,
+        value: i32,
+        port: &str,
+    ) -> CortexResult<MarkerInfo> {
+        let micros = now_micros();
+        let info = client
+            .inject_marker(
+                &self.session_id,
+                label,
+                value,
+                port,
+                Some(micros_to_millis(micros)),
+            )
+            .await?;
+        self.add_marker(&StreamMarker {
+            timestamp: micros,
+            label: label.to_string(),
+            value,
+            port: port.to_string(),
+        });
+        Ok(info)
+    }
+
+    /// Stop recording, write the samples already queued, and finish every
+    /// file. Returns one report per stream file, then one for the CSV
+    /// markers file. The streams stay subscribed.
+    pub async fn stop(self) -> Vec<SinkReport> {
+        let _ = self.shutdown.send(true);
+        let mut reports = Vec::with_capacity(self.files.len());
+        for task in self.tasks {
+            reports.push(task.await.unwrap_or_else(|e| SinkReport {
+                error: Some(format!("recorder task failed: {e}")),
+                ..SinkReport::new("file recorder")
+            }));
+        }
+        let _ = self.gap_task.await;
+        if let Some(file) = self.marker_file.and_then(|file| Arc::try_unwrap(file).ok()) {
+            let file = file.into_inner().unwrap_or_else(PoisonError::into_inner);
+            reports.push(file.finish());
+        }
+        reports
+    }
+}
+
+impl Sink for FileRecorder {
+    fn name(&self) -> String {
+        format!("file recorder on {}", self.session_id)
+    }
+
+    fn close(self: Box<Self>) -> BoxFuture<'static, Vec<SinkReport>> {
+        Box::pin(self.stop())
+    }
+}
+
+// ─── Writing ─────────────────────────────────────────────────────────────
+
+/// Scalar columns of a stream's data array and their labels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Layout {
+    columns: Vec<(usize, String)>,
+}
+
+impl Layout {
+    /// Labels from the subscribe `cols` when they match the data width,
+    /// otherwise `v0, v1, ...`. Nested values (the `eeg` `MARKERS`
+    /// array) are left out.
+    fn new(cols: &[String], data: &[Value]) -> Self {
+        let columns = data
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| matches!(v, Value::Number(_) | Value::Bool(_) | Value::Null))
+            .map(|(i, _)| {
+                let label = if cols.len() == data.len() {
+                    cols[i].clone()
+                } else {
+                    format!("v{i}")
+                };
+                (i, label)
+            })
+            .collect();
+        Self { columns }
+    }
+
+    fn values(&self, data: &[Value]) -> Vec<f64> {
+        self.columns
+            .iter()
+            .map(|(i, _)| match data.get(*i) {
+                Some(Value::Number(n)) => n.as_f64().unwrap_or(f64::NAN),
+                Some(Value::Bool(b)) => f64::from(u8::from(*b)),
+                _ => f64::NAN,
+            })
+            .collect()
+    }
+}
+
+enum EdfState {
+    /// No sample yet; the signals come from the first one.
+    Waiting {
+        out: BufWriter<File>,
+        settings: EdfStreamSettings,
+        patient: Option<String>,
+        markers: Vec<StreamMarker>,
+        gaps: Vec<GapAnnotation>,
+    },
+    Writing {
+        writer: EdfWriter<BufWriter<File>>,
+        layout: Layout,
+    },
+    Failed,
+}
+
+enum StreamFile {
+    Csv {
+        path: PathBuf,
+        writer: BufWriter<File>,
+        layout: Option<Layout>,
+    },
+    Edf {
+        path: PathBuf,
+        state: EdfState,
+    },
+}
+
+impl StreamFile {
+    fn path(&self) -> &Path {
+        match self {
+            StreamFile::Csv { path, .. } | StreamFile::Edf { path, .. } => path,
+        }
+    }
+
+    fn label(&self, stream: &str) -> String {
+        match self {
+            StreamFile::Csv { path, .. } => format!("{stream} -> csv:{}", path.display()),
+            StreamFile::Edf { path, .. } => format!("{stream} -> edf:{}", path.display()),
+        }
+    }
+
+    fn write(&mut self, cols: &[String], time: f64, data: &[Value]) -> io::Result<()> {
+        match self {
+            StreamFile::Csv { writer, layout, .. } => {
+                if layout.is_none() {
+                    let new = Layout::new(cols, data);
+                    let header: Vec<&str> = new.columns.iter().map(|(_, l)| l.as_str()).collect();
+                    writeln!(writer, "time,{}", header.join(","))?;
+                    *layout = Some(new);
+                }
+                let Some(layout) = layout else {
+                    return Ok(());
+                };
+                let mut row = time.to_string();
+                for value in layout.values(data) {
+                    row.push(',');
+                    if value.is_finite() {
+                        let _ = write!(row, "{value}");
+                    }
+                }
+                row.push('\n');
+                writer.write_all(row.as_bytes())
+            }
+            StreamFile::Edf { state, .. } => {
+                if let EdfState::Waiting { .. } = state {
+                    let EdfState::Waiting {
+                        out,
+                        settings,
+                        patient,
+                        markers,
+                        gaps,
+                    } = std::mem::replace(state, EdfState::Failed)
+                    else {
+                        return Ok(());
+                    };
+                    let layout = Layout::new(cols, data);
+                    let signals = layout
+                        .columns
+                        .iter()
+                        .map(|(_, label)| EdfSignal {
+                            label: label.clone(),
+                            unit: if BOOKKEEPING_COLUMNS.contains(&label.as_str()) {
+                                String::new()
+                            } else {
+                                settings.unit.clone()
+                            },
+                            physical_min: settings.physical_min,
+                            physical_max: settings.physical_max,
+                        })
+                        .collect();
+                    let mut writer = EdfWriter::new(out, signals, settings.sample_rate);
+                    if let Some(patient) = &patient {
+                        writer = writer.with_patient(patient);
+                    }
+                    for marker in &markers {
+                        writer.annotate(micros_to_secs(marker.timestamp), &marker.label);
+                    }
+                    for gap in &gaps {
+                        annotate_gap(&mut writer, gap);
+                    }
+                    *state = EdfState::Writing { writer, layout };
+                }
+                match state {
+                    EdfState::Writing { writer, layout } => writer.push(time, &layout.values(data)),
+                    _ => Err(io::Error::other("EDF output failed")),
+                }
+            }
+        }
+    }
+
+    fn mark(&mut self, marker: StreamMarker) {
+        if let StreamFile::Edf { state, .. } = self {
+            match state {
+                EdfState::Waiting { markers, .. } => markers.push(marker),
+                EdfState::Writing { writer, .. } => {
+                    writer.annotate(micros_to_secs(marker.timestamp), &marker.label);
+                }
+                EdfState::Failed => {}
+            }
+        }
+    }
+
+    fn gap(&mut self, gap: GapAnnotation) {
+        if let StreamFile::Edf { state, .. } = self {
+            match state {
+                EdfState::Waiting { gaps, .. } => gaps.push(gap),
+                EdfState::Writing { writer, .. } => annotate_gap(writer, &gap),
+                EdfState::Failed => {}
+            }
+        }
+    }
+
+    fn finish(self) -> io::Result<()> {
+        match self {
+            StreamFile::Csv { mut writer, .. } => writer.flush(),
+            StreamFile::Edf { state, .. } => match state {
+                // No samples: no signals to describe; leave the file empty.
+                EdfState::Waiting { mut out, .. } => out.flush(),
+                EdfState::Writing { writer, .. } => {
+                    let (_, summary) = writer.finish()?;
+                    if summary.clipped > 0 {
+                        tracing::warn!(
+                            clipped = summary.clipped,
+                            "EDF values outside the physical range were clipped"
+                        );
+                    }
+                    if summary.dropped_annotations > 0 {
+                        tracing::warn!(
+                            dropped = summary.dropped_annotations,
+                            "EDF annotations did not fit in the last data record"
+                        );
+                    }
+                    Ok(())
+                }
+                EdfState::Failed => Err(io::Error::other("EDF output failed")),
+            },
+        }
+    }
+}
+
+/// Output of each stream, in the order given.
+type StreamFiles = Vec<(String, StreamFile)>;
+
+/// Create the stream files, for CSV the markers file, and the metadata
+/// sidecar if there is metadata.
+fn open_files(
+    streams: &[&str],
+    options: &FileRecorderOptions,
+    identity: &ExportIdentity,
+) -> CortexResult<(StreamFiles, Option<MarkerFile>, Option<PathBuf>)> {
+    let config_error = |reason: String| CortexError::ConfigError {
+        reason: format!("file recorder: {reason}"),
+    };
+    fs::create_dir_all(&options.dir)
+        .map_err(|e| config_error(format!("{}: {e}", options.dir.display())))?;
+    let create = |stem: String, extension: &str| {
+        let path = options
+            .dir
+            .join(record_template::file_name(&stem, extension));
+        File::create(&path)
+            .map(|file| (path.clone(), BufWriter::new(file)))
+            .map_err(|e| config_error(format!("{}: {e}", path.display())))
+    };
+
+    let mut outputs = Vec::with_capacity(streams.len());
+    for &stream in streams {
+        if !FILE_RECORDER_STREAMS.contains(&stream) {
+            return Err(config_error(format!(
+                "stream '{stream}' is not numeric; use one of {FILE_RECORDER_STREAMS:?}"
+            )));
+        }
+        let stem = format!("{}_{stream}", options.name);
+        let output = match options.format {
+            ExportFormat::Csv => {
+                let (path, writer) = create(stem, "csv")?;
+                StreamFile::Csv {
+                    path,
+                    writer,
+                    layout: None,
+                }
+            }
+            ExportFormat::Edf => {
+                let settings = options
+                    .edf
+                    .get(stream)
+                    .cloned()
+                    .or_else(|| EdfStreamSettings::defaults_for(stream))
+                    .ok_or_else(|| config_error(format!("no EDF settings for '{stream}'")))?;
+                let (path, writer) = create(stem, "edf")?;
+                StreamFile::Edf {
+                    path,
+                    state: EdfState::Waiting {
+                        out: writer,
+                        settings,
+                        patient: identity.subject(),
+                        markers: Vec::new(),
+                        gaps: Vec::new(),
+                    },
+                }
+            }
+        };
+        outputs.push((stream.to_string(), output));
+    }
+    let marker_file = match options.format {
+        ExportFormat::Csv => {
+            let (path, mut writer) = create(format!("{}_markers", options.name), "csv")?;
+            writer
+                .write_all(b"time,label,value,port,duration\n")
+                .map_err(|e| config_error(format!("{}: {e}", path.display())))?;
+            Some(MarkerFile {
+                path,
+                writer,
+                written: 0,
+                failed: 0,
+            })
+        }
+        ExportFormat::Edf => None,
+    };
+    let metadata_path = options.dir.join(record_template::file_name(
+        &format!("{}_metadata", options.name),
+        "json",
+    ));
+    let metadata_file = identity
+        .write_metadata(&metadata_path, options.metadata.as_ref())
+        .map_err(|e| config_error(format!("{}: {e}", metadata_path.display())))?
+        .then_some(metadata_path);
+    Ok((outputs, marker_file, metadata_file))
+}
+
+fn annotate_gap(writer: &mut EdfWriter<BufWriter<File>>, gap: &GapAnnotation) {
+    writer.annotate_span(
+        micros_to_secs(gap.start_us),
+        micros_to_secs(gap.duration_us()),
+        &gap.label(),
+    );
+}
+
+/// What a stream's writer task reads from.
+struct StreamInputs {
+    stream: String,
+    cols: Vec<String>,
+    rx: mpsc::Receiver<Value>,
+    markers: mpsc::UnboundedReceiver<StreamMarker>,
+    gaps: CaptureGaps,
+    closed_gaps: mpsc::UnboundedReceiver<GapAnnotation>,
+    shutdown: watch::Receiver<bool>,
+}
+
+async fn write_stream(
+    inputs: StreamInputs,
+    mut output: StreamFile,
+    identity: ExportIdentity,
+) -> SinkReport {
+    let StreamInputs {
+        stream,
+        cols,
+        mut rx,
+        mut markers,
+        gaps,
+        mut closed_gaps,
+        mut shutdown,
+    } = inputs;
+    let mut report = SinkReport::new(output.label(&stream));
+    let mut write = |output: &mut StreamFile, event: &Value| {
+        if gaps.is_paused() {
+            return;
+        }
+        let (Some(time), Some(data)) = (
+            event.get("time").and_then(Value::as_f64),
+            event.get(stream.as_str()).and_then(Value::as_array),
+        ) else {
+            report.dropped += 1;
+            return;
+        };
+        match output.write(&cols, identity.shift_secs(time), data) {
+            Ok(()) => report.written += 1,
+            Err(e) => {
+                if report.dropped == 0 {
+                    tracing::warn!(stream = %stream, error = %e, "Recorder write failed");
+                }
+                report.dropped += 1;
+            }
+        }
+    };
+
+    loop {
+        tokio::select! {
+            item = rx.recv() => {
+                let Some(event) = item else { break };
+                write(&mut output, &event);
+            }
+            Some(marker) = markers.recv() => output.mark(marker),
+            Some(gap) = closed_gaps.recv() => output.gap(identity.shift_gap(gap)),
+            _ = shutdown.changed() => {
+                while let Ok(marker) = markers.try_recv() {
+                    output.mark(marker);
+                }
+                while let Ok(event) = rx.try_recv() {
+                    write(&mut output, &event);
+                }
+                while let Ok(gap) = closed_gaps.try_recv() {
+                    output.gap(identity.shift_gap(gap));
+                }
+                if let Some(gap) = gaps.open_gap() {
+                    output.gap(identity.shift_gap(gap));
+                }
+                break;
+            }
+        }
+    }
+
+    if let Err(e) = output.finish() {
+        tracing::warn!(stream = %stream, error = %e, "Recorder output did not finish");
+        report.error = Some(format!("finish failed: {e}"));
+    }
+    report
+}
+
+/// Pause on disconnects of the client, resume on reconnects, and write
+/// closed gaps to the CSV markers file until shutdown.
+async fn follow_gaps(
+    mut events: broadcast::Receiver<ConnectionEvent>,
+    gaps: CaptureGaps,
+    mut closed: mpsc::UnboundedReceiver<GapAnnotation>,
+    marker_file: Option<Arc<Mutex<MarkerFile>>>,
+    identity: ExportIdentity,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let write = |gap: GapAnnotation| {
+        if let Some(file) = &marker_file {
+            let mut file = file.lock().unwrap_or_else(PoisonError::into_inner);
+            file.write_gap(&identity.shift_gap(gap));
+        }
+    };
+    let mut connected = true;
+    loop {
+        tokio::select! {
+            event = events.recv(), if connected => match event {
+                Ok(ConnectionEvent::Disconnected { .. }) => gaps.pause(GapCause::Disconnected),
+                Ok(ConnectionEvent::Reconnected) => gaps.resume(&GapCause::Disconnected),
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => connected = false,
+            },
+            Some(gap) = closed.recv() => write(gap),
+            _ = shutdown.changed() => {
+                while let Ok(gap) = closed.try_recv() {
+                    write(gap);
+                }
+                if let Some(gap) = gaps.open_gap() {
+                    write(gap);
+                }
+                break;
+            }
+        }
+    }
+}
+
+/// Markers of a CSV recording.
+struct MarkerFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    written: u64,
+    failed: u64,
+}
+
+impl MarkerFile {
+    fn write_marker(&mut self, marker: &StreamMarker) {
+        let label = csv_field(&marker.label);
+        let port = csv_field(&marker.port);
+        self.write(&format!(
+            "{},{label},{},{port},0\n",
+            micros_to_secs(marker.timestamp),
+            marker.value
+        ));
+    }
+
+    /// A gap row: its label, value 0, no port, and its duration.
+    fn write_gap(&mut self, gap: &GapAnnotation) {
+        self.write(&format!(
+            "{},{},0,,{}\n",
+            micros_to_secs(gap.start_us),
+            csv_field(&gap.label()),
+            micros_to_secs(gap.duration_us())
+        ));
+    }
+
+    fn write(&mut self, row: &str) {
+        match self.writer.write_all(row.as_bytes()) {
+            Ok(()) => self.written += 1,
+            Err(_) => self.failed += 1,
+        }
+    }
+
+    fn finish(mut self) -> SinkReport {
+        let error = self
+            .writer
+            .flush()
+            .err()
+            .map(|e| format!("flush failed: {e}"));
+        SinkReport {
+            sink: format!("markers -> csv:{}", display(&self.path)),
+            written: self.written,
+            dropped: self.failed,
+            unflushed: if error.is_some() { self.written } else { 0 },
+            error,
+        }
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn display(path: &Path) -> String {
+    path.display().to_string()
+}
+
+fn micros_to_millis(micros: i64) -> f64 {
+    i64_to_f64(micros) / 1e3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_labels_scalar_columns() {
+        let cols: Vec<String> = ["COUNTER", "AF3", "RAW_CQ", "MARKERS"]
+            .map(String::from)
+            .to_vec();
+        let data = serde_json::json!([7, 4201.5, 1, []]);
+        let data = data.as_array().unwrap();
+
+        let layout = Layout::new(&cols, data);
+        let labels: Vec<&str> = layout.columns.iter().map(|(_, l)| l.as_str()).collect();
+        assert_eq!(labels, ["COUNTER", "AF3", "RAW_CQ"]);
+        assert_eq!(layout.values(data), [7.0, 4201.5, 1.0]);
+
+        let unlabeled = Layout::new(&[], &data[..2]);
+        assert_eq!(unlabeled.columns, [(0, "v0".into()), (1, "v1".into())]);
+        let met = serde_json::json!([true, null, 0.5]);
+        let met = met.as_array().unwrap();
+        let values = Layout::new(&[], met).values(met);
+        assert!((values[0] - 1.0).abs() < f64::EPSILON);
+        assert!(values[1].is_nan());
+    }
+
+    #[test]
+    fn test_edf_gap_is_written_as_timed_annotation() {
+        let path = std::env::temp_dir().join(format!("edf-gap-{}.edf", std::process::id()));
+        let mut output = StreamFile::Edf {
+            path: path.clone(),
+            state: EdfState::Waiting {
+                out: BufWriter::new(File::create(&path).unwrap()),
+                settings: EdfStreamSettings {
+                    sample_rate: 2.0,
+                    physical_min: -10.0,
+                    physical_max: 10.0,
+                    unit: "uV".into(),
+                },
+                patient: None,
+                markers: Vec::new(),
+                gaps: Vec::new(),
+            },
+        };
+        let cols = ["AF3".to_string()];
+        // A gap that closed before the first sample is kept for the header.
+        output.gap(GapAnnotation {
+            start_us: 1_000_000_000,
+            end_us: 1_000_250_000,
+            cause: GapCause::QualityGate,
+        });
+        output
+            .write(&cols, 1000.0, &[serde_json::json!(1.0)])
+            .unwrap();
+        output.gap(GapAnnotation {
+            start_us: 1_000_500_000,
+            end_us: 1_001_500_000,
+            cause: GapCause::Disconnected,
+        });
+        for time in [1001.5, 1002.0, 1002.5] {
+            output
+                .write(&cols, time, &[serde_json::json!(2.0)])
+                .unwrap();
+        }
+        output.finish().unwrap();
+
+        let bytes = fs::read(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(
+            text.contains("+0\x150.25\x14capture_gap:quality_gate\x14\0"),
+            "{text:?}"
+        );
+        assert!(text.contains("+0.5\x151\x14capture_gap:disconnected\x14\0"));
+    }
+}
@@ -0,0 +1,749 @@
+//! # Stream Recorder
+//!
+//! [`StreamRecorder`] writes stream samples to disk from a dedicated
+//! writer thread, so a slow disk never stalls the async runtime or the
+//! client's reader loop. Records queue in memory between the producer and
+//! the writer.
+//!
+//! On a small acquisition PC a long recording to a slow disk can let that
+//! queue grow without bound. Setting [`RecorderConfig::memory_limit`]
+//! enables bounded-memory mode: once the queue holds that many bytes,
+//! further records are appended to temporary spill files in a private
+//! directory (mode `0700` on Unix) created under
+//! [`RecorderConfig::spill_dir`]. The writer replays spill files in order
+//! once it has caught up with memory and deletes each one after it has
+//! been copied, so the output is identical to an unbounded recording.
+//! [`StreamRecorder::metrics`] reports memory use and spill volume.
+//!
+//! [`StreamRecorder`] is a [`Sink`]: closing it through that trait (or
+//! attaching it with [`ResilientClient::attach_sink`]) writes the queue
+//! out and reports records that were rejected or never flushed instead of
+//! failing.
+//!
+//! ```no_run
+//! use emotiv_cortex_v2::recorder::{RecorderConfig, StreamRecorder};
+//!
+//! # async fn demo(mut eeg: tokio::sync::mpsc::Receiver<serde_json::Value>) -> emotiv_cortex_v2::CortexResult<()> {
+//! let config = RecorderConfig {
+//!     memory_limit: Some(16 * 1024 * 1024),
+//!     ..RecorderConfig::default()
+//! };
+//! let recorder = StreamRecorder::create("eeg.jsonl", config)?;
+//! while let Some(event) = eeg.recv().await {
+//!     recorder.record_json(&event)?;
+//! }
+//! let metrics = recorder.finish()?;
+//! println!("spilled {} bytes", metrics.spilled_bytes);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## CSV and EDF+ files
+//!
+//! [`FileRecorder`] subscribes the numeric streams (`eeg`, `mot`, `pow`,
+//! `met`) and writes each to its own CSV or EDF+ file, without creating a
+//! Cortex record or waiting for `exportRecord`. Column labels come from
+//! the subscribe response; a `time` column (CSV) or the record start
+//! (EDF) carries the Cortex timestamp. Markers passed to
+//! [`FileRecorder::inject_marker`] or [`FileRecorder::add_marker`] go to
+//! `<name>_markers.csv`, or into every EDF file as annotations.
+//!
+//! EDF stores samples at a fixed rate within a fixed physical range, set
+//! per stream by [`EdfStreamSettings`]; values outside the range are
+//! clipped and samples are placed by count, not by their timestamps.
+//!
+//! [`FileRecorderOptions::subject`] goes into the EDF patient field and,
+//! with [`FileRecorderOptions::metadata`], into `<name>_metadata.json`.
+//! Setting [`FileRecorderOptions::anonymize`] replaces the subject with
+//! its pseudonym, shifts sample and marker times, and scrubs the
+//! metadata (see [`crate::anonymize`]).
+//!
+//! [`FileRecorder::pause`] and [`FileRecorder::resume`], disconnects of
+//! the client, and any producer sharing [`FileRecorderOptions::gaps`]
+//! (such as a quality gate) suspend recording. The skipped interval is
+//! written as an EDF+ annotation or a `capture_gap:<cause>` row of the
+//! markers file, with its duration; see [`crate::annotations`].
+//!
+//! ```no_run
+//! use emotiv_cortex_v2::ResilientClient;
+//! use emotiv_cortex_v2::annotations::CaptureGaps;
+//! use emotiv_cortex_v2::anonymize::{AnonymizeOptions, Anonymizer};
+//! use emotiv_cortex_v2::protocol::records::ExportFormat;
+//! use emotiv_cortex_v2::recorder::{FileRecorder, FileRecorderOptions};
+//!
+//! # async fn demo(client: &ResilientClient, session_id: &str) -> emotiv_cortex_v2::CortexResult<()> {
+//! let gaps = CaptureGaps::new(); // also handed to a quality gate
+//! let options = FileRecorderOptions {
+//!     format: ExportFormat::Edf,
+//!     dir: "data".into(),
+//!     name: "SUBJ01_rest".into(),
+//!     subject: Some("SUBJ01".into()),
+//!     anonymize: Some(Anonymizer::new(AnonymizeOptions::with_salt("lab-secret"))),
+//!     gaps: Some(gaps.clone()),
+//!     ..FileRecorderOptions::default()
+//! };
+//! let recorder = FileRecorder::start(client, session_id, &["eeg", "mot"], options).await?;
+//! recorder.inject_marker(client, "eyes-closed", 1, "app").await?;
+//! recorder.pause();
+//! // ... the subject adjusts the headset
+//! recorder.resume();
+//! for report in recorder.stop().await {
+//!     println!("{}: {} samples", report.sink, report.written);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`ResilientClient::attach_sink`]: crate::ResilientClient::attach_sink
+
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+
+use futures_util::future::BoxFuture;
+use serde::Serialize;
+
+use crate::error::{CortexError, CortexResult};
+use crate::sink::{Sink, SinkReport};
+
+mod edf;
+mod files;
+
+pub use files::{EdfStreamSettings, FILE_RECORDER_STREAMS, FileRecorder, FileRecorderOptions};
+
+/// Default size at which a spill file is sealed and handed to the writer.
+pub const DEFAULT_SPILL_FILE_BYTES: usize = 8 * 1024 * 1024;
+
+/// Records are coalesced into in-memory segments of up to this size.
+const MEMORY_SEGMENT_BYTES: usize = 64 * 1024;
+
+/// Distinguishes spill directories of recorders in the same process.
+static RECORDER_IDS: AtomicU64 = AtomicU64::new(0);
+
+/// Tuning for [`StreamRecorder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecorderConfig {
+    /// Bytes the in-memory queue may hold before records spill to disk.
+    /// `None` (the default) keeps everything in memory.
+    pub memory_limit: Option<usize>,
+    /// Directory under which each recorder creates its private spill
+    /// directory (default: the system temp directory).
+    pub spill_dir: PathBuf,
+    /// Size at which a spill file is sealed and a new one started.
+    pub spill_file_bytes: usize,
+}
+
+impl Default for RecorderConfig {
+    fn default() -> Self {
+        Self {
+            memory_limit: None,
+            spill_dir: std::env::temp_dir(),
+            spill_file_bytes: DEFAULT_SPILL_FILE_BYTES,
+        }
+    }
+}
+
+/// Memory and spill accounting of a [`StreamRecorder`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct RecorderMetrics {
+    /// Records accepted.
+    pub records: u64,
+    /// Bytes currently queued in memory.
+    pub memory_bytes: u64,
+    /// Highest `memory_bytes` seen.
+    pub peak_memory_bytes: u64,
+    /// Bytes written to spill files in total.
+    pub spilled_bytes: u64,
+    /// Spill files created in total.
+    pub spill_files: u64,
+    /// Spilled bytes not yet copied to the output.
+    pub pending_spill_bytes: u64,
+    /// Bytes written to the output.
+    pub written_bytes: u64,
+    /// Records written to the output and flushed.
+    pub flushed_records: u64,
+    /// Records refused because the recorder was closed or its output had
+    /// failed.
+    pub rejected_records: u64,
+}
+
+enum Segment {
+    Memory {
+        buf: Vec<u8>,
+        records: u64,
+    },
+    Spill {
+        path: PathBuf,
+        len: u64,
+        records: u64,
+    },
+}
+
+/// The spill file currently being appended to.
+struct OpenSpill {
+    path: PathBuf,
+    file: BufWriter<File>,
+    len: u64,
+    records: u64,
+}
+
+struct Queue {
+    segments: VecDeque<Segment>,
+    /// Private directory holding this recorder's spill files, created on
+    /// the first spill and removed with the recorder.
+    spill_dir: Option<PathBuf>,
+    spill: Option<OpenSpill>,
+    next_spill: u64,
+    metrics: RecorderMetrics,
+    closed: bool,
+    failed: Option<String>,
+}
+
+struct Shared {
+    queue: Mutex<Queue>,
+    ready: Condvar,
+    config: RecorderConfig,
+    id: u64,
+}
+
+impl Drop for Shared {
+    /// Removes the spill directory, including spill files a failed output
+    /// left unread.
+    fn drop(&mut self) {
+        let queue = self
+            .queue
+            .get_mut()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(dir) = &queue.spill_dir {
+            if let Err(e) = fs::remove_dir_all(dir) {
+                tracing::warn!(path = %dir.display(), error = %e, "Failed to remove spill directory");
+            }
+        }
+    }
+}
+
+/// Writes records to an output on a dedicated thread, optionally bounding
+/// memory by spilling to disk.
+///
+/// See the [module documentation](self).
+pub struct StreamRecorder {
+    shared: Arc<Shared>,
+    writer: Option<JoinHandle<()>>,
+    /// Output description for [`SinkReport::sink`].
+    label: String,
+}
+
+impl StreamRecorder {
+    /// Record to `output`.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::Io`] if the writer thread cannot be spawned.
+    pub fn new<W>(output: W, config: RecorderConfig) -> CortexResult<Self>
+    where
+        W: Write + Send + 'static,
+    {
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(Queue {
+                segments: VecDeque::new(),
+                spill_dir: None,
+                spill: None,
+                next_spill: 0,
+                metrics: RecorderMetrics::default(),
+                closed: false,
+                failed: None,
+            }),
+            ready: Condvar::new(),
+            config,
+            id: RECORDER_IDS.fetch_add(1, Ordering::Relaxed),
+        });
+        let thread_shared = Arc::clone(&shared);
+        let writer = std::thread::Builder::new()
+            .name("cortex-recorder".into())
+            .spawn(move || {
+                if let Err(e) = write_loop(&thread_shared, output) {
+                    tracing::warn!(error = %e, "Stream recorder output failed");
+                    if let Ok(mut queue) = thread_shared.queue.lock() {
+                        queue.failed = Some(e.to_string());
+                    }
+                }
+            })?;
+        Ok(Self {
+            shared,
+            writer: Some(writer),
+            label: "recorder".into(),
+        })
+    }
+
+    /// Record to a new file at `path`, truncating an existing one.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::Io`] if the file cannot be created or the
+    /// writer thread cannot be spawned.
+    pub fn create(path: impl AsRef<Path>, config: RecorderConfig) -> CortexResult<Self> {
+        let path = path.as_ref();
+        let mut recorder = Self::new(BufWriter::new(File::create(path)?), config)?;
+        recorder.label = format!("recorder:{}", path.display());
+        Ok(recorder)
+    }
+
+    /// Queue `record` for writing, verbatim.
+    ///
+    /// In bounded-memory mode a record that does not fit in memory is
+    /// appended to a spill file on the calling thread; these are
+    /// sequential writes to [`RecorderConfig::spill_dir`].
+    ///
+    /// # Errors
+    /// Returns [`CortexError::Io`] if a spill file cannot be written or
+    /// the output has already failed, and [`CortexError::StreamError`]
+    /// after [`finish`](Self::finish) has started.
+    pub fn record(&self, record: &[u8]) -> CortexResult<()> {
+        let mut queue = self.lock()?;
+        if let Some(failed) = &queue.failed {
+            let failed = failed.clone();
+            queue.metrics.rejected_records += 1;
+            return Err(io::Error::other(failed).into());
+        }
+        if queue.closed {
+            queue.metrics.rejected_records += 1;
+            return Err(CortexError::StreamError {
+                reason: "stream recorder is closed".into(),
+            });
+        }
+        push(&mut queue, &self.shared, record)?;
+        drop(queue);
+        self.shared.ready.notify_one();
+        Ok(())
+    }
+
+    /// Queue `value` as one line of JSON.
+    ///
+    /// # Errors
+    /// As for [`record`](Self::record).
+    pub fn record_json(&self, value: &serde_json::Value) -> CortexResult<()> {
+        let mut line = serde_json::to_vec(value)?;
+        line.push(b'\n');
+        self.record(&line)
+    }
+
+    /// Current memory and spill accounting.
+    #[must_use]
+    pub fn metrics(&self) -> RecorderMetrics {
+        self.shared
+            .queue
+            .lock()
+            .map(|queue| queue.metrics)
+            .unwrap_or_default()
+    }
+
+    /// Stop accepting records, wait until everything queued (including
+    /// spill files) has been written and flushed, and return the final
+    /// metrics.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::Io`] if writing the output failed.
+    pub fn finish(mut self) -> CortexResult<RecorderMetrics> {
+        self.shut_down()
+    }
+
+    /// Like [`finish`](Self::finish), but a failed output is reported in
+    /// the [`SinkReport`] together with how many records it lost.
+    #[must_use]
+    pub fn finish_report(mut self) -> SinkReport {
+        let result = self.shut_down();
+        let metrics = self.metrics();
+        SinkReport {
+            sink: self.label.clone(),
+            written: metrics.flushed_records,
+            dropped: metrics.rejected_records,
+            unflushed: metrics.records.saturating_sub(metrics.flushed_records),
+            error: result.err().map(|e| e.to_string()),
+        }
+    }
+
+    fn shut_down(&mut self) -> CortexResult<RecorderMetrics> {
+        self.close();
+        if let Some(writer) = self.writer.take() {
+            writer.join().map_err(|_| CortexError::StreamError {
+                reason: "stream recorder thread panicked".into(),
+            })?;
+        }
+        let queue = self.lock()?;
+        match &queue.failed {
+            Some(failed) => Err(io::Error::other(failed.clone()).into()),
+            None => Ok(queue.metrics),
+        }
+    }
+
+    fn close(&self) {
+        if let Ok(mut queue) = self.shared.queue.lock() {
+            queue.closed = true;
+        }
+        self.shared.ready.notify_all();
+    }
+
+    fn lock(&self) -> CortexResult<std::sync::MutexGuard<'_, Queue>> {
+        self.shared
+            .queue
+            .lock()
+            .map_err(|_| CortexError::StreamError {
+                reason: "stream recorder state poisoned".into(),
+            })
+    }
+}
+
+impl Sink for StreamRecorder {
+    fn name(&self) -> String {
+        self.label.clone()
+    }
+
+    /// Runs [`StreamRecorder::finish_report`] on the blocking pool.
+    fn close(self: Box<Self>) -> BoxFuture<'static, Vec<SinkReport>> {
+        let label = self.label.clone();
+        Box::pin(async move {
+            let report = tokio::task::spawn_blocking(move || self.finish_report())
+                .await
+                .unwrap_or_else(|e| SinkReport {
+                    error: Some(format!("recorder close task failed: {e}")),
+                    ..SinkReport::new(label)
+                });
+            vec![report]
+        })
+    }
+}
+
+impl Drop for StreamRecorder {
+    /// Closes the recorder; the writer thread finishes the queue in the
+    /// background.
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+fn len_u64(len: usize) -> u64 {
+    u64::try_from(len).unwrap_or(u64::MAX)
+}
+
+fn push(queue: &mut Queue, shared: &Shared, record: &[u8]) -> io::Result<()> {
+    let len = len_u64(record.len());
+    queue.metrics.records += 1;
+
+    let fits = shared
+        .config
+        .memory_limit
+        .is_none_or(|limit| queue.metrics.memory_bytes + len <= len_u64(limit));
+    // Once a spill file is open, later records must follow it on disk to
+    // keep the output in order.
+    if fits && queue.spill.is_none() {
+        match queue.segments.back_mut() {
+            Some(Segment::Memory { buf, records })
+                if buf.len() + record.len() <= MEMORY_SEGMENT_BYTES =>
+            {
+                buf.extend_from_slice(record);
+                *records += 1;
+            }
+            _ => queue.segments.push_back(Segment::Memory {
+                buf: record.to_vec(),
+                records: 1,
+            }),
+        }
+        queue.metrics.memory_bytes += len;
+        queue.metrics.peak_memory_bytes = queue
+            .metrics
+            .peak_memory_bytes
+            .max(queue.metrics.memory_bytes);
+        return Ok(());
+    }
+
+    if queue.spill.is_none() {
+        let dir = if let Some(dir) = &queue.spill_dir {
+            dir.clone()
+        } else {
+            let dir = create_private_dir(&shared.config.spill_dir, shared.id)?;
+            queue.spill_dir.insert(dir).clone()
+        };
+        let path = dir.join(format!("{}.spill", queue.next_spill));
+        // `create_new` refuses to follow or reuse anything already there.
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        queue.next_spill += 1;
+        queue.metrics.spill_files += 1;
+        tracing::debug!(path = %path.display(), "Stream recorder spilling to disk");
+        queue.spill = Some(OpenSpill {
+            file: BufWriter::new(file),
+            path,
+            len: 0,
+            records: 0,
+        });
+    }
+    if let Some(spill) = queue.spill.as_mut() {
+        spill.file.write_all(record)?;
+        spill.len += len;
+        spill.records += 1;
+        if spill.len >= len_u64(shared.config.spill_file_bytes) {
+            seal_spill(queue)?;
+        }
+    }
+    queue.metrics.spilled_bytes += len;
+    queue.metrics.pending_spill_bytes += len;
+    Ok(())
+}
+
+/// Create a directory under `parent` that only the current user can
+/// access, with an unpredictable name.
+fn create_private_dir(parent: &Path, recorder_id: u64) -> io::Result<PathBuf> {
+    let mut builder = fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    let mut attempts = 0;
+    loop {
+        let dir = parent.join(format!(
+            "emotiv-recorder-{}-{recorder_id}-{:016x}",
+            std::process::id(),
+            rand::random::<u64>()
+        ));
+        match builder.create(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempts < 8 => attempts += 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Finish the open spill file and queue it for the writer.
+fn seal_spill(queue: &mut Queue) -> io::Result<()> {
+    if let Some(mut spill) = queue.spill.take() {
+        spill.file.flush()?;
+        queue.segments.push_back(Segment::Spill {
+            path: spill.path,
+            len: spill.len,
+            records: spill.records,
+        });
+    }
+    Ok(())
+}
+
+enum Work {
+    Write(Segment),
+    Flush,
+    Done,
+}
+
+/// Wait for the next thing the writer thread should do.
+fn next_work(shared: &Shared, dirty: bool) -> io::Result<Work> {
+    let mut queue = shared
+        .queue
+        .lock()
+        .map_err(|_| io::Error::other("stream recorder state poisoned"))?;
+    loop {
+        if let Some(segment) = queue.segments.pop_front() {
+            return Ok(Work::Write(segment));
+        }
+        // Caught up with memory: take the partly filled spill file.
+        if queue.spill.as_ref().is_some_and(|spill| spill.len > 0) {
+            seal_spill(&mut queue)?;
+            continue;
+        }
+        if queue.closed {
+            return Ok(Work::Done);
+        }
+        // Idle: flush outside the lock before sleeping.
+        if dirty {
+            return Ok(Work::Flush);
+        }
+        queue = shared
+            .ready
+            .wait(queue)
+            .map_err(|_| io::Error::other("stream recorder state poisoned"))?;
+    }
+}
+
+/// Count records as flushed once `output.flush()` has succeeded.
+fn flushed(shared: &Shared, records: u64) {
+    if let Ok(mut queue) = shared.queue.lock() {
+        queue.metrics.flushed_records += records;
+    }
+}
+
+fn write_loop<W: Write>(shared: &Shared, mut output: W) -> io::Result<()> {
+    let mut dirty = false;
+    // Records written since the last flush.
+    let mut unflushed = 0;
+    loop {
+        let (written, records) = match next_work(shared, dirty)? {
+            Work::Done => {
+                output.flush()?;
+                flushed(shared, unflushed);
+                return Ok(());
+            }
+            Work::Flush => {
+                output.flush()?;
+                flushed(shared, std::mem::take(&mut unflushed));
+                dirty = false;
+                continue;
+            }
+            Work::Write(Segment::Memory { buf, records }) => {
+                output.write_all(&buf)?;
+                let len = len_u64(buf.len());
+                if let Ok(mut queue) = shared.queue.lock() {
+                    queue.metrics.memory_bytes -= len;
+                }
+                (len, records)
+            }
+            Work::Write(Segment::Spill { path, len, records }) => {
+                let mut file = File::open(&path)?;
+                io::copy(&mut file, &mut output)?;
+                drop(file);
+                if let Err(e) = fs::remove_file(&path) {
+                    tracing::warn!(path = %path.display(), error = %e, "Failed to remove spill file");
+                }
+                if let Ok(mut queue) = shared.queue.lock() {
+                    queue.metrics.pending_spill_bytes -= len;
+                }
+                (len, records)
+            }
+        };
+        unflushed += records;
+        dirty = true;
+        if let Ok(mut queue) = shared.queue.lock() {
+            queue.metrics.written_bytes += written;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Output that blocks while the test holds its lock.
+    #[derive(Clone)]
+    struct GatedOutput(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for GatedOutput {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn spill_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_unbounded_recorder_keeps_everything_in_memory() {
+        let output = GatedOutput(Arc::new(Mutex::new(Vec::new())));
+        let recorder = StreamRecorder::new(output.clone(), RecorderConfig::default()).unwrap();
+        for i in 0..100 {
+            recorder
+                .record_json(&serde_json::json!({"eeg": [i]}))
+                .unwrap();
+        }
+        let metrics = recorder.finish().unwrap();
+
+        assert_eq!(metrics.records, 100);
+        assert_eq!(metrics.spilled_bytes, 0);
+        assert_eq!(metrics.memory_bytes, 0);
+        let written = output.0.lock().unwrap();
+        assert_eq!(metrics.written_bytes, len_u64(written.len()));
+        assert_eq!(written.split(|b| *b == b'\n').count(), 101);
+    }
+
+    /// Output whose flush always fails.
+    struct UnflushableOutput;
+
+    impl Write for UnflushableOutput {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Err(io::Error::other("disk full"))
+        }
+    }
+
+    #[test]
+    fn test_finish_report_counts_unflushed_and_rejected_records() {
+        let recorder = StreamRecorder::new(UnflushableOutput, RecorderConfig::default()).unwrap();
+        for i in 0..10 {
+            // The first failed flush closes the output; later records are
+            // refused.
+            let _ = recorder.record_json(&serde_json::json!({"eeg": [i]}));
+        }
+        let metrics = recorder.metrics();
+        let report = recorder.finish_report();
+
+        assert_eq!(report.sink, "recorder");
+        assert_eq!(report.written, 0);
+        assert_eq!(report.unflushed, metrics.records);
+        assert_eq!(report.unflushed + report.dropped, 10);
+        assert!(report.error.as_deref().unwrap().contains("disk full"));
+        assert!(!report.is_lossless());
+    }
+
+    #[test]
+    fn test_bounded_recorder_spills_and_preserves_order() {
+        let dir = spill_dir("emotiv-recorder-spill-test");
+        let output = GatedOutput(Arc::new(Mutex::new(Vec::new())));
+        let config = RecorderConfig {
+            memory_limit: Some(256),
+            spill_dir: dir.clone(),
+            spill_file_bytes: 1024,
+        };
+        let recorder = StreamRecorder::new(output.clone(), config).unwrap();
+
+        let mut expected = Vec::new();
+        {
+            // Stall the output so records pile up.
+            let _gate = output.0.lock().unwrap();
+            for i in 0..500 {
+                let line = format!("sample {i:04}\n");
+                expected.extend_from_slice(line.as_bytes());
+                recorder.record(line.as_bytes()).unwrap();
+            }
+            let metrics = recorder.metrics();
+            assert!(metrics.memory_bytes <= 256, "{metrics:?}");
+            assert!(metrics.spill_files > 1, "{metrics:?}");
+            assert!(metrics.pending_spill_bytes > 0, "{metrics:?}");
+
+            let private: Vec<_> = fs::read_dir(&dir).unwrap().collect();
+            assert_eq!(private.len(), 1, "one private spill directory");
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let mode = private[0]
+                    .as_ref()
+                    .unwrap()
+                    .metadata()
+                    .unwrap()
+                    .permissions()
+                    .mode();
+                assert_eq!(mode & 0o777, 0o700);
+            }
+        }
+        let metrics = recorder.finish().unwrap();
+
+        assert_eq!(*output.0.lock().unwrap(), expected);
+        assert!(metrics.peak_memory_bytes <= 256, "{metrics:?}");
+        assert_eq!(metrics.pending_spill_bytes, 0);
+        assert_eq!(metrics.written_bytes, len_u64(expected.len()));
+        assert_eq!(
+            fs::read_dir(&dir).unwrap().count(),
+            0,
+            "spill files left behind"
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,554 @@
+//! # Record & Replay
+//!
+//! Develop and test stream consumers without a headset or the Cortex
+//! service: capture a real session once, then replay it as often as
+//! needed.
+//!
+//! [`FrameCapture`] records every inbound frame of a [`CortexClient`]
+//! (RPC responses, tagged with the method they answer, stream events and
+//! warnings) to newline-delimited JSON with the time it arrived. Install
+//! it with [`CortexClient::with_capture`].
+//!
+//! [`ReplayClient`] serves a capture from a local WebSocket
+//! ([`ReplayServer`]) and connects a regular [`CortexClient`] to it, so
+//! the whole client API (and [`crate::streams`] helpers) work unchanged:
+//!
+//! - each request is answered with the next recorded response for its
+//!   method (the last one again once they run out, `-32601` if the method
+//!   was never recorded);
+//! - a `subscribe` response starts the stream events recorded after it,
+//!   with their original spacing scaled by [`ReplayOptions::speed`],
+//!   until the stream is unsubscribed;
+//! - warnings are pushed at their original offset from the start of the
+//!   capture.
+//!
+//! ```no_run
+//! use emotiv_cortex_v2::{CortexClient, CortexConfig, streams};
+//! use emotiv_cortex_v2::replay::{FrameCapture, ReplayClient, ReplayOptions};
+//! use futures_util::StreamExt;
+//!
+//! # async fn demo(config: &CortexConfig) -> emotiv_cortex_v2::CortexResult<()> {
+//! // Once, with a headset:
+//! let capture = FrameCapture::create("session.ndjson")?;
+//! let client = CortexClient::connect(config).await?.with_capture(capture.clone());
+//! // ... authenticate, create a session, subscribe, stream ...
+//! capture.flush()?;
+//!
+//! // Any time later, offline:
+//! let replay = ReplayClient::open("session.ndjson", ReplayOptions::default()).await?;
+//! let token = replay.authenticate("id", "secret").await?;
+//! let mut eeg = streams::subscribe_eeg(&replay, &token, "session-id", 14).await?;
+//! while let Some(sample) = eeg.next().await {
+//!     println!("{:?}", sample.channels);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::net::Ipv4Addr;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tokio_tungstenite::accept_async;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::client::CortexClient;
+use crate::error::{CortexError, CortexResult};
+use crate::protocol::constants::{ErrorCodes, Methods};
+use crate::protocol::streams::SubscriptionResult;
+
+// ─── Capture ─────────────────────────────────────────────────────────────
+
+/// One captured inbound frame: a line of a capture file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CaptureFrame {
+    /// Microseconds since the capture started.
+    pub elapsed_us: u64,
+    /// Cortex method the frame answers, for RPC responses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    /// The frame as received.
+    pub frame: Value,
+}
+
+struct CaptureState {
+    out: Box<dyn Write + Send>,
+    /// Methods of requests awaiting a response, by request id.
+    in_flight: HashMap<u64, &'static str>,
+    frames: u64,
+    failed: u64,
+}
+
+/// Records the inbound frames of a [`CortexClient`]. See the
+/// [module documentation](self).
+///
+/// Frames are buffered; call [`Self::flush`] before reading the capture
+/// while the client is still running.
+pub struct FrameCapture {
+    started: Instant,
+    state: Mutex<CaptureState>,
+}
+
+impl FrameCapture {
+    /// Capture to a new file at `path`.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ConfigError`] if the file cannot be created.
+    pub fn create(path: impl AsRef<Path>) -> CortexResult<Arc<Self>> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|e| CortexError::ConfigError {
+            reason: format!("cannot create capture '{}': {e}", path.display()),
+        })?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+
+    /// Capture to `out`.
+    #[must_use]
+    pub fn new(out: impl Write + Send + 'static) -> Arc<Self> {
+        Arc::new(Self {
+            started: Instant::now(),
+            state: Mutex::new(CaptureState {
+                out: Box::new(out),
+                in_flight: HashMap::new(),
+                frames: 0,
+                failed: 0,
+            }),
+        })
+    }
+
+    /// Frames written so far.
+    #[must_use]
+    pub fn frames(&self) -> u64 {
+        self.state.lock().map_or(0, |s| s.frames)
+    }
+
+    /// Frames that could not be written.
+    #[must_use]
+    pub fn failed(&self) -> u64 {
+        self.state.lock().map_or(0, |s| s.failed)
+    }
+
+    /// Flush buffered frames to the output.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ConfigError`] if the output cannot be
+    /// flushed.
+    pub fn flush(&self) -> CortexResult<()> {
+        let Ok(mut state) = self.state.lock() else {
+            return Ok(());
+        };
+        state.out.flush().map_err(|e| CortexError::ConfigError {
+            reason: format!("cannot flush capture: {e}"),
+        })
+    }
+
+    /// Remember that request `id` calls `method`, so its response is
+    /// tagged with it.
+    pub(crate) fn expect_response(&self, id: u64, method: &'static str) {
+        if let Ok(mut state) = self.state.lock() {
+            state.in_flight.insert(id, method);
+        }
+    }
+
+    /// Write one inbound frame.
+    pub(crate) fn record(&self, frame: &Value) {
+        let elapsed_us = u64::try_from(self.started.elapsed().as_micros()).unwrap_or(u64::MAX);
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let method = frame
+            .get("id")
+            .and_then(Value::as_u64)
+            .and_then(|id| state.in_flight.remove(&id))
+            .map(str::to_string);
+        let line = CaptureFrame {
+            elapsed_us,
+            method,
+            frame: frame.clone(),
+        };
+        let written = serde_json::to_vec(&line)
+            .map_err(std::io::Error::other)
+            .and_then(|mut bytes| {
+                bytes.push(b'\n');
+                state.out.write_all(&bytes)
+            });
+        match written {
+            Ok(()) => state.frames += 1,
+            Err(e) => {
+                if state.failed == 0 {
+                    tracing::warn!(error = %e, "Frame capture write failed");
+                }
+                state.failed += 1;
+            }
+        }
+    }
+}
+
+/// Read a capture file written by [`FrameCapture`].
+///
+/// # Errors
+/// Returns [`CortexError::ConfigError`] if the file cannot be read or a
+/// line is not a [`CaptureFrame`].
+pub fn read_capture(path: impl AsRef<Path>) -> CortexResult<Vec<CaptureFrame>> {
+    let path = path.as_ref();
+    let error = |reason: String| CortexError::ConfigError {
+        reason: format!("capture '{}': {reason}", path.display()),
+    };
+    let file = File::open(path).map_err(|e| error(e.to_string()))?;
+    let mut frames = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| error(e.to_string()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        frames
+            .push(serde_json::from_str(&line).map_err(|e| error(format!("line {}: {e}", i + 1)))?);
+    }
+    Ok(frames)
+}
+
+// ─── Replay ──────────────────────────────────────────────────────────────
+
+/// How a capture is replayed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayOptions {
+    /// Playback speed: `2.0` sends frames twice as fast as captured.
+    /// Zero or less sends them without delay.
+    pub speed: f64,
+}
+
+impl Default for ReplayOptions {
+    fn default() -> Self {
+        Self { speed: 1.0 }
+    }
+}
+
+impl ReplayOptions {
+    fn scale(self, elapsed_us: u64) -> Duration {
+        if self.speed.is_finite() && self.speed > 0.0 {
+            Duration::from_micros(elapsed_us).div_f64(self.speed)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+/// A capture split for serving.
+struct Script {
+    /// Responses per method, in capture order.
+    responses: HashMap<String, Vec<CaptureFrame>>,
+    /// Stream events in capture order.
+    events: Vec<CaptureFrame>,
+    /// Warnings in capture order.
+    warnings: Vec<CaptureFrame>,
+    options: ReplayOptions,
+}
+
+impl Script {
+    fn new(frames: Vec<CaptureFrame>, options: ReplayOptions) -> Self {
+        let mut script = Self {
+            responses: HashMap::new(),
+            events: Vec::new(),
+            warnings: Vec::new(),
+            options,
+        };
+        for frame in frames {
+            if let Some(method) = frame.method.clone() {
+                script.responses.entry(method).or_default().push(frame);
+            } else if frame.frame.get("warning").is_some() {
+                script.warnings.push(frame);
+            } else if frame.frame.get("id").is_none() {
+                script.events.push(frame);
+            }
+        }
+        script
+    }
+}
+
+/// Serves a capture over a local WebSocket. Every connection replays the
+/// capture from the start. See the [module documentation](self).
+///
+/// Use [`Self::url`] as [`CortexConfig::cortex_url`] to replay into a
+/// [`ResilientClient`]. The server stops when dropped.
+///
+/// [`CortexConfig::cortex_url`]: crate::CortexConfig::cortex_url
+/// [`ResilientClient`]: crate::ResilientClient
+pub struct ReplayServer {
+    url: String,
+    task: JoinHandle<()>,
+}
+
+impl ReplayServer {
+    /// Start serving `frames` on a free localhost port.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ConfigError`] if no port can be bound.
+    pub async fn start(frames: Vec<CaptureFrame>, options: ReplayOptions) -> CortexResult<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .map_err(|e| CortexError::ConfigError {
+                reason: format!("cannot bind replay server: {e}"),
+            })?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| CortexError::ConfigError {
+                reason: format!("replay server address: {e}"),
+            })?;
+        let script = Arc::new(Script::new(frames, options));
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, Arc::clone(&script)));
+            }
+        });
+        Ok(Self {
+            url: format!("ws://{addr}"),
+            task,
+        })
+    }
+
+    /// WebSocket URL of the server.
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl Drop for ReplayServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// A [`CortexClient`] connected to a [`ReplayServer`]. Dereferences to
+/// the client, so it takes the same calls. See the
+/// [module documentation](self).
+pub struct ReplayClient {
+    client: CortexClient,
+    server: ReplayServer,
+}
+
+impl ReplayClient {
+    /// Replay the capture file at `path`.
+    ///
+    /// # Errors
+    /// Returns any error from [`read_capture`] or [`Self::from_frames`].
+    pub async fn open(path: impl AsRef<Path>, options: ReplayOptions) -> CortexResult<Self> {
+        Self::from_frames(read_capture(path)?, options).await
+    }
+
+    /// Replay captured `frames`.
+    ///
+    /// # Errors
+    /// Returns any error from starting the server or connecting to it.
+    pub async fn from_frames(
+        frames: Vec<CaptureFrame>,
+        options: ReplayOptions,
+    ) -> CortexResult<Self> {
+        let server = ReplayServer::start(frames, options).await?;
+        let client = CortexClient::connect_url(server.url()).await?;
+        Ok(Self { client, server })
+    }
+
+    /// The server the client is connected to.
+    #[must_use]
+    pub fn server(&self) -> &ReplayServer {
+        &self.server
+    }
+
+    /// The client and the server it needs; keep the server alive while
+    /// the client is in use.
+    #[must_use]
+    pub fn into_parts(self) -> (CortexClient, ReplayServer) {
+        (self.client, self.server)
+    }
+}
+
+impl std::ops::Deref for ReplayClient {
+    type Target = CortexClient;
+
+    fn deref(&self) -> &CortexClient {
+        &self.client
+    }
+}
+
+impl std::ops::DerefMut for ReplayClient {
+    fn deref_mut(&mut self) -> &mut CortexClient {
+        &mut self.client
+    }
+}
+
+/// Replay the script on one connection.
+async fn serve(stream: TcpStream, script: Arc<Script>) {
+    let Ok(ws) = accept_async(stream).await else {
+        return;
+    };
+    let (mut sink, mut source) = ws.split();
+    let (out_tx, mut out_rx) = mpsc::unbounded_channel::<Value>();
+    let writer = tokio::spawn(async move {
+        while let Some(value) = out_rx.recv().await {
+            if sink
+                .send(Message::Text(value.to_string().into()))
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    let started = Instant::now();
+    let active = Arc::new(Mutex::new(HashSet::<String>::new()));
+    let mut tasks = vec![tokio::spawn(push_at(
+        script.warnings.clone(),
+        started,
+        0,
+        script.options,
+        out_tx.clone(),
+        None,
+    ))];
+    let mut cursors: HashMap<String, usize> = HashMap::new();
+
+    while let Some(Ok(message)) = source.next().await {
+        let Message::Text(text) = message else {
+            continue;
+        };
+        let Ok(request) = serde_json::from_str::<Value>(&text) else {
+            continue;
+        };
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let method = request
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        let Some(recorded) = script.responses.get(&method).and_then(|responses| {
+            let cursor = cursors.entry(method.clone()).or_default();
+            let recorded = responses.get(*cursor).or_else(|| responses.last());
+            *cursor += 1;
+            recorded
+        }) else {
+            let _ = out_tx.send(serde_json::json!({
+                "id": id,
+                "jsonrpc": "2.0",
+                "error": {
+                    "code": ErrorCodes::METHOD_NOT_FOUND,
+                    "message": format!("no recorded response for {method}"),
+                },
+            }));
+            continue;
+        };
+
+        let mut response = recorded.frame.clone();
+        response["id"] = id;
+        let _ = out_tx.send(response);
+
+        let streams: Vec<String> = recorded
+            .frame
+            .get("result")
+            .map(SubscriptionResult::from_response)
+            .map(|r| r.success.into_iter().map(|s| s.stream_name).collect())
+            .unwrap_or_default();
+        if method == Methods::SUBSCRIBE {
+            if let Ok(mut active) = active.lock() {
+                active.extend(streams.iter().cloned());
+            }
+            let events = script
+                .events
+                .iter()
+                .filter(|e| e.elapsed_us >= recorded.elapsed_us)
+                .filter(|e| stream_key(&e.frame).is_some_and(|k| streams.iter().any(|s| s == k)))
+                .cloned()
+                .collect();
+            tasks.push(tokio::spawn(push_at(
+                events,
+                Instant::now(),
+                recorded.elapsed_us,
+                script.options,
+                out_tx.clone(),
+                Some(Arc::clone(&active)),
+            )));
+        } else if method == Methods::UNSUBSCRIBE {
+            if let Ok(mut active) = active.lock() {
+                for stream in &streams {
+                    active.remove(stream);
+                }
+            }
+        }
+    }
+
+    for task in tasks {
+        task.abort();
+    }
+    writer.abort();
+}
+
+/// Send `frames` at their captured offsets after `origin_us`, counted
+/// from `start`. With `active`, stream events are only sent while their
+/// stream is in the set.
+async fn push_at(
+    frames: Vec<CaptureFrame>,
+    start: Instant,
+    origin_us: u64,
+    options: ReplayOptions,
+    out: mpsc::UnboundedSender<Value>,
+    active: Option<Arc<Mutex<HashSet<String>>>>,
+) {
+    for frame in frames {
+        tokio::time::sleep_until(start + options.scale(frame.elapsed_us.saturating_sub(origin_us)))
+            .await;
+        if let Some(active) = &active {
+            let live = stream_key(&frame.frame)
+                .is_some_and(|key| active.lock().is_ok_and(|active| active.contains(key)));
+            if !live {
+                continue;
+            }
+        }
+        if out.send(frame.frame).is_err() {
+            return;
+        }
+    }
+}
+
+/// Stream name of a stream event: its key other than `sid` and `time`.
+fn stream_key(frame: &Value) -> Option<&str> {
+    frame
+        .as_object()?
+        .keys()
+        .map(String::as_str)
+        .find(|key| !matches!(*key, "sid" | "time"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_tags_responses_with_method() {
+        let path = std::env::temp_dir().join(format!("capture-{}.ndjson", std::process::id()));
+        let capture = FrameCapture::create(&path).unwrap();
+        capture.expect_response(7, Methods::GET_CORTEX_INFO);
+        capture.record(&serde_json::json!({"id": 7, "result": {"version": "2.0"}}));
+        capture.record(&serde_json::json!({"sid": "s-1", "time": 1.0, "eeg": [1, 0, 4200.0]}));
+        capture.flush().unwrap();
+
+        let frames = read_capture(&path).unwrap();
+        assert_eq!(capture.frames(), 2);
+        assert_eq!(frames[0].method.as_deref(), Some(Methods::GET_CORTEX_INFO));
+        assert_eq!(frames[1].method, None);
+        assert!(frames[1].elapsed_us >= frames[0].elapsed_us);
+        assert_eq!(stream_key(&frames[1].frame), Some("eeg"));
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,833 @@
+//! # Sample-Rate Conversion
+//!
+//! LSL outlets and EDF writers declare a nominal sample rate and assume
+//! every sample sits exactly on that grid. A headset's effective rate
+//! differs from its nominal rate by tens of ppm, so relabelling samples
+//! at the nominal rate accumulates timing error — 50 ppm is 0.18 s per
+//! hour.
+//!
+//! [`EegResampler`] converts EEG to an exact target rate instead. It fits
+//! the input clock (the Cortex sample timestamps, which Cortex corrects
+//! against the headset clock) with a least-squares line over a sliding
+//! window, and interpolates every output instant from that fit with a
+//! polyphase windowed-sinc filter bank. When the target rate is below
+//! the input rate the filter also low-passes at the new Nyquist
+//! frequency. [`DriftReport`] shows the effective input rate and the
+//! cumulative correction applied so far.
+//!
+//! Motion samples resample the same way through [`MotionResampler`]; any
+//! other sample type can implement [`Resample`]. Typed streams get the
+//! same adapter from [`SampleStreamExt::resample_to`].
+//!
+//! Output samples lie on the grid `first_timestamp + k / target_rate_hz`
+//! and carry `k` as their counter. Short gaps in the input are bridged
+//! by holding the last sample; gaps longer than
+//! [`ResamplerConfig::max_gap_secs`] restart the clock fit, and output
+//! resumes on the same grid.
+//!
+//! ```no_run
+//! use futures_util::StreamExt;
+//! use emotiv_cortex_v2::{CortexClient, HeadsetModel};
+//! use emotiv_cortex_v2::resample::{self, ResamplerConfig};
+//!
+//! # async fn demo(client: &CortexClient, token: &str, session_id: &str) -> emotiv_cortex_v2::CortexResult<()> {
+//! let model = HeadsetModel::Insight;
+//! let mut eeg = resample::subscribe_eeg_resampled(
+//!     client, token, session_id, model.num_channels(),
+//!     ResamplerConfig::new(model.sampling_rate_hz(), 128.0)?,
+//! ).await?;
+//!
+//! while let Some(sample) = eeg.next().await {
+//!     println!("{} {:?}", sample.timestamp, sample.channels);
+//! }
+//! println!("{:?}", eeg.drift());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`SampleStreamExt::resample_to`]: crate::streams::SampleStreamExt::resample_to
+
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use serde::Serialize;
+
+use crate::client::CortexClient;
+use crate::error::{CortexError, CortexResult};
+use crate::protocol::streams::{EegData, MotionData, seconds_to_micros_i64};
+use crate::streams::{self, micros_to_secs};
+use crate::util::{f64_to_f32, f64_to_i64, f64_to_u64, f64_to_usize, u64_to_f64, usize_to_f64};
+
+/// Default filter length (taps per phase at unity rate ratio).
+pub const DEFAULT_FILTER_TAPS: usize = 16;
+
+/// Default number of filter phases (fractional-delay resolution).
+pub const DEFAULT_FILTER_PHASES: usize = 64;
+
+/// Default number of input samples in the clock fit.
+pub const DEFAULT_CLOCK_WINDOW: usize = 4096;
+
+/// Default longest input gap bridged without restarting the clock fit.
+pub const DEFAULT_MAX_GAP_SECS: f64 = 1.0;
+
+/// Input samples between clock refits.
+const REFIT_INTERVAL: u64 = 32;
+
+/// Settings for [`EegResampler`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResamplerConfig {
+    /// Nominal input rate (e.g. [`HeadsetModel::sampling_rate_hz`]).
+    ///
+    /// [`HeadsetModel::sampling_rate_hz`]: crate::HeadsetModel::sampling_rate_hz
+    pub nominal_rate_hz: f64,
+    /// Exact output rate.
+    pub target_rate_hz: f64,
+    /// Filter taps per phase when not downsampling; scaled up by the
+    /// downsampling ratio so the stopband stays sharp.
+    pub taps: usize,
+    /// Filter phases; higher values reduce fractional-delay error.
+    pub phases: usize,
+    /// Input samples the clock fit covers.
+    pub clock_window: usize,
+    /// Longest input gap bridged by holding the last sample.
+    pub max_gap_secs: f64,
+}
+
+impl ResamplerConfig {
+    /// Convert from `nominal_rate_hz` to `target_rate_hz` with default
+    /// filter and clock settings.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ConfigError`] unless both rates are finite
+    /// and positive.
+    pub fn new(nominal_rate_hz: f64, target_rate_hz: f64) -> CortexResult<Self> {
+        let config = Self {
+            nominal_rate_hz,
+            target_rate_hz,
+            taps: DEFAULT_FILTER_TAPS,
+            phases: DEFAULT_FILTER_PHASES,
+            clock_window: DEFAULT_CLOCK_WINDOW,
+            max_gap_secs: DEFAULT_MAX_GAP_SECS,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check that both rates are finite and positive.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ConfigError`] naming the offending rate.
+    pub fn validate(&self) -> CortexResult<()> {
+        for (name, rate) in [
+            ("nominal_rate_hz", self.nominal_rate_hz),
+            ("target_rate_hz", self.target_rate_hz),
+        ] {
+            if !rate.is_finite() || rate <= 0.0 {
+                return Err(CortexError::ConfigError {
+                    reason: format!("resampler {name} must be finite and positive, got {rate}"),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Clock drift observed and corrected by an [`EegResampler`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct DriftReport {
+    /// Input samples received.
+    pub input_samples: u64,
+    /// Output samples produced.
+    pub output_samples: u64,
+    /// Missing input samples bridged by holding the previous sample.
+    pub filled_samples: u64,
+    /// Clock fit restarts after long gaps or channel-count changes.
+    pub resets: u64,
+    /// Input rate according to the current clock fit.
+    pub effective_rate_hz: f64,
+    /// Deviation of `effective_rate_hz` from the nominal rate.
+    pub drift_ppm: f64,
+    /// Cumulative timing error avoided compared with relabelling input
+    /// samples at the nominal rate. Negative when the input runs fast.
+    pub correction_secs: f64,
+    /// `correction_secs` in output samples: samples inserted (positive)
+    /// or dropped (negative).
+    pub correction_samples: i64,
+}
+
+// ─── Resample Trait ──────────────────────────────────────────────────────
+
+/// Samples a [`Resampler`] can interpolate: a timestamp and a fixed
+/// number of numeric values, plus fields copied from the nearest input.
+pub trait Resample: Clone {
+    /// Sample time in microseconds.
+    fn timestamp_us(&self) -> i64;
+
+    /// Number of interpolated values. A change restarts the clock fit.
+    fn value_count(&self) -> usize;
+
+    /// Add `weight` times each value to `sums`.
+    fn accumulate(&self, weight: f64, sums: &mut [f64]);
+
+    /// Output sample `index` of the grid at `timestamp_us`, with `values`
+    /// and the other fields of `self` (the nearest input sample).
+    #[must_use]
+    fn resampled(&self, values: &[f64], timestamp_us: i64, index: u64) -> Self;
+
+    /// A copy of `self` standing in for a missing input sample.
+    #[must_use]
+    fn held(&self) -> Self {
+        self.clone()
+    }
+}
+
+impl Resample for EegData {
+    fn timestamp_us(&self) -> i64 {
+        self.timestamp
+    }
+
+    fn value_count(&self) -> usize {
+        self.channels.len()
+    }
+
+    fn accumulate(&self, weight: f64, sums: &mut [f64]) {
+        for (sum, value) in sums.iter_mut().zip(&self.channels) {
+            *sum += weight * f64::from(*value);
+        }
+    }
+
+    /// `counter` is set to the grid index (wrapping at `u32::MAX`).
+    fn resampled(&self, values: &[f64], timestamp_us: i64, index: u64) -> Self {
+        Self {
+            timestamp: timestamp_us,
+            counter: u32::try_from(index % (u64::from(u32::MAX) + 1)).unwrap_or_default(),
+            interpolated: self.interpolated,
+            channels: values.iter().copied().map(f64_to_f32).collect(),
+            raw_cq: self.raw_cq,
+        }
+    }
+
+    fn held(&self) -> Self {
+        Self {
+            interpolated: true,
+            ..self.clone()
+        }
+    }
+}
+
+impl Resample for MotionData {
+    fn timestamp_us(&self) -> i64 {
+        self.timestamp
+    }
+
+    /// Accelerometer, magnetometer, and gyroscope axes when present. The
+    /// quaternion is copied from the nearest sample, since interpolating
+    /// its components does not yield a valid rotation.
+    fn value_count(&self) -> usize {
+        if self.gyroscope.is_some() { 9 } else { 6 }
+    }
+
+    fn accumulate(&self, weight: f64, sums: &mut [f64]) {
+        let axes = self
+            .accelerometer
+            .iter()
+            .chain(&self.magnetometer)
+            .chain(self.gyroscope.iter().flatten());
+        for (sum, value) in sums.iter_mut().zip(axes) {
+            *sum += weight * f64::from(*value);
+        }
+    }
+
+    fn resampled(&self, values: &[f64], timestamp_us: i64, _index: u64) -> Self {
+        let axes = |offset: usize| -> Option<[f32; 3]> {
+            let axes = values.get(offset..offset + 3)?;
+            Some([
+                f64_to_f32(axes[0]),
+                f64_to_f32(axes[1]),
+                f64_to_f32(axes[2]),
+            ])
+        };
+        Self {
+            timestamp: timestamp_us,
+            quaternion: self.quaternion,
+            accelerometer: axes(0).unwrap_or(self.accelerometer),
+            magnetometer: axes(3).unwrap_or(self.magnetometer),
+            gyroscope: self.gyroscope.and_then(|_| axes(6)),
+        }
+    }
+}
+
+// ─── Filter Bank ─────────────────────────────────────────────────────────
+
+/// Blackman-windowed sinc kernels, one row per fractional phase.
+#[derive(Debug)]
+struct FilterBank {
+    phases: usize,
+    /// Taps each side of the interpolation point; rows have `2 * half`.
+    half: usize,
+    rows: Vec<Vec<f64>>,
+}
+
+impl FilterBank {
+    fn new(config: &ResamplerConfig) -> Self {
+        let phases = config.phases.max(1);
+        let cutoff = (config.target_rate_hz / config.nominal_rate_hz).clamp(f64::EPSILON, 1.0);
+        let half = f64_to_usize((usize_to_f64(config.taps.max(2) / 2) / cutoff).ceil()).max(1);
+        let rows = (0..=phases)
+            .map(|p| {
+                let frac = usize_to_f64(p) / usize_to_f64(phases);
+                let mut row: Vec<f64> = (0..2 * half)
+                    .map(|j| {
+                        // Distance from the interpolation point to tap j,
+                        // which sits at input index floor(x) + j - half + 1.
+                        let d = usize_to_f64(j + 1) - usize_to_f64(half) - frac;
+                        cutoff * sinc(cutoff * d) * blackman(d / usize_to_f64(half))
+                    })
+                    .collect();
+                let gain: f64 = row.iter().sum();
+                if gain.abs() > f64::EPSILON {
+                    for c in &mut row {
+                        *c /= gain;
+                    }
+                }
+                row
+            })
+            .collect();
+        Self { phases, half, rows }
+    }
+
+    /// Row for the fractional part of an input position.
+    fn row(&self, frac: f64) -> &[f64] {
+        let phase = f64_to_usize((frac * usize_to_f64(self.phases)).round()).min(self.phases);
+        &self.rows[phase]
+    }
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-12 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Blackman window over `u` in `[-1, 1]`.
+fn blackman(u: f64) -> f64 {
+    if u.abs() > 1.0 {
+        0.0
+    } else {
+        0.42 + 0.5 * (PI * u).cos() + 0.08 * (2.0 * PI * u).cos()
+    }
+}
+
+// ─── Clock Fit ───────────────────────────────────────────────────────────
+
+/// Least-squares fit of `time = offset + period * index` over recent
+/// input samples. Times are seconds relative to the segment start.
+#[derive(Debug)]
+struct ClockFit {
+    points: VecDeque<(f64, f64)>,
+    capacity: usize,
+    offset: f64,
+    period: f64,
+}
+
+impl ClockFit {
+    fn new(capacity: usize, nominal_rate_hz: f64) -> Self {
+        Self {
+            points: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(2),
+            offset: 0.0,
+            period: 1.0 / nominal_rate_hz,
+        }
+    }
+
+    fn add(&mut self, index: f64, time: f64) {
+        if self.points.len() == self.capacity {
+            self.points.pop_front();
+        }
+        self.points.push_back((index, time));
+    }
+
+    fn refit(&mut self) {
+        if self.points.len() < 2 {
+            return;
+        }
+        let n = usize_to_f64(self.points.len());
+        let mean_x = self.points.iter().map(|p| p.0).sum::<f64>() / n;
+        let mean_t = self.points.iter().map(|p| p.1).sum::<f64>() / n;
+        let (sxx, sxt) = self.points.iter().fold((0.0, 0.0), |(sxx, sxt), (x, t)| {
+            let dx = x - mean_x;
+            (sxx + dx * dx, sxt + dx * (t - mean_t))
+        });
+        let period = sxt / sxx;
+        if period.is_finite() && period > 0.0 {
+            self.period = period;
+            self.offset = mean_t - period * mean_x;
+        }
+    }
+
+    fn index_at(&self, time: f64) -> f64 {
+        (time - self.offset) / self.period
+    }
+}
+
+// ─── Resampler ───────────────────────────────────────────────────────────
+
+/// Converts samples to an exact output rate. See the
+/// [module documentation](self).
+///
+/// Output lags input by half the filter length, so the last few input
+/// samples are held back until more arrive.
+#[derive(Debug)]
+pub struct Resampler<T> {
+    config: ResamplerConfig,
+    bank: FilterBank,
+    clock: ClockFit,
+    /// Input samples of the current segment, starting at `buffer_start`.
+    buffer: VecDeque<T>,
+    buffer_start: u64,
+    /// Index the next input sample of the segment gets.
+    next_index: u64,
+    /// Timestamp of output sample 0 (microseconds).
+    grid_origin_us: Option<i64>,
+    /// Timestamp of the current segment's first input sample.
+    segment_origin_us: i64,
+    last_time: f64,
+    last_position: f64,
+    next_output: u64,
+    prior_correction_secs: f64,
+    report: DriftReport,
+}
+
+/// [`Resampler`] for EEG.
+pub type EegResampler = Resampler<EegData>;
+
+/// [`Resampler`] for motion.
+pub type MotionResampler = Resampler<MotionData>;
+
+impl<T: Resample> Resampler<T> {
+    /// Create a resampler.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ConfigError`] if `config` fails
+    /// [`ResamplerConfig::validate`].
+    pub fn new(config: ResamplerConfig) -> CortexResult<Self> {
+        config.validate()?;
+        Ok(Self {
+            bank: FilterBank::new(&config),
+            clock: ClockFit::new(config.clock_window, config.nominal_rate_hz),
+            config,
+            buffer: VecDeque::new(),
+            buffer_start: 0,
+            next_index: 0,
+            grid_origin_us: None,
+            segment_origin_us: 0,
+            last_time: 0.0,
+            last_position: 0.0,
+            next_output: 0,
+            prior_correction_secs: 0.0,
+            report: DriftReport::default(),
+        })
+    }
+
+    /// Feed one input sample; returns the output samples it completes.
+    pub fn push(&mut self, sample: T) -> Vec<T> {
+        self.report.input_samples += 1;
+        let channel_count_changed = self
+            .buffer
+            .back()
+            .is_some_and(|last| last.value_count() != sample.value_count());
+        if self.grid_origin_us.is_none() || channel_count_changed {
+            self.start_segment(&sample);
+        } else {
+            let time = micros_to_secs(sample.timestamp_us() - self.segment_origin_us);
+            let gap = time - self.last_time;
+            if gap > self.config.max_gap_secs || gap < -self.config.max_gap_secs {
+                tracing::debug!(
+                    gap_secs = gap,
+                    "Resampler input gap too long; restarting clock fit"
+                );
+                self.start_segment(&sample);
+            } else {
+                self.fill_gap(gap);
+            }
+        }
+
+        let time = micros_to_secs(sample.timestamp_us() - self.segment_origin_us);
+        let index = self.next_index;
+        self.clock.add(u64_to_f64(index), time);
+        if index < REFIT_INTERVAL || index % REFIT_INTERVAL == 0 {
+            self.clock.refit();
+        }
+        self.last_time = time;
+        self.buffer.push_back(sample);
+        self.next_index += 1;
+
+        self.drain()
+    }
+
+    /// Drift observed so far.
+    #[must_use]
+    pub fn drift(&self) -> DriftReport {
+        let effective_rate_hz = 1.0 / self.clock.period;
+        let segment_correction = u64_to_f64(self.next_index.saturating_sub(1))
+            * (self.clock.period - 1.0 / self.config.nominal_rate_hz);
+        let correction_secs = self.prior_correction_secs + segment_correction;
+        DriftReport {
+            effective_rate_hz,
+            drift_ppm: (effective_rate_hz / self.config.nominal_rate_hz - 1.0) * 1e6,
+            correction_secs,
+            correction_samples: f64_to_i64((correction_secs * self.config.target_rate_hz).round()),
+            ..self.report
+        }
+    }
+
+    /// Settings in use.
+    #[must_use]
+    pub fn config(&self) -> &ResamplerConfig {
+        &self.config
+    }
+
+    /// Start a new clock segment at `sample`, keeping the output grid.
+    fn start_segment(&mut self, sample: &T) {
+        if self.grid_origin_us.is_some() {
+            self.report.resets += 1;
+            self.prior_correction_secs = self.drift().correction_secs;
+        }
+        let grid_origin_us = *self.grid_origin_us.get_or_insert(sample.timestamp_us());
+        let since_origin = micros_to_secs(sample.timestamp_us() - grid_origin_us);
+        self.next_output = f64_to_u64((since_origin * self.config.target_rate_hz - 1e-9).ceil())
+            .max(self.next_output);
+        self.segment_origin_us = sample.timestamp_us();
+        self.clock = ClockFit::new(self.config.clock_window, self.config.nominal_rate_hz);
+        self.buffer.clear();
+        self.buffer_start = 0;
+        self.next_index = 0;
+        self.last_time = 0.0;
+        self.last_position = 0.0;
+    }
+
+    /// Hold the last sample over input samples missing before one that
+    /// arrives `gap` seconds after it.
+    fn fill_gap(&mut self, gap: f64) {
+        let missing = f64_to_u64((gap / self.clock.period).round()).saturating_sub(1);
+        let Some(last) = self.buffer.back().cloned() else {
+            return;
+        };
+        for _ in 0..missing {
+            self.buffer.push_back(last.held());
+            self.next_index += 1;
+        }
+        self.report.filled_samples += missing;
+    }
+
+    /// Emit every output sample whose filter support is available.
+    fn drain(&mut self) -> Vec<T> {
+        let mut out = Vec::new();
+        let Some(grid_origin_us) = self.grid_origin_us else {
+            return out;
+        };
+        let half = usize_to_f64(self.bank.half);
+        let newest = u64_to_f64(self.next_index) - 1.0;
+        let origin_offset = micros_to_secs(self.segment_origin_us - grid_origin_us);
+
+        loop {
+            let grid_secs = u64_to_f64(self.next_output) / self.config.target_rate_hz;
+            let position = self
+                .clock
+                .index_at(grid_secs - origin_offset)
+                .max(self.last_position)
+                .max(0.0);
+            let base = position.floor();
+            if base + half > newest {
+                break;
+            }
+            let timestamp = grid_origin_us + seconds_to_micros_i64(grid_secs).unwrap_or(0);
+            if let Some(sample) = self.interpolate(base, position - base, timestamp) {
+                out.push(sample);
+                self.report.output_samples += 1;
+            }
+            self.last_position = position;
+            self.next_output += 1;
+        }
+
+        // Keep the samples the next output's left taps still need.
+        let keep_from = f64_to_u64((self.last_position + 1.0 - half).floor());
+        while self.buffer_start < keep_from && !self.buffer.is_empty() {
+            self.buffer.pop_front();
+            self.buffer_start += 1;
+        }
+        out
+    }
+
+    /// Filter the buffer around input position `base + frac` into output
+    /// sample `next_output`. Taps before the start of the buffer use its
+    /// first sample.
+    fn interpolate(&self, base: f64, frac: f64, timestamp_us: i64) -> Option<T> {
+        let row = self.bank.row(frac);
+        let start = i64::try_from(self.buffer_start).unwrap_or(i64::MAX);
+        let first = f64_to_i64((base + 1.0 - usize_to_f64(self.bank.half)).round());
+        let nearest = f64_to_i64((base + frac).round());
+        let at = |index: i64| {
+            let offset = (index - start).max(0);
+            self.buffer
+                .get(usize::try_from(offset).unwrap_or(usize::MAX))
+                .or_else(|| self.buffer.back())
+        };
+
+        let template = at(nearest)?;
+        let mut sums = vec![0.0_f64; template.value_count()];
+        for (index, coeff) in (first..).zip(row) {
+            at(index)?.accumulate(*coeff, &mut sums);
+        }
+        Some(template.resampled(&sums, timestamp_us, self.next_output))
+    }
+}
+
+// ─── Stream Adapter ──────────────────────────────────────────────────────
+
+/// Stream adapter applying a [`Resampler`] to an inner stream.
+pub struct Resampled<S, T = EegData> {
+    inner: S,
+    resampler: Resampler<T>,
+    pending: VecDeque<T>,
+}
+
+impl<S, T> Resampled<S, T>
+where
+    S: Stream<Item = T> + Unpin,
+    T: Resample,
+{
+    /// Wrap `inner`.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ConfigError`] if `config` fails
+    /// [`ResamplerConfig::validate`].
+    pub fn new(inner: S, config: ResamplerConfig) -> CortexResult<Self> {
+        Ok(Self {
+            inner,
+            resampler: Resampler::new(config)?,
+            pending: VecDeque::new(),
+        })
+    }
+
+    /// Drift observed so far.
+    #[must_use]
+    pub fn drift(&self) -> DriftReport {
+        self.resampler.drift()
+    }
+}
+
+impl<S, T> Stream for Resampled<S, T>
+where
+    S: Stream<Item = T> + Unpin,
+    T: Resample + Unpin,
+{
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(sample) = self.pending.pop_front() {
+                return Poll::Ready(Some(sample));
+            }
+            match Pin::new(&mut self.inner).poll_next(cx) {
+                Poll::Ready(Some(sample)) => {
+                    let output = self.resampler.push(sample);
+                    self.pending.extend(output);
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// Subscribe to the EEG stream and deliver it at exactly
+/// `config.target_rate_hz`.
+///
+/// # Errors
+/// Returns [`CortexError::ConfigError`] for invalid rates (before
+/// subscribing), or any error produced by stream channel registration or
+/// subscription RPC calls.
+pub async fn subscribe_eeg_resampled(
+    client: &CortexClient,
+    cortex_token: &str,
+    session_id: &str,
+    num_channels: usize,
+    config: ResamplerConfig,
+) -> CortexResult<Resampled<Pin<Box<dyn Stream<Item = EegData> + Send>>>> {
+    config.validate()?;
+    let stream = streams::subscribe_eeg(client, cortex_token, session_id, num_channels).await?;
+    Resampled::new(stream, config)
+}
+
+/// Subscribe to the motion stream and deliver it at exactly
+/// `config.target_rate_hz`.
+///
+/// # Errors
+/// Returns [`CortexError::ConfigError`] for invalid rates (before
+/// subscribing), or any error produced by stream channel registration or
+/// subscription RPC calls.
+pub async fn subscribe_motion_resampled(
+    client: &CortexClient,
+    cortex_token: &str,
+    session_id: &str,
+    config: ResamplerConfig,
+) -> CortexResult<Resampled<Pin<Box<dyn Stream<Item = MotionData> + Send>>, MotionData>> {
+    config.validate()?;
+    let stream = streams::subscribe_motion(client, cortex_token, session_id).await?;
+    Resampled::new(stream, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Single-channel samples of `signal` at `rate_hz` for `secs`.
+    fn input(rate_hz: f64, secs: f64, signal: impl Fn(f64) -> f64) -> Vec<EegData> {
+        (0..i32::MAX)
+            .map(|i| f64::from(i) / rate_hz)
+            .take_while(|t| *t < secs)
+            .map(|t| EegData {
+                timestamp: 1_700_000_000_000_000 + seconds_to_micros_i64(t).unwrap(),
+                counter: 0,
+                interpolated: false,
+                channels: vec![f64_to_f32(signal(t))],
+                raw_cq: 4.0,
+            })
+            .collect()
+    }
+
+    fn run(config: ResamplerConfig, samples: Vec<EegData>) -> (Vec<EegData>, DriftReport) {
+        let mut resampler = EegResampler::new(config).unwrap();
+        let out = samples
+            .into_iter()
+            .flat_map(|s| resampler.push(s))
+            .collect();
+        (out, resampler.drift())
+    }
+
+    fn secs_since(sample: &EegData, origin: &EegData) -> f64 {
+        micros_to_secs(sample.timestamp - origin.timestamp)
+    }
+
+    #[test]
+    fn test_output_on_exact_grid_despite_drift() {
+        // A headset running 200 ppm fast.
+        let tone = |t: f64| (2.0 * PI * 5.0 * t).sin();
+        let samples = input(128.0 * 1.000_2, 60.0, tone);
+        let (out, drift) = run(ResamplerConfig::new(128.0, 128.0).unwrap(), samples);
+
+        for pair in out.windows(2) {
+            let step = pair[1].timestamp - pair[0].timestamp;
+            assert!(step == 7812 || step == 7813, "step {step}");
+            assert_eq!(pair[1].counter, pair[0].counter + 1);
+        }
+        let last = out.last().unwrap();
+        assert_eq!(
+            last.timestamp - out[0].timestamp,
+            seconds_to_micros_i64(f64::from(last.counter) / 128.0).unwrap()
+        );
+        for sample in &out[64..] {
+            let expected = tone(secs_since(sample, &out[0]));
+            assert!((f64::from(sample.channels[0]) - expected).abs() < 0.01);
+        }
+
+        assert!((drift.drift_ppm - 200.0).abs() < 5.0, "{drift:?}");
+        assert!((drift.correction_secs + 0.012).abs() < 0.001, "{drift:?}");
+        assert_eq!(drift.correction_samples, -2);
+    }
+
+    #[test]
+    fn test_downsampling_removes_content_above_target_nyquist() {
+        let slow = |t: f64| (2.0 * PI * 4.0 * t).sin();
+        let samples = input(256.0, 10.0, |t| slow(t) + (2.0 * PI * 60.0 * t).sin());
+        let (out, drift) = run(ResamplerConfig::new(256.0, 64.0).unwrap(), samples);
+
+        assert!((630..=640).contains(&out.len()), "{}", out.len());
+        for sample in &out[32..] {
+            let expected = slow(secs_since(sample, &out[0]));
+            assert!((f64::from(sample.channels[0]) - expected).abs() < 0.05);
+        }
+        assert!(drift.drift_ppm.abs() < 1.0);
+    }
+
+    #[test]
+    fn test_short_gaps_filled_and_long_gaps_restart_on_grid() {
+        let mut samples = input(128.0, 20.0, |_| 1.0);
+        samples.drain(600..603);
+        samples.drain(1200..1200 + 128 * 3);
+        let (out, drift) = run(ResamplerConfig::new(128.0, 128.0).unwrap(), samples);
+
+        assert_eq!(drift.filled_samples, 3);
+        assert_eq!(drift.resets, 1);
+        for pair in out.windows(2) {
+            let elapsed = pair[1].timestamp - pair[0].timestamp;
+            let expected = f64::from(pair[1].counter - pair[0].counter) / 128.0;
+            assert!((micros_to_secs(elapsed) - expected).abs() < 2e-6);
+        }
+        assert!(out.iter().any(|s| s.counter > 128 * 15));
+        assert!(out.iter().all(|s| (s.channels[0] - 1.0).abs() < 1e-4));
+    }
+
+    #[test]
+    fn test_config_rejects_zero_and_negative_rates() {
+        for (nominal, target) in [(128.0, 0.0), (0.0, 128.0), (128.0, -64.0), (-128.0, 64.0)] {
+            let err = ResamplerConfig::new(nominal, target).unwrap_err();
+            assert!(matches!(err, CortexError::ConfigError { .. }), "{err:?}");
+        }
+    }
+
+    #[test]
+    fn test_config_rejects_non_finite_rates() {
+        for (nominal, target) in [
+            (128.0, f64::NAN),
+            (f64::NAN, 128.0),
+            (128.0, f64::INFINITY),
+            (f64::NEG_INFINITY, 128.0),
+        ] {
+            let err = ResamplerConfig::new(nominal, target).unwrap_err();
+            assert!(matches!(err, CortexError::ConfigError { .. }), "{err:?}");
+        }
+
+        let config = ResamplerConfig {
+            target_rate_hz: f64::NAN,
+            ..ResamplerConfig::new(128.0, 64.0).unwrap()
+        };
+        assert!(EegResampler::new(config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resample_to_converts_motion_and_keeps_quaternion() {
+        use crate::streams::SampleStreamExt;
+        use futures_util::{StreamExt, stream};
+
+        let samples = input(64.0, 10.0, |t| (2.0 * PI * t).sin()).into_iter();
+        let motion = samples.map(|eeg| MotionData {
+            timestamp: eeg.timestamp,
+            quaternion: Some([1.0, 0.0, 0.0, 0.0]),
+            gyroscope: None,
+            accelerometer: [eeg.channels[0], 0.0, 1.0],
+            magnetometer: [0.0; 3],
+        });
+        let out: Vec<MotionData> = stream::iter(motion)
+            .resample_to(64.0, 16.0)
+            .unwrap()
+            .collect()
+            .await;
+
+        assert!((150..=160).contains(&out.len()), "{}", out.len());
+        for sample in &out[8..] {
+            let t = micros_to_secs(sample.timestamp - out[0].timestamp);
+            let expected = (2.0 * PI * t).sin();
+            assert!((f64::from(sample.accelerometer[0]) - expected).abs() < 0.05);
+            assert!((sample.accelerometer[2] - 1.0).abs() < 1e-4);
+            assert_eq!(sample.quaternion, Some([1.0, 0.0, 0.0, 0.0]));
+            assert!(sample.gyroscope.is_none());
+        }
+    }
+}
@@ -11,6 +11,42 @@
 //! | [`RetryPolicy::idempotent()`] | 2 | State-changing but safe to retry: `subscribe`, `controlDevice` |
 //! | [`RetryPolicy::none()`] | 0 | Non-idempotent: `authorize`, `createSession`, `injectMarker` |
 //!
+//! ## Presets
+//!
+//! [`RetryPolicy`] retries whatever [`CortexError::is_retryable()`] accepts.
+//! A [`RetryPreset`] pairs a backoff schedule with its own error
+//! classification, for use with [`retry_with_policy`] — including around
+//! your own Cortex-adjacent operations:
+//!
+//! | Preset | Retries | Retried errors |
+//! |--------|---------|----------------|
+//! | [`RetryPreset::NetworkTransient`] | 5, 250ms → 5s | Dropped/failed connections, timeouts, transient Cortex error codes |
+//! | [`RetryPreset::RateLimited`] | 6, 1s → 30s | Throttling ([`CortexError::is_rate_limited()`]), Cortex starting |
+//! | [`RetryPreset::Idempotent`] | 2, 1s → 15s | Anything retryable, plus throttling |
+//!
+//! ## Client Hooks
+//!
+//! [`ResilientClient`](crate::ResilientClient) retries no RPC by default.
+//! [`RetryHooks`] opts methods in: a retry budget per method (or one for
+//! all), which errors count as retryable, [`Jitter`] on the backoff, and a
+//! callback for each retry. Install them with
+//! [`ResilientClient::set_retry_hooks`](crate::ResilientClient::set_retry_hooks).
+//!
+//! ```
+//! use emotiv_cortex_v2::protocol::constants::{ErrorCodes, Methods};
+//! use emotiv_cortex_v2::retry::{Jitter, RetryHooks, RetryPolicy};
+//!
+//! let hooks = RetryHooks::new()
+//!     .method(Methods::QUERY_HEADSETS, RetryPolicy::query())
+//!     .method(Methods::SUBSCRIBE, RetryPolicy::idempotent())
+//!     .retryable_codes([ErrorCodes::HEADSET_UNAVAILABLE])
+//!     .jitter(Jitter::Full)
+//!     .on_retry(|method, retry| {
+//!         eprintln!("{method}: retry {} after {:?}: {}", retry.attempt, retry.delay, retry.error);
+//!     });
+//! # let _ = hooks;
+//! ```
+//!
 //! ## Usage
 //!
 //! ```rust
@@ -41,6 +77,8 @@
 //! assert_eq!(result.unwrap(), 42);
 //! ```
 
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use std::time::Duration;
 
 use crate::error::{CortexError, CortexResult};
@@ -135,6 +173,316 @@ impl RetryPolicy {
     }
 }
 
+// ─── Strategies & Presets ────────────────────────────────────────────────
+
+/// A backoff schedule plus the rule for which errors are worth retrying.
+///
+/// Implemented by [`RetryPolicy`] (retries [`CortexError::is_retryable()`]
+/// errors, which covers transient Cortex error codes such as internal
+/// errors and a headset that is not ready yet) and [`RetryPreset`]. Implement it to plug custom classification
+/// into [`retry_with_policy`].
+pub trait RetryStrategy {
+    /// Backoff schedule to follow.
+    fn policy(&self) -> RetryPolicy;
+
+    /// Whether `error` should be retried. Errors rejected here are
+    /// returned immediately.
+    fn should_retry(&self, error: &CortexError) -> bool {
+        error.is_retryable()
+    }
+
+    /// Randomization applied to each backoff delay. None by default.
+    fn jitter(&self) -> Jitter {
+        Jitter::None
+    }
+
+    /// Called before sleeping ahead of each retry.
+    fn on_retry(&self, _retry: &RetryAttempt<'_>) {}
+}
+
+/// Randomization of backoff delays, so clients that failed together do
+/// not retry in lockstep.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Jitter {
+    /// Sleep exactly the backoff delay.
+    #[default]
+    None,
+    /// Sleep a random time between zero and the backoff delay.
+    Full,
+    /// Sleep half the backoff delay plus a random part of the other half.
+    Equal,
+}
+
+impl Jitter {
+    /// The time to sleep for a backoff `delay`. The random part is
+    /// uniform over its range, drawn from the thread-local RNG.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use emotiv_cortex_v2::retry::Jitter;
+    /// use std::time::Duration;
+    ///
+    /// let delay = Duration::from_millis(400);
+    /// assert_eq!(Jitter::None.apply(delay), delay);
+    /// assert!(Jitter::Equal.apply(delay) >= Duration::from_millis(200));
+    /// assert!(Jitter::Full.apply(delay) <= delay);
+    /// ```
+    #[must_use]
+    pub fn apply(self, delay: Duration) -> Duration {
+        match self {
+            Jitter::None => delay,
+            Jitter::Full => delay.mul_f64(rand::random::<f64>()),
+            Jitter::Equal => {
+                let half = delay / 2;
+                half + half.mul_f64(rand::random::<f64>())
+            }
+        }
+    }
+}
+
+/// A retry about to happen, as passed to [`RetryStrategy::on_retry`].
+#[derive(Debug, Clone, Copy)]
+pub struct RetryAttempt<'a> {
+    /// 1 for the first retry.
+    pub attempt: u32,
+    /// Retries the policy allows in total.
+    pub max_retries: u32,
+    /// Time until the retry, after jitter.
+    pub delay: Duration,
+    /// The error being retried.
+    pub error: &'a CortexError,
+}
+
+impl RetryStrategy for RetryPolicy {
+    fn policy(&self) -> RetryPolicy {
+        self.clone()
+    }
+}
+
+/// Ready-made retry strategies with their own error classification.
+///
+/// # Examples
+///
+/// ```
+/// use emotiv_cortex_v2::retry::{RetryPreset, RetryStrategy};
+/// use emotiv_cortex_v2::CortexError;
+///
+/// let throttled = CortexError::from_api_error(-32999, "rate limit exceeded");
+/// assert!(RetryPreset::RateLimited.should_retry(&throttled));
+/// assert!(!RetryPreset::NetworkTransient.should_retry(&throttled));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RetryPreset {
+    /// Network hiccups: dropped or refused connections, timeouts, and the
+    /// service still starting. 5 retries, 250ms base, 5s cap.
+    NetworkTransient,
+    /// Server-side throttling. 6 retries, 1s base, 30s cap.
+    RateLimited,
+    /// Operations safe to repeat: any retryable error plus throttling.
+    /// Same schedule as [`RetryPolicy::idempotent()`].
+    Idempotent,
+}
+
+impl RetryStrategy for RetryPreset {
+    fn policy(&self) -> RetryPolicy {
+        match self {
+            RetryPreset::NetworkTransient => {
+                RetryPolicy::custom(5, Duration::from_millis(250), Duration::from_secs(5))
+            }
+            RetryPreset::RateLimited => {
+                RetryPolicy::custom(6, Duration::from_secs(1), Duration::from_secs(30))
+            }
+            RetryPreset::Idempotent => RetryPolicy::idempotent(),
+        }
+    }
+
+    fn should_retry(&self, error: &CortexError) -> bool {
+        match self {
+            RetryPreset::NetworkTransient => {
+                error.is_retryable() || matches!(error, CortexError::ConnectionFailed { .. })
+            }
+            RetryPreset::RateLimited => {
+                error.is_rate_limited() || matches!(error, CortexError::CortexStarting)
+            }
+            RetryPreset::Idempotent => error.is_retryable() || error.is_rate_limited(),
+        }
+    }
+}
+
+// ─── Client Hooks ────────────────────────────────────────────────────────
+
+type RetryClassifier = Arc<dyn Fn(&CortexError) -> bool + Send + Sync>;
+type RetryCallback = Arc<dyn Fn(&str, &RetryAttempt<'_>) + Send + Sync>;
+
+/// Per-method retry settings for the RPCs a
+/// [`ResilientClient`](crate::ResilientClient) sends. See the
+/// [module docs](self#client-hooks).
+///
+/// Methods without a policy of their own use the
+/// [default policy](Self::default_policy), which is
+/// [`RetryPolicy::None`] unless set — so by default nothing is retried.
+/// Errors are retried when [`CortexError::is_retryable`] accepts them or
+/// their [code](CortexError::error_code) is one of the
+/// [retryable codes](Self::retryable_codes), unless
+/// [`retry_if`](Self::retry_if) replaces that rule. Connection errors are
+/// never retried here; the client reconnects and repeats the call instead.
+#[derive(Clone, Default)]
+pub struct RetryHooks {
+    default: Option<RetryPolicy>,
+    methods: HashMap<String, RetryPolicy>,
+    codes: HashSet<i32>,
+    classifier: Option<RetryClassifier>,
+    jitter: Jitter,
+    on_retry: Option<RetryCallback>,
+}
+
+impl RetryHooks {
+    /// Hooks that retry nothing, i.e. the client's default behavior.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Policy for methods without one of their own.
+    #[must_use]
+    pub fn default_policy(mut self, policy: RetryPolicy) -> Self {
+        self.default = Some(policy);
+        self
+    }
+
+    /// Retry budget for one Cortex method, e.g.
+    /// [`Methods::QUERY_HEADSETS`](crate::protocol::constants::Methods::QUERY_HEADSETS).
+    #[must_use]
+    pub fn method(mut self, method: impl Into<String>, policy: RetryPolicy) -> Self {
+        self.methods.insert(method.into(), policy);
+        self
+    }
+
+    /// Also retry errors with these Cortex error codes.
+    ///
+    /// Codes are compared through the variant they map to, so a code that
+    /// shares its variant with others (`-32001` and `-32004` both mean
+    /// [`CortexError::NoHeadsetFound`]) also retries those. Codes of
+    /// variants without an [error code](CortexError::error_code), such as
+    /// [`CortexError::SessionError`], never match; use
+    /// [`retry_if`](Self::retry_if) for those.
+    #[must_use]
+    pub fn retryable_codes(mut self, codes: impl IntoIterator<Item = i32>) -> Self {
+        self.codes.extend(codes.into_iter().map(|code| {
+            CortexError::from_api_error(code, String::new())
+                .error_code()
+                .unwrap_or(code)
+        }));
+        self
+    }
+
+    /// Decide which errors are retried, replacing the built-in rule and
+    /// any [retryable codes](Self::retryable_codes).
+    #[must_use]
+    pub fn retry_if(
+        mut self,
+        classify: impl Fn(&CortexError) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.classifier = Some(Arc::new(classify));
+        self
+    }
+
+    /// Randomize backoff delays.
+    #[must_use]
+    pub fn jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Call `callback` with the method name before each retry, e.g. to log
+    /// or count retries.
+    #[must_use]
+    pub fn on_retry(
+        mut self,
+        callback: impl Fn(&str, &RetryAttempt<'_>) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_retry = Some(Arc::new(callback));
+        self
+    }
+
+    /// The policy `method` is retried with.
+    #[must_use]
+    pub fn policy_for(&self, method: &str) -> RetryPolicy {
+        self.methods
+            .get(method)
+            .or(self.default.as_ref())
+            .cloned()
+            .unwrap_or(RetryPolicy::None)
+    }
+
+    /// Whether `error` is retried.
+    #[must_use]
+    pub fn should_retry(&self, error: &CortexError) -> bool {
+        if error.is_connection_error() {
+            return false;
+        }
+        match &self.classifier {
+            Some(classify) => classify(error),
+            None => {
+                error.is_retryable()
+                    || error
+                        .error_code()
+                        .is_some_and(|code| self.codes.contains(&code))
+            }
+        }
+    }
+
+    /// The hooks as a [`RetryStrategy`] for one method.
+    pub(crate) fn for_method<'a>(&'a self, method: &'a str) -> MethodRetry<'a> {
+        MethodRetry {
+            hooks: self,
+            method,
+        }
+    }
+}
+
+impl std::fmt::Debug for RetryHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryHooks")
+            .field("default", &self.default)
+            .field("methods", &self.methods)
+            .field("codes", &self.codes)
+            .field("classifier", &self.classifier.is_some())
+            .field("jitter", &self.jitter)
+            .field("on_retry", &self.on_retry.is_some())
+            .finish()
+    }
+}
+
+/// [`RetryHooks`] applied to one method.
+pub(crate) struct MethodRetry<'a> {
+    hooks: &'a RetryHooks,
+    method: &'a str,
+}
+
+impl RetryStrategy for MethodRetry<'_> {
+    fn policy(&self) -> RetryPolicy {
+        self.hooks.policy_for(self.method)
+    }
+
+    fn should_retry(&self, error: &CortexError) -> bool {
+        self.hooks.should_retry(error)
+    }
+
+    fn jitter(&self) -> Jitter {
+        self.hooks.jitter
+    }
+
+    fn on_retry(&self, retry: &RetryAttempt<'_>) {
+        if let Some(callback) = &self.hooks.on_retry {
+            callback(self.method, retry);
+        }
+    }
+}
+
+// ─── Combinators ─────────────────────────────────────────────────────────
+
 /// Execute an async operation with retry logic.
 ///
 /// The operation is retried according to the policy when the error is
@@ -147,12 +495,46 @@ impl RetryPolicy {
 /// # Errors
 /// Returns any error from the operation, including a wrapped
 /// [`CortexError::RetriesExhausted`] when retry attempts are exhausted.
-pub async fn with_retry<F, Fut, T>(policy: &RetryPolicy, mut operation: F) -> CortexResult<T>
+pub async fn with_retry<F, Fut, T>(policy: &RetryPolicy, operation: F) -> CortexResult<T>
 where
     F: FnMut() -> Fut,
     Fut: std::future::Future<Output = CortexResult<T>>,
 {
-    match policy {
+    retry_with_policy(policy, operation).await
+}
+
+/// Execute an async operation under any [`RetryStrategy`], such as a
+/// [`RetryPreset`].
+///
+/// Errors the strategy does not classify as retryable are returned
+/// immediately; on exhaustion the last error is wrapped in
+/// [`CortexError::RetriesExhausted`].
+///
+/// ```rust
+/// use emotiv_cortex_v2::retry::{RetryPreset, retry_with_policy};
+/// use emotiv_cortex_v2::CortexError;
+///
+/// # async fn demo() -> emotiv_cortex_v2::CortexResult<()> {
+/// let value = retry_with_policy(&RetryPreset::NetworkTransient, || async {
+///     // Any Cortex-adjacent operation returning `CortexResult`.
+///     Ok::<_, CortexError>(7)
+/// })
+/// .await?;
+/// assert_eq!(value, 7);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns any error from the operation, including a wrapped
+/// [`CortexError::RetriesExhausted`] when retry attempts are exhausted.
+pub async fn retry_with_policy<S, F, Fut, T>(strategy: &S, mut operation: F) -> CortexResult<T>
+where
+    S: RetryStrategy + ?Sized,
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = CortexResult<T>>,
+{
+    match &strategy.policy() {
         RetryPolicy::None => operation().await,
         RetryPolicy::Backoff {
             max_retries,
@@ -166,7 +548,7 @@ where
                     Ok(result) => return Ok(result),
                     Err(e) => {
                         // Non-retryable errors fail immediately
-                        if !e.is_retryable() {
+                        if !strategy.should_retry(&e) {
                             return Err(e);
                         }
 
@@ -178,15 +560,22 @@ where
                             });
                         }
 
+                        let sleep = strategy.jitter().apply(delay);
                         tracing::warn!(
                             attempt = attempt + 1,
                             max = max_retries + 1,
                             error = %e,
-                            delay_ms = u64::try_from(delay.as_millis()).unwrap_or(u64::MAX),
+                            delay_ms = u64::try_from(sleep.as_millis()).unwrap_or(u64::MAX),
                             "Retrying after transient error"
                         );
+                        strategy.on_retry(&RetryAttempt {
+                            attempt: attempt + 1,
+                            max_retries: *max_retries,
+                            delay: sleep,
+                            error: &e,
+                        });
 
-                        tokio::time::sleep(delay).await;
+                        tokio::time::sleep(sleep).await;
 
                         // Exponential backoff with cap
                         delay = std::cmp::min(delay * 2, *max_delay);
@@ -304,6 +693,30 @@ mod tests {
         assert_eq!(attempts.load(Ordering::SeqCst), 1); // Only tried once
     }
 
+    #[tokio::test]
+    async fn test_retry_decision_follows_cortex_error_code() {
+        let attempts = AtomicU32::new(0);
+        let policy = RetryPolicy::custom(3, Duration::from_millis(1), Duration::from_millis(10));
+
+        let result = with_retry(&policy, || {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if attempt == 0 {
+                    Err(CortexError::from_api_error(-32603, "internal error"))
+                } else {
+                    Err::<i32, _>(CortexError::from_api_error(-32104, "record not found"))
+                }
+            }
+        })
+        .await;
+
+        assert!(matches!(
+            result.unwrap_err(),
+            CortexError::RecordNotFound { .. }
+        ));
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
     #[tokio::test]
     async fn test_backoff_policy_succeeds_on_first_try() {
         let result = with_retry(&RetryPolicy::query(), || async { Ok::<_, CortexError>(99) }).await;
@@ -352,6 +765,128 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_preset_classification() {
+        let throttled = CortexError::from_api_error(-32999, "Too Many Requests");
+        let dial = CortexError::ConnectionFailed {
+            url: "wss://localhost:6868".into(),
+            reason: "refused".into(),
+        };
+
+        assert!(RetryPreset::NetworkTransient.should_retry(&dial));
+        assert!(!RetryPreset::NetworkTransient.should_retry(&throttled));
+        assert!(RetryPreset::RateLimited.should_retry(&throttled));
+        assert!(!RetryPreset::RateLimited.should_retry(&CortexError::Timeout { seconds: 1 }));
+        assert!(RetryPreset::Idempotent.should_retry(&throttled));
+        assert!(RetryPreset::Idempotent.should_retry(&CortexError::Timeout { seconds: 1 }));
+        assert!(!RetryPreset::Idempotent.should_retry(&CortexError::NoHeadsetFound));
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_custom_strategy() {
+        struct RetryHeadsetMissing;
+        impl RetryStrategy for RetryHeadsetMissing {
+            fn policy(&self) -> RetryPolicy {
+                RetryPolicy::custom(2, Duration::from_millis(1), Duration::from_millis(1))
+            }
+            fn should_retry(&self, error: &CortexError) -> bool {
+                matches!(error, CortexError::NoHeadsetFound)
+            }
+        }
+
+        let attempts = AtomicU32::new(0);
+        let result = retry_with_policy(&RetryHeadsetMissing, || {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if attempt == 0 {
+                    Err(CortexError::NoHeadsetFound)
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 1);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_retry_hooks_policy_and_classification() {
+        let hooks = RetryHooks::new()
+            .method("queryHeadsets", RetryPolicy::query())
+            .retryable_codes([-32004]);
+
+        assert!(matches!(
+            hooks.policy_for("queryHeadsets"),
+            RetryPolicy::Backoff { max_retries: 3, .. }
+        ));
+        assert!(matches!(
+            hooks.policy_for("createSession"),
+            RetryPolicy::None
+        ));
+        assert!(matches!(
+            hooks
+                .clone()
+                .default_policy(RetryPolicy::stop())
+                .policy_for("createSession"),
+            RetryPolicy::Backoff { max_retries: 2, .. }
+        ));
+
+        assert!(hooks.should_retry(&CortexError::Timeout { seconds: 1 }));
+        assert!(hooks.should_retry(&CortexError::NoHeadsetFound));
+        assert!(!hooks.should_retry(&CortexError::from_api_error(-32104, "x")));
+        assert!(!hooks.should_retry(&CortexError::ConnectionLost { reason: "x".into() }));
+
+        let custom = hooks.retry_if(|e| matches!(e, CortexError::RecordNotFound { .. }));
+        assert!(custom.should_retry(&CortexError::from_api_error(-32104, "x")));
+        assert!(!custom.should_retry(&CortexError::Timeout { seconds: 1 }));
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let delay = Duration::from_secs(1);
+        for _ in 0..100 {
+            assert!(Jitter::Full.apply(delay) <= delay);
+            let equal = Jitter::Equal.apply(delay);
+            assert!(equal >= delay / 2 && equal <= delay);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_hooks_report_each_retry() {
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = Arc::clone(&seen);
+        let hooks = RetryHooks::new()
+            .default_policy(RetryPolicy::custom(
+                2,
+                Duration::from_millis(1),
+                Duration::from_millis(1),
+            ))
+            .on_retry(move |method, retry| {
+                log.lock()
+                    .unwrap()
+                    .push((method.to_string(), retry.attempt));
+            });
+
+        let result = retry_with_policy(&hooks.for_method("getCortexInfo"), || async {
+            Err::<(), _>(CortexError::Timeout { seconds: 1 })
+        })
+        .await;
+
+        assert!(matches!(
+            result.unwrap_err(),
+            CortexError::RetriesExhausted { attempts: 3, .. }
+        ));
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                ("getCortexInfo".to_string(), 1),
+                ("getCortexInfo".to_string(), 2)
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_backoff_delay_caps_at_max_delay() {
         let attempts = AtomicU32::new(0);
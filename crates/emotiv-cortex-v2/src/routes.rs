@@ -0,0 +1,695 @@
+//! # Declarative Stream Routes
+//!
+//! Routes let `cortex.toml` decide where stream data goes, so a headless
+//! deployment needs no routing code:
+//!
+//! ```toml
+//! routes = [
+//!     "pow -> osc:127.0.0.1:9000",
+//!     "met -> csv:/data/met.csv",
+//! ]
+//! ```
+//!
+//! Each route is `<stream> -> <sink>`, where `<stream>` is a Cortex stream
+//! name ([`Streams::ALL`]) and `<sink>` is one of:
+//!
+//! | Sink | Output |
+//! |------|--------|
+//! | `csv:<path>` | One row per sample: `time` then the flattened data array. Appends; the header is written to new files only. |
+//! | `osc:<host:port>` | One UDP OSC message per sample to `/emotiv/<stream>`, with numbers as `f` and labels as `s` arguments. |
+//! | `lsl` | Parsed for compatibility with `emotiv-cortex-tui`, which owns the LSL outlets; [`ResilientClient::start_routes`] rejects it. |
+//!
+//! After creating a session, call [`ResilientClient::start_routes`] to
+//! subscribe every routed stream and start forwarding. Routes stop with
+//! [`RouteHandle::stop`], which writes the events still queued, flushes,
+//! and reports per route what was lost; pass the handle to
+//! [`ResilientClient::attach_sink`] to have shutdown do it. Like other
+//! subscriptions, routes are not carried across a reconnect, which
+//! creates a new session.
+//!
+//! ```no_run
+//! use emotiv_cortex_v2::{CortexConfig, ResilientClient};
+//!
+//! # async fn demo(headset_id: &str) -> emotiv_cortex_v2::CortexResult<()> {
+//! let config = CortexConfig::discover(None)?;
+//! let client = ResilientClient::connect(config).await?;
+//! let session = client.create_session(headset_id).await?;
+//!
+//! let routes = client.start_routes(&session.id).await?;
+//! let _ = tokio::signal::ctrl_c().await;
+//! for report in routes.stop().await {
+//!     println!("{}: {} written, {} lost", report.sink, report.written, report.lost());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+
+use crate::error::{CortexError, CortexResult};
+use crate::protocol::constants::Streams;
+use crate::protocol::streams::SubscriptionResult;
+use crate::reconnect::ResilientClient;
+use crate::sink::{Sink, SinkReport};
+use crate::util::f64_to_f32;
+
+// ─── Route Definitions ───────────────────────────────────────────────────
+
+/// Destination of a [`StreamRoute`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteSink {
+    /// Lab Streaming Layer outlet (handled by `emotiv-cortex-tui`).
+    Lsl,
+    /// OSC over UDP.
+    Osc(SocketAddr),
+    /// CSV file.
+    Csv(PathBuf),
+}
+
+impl fmt::Display for RouteSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteSink::Lsl => f.write_str("lsl"),
+            RouteSink::Osc(addr) => write!(f, "osc:{addr}"),
+            RouteSink::Csv(path) => write!(f, "csv:{}", path.display()),
+        }
+    }
+}
+
+/// One `<stream> -> <sink>` route from [`CortexConfig::routes`].
+///
+/// ```
+/// use emotiv_cortex_v2::routes::{RouteSink, StreamRoute};
+///
+/// let route: StreamRoute = "pow -> osc:127.0.0.1:9000".parse().unwrap();
+/// assert_eq!(route.stream, "pow");
+/// assert_eq!(route.sink, RouteSink::Osc("127.0.0.1:9000".parse().unwrap()));
+/// assert_eq!(route.to_string(), "pow -> osc:127.0.0.1:9000");
+/// ```
+///
+/// [`CortexConfig::routes`]: crate::CortexConfig::routes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct StreamRoute {
+    /// Cortex stream name.
+    pub stream: String,
+    /// Where the stream's samples go.
+    pub sink: RouteSink,
+}
+
+impl FromStr for StreamRoute {
+    type Err = CortexError;
+
+    fn from_str(s: &str) -> CortexResult<Self> {
+        let invalid = |reason: String| CortexError::ConfigError {
+            reason: format!("invalid route '{s}': {reason}"),
+        };
+
+        let (stream, sink) = s
+            .split_once("->")
+            .ok_or_else(|| invalid("expected '<stream> -> <sink>'".into()))?;
+        let stream = stream.trim();
+        if !Streams::ALL.contains(&stream) {
+            return Err(invalid(format!(
+                "unknown stream '{stream}' (expected one of {})",
+                Streams::ALL.join(", ")
+            )));
+        }
+
+        let sink = sink.trim();
+        let (kind, target) = sink.split_once(':').unwrap_or((sink, ""));
+        let target = target.trim();
+        let sink = match kind.trim() {
+            "lsl" if target.is_empty() => RouteSink::Lsl,
+            "osc" => {
+                let addr = target
+                    .to_socket_addrs()
+                    .ok()
+                    .and_then(|mut addrs| addrs.next())
+                    .ok_or_else(|| invalid(format!("cannot resolve OSC address '{target}'")))?;
+                RouteSink::Osc(addr)
+            }
+            "csv" if !target.is_empty() => RouteSink::Csv(PathBuf::from(target)),
+            "csv" => return Err(invalid("csv sink needs a path".into())),
+            other => {
+                return Err(invalid(format!(
+                    "unknown sink '{other}' (expected lsl, osc:<host:port>, or csv:<path>)"
+                )));
+            }
+        };
+
+        Ok(Self {
+            stream: stream.to_string(),
+            sink,
+        })
+    }
+}
+
+impl TryFrom<String> for StreamRoute {
+    type Error = CortexError;
+
+    fn try_from(s: String) -> CortexResult<Self> {
+        s.parse()
+    }
+}
+
+impl From<StreamRoute> for String {
+    fn from(route: StreamRoute) -> Self {
+        route.to_string()
+    }
+}
+
+impl fmt::Display for StreamRoute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {}", self.stream, self.sink)
+    }
+}
+
+// ─── Running Routes ──────────────────────────────────────────────────────
+
+/// Counters for one running route.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RouteStats {
+    /// The route.
+    #[serde(serialize_with = "serialize_display")]
+    pub route: StreamRoute,
+    /// Samples written to the sink.
+    pub samples: u64,
+    /// Samples that failed to write.
+    pub errors: u64,
+}
+
+fn serialize_display<S: serde::Serializer>(
+    route: &StreamRoute,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(route)
+}
+
+#[derive(Default)]
+struct Counters {
+    samples: AtomicU64,
+    errors: AtomicU64,
+    /// Samples written since the last successful flush when the final
+    /// flush failed.
+    unflushed: AtomicU64,
+    /// Why the route did not close cleanly.
+    close_error: Mutex<Option<String>>,
+}
+
+impl Counters {
+    fn set_close_error(&self, error: String) {
+        if let Ok(mut slot) = self.close_error.lock() {
+            slot.get_or_insert(error);
+        }
+    }
+}
+
+/// An open sink with its route's counters.
+struct RouteOutput {
+    sink: ActiveSink,
+    counters: Arc<Counters>,
+    /// Samples written since the last successful flush.
+    pending: u64,
+}
+
+/// Running routes started by [`ResilientClient::start_routes`].
+///
+/// Dropping the handle leaves the forwarding tasks running until their
+/// streams end; call [`Self::stop`] to stop them and flush files.
+pub struct RouteHandle {
+    session_id: String,
+    streams: Vec<String>,
+    counters: Vec<(StreamRoute, Arc<Counters>)>,
+    shutdown: watch::Sender<bool>,
+    /// Forwarding task per stream.
+    tasks: Vec<(String, JoinHandle<()>)>,
+}
+
+impl RouteHandle {
+    /// Session the routed streams are subscribed on.
+    #[must_use]
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Distinct streams subscribed for the routes.
+    #[must_use]
+    pub fn streams(&self) -> &[String] {
+        &self.streams
+    }
+
+    /// Per-route counters, in config order.
+    #[must_use]
+    pub fn stats(&self) -> Vec<RouteStats> {
+        self.counters
+            .iter()
+            .map(|(route, c)| RouteStats {
+                route: route.clone(),
+                samples: c.samples.load(Ordering::Relaxed),
+                errors: c.errors.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+
+    /// Stop forwarding, write the events already queued for each route,
+    /// and flush every sink. Returns one report per route, in config
+    /// order.
+    ///
+    /// `unflushed` counts the samples written since the last successful
+    /// flush of a sink whose final flush failed; some of them may have
+    /// reached the file when its buffer filled. The streams stay
+    /// subscribed; unsubscribe them or close the session afterwards.
+    pub async fn stop(self) -> Vec<SinkReport> {
+        let _ = self.shutdown.send(true);
+        for (stream, task) in self.tasks {
+            if task.await.is_err() {
+                for (route, c) in &self.counters {
+                    if route.stream == stream {
+                        c.set_close_error("forwarding task panicked".into());
+                    }
+                }
+            }
+        }
+        self.counters
+            .iter()
+            .map(|(route, c)| SinkReport {
+                sink: route.to_string(),
+                written: c.samples.load(Ordering::Relaxed),
+                dropped: c.errors.load(Ordering::Relaxed),
+                unflushed: c.unflushed.load(Ordering::Relaxed),
+                error: c.close_error.lock().ok().and_then(|e| e.clone()),
+            })
+            .collect()
+    }
+}
+
+impl Sink for RouteHandle {
+    fn name(&self) -> String {
+        format!("routes on {}", self.session_id)
+    }
+
+    fn close(self: Box<Self>) -> BoxFuture<'static, Vec<SinkReport>> {
+        Box::pin(self.stop())
+    }
+}
+
+/// Open sinks, subscribe the routed streams, and spawn one forwarding
+/// task per stream.
+pub(crate) async fn start(
+    client: &ResilientClient,
+    routes: &[StreamRoute],
+    session_id: &str,
+) -> CortexResult<RouteHandle> {
+    if let Some(route) = routes.iter().find(|r| r.sink == RouteSink::Lsl) {
+        return Err(CortexError::ConfigError {
+            reason: format!(
+                "route '{route}': LSL outlets are provided by emotiv-cortex-tui, \
+                 not by ResilientClient::start_routes"
+            ),
+        });
+    }
+
+    // Open every sink before subscribing so config mistakes fail fast.
+    let mut by_stream: Vec<(String, Vec<RouteOutput>)> = Vec::new();
+    let mut counters = Vec::with_capacity(routes.len());
+    for route in routes {
+        let output = RouteOutput {
+            sink: ActiveSink::open(route)?,
+            counters: Arc::new(Counters::default()),
+            pending: 0,
+        };
+        counters.push((route.clone(), Arc::clone(&output.counters)));
+        match by_stream.iter_mut().find(|(s, _)| *s == route.stream) {
+            Some((_, outputs)) => outputs.push(output),
+            None => by_stream.push((route.stream.clone(), vec![output])),
+        }
+    }
+
+    let streams: Vec<String> = by_stream.iter().map(|(s, _)| s.clone()).collect();
+    let (shutdown, _) = watch::channel(false);
+    if streams.is_empty() {
+        return Ok(RouteHandle {
+            session_id: session_id.to_string(),
+            streams,
+            counters,
+            shutdown,
+            tasks: Vec::new(),
+        });
+    }
+
+    let names: Vec<&str> = streams.iter().map(String::as_str).collect();
+    let mut receivers = client.create_stream_channels(&names).await;
+    let result = client.subscribe_streams(session_id, &names).await?;
+    if let Some(failure) = result.failure.first() {
+        let subscribed: Vec<&str> = result
+            .success
+            .iter()
+            .map(|s| s.stream_name.as_str())
+            .collect();
+        if !subscribed.is_empty() {
+            let _ = client.unsubscribe_streams(session_id, &subscribed).await;
+        }
+        return Err(CortexError::from_api_error(
+            failure.code,
+            format!(
+                "route stream '{}': {}",
+                failure.stream_name, failure.message
+            ),
+        ));
+    }
+
+    let mut tasks = Vec::with_capacity(by_stream.len());
+    for (stream, outputs) in by_stream {
+        let Some(rx) = receivers.remove(stream.as_str()) else {
+            continue;
+        };
+        let cols = columns(&result, &stream);
+        let task = tokio::spawn(forward(
+            stream.clone(),
+            cols,
+            rx,
+            outputs,
+            shutdown.subscribe(),
+        ));
+        tasks.push((stream, task));
+    }
+
+    tracing::info!(routes = routes.len(), streams = ?streams, "Stream routes started");
+    Ok(RouteHandle {
+        session_id: session_id.to_string(),
+        streams,
+        counters,
+        shutdown,
+        tasks,
+    })
+}
+
+fn columns(result: &SubscriptionResult, stream: &str) -> Vec<String> {
+    result
+        .subscription(stream)
+        .map(|s| s.cols.clone())
+        .unwrap_or_default()
+}
+
+async fn forward(
+    stream: String,
+    cols: Vec<String>,
+    mut rx: mpsc::Receiver<Value>,
+    mut outputs: Vec<RouteOutput>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    loop {
+        tokio::select! {
+            item = rx.recv() => {
+                let Some(event) = item else { break };
+                write_event(&stream, &cols, &event, &mut outputs);
+            }
+            _ = shutdown.changed() => {
+                // Events already dispatched to this route still belong in
+                // the output.
+                while let Ok(event) = rx.try_recv() {
+                    write_event(&stream, &cols, &event, &mut outputs);
+                }
+                break;
+            }
+        }
+    }
+
+    for output in &mut outputs {
+        match output.sink.flush() {
+            Ok(()) => output.pending = 0,
+            Err(e) => {
+                tracing::warn!(stream = %stream, error = %e, "Route flush failed");
+                output
+                    .counters
+                    .unflushed
+                    .store(output.pending, Ordering::Relaxed);
+                output
+                    .counters
+                    .set_close_error(format!("flush failed: {e}"));
+            }
+        }
+    }
+}
+
+fn write_event(stream: &str, cols: &[String], event: &Value, outputs: &mut [RouteOutput]) {
+    let time = event.get("time").and_then(Value::as_f64);
+    let mut values = Vec::new();
+    if let Some(data) = event.get(stream) {
+        flatten(data, &mut values);
+    }
+    for output in outputs {
+        let counters = &output.counters;
+        match output.sink.write(stream, cols, time, &values) {
+            Ok(()) => {
+                counters.samples.fetch_add(1, Ordering::Relaxed);
+                output.pending += 1;
+            }
+            Err(e) => {
+                if counters.errors.fetch_add(1, Ordering::Relaxed) == 0 {
+                    tracing::warn!(stream = %stream, error = %e, "Route write failed");
+                }
+            }
+        }
+    }
+}
+
+/// Flatten nested data arrays (e.g. `dev` contact quality) into one row.
+fn flatten<'a>(value: &'a Value, out: &mut Vec<&'a Value>) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                flatten(item, out);
+            }
+        }
+        other => out.push(other),
+    }
+}
+
+// ─── Sinks ───────────────────────────────────────────────────────────────
+
+enum ActiveSink {
+    Csv {
+        writer: BufWriter<File>,
+        needs_header: bool,
+    },
+    Osc {
+        socket: UdpSocket,
+        addr: SocketAddr,
+    },
+}
+
+impl ActiveSink {
+    fn open(route: &StreamRoute) -> CortexResult<Self> {
+        let config_error = |e: std::io::Error| CortexError::ConfigError {
+            reason: format!("route '{route}': {e}"),
+        };
+        match &route.sink {
+            RouteSink::Csv(path) => {
+                if let Some(parent) = path.parent() {
+                    if !parent.as_os_str().is_empty() {
+                        std::fs::create_dir_all(parent).map_err(config_error)?;
+                    }
+                }
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(config_error)?;
+                let needs_header = file.metadata().map_err(config_error)?.len() == 0;
+                Ok(ActiveSink::Csv {
+                    writer: BufWriter::new(file),
+                    needs_header,
+                })
+            }
+            RouteSink::Osc(addr) => {
+                let bind: SocketAddr = if addr.is_ipv4() {
+                    ([0, 0, 0, 0], 0).into()
+                } else {
+                    (std::net::Ipv6Addr::UNSPECIFIED, 0).into()
+                };
+                let socket = UdpSocket::bind(bind).map_err(config_error)?;
+                socket.set_nonblocking(true).map_err(config_error)?;
+                Ok(ActiveSink::Osc {
+                    socket,
+                    addr: *addr,
+                })
+            }
+            RouteSink::Lsl => Err(CortexError::ConfigError {
+                reason: format!("route '{route}': LSL is not supported here"),
+            }),
+        }
+    }
+
+    fn write(
+        &mut self,
+        stream: &str,
+        cols: &[String],
+        time: Option<f64>,
+        values: &[&Value],
+    ) -> std::io::Result<()> {
+        match self {
+            ActiveSink::Csv {
+                writer,
+                needs_header,
+            } => {
+                if *needs_header {
+                    writer.write_all(csv_header(cols, values.len()).as_bytes())?;
+                    *needs_header = false;
+                }
+                writer.write_all(csv_row(time, values).as_bytes())
+            }
+            ActiveSink::Osc { socket, addr } => {
+                let packet = osc_message(&format!("/emotiv/{stream}"), values);
+                socket.send_to(&packet, *addr).map(|_| ())
+            }
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            ActiveSink::Csv { writer, .. } => writer.flush(),
+            ActiveSink::Osc { .. } => Ok(()),
+        }
+    }
+}
+
+/// Header from the subscribe `cols` when they match the row width,
+/// otherwise `v0, v1, ...`.
+fn csv_header(cols: &[String], width: usize) -> String {
+    let mut fields = vec!["time".to_string()];
+    if cols.len() == width {
+        fields.extend(cols.iter().map(|c| csv_field(c)));
+    } else {
+        fields.extend((0..width).map(|i| format!("v{i}")));
+    }
+    fields.join(",") + "\n"
+}
+
+fn csv_row(time: Option<f64>, values: &[&Value]) -> String {
+    let mut fields = Vec::with_capacity(values.len() + 1);
+    fields.push(time.map(|t| t.to_string()).unwrap_or_default());
+    fields.extend(values.iter().map(|v| match v {
+        Value::Null => String::new(),
+        Value::String(s) => csv_field(s),
+        other => other.to_string(),
+    }));
+    fields.join(",") + "\n"
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Encode an OSC 1.0 message: numbers and booleans as `f`, strings as
+/// `s`; nulls are skipped.
+fn osc_message(address: &str, values: &[&Value]) -> Vec<u8> {
+    let mut tags = String::from(",");
+    let mut args = Vec::new();
+    for value in values {
+        match value {
+            Value::Number(n) => {
+                tags.push('f');
+                let f = f64_to_f32(n.as_f64().unwrap_or(f64::NAN));
+                args.extend_from_slice(&f.to_be_bytes());
+            }
+            Value::Bool(b) => {
+                tags.push('f');
+                args.extend_from_slice(&(if *b { 1.0_f32 } else { 0.0 }).to_be_bytes());
+            }
+            Value::String(s) => {
+                tags.push('s');
+                push_osc_string(&mut args, s);
+            }
+            _ => {}
+        }
+    }
+
+    let mut packet = Vec::with_capacity(address.len() + tags.len() + args.len() + 8);
+    push_osc_string(&mut packet, address);
+    push_osc_string(&mut packet, &tags);
+    packet.extend_from_slice(&args);
+    packet
+}
+
+/// OSC strings are NUL-terminated and padded to a multiple of 4 bytes.
+fn push_osc_string(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(s.as_bytes());
+    let padding = 4 - s.len() % 4;
+    out.extend(std::iter::repeat_n(0, padding));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_routes() {
+        let route: StreamRoute = " met->csv: /data/met.csv ".parse().unwrap();
+        assert_eq!(route.stream, "met");
+        assert_eq!(route.sink, RouteSink::Csv(PathBuf::from("/data/met.csv")));
+        assert_eq!(
+            "eeg -> lsl".parse::<StreamRoute>().unwrap().sink,
+            RouteSink::Lsl
+        );
+
+        for bad in [
+            "eeg",
+            "xyz -> lsl",
+            "eeg -> csv:",
+            "eeg -> mqtt:host",
+            "pow -> osc:nope",
+        ] {
+            assert!(bad.parse::<StreamRoute>().is_err(), "{bad} should fail");
+        }
+    }
+
+    #[test]
+    fn test_csv_rows_flatten_and_escape() {
+        let data = json!([4, 2, [4, 3.5], "a,b"]);
+        let mut values = Vec::new();
+        flatten(&data, &mut values);
+
+        assert_eq!(csv_header(&[], values.len()), "time,v0,v1,v2,v3,v4\n");
+        assert_eq!(
+            csv_header(
+                &["x".into(), "y".into(), "z".into(), "w".into(), "q\"".into()],
+                5
+            ),
+            "time,x,y,z,w,\"q\"\"\"\n"
+        );
+        assert_eq!(csv_row(Some(1.5), &values), "1.5,4,2,4,3.5,\"a,b\"\n");
+    }
+
+    #[test]
+    fn test_osc_message_encoding() {
+        let data = json!([0.5, "push"]);
+        let mut values = Vec::new();
+        flatten(&data, &mut values);
+
+        let packet = osc_message("/emotiv/com", &values);
+        let mut expected = b"/emotiv/com\0,fs\0".to_vec();
+        expected.extend_from_slice(&0.5_f32.to_be_bytes());
+        expected.extend_from_slice(b"push\0\0\0\0");
+        assert_eq!(packet, expected);
+    }
+}
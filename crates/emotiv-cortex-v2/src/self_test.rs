@@ -0,0 +1,487 @@
+//! # Deployment Self-Test
+//!
+//! [`ResilientClient::self_test`] checks that a new acquisition machine is
+//! set up correctly before a participant arrives, without a headset:
+//!
+//! - **features**: which crate features (TLS backend, TOML config,
+//!   telemetry) the build has;
+//! - **runtime**: the Tokio runtime flavor;
+//! - **cortex**: a `getCortexInfo` round trip over the configured URL,
+//!   which exercises TLS;
+//! - **stream**: samples from the [`testing`](crate::testing) generators,
+//!   encoded as Cortex stream events, pushed through a stream channel,
+//!   parsed by the same [`TypedStream`] adapter and parsers the
+//!   `subscribe_*` helpers use, and written to a [`StreamRecorder`] in the
+//!   scratch directory, which is closed through the [`Sink`] contract and
+//!   must lose nothing;
+//! - **routes**: that each of [`CortexConfig::routes`] can be served
+//!   (CSV directory writable, UDP socket available), without writing to
+//!   the targets.
+//!
+//! ```no_run
+//! use emotiv_cortex_v2::{CortexConfig, ResilientClient};
+//!
+//! # async fn demo() -> emotiv_cortex_v2::CortexResult<()> {
+//! let client = ResilientClient::connect(CortexConfig::discover(None)?).await?;
+//! let report = client.self_test().await;
+//! print!("{report}");
+//! if !report.passed() {
+//!     std::process::exit(1);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`ResilientClient::self_test`]: crate::ResilientClient::self_test
+//! [`TypedStream`]: crate::streams::TypedStream
+//! [`StreamRecorder`]: crate::recorder::StreamRecorder
+//! [`Sink`]: crate::sink::Sink
+//! [`CortexConfig::routes`]: crate::CortexConfig::routes
+
+use std::fmt;
+use std::fs;
+use std::net::UdpSocket;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use futures_util::StreamExt;
+use serde::Serialize;
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+
+use crate::config::CortexConfig;
+use crate::headset::HeadsetModel;
+use crate::protocol::constants::Streams;
+use crate::reconnect::ResilientClient;
+use crate::recorder::{RecorderConfig, StreamRecorder};
+use crate::routes::RouteSink;
+use crate::sink::Sink;
+use crate::streams::{ParsedSample, StreamSample, TypedStream, micros_to_secs, parse_sample_value};
+use crate::testing::{GeneratorSpec, gen_band_power_samples, gen_eeg_samples};
+
+/// Session ID carried by the simulated stream events.
+const SELF_TEST_SESSION: &str = "self-test";
+
+/// What [`ResilientClient::self_test_with`](crate::ResilientClient::self_test_with)
+/// simulates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfTestOptions {
+    /// Headset whose streams are simulated.
+    pub model: HeadsetModel,
+    /// Length of the simulated acquisition.
+    pub duration: Duration,
+    /// Directory for the temporary recording; removed again afterwards.
+    pub scratch_dir: PathBuf,
+}
+
+impl Default for SelfTestOptions {
+    /// Two seconds of an Insight, recorded to the system temp directory.
+    fn default() -> Self {
+        Self {
+            model: HeadsetModel::Insight,
+            duration: Duration::from_secs(2),
+            scratch_dir: std::env::temp_dir(),
+        }
+    }
+}
+
+/// Outcome of one self-test check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    /// Works as expected.
+    Pass,
+    /// Works, but not as a production deployment should.
+    Warn,
+    /// Does not work.
+    Fail,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+        })
+    }
+}
+
+impl CheckStatus {
+    /// `Warn`, unless already `Fail`.
+    fn max_warn(self) -> Self {
+        match self {
+            CheckStatus::Fail => CheckStatus::Fail,
+            _ => CheckStatus::Warn,
+        }
+    }
+}
+
+/// One self-test check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SelfTestCheck {
+    /// Check name: `features`, `runtime`, `cortex`, `stream`, or `routes`.
+    pub name: &'static str,
+    /// Outcome.
+    pub status: CheckStatus,
+    /// What was found.
+    pub detail: String,
+    /// Time the check took.
+    pub elapsed: Duration,
+}
+
+/// Result of [`ResilientClient::self_test`](crate::ResilientClient::self_test).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SelfTestReport {
+    /// Checks in the order they ran.
+    pub checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    /// Whether no check failed. Warnings do not fail the self-test.
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.status != CheckStatus::Fail)
+    }
+
+    /// The check named `name`, if it ran.
+    #[must_use]
+    pub fn check(&self, name: &str) -> Option<&SelfTestCheck> {
+        self.checks.iter().find(|c| c.name == name)
+    }
+
+    fn push(
+        &mut self,
+        name: &'static str,
+        started: Instant,
+        (status, detail): (CheckStatus, String),
+    ) {
+        self.checks.push(SelfTestCheck {
+            name,
+            status,
+            detail,
+            elapsed: started.elapsed(),
+        });
+    }
+}
+
+impl fmt::Display for SelfTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            writeln!(
+                f,
+                "{} {:<8} {} ({:?})",
+                check.status, check.name, check.detail, check.elapsed
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Run every check. See the [module documentation](self).
+pub(crate) async fn run(
+    client: &ResilientClient,
+    config: &CortexConfig,
+    options: &SelfTestOptions,
+) -> SelfTestReport {
+    let mut report = SelfTestReport::default();
+
+    let started = Instant::now();
+    report.push("features", started, (CheckStatus::Pass, features()));
+
+    let started = Instant::now();
+    report.push("runtime", started, runtime());
+
+    let started = Instant::now();
+    let cortex = match client.get_cortex_info().await {
+        Ok(info) if config.allow_insecure_tls => (
+            CheckStatus::Warn,
+            format!(
+                "Cortex {} at {} with certificate verification disabled",
+                version(&info),
+                config.cortex_url
+            ),
+        ),
+        Ok(info) => (
+            CheckStatus::Pass,
+            format!("Cortex {} at {}", version(&info), config.cortex_url),
+        ),
+        Err(e) => (
+            CheckStatus::Fail,
+            format!("{} unreachable: {e}", config.cortex_url),
+        ),
+    };
+    report.push("cortex", started, cortex);
+
+    let started = Instant::now();
+    report.push("stream", started, stream_round_trip(options).await);
+
+    if !config.routes.is_empty() {
+        let started = Instant::now();
+        report.push("routes", started, routes(config));
+    }
+
+    for check in &report.checks {
+        tracing::info!(check = check.name, status = %check.status, detail = %check.detail, "Self-test");
+    }
+    report
+}
+
+fn features() -> String {
+    let features = [
+        ("rustls-tls", cfg!(feature = "rustls-tls")),
+        ("native-tls", cfg!(feature = "native-tls")),
+        ("config-toml", cfg!(feature = "config-toml")),
+        ("support-bundle", cfg!(feature = "support-bundle")),
+        ("otel", cfg!(feature = "otel")),
+    ];
+    let enabled: Vec<&str> = features
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| *name)
+        .collect();
+    format!("enabled: {}", enabled.join(", "))
+}
+
+fn runtime() -> (CheckStatus, String) {
+    use tokio::runtime::{Handle, RuntimeFlavor};
+
+    match Handle::current().runtime_flavor() {
+        RuntimeFlavor::CurrentThread => (
+            CheckStatus::Warn,
+            "current-thread runtime: stream consumers share one thread with the reader loop".into(),
+        ),
+        flavor => (CheckStatus::Pass, format!("{flavor:?} runtime")),
+    }
+}
+
+fn version(info: &Value) -> &str {
+    info.get("version")
+        .and_then(Value::as_str)
+        .unwrap_or("(unknown version)")
+}
+
+/// Generate, dispatch, parse, and record simulated `eeg` and `pow`
+/// samples.
+async fn stream_round_trip(options: &SelfTestOptions) -> (CheckStatus, String) {
+    let spec = GeneratorSpec::default();
+    let eeg = gen_eeg_samples(&options.model, options.duration, &spec);
+    let pow = gen_band_power_samples(&options.model, options.duration, &spec);
+
+    let mut events: Vec<(&str, Value)> = Vec::with_capacity(eeg.len() + pow.len());
+    events.extend(eeg.iter().map(|s| {
+        let mut values = vec![json!(s.counter), json!(u8::from(s.interpolated))];
+        values.extend(s.channels.iter().map(|v| json!(v)));
+        values.extend([json!(s.raw_cq), json!(0), json!([])]);
+        (
+            Streams::EEG,
+            stream_event(Streams::EEG, s.timestamp, values),
+        )
+    }));
+    events.extend(pow.iter().map(|s| {
+        let values = s
+            .channel_powers
+            .iter()
+            .flatten()
+            .map(|v| json!(v))
+            .collect();
+        (
+            Streams::POW,
+            stream_event(Streams::POW, s.timestamp, values),
+        )
+    }));
+    let expected = events.len();
+
+    let path = options
+        .scratch_dir
+        .join(format!("emotiv-self-test-{}.jsonl", std::process::id()));
+    let recorder = match StreamRecorder::create(&path, RecorderConfig::default()) {
+        Ok(recorder) => recorder,
+        Err(e) => {
+            return (
+                CheckStatus::Fail,
+                format!("cannot record to {}: {e}", options.scratch_dir.display()),
+            );
+        }
+    };
+
+    let mut parsed = 0;
+    let mut mismatched = 0;
+    for (kind, rx) in dispatch(events) {
+        let mut stream = TypedStream::new(rx, move |event| parse_sample_value(kind, &event));
+        while let Some(sample) = stream.next().await {
+            parsed += 1;
+            if sample.kind() != kind || !matches_generated(&sample, &eeg, &pow) {
+                mismatched += 1;
+            }
+            if let Ok(line) = serde_json::to_value(&sample) {
+                let _ = recorder.record_json(&line);
+            }
+        }
+    }
+
+    let reports = Box::new(recorder).close().await;
+    let written_lines = fs::read_to_string(&path).map_or(0, |text| text.lines().count());
+    let _ = fs::remove_file(&path);
+
+    let lost: u64 = reports.iter().map(crate::sink::SinkReport::lost).sum();
+    let errors: Vec<String> = reports.iter().filter_map(|r| r.error.clone()).collect();
+    if parsed != expected || mismatched > 0 {
+        return (
+            CheckStatus::Fail,
+            format!("parsed {parsed} of {expected} simulated samples, {mismatched} did not match"),
+        );
+    }
+    if lost > 0 || !errors.is_empty() || written_lines != expected {
+        return (
+            CheckStatus::Fail,
+            format!(
+                "recorder wrote {written_lines} of {expected} samples to {} ({lost} lost{})",
+                options.scratch_dir.display(),
+                errors.first().map(|e| format!(": {e}")).unwrap_or_default()
+            ),
+        );
+    }
+    (
+        CheckStatus::Pass,
+        format!(
+            "{expected} simulated {:?} samples dispatched, parsed, and recorded",
+            options.model
+        ),
+    )
+}
+
+/// Route each event to its stream's channel, as the client's reader loop
+/// does, and close the channels.
+fn dispatch(events: Vec<(&'static str, Value)>) -> Vec<(&'static str, mpsc::Receiver<Value>)> {
+    let mut channels: Vec<(&str, mpsc::Sender<Value>, mpsc::Receiver<Value>)> = Vec::new();
+    for (kind, event) in events {
+        if !channels.iter().any(|(k, _, _)| *k == kind) {
+            // Sized for the whole run so nothing is dropped for being full.
+            let (tx, rx) = mpsc::channel(1 << 16);
+            channels.push((kind, tx, rx));
+        }
+        if let Some((_, tx, _)) = channels.iter().find(|(k, _, _)| *k == kind) {
+            let _ = tx.try_send(event);
+        }
+    }
+    channels
+        .into_iter()
+        .map(|(kind, _, rx)| (kind, rx))
+        .collect()
+}
+
+fn stream_event(kind: &str, timestamp: i64, values: Vec<Value>) -> Value {
+    let mut event = json!({
+        "sid": SELF_TEST_SESSION,
+        "time": micros_to_secs(timestamp),
+    });
+    event[kind] = Value::Array(values);
+    event
+}
+
+fn matches_generated(
+    sample: &ParsedSample,
+    eeg: &[crate::protocol::streams::EegData],
+    pow: &[crate::protocol::streams::BandPowerData],
+) -> bool {
+    match sample {
+        ParsedSample::Eeg(s) => eeg
+            .iter()
+            .any(|g| g.counter == s.counter && g.channels == s.channels),
+        ParsedSample::Pow(s) => pow.iter().any(|g| g.channel_powers == s.channel_powers),
+        _ => false,
+    }
+}
+
+/// Check every configured route can be served, without touching targets.
+fn routes(config: &CortexConfig) -> (CheckStatus, String) {
+    let mut status = CheckStatus::Pass;
+    let mut notes = Vec::new();
+    for route in &config.routes {
+        let problem = match &route.sink {
+            RouteSink::Csv(path) => csv_dir_problem(path),
+            RouteSink::Osc(_) => UdpSocket::bind(("0.0.0.0", 0))
+                .err()
+                .map(|e| format!("cannot open a UDP socket: {e}")),
+            RouteSink::Lsl => {
+                status = status.max_warn();
+                notes.push(format!("{route}: served by emotiv-cortex-tui only"));
+                None
+            }
+        };
+        if let Some(problem) = problem {
+            status = CheckStatus::Fail;
+            notes.push(format!("{route}: {problem}"));
+        }
+    }
+    if notes.is_empty() {
+        return (
+            status,
+            format!("{} routes can be served", config.routes.len()),
+        );
+    }
+    (status, notes.join("; "))
+}
+
+fn csv_dir_problem(path: &Path) -> Option<String> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    match fs::metadata(dir) {
+        Ok(meta) if !meta.is_dir() => Some(format!("{} is not a directory", dir.display())),
+        Ok(meta) if meta.permissions().readonly() => {
+            Some(format!("{} is read-only", dir.display()))
+        }
+        Ok(_) => None,
+        Err(e) => Some(format!("{}: {e}", dir.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stream_round_trip_records_every_sample() {
+        let dir = std::env::temp_dir().join(format!("self-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let options = SelfTestOptions {
+            model: HeadsetModel::EpocX,
+            duration: Duration::from_secs(1),
+            scratch_dir: dir.clone(),
+        };
+
+        let (status, detail) = stream_round_trip(&options).await;
+        assert_eq!(status, CheckStatus::Pass, "{detail}");
+        // 256 eeg + 8 pow samples.
+        assert!(detail.starts_with("264 "), "{detail}");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stream_round_trip_fails_without_scratch_dir() {
+        let options = SelfTestOptions {
+            scratch_dir: PathBuf::from("/nonexistent/emotiv-self-test"),
+            ..SelfTestOptions::default()
+        };
+        let (status, detail) = stream_round_trip(&options).await;
+        assert_eq!(status, CheckStatus::Fail);
+        assert!(detail.contains("cannot record"), "{detail}");
+    }
+
+    #[test]
+    fn test_routes_check_reports_unservable_csv_dir() {
+        let mut config = CortexConfig::new("id", "secret");
+        config.routes = vec![
+            "eeg -> csv:/nonexistent/dir/eeg.csv".parse().unwrap(),
+            "pow -> lsl".parse().unwrap(),
+        ];
+        let (status, detail) = routes(&config);
+        assert_eq!(status, CheckStatus::Fail);
+        assert!(detail.contains("/nonexistent/dir"), "{detail}");
+        assert!(detail.contains("emotiv-cortex-tui"), "{detail}");
+    }
+}
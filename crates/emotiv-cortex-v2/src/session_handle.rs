@@ -0,0 +1,527 @@
+//! # Session Handles
+//!
+//! A [`SessionHandle`] owns one acquisition run on a [`ResilientClient`]:
+//! the session, the headset it is bound to, the loaded profile, the
+//! subscribed streams, and the active record. When the headset dies
+//! mid-run, [`SessionHandle::migrate_to_headset`] moves the whole run
+//! to a spare without tearing down the consumers.
+//!
+//! Cortex delivers stream events by stream name, so the receivers
+//! returned from [`SessionHandle::open`] keep working across a
+//! migration: the streams are simply subscribed again on the new
+//! session. Consumers that need to know which headset produced a sample
+//! follow [`SessionHandle::watch`], which flips to the new
+//! [`SessionBinding`] in a single step once the spare is fully set up.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use emotiv_cortex_v2::{CortexConfig, ResilientClient};
+//! use emotiv_cortex_v2::protocol::constants::Streams;
+//! use emotiv_cortex_v2::session_handle::{SessionHandle, SessionHandleOptions};
+//!
+//! # async fn demo() -> emotiv_cortex_v2::CortexResult<()> {
+//! let client = Arc::new(ResilientClient::connect(CortexConfig::discover(None)?).await?);
+//! let options = SessionHandleOptions {
+//!     streams: vec![Streams::EEG.to_string()],
+//!     profile: Some("alice".into()),
+//!     record_title: Some("study-42".into()),
+//!     ..SessionHandleOptions::default()
+//! };
+//! let (handle, mut streams) = SessionHandle::open(client, "INSIGHT-A1B2C3D4", options).await?;
+//! let mut eeg = streams.remove(Streams::EEG).unwrap();
+//! tokio::spawn(async move { while let Some(_event) = eeg.recv().await {} });
+//!
+//! // The first headset's battery died; carry on with the spare.
+//! let migration = handle.migrate_to_headset("INSIGHT-E5F6A7B8").await?;
+//! println!("continuing in record {:?}", migration.current.record_id);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Epochs
+//!
+//! With [`SessionHandleOptions::replay_window`] set, the handle keeps the
+//! most recent EEG in an [`EpochBuffer`] as it passes through to the
+//! `eeg` receiver, and [`SessionHandle::epoch_around`] cuts marker-locked
+//! epochs out of it:
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//! use emotiv_cortex_v2::{CortexConfig, ResilientClient};
+//! use emotiv_cortex_v2::epochs::marker_time_us;
+//! use emotiv_cortex_v2::protocol::constants::Streams;
+//! use emotiv_cortex_v2::session_handle::{SessionHandle, SessionHandleOptions};
+//!
+//! # async fn demo(marker_ms: f64) -> emotiv_cortex_v2::CortexResult<()> {
+//! let client = Arc::new(ResilientClient::connect(CortexConfig::discover(None)?).await?);
+//! let options = SessionHandleOptions {
+//!     streams: vec![Streams::EEG.to_string()],
+//!     replay_window: Some(Duration::from_secs(10)),
+//!     ..SessionHandleOptions::default()
+//! };
+//! let (handle, _streams) = SessionHandle::open(client, "INSIGHT-A1B2C3D4", options).await?;
+//! // Once the post-marker window has arrived:
+//! let epoch = handle.epoch_around(
+//!     marker_time_us(marker_ms), Duration::from_millis(200), Duration::from_millis(800),
+//! );
+//! # let _ = epoch;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{Mutex, mpsc, watch};
+
+use crate::client::StreamReceivers;
+use crate::epochs::{Epoch, EpochBuffer, EpochWriter};
+use crate::error::{CortexError, CortexResult};
+use crate::headset::HeadsetModel;
+use crate::protocol::constants::Streams;
+use crate::protocol::profiles::ProfileAction;
+use crate::protocol::records::MarkerInfo;
+use crate::protocol::streams::EegData;
+use crate::reconnect::ResilientClient;
+use crate::record_template::{self, RecordNaming};
+
+/// Route the `eeg` receiver through a task on `client` that records each
+/// sample in a new replay buffer before passing the event on.
+///
+/// Events are passed on without waiting: when the consumer's receiver is
+/// full the event is counted in `dropped` and skipped, so the buffer
+/// keeps filling while the consumer is not reading.
+fn tap_eeg(
+    client: &ResilientClient,
+    receivers: &mut StreamReceivers,
+    headset_id: &str,
+    window: Duration,
+    dropped: Arc<AtomicU64>,
+) -> Option<EpochBuffer> {
+    let mut events = receivers.remove(Streams::EEG)?;
+    let model = HeadsetModel::from_headset_id(headset_id);
+    let num_channels = model.num_channels();
+    let (mut writer, buffer) = EpochBuffer::new(num_channels, model.sampling_rate_hz(), window);
+    let (tx, rx) = mpsc::channel(events.max_capacity());
+    receivers.insert(Streams::EEG, rx);
+    client.spawn_task("eeg replay tap", async move {
+        while let Some(event) = events.recv().await {
+            record_eeg(&mut writer, &event, num_channels);
+            // Keep buffering after the consumer drops its receiver.
+            if let Err(TrySendError::Full(_)) = tx.try_send(event) {
+                dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    });
+    Some(buffer)
+}
+
+fn record_eeg(writer: &mut EpochWriter, event: &serde_json::Value, num_channels: usize) {
+    let sample = event
+        .get("time")
+        .and_then(serde_json::Value::as_f64)
+        .and_then(|time| {
+            EegData::from_eeg_array(event.get("eeg")?.as_array()?, num_channels, time)
+        });
+    if let Some(sample) = sample {
+        if !writer.push(&sample) {
+            tracing::warn!(
+                expected = num_channels,
+                got = sample.channels.len(),
+                "EEG sample with unexpected channel count dropped from replay buffer"
+            );
+        }
+    }
+}
+
+/// Port reported on the marker that links a continuation record to the
+/// record it continues.
+pub const MIGRATION_MARKER_PORT: &str = "emotiv-cortex-v2";
+
+/// Label prefix of the linking marker; the previous record id follows
+/// the colon.
+pub const MIGRATION_MARKER_PREFIX: &str = "continues:";
+
+/// What a [`SessionHandle`] sets up on every headset it is bound to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionHandleOptions {
+    /// Streams to subscribe, e.g. [`Streams::EEG`](crate::protocol::constants::Streams::EEG).
+    pub streams: Vec<String>,
+    /// Profile to load on the headset before subscribing.
+    pub profile: Option<String>,
+    /// Record title. When set, a record is started on open and a
+    /// continuation record on every migration.
+    pub record_title: Option<String>,
+    /// Title the record from a naming template instead, continuing the
+    /// run numbering of the records already in Cortex. Takes precedence
+    /// over `record_title`.
+    pub record_template: Option<RecordNaming>,
+    /// Keep this much of the most recent EEG for
+    /// [`SessionHandle::epoch_around`]. Needs `eeg` in `streams`; the
+    /// buffer is sized for the model of the headset the handle opens on.
+    pub replay_window: Option<Duration>,
+}
+
+/// The session a [`SessionHandle`] is currently bound to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionBinding {
+    /// Cortex session id.
+    pub session_id: String,
+    /// Headset the session runs on.
+    pub headset_id: String,
+    /// Active record, if the handle records.
+    pub record_id: Option<String>,
+    /// Zero on open, incremented by each migration.
+    pub generation: u32,
+}
+
+/// Outcome of [`SessionHandle::migrate_to_headset`].
+#[derive(Debug)]
+pub struct Migration {
+    /// Binding before the migration.
+    pub previous: SessionBinding,
+    /// Binding after the migration.
+    pub current: SessionBinding,
+    /// Marker injected into the continuation record.
+    pub marker: Option<MarkerInfo>,
+    /// Failures while releasing the previous session. The old headset is
+    /// usually gone by the time of a migration, so these are expected and
+    /// do not fail the migration.
+    pub cleanup_errors: Vec<CortexError>,
+}
+
+/// An acquisition run that can be moved between headsets.
+///
+/// See the [module documentation](self).
+pub struct SessionHandle {
+    client: Arc<ResilientClient>,
+    options: SessionHandleOptions,
+    record_title: Option<String>,
+    binding: watch::Sender<SessionBinding>,
+    migration: Mutex<()>,
+    replay: Option<EpochBuffer>,
+    /// EEG events the replay tap skipped because the `eeg` receiver was
+    /// full.
+    replay_dropped: Arc<AtomicU64>,
+}
+
+impl SessionHandle {
+    /// Create a session on `headset_id`, load the profile, start the
+    /// record, and subscribe the streams.
+    ///
+    /// With [`SessionHandleOptions::record_template`] set, the record
+    /// title uses the next free run number, found with `queryRecords`
+    /// before the session is created.
+    ///
+    /// Returns the handle together with one receiver per stream; the
+    /// receivers outlive any number of migrations. With
+    /// [`SessionHandleOptions::replay_window`] set, `eeg` events reach
+    /// their receiver through the replay buffer.
+    ///
+    /// # Errors
+    /// Returns any error from the Cortex calls involved, including
+    /// per-stream subscribe failures. The new session is closed again
+    /// before returning an error.
+    pub async fn open(
+        client: Arc<ResilientClient>,
+        headset_id: &str,
+        options: SessionHandleOptions,
+    ) -> CortexResult<(Self, StreamReceivers)> {
+        let record_title = match &options.record_template {
+            Some(naming) => {
+                let run = naming.template.next_run(&client, &naming.fields).await?;
+                Some(naming.template.render(&naming.fields, run))
+            }
+            None => options.record_title.clone(),
+        };
+        let streams: Vec<&str> = options.streams.iter().map(String::as_str).collect();
+        let mut receivers = client.create_stream_channels(&streams).await;
+        let replay_dropped = Arc::new(AtomicU64::new(0));
+        let replay = options.replay_window.and_then(|window| {
+            tap_eeg(
+                &client,
+                &mut receivers,
+                headset_id,
+                window,
+                Arc::clone(&replay_dropped),
+            )
+        });
+        let session = client.create_session(headset_id).await?;
+        let handle = Self {
+            client,
+            options,
+            record_title,
+            binding: watch::Sender::new(SessionBinding {
+                session_id: session.id.clone(),
+                headset_id: headset_id.to_string(),
+                record_id: None,
+                generation: 0,
+            }),
+            migration: Mutex::new(()),
+            replay,
+            replay_dropped,
+        };
+
+        let setup = async {
+            handle.load_profile(headset_id).await?;
+            let record_id = match &handle.record_title {
+                Some(title) => Some(handle.client.create_record(&session.id, title).await?.uuid),
+                None => None,
+            };
+            handle.subscribe(&session.id).await?;
+            Ok::<_, CortexError>(record_id)
+        };
+        match setup.await {
+            Ok(record_id) => {
+                handle
+                    .binding
+                    .send_modify(|binding| binding.record_id = record_id);
+                Ok((handle, receivers))
+            }
+            Err(e) => {
+                handle.abandon(&session.id).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// The current binding.
+    #[must_use]
+    pub fn binding(&self) -> SessionBinding {
+        self.binding.borrow().clone()
+    }
+
+    /// Follow the binding; the receiver sees each migration as one update.
+    #[must_use]
+    pub fn watch(&self) -> watch::Receiver<SessionBinding> {
+        self.binding.subscribe()
+    }
+
+    /// The options the handle applies to every headset.
+    #[must_use]
+    pub fn options(&self) -> &SessionHandleOptions {
+        &self.options
+    }
+
+    /// Title of the first record, if the handle records. Continuation
+    /// records append ` (part N)`.
+    #[must_use]
+    pub fn record_title(&self) -> Option<&str> {
+        self.record_title.as_deref()
+    }
+
+    /// A file name matching the record title, for local output of the
+    /// same run; see [`record_template::file_name`].
+    #[must_use]
+    pub fn record_file_name(&self, extension: &str) -> Option<String> {
+        self.record_title
+            .as_deref()
+            .map(|title| record_template::file_name(title, extension))
+    }
+
+    /// The EEG replay buffer, if [`SessionHandleOptions::replay_window`]
+    /// is set and `eeg` is subscribed.
+    #[must_use]
+    pub fn replay_buffer(&self) -> Option<&EpochBuffer> {
+        self.replay.as_ref()
+    }
+
+    /// EEG events not passed on to the `eeg` receiver because it was full.
+    /// They are still in the replay buffer.
+    #[must_use]
+    pub fn replay_dropped(&self) -> u64 {
+        self.replay_dropped.load(Ordering::Relaxed)
+    }
+
+    /// Extract an epoch time-locked to `marker_time_us` from the replay
+    /// buffer; see [`EpochBuffer::epoch_around`].
+    ///
+    /// Returns `None` without a replay buffer, or when the window is not
+    /// fully buffered yet.
+    #[must_use]
+    pub fn epoch_around(
+        &self,
+        marker_time_us: i64,
+        pre: Duration,
+        post: Duration,
+    ) -> Option<Epoch> {
+        self.replay
+            .as_ref()?
+            .epoch_around(marker_time_us, pre, post)
+    }
+
+    /// The client the handle runs on.
+    #[must_use]
+    pub fn client(&self) -> &ResilientClient {
+        &self.client
+    }
+
+    /// Move the run to `new_headset_id`.
+    ///
+    /// The spare is connected, given a new session and the profile, and,
+    /// if the handle records, a continuation record whose first marker
+    /// (`continues:<previous record id>`) links it to the previous one.
+    /// Only then are the streams moved: the old subscriptions are dropped
+    /// before the new ones are made, so consumers never see samples from
+    /// both headsets interleaved. The binding is swapped last, after
+    /// which the previous record is stopped and its session closed.
+    ///
+    /// If anything fails before the swap, the spare's session is closed,
+    /// the old subscriptions are restored where possible, and the handle
+    /// stays bound to the previous headset.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::SessionError`] if the handle is already on
+    /// `new_headset_id`, and any error from setting up the spare.
+    pub async fn migrate_to_headset(&self, new_headset_id: &str) -> CortexResult<Migration> {
+        let _guard = self.migration.lock().await;
+        let previous = self.binding();
+        if previous.headset_id == new_headset_id {
+            return Err(CortexError::SessionError {
+                reason: format!("session is already bound to headset {new_headset_id}"),
+            });
+        }
+
+        self.client.connect_headset(new_headset_id).await?;
+        let session = self.client.create_session(new_headset_id).await?;
+        let generation = previous.generation + 1;
+        let prepared = async {
+            self.load_profile(new_headset_id).await?;
+            self.continue_record(&session.id, &previous, generation)
+                .await
+        };
+        let (record_id, marker) = match prepared.await {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                self.abandon(&session.id).await;
+                return Err(e);
+            }
+        };
+
+        let mut cleanup_errors = Vec::new();
+        self.unsubscribe(&previous.session_id, &mut cleanup_errors)
+            .await;
+        if let Err(e) = self.subscribe(&session.id).await {
+            self.abandon(&session.id).await;
+            if let Err(restore) = self.subscribe(&previous.session_id).await {
+                tracing::warn!(error = %restore, "failed to restore streams on previous session");
+            }
+            return Err(e);
+        }
+
+        let current = SessionBinding {
+            session_id: session.id,
+            headset_id: new_headset_id.to_string(),
+            record_id,
+            generation,
+        };
+        self.binding.send_replace(current.clone());
+        tracing::info!(
+            from = %previous.headset_id,
+            to = %new_headset_id,
+            session = %current.session_id,
+            "migrated session to spare headset"
+        );
+
+        if previous.record_id.is_some() {
+            if let Err(e) = self.client.stop_record(&previous.session_id).await {
+                cleanup_errors.push(e);
+            }
+        }
+        if let Err(e) = self.client.close_session(&previous.session_id).await {
+            cleanup_errors.push(e);
+        }
+        for error in &cleanup_errors {
+            tracing::warn!(%error, session = %previous.session_id, "previous session cleanup failed");
+        }
+
+        Ok(Migration {
+            previous,
+            current,
+            marker,
+            cleanup_errors,
+        })
+    }
+
+    async fn load_profile(&self, headset_id: &str) -> CortexResult<()> {
+        match &self.options.profile {
+            Some(profile) => {
+                self.client
+                    .setup_profile(headset_id, profile, ProfileAction::Load)
+                    .await
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Start the continuation record and inject the linking marker.
+    async fn continue_record(
+        &self,
+        session_id: &str,
+        previous: &SessionBinding,
+        generation: u32,
+    ) -> CortexResult<(Option<String>, Option<MarkerInfo>)> {
+        let Some(title) = &self.record_title else {
+            return Ok((None, None));
+        };
+        let title = format!("{title} (part {})", generation + 1);
+        let record = self.client.create_record(session_id, &title).await?;
+        let marker = match &previous.record_id {
+            Some(previous_record) => Some(
+                self.client
+                    .inject_marker(
+                        session_id,
+                        &format!("{MIGRATION_MARKER_PREFIX}{previous_record}"),
+                        i32::try_from(generation).unwrap_or(i32::MAX),
+                        MIGRATION_MARKER_PORT,
+                        None,
+                    )
+                    .await?,
+            ),
+            None => None,
+        };
+        Ok((Some(record.uuid), marker))
+    }
+
+    /// Subscribe every configured stream, failing on the first rejected one.
+    async fn subscribe(&self, session_id: &str) -> CortexResult<()> {
+        if self.options.streams.is_empty() {
+            return Ok(());
+        }
+        let streams: Vec<&str> = self.options.streams.iter().map(String::as_str).collect();
+        let result = self.client.subscribe_streams(session_id, &streams).await?;
+        if let Some(failure) = result.failure.first() {
+            return Err(CortexError::from_api_error(
+                failure.code,
+                format!(
+                    "session stream '{}': {}",
+                    failure.stream_name, failure.message
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn unsubscribe(&self, session_id: &str, errors: &mut Vec<CortexError>) {
+        if self.options.streams.is_empty() {
+            return;
+        }
+        let streams: Vec<&str> = self.options.streams.iter().map(String::as_str).collect();
+        if let Err(e) = self.client.unsubscribe_streams(session_id, &streams).await {
+            errors.push(e);
+        }
+    }
+
+    /// Best-effort release of a session that never became the binding.
+    async fn abandon(&self, session_id: &str) {
+        if self.record_title.is_some() {
+            let _ = self.client.stop_record(session_id).await;
+        }
+        if let Err(e) = self.client.close_session(session_id).await {
+            tracing::warn!(error = %e, session = %session_id, "failed to close abandoned session");
+        }
+    }
+}
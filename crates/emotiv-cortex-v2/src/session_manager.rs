@@ -0,0 +1,315 @@
+//! # Session Manager
+//!
+//! Every app otherwise repeats the same sequence: query headsets, connect
+//! one, poll until Cortex reports it `connected`, create a session, and
+//! at the end close the session and disconnect the headset.
+//! [`SessionManager::open`] does the first half for a
+//! [`HeadsetSelector`] and returns a [`ManagedSession`] guard that does
+//! the second half when it is closed or dropped.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use emotiv_cortex_v2::{CortexConfig, ResilientClient};
+//! use emotiv_cortex_v2::headset::HeadsetModel;
+//! use emotiv_cortex_v2::session_manager::{HeadsetSelector, SessionManager, SessionManagerConfig};
+//!
+//! # async fn demo() -> emotiv_cortex_v2::CortexResult<()> {
+//! let client = Arc::new(ResilientClient::connect(CortexConfig::discover(None)?).await?);
+//! let manager = SessionManager::new(client, SessionManagerConfig::default());
+//! let session = manager.open(&HeadsetSelector::Model(HeadsetModel::Insight)).await?;
+//! println!("{} on {:?}", session.session_id(), session.headset_model());
+//! // ... subscribe, record ...
+//! session.close().await?; // or drop it
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::{CortexError, CortexResult};
+use crate::headset::HeadsetModel;
+use crate::protocol::headset::{HeadsetInfo, QueryHeadsetsOptions};
+use crate::reconnect::ResilientClient;
+
+/// Default time to wait for a headset to report `connected`.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default interval between `queryHeadsets` polls while connecting.
+pub const DEFAULT_CONNECT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Which headset [`SessionManager::open`] uses. The first match in
+/// `queryHeadsets` order wins, preferring headsets already connected.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum HeadsetSelector {
+    /// Any headset Cortex knows about.
+    #[default]
+    Any,
+    /// The headset with this id.
+    Id(String),
+    /// Any headset of this model.
+    Model(HeadsetModel),
+}
+
+impl HeadsetSelector {
+    /// Whether `headset` is selected.
+    #[must_use]
+    pub fn matches(&self, headset: &HeadsetInfo) -> bool {
+        match self {
+            HeadsetSelector::Any => true,
+            HeadsetSelector::Id(id) => headset.id == *id,
+            HeadsetSelector::Model(model) => HeadsetModel::from_headset_info(headset) == *model,
+        }
+    }
+}
+
+/// Tuning for [`SessionManager`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionManagerConfig {
+    /// How long to wait for a headset to report `connected`.
+    pub connect_timeout: Duration,
+    /// Interval between `queryHeadsets` polls while waiting.
+    pub poll_interval: Duration,
+    /// Disconnect the headset when the session is closed, including
+    /// headsets that were already connected before [`SessionManager::open`].
+    pub disconnect_on_close: bool,
+}
+
+impl Default for SessionManagerConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            poll_interval: DEFAULT_CONNECT_POLL_INTERVAL,
+            disconnect_on_close: true,
+        }
+    }
+}
+
+/// Opens sessions on selected headsets. See the
+/// [module documentation](self).
+#[derive(Clone)]
+pub struct SessionManager {
+    client: Arc<ResilientClient>,
+    config: SessionManagerConfig,
+}
+
+impl SessionManager {
+    /// A manager opening sessions on `client`.
+    #[must_use]
+    pub fn new(client: Arc<ResilientClient>, config: SessionManagerConfig) -> Self {
+        Self { client, config }
+    }
+
+    /// The client sessions are opened on.
+    #[must_use]
+    pub fn client(&self) -> &Arc<ResilientClient> {
+        &self.client
+    }
+
+    /// Connect a headset matching `selector`, wait until Cortex reports
+    /// it `connected`, and create a session on it.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::NoHeadsetFound`] if no headset matches,
+    /// [`CortexError::Timeout`] if the headset does not connect within
+    /// [`SessionManagerConfig::connect_timeout`], and any error from the
+    /// Cortex calls involved. A headset connected here is disconnected
+    /// again before an error is returned.
+    pub async fn open(&self, selector: &HeadsetSelector) -> CortexResult<ManagedSession> {
+        let headsets = self
+            .client
+            .query_headsets(QueryHeadsetsOptions::default())
+            .await?;
+        let Some(candidate) = headsets
+            .iter()
+            .filter(|h| selector.matches(h))
+            .min_by_key(|h| h.status != "connected")
+            .cloned()
+        else {
+            return Err(CortexError::NoHeadsetFound);
+        };
+
+        let connected_here = candidate.status != "connected";
+        let opened = async {
+            let headset = if connected_here {
+                self.client.connect_headset(&candidate.id).await?;
+                self.wait_connected(&candidate.id).await?
+            } else {
+                candidate.clone()
+            };
+            let session = self.client.create_session(&headset.id).await?;
+            Ok::<_, CortexError>((headset, session.id))
+        };
+        match opened.await {
+            Ok((headset, session_id)) => {
+                tracing::info!(
+                    headset = %headset.id,
+                    session_id = %session_id,
+                    "Managed session opened"
+                );
+                Ok(ManagedSession {
+                    cleanup: Some(Cleanup {
+                        client: Arc::clone(&self.client),
+                        session_id: session_id.clone(),
+                        headset_id: headset.id.clone(),
+                        disconnect: self.config.disconnect_on_close,
+                    }),
+                    session_id,
+                    model: HeadsetModel::from_headset_info(&headset),
+                    headset,
+                })
+            }
+            Err(e) => {
+                if connected_here {
+                    let _ = self.client.disconnect_headset(&candidate.id).await;
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Poll `queryHeadsets` until `headset_id` reports `connected`.
+    async fn wait_connected(&self, headset_id: &str) -> CortexResult<HeadsetInfo> {
+        let options = QueryHeadsetsOptions {
+            id: Some(headset_id.to_string()),
+            ..QueryHeadsetsOptions::default()
+        };
+        let poll = async {
+            loop {
+                let headsets = self.client.query_headsets(options.clone()).await?;
+                if let Some(headset) = headsets
+                    .into_iter()
+                    .find(|h| h.id == headset_id && h.status == "connected")
+                {
+                    return Ok(headset);
+                }
+                tokio::time::sleep(self.config.poll_interval).await;
+            }
+        };
+        tokio::time::timeout(self.config.connect_timeout, poll)
+            .await
+            .map_err(|_| CortexError::Timeout {
+                seconds: self.config.connect_timeout.as_secs(),
+            })?
+    }
+}
+
+/// What a [`ManagedSession`] undoes.
+struct Cleanup {
+    client: Arc<ResilientClient>,
+    session_id: String,
+    headset_id: String,
+    disconnect: bool,
+}
+
+impl Cleanup {
+    async fn run(self) -> CortexResult<()> {
+        let closed = self.client.close_session(&self.session_id).await;
+        if self.disconnect {
+            self.client.disconnect_headset(&self.headset_id).await?;
+        }
+        closed
+    }
+}
+
+/// A session opened by [`SessionManager::open`]. Closing or dropping it
+/// closes the session and, per
+/// [`SessionManagerConfig::disconnect_on_close`], disconnects the
+/// headset.
+pub struct ManagedSession {
+    session_id: String,
+    headset: HeadsetInfo,
+    model: HeadsetModel,
+    cleanup: Option<Cleanup>,
+}
+
+impl ManagedSession {
+    /// Cortex session id.
+    #[must_use]
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Id of the headset the session is on.
+    #[must_use]
+    pub fn headset_id(&self) -> &str {
+        &self.headset.id
+    }
+
+    /// Model of the headset.
+    #[must_use]
+    pub fn headset_model(&self) -> &HeadsetModel {
+        &self.model
+    }
+
+    /// The headset as Cortex reported it once connected.
+    #[must_use]
+    pub fn headset(&self) -> &HeadsetInfo {
+        &self.headset
+    }
+
+    /// Close the session and disconnect the headset, reporting errors
+    /// that dropping the guard would only log.
+    ///
+    /// # Errors
+    /// Returns any error from `disconnectHeadset` or, failing that,
+    /// from closing the session. The headset is disconnected even if
+    /// closing the session fails.
+    pub async fn close(mut self) -> CortexResult<()> {
+        match self.cleanup.take() {
+            Some(cleanup) => cleanup.run().await,
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::fmt::Debug for ManagedSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ManagedSession")
+            .field("session_id", &self.session_id)
+            .field("headset_id", &self.headset.id)
+            .field("model", &self.model)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for ManagedSession {
+    fn drop(&mut self) {
+        let Some(cleanup) = self.cleanup.take() else {
+            return;
+        };
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                let session_id = cleanup.session_id.clone();
+                if let Err(error) = cleanup.run().await {
+                    tracing::warn!(%error, session_id, "Managed session cleanup failed");
+                }
+            });
+        } else {
+            tracing::warn!(
+                session_id = self.session_id,
+                "ManagedSession dropped outside a Tokio runtime; session left open"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headset(id: &str) -> HeadsetInfo {
+        serde_json::from_value(serde_json::json!({"id": id, "status": "discovered"})).unwrap()
+    }
+
+    #[test]
+    fn test_selector_matches_id_and_model() {
+        let insight = headset("INSIGHT-A1B2C3D4");
+        let epoc = headset("EPOCX-11223344");
+        assert!(HeadsetSelector::Any.matches(&epoc));
+        assert!(HeadsetSelector::Id("INSIGHT-A1B2C3D4".into()).matches(&insight));
+        assert!(!HeadsetSelector::Id("INSIGHT-A1B2C3D4".into()).matches(&epoc));
+        assert!(HeadsetSelector::Model(HeadsetModel::EpocX).matches(&epoc));
+        assert!(!HeadsetSelector::Model(HeadsetModel::EpocX).matches(&insight));
+    }
+}
@@ -0,0 +1,465 @@
+//! # Session Pooling
+//!
+//! Opening and closing a Cortex session for every short acquisition
+//! (QA rigs, automated test benches) quickly runs into Cortex rate
+//! limits. [`SessionPool`] keeps one session open per headset and hands
+//! out scoped [`SessionLease`]s instead.
+//!
+//! Returning a lease unsubscribes whatever the lease subscribed to, but
+//! leaves the session open for the next caller. Sessions that sit idle
+//! longer than [`SessionPoolConfig::idle_timeout`] are closed on the next
+//! [`SessionPool::acquire`] or [`SessionPool::evict_idle`].
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use emotiv_cortex_v2::{CortexClient, CortexConfig};
+//! use emotiv_cortex_v2::protocol::constants::Streams;
+//! use emotiv_cortex_v2::session_pool::{SessionPool, SessionPoolConfig};
+//!
+//! # async fn demo() -> emotiv_cortex_v2::CortexResult<()> {
+//! let config = CortexConfig::discover(None)?;
+//! let client = CortexClient::connect(&config).await?;
+//! let token = client.authenticate(&config.client_id, &config.client_secret).await?;
+//! let pool = SessionPool::new(Arc::new(client), token, SessionPoolConfig::default());
+//!
+//! for _ in 0..10 {
+//!     let mut lease = pool.acquire("INSIGHT-A1B2C3D4").await?;
+//!     lease.subscribe(&[Streams::EEG]).await?;
+//!     // ... short acquisition ...
+//!     lease.release().await?; // unsubscribes, keeps the session open
+//! }
+//!
+//! pool.close_all().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::client::CortexClient;
+use crate::error::{CortexError, CortexResult};
+use crate::protocol::streams::SubscriptionResult;
+
+/// Default idle time before a pooled session is closed.
+pub const DEFAULT_SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Tuning for [`SessionPool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionPoolConfig {
+    /// How long a returned session may stay open without a lease.
+    pub idle_timeout: Duration,
+}
+
+impl Default for SessionPoolConfig {
+    fn default() -> Self {
+        Self {
+            idle_timeout: DEFAULT_SESSION_IDLE_TIMEOUT,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PooledSession {
+    session_id: String,
+    /// `None` while leased, otherwise when the session was returned.
+    idle_since: Option<Instant>,
+}
+
+type PoolMap = Arc<Mutex<HashMap<String, PooledSession>>>;
+
+/// Keeps one Cortex session open per headset and lends it out as
+/// [`SessionLease`]s.
+///
+/// Each headset has at most one outstanding lease; acquiring a headset
+/// that is already leased returns [`CortexError::SessionError`].
+pub struct SessionPool {
+    client: Arc<CortexClient>,
+    cortex_token: Arc<Mutex<String>>,
+    config: SessionPoolConfig,
+    sessions: PoolMap,
+}
+
+impl SessionPool {
+    /// Create an empty pool that opens sessions through `client`.
+    pub fn new(
+        client: Arc<CortexClient>,
+        cortex_token: impl Into<String>,
+        config: SessionPoolConfig,
+    ) -> Self {
+        Self {
+            client,
+            cortex_token: Arc::new(Mutex::new(cortex_token.into())),
+            config,
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Replace the cortex token used for subsequent pool operations
+    /// (e.g. after re-authenticating).
+    pub fn set_cortex_token(&self, cortex_token: impl Into<String>) {
+        if let Ok(mut token) = self.cortex_token.lock() {
+            *token = cortex_token.into();
+        }
+    }
+
+    /// Pool configuration.
+    #[must_use]
+    pub fn config(&self) -> SessionPoolConfig {
+        self.config
+    }
+
+    /// Number of sessions held open by the pool (leased or idle).
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.sessions.lock().map_or(0, |sessions| sessions.len())
+    }
+
+    /// Whether the pool holds no sessions.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of open sessions not currently leased.
+    #[must_use]
+    pub fn idle_count(&self) -> usize {
+        self.sessions.lock().map_or(0, |sessions| {
+            sessions.values().filter(|s| s.idle_since.is_some()).count()
+        })
+    }
+
+    /// Lease the pooled session for `headset_id`, creating one if needed.
+    ///
+    /// Expired idle sessions are closed first.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::SessionError`] if the headset is already
+    /// leased, or any error from `createSession`.
+    pub async fn acquire(&self, headset_id: &str) -> CortexResult<SessionLease> {
+        self.evict_idle().await;
+
+        let reused = {
+            let mut sessions = lock_sessions(&self.sessions)?;
+            match sessions.get_mut(headset_id) {
+                Some(entry) if entry.idle_since.is_none() => {
+                    return Err(CortexError::SessionError {
+                        reason: format!(
+                            "Pooled session for headset {headset_id} is already leased"
+                        ),
+                    });
+                }
+                Some(entry) => {
+                    entry.idle_since = None;
+                    Some(entry.session_id.clone())
+                }
+                None => None,
+            }
+        };
+
+        let session_id = if let Some(session_id) = reused {
+            tracing::debug!(headset_id, session_id, "Reusing pooled session");
+            session_id
+        } else {
+            let token = self.cortex_token();
+            let session = self.client.create_session(&token, headset_id).await?;
+            let mut sessions = lock_sessions(&self.sessions)?;
+            sessions.insert(
+                headset_id.to_string(),
+                PooledSession {
+                    session_id: session.id.clone(),
+                    idle_since: None,
+                },
+            );
+            session.id
+        };
+
+        Ok(SessionLease {
+            client: Arc::clone(&self.client),
+            cortex_token: Arc::clone(&self.cortex_token),
+            sessions: Arc::clone(&self.sessions),
+            headset_id: headset_id.to_string(),
+            session_id,
+            subscribed: Vec::new(),
+            returned: false,
+        })
+    }
+
+    /// Close idle sessions older than the configured idle timeout.
+    ///
+    /// Returns the number of sessions evicted. Close failures are logged
+    /// and the session is dropped from the pool regardless.
+    pub async fn evict_idle(&self) -> usize {
+        let expired: Vec<String> = match self.sessions.lock() {
+            Ok(mut sessions) => {
+                let idle_timeout = self.config.idle_timeout;
+                let expired_headsets: Vec<String> = sessions
+                    .iter()
+                    .filter(|(_, s)| s.idle_since.is_some_and(|t| t.elapsed() >= idle_timeout))
+                    .map(|(headset, _)| headset.clone())
+                    .collect();
+                expired_headsets
+                    .iter()
+                    .filter_map(|headset| sessions.remove(headset))
+                    .map(|s| s.session_id)
+                    .collect()
+            }
+            Err(_) => return 0,
+        };
+
+        let token = self.cortex_token();
+        for session_id in &expired {
+            if let Err(e) = self.client.close_session(&token, session_id).await {
+                tracing::warn!(session_id, error = %e, "Failed to close idle pooled session");
+            }
+        }
+        expired.len()
+    }
+
+    /// Close every idle session and empty the pool.
+    ///
+    /// Sessions that are still leased are left open; they are closed
+    /// instead of pooled when their lease is returned.
+    ///
+    /// # Errors
+    /// Returns the first `updateSession` error encountered; remaining
+    /// sessions are still closed.
+    pub async fn close_all(&self) -> CortexResult<()> {
+        let idle: Vec<String> = {
+            let mut sessions = lock_sessions(&self.sessions)?;
+            let drained: Vec<PooledSession> = sessions.drain().map(|(_, s)| s).collect();
+            drained
+                .into_iter()
+                .filter(|s| s.idle_since.is_some())
+                .map(|s| s.session_id)
+                .collect()
+        };
+
+        let token = self.cortex_token();
+        let mut first_error = None;
+        for session_id in &idle {
+            if let Err(e) = self.client.close_session(&token, session_id).await {
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    fn cortex_token(&self) -> String {
+        read_token(&self.cortex_token)
+    }
+}
+
+/// A scoped loan of a pooled session.
+///
+/// Call [`SessionLease::release`] to reset subscriptions and return the
+/// session to the pool. Dropping a lease without releasing it performs
+/// the same reset on a background task.
+pub struct SessionLease {
+    client: Arc<CortexClient>,
+    cortex_token: Arc<Mutex<String>>,
+    sessions: PoolMap,
+    headset_id: String,
+    session_id: String,
+    subscribed: Vec<String>,
+    returned: bool,
+}
+
+impl SessionLease {
+    /// Cortex session ID backing this lease.
+    #[must_use]
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Headset the session was opened for.
+    #[must_use]
+    pub fn headset_id(&self) -> &str {
+        &self.headset_id
+    }
+
+    /// Client the session lives on, for calls not wrapped by the lease.
+    #[must_use]
+    pub fn client(&self) -> &CortexClient {
+        &self.client
+    }
+
+    /// Streams this lease has subscribed to and will unsubscribe on return.
+    #[must_use]
+    pub fn subscribed_streams(&self) -> &[String] {
+        &self.subscribed
+    }
+
+    /// Subscribe to `streams` on the leased session.
+    ///
+    /// Successfully subscribed streams are unsubscribed when the lease is
+    /// returned. Use the `streams::subscribe_*` helpers with
+    /// [`Self::client`] and [`Self::session_id`] and then
+    /// [`Self::track_streams`] if you need typed streams.
+    ///
+    /// # Errors
+    /// Returns any error from [`CortexClient::subscribe_streams`].
+    pub async fn subscribe(&mut self, streams: &[&str]) -> CortexResult<SubscriptionResult> {
+        let token = read_token(&self.cortex_token);
+        let result = self
+            .client
+            .subscribe_streams(&token, &self.session_id, streams)
+            .await?;
+        self.track_streams(result.success.iter().map(|s| s.stream_name.as_str()));
+        Ok(result)
+    }
+
+    /// Record streams subscribed outside [`Self::subscribe`] so they are
+    /// reset when the lease is returned.
+    pub fn track_streams<'a>(&mut self, streams: impl IntoIterator<Item = &'a str>) {
+        for stream in streams {
+            if !self.subscribed.iter().any(|s| s == stream) {
+                self.subscribed.push(stream.to_string());
+            }
+        }
+    }
+
+    /// Unsubscribe this lease's streams and return the session to the pool.
+    ///
+    /// If the reset fails the session is closed and dropped from the pool
+    /// so the next lease starts from a fresh session.
+    ///
+    /// # Errors
+    /// Returns the `unsubscribe` error that caused the session to be
+    /// discarded.
+    pub async fn release(mut self) -> CortexResult<()> {
+        self.returned = true;
+        let inner = ReturnedLease::take(&mut self);
+        inner.reset().await
+    }
+}
+
+impl Drop for SessionLease {
+    fn drop(&mut self) {
+        if self.returned {
+            return;
+        }
+        let inner = ReturnedLease::take(self);
+        if inner.streams.is_empty() {
+            inner.mark_idle();
+            return;
+        }
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                let _ = inner.reset().await;
+            });
+        } else {
+            // No runtime to unsubscribe on; forget the session rather
+            // than lend it out with stale subscriptions.
+            tracing::warn!(
+                session_id = inner.session_id,
+                "SessionLease dropped outside a Tokio runtime; session discarded from pool"
+            );
+            inner.forget();
+        }
+    }
+}
+
+/// Owned state needed to return a lease, detached from the lease itself
+/// so the reset can run after the lease is dropped.
+struct ReturnedLease {
+    client: Arc<CortexClient>,
+    cortex_token: Arc<Mutex<String>>,
+    sessions: PoolMap,
+    headset_id: String,
+    session_id: String,
+    streams: Vec<String>,
+}
+
+impl ReturnedLease {
+    fn take(lease: &mut SessionLease) -> Self {
+        Self {
+            client: Arc::clone(&lease.client),
+            cortex_token: Arc::clone(&lease.cortex_token),
+            sessions: Arc::clone(&lease.sessions),
+            headset_id: std::mem::take(&mut lease.headset_id),
+            session_id: std::mem::take(&mut lease.session_id),
+            streams: std::mem::take(&mut lease.subscribed),
+        }
+    }
+
+    async fn reset(self) -> CortexResult<()> {
+        if self.streams.is_empty() {
+            self.mark_idle();
+            return Ok(());
+        }
+
+        let token = read_token(&self.cortex_token);
+        let streams: Vec<&str> = self.streams.iter().map(String::as_str).collect();
+        match self
+            .client
+            .unsubscribe_streams(&token, &self.session_id, &streams)
+            .await
+        {
+            Ok(()) => {
+                self.mark_idle();
+                Ok(())
+            }
+            Err(e) => {
+                tracing::warn!(
+                    session_id = self.session_id,
+                    error = %e,
+                    "Failed to reset pooled session; closing it"
+                );
+                self.forget();
+                let _ = self.client.close_session(&token, &self.session_id).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Return the session to the pool as idle. If the pool was emptied
+    /// while leased (see [`SessionPool::close_all`]) the entry is gone and
+    /// the session is closed instead.
+    fn mark_idle(&self) {
+        let pooled = self.sessions.lock().is_ok_and(|mut sessions| {
+            match sessions.get_mut(&self.headset_id) {
+                Some(entry) if entry.session_id == self.session_id => {
+                    entry.idle_since = Some(Instant::now());
+                    true
+                }
+                _ => false,
+            }
+        });
+        if pooled {
+            return;
+        }
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let client = Arc::clone(&self.client);
+            let token = read_token(&self.cortex_token);
+            let session_id = self.session_id.clone();
+            handle.spawn(async move {
+                let _ = client.close_session(&token, &session_id).await;
+            });
+        }
+    }
+
+    fn forget(&self) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            if sessions
+                .get(&self.headset_id)
+                .is_some_and(|s| s.session_id == self.session_id)
+            {
+                sessions.remove(&self.headset_id);
+            }
+        }
+    }
+}
+
+fn lock_sessions(
+    sessions: &PoolMap,
+) -> CortexResult<std::sync::MutexGuard<'_, HashMap<String, PooledSession>>> {
+    sessions.lock().map_err(|_| CortexError::SessionError {
+        reason: "Session pool state is poisoned".into(),
+    })
+}
+
+fn read_token(token: &Mutex<String>) -> String {
+    token.lock().map(|t| t.clone()).unwrap_or_default()
+}
@@ -0,0 +1,98 @@
+//! # Sink Close Contract
+//!
+//! Everything that moves stream data out of the process (routes, the
+//! stream recorder, the socket supervisor, the TUI's LSL outlets) buffers
+//! samples somewhere: a channel, a `BufWriter`, a consumer queue. Stopping
+//! one without accounting for those buffers loses the tail of a recording
+//! silently.
+//!
+//! Every such sink implements [`Sink`]. [`Sink::close`] stops accepting
+//! data, drains what is already queued, flushes, and returns one
+//! [`SinkReport`] per output saying how many samples were written and how
+//! many never made it. Attach sinks with
+//! [`ResilientClient::attach_sink`] and [`ResilientClient::shutdown`]
+//! closes them first ([`TeardownStage::CloseSinks`]) and includes their
+//! reports in the [`TeardownReport`]:
+//!
+//! ```no_run
+//! use emotiv_cortex_v2::{CortexConfig, ResilientClient};
+//!
+//! # async fn demo(headset_id: &str) -> emotiv_cortex_v2::CortexResult<()> {
+//! let client = ResilientClient::connect(CortexConfig::discover(None)?).await?;
+//! let session = client.create_session(headset_id).await?;
+//! client.attach_sink(client.start_routes(&session.id).await?).await;
+//!
+//! let report = client.shutdown_on(tokio::signal::ctrl_c()).await;
+//! for sink in &report.sinks {
+//!     if !sink.is_lossless() {
+//!         eprintln!("{}: lost {} samples", sink.sink, sink.lost());
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`ResilientClient::attach_sink`]: crate::ResilientClient::attach_sink
+//! [`ResilientClient::shutdown`]: crate::ResilientClient::shutdown
+//! [`TeardownStage::CloseSinks`]: crate::teardown::TeardownStage::CloseSinks
+//! [`TeardownReport`]: crate::teardown::TeardownReport
+
+use futures_util::future::BoxFuture;
+use serde::Serialize;
+
+/// Data-loss accounting for one output of a closed [`Sink`].
+///
+/// Counts are in the sink's own unit: stream events for routes and the
+/// supervisor, records for the recorder, samples for LSL outlets.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SinkReport {
+    /// The output, e.g. `met -> csv:/data/met.csv`.
+    pub sink: String,
+    /// Samples delivered to the output.
+    pub written: u64,
+    /// Samples discarded before reaching the output: failed writes,
+    /// consumers that fell behind, outlets that were down.
+    pub dropped: u64,
+    /// Samples accepted but not confirmed on the output when it closed,
+    /// e.g. buffered data whose final flush failed.
+    pub unflushed: u64,
+    /// Why the output did not close cleanly, if it did not.
+    pub error: Option<String>,
+}
+
+impl SinkReport {
+    /// A report for `sink` with all counts zero.
+    #[must_use]
+    pub fn new(sink: impl Into<String>) -> Self {
+        Self {
+            sink: sink.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Samples that did not reach the output.
+    #[must_use]
+    pub fn lost(&self) -> u64 {
+        self.dropped + self.unflushed
+    }
+
+    /// Whether every accepted sample reached the output.
+    #[must_use]
+    pub fn is_lossless(&self) -> bool {
+        self.lost() == 0 && self.error.is_none()
+    }
+}
+
+/// A consumer of stream data that must be closed to avoid losing data.
+///
+/// See the [module documentation](self).
+pub trait Sink: Send + 'static {
+    /// Short description used as the teardown step target.
+    fn name(&self) -> String;
+
+    /// Stop accepting data, write what is already queued, flush, and
+    /// report one [`SinkReport`] per output.
+    ///
+    /// Closing never fails; problems are recorded in the reports.
+    fn close(self: Box<Self>) -> BoxFuture<'static, Vec<SinkReport>>;
+}
@@ -0,0 +1,309 @@
+//! # Stream Statistics
+//!
+//! "Is the headset actually delivering 128 Hz?" comes up whenever a
+//! pipeline misbehaves. [`StreamStats`] answers it for one typed stream:
+//! sample count, effective sampling rate, inter-sample interval and
+//! jitter, EEG packet loss from counter gaps, and when the last sample
+//! arrived.
+//!
+//! Attach it with [`SampleStreamExt::with_stats`], which passes samples
+//! through unchanged, and query it from anywhere through a clone:
+//!
+//! ```no_run
+//! use futures_util::StreamExt;
+//! use emotiv_cortex_v2::{CortexClient, HeadsetModel, streams};
+//! use emotiv_cortex_v2::stream_stats::StreamStats;
+//! use emotiv_cortex_v2::streams::SampleStreamExt;
+//!
+//! # async fn demo(client: &CortexClient, token: &str, session_id: &str) -> emotiv_cortex_v2::CortexResult<()> {
+//! let model = HeadsetModel::Insight;
+//! let stats = StreamStats::new();
+//! let eeg = streams::subscribe_eeg(client, token, session_id, model.num_channels()).await?;
+//! let mut eeg = eeg.with_stats(stats.clone());
+//!
+//! tokio::spawn(async move {
+//!     loop {
+//!         tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+//!         let snapshot = stats.snapshot();
+//!         println!("{:?} Hz, {} lost", snapshot.effective_rate_hz, snapshot.lost_samples);
+//!     }
+//! });
+//! while let Some(_sample) = eeg.next().await {}
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Rate and intervals come from the sample timestamps for streams that
+//! keep them (see [`StreamSample::timestamp`]), and from the arrival time
+//! otherwise.
+//!
+//! [`SampleStreamExt::with_stats`]: crate::streams::SampleStreamExt::with_stats
+
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
+
+use futures_core::Stream;
+use serde::Serialize;
+
+use crate::streams::StreamSample;
+use crate::util::{i64_to_f64, now_micros, u64_to_f64};
+
+/// Statistics of one stream at a point in time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StreamStatsSnapshot {
+    /// Cortex stream name of the samples seen, once one has arrived.
+    pub kind: Option<&'static str>,
+    /// Samples seen.
+    pub samples: u64,
+    /// Samples per second between the first and the last sample.
+    pub effective_rate_hz: Option<f64>,
+    /// Mean time between consecutive samples (ms).
+    pub mean_interval_ms: Option<f64>,
+    /// Standard deviation of the time between samples (ms).
+    pub jitter_ms: Option<f64>,
+    /// Longest time between consecutive samples (ms).
+    pub max_interval_ms: Option<f64>,
+    /// EEG counter discontinuities.
+    pub counter_gaps: u64,
+    /// EEG samples missing according to the counter.
+    pub lost_samples: u64,
+    /// Time of the first sample (µs since the Unix epoch).
+    pub first_timestamp: Option<i64>,
+    /// Time of the last sample (µs since the Unix epoch).
+    pub last_timestamp: Option<i64>,
+    /// Wall-clock time the last sample arrived.
+    pub last_received: Option<SystemTime>,
+}
+
+impl StreamStatsSnapshot {
+    /// Time since the last sample arrived, or `None` before the first.
+    #[must_use]
+    pub fn idle_for(&self) -> Option<Duration> {
+        let last = self.last_received?;
+        Some(SystemTime::now().duration_since(last).unwrap_or_default())
+    }
+
+    /// Fraction of expected EEG samples that were lost.
+    #[must_use]
+    pub fn loss_ratio(&self) -> f64 {
+        let expected = self.samples + self.lost_samples;
+        if expected == 0 {
+            0.0
+        } else {
+            u64_to_f64(self.lost_samples) / u64_to_f64(expected)
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct StatsState {
+    snapshot: StreamStatsSnapshot,
+    last_counter: Option<u32>,
+    /// Welford accumulators over intervals in µs.
+    intervals: u64,
+    interval_mean: f64,
+    interval_m2: f64,
+    interval_max: f64,
+}
+
+impl StatsState {
+    fn observe(&mut self, kind: &'static str, time_us: i64, counter: Option<u32>) {
+        let snapshot = &mut self.snapshot;
+        snapshot.kind = Some(kind);
+        snapshot.samples += 1;
+        snapshot.last_received = Some(SystemTime::now());
+
+        if let Some(previous) = snapshot.last_timestamp {
+            let interval = i64_to_f64(time_us - previous);
+            self.intervals += 1;
+            let delta = interval - self.interval_mean;
+            self.interval_mean += delta / u64_to_f64(self.intervals);
+            self.interval_m2 += delta * (interval - self.interval_mean);
+            self.interval_max = self.interval_max.max(interval);
+        }
+        let first = *snapshot.first_timestamp.get_or_insert(time_us);
+        snapshot.last_timestamp = Some(time_us);
+
+        if let (Some(previous), Some(counter)) = (self.last_counter, counter) {
+            let missing = if counter > previous {
+                counter - previous - 1
+            } else {
+                // A wrap back to zero is expected; a drop to any other
+                // value skipped the counters below it.
+                counter
+            };
+            if missing > 0 {
+                snapshot.counter_gaps += 1;
+                snapshot.lost_samples += u64::from(missing);
+            }
+        }
+        if counter.is_some() {
+            self.last_counter = counter;
+        }
+
+        if self.intervals > 0 {
+            let span = i64_to_f64(time_us - first);
+            snapshot.effective_rate_hz =
+                (span > 0.0).then(|| u64_to_f64(self.intervals) * 1e6 / span);
+            snapshot.mean_interval_ms = Some(self.interval_mean / 1e3);
+            snapshot.jitter_ms = Some((self.interval_m2 / u64_to_f64(self.intervals)).sqrt() / 1e3);
+            snapshot.max_interval_ms = Some(self.interval_max / 1e3);
+        }
+    }
+}
+
+/// Shared statistics collector for one stream. Clones share the same
+/// state. See the [module documentation](self).
+#[derive(Debug, Clone, Default)]
+pub struct StreamStats {
+    state: Arc<Mutex<StatsState>>,
+}
+
+impl StreamStats {
+    /// An empty collector.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Account for one sample.
+    pub fn observe<T: StreamSample + ?Sized>(&self, sample: &T) {
+        let time_us = sample.timestamp().unwrap_or_else(now_micros);
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .observe(sample.kind(), time_us, sample.counter());
+    }
+
+    /// The statistics so far.
+    #[must_use]
+    pub fn snapshot(&self) -> StreamStatsSnapshot {
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .snapshot
+            .clone()
+    }
+
+    /// Start over, e.g. after resubscribing.
+    pub fn reset(&self) {
+        *self.state.lock().unwrap_or_else(PoisonError::into_inner) = StatsState::default();
+    }
+}
+
+/// A stream that records each sample in a [`StreamStats`] and passes it
+/// on unchanged. Ends with the inner stream.
+pub struct WithStats<S> {
+    inner: S,
+    stats: StreamStats,
+}
+
+impl<S> WithStats<S>
+where
+    S: Stream + Unpin,
+    S::Item: StreamSample,
+{
+    /// Record `inner` in `stats`.
+    pub fn new(inner: S, stats: StreamStats) -> Self {
+        Self { inner, stats }
+    }
+
+    /// The collector.
+    #[must_use]
+    pub fn stats(&self) -> &StreamStats {
+        &self.stats
+    }
+}
+
+impl<S> Stream for WithStats<S>
+where
+    S: Stream + Unpin,
+    S::Item: StreamSample,
+{
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let next = Pin::new(&mut this.inner).poll_next(cx);
+        if let Poll::Ready(Some(sample)) = &next {
+            this.stats.observe(sample);
+        }
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::streams::{MentalCommand, MentalCommandAction};
+    use crate::streams::SampleStreamExt;
+    use crate::testing::eeg_sample;
+    use futures_util::{StreamExt, stream};
+
+    #[test]
+    fn test_rate_and_jitter_from_sample_timestamps() {
+        let stats = StreamStats::new();
+        // 128 Hz nominal (7812.5 µs) with alternating ±500 µs jitter.
+        for n in 0..129_u32 {
+            let jitter = if n % 2 == 0 { 500 } else { -500 };
+            let timestamp = i64::from(n) * 7_812 + jitter;
+            stats.observe(&eeg_sample(timestamp, n % 128, vec![4200.0]));
+        }
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.kind, Some("eeg"));
+        assert_eq!(snapshot.samples, 129);
+        let rate = snapshot.effective_rate_hz.unwrap();
+        assert!((rate - 128.0).abs() < 0.1, "{rate}");
+        assert!((snapshot.jitter_ms.unwrap() - 1.0).abs() < 0.01);
+        assert_eq!(snapshot.counter_gaps, 0);
+        assert!(snapshot.last_received.is_some());
+    }
+
+    #[test]
+    fn test_counter_gaps_count_lost_samples_across_wraps() {
+        let stats = StreamStats::new();
+        for (n, counter) in [125, 126, 127, 0, 1, 4, 5, 127, 3].into_iter().enumerate() {
+            stats.observe(&eeg_sample(
+                i64::try_from(n).unwrap() * 7_812,
+                counter,
+                vec![4200.0],
+            ));
+        }
+        let snapshot = stats.snapshot();
+        // 1 -> 4 skips two, 5 -> 127 skips 121, and 127 -> 3 skips 0..=2.
+        assert_eq!(snapshot.counter_gaps, 3);
+        assert_eq!(snapshot.lost_samples, 2 + 121 + 3);
+
+        stats.reset();
+        assert_eq!(stats.snapshot(), StreamStatsSnapshot::default());
+    }
+
+    #[tokio::test]
+    async fn test_with_stats_passes_samples_through() {
+        let stats = StreamStats::new();
+        let commands = [
+            MentalCommand {
+                action: MentalCommandAction::Push,
+                power: 0.5,
+            },
+            MentalCommand {
+                action: MentalCommandAction::Neutral,
+                power: 0.0,
+            },
+        ];
+        let seen: Vec<_> = stream::iter(commands)
+            .with_stats(stats.clone())
+            .map(|command| command.action)
+            .collect()
+            .await;
+        assert_eq!(
+            seen,
+            vec![MentalCommandAction::Push, MentalCommandAction::Neutral]
+        );
+        let snapshot = stats.snapshot();
+        assert_eq!((snapshot.kind, snapshot.samples), (Some("com"), 2));
+        assert_eq!(snapshot.lost_samples, 0);
+    }
+}
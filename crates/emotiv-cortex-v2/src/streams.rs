@@ -8,6 +8,10 @@
 //! `mpsc` channel into typed values using a parser closure. Events that fail
 //! to parse are silently skipped.
 //!
+//! [`TypedStream::with_raw`] keeps the original event next to each parsed
+//! item ([`WithRaw`]), for logging frames that parse to suspicious values;
+//! [`subscribe_with_raw`] does the same for any Cortex stream.
+//!
 //! ## Convenience Subscriptions
 //!
 //! This module provides subscribe functions for all 9 Cortex data streams.
@@ -27,20 +31,95 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! [`subscribe_all`] subscribes every stream in one call and returns
+//! typed receivers for those the headset and license support, listing
+//! the rest as unsupported.
+//!
+//! ## Decimation
+//!
+//! [`subscribe_eeg_decimated`] and [`subscribe_motion_decimated`] wrap the
+//! typed stream in a [`BoxcarDecimator`] for consumers (UIs, dashboards)
+//! that do not need the full sample rate. Consumers that need an exact
+//! output rate (LSL, EDF) should use [`crate::resample`] instead. The
+//! same adapters are available on any EEG or motion stream as
+//! [`SampleStreamExt::decimate`] and [`SampleStreamExt::resample_to`], e.g.
+//! `eeg.resample_to(256.0, 32.0)?` for a 32 Hz view of 256 Hz data.
+//!
+//! ## Layout Changes
+//!
+//! If the headset mode changes mid-session, the stream's array layout can
+//! change. The `*_tracked` helpers ([`subscribe_eeg_tracked`],
+//! [`subscribe_eq_tracked`], [`subscribe_band_power_tracked`]) yield
+//! [`StreamItem::SchemaChanged`] with the refreshed [`StreamSchema`]
+//! instead of parsing the new layout with the old channel count.
+//!
+//! ## Generic Consumers
+//!
+//! Every typed sample implements [`StreamSample`] (stream kind, timestamp,
+//! session ID, flat channel values where applicable), as does
+//! [`ParsedSample`], so sinks can be written once for all streams.
+//!
+//! ## Markers
+//!
+//! [`MarkerStreamExt::with_markers`] merges a channel of
+//! [`StreamMarker`]s into any typed sample stream in timestamp order, so
+//! a single loop can render markers inline with the data.
+//!
+//! ## Combinators
+//!
+//! [`SampleStreamExt`] adds [`merge`](SampleStreamExt::merge) (several
+//! typed streams as one stream of [`ParsedSample`]s),
+//! [`with_timeout`](SampleStreamExt::with_timeout) (an error item when a
+//! stream goes silent), [`quality_gated`](SampleStreamExt::quality_gated)
+//! (EEG dropped while the paired `dev`/`eq` stream reports poor contact),
+//! [`microvolts`](SampleStreamExt::microvolts) (EEG scaled to
+//! microvolts with the DC offset removed, see [`crate::eeg_units`]),
+//! [`artifacts`](SampleStreamExt::artifacts) (EEG windows flagged for
+//! blinks and muscle artifacts, see [`crate::artifacts`]), and
+//! [`with_stats`](SampleStreamExt::with_stats) (rate, jitter and packet
+//! loss of any stream, see [`crate::stream_stats`]).
+//! With the `dsp` feature, `filtered` runs EEG through notch and bandpass
+//! filters (see `dsp`).
+//!
+//! ## Exporting to Files
+//!
+//! [`exporter::EegExporter`] writes an EEG stream to NDJSON or CSV files
+//! with size- or duration-based rotation and optional gzip compression.
+//!
+//! ## Validating Captures
+//!
+//! [`parse_sample`] runs a single captured message through the same
+//! parsers, so recorded traffic can be checked against the crate.
 
+pub mod exporter;
+
+use std::collections::{HashMap, VecDeque};
 use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::task::{Context, Poll};
 
 use futures_core::Stream;
+use futures_util::StreamExt;
+use serde::Serialize;
 use tokio::sync::mpsc;
 
+use crate::annotations::{CaptureGaps, GapCause};
+use crate::artifacts::{ArtifactDetector, ArtifactFlags};
+use crate::band_power::{BandPowerSmoother, RelativeBandPower, SmoothedBandPower};
 use crate::client::CortexClient;
+use crate::eeg_units::{EegInMicrovolts, EegUnitConverter};
 use crate::error::{CortexError, CortexResult};
-use crate::protocol::constants::Streams;
+use crate::headset::HeadsetModel;
+use crate::protocol::constants::{ErrorCodes, Streams};
 use crate::protocol::streams::{
     BandPowerData, DeviceQuality, EegData, EegQuality, EqEvent, FacialExpression, MentalCommand,
-    MotEvent, MotionData, PerformanceMetrics, PowEvent, SysEvent,
+    MetColumnMap, MotEvent, MotionData, MotionLayout, PerformanceMetrics, PowEvent,
+    StreamSubscriptionFailure, SubscriptionResult, SysEvent, seconds_to_micros_i64,
 };
+use crate::resample::{Resample, Resampled, ResamplerConfig};
+use crate::stream_stats::{StreamStats, WithStats};
 
 fn f64_to_f32(value: f64) -> Option<f32> {
     if !value.is_finite() {
@@ -49,17 +128,6 @@ fn f64_to_f32(value: f64) -> Option<f32> {
     value.to_string().parse::<f32>().ok()
 }
 
-fn seconds_to_micros_i64(timestamp_secs: f64) -> Option<i64> {
-    if !timestamp_secs.is_finite() {
-        return None;
-    }
-    let micros = timestamp_secs * 1_000_000.0;
-    if !micros.is_finite() {
-        return None;
-    }
-    format!("{micros:.0}").parse::<i64>().ok()
-}
-
 /// Generic stream adapter that receives raw JSON events from an mpsc channel
 /// and transforms them into typed values using a parser closure.
 ///
@@ -131,8 +199,297 @@ where
     }
 }
 
+impl<T, F> TypedStream<T, F>
+where
+    T: Send + 'static,
+    F: Fn(serde_json::Value) -> Option<T> + Unpin + Send + 'static,
+{
+    /// Deliver each sample to `callback` instead of polling the stream.
+    ///
+    /// Equivalent to [`on_sample`] with [`CallbackOptions::default()`].
+    ///
+    /// # Errors
+    /// Returns [`CortexError::Io`] if the callback thread cannot be spawned.
+    pub fn on_sample<C>(self, callback: C) -> CortexResult<SampleCallbackGuard>
+    where
+        C: FnMut(T) + Send + 'static,
+    {
+        on_sample(self, CallbackOptions::default(), callback)
+    }
+
+    /// Like [`Self::on_sample`], with explicit queue limits.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::Io`] if the callback thread cannot be spawned.
+    pub fn on_sample_with<C>(
+        self,
+        options: CallbackOptions,
+        callback: C,
+    ) -> CortexResult<SampleCallbackGuard>
+    where
+        C: FnMut(T) + Send + 'static,
+    {
+        on_sample(self, options, callback)
+    }
+}
+
+impl<T, F> TypedStream<T, F>
+where
+    F: Fn(serde_json::Value) -> Option<T>,
+{
+    /// Yield each parsed item together with the event it was parsed from.
+    ///
+    /// A debugging aid: every event is cloned before parsing, so leave it
+    /// off in production pipelines.
+    pub fn with_raw(self) -> RawTypedStream<T, F> {
+        RawTypedStream {
+            rx: self.rx,
+            parser: self.parser,
+        }
+    }
+}
+
+// ─── Raw Payloads ────────────────────────────────────────────────────────
+
+/// A parsed item and the Cortex event it came from, yielded by
+/// [`TypedStream::with_raw`] and [`subscribe_with_raw`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WithRaw<T> {
+    /// The parsed item.
+    pub sample: T,
+    /// The event as Cortex sent it.
+    pub raw: serde_json::Value,
+}
+
+impl<T: StreamSample> StreamSample for WithRaw<T> {
+    fn kind(&self) -> &'static str {
+        self.sample.kind()
+    }
+
+    fn timestamp(&self) -> Option<i64> {
+        self.sample.timestamp()
+    }
+
+    fn session_id(&self) -> Option<&str> {
+        self.sample
+            .session_id()
+            .or_else(|| self.raw.get("sid")?.as_str())
+    }
+
+    fn as_f32_slice(&self) -> Option<&[f32]> {
+        self.sample.as_f32_slice()
+    }
+}
+
+/// A [`TypedStream`] that keeps each event next to its parsed item; see
+/// [`TypedStream::with_raw`].
+///
+/// ```rust
+/// use emotiv_cortex_v2::streams::TypedStream;
+/// use futures_util::StreamExt;
+/// use tokio::sync::mpsc;
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let (tx, rx) = mpsc::channel(4);
+/// let mut stream = TypedStream::new(rx, |event| event.get("value")?.as_f64()).with_raw();
+///
+/// tx.send(serde_json::json!({"value": 1e9, "sid": "s"})).await.unwrap();
+/// let item = stream.next().await.unwrap();
+/// if item.sample > 1e6 {
+///     eprintln!("suspicious frame: {}", item.raw);
+/// }
+/// # });
+/// ```
+pub struct RawTypedStream<T, F>
+where
+    F: Fn(serde_json::Value) -> Option<T>,
+{
+    rx: mpsc::Receiver<serde_json::Value>,
+    parser: F,
+}
+
+impl<T, F> Stream for RawTypedStream<T, F>
+where
+    T: Send,
+    F: Fn(serde_json::Value) -> Option<T> + Unpin + Send,
+{
+    type Item = WithRaw<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.rx.poll_recv(cx) {
+                Poll::Ready(Some(raw)) => {
+                    if let Some(sample) = (self.parser)(raw.clone()) {
+                        return Poll::Ready(Some(WithRaw { sample, raw }));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// Subscribe to `stream` and yield each [`ParsedSample`] with the event it
+/// was parsed from.
+///
+/// Channel counts and `met` columns are inferred per message as in
+/// [`parse_sample`], so one helper covers every stream while debugging a
+/// parse. Use the typed `subscribe_*` helpers otherwise.
+///
+/// # Errors
+/// Returns any error produced by stream channel registration or
+/// subscription RPC calls.
+pub async fn subscribe_with_raw(
+    client: &CortexClient,
+    cortex_token: &str,
+    session_id: &str,
+    stream: &str,
+) -> CortexResult<Pin<Box<dyn Stream<Item = WithRaw<ParsedSample>> + Send>>> {
+    let rx = add_channel(client, stream)?;
+
+    let result = client
+        .subscribe_streams(cortex_token, session_id, &[stream])
+        .await?;
+    ensure_subscribed(&result, stream)?;
+
+    let kind = stream.to_string();
+    Ok(Box::pin(
+        TypedStream::new(rx, move |event| parse_sample_value(&kind, &event)).with_raw(),
+    ))
+}
+
+// ─── Callbacks ───────────────────────────────────────────────────────────
+
+/// Default number of samples queued ahead of a slow sample callback.
+pub const DEFAULT_CALLBACK_QUEUE_CAPACITY: usize = 256;
+
+/// Queue limits for push-based delivery via [`on_sample`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallbackOptions {
+    /// Samples buffered between the stream and the callback. When the
+    /// callback falls this far behind, new samples are dropped (and
+    /// counted) rather than stalling the stream.
+    pub queue_capacity: usize,
+}
+
+impl Default for CallbackOptions {
+    fn default() -> Self {
+        Self {
+            queue_capacity: DEFAULT_CALLBACK_QUEUE_CAPACITY,
+        }
+    }
+}
+
+/// Registration handle returned by [`on_sample`].
+///
+/// Dropping the guard deregisters the callback: the forwarding task stops,
+/// which closes the underlying stream, and the callback thread exits after
+/// draining any queued samples.
+#[derive(Debug)]
+pub struct SampleCallbackGuard {
+    task: tokio::task::JoinHandle<()>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl SampleCallbackGuard {
+    /// Samples discarded because the callback queue was full.
+    #[must_use]
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Whether forwarding has stopped (stream ended or callback panicked).
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
+
+impl Drop for SampleCallbackGuard {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Deliver every item of `stream` to a synchronous `callback`.
+///
+/// For push-based integrations (audio callbacks, game loops) that cannot
+/// poll a `Stream`. A Tokio task forwards samples into a bounded queue and
+/// a dedicated thread runs `callback`, so a slow or blocking callback
+/// never stalls the async runtime or the client's reader loop.
+///
+/// Must be called from within a Tokio runtime.
+///
+/// ```no_run
+/// use emotiv_cortex_v2::{CortexClient, streams};
+///
+/// # async fn demo(client: &CortexClient, token: &str, session_id: &str) -> emotiv_cortex_v2::CortexResult<()> {
+/// let eeg = streams::subscribe_eeg(client, token, session_id, 5).await?;
+/// let guard = streams::on_sample(eeg, streams::CallbackOptions::default(), |sample| {
+///     println!("counter {}", sample.counter);
+/// })?;
+/// // ... later: stop delivery
+/// drop(guard);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns [`CortexError::Io`] if the callback thread cannot be spawned.
+pub fn on_sample<S, T, C>(
+    stream: S,
+    options: CallbackOptions,
+    mut callback: C,
+) -> CortexResult<SampleCallbackGuard>
+where
+    S: Stream<Item = T> + Send + 'static,
+    T: Send + 'static,
+    C: FnMut(T) + Send + 'static,
+{
+    let (tx, rx) = std::sync::mpsc::sync_channel::<T>(options.queue_capacity.max(1));
+    std::thread::Builder::new()
+        .name("cortex-sample-callback".into())
+        .spawn(move || {
+            while let Ok(sample) = rx.recv() {
+                callback(sample);
+            }
+        })?;
+
+    let dropped = Arc::new(AtomicU64::new(0));
+    let counter = Arc::clone(&dropped);
+    let task = tokio::spawn(async move {
+        let mut stream = Box::pin(stream);
+        while let Some(sample) = stream.next().await {
+            match tx.try_send(sample) {
+                Ok(()) => {}
+                Err(std::sync::mpsc::TrySendError::Full(_)) => {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }
+                // Callback thread is gone (it panicked); stop forwarding.
+                Err(std::sync::mpsc::TrySendError::Disconnected(_)) => break,
+            }
+        }
+    });
+
+    Ok(SampleCallbackGuard { task, dropped })
+}
+
 // ─── Helper ──────────────────────────────────────────────────────────────
 
+/// Turn a per-stream rejection in a `subscribe` result into an error.
+///
+/// The client has already dropped the rejected stream's channel.
+fn ensure_subscribed(result: &SubscriptionResult, stream: &str) -> CortexResult<()> {
+    match result.failure_for(stream) {
+        Some(failure) => Err(CortexError::from_api_error(
+            failure.code,
+            failure.message.clone(),
+        )),
+        None => Ok(()),
+    }
+}
+
 /// Create a stream channel on the client, returning a `ProtocolError` if the
 /// internal mutex is poisoned (should never happen in practice).
 fn add_channel(
@@ -194,15 +551,22 @@ pub async fn subscribe_eeg(
 ) -> CortexResult<Pin<Box<dyn Stream<Item = EegData> + Send>>> {
     let rx = add_channel(client, Streams::EEG)?;
 
-    client
+    let result = client
         .subscribe_streams(cortex_token, session_id, &[Streams::EEG])
         .await?;
+    ensure_subscribed(&result, Streams::EEG)?;
+    Ok(eeg_stream(rx, num_channels))
+}
 
-    Ok(Box::pin(TypedStream::new(rx, move |event| {
+fn eeg_stream(
+    rx: mpsc::Receiver<serde_json::Value>,
+    num_channels: usize,
+) -> Pin<Box<dyn Stream<Item = EegData> + Send>> {
+    Box::pin(TypedStream::new(rx, move |event| {
         let time = event.get("time")?.as_f64()?;
         let eeg_array = event.get("eeg")?.as_array()?;
         EegData::from_eeg_array(eeg_array, num_channels, time)
-    })))
+    }))
 }
 
 // ─── Device Quality Stream ───────────────────────────────────────────────
@@ -229,15 +593,22 @@ pub async fn subscribe_dev(
 ) -> CortexResult<Pin<Box<dyn Stream<Item = DeviceQuality> + Send>>> {
     let rx = add_channel(client, Streams::DEV)?;
 
-    client
+    let result = client
         .subscribe_streams(cortex_token, session_id, &[Streams::DEV])
         .await?;
+    ensure_subscribed(&result, Streams::DEV)?;
+    Ok(dev_stream(rx, num_channels))
+}
 
-    Ok(Box::pin(TypedStream::new(rx, move |event| {
+fn dev_stream(
+    rx: mpsc::Receiver<serde_json::Value>,
+    num_channels: usize,
+) -> Pin<Box<dyn Stream<Item = DeviceQuality> + Send>> {
+    Box::pin(TypedStream::new(rx, move |event| {
         let dev_array = event.get("dev")?.as_array()?;
         let dev_values: Vec<serde_json::Value> = dev_array.clone();
         DeviceQuality::from_dev_array(&dev_values, num_channels)
-    })))
+    }))
 }
 
 // ─── Motion Stream ───────────────────────────────────────────────────────
@@ -245,7 +616,9 @@ pub async fn subscribe_dev(
 /// Subscribe to the motion/IMU data stream.
 ///
 /// Returns a stream of [`MotionData`] containing accelerometer,
-/// magnetometer, and quaternion readings.
+/// magnetometer, and quaternion or gyroscope readings, per the
+/// [`MotionLayout`] named in the subscribe `cols` (or told from each
+/// array's length when Cortex omits them).
 ///
 /// # Errors
 /// Returns any error produced by stream channel registration or
@@ -257,14 +630,28 @@ pub async fn subscribe_motion(
 ) -> CortexResult<Pin<Box<dyn Stream<Item = MotionData> + Send>>> {
     let rx = add_channel(client, Streams::MOT)?;
 
-    client
+    let result = client
         .subscribe_streams(cortex_token, session_id, &[Streams::MOT])
         .await?;
+    ensure_subscribed(&result, Streams::MOT)?;
+    Ok(motion_stream(rx, &result))
+}
+
+fn motion_stream(
+    rx: mpsc::Receiver<serde_json::Value>,
+    result: &SubscriptionResult,
+) -> Pin<Box<dyn Stream<Item = MotionData> + Send>> {
+    let layout = result
+        .subscription(Streams::MOT)
+        .and_then(|s| MotionLayout::from_cols(&s.cols));
 
-    Ok(Box::pin(TypedStream::new(rx, |event| {
+    Box::pin(TypedStream::new(rx, move |event| {
         let mot_event: MotEvent = serde_json::from_value(event).ok()?;
-        MotionData::from_mot_array(&mot_event.mot, mot_event.time)
-    })))
+        match layout {
+            Some(layout) => MotionData::from_mot_array_with(&mot_event.mot, mot_event.time, layout),
+            None => MotionData::from_mot_array(&mot_event.mot, mot_event.time),
+        }
+    }))
 }
 
 // ─── EEG Quality Stream ─────────────────────────────────────────────────
@@ -288,14 +675,21 @@ pub async fn subscribe_eq(
 ) -> CortexResult<Pin<Box<dyn Stream<Item = EegQuality> + Send>>> {
     let rx = add_channel(client, Streams::EQ)?;
 
-    client
+    let result = client
         .subscribe_streams(cortex_token, session_id, &[Streams::EQ])
         .await?;
+    ensure_subscribed(&result, Streams::EQ)?;
+    Ok(eq_stream(rx, num_channels))
+}
 
-    Ok(Box::pin(TypedStream::new(rx, move |event| {
+fn eq_stream(
+    rx: mpsc::Receiver<serde_json::Value>,
+    num_channels: usize,
+) -> Pin<Box<dyn Stream<Item = EegQuality> + Send>> {
+    Box::pin(TypedStream::new(rx, move |event| {
         let eq_event: EqEvent = serde_json::from_value(event).ok()?;
         EegQuality::from_eq_array(&eq_event.eq, num_channels)
-    })))
+    }))
 }
 
 // ─── Band Power Stream ──────────────────────────────────────────────────
@@ -318,14 +712,21 @@ pub async fn subscribe_band_power(
 ) -> CortexResult<Pin<Box<dyn Stream<Item = BandPowerData> + Send>>> {
     let rx = add_channel(client, Streams::POW)?;
 
-    client
+    let result = client
         .subscribe_streams(cortex_token, session_id, &[Streams::POW])
         .await?;
+    ensure_subscribed(&result, Streams::POW)?;
+    Ok(band_power_stream(rx, num_channels))
+}
 
-    Ok(Box::pin(TypedStream::new(rx, move |event| {
+fn band_power_stream(
+    rx: mpsc::Receiver<serde_json::Value>,
+    num_channels: usize,
+) -> Pin<Box<dyn Stream<Item = BandPowerData> + Send>> {
+    Box::pin(TypedStream::new(rx, move |event| {
         let pow_event: PowEvent = serde_json::from_value(event).ok()?;
         BandPowerData::from_pow_array(&pow_event.pow, num_channels, pow_event.time)
-    })))
+    }))
 }
 
 // ─── Performance Metrics Stream ─────────────────────────────────────────
@@ -345,53 +746,30 @@ pub async fn subscribe_metrics(
 ) -> CortexResult<Pin<Box<dyn Stream<Item = PerformanceMetrics> + Send>>> {
     let rx = add_channel(client, Streams::MET)?;
 
-    let resp = client
+    let result = client
         .subscribe_streams(cortex_token, session_id, &[Streams::MET])
         .await?;
+    ensure_subscribed(&result, Streams::MET)?;
+    Ok(metrics_stream(rx, &result))
+}
 
-    let cols: Vec<String> = resp
-        .get("success")
-        .and_then(|s| s.as_array())
-        .and_then(|a| a.first())
-        .and_then(|entry| entry.get("cols"))
-        .and_then(|c| c.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|v| v.as_str().map(String::from))
-                .collect()
-        })
-        .unwrap_or_default();
-
-    let col_idx = |name: &str| cols.iter().position(|c| c == name);
-    let att_idx = col_idx("attention");
-    let eng_idx = col_idx("eng");
-    let exc_idx = col_idx("exc");
-    let lex_idx = col_idx("lex");
-    let str_idx = col_idx("str");
-    let rel_idx = col_idx("rel");
-    let int_idx = col_idx("int");
-    let foc_idx = col_idx("foc");
-
-    Ok(Box::pin(TypedStream::new(rx, move |event| {
+fn metrics_stream(
+    rx: mpsc::Receiver<serde_json::Value>,
+    result: &SubscriptionResult,
+) -> Pin<Box<dyn Stream<Item = PerformanceMetrics> + Send>> {
+    let map = result
+        .subscription(Streams::MET)
+        .filter(|sub| !sub.cols.is_empty())
+        .map(|sub| MetColumnMap::new(&sub.cols));
+
+    Box::pin(TypedStream::new(rx, move |event| {
         let met = event.get("met")?.as_array()?;
-        let val = |i: usize| -> Option<f32> {
-            met.get(i)
-                .and_then(serde_json::Value::as_f64)
-                .and_then(f64_to_f32)
-        };
         let time = event.get("time")?.as_f64()?;
-        Some(PerformanceMetrics {
-            timestamp: seconds_to_micros_i64(time)?,
-            attention: att_idx.and_then(&val),
-            engagement: eng_idx.and_then(&val),
-            excitement: exc_idx.and_then(&val),
-            long_excitement: lex_idx.and_then(&val),
-            stress: str_idx.and_then(&val),
-            relaxation: rel_idx.and_then(&val),
-            interest: int_idx.and_then(&val),
-            focus: foc_idx.and_then(&val),
-        })
-    })))
+        match &map {
+            Some(map) => map.parse(met, time),
+            None => MetColumnMap::for_len(met.len()).parse(met, time),
+        }
+    }))
 }
 
 // ─── Mental Command Stream ──────────────────────────────────────────────
@@ -411,16 +789,19 @@ pub async fn subscribe_mental_commands(
 ) -> CortexResult<Pin<Box<dyn Stream<Item = MentalCommand> + Send>>> {
     let rx = add_channel(client, Streams::COM)?;
 
-    client
+    let result = client
         .subscribe_streams(cortex_token, session_id, &[Streams::COM])
         .await?;
+    ensure_subscribed(&result, Streams::COM)?;
+    Ok(mental_command_stream(rx))
+}
 
-    Ok(Box::pin(TypedStream::new(rx, |event| {
-        let com = event.get("com")?.as_array()?;
-        let action = com.first()?.as_str()?.to_string();
-        let power = f64_to_f32(com.get(1)?.as_f64()?)?;
-        Some(MentalCommand { action, power })
-    })))
+fn mental_command_stream(
+    rx: mpsc::Receiver<serde_json::Value>,
+) -> Pin<Box<dyn Stream<Item = MentalCommand> + Send>> {
+    Box::pin(TypedStream::new(rx, |event| {
+        MentalCommand::from_com_array(event.get("com")?.as_array()?)
+    }))
 }
 
 // ─── Facial Expression Stream ───────────────────────────────────────────
@@ -440,25 +821,19 @@ pub async fn subscribe_facial_expressions(
 ) -> CortexResult<Pin<Box<dyn Stream<Item = FacialExpression> + Send>>> {
     let rx = add_channel(client, Streams::FAC)?;
 
-    client
+    let result = client
         .subscribe_streams(cortex_token, session_id, &[Streams::FAC])
         .await?;
+    ensure_subscribed(&result, Streams::FAC)?;
+    Ok(facial_expression_stream(rx))
+}
 
-    Ok(Box::pin(TypedStream::new(rx, |event| {
-        let fac = event.get("fac")?.as_array()?;
-        let eye_action = fac.first()?.as_str()?.to_string();
-        let upper_face_action = fac.get(1)?.as_str()?.to_string();
-        let upper_face_power = f64_to_f32(fac.get(2)?.as_f64()?)?;
-        let lower_face_action = fac.get(3)?.as_str()?.to_string();
-        let lower_face_power = f64_to_f32(fac.get(4)?.as_f64()?)?;
-        Some(FacialExpression {
-            eye_action,
-            upper_face_action,
-            upper_face_power,
-            lower_face_action,
-            lower_face_power,
-        })
-    })))
+fn facial_expression_stream(
+    rx: mpsc::Receiver<serde_json::Value>,
+) -> Pin<Box<dyn Stream<Item = FacialExpression> + Send>> {
+    Box::pin(TypedStream::new(rx, |event| {
+        FacialExpression::from_fac_array(event.get("fac")?.as_array()?)
+    }))
 }
 
 // ─── System Events Stream ───────────────────────────────────────────────
@@ -478,85 +853,1936 @@ pub async fn subscribe_sys(
 ) -> CortexResult<Pin<Box<dyn Stream<Item = SysEvent> + Send>>> {
     let rx = add_channel(client, Streams::SYS)?;
 
-    client
+    let result = client
         .subscribe_streams(cortex_token, session_id, &[Streams::SYS])
         .await?;
+    ensure_subscribed(&result, Streams::SYS)?;
+    Ok(sys_stream(rx))
+}
 
-    Ok(Box::pin(TypedStream::new(rx, |event| {
+fn sys_stream(
+    rx: mpsc::Receiver<serde_json::Value>,
+) -> Pin<Box<dyn Stream<Item = SysEvent> + Send>> {
+    Box::pin(TypedStream::new(rx, |event| {
         serde_json::from_value::<SysEvent>(event).ok()
-    })))
+    }))
 }
 
-// ─── Unsubscribe ─────────────────────────────────────────────────────────
+// ─── All Streams ─────────────────────────────────────────────────────────
 
-/// Unsubscribe from one or more data streams and remove the corresponding
-/// channels from the client.
+/// Typed receivers for every stream [`subscribe_all`] could subscribe.
+///
+/// A field is `None` when Cortex refused the stream; the reason is in
+/// [`Self::unsupported`].
+#[derive(Default)]
+pub struct AllStreams {
+    /// Raw EEG.
+    pub eeg: Option<Pin<Box<dyn Stream<Item = EegData> + Send>>>,
+    /// Device quality.
+    pub dev: Option<Pin<Box<dyn Stream<Item = DeviceQuality> + Send>>>,
+    /// Motion/IMU.
+    pub motion: Option<Pin<Box<dyn Stream<Item = MotionData> + Send>>>,
+    /// EEG quality.
+    pub eq: Option<Pin<Box<dyn Stream<Item = EegQuality> + Send>>>,
+    /// Band power.
+    pub band_power: Option<Pin<Box<dyn Stream<Item = BandPowerData> + Send>>>,
+    /// Performance metrics.
+    pub metrics: Option<Pin<Box<dyn Stream<Item = PerformanceMetrics> + Send>>>,
+    /// Mental commands.
+    pub mental_commands: Option<Pin<Box<dyn Stream<Item = MentalCommand> + Send>>>,
+    /// Facial expressions.
+    pub facial_expressions: Option<Pin<Box<dyn Stream<Item = FacialExpression> + Send>>>,
+    /// System events.
+    pub sys: Option<Pin<Box<dyn Stream<Item = SysEvent> + Send>>>,
+    /// Streams the headset or license does not support, with Cortex's
+    /// reason.
+    pub unsupported: Vec<StreamSubscriptionFailure>,
+}
+
+impl AllStreams {
+    /// Names of the streams that were subscribed, in [`Streams::ALL`]
+    /// order.
+    #[must_use]
+    pub fn subscribed(&self) -> Vec<&'static str> {
+        let present = [
+            self.eeg.is_some(),
+            self.dev.is_some(),
+            self.motion.is_some(),
+            self.eq.is_some(),
+            self.band_power.is_some(),
+            self.metrics.is_some(),
+            self.mental_commands.is_some(),
+            self.facial_expressions.is_some(),
+            self.sys.is_some(),
+        ];
+        Streams::ALL
+            .iter()
+            .zip(present)
+            .filter_map(|(stream, present)| present.then_some(*stream))
+            .collect()
+    }
+
+    /// Whether `stream` was subscribed.
+    #[must_use]
+    pub fn is_subscribed(&self, stream: &str) -> bool {
+        self.subscribed().contains(&stream)
+    }
+}
+
+/// Subscribe to every Cortex stream the headset and license support.
+///
+/// All streams are requested in one `subscribe` call. Streams Cortex
+/// accepts get a typed receiver in [`AllStreams`], sized for `model`;
+/// those it rejects (no license for raw EEG, no trained profile, ...)
+/// are listed in [`AllStreams::unsupported`] instead of failing the
+/// call.
 ///
 /// # Errors
-/// Returns any error produced by the Cortex `unsubscribe` RPC call.
-pub async fn unsubscribe(
+/// Returns any error produced by stream channel registration or the
+/// subscription RPC call itself.
+pub async fn subscribe_all(
     client: &CortexClient,
     cortex_token: &str,
     session_id: &str,
-    streams: &[&str],
-) -> CortexResult<()> {
-    client
-        .unsubscribe_streams(cortex_token, session_id, streams)
-        .await?;
-
-    for &stream in streams {
-        client.remove_stream_channel(stream);
+    model: &HeadsetModel,
+) -> CortexResult<AllStreams> {
+    let mut channels = HashMap::new();
+    for stream in Streams::ALL {
+        channels.insert(*stream, add_channel(client, stream)?);
     }
-
-    Ok(())
+    let result = match client
+        .subscribe_streams(cortex_token, session_id, Streams::ALL)
+        .await
+    {
+        Ok(result) => result,
+        Err(e) => {
+            for stream in Streams::ALL {
+                client.remove_stream_channel(stream);
+            }
+            return Err(e);
+        }
+    };
+
+    let mut all = AllStreams {
+        unsupported: result.failure.clone(),
+        ..AllStreams::default()
+    };
+    let mut accepted = |stream: &str| {
+        if result.subscription(stream).is_some() {
+            return channels.remove(stream);
+        }
+        if result.failure_for(stream).is_none() {
+            // Neither accepted nor refused: nothing will arrive on it.
+            client.remove_stream_channel(stream);
+            all.unsupported.push(StreamSubscriptionFailure {
+                stream_name: stream.to_string(),
+                code: ErrorCodes::INVALID_STREAM,
+                message: "missing from subscribe response".into(),
+            });
+        }
+        None
+    };
+
+    let num_channels = model.num_channels();
+    let eeg = accepted(Streams::EEG).map(|rx| eeg_stream(rx, num_channels));
+    let dev = accepted(Streams::DEV).map(|rx| dev_stream(rx, num_channels));
+    let motion = accepted(Streams::MOT).map(|rx| motion_stream(rx, &result));
+    let eq = accepted(Streams::EQ).map(|rx| eq_stream(rx, num_channels));
+    let band_power = accepted(Streams::POW).map(|rx| band_power_stream(rx, num_channels));
+    let metrics = accepted(Streams::MET).map(|rx| metrics_stream(rx, &result));
+    let mental_commands = accepted(Streams::COM).map(mental_command_stream);
+    let facial_expressions = accepted(Streams::FAC).map(facial_expression_stream);
+    let sys = accepted(Streams::SYS).map(sys_stream);
+
+    if !all.unsupported.is_empty() {
+        tracing::info!(
+            session_id,
+            unsupported = ?all.unsupported.iter().map(|f| f.stream_name.as_str()).collect::<Vec<_>>(),
+            "Some streams are not available for this headset or license"
+        );
+    }
+    Ok(AllStreams {
+        eeg,
+        dev,
+        motion,
+        eq,
+        band_power,
+        metrics,
+        mental_commands,
+        facial_expressions,
+        sys,
+        ..all
+    })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use futures_util::StreamExt;
+// ─── Schema Tracking ─────────────────────────────────────────────────────
 
-    #[tokio::test]
-    async fn test_typed_stream_parses_valid_events() {
-        let (tx, rx) = mpsc::channel(16);
+/// Column layout of a subscribed stream.
+///
+/// Starts from the `cols` Cortex returned in the `subscribe` response and
+/// is refreshed from the observed array layout when it shifts mid-session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StreamSchema {
+    /// Cortex stream name (`"eeg"`, `"pow"`, ...).
+    pub stream: String,
+    /// Column labels, one per element of the stream's data array. Labels
+    /// the crate had to infer after a layout shift are named `col<N>`.
+    pub cols: Vec<String>,
+}
 
-        let mut stream =
-            TypedStream::new(rx, |event| event.get("value")?.as_i64().map(|v| v as i32));
+impl StreamSchema {
+    /// Schema for `stream` as reported in a `subscribe` result.
+    #[must_use]
+    pub fn from_subscription(result: &SubscriptionResult, stream: &str) -> Self {
+        Self {
+            stream: stream.to_string(),
+            cols: result
+                .subscription(stream)
+                .map(|s| s.cols.clone())
+                .unwrap_or_default(),
+        }
+    }
 
-        tx.send(serde_json::json!({"value": 42})).await.unwrap();
-        tx.send(serde_json::json!({"value": 99})).await.unwrap();
-        drop(tx);
+    /// Number of columns.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cols.len()
+    }
 
-        assert_eq!(stream.next().await, Some(42));
-        assert_eq!(stream.next().await, Some(99));
-        assert_eq!(stream.next().await, None);
+    /// Whether no columns are known (e.g. Cortex omitted `cols`).
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cols.is_empty()
     }
 
-    #[tokio::test]
-    async fn test_typed_stream_skips_unparseable_events() {
-        let (tx, rx) = mpsc::channel(16);
+    /// Re-derive the schema for an array of `len` elements: the trailing
+    /// `suffix` labels stay anchored to the end, all others are reused
+    /// positionally.
+    fn resized(&self, len: usize, suffix: usize) -> Self {
+        let label = |i: usize| format!("col{i}");
+        let old_suffix_start = self.cols.len().saturating_sub(suffix);
+        let new_suffix_start = len.saturating_sub(suffix);
+        let cols = (0..len)
+            .map(|i| {
+                let old = if i >= new_suffix_start {
+                    self.cols.get(old_suffix_start + (i - new_suffix_start))
+                } else if i < old_suffix_start {
+                    self.cols.get(i)
+                } else {
+                    None
+                };
+                old.cloned().unwrap_or_else(|| label(i))
+            })
+            .collect();
+        Self {
+            stream: self.stream.clone(),
+            cols,
+        }
+    }
+}
 
-        let mut stream =
-            TypedStream::new(rx, |event| event.get("value")?.as_i64().map(|v| v as i32));
+/// Item yielded by schema-tracked streams such as
+/// [`subscribe_eeg_tracked`].
+#[derive(Debug, Clone)]
+pub enum StreamItem<T> {
+    /// A sample parsed with the current schema.
+    Sample(T),
+    /// The stream's array layout changed (e.g. a headset mode switch).
+    /// Samples after this item are parsed with `current`.
+    SchemaChanged {
+        /// Layout in effect before the change.
+        previous: StreamSchema,
+        /// Layout observed in the latest event.
+        current: StreamSchema,
+    },
+}
 
-        tx.send(serde_json::json!({"bad": "data"})).await.unwrap();
-        tx.send(serde_json::json!({"value": "not_a_number"}))
-            .await
-            .unwrap();
-        tx.send(serde_json::json!({"value": 7})).await.unwrap();
-        drop(tx);
+/// Stream adapter that parses events against the stream's current
+/// [`StreamSchema`] and yields [`StreamItem::SchemaChanged`] when the
+/// array layout shifts, instead of misparsing the new layout.
+///
+/// Channel counts are derived from each event's array length, so a
+/// layout shift is never silently parsed with the old channel count.
+pub struct SchemaTracked<T> {
+    rx: mpsc::Receiver<serde_json::Value>,
+    schema: StreamSchema,
+    /// Array length of the last event, or of the subscribe `cols`.
+    expected_len: Option<usize>,
+    /// Fixed columns after the per-channel block.
+    suffix: usize,
+    parse: fn(&[serde_json::Value], f64) -> Option<T>,
+    pending: Option<T>,
+}
 
-        // The first two events should be skipped
-        assert_eq!(stream.next().await, Some(7));
-        assert_eq!(stream.next().await, None);
+impl<T> SchemaTracked<T> {
+    /// Current schema.
+    #[must_use]
+    pub fn schema(&self) -> &StreamSchema {
+        &self.schema
     }
 
-    #[tokio::test]
-    async fn test_typed_stream_ends_when_sender_dropped() {
-        let (tx, rx) = mpsc::channel(16);
-        let mut stream = TypedStream::new(rx, |event| event.get("v")?.as_i64().map(|v| v as i32));
-
-        drop(tx);
-        assert_eq!(stream.next().await, None);
+    fn handle(&mut self, event: &serde_json::Value) -> Option<StreamItem<T>> {
+        let time = event.get("time")?.as_f64()?;
+        let array = event.get(self.schema.stream.as_str())?.as_array()?;
+        let sample = (self.parse)(array, time);
+
+        match self.expected_len {
+            Some(len) if len != array.len() => {
+                let previous = self.schema.clone();
+                self.schema = previous.resized(array.len(), self.suffix);
+                self.expected_len = Some(array.len());
+                tracing::warn!(
+                    stream = %self.schema.stream,
+                    previous = len,
+                    current = array.len(),
+                    "Stream layout changed mid-session"
+                );
+                self.pending = sample;
+                Some(StreamItem::SchemaChanged {
+                    previous,
+                    current: self.schema.clone(),
+                })
+            }
+            Some(_) => sample.map(StreamItem::Sample),
+            None => {
+                // Cortex gave no `cols`: adopt the first observed layout.
+                self.expected_len = Some(array.len());
+                self.schema = self.schema.resized(array.len(), self.suffix);
+                sample.map(StreamItem::Sample)
+            }
+        }
+    }
+}
+
+impl<T: Unpin> Stream for SchemaTracked<T> {
+    type Item = StreamItem<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if let Some(sample) = this.pending.take() {
+            return Poll::Ready(Some(StreamItem::Sample(sample)));
+        }
+        loop {
+            match this.rx.poll_recv(cx) {
+                Poll::Ready(Some(event)) => {
+                    if let Some(item) = this.handle(&event) {
+                        return Poll::Ready(Some(item));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+async fn subscribe_tracked<T>(
+    client: &CortexClient,
+    cortex_token: &str,
+    session_id: &str,
+    stream: &'static str,
+    suffix: usize,
+    parse: fn(&[serde_json::Value], f64) -> Option<T>,
+) -> CortexResult<SchemaTracked<T>> {
+    let rx = add_channel(client, stream)?;
+
+    let result = client
+        .subscribe_streams(cortex_token, session_id, &[stream])
+        .await?;
+    ensure_subscribed(&result, stream)?;
+
+    let schema = StreamSchema::from_subscription(&result, stream);
+    Ok(SchemaTracked {
+        rx,
+        expected_len: (!schema.is_empty()).then(|| schema.len()),
+        schema,
+        suffix,
+        parse,
+        pending: None,
+    })
+}
+
+/// Subscribe to the EEG stream with layout-change detection.
+///
+/// Unlike [`subscribe_eeg`] no channel count is needed: it is derived
+/// from each event, and a mid-session layout shift yields
+/// [`StreamItem::SchemaChanged`] before the first sample in the new
+/// layout.
+///
+/// # Errors
+/// Returns any error produced by stream channel registration or
+/// subscription RPC calls.
+pub async fn subscribe_eeg_tracked(
+    client: &CortexClient,
+    cortex_token: &str,
+    session_id: &str,
+) -> CortexResult<SchemaTracked<EegData>> {
+    // COUNTER, INTERPOLATED | channels | RAW_CQ, MARKER_HARDWARE, MARKERS
+    subscribe_tracked(
+        client,
+        cortex_token,
+        session_id,
+        Streams::EEG,
+        3,
+        |eeg, time| EegData::from_eeg_array(eeg, eeg.len().checked_sub(5)?, time),
+    )
+    .await
+}
+
+/// Subscribe to the EEG quality stream with layout-change detection.
+///
+/// See [`subscribe_eeg_tracked`].
+///
+/// # Errors
+/// Returns any error produced by stream channel registration or
+/// subscription RPC calls.
+pub async fn subscribe_eq_tracked(
+    client: &CortexClient,
+    cortex_token: &str,
+    session_id: &str,
+) -> CortexResult<SchemaTracked<EegQuality>> {
+    // batteryPercent, overall, sampleRateQuality | channels
+    subscribe_tracked(client, cortex_token, session_id, Streams::EQ, 0, |eq, _| {
+        EegQuality::from_eq_array(eq, eq.len().checked_sub(3)?)
+    })
+    .await
+}
+
+/// Subscribe to the band power stream with layout-change detection.
+///
+/// See [`subscribe_eeg_tracked`].
+///
+/// # Errors
+/// Returns any error produced by stream channel registration or
+/// subscription RPC calls.
+pub async fn subscribe_band_power_tracked(
+    client: &CortexClient,
+    cortex_token: &str,
+    session_id: &str,
+) -> CortexResult<SchemaTracked<BandPowerData>> {
+    subscribe_tracked(
+        client,
+        cortex_token,
+        session_id,
+        Streams::POW,
+        0,
+        |pow, time| {
+            let pow: Vec<f64> = pow
+                .iter()
+                .map(serde_json::Value::as_f64)
+                .collect::<Option<_>>()?;
+            if pow.len() % 5 != 0 {
+                return None;
+            }
+            BandPowerData::from_pow_array(&pow, pow.len() / 5, time)
+        },
+    )
+    .await
+}
+
+// ─── Sample Trait ────────────────────────────────────────────────────────
+
+/// Common view over the typed sample structs, so generic consumers
+/// (recorders, forwarders, statistics) can be written once.
+///
+/// Only [`EegData`], [`MotionData`], [`BandPowerData`], and
+/// [`PerformanceMetrics`] keep the sample time, and only [`SysEvent`]
+/// keeps the session ID; the other types return `None`.
+pub trait StreamSample {
+    /// Cortex stream name (one of the [`Streams`] constants).
+    fn kind(&self) -> &'static str;
+
+    /// Sample time in microseconds since the Unix epoch.
+    fn timestamp(&self) -> Option<i64> {
+        None
+    }
+
+    /// Session the sample belongs to.
+    fn session_id(&self) -> Option<&str> {
+        None
+    }
+
+    /// Per-channel values as one flat slice, for types that are a plain
+    /// array of channel values.
+    fn as_f32_slice(&self) -> Option<&[f32]> {
+        None
+    }
+
+    /// Headset sample counter, for types that carry one.
+    fn counter(&self) -> Option<u32> {
+        None
+    }
+}
+
+/// Convert a [`StreamSample::timestamp`] (or a difference of two) to
+/// seconds.
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn micros_to_secs(micros: i64) -> f64 {
+    micros as f64 / 1_000_000.0
+}
+
+impl StreamSample for EegData {
+    fn kind(&self) -> &'static str {
+        Streams::EEG
+    }
+
+    fn timestamp(&self) -> Option<i64> {
+        Some(self.timestamp)
+    }
+
+    fn counter(&self) -> Option<u32> {
+        Some(self.counter)
+    }
+
+    fn as_f32_slice(&self) -> Option<&[f32]> {
+        Some(&self.channels)
+    }
+}
+
+impl StreamSample for DeviceQuality {
+    fn kind(&self) -> &'static str {
+        Streams::DEV
+    }
+
+    fn as_f32_slice(&self) -> Option<&[f32]> {
+        Some(&self.channel_quality)
+    }
+}
+
+impl StreamSample for MotionData {
+    fn kind(&self) -> &'static str {
+        Streams::MOT
+    }
+
+    fn timestamp(&self) -> Option<i64> {
+        Some(self.timestamp)
+    }
+}
+
+impl StreamSample for EegQuality {
+    fn kind(&self) -> &'static str {
+        Streams::EQ
+    }
+
+    fn as_f32_slice(&self) -> Option<&[f32]> {
+        Some(&self.sensor_quality)
+    }
+}
+
+impl StreamSample for BandPowerData {
+    fn kind(&self) -> &'static str {
+        Streams::POW
+    }
+
+    fn timestamp(&self) -> Option<i64> {
+        Some(self.timestamp)
+    }
+
+    /// Channel-major: five bands (theta, alpha, betaL, betaH, gamma) per
+    /// channel.
+    fn as_f32_slice(&self) -> Option<&[f32]> {
+        Some(self.channel_powers.as_flattened())
+    }
+}
+
+impl StreamSample for PerformanceMetrics {
+    fn kind(&self) -> &'static str {
+        Streams::MET
+    }
+
+    fn timestamp(&self) -> Option<i64> {
+        Some(self.timestamp)
+    }
+}
+
+impl StreamSample for MentalCommand {
+    fn kind(&self) -> &'static str {
+        Streams::COM
+    }
+}
+
+impl StreamSample for FacialExpression {
+    fn kind(&self) -> &'static str {
+        Streams::FAC
+    }
+}
+
+impl StreamSample for SysEvent {
+    fn kind(&self) -> &'static str {
+        Streams::SYS
+    }
+
+    fn timestamp(&self) -> Option<i64> {
+        seconds_to_micros_i64(self.time)
+    }
+
+    fn session_id(&self) -> Option<&str> {
+        Some(&self.sid)
+    }
+}
+
+impl ParsedSample {
+    fn as_sample(&self) -> &dyn StreamSample {
+        match self {
+            ParsedSample::Eeg(s) => s,
+            ParsedSample::Dev(s) => s,
+            ParsedSample::Mot(s) => s,
+            ParsedSample::Eq(s) => s,
+            ParsedSample::Pow(s) => s,
+            ParsedSample::Met(s) => s,
+            ParsedSample::Com(s) => s,
+            ParsedSample::Fac(s) => s,
+            ParsedSample::Sys(s) => s,
+        }
+    }
+}
+
+impl StreamSample for ParsedSample {
+    fn kind(&self) -> &'static str {
+        self.as_sample().kind()
+    }
+
+    fn timestamp(&self) -> Option<i64> {
+        self.as_sample().timestamp()
+    }
+
+    fn session_id(&self) -> Option<&str> {
+        self.as_sample().session_id()
+    }
+
+    fn as_f32_slice(&self) -> Option<&[f32]> {
+        self.as_sample().as_f32_slice()
+    }
+
+    fn counter(&self) -> Option<u32> {
+        self.as_sample().counter()
+    }
+}
+
+// ─── Sample Parsing ──────────────────────────────────────────────────────
+
+/// A single stream message parsed by [`parse_sample`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "stream", content = "sample", rename_all = "lowercase")]
+pub enum ParsedSample {
+    /// `eeg` message.
+    Eeg(EegData),
+    /// `dev` message.
+    Dev(DeviceQuality),
+    /// `mot` message.
+    Mot(MotionData),
+    /// `eq` message.
+    Eq(EegQuality),
+    /// `pow` message.
+    Pow(BandPowerData),
+    /// `met` message.
+    Met(PerformanceMetrics),
+    /// `com` message.
+    Com(MentalCommand),
+    /// `fac` message.
+    Fac(FacialExpression),
+    /// `sys` message.
+    Sys(SysEvent),
+}
+
+/// Parse one captured stream message with the same parsers the
+/// `subscribe_*` helpers use.
+///
+/// `kind` is the Cortex stream name (`"eeg"`, `"dev"`, ...) and `json` the
+/// raw message text. Because there is no `subscribe` context, channel
+/// counts are inferred from the documented array layouts (e.g. an `eeg`
+/// array holds 5 non-channel columns) and `met` columns fall back to the
+/// default layouts (see [`MetColumnMap::for_len`]).
+///
+/// ```
+/// use emotiv_cortex_v2::streams::{ParsedSample, parse_sample};
+///
+/// let msg = r#"{"com":["push",0.62],"sid":"s","time":1712345678.0}"#;
+/// let ParsedSample::Com(cmd) = parse_sample("com", msg).unwrap() else {
+///     panic!("expected a mental command");
+/// };
+/// assert_eq!(cmd.action, "push");
+/// ```
+///
+/// # Errors
+/// Returns [`CortexError::ProtocolError`] if the text is not JSON, `kind`
+/// is not a Cortex stream, or the payload does not match the stream's
+/// layout.
+pub fn parse_sample(kind: &str, json: &str) -> CortexResult<ParsedSample> {
+    let event: serde_json::Value = serde_json::from_str(json)?;
+    parse_sample_value(kind, &event).ok_or_else(|| CortexError::ProtocolError {
+        reason: format!("'{kind}' sample does not match the expected layout: {json}"),
+    })
+}
+
+pub(crate) fn parse_sample_value(kind: &str, event: &serde_json::Value) -> Option<ParsedSample> {
+    let time = || event.get("time")?.as_f64();
+    let array = |key: &str| event.get(key)?.as_array();
+    let floats = |key: &str| -> Option<Vec<f64>> {
+        array(key)?.iter().map(serde_json::Value::as_f64).collect()
+    };
+
+    let sample = match kind {
+        Streams::EEG => {
+            let eeg = array("eeg")?;
+            let num_channels = eeg.len().checked_sub(5)?;
+            ParsedSample::Eeg(EegData::from_eeg_array(eeg, num_channels, time()?)?)
+        }
+        Streams::DEV => {
+            let dev = array("dev")?;
+            let num_channels = dev.get(2)?.as_array()?.len().checked_sub(1)?;
+            ParsedSample::Dev(DeviceQuality::from_dev_array(dev, num_channels)?)
+        }
+        Streams::MOT => ParsedSample::Mot(MotionData::from_mot_array(&floats("mot")?, time()?)?),
+        Streams::EQ => {
+            let eq = array("eq")?;
+            let num_channels = eq.len().checked_sub(3)?;
+            ParsedSample::Eq(EegQuality::from_eq_array(eq, num_channels)?)
+        }
+        Streams::POW => {
+            let pow = floats("pow")?;
+            if pow.len() % 5 != 0 {
+                return None;
+            }
+            ParsedSample::Pow(BandPowerData::from_pow_array(&pow, pow.len() / 5, time()?)?)
+        }
+        Streams::MET => {
+            let met = array("met")?;
+            ParsedSample::Met(MetColumnMap::for_len(met.len()).parse(met, time()?)?)
+        }
+        Streams::COM => ParsedSample::Com(MentalCommand::from_com_array(array("com")?)?),
+        Streams::FAC => ParsedSample::Fac(FacialExpression::from_fac_array(array("fac")?)?),
+        Streams::SYS => ParsedSample::Sys(serde_json::from_value(event.clone()).ok()?),
+        _ => return None,
+    };
+    Some(sample)
+}
+
+// ─── Decimation ──────────────────────────────────────────────────────────
+
+/// Samples that can be collapsed into one by a boxcar (block-mean) decimator.
+pub trait Decimate: Sized {
+    /// Combine a full block of consecutive samples into one.
+    ///
+    /// Returns `None` when the block cannot be combined (e.g. the channel
+    /// count changed mid-block); the block is then dropped.
+    fn boxcar_mean(block: &[Self]) -> Option<Self>;
+}
+
+impl Decimate for EegData {
+    /// Channel values are averaged. `timestamp` and `counter` are taken
+    /// from the last sample, `interpolated` is set if any sample was
+    /// interpolated, and `raw_cq` is the block minimum.
+    fn boxcar_mean(block: &[Self]) -> Option<Self> {
+        let last = block.last()?;
+        let num_channels = last.channels.len();
+        let mut sums = vec![0.0_f64; num_channels];
+        for sample in block {
+            if sample.channels.len() != num_channels {
+                return None;
+            }
+            for (sum, value) in sums.iter_mut().zip(&sample.channels) {
+                *sum += f64::from(*value);
+            }
+        }
+        let n = f64::from(u32::try_from(block.len()).ok()?);
+
+        Some(Self {
+            timestamp: last.timestamp,
+            counter: last.counter,
+            interpolated: block.iter().any(|s| s.interpolated),
+            channels: sums
+                .into_iter()
+                .map(|sum| f64_to_f32(sum / n))
+                .collect::<Option<Vec<_>>>()?,
+            raw_cq: block.iter().map(|s| s.raw_cq).fold(f32::INFINITY, f32::min),
+        })
+    }
+}
+
+impl Decimate for MotionData {
+    /// Accelerometer, magnetometer, and gyroscope axes are averaged;
+    /// `timestamp` and `quaternion` are taken from the last sample
+    /// (averaging quaternion components does not yield a valid rotation).
+    fn boxcar_mean(block: &[Self]) -> Option<Self> {
+        let last = block.last()?;
+        let n = f64::from(u32::try_from(block.len()).ok()?);
+        let mean_axes = |axes: fn(&MotionData) -> [f32; 3]| -> Option<[f32; 3]> {
+            let mut sums = [0.0_f64; 3];
+            for sample in block {
+                for (sum, value) in sums.iter_mut().zip(axes(sample)) {
+                    *sum += f64::from(value);
+                }
+            }
+            Some([
+                f64_to_f32(sums[0] / n)?,
+                f64_to_f32(sums[1] / n)?,
+                f64_to_f32(sums[2] / n)?,
+            ])
+        };
+
+        Some(Self {
+            timestamp: last.timestamp,
+            quaternion: last.quaternion,
+            gyroscope: match last.gyroscope {
+                Some(_) if block.iter().all(|m| m.gyroscope.is_some()) => {
+                    Some(mean_axes(|m| m.gyroscope.unwrap_or_default())?)
+                }
+                // The layout changed mid-block.
+                Some(_) => return None,
+                None => None,
+            },
+            accelerometer: mean_axes(|m| m.accelerometer)?,
+            magnetometer: mean_axes(|m| m.magnetometer)?,
+        })
+    }
+}
+
+/// Boxcar decimator: emits the mean of every `factor` consecutive samples.
+///
+/// A boxcar average is a cheap low-pass filter, not a proper
+/// anti-aliasing filter. Its frequency response is a sinc with the first
+/// null at `rate / factor`, so content between `rate / (2 * factor)` and
+/// that null is only partially attenuated and can fold back into the
+/// decimated band. That is fine for rendering at display rates; use the
+/// full-rate stream for spectral analysis.
+#[derive(Debug, Clone)]
+pub struct BoxcarDecimator<T> {
+    factor: usize,
+    block: Vec<T>,
+}
+
+impl<T: Decimate> BoxcarDecimator<T> {
+    /// Create a decimator reducing the sample rate by `factor`
+    /// (a factor of 0 is treated as 1, i.e. pass-through).
+    #[must_use]
+    pub fn new(factor: usize) -> Self {
+        let factor = factor.max(1);
+        Self {
+            factor,
+            block: Vec::with_capacity(factor),
+        }
+    }
+
+    /// Decimation factor.
+    #[must_use]
+    pub fn factor(&self) -> usize {
+        self.factor
+    }
+
+    /// Feed one sample; returns the block mean once `factor` samples
+    /// have accumulated.
+    pub fn push(&mut self, sample: T) -> Option<T> {
+        self.block.push(sample);
+        if self.block.len() < self.factor {
+            return None;
+        }
+        let output = T::boxcar_mean(&self.block);
+        self.block.clear();
+        output
+    }
+}
+
+/// Stream adapter applying a [`BoxcarDecimator`] to an inner stream.
+///
+/// A trailing partial block is discarded when the inner stream ends.
+pub struct Decimated<S, T> {
+    inner: S,
+    decimator: BoxcarDecimator<T>,
+}
+
+impl<S, T> Decimated<S, T>
+where
+    S: Stream<Item = T> + Unpin,
+    T: Decimate,
+{
+    /// Wrap `inner`, emitting one sample per `factor` input samples.
+    pub fn new(inner: S, factor: usize) -> Self {
+        Self {
+            inner,
+            decimator: BoxcarDecimator::new(factor),
+        }
+    }
+}
+
+impl<S, T> Stream for Decimated<S, T>
+where
+    S: Stream<Item = T> + Unpin,
+    T: Decimate + Unpin,
+{
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match Pin::new(&mut self.inner).poll_next(cx) {
+                Poll::Ready(Some(sample)) => {
+                    if let Some(output) = self.decimator.push(sample) {
+                        return Poll::Ready(Some(output));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// Subscribe to the EEG stream and deliver one block-averaged sample per
+/// `factor` raw samples.
+///
+/// Intended for UI consumers that render at ~30 fps: an Insight at
+/// 128 Hz with `factor = 4` delivers 32 samples/s. See
+/// [`BoxcarDecimator`] for the anti-aliasing caveats.
+///
+/// # Errors
+/// Returns any error produced by stream channel registration or
+/// subscription RPC calls.
+pub async fn subscribe_eeg_decimated(
+    client: &CortexClient,
+    cortex_token: &str,
+    session_id: &str,
+    num_channels: usize,
+    factor: usize,
+) -> CortexResult<Pin<Box<dyn Stream<Item = EegData> + Send>>> {
+    let stream = subscribe_eeg(client, cortex_token, session_id, num_channels).await?;
+    Ok(Box::pin(Decimated::new(stream, factor)))
+}
+
+/// Subscribe to the band power stream and deliver exponentially smoothed
+/// and relative band power.
+///
+/// See [`BandPowerSmoother`] for the smoothing.
+///
+/// # Errors
+/// Returns any error produced by stream channel registration or
+/// subscription RPC calls.
+pub async fn subscribe_relative_band_power(
+    client: &CortexClient,
+    cortex_token: &str,
+    session_id: &str,
+    num_channels: usize,
+    smoother: BandPowerSmoother,
+) -> CortexResult<Pin<Box<dyn Stream<Item = RelativeBandPower> + Send>>> {
+    let stream = subscribe_band_power(client, cortex_token, session_id, num_channels).await?;
+    Ok(Box::pin(SmoothedBandPower::new(stream, smoother)))
+}
+
+/// Subscribe to the motion stream and deliver one block-averaged sample
+/// per `factor` raw samples.
+///
+/// See [`BoxcarDecimator`] and [`Decimate`] for how samples are combined.
+///
+/// # Errors
+/// Returns any error produced by stream channel registration or
+/// subscription RPC calls.
+pub async fn subscribe_motion_decimated(
+    client: &CortexClient,
+    cortex_token: &str,
+    session_id: &str,
+    factor: usize,
+) -> CortexResult<Pin<Box<dyn Stream<Item = MotionData> + Send>>> {
+    let stream = subscribe_motion(client, cortex_token, session_id).await?;
+    Ok(Box::pin(Decimated::new(stream, factor)))
+}
+
+// ─── Markers ─────────────────────────────────────────────────────────────
+
+/// A marker to render inline with a data stream, as sent to
+/// [`MarkerStreamExt::with_markers`].
+///
+/// Typically built right after [`CortexClient::inject_marker`]; note that
+/// `timestamp` is in microseconds like [`StreamSample::timestamp`], while
+/// the `time` passed to `inject_marker` is in milliseconds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StreamMarker {
+    /// Marker time in microseconds since the Unix epoch.
+    pub timestamp: i64,
+    /// Marker label.
+    pub label: String,
+    /// Marker value.
+    pub value: i32,
+    /// Marker port (source).
+    pub port: String,
+}
+
+/// Item yielded by [`WithMarkers`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Marked<T> {
+    /// A sample from the data stream.
+    Data(T),
+    /// A marker, placed before the first sample at or after its time.
+    Marker(StreamMarker),
+}
+
+/// Stream adapter that interleaves [`StreamMarker`]s into a data stream
+/// in timestamp order.
+///
+/// A marker is held until a sample at or after its time arrives, then
+/// yielded just before that sample. Markers that arrive after the data
+/// has already passed their time are yielded immediately, and markers
+/// still held when the data stream ends are yielded before it ends.
+/// Samples without a timestamp are passed through as they arrive.
+pub struct WithMarkers<S, T> {
+    inner: S,
+    markers: Option<mpsc::Receiver<StreamMarker>>,
+    /// Markers waiting for the data to reach them, in timestamp order.
+    held: VecDeque<StreamMarker>,
+    ready: VecDeque<Marked<T>>,
+    last_timestamp: Option<i64>,
+    inner_done: bool,
+}
+
+impl<S, T> WithMarkers<S, T>
+where
+    S: Stream<Item = T> + Unpin,
+    T: StreamSample,
+{
+    /// Wrap `inner`, interleaving the markers received on `markers`.
+    pub fn new(inner: S, markers: mpsc::Receiver<StreamMarker>) -> Self {
+        Self {
+            inner,
+            markers: Some(markers),
+            held: VecDeque::new(),
+            ready: VecDeque::new(),
+            last_timestamp: None,
+            inner_done: false,
+        }
+    }
+
+    fn hold(&mut self, marker: StreamMarker) {
+        if self
+            .last_timestamp
+            .is_some_and(|last| marker.timestamp <= last)
+        {
+            self.ready.push_back(Marked::Marker(marker));
+            return;
+        }
+        let at = self
+            .held
+            .partition_point(|held| held.timestamp <= marker.timestamp);
+        self.held.insert(at, marker);
+    }
+
+    fn push_sample(&mut self, sample: T) {
+        if let Some(timestamp) = sample.timestamp() {
+            while self
+                .held
+                .front()
+                .is_some_and(|marker| marker.timestamp <= timestamp)
+            {
+                if let Some(marker) = self.held.pop_front() {
+                    self.ready.push_back(Marked::Marker(marker));
+                }
+            }
+            self.last_timestamp = Some(timestamp);
+        }
+        self.ready.push_back(Marked::Data(sample));
+    }
+}
+
+impl<S, T> Stream for WithMarkers<S, T>
+where
+    S: Stream<Item = T> + Unpin,
+    T: StreamSample + Unpin,
+{
+    type Item = Marked<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(item) = self.ready.pop_front() {
+                return Poll::Ready(Some(item));
+            }
+            while let Some(markers) = self.markers.as_mut() {
+                match markers.poll_recv(cx) {
+                    Poll::Ready(Some(marker)) => self.hold(marker),
+                    Poll::Ready(None) => self.markers = None,
+                    Poll::Pending => break,
+                }
+            }
+            if !self.ready.is_empty() {
+                continue;
+            }
+            if self.inner_done {
+                return Poll::Ready(self.held.pop_front().map(Marked::Marker));
+            }
+            match Pin::new(&mut self.inner).poll_next(cx) {
+                Poll::Ready(Some(sample)) => self.push_sample(sample),
+                Poll::Ready(None) => self.inner_done = true,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// Adds [`with_markers`](Self::with_markers) to every typed sample stream.
+pub trait MarkerStreamExt: Stream + Sized {
+    /// Interleave the markers received on `markers` into this stream in
+    /// timestamp order. See [`WithMarkers`].
+    ///
+    /// ```
+    /// use emotiv_cortex_v2::protocol::streams::EegData;
+    /// use emotiv_cortex_v2::streams::{Marked, MarkerStreamExt, StreamMarker};
+    /// use futures_util::{StreamExt, stream};
+    /// use tokio::sync::mpsc;
+    ///
+    /// # let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    /// # rt.block_on(async {
+    /// let sample = |timestamp| EegData {
+    ///     timestamp,
+    ///     counter: 0,
+    ///     interpolated: false,
+    ///     channels: vec![0.0; 5],
+    ///     raw_cq: 4.0,
+    /// };
+    /// let (marker_tx, marker_rx) = mpsc::channel(8);
+    /// marker_tx
+    ///     .send(StreamMarker {
+    ///         timestamp: 1_500,
+    ///         label: "stimulus".into(),
+    ///         value: 1,
+    ///         port: "psychopy".into(),
+    ///     })
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// let items: Vec<_> = stream::iter([sample(1_000), sample(2_000)])
+    ///     .with_markers(marker_rx)
+    ///     .collect()
+    ///     .await;
+    /// assert!(matches!(&items[1], Marked::Marker(m) if m.label == "stimulus"));
+    /// assert!(matches!(&items[2], Marked::Data(eeg) if eeg.timestamp == 2_000));
+    /// # });
+    /// ```
+    fn with_markers(self, markers: mpsc::Receiver<StreamMarker>) -> WithMarkers<Self, Self::Item>
+    where
+        Self: Unpin,
+        Self::Item: StreamSample,
+    {
+        WithMarkers::new(self, markers)
+    }
+}
+
+impl<S: Stream> MarkerStreamExt for S {}
+
+// ─── Combinators ─────────────────────────────────────────────────────────
+
+macro_rules! parsed_sample_from {
+    ($($variant:ident($ty:ty)),* $(,)?) => {
+        $(
+            impl From<$ty> for ParsedSample {
+                fn from(sample: $ty) -> Self {
+                    ParsedSample::$variant(sample)
+                }
+            }
+        )*
+    };
+}
+
+parsed_sample_from!(
+    Eeg(EegData),
+    Dev(DeviceQuality),
+    Mot(MotionData),
+    Eq(EegQuality),
+    Pow(BandPowerData),
+    Met(PerformanceMetrics),
+    Com(MentalCommand),
+    Fac(FacialExpression),
+    Sys(SysEvent),
+);
+
+/// Several typed streams merged into one stream of [`ParsedSample`]s,
+/// built by [`SampleStreamExt::merge`] and [`Self::with`].
+///
+/// Items are yielded as they arrive from any input; the merged stream
+/// ends when every input has ended.
+pub struct Merged {
+    inner: futures_util::stream::SelectAll<futures_util::stream::BoxStream<'static, ParsedSample>>,
+}
+
+impl Merged {
+    /// An empty merge; it ends at once unless streams are added.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            inner: futures_util::stream::SelectAll::new(),
+        }
+    }
+
+    /// Add `stream` to the merge.
+    #[must_use]
+    pub fn with<S>(mut self, stream: S) -> Self
+    where
+        S: Stream + Send + 'static,
+        S::Item: Into<ParsedSample>,
+    {
+        self.inner.push(stream.map(Into::into).boxed());
+        self
+    }
+
+    /// Number of inputs that have not ended.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Whether every input has ended.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
+
+impl Default for Merged {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Stream for Merged {
+    type Item = ParsedSample;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
+/// Stream adapter yielding a [`CortexError::StreamError`] item whenever
+/// the inner stream is silent for its timeout; see
+/// [`SampleStreamExt::with_timeout`].
+pub struct WithTimeout<S> {
+    inner: S,
+    timeout: std::time::Duration,
+    deadline: Pin<Box<tokio::time::Sleep>>,
+}
+
+impl<S: Stream + Unpin> Stream for WithTimeout<S> {
+    type Item = CortexResult<S::Item>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        match Pin::new(&mut this.inner).poll_next(cx) {
+            Poll::Ready(Some(item)) => {
+                let next = tokio::time::Instant::now() + this.timeout;
+                this.deadline.as_mut().reset(next);
+                return Poll::Ready(Some(Ok(item)));
+            }
+            Poll::Ready(None) => return Poll::Ready(None),
+            Poll::Pending => {}
+        }
+        match this.deadline.as_mut().poll(cx) {
+            Poll::Ready(()) => {
+                let next = tokio::time::Instant::now() + this.timeout;
+                this.deadline.as_mut().reset(next);
+                Poll::Ready(Some(Err(CortexError::StreamError {
+                    reason: format!("no data for {} ms", this.timeout.as_millis()),
+                })))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Contact quality reported by a `dev` or `eq` sample, 0.0–1.0.
+pub trait ContactQuality {
+    /// Overall contact quality, 0.0–1.0.
+    fn contact_quality(&self) -> f32;
+}
+
+impl ContactQuality for DeviceQuality {
+    fn contact_quality(&self) -> f32 {
+        self.overall_quality
+    }
+}
+
+impl ContactQuality for EegQuality {
+    fn contact_quality(&self) -> f32 {
+        self.overall
+    }
+}
+
+/// EEG stream adapter that drops samples while the paired quality stream
+/// reports contact quality below a threshold; see
+/// [`SampleStreamExt::quality_gated`].
+///
+/// Samples are dropped until the first quality report arrives. When the
+/// quality stream ends, its last report keeps applying.
+pub struct QualityGated<S, Q> {
+    inner: S,
+    quality: Option<Q>,
+    threshold: f32,
+    current: Option<f32>,
+    dropped: u64,
+    gaps: Option<CaptureGaps>,
+    closed: bool,
+}
+
+impl<S, Q> QualityGated<S, Q> {
+    /// Report each stretch of dropped samples to `gaps` as a
+    /// [`GapCause::QualityGate`] gap, so local writers annotate it.
+    #[must_use]
+    pub fn report_gaps(mut self, gaps: CaptureGaps) -> Self {
+        self.gaps = Some(gaps);
+        self
+    }
+
+    /// Latest contact quality reported, if any.
+    #[must_use]
+    pub fn quality(&self) -> Option<f32> {
+        self.current
+    }
+
+    /// Samples dropped so far.
+    #[must_use]
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
+
+impl<S, Q> Stream for QualityGated<S, Q>
+where
+    S: Stream<Item = EegData> + Unpin,
+    Q: Stream + Unpin,
+    Q::Item: ContactQuality,
+{
+    type Item = EegData;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        while let Some(quality) = this.quality.as_mut() {
+            match Pin::new(quality).poll_next(cx) {
+                Poll::Ready(Some(report)) => this.current = Some(report.contact_quality()),
+                Poll::Ready(None) => this.quality = None,
+                Poll::Pending => break,
+            }
+        }
+        loop {
+            match Pin::new(&mut this.inner).poll_next(cx) {
+                Poll::Ready(Some(sample)) => {
+                    let open = this.current.is_some_and(|q| q >= this.threshold);
+                    if open == this.closed {
+                        this.closed = !open;
+                        if let Some(gaps) = &this.gaps {
+                            if open {
+                                gaps.resume(&GapCause::QualityGate);
+                            } else {
+                                gaps.pause(GapCause::QualityGate);
+                            }
+                        }
+                    }
+                    if open {
+                        return Poll::Ready(Some(sample));
+                    }
+                    this.dropped += 1;
+                }
+                other => return other,
+            }
+        }
+    }
+}
+
+/// Combinators for typed sample streams such as [`TypedStream`].
+pub trait SampleStreamExt: Stream + Sized {
+    /// Merge this stream with `other` into one stream of
+    /// [`ParsedSample`]s; add more inputs with [`Merged::with`].
+    ///
+    /// ```
+    /// use emotiv_cortex_v2::protocol::streams::{MentalCommand, MotionData};
+    /// use emotiv_cortex_v2::streams::{ParsedSample, SampleStreamExt};
+    /// use futures_util::{StreamExt, stream};
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let com = stream::iter([MentalCommand { action: "push".into(), power: 0.6 }]);
+    /// let mot = stream::iter([MotionData {
+    ///     timestamp: 0,
+    ///     quaternion: None,
+    ///     gyroscope: None,
+    ///     accelerometer: [0.0; 3],
+    ///     magnetometer: [0.0; 3],
+    /// }]);
+    /// let items: Vec<ParsedSample> = com.merge(mot).collect().await;
+    /// assert_eq!(items.len(), 2);
+    /// # });
+    /// ```
+    fn merge<S>(self, other: S) -> Merged
+    where
+        Self: Send + 'static,
+        Self::Item: Into<ParsedSample>,
+        S: Stream + Send + 'static,
+        S::Item: Into<ParsedSample>,
+    {
+        Merged::new().with(self).with(other)
+    }
+
+    /// Yield each item as `Ok`, and a [`CortexError::StreamError`] each
+    /// time no item arrives for `timeout` (e.g. the headset went out of
+    /// range). The stream carries on after a timeout. Must be polled
+    /// inside a Tokio runtime with the time driver enabled.
+    fn with_timeout(self, timeout: std::time::Duration) -> WithTimeout<Self>
+    where
+        Self: Unpin,
+    {
+        WithTimeout {
+            inner: self,
+            timeout,
+            deadline: Box::pin(tokio::time::sleep(timeout)),
+        }
+    }
+
+    /// Drop EEG samples while `quality` (a `dev` or `eq` stream of the
+    /// same headset) reports overall contact quality below `threshold`
+    /// (0.0–1.0). See [`QualityGated`].
+    fn quality_gated<Q>(self, quality: Q, threshold: f32) -> QualityGated<Self, Q>
+    where
+        Self: Stream<Item = EegData> + Unpin,
+        Q: Stream + Unpin,
+        Q::Item: ContactQuality,
+    {
+        QualityGated {
+            inner: self,
+            quality: Some(quality),
+            threshold,
+            current: None,
+            dropped: 0,
+            gaps: None,
+            closed: false,
+        }
+    }
+
+    /// Scale EEG samples to microvolts and remove their DC offset with
+    /// `converter`. See [`crate::eeg_units`].
+    fn microvolts(self, converter: EegUnitConverter) -> EegInMicrovolts<Self>
+    where
+        Self: Stream<Item = EegData> + Unpin,
+    {
+        EegInMicrovolts::new(self, converter)
+    }
+
+    /// Emit the mean of every `factor` samples; see [`BoxcarDecimator`]
+    /// for its aliasing caveats.
+    fn decimate(self, factor: usize) -> Decimated<Self, Self::Item>
+    where
+        Self: Unpin,
+        Self::Item: Decimate,
+    {
+        Decimated::new(self, factor)
+    }
+
+    /// Convert from `nominal_rate_hz` to exactly `target_rate_hz`, with
+    /// anti-alias filtering when downsampling. See [`crate::resample`].
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ConfigError`] unless both rates are finite
+    /// and positive.
+    fn resample_to(
+        self,
+        nominal_rate_hz: f64,
+        target_rate_hz: f64,
+    ) -> CortexResult<Resampled<Self, Self::Item>>
+    where
+        Self: Unpin,
+        Self::Item: Resample,
+    {
+        Resampled::new(self, ResamplerConfig::new(nominal_rate_hz, target_rate_hz)?)
+    }
+
+    /// Record each sample in `stats` and pass it on unchanged. See
+    /// [`crate::stream_stats`].
+    fn with_stats(self, stats: StreamStats) -> WithStats<Self>
+    where
+        Self: Unpin,
+        Self::Item: StreamSample,
+    {
+        WithStats::new(self, stats)
+    }
+
+    /// Split EEG samples into windows flagged for blink and muscle
+    /// artifacts by `detector`. See [`crate::artifacts`].
+    fn artifacts(self, detector: ArtifactDetector) -> ArtifactFlags<Self>
+    where
+        Self: Stream<Item = EegData> + Unpin,
+    {
+        ArtifactFlags::new(self, detector)
+    }
+
+    /// Filter EEG samples with `filter`. See [`crate::dsp`].
+    #[cfg(feature = "dsp")]
+    fn filtered(self, filter: crate::dsp::EegFilter) -> crate::dsp::Filtered<Self>
+    where
+        Self: Stream<Item = EegData> + Unpin,
+    {
+        crate::dsp::Filtered::new(self, filter)
+    }
+}
+
+impl<S: Stream> SampleStreamExt for S {}
+
+// ─── Unsubscribe ─────────────────────────────────────────────────────────
+
+/// Unsubscribe from one or more data streams and remove the corresponding
+/// channels from the client.
+///
+/// # Errors
+/// Returns any error produced by the Cortex `unsubscribe` RPC call.
+pub async fn unsubscribe(
+    client: &CortexClient,
+    cortex_token: &str,
+    session_id: &str,
+    streams: &[&str],
+) -> CortexResult<()> {
+    client
+        .unsubscribe_streams(cortex_token, session_id, streams)
+        .await?;
+
+    for &stream in streams {
+        client.remove_stream_channel(stream);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::cast_possible_truncation)]
+mod tests {
+    use super::*;
+    use crate::testing::eeg_sample;
+    use futures_util::StreamExt;
+
+    #[tokio::test]
+    async fn test_typed_stream_parses_valid_events() {
+        let (tx, rx) = mpsc::channel(16);
+
+        let mut stream =
+            TypedStream::new(rx, |event| event.get("value")?.as_i64().map(|v| v as i32));
+
+        tx.send(serde_json::json!({"value": 42})).await.unwrap();
+        tx.send(serde_json::json!({"value": 99})).await.unwrap();
+        drop(tx);
+
+        assert_eq!(stream.next().await, Some(42));
+        assert_eq!(stream.next().await, Some(99));
+        assert_eq!(stream.next().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_typed_stream_skips_unparseable_events() {
+        let (tx, rx) = mpsc::channel(16);
+
+        let mut stream =
+            TypedStream::new(rx, |event| event.get("value")?.as_i64().map(|v| v as i32));
+
+        tx.send(serde_json::json!({"bad": "data"})).await.unwrap();
+        tx.send(serde_json::json!({"value": "not_a_number"}))
+            .await
+            .unwrap();
+        tx.send(serde_json::json!({"value": 7})).await.unwrap();
+        drop(tx);
+
+        // The first two events should be skipped
+        assert_eq!(stream.next().await, Some(7));
+        assert_eq!(stream.next().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_with_raw_keeps_source_event() {
+        let (tx, rx) = mpsc::channel(16);
+        let kind = Streams::COM;
+        let mut stream =
+            TypedStream::new(rx, move |event| parse_sample_value(kind, &event)).with_raw();
+
+        let event = serde_json::json!({"com": ["push", 0.5], "sid": "s-1", "time": 1.0});
+        tx.send(serde_json::json!({"com": "garbled"}))
+            .await
+            .unwrap();
+        tx.send(event.clone()).await.unwrap();
+        drop(tx);
+
+        let item = stream.next().await.unwrap();
+        assert!(matches!(&item.sample, ParsedSample::Com(c) if c.action == "push"));
+        assert_eq!(item.raw, event);
+        assert_eq!(item.kind(), Streams::COM);
+        assert_eq!(item.session_id(), Some("s-1"));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_typed_stream_ends_when_sender_dropped() {
+        let (tx, rx) = mpsc::channel(16);
+        let mut stream = TypedStream::new(rx, |event| event.get("v")?.as_i64().map(|v| v as i32));
+
+        drop(tx);
+        assert_eq!(stream.next().await, None);
+    }
+
+    fn eeg(counter: u32, value: f32) -> EegData {
+        EegData {
+            interpolated: counter == 2,
+            raw_cq: 4.0 - value,
+            ..eeg_sample(
+                i64::from(counter) * 1_000,
+                counter,
+                vec![value, value * 2.0],
+            )
+        }
+    }
+
+    #[test]
+    fn test_boxcar_decimator_averages_blocks() {
+        let mut decimator = BoxcarDecimator::new(2);
+        assert!(decimator.push(eeg(1, 1.0)).is_none());
+        let out = decimator.push(eeg(2, 3.0)).unwrap();
+        assert_eq!(out.counter, 2);
+        assert_eq!(out.timestamp, 2_000);
+        assert!(out.interpolated);
+        assert_eq!(out.channels, vec![2.0, 4.0]);
+        assert!((out.raw_cq - 1.0).abs() < f32::EPSILON);
+
+        let mut passthrough = BoxcarDecimator::new(0);
+        assert_eq!(passthrough.factor(), 1);
+        assert!(passthrough.push(eeg(3, 1.0)).is_some());
+    }
+
+    #[test]
+    fn test_boxcar_drops_inconsistent_block() {
+        let mut decimator = BoxcarDecimator::new(2);
+        let mut odd = eeg(1, 1.0);
+        odd.channels.push(0.0);
+        assert!(decimator.push(odd).is_none());
+        assert!(decimator.push(eeg(2, 1.0)).is_none());
+        // Next block starts fresh.
+        assert!(decimator.push(eeg(3, 1.0)).is_none());
+        assert!(decimator.push(eeg(4, 1.0)).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_decimated_stream_discards_partial_tail() {
+        let (tx, rx) = mpsc::channel(16);
+        for counter in 1..=7 {
+            tx.send(eeg(counter, 1.0)).await.unwrap();
+        }
+        drop(tx);
+
+        let inner = Box::pin(futures_util::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|sample| (sample, rx))
+        }));
+        let counters: Vec<u32> = Decimated::new(inner, 3)
+            .map(|sample| sample.counter)
+            .collect()
+            .await;
+        assert_eq!(counters, vec![3, 6]);
+    }
+
+    #[tokio::test]
+    async fn test_on_sample_delivers_and_deregisters_on_drop() {
+        let (tx, rx) = mpsc::channel(16);
+        let stream = TypedStream::new(rx, |event| event.get("v")?.as_u64());
+
+        let (seen_tx, mut seen_rx) = mpsc::unbounded_channel();
+        let guard = stream
+            .on_sample(move |v| {
+                let _ = seen_tx.send(v);
+            })
+            .unwrap();
+
+        for v in 1..=3_u64 {
+            tx.send(serde_json::json!({ "v": v })).await.unwrap();
+        }
+        let mut seen = Vec::new();
+        for _ in 0..3 {
+            let v = tokio::time::timeout(std::time::Duration::from_secs(2), seen_rx.recv())
+                .await
+                .unwrap();
+            seen.extend(v);
+        }
+        assert_eq!(seen, vec![1, 2, 3]);
+        assert_eq!(guard.dropped(), 0);
+
+        drop(guard);
+        tokio::time::timeout(std::time::Duration::from_secs(2), tx.closed())
+            .await
+            .expect("dropping the guard should close the stream");
+    }
+
+    #[tokio::test]
+    async fn test_on_sample_drops_when_callback_queue_is_full() {
+        let (tx, rx) = mpsc::channel(64);
+        let stream = TypedStream::new(rx, |event| event.get("v")?.as_u64());
+
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let guard = stream
+            .on_sample_with(CallbackOptions { queue_capacity: 1 }, move |_| {
+                // Block until the test releases the callback.
+                let _ = release_rx.recv();
+            })
+            .unwrap();
+
+        for v in 0..10_u64 {
+            tx.send(serde_json::json!({ "v": v })).await.unwrap();
+        }
+        drop(tx);
+        tokio::time::timeout(std::time::Duration::from_secs(2), async {
+            while !guard.is_finished() {
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        // One sample in the callback, one queued, the rest dropped.
+        assert!(guard.dropped() >= 8);
+        drop(release_tx);
+    }
+
+    #[tokio::test]
+    async fn test_schema_tracked_reports_layout_change_before_sample() {
+        let (tx, rx) = mpsc::channel(8);
+        let cols = [
+            "COUNTER",
+            "INTERPOLATED",
+            "AF3",
+            "AF4",
+            "RAW_CQ",
+            "MARKER_HARDWARE",
+            "MARKERS",
+        ];
+        let mut stream = SchemaTracked {
+            rx,
+            schema: StreamSchema {
+                stream: "eeg".into(),
+                cols: cols.iter().map(ToString::to_string).collect(),
+            },
+            expected_len: Some(cols.len()),
+            suffix: 3,
+            parse: |eeg: &[serde_json::Value], time| {
+                EegData::from_eeg_array(eeg, eeg.len().checked_sub(5)?, time)
+            },
+            pending: None,
+        };
+
+        tx.send(serde_json::json!({"time": 1.0, "eeg": [1, 0, 4200.0, 4201.0, 4.0, 0, []]}))
+            .await
+            .unwrap();
+        tx.send(serde_json::json!({
+            "time": 2.0,
+            "eeg": [2, 0, 4200.0, 4201.0, 4202.0, 4.0, 0, []]
+        }))
+        .await
+        .unwrap();
+        drop(tx);
+
+        let Some(StreamItem::Sample(first)) = stream.next().await else {
+            panic!("expected a sample");
+        };
+        assert_eq!(first.channels.len(), 2);
+
+        let Some(StreamItem::SchemaChanged { previous, current }) = stream.next().await else {
+            panic!("expected a schema change");
+        };
+        assert_eq!(previous.len(), 7);
+        assert_eq!(
+            current.cols,
+            [
+                "COUNTER",
+                "INTERPOLATED",
+                "AF3",
+                "AF4",
+                "col4",
+                "RAW_CQ",
+                "MARKER_HARDWARE",
+                "MARKERS"
+            ]
+        );
+
+        let Some(StreamItem::Sample(second)) = stream.next().await else {
+            panic!("expected the sample in the new layout");
+        };
+        assert_eq!(second.channels.len(), 3);
+        assert!((second.raw_cq - 4.0).abs() < f32::EPSILON);
+        assert_eq!(stream.schema(), &current);
+        assert!(stream.next().await.is_none());
+    }
+
+    #[test]
+    fn test_schema_resized_keeps_suffix_labels_when_shrinking() {
+        let schema = StreamSchema {
+            stream: "eq".into(),
+            cols: [
+                "batteryPercent",
+                "overall",
+                "sampleRateQuality",
+                "AF3",
+                "T7",
+                "Pz",
+            ]
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+        };
+        assert_eq!(
+            schema.resized(5, 0).cols,
+            [
+                "batteryPercent",
+                "overall",
+                "sampleRateQuality",
+                "AF3",
+                "T7"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stream_sample_flattens_band_power() {
+        let pow = BandPowerData {
+            timestamp: 42,
+            channel_powers: vec![[1.0, 2.0, 3.0, 4.0, 5.0], [6.0, 7.0, 8.0, 9.0, 10.0]],
+        };
+        let sample = ParsedSample::Pow(pow);
+        assert_eq!(sample.kind(), Streams::POW);
+        assert_eq!(sample.timestamp(), Some(42));
+        assert_eq!(sample.session_id(), None);
+        assert_eq!(sample.as_f32_slice().map(<[f32]>::len), Some(10));
+
+        let sys = SysEvent {
+            sid: "session-1".into(),
+            time: 1.5,
+            sys: Vec::new(),
+        };
+        assert_eq!(sys.session_id(), Some("session-1"));
+        assert_eq!(sys.timestamp(), Some(1_500_000));
+        assert!(sys.as_f32_slice().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_with_markers_interleaves_in_timestamp_order() {
+        fn eeg(timestamp: i64) -> EegData {
+            eeg_sample(timestamp, 0, vec![0.0; 5])
+        }
+        fn marker(timestamp: i64, label: &str) -> StreamMarker {
+            StreamMarker {
+                timestamp,
+                label: label.into(),
+                value: 1,
+                port: "test".into(),
+            }
+        }
+        fn label<T>(item: &Marked<T>) -> String
+        where
+            T: StreamSample,
+        {
+            match item {
+                Marked::Data(sample) => sample.timestamp().unwrap_or_default().to_string(),
+                Marked::Marker(marker) => marker.label.clone(),
+            }
+        }
+
+        let (data_tx, data_rx) = mpsc::channel(8);
+        let (marker_tx, marker_rx) = mpsc::channel(8);
+        let data = TypedStream::new(data_rx, |event| event.get("t")?.as_i64().map(eeg));
+        let mut merged = data.with_markers(marker_rx);
+
+        // Out-of-order markers ahead of the data are sorted and held.
+        marker_tx.send(marker(25, "b")).await.unwrap();
+        marker_tx.send(marker(5, "a")).await.unwrap();
+        marker_tx.send(marker(100, "end")).await.unwrap();
+        for t in [10, 20, 30] {
+            data_tx.send(serde_json::json!({"t": t})).await.unwrap();
+        }
+        let mut seen = Vec::new();
+        for _ in 0..5 {
+            seen.push(label(&merged.next().await.unwrap()));
+        }
+        assert_eq!(seen, ["a", "10", "20", "b", "30"]);
+
+        // A marker whose time the data already passed comes out at once.
+        marker_tx.send(marker(15, "late")).await.unwrap();
+        assert_eq!(label(&merged.next().await.unwrap()), "late");
+
+        // Held markers are flushed when the data ends.
+        drop(data_tx);
+        assert_eq!(label(&merged.next().await.unwrap()), "end");
+        assert!(merged.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_quality_gated_drops_eeg_while_contact_is_poor() {
+        use futures_util::FutureExt;
+
+        let sample = |counter| eeg_sample(0, counter, vec![0.0; 5]);
+        let quality = |overall| EegQuality {
+            battery_percent: 100,
+            overall,
+            sample_rate_quality: 1.0,
+            sensor_quality: vec![overall; 5],
+        };
+        let (eeg_tx, eeg_rx) = mpsc::channel(16);
+        let (eq_tx, eq_rx) = mpsc::channel(16);
+        let gaps = CaptureGaps::new();
+        let mut gated = tokio_stream_of(eeg_rx)
+            .quality_gated(tokio_stream_of(eq_rx), 0.5)
+            .report_gaps(gaps.clone());
+
+        // No quality report yet.
+        eeg_tx.send(sample(0)).await.unwrap();
+        assert!(gated.next().now_or_never().is_none());
+        eq_tx.send(quality(0.9)).await.unwrap();
+        eeg_tx.send(sample(1)).await.unwrap();
+        assert_eq!(gated.next().await.unwrap().counter, 1);
+        eq_tx.send(quality(0.2)).await.unwrap();
+        eeg_tx.send(sample(2)).await.unwrap();
+        assert!(gated.next().now_or_never().is_none());
+        eq_tx.send(quality(0.8)).await.unwrap();
+        eeg_tx.send(sample(3)).await.unwrap();
+        drop(eeg_tx);
+        let rest: Vec<u32> = gated.by_ref().map(|s| s.counter).collect().await;
+        assert_eq!(rest, [3]);
+        assert_eq!(gated.dropped(), 2);
+        assert_eq!(gated.quality(), Some(0.8));
+        // One gap before the first report, one while contact was poor.
+        let causes: Vec<GapCause> = gaps.annotations().into_iter().map(|g| g.cause).collect();
+        assert_eq!(causes, [GapCause::QualityGate, GapCause::QualityGate]);
+        assert!(!gaps.is_paused());
+    }
+
+    #[tokio::test]
+    async fn test_with_timeout_reports_silence_and_continues() {
+        let (tx, rx) = mpsc::channel(16);
+        let mut stream = TypedStream::new(rx, |event| event.as_i64())
+            .with_timeout(std::time::Duration::from_millis(20));
+
+        tx.send(serde_json::json!(1)).await.unwrap();
+        assert_eq!(stream.next().await.unwrap().unwrap(), 1);
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(CortexError::StreamError { .. }))
+        ));
+        tx.send(serde_json::json!(2)).await.unwrap();
+        assert_eq!(stream.next().await.unwrap().unwrap(), 2);
+        drop(tx);
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_merge_tags_items_by_stream() {
+        let com = futures_util::stream::iter([MentalCommand {
+            action: "push".into(),
+            power: 0.5,
+        }]);
+        let eq = futures_util::stream::iter([EegQuality {
+            battery_percent: 90,
+            overall: 1.0,
+            sample_rate_quality: 1.0,
+            sensor_quality: Vec::new(),
+        }]);
+        let merged = com
+            .merge(eq)
+            .with(futures_util::stream::empty::<SysEvent>());
+        assert_eq!(merged.len(), 3);
+        let mut kinds: Vec<&str> = merged.map(|item| item.kind()).collect().await;
+        kinds.sort_unstable();
+        assert_eq!(kinds, [Streams::COM, Streams::EQ]);
+    }
+
+    /// A stream over the items sent on `rx`.
+    fn tokio_stream_of<T>(mut rx: mpsc::Receiver<T>) -> impl Stream<Item = T> + Unpin {
+        futures_util::stream::poll_fn(move |cx| rx.poll_recv(cx))
     }
 }
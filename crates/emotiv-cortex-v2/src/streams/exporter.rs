@@ -0,0 +1,783 @@
+//! # EEG File Exporter
+//!
+//! [`EegExporter`] writes a typed EEG stream to newline-delimited JSON or
+//! CSV files for as long as the stream runs. Long sessions are split
+//! into numbered segments (`<name>_0001.csv`, `<name>_0002.csv`, ...) by
+//! [`Rotation`] so an overnight recording does not end up as one
+//! multi-gigabyte file, and with the `gzip` feature each segment can be
+//! gzip-compressed as it is written.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use emotiv_cortex_v2::CortexClient;
+//! use emotiv_cortex_v2::streams::{self, exporter::{EegExporter, ExporterOptions, Rotation}};
+//!
+//! # async fn demo(client: &CortexClient, token: &str, session_id: &str) -> emotiv_cortex_v2::CortexResult<()> {
+//! let eeg = streams::subscribe_eeg(client, token, session_id, 14).await?;
+//! let exporter = EegExporter::spawn(eeg, ExporterOptions {
+//!     dir: "night-1".into(),
+//!     rotation: Rotation {
+//!         max_duration: Some(Duration::from_secs(3600)),
+//!         ..Rotation::default()
+//!     },
+//!     ..ExporterOptions::default()
+//! })?;
+//! // ... later
+//! let files = exporter.files();
+//! let report = exporter.stop().await;
+//! println!("{} samples in {files:?}", report.written);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! While [`EegExporter::gaps`] is paused, samples are skipped, and each
+//! gap is listed in `<name>_events.tsv` (BIDS, onsets relative to the
+//! first sample) when the exporter stops; see [`crate::annotations`].
+//!
+//! With [`ExporterOptions::anonymize`] set, sample timestamps are shifted
+//! by the subject's offset and the `<name>_metadata.json` sidecar carries
+//! the pseudonym instead of the subject name; see [`crate::anonymize`].
+//!
+//! Segment boundaries follow sample timestamps for
+//! [`Rotation::max_duration`] and bytes before compression for
+//! [`Rotation::max_bytes`], so a segment may run one sample past either
+//! limit.
+
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_core::Stream;
+use futures_util::future::BoxFuture;
+use futures_util::{FutureExt, StreamExt};
+use serde_json::Value;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::annotations::{CaptureGaps, GapAnnotation, GapCause, bids_events_tsv};
+use crate::anonymize::{Anonymizer, ExportIdentity};
+use crate::error::{CortexError, CortexResult};
+use crate::protocol::streams::EegData;
+use crate::sink::{Sink, SinkReport};
+use crate::streams::micros_to_secs;
+
+/// Default interval between flushes of the current segment.
+pub const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How an [`EegExporter`] encodes samples.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportEncoding {
+    /// One serialized [`EegData`] object per line.
+    #[default]
+    Ndjson,
+    /// A header row, then `timestamp,counter,interpolated,<channels>,raw_cq`
+    /// with the timestamp in seconds.
+    Csv,
+}
+
+impl ExportEncoding {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Ndjson => "ndjson",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+/// When an [`EegExporter`] starts a new segment. With both limits set,
+/// whichever is reached first wins; with neither, everything goes to one
+/// file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rotation {
+    /// Sample time covered by one segment.
+    pub max_duration: Option<Duration>,
+    /// Bytes written to one segment, before compression.
+    pub max_bytes: Option<u64>,
+}
+
+/// Where and how an [`EegExporter`] writes.
+#[derive(Debug, Clone)]
+pub struct ExporterOptions {
+    /// Directory for the segments; created if missing.
+    pub dir: PathBuf,
+    /// File name stem: segments are `<name>_<NNNN>.<ext>`.
+    pub name: String,
+    /// Sample encoding.
+    pub encoding: ExportEncoding,
+    /// CSV channel column labels, e.g. from
+    /// [`HeadsetModel::channel_names`](crate::headset::HeadsetModel::channel_names).
+    /// Empty labels the columns `CH1`, `CH2`, ...
+    pub channel_labels: Vec<String>,
+    /// Segment rotation.
+    pub rotation: Rotation,
+    /// Interval between flushes of the current segment.
+    pub flush_interval: Duration,
+    /// Gzip-compress each segment (`.ndjson.gz`, `.csv.gz`). Requires
+    /// the `gzip` feature.
+    pub gzip: bool,
+    /// Subject of the recording, written as `subjectName` to the
+    /// metadata sidecar.
+    pub subject: Option<String>,
+    /// Written to `<name>_metadata.json` when set (or when
+    /// [`Self::subject`] is).
+    pub metadata: Option<Value>,
+    /// De-identify the export: shift sample timestamps by the subject's
+    /// offset and scrub the metadata sidecar.
+    pub anonymize: Option<Anonymizer>,
+    /// Pause state shared with other producers, such as a
+    /// [quality gate](crate::streams::QualityGated::report_gaps); the
+    /// exporter makes its own when `None`.
+    pub gaps: Option<CaptureGaps>,
+}
+
+impl Default for ExporterOptions {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("."),
+            name: "eeg".into(),
+            encoding: ExportEncoding::default(),
+            channel_labels: Vec::new(),
+            rotation: Rotation::default(),
+            flush_interval: DEFAULT_FLUSH_INTERVAL,
+            gzip: false,
+            subject: None,
+            metadata: None,
+            anonymize: None,
+            gaps: None,
+        }
+    }
+}
+
+/// Writes an EEG stream to rotating files. See the
+/// [module documentation](self).
+///
+/// Dropping the exporter leaves its task running until the stream ends;
+/// call [`Self::stop`] to finish the current segment.
+pub struct EegExporter {
+    files: Arc<Mutex<Vec<PathBuf>>>,
+    gaps: CaptureGaps,
+    shutdown: watch::Sender<bool>,
+    task: JoinHandle<SinkReport>,
+}
+
+impl EegExporter {
+    /// Open the first segment and start writing `stream` in a background
+    /// task.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ConfigError`] if the directory, metadata
+    /// sidecar, or first segment cannot be created, or if `gzip` is set
+    /// without the `gzip` feature.
+    pub fn spawn<S>(stream: S, options: ExporterOptions) -> CortexResult<Self>
+    where
+        S: Stream<Item = EegData> + Send + Unpin + 'static,
+    {
+        if options.gzip && !cfg!(feature = "gzip") {
+            return Err(CortexError::ConfigError {
+                reason: "gzip export requires the `gzip` feature".into(),
+            });
+        }
+        fs::create_dir_all(&options.dir).map_err(|e| CortexError::ConfigError {
+            reason: format!("cannot create '{}': {e}", options.dir.display()),
+        })?;
+        let identity = ExportIdentity::new(options.anonymize.clone(), options.subject.clone());
+        let metadata_path = options.dir.join(format!("{}_metadata.json", options.name));
+        identity
+            .write_metadata(&metadata_path, options.metadata.as_ref())
+            .map_err(|e| CortexError::ConfigError {
+                reason: format!("cannot write '{}': {e}", metadata_path.display()),
+            })?;
+        let gaps = options.gaps.clone().unwrap_or_default();
+        let files = Arc::new(Mutex::new(Vec::new()));
+        let mut writer = SegmentWriter {
+            options,
+            identity,
+            files: Arc::clone(&files),
+            current: None,
+            next_index: 1,
+            first_timestamp: None,
+        };
+        writer.open_next().map_err(|e| CortexError::ConfigError {
+            reason: format!("cannot create export file: {e}"),
+        })?;
+
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        let task = tokio::spawn(export(stream, writer, gaps.clone(), shutdown_rx));
+        Ok(Self {
+            files,
+            gaps,
+            shutdown,
+            task,
+        })
+    }
+
+    /// Segments created so far, oldest first.
+    #[must_use]
+    pub fn files(&self) -> Vec<PathBuf> {
+        self.files.lock().map(|f| f.clone()).unwrap_or_default()
+    }
+
+    /// Pause state of the export. Samples arriving while it is paused
+    /// are skipped and the gap is listed in `<name>_events.tsv`.
+    #[must_use]
+    pub fn gaps(&self) -> &CaptureGaps {
+        &self.gaps
+    }
+
+    /// Pause exporting on the operator's behalf ([`GapCause::Paused`]).
+    pub fn pause(&self) {
+        self.gaps.pause(GapCause::Paused);
+    }
+
+    /// Undo [`Self::pause`]. Exporting resumes once no other cause holds
+    /// it paused.
+    pub fn resume(&self) {
+        self.gaps.resume(&GapCause::Paused);
+    }
+
+    /// Stop exporting, write the samples the stream already has ready,
+    /// finish the current segment, and write the gaps, if any.
+    pub async fn stop(self) -> SinkReport {
+        let _ = self.shutdown.send(true);
+        self.task.await.unwrap_or_else(|e| SinkReport {
+            error: Some(format!("exporter task failed: {e}")),
+            ..SinkReport::new("eeg exporter")
+        })
+    }
+}
+
+impl Sink for EegExporter {
+    fn name(&self) -> String {
+        "eeg exporter".into()
+    }
+
+    fn close(self: Box<Self>) -> BoxFuture<'static, Vec<SinkReport>> {
+        Box::pin(async move { vec![self.stop().await] })
+    }
+}
+
+// ─── Writing ─────────────────────────────────────────────────────────────
+
+async fn export<S>(
+    mut stream: S,
+    mut writer: SegmentWriter,
+    gaps: CaptureGaps,
+    mut shutdown: watch::Receiver<bool>,
+) -> SinkReport
+where
+    S: Stream<Item = EegData> + Unpin,
+{
+    let mut report = SinkReport::new(writer.label());
+    let mut flush = tokio::time::interval(writer.options.flush_interval);
+    flush.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut closed_gaps = gaps.listen();
+    let mut gap_list = Vec::new();
+
+    loop {
+        tokio::select! {
+            item = stream.next() => {
+                let Some(sample) = item else { break };
+                if !gaps.is_paused() {
+                    writer.write_counted(&sample, &mut report);
+                }
+            }
+            Some(gap) = closed_gaps.recv() => gap_list.push(gap),
+            _ = flush.tick() => {
+                if let Err(e) = writer.flush() {
+                    tracing::warn!(error = %e, "Exporter flush failed");
+                }
+            }
+            _ = shutdown.changed() => {
+                while let Some(Some(sample)) = stream.next().now_or_never() {
+                    if !gaps.is_paused() {
+                        writer.write_counted(&sample, &mut report);
+                    }
+                }
+                break;
+            }
+        }
+    }
+
+    while let Ok(gap) = closed_gaps.try_recv() {
+        gap_list.push(gap);
+    }
+    gap_list.extend(gaps.open_gap());
+    if let Err(e) = writer.finish() {
+        tracing::warn!(error = %e, "Exporter segment did not finish");
+        report.error = Some(format!("finish failed: {e}"));
+    }
+    if let Err(e) = writer.write_events(gap_list) {
+        tracing::warn!(error = %e, "Exporter events file was not written");
+        report
+            .error
+            .get_or_insert(format!("events file failed: {e}"));
+    }
+    report
+}
+
+/// The open output file of a segment.
+enum Output {
+    Plain(BufWriter<File>),
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::write::GzEncoder<BufWriter<File>>),
+}
+
+impl Output {
+    fn writer(&mut self) -> &mut dyn Write {
+        match self {
+            Self::Plain(w) => w,
+            #[cfg(feature = "gzip")]
+            Self::Gzip(w) => w,
+        }
+    }
+
+    fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Plain(mut w) => w.flush(),
+            #[cfg(feature = "gzip")]
+            Self::Gzip(w) => w.finish()?.flush(),
+        }
+    }
+}
+
+struct Segment {
+    output: Output,
+    bytes: u64,
+    first_timestamp: Option<i64>,
+}
+
+struct SegmentWriter {
+    options: ExporterOptions,
+    identity: ExportIdentity,
+    files: Arc<Mutex<Vec<PathBuf>>>,
+    current: Option<Segment>,
+    next_index: u32,
+    /// Timestamp of the first sample written, as written.
+    first_timestamp: Option<i64>,
+}
+
+impl SegmentWriter {
+    fn label(&self) -> String {
+        format!(
+            "eeg -> {}:{}",
+            self.options.encoding.extension(),
+            self.options
+                .dir
+                .join(format!("{}_*.{}", self.options.name, self.extension()))
+                .display()
+        )
+    }
+
+    fn extension(&self) -> String {
+        let ext = self.options.encoding.extension();
+        if self.options.gzip {
+            format!("{ext}.gz")
+        } else {
+            ext.to_string()
+        }
+    }
+
+    fn open_next(&mut self) -> io::Result<()> {
+        let path = self.options.dir.join(format!(
+            "{}_{:04}.{}",
+            self.options.name,
+            self.next_index,
+            self.extension()
+        ));
+        let file = BufWriter::new(File::create(&path)?);
+        let output = if self.options.gzip {
+            gzip(file)?
+        } else {
+            Output::Plain(file)
+        };
+        self.next_index += 1;
+        self.current = Some(Segment {
+            output,
+            bytes: 0,
+            first_timestamp: None,
+        });
+        if let Ok(mut files) = self.files.lock() {
+            files.push(path);
+        }
+        Ok(())
+    }
+
+    fn write_counted(&mut self, sample: &EegData, report: &mut SinkReport) {
+        let shifted;
+        let sample = if self.options.anonymize.is_some() {
+            shifted = EegData {
+                timestamp: self.identity.shift_micros(sample.timestamp),
+                ..sample.clone()
+            };
+            &shifted
+        } else {
+            sample
+        };
+        match self.write(sample) {
+            Ok(()) => report.written += 1,
+            Err(e) => {
+                if report.dropped == 0 {
+                    tracing::warn!(error = %e, "Exporter write failed");
+                }
+                report.dropped += 1;
+            }
+        }
+    }
+
+    fn write(&mut self, sample: &EegData) -> io::Result<()> {
+        if self
+            .current
+            .as_ref()
+            .is_some_and(|s| self.is_full(s, sample))
+        {
+            if let Some(segment) = self.current.take() {
+                segment.output.finish()?;
+            }
+        }
+        if self.current.is_none() {
+            self.open_next()?;
+        }
+
+        let mut line = String::new();
+        if self.current.as_ref().is_some_and(|s| s.bytes == 0)
+            && self.options.encoding == ExportEncoding::Csv
+        {
+            line.push_str(&self.csv_header(sample.channels.len()));
+        }
+        match self.options.encoding {
+            ExportEncoding::Ndjson => {
+                line.push_str(&serde_json::to_string(sample).map_err(io::Error::other)?);
+                line.push('\n');
+            }
+            ExportEncoding::Csv => csv_row(&mut line, sample),
+        }
+
+        let Some(segment) = self.current.as_mut() else {
+            return Ok(());
+        };
+        segment.output.writer().write_all(line.as_bytes())?;
+        segment.bytes += line.len() as u64;
+        segment.first_timestamp.get_or_insert(sample.timestamp);
+        self.first_timestamp.get_or_insert(sample.timestamp);
+        Ok(())
+    }
+
+    /// Write `gaps` to `<name>_events.tsv`, onsets relative to the first
+    /// sample. Nothing is written without gaps.
+    fn write_events(&self, gaps: Vec<GapAnnotation>) -> io::Result<()> {
+        let gaps: Vec<GapAnnotation> = gaps
+            .into_iter()
+            .map(|gap| self.identity.shift_gap(gap))
+            .collect();
+        let Some(first_gap) = gaps.first() else {
+            return Ok(());
+        };
+        let start = self.first_timestamp.unwrap_or(first_gap.start_us);
+        let path = self
+            .options
+            .dir
+            .join(format!("{}_events.tsv", self.options.name));
+        fs::write(path, bids_events_tsv(&gaps, start))
+    }
+
+    /// Whether `sample` belongs in the next segment.
+    fn is_full(&self, segment: &Segment, sample: &EegData) -> bool {
+        let rotation = self.options.rotation;
+        let too_big = rotation.max_bytes.is_some_and(|max| segment.bytes >= max);
+        let too_long = match (rotation.max_duration, segment.first_timestamp) {
+            (Some(max), Some(first)) => {
+                let elapsed = u64::try_from(sample.timestamp.saturating_sub(first)).unwrap_or(0);
+                u128::from(elapsed) >= max.as_micros()
+            }
+            _ => false,
+        };
+        too_big || too_long
+    }
+
+    fn csv_header(&self, channels: usize) -> String {
+        let mut header = String::from("timestamp,counter,interpolated");
+        for i in 0..channels {
+            match self.options.channel_labels.get(i) {
+                Some(label) => {
+                    let _ = write!(header, ",{label}");
+                }
+                None => {
+                    let _ = write!(header, ",CH{}", i + 1);
+                }
+            }
+        }
+        header.push_str(",raw_cq\n");
+        header
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.current.as_mut() {
+            Some(segment) => segment.output.writer().flush(),
+            None => Ok(()),
+        }
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        match self.current.take() {
+            Some(segment) => segment.output.finish(),
+            None => Ok(()),
+        }
+    }
+}
+
+fn csv_row(line: &mut String, sample: &EegData) {
+    let _ = write!(
+        line,
+        "{},{},{}",
+        micros_to_secs(sample.timestamp),
+        sample.counter,
+        u8::from(sample.interpolated)
+    );
+    for value in &sample.channels {
+        let _ = write!(line, ",{value}");
+    }
+    let _ = writeln!(line, ",{}", sample.raw_cq);
+}
+
+#[cfg(feature = "gzip")]
+#[allow(clippy::unnecessary_wraps)]
+fn gzip(file: BufWriter<File>) -> io::Result<Output> {
+    Ok(Output::Gzip(flate2::write::GzEncoder::new(
+        file,
+        flate2::Compression::default(),
+    )))
+}
+
+#[cfg(not(feature = "gzip"))]
+fn gzip(_: BufWriter<File>) -> io::Result<Output> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "gzip export requires the `gzip` feature",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(timestamp: i64, counter: u32) -> EegData {
+        EegData {
+            timestamp,
+            counter,
+            interpolated: false,
+            channels: vec![4200.5, 4100.0],
+            raw_cq: 1.0,
+        }
+    }
+
+    fn temp_dir(label: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("exporter-{label}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_csv_export_rotates_by_duration() {
+        let dir = temp_dir("duration");
+        // 5 samples, 0.5 s apart: segments of [0, 0.5], [1.0, 1.5], [2.0].
+        let samples: Vec<EegData> = (0..5)
+            .map(|i| sample(1_000_000 + i64::from(i) * 500_000, i))
+            .collect();
+        let exporter = EegExporter::spawn(
+            futures_util::stream::iter(samples),
+            ExporterOptions {
+                dir: dir.clone(),
+                encoding: ExportEncoding::Csv,
+                channel_labels: vec!["AF3".into()],
+                rotation: Rotation {
+                    max_duration: Some(Duration::from_secs(1)),
+                    ..Rotation::default()
+                },
+                ..ExporterOptions::default()
+            },
+        )
+        .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let files = exporter.files();
+        let report = exporter.stop().await;
+
+        assert_eq!(report.written, 5);
+        assert!(report.is_lossless(), "{report:?}");
+        assert_eq!(files.len(), 3);
+        assert!(files[0].ends_with("eeg_0001.csv"));
+        let first = fs::read_to_string(&files[0]).unwrap();
+        assert_eq!(
+            first,
+            "timestamp,counter,interpolated,AF3,CH2,raw_cq\n\
+             1,0,0,4200.5,4100,1\n\
+             1.5,1,0,4200.5,4100,1\n"
+        );
+        let last = fs::read_to_string(&files[2]).unwrap();
+        assert_eq!(last.lines().count(), 2);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_export_rotates_by_size() {
+        let dir = temp_dir("size");
+        let samples: Vec<EegData> = (0..4).map(|i| sample(i64::from(i), i)).collect();
+        let exporter = EegExporter::spawn(
+            futures_util::stream::iter(samples),
+            ExporterOptions {
+                dir: dir.clone(),
+                rotation: Rotation {
+                    max_bytes: Some(1),
+                    ..Rotation::default()
+                },
+                ..ExporterOptions::default()
+            },
+        )
+        .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let files = exporter.files();
+        let report = exporter.stop().await;
+
+        assert_eq!(report.written, 4);
+        assert_eq!(files.len(), 4);
+        let line = fs::read_to_string(&files[3]).unwrap();
+        let value: serde_json::Value = serde_json::from_str(line.trim_end()).unwrap();
+        assert_eq!(value["counter"], 3);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_anonymized_export_shifts_timestamps_and_scrubs_metadata() {
+        use crate::anonymize::AnonymizeOptions;
+
+        let dir = temp_dir("anonymize");
+        let anon = Anonymizer::new(AnonymizeOptions::with_salt("lab"));
+        let start = 1_700_000_000_000_000;
+        let exporter = EegExporter::spawn(
+            futures_util::stream::iter(vec![sample(start, 0), sample(start + 500_000, 1)]),
+            ExporterOptions {
+                dir: dir.clone(),
+                subject: Some("Jane Doe".into()),
+                metadata: Some(serde_json::json!({"dateOfBirth": "1990-01-01", "task": "rest"})),
+                anonymize: Some(anon.clone()),
+                ..ExporterOptions::default()
+            },
+        )
+        .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let files = exporter.files();
+        assert_eq!(exporter.stop().await.written, 2);
+
+        let text = fs::read_to_string(&files[0]).unwrap();
+        let timestamps: Vec<i64> = text
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<Value>(line).unwrap()["timestamp"]
+                    .as_i64()
+                    .unwrap()
+            })
+            .collect();
+        let shifted = anon.shift_micros("Jane Doe", start);
+        assert_ne!(shifted, start);
+        assert_eq!(timestamps, [shifted, shifted + 500_000]);
+
+        let metadata: Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("eeg_metadata.json")).unwrap())
+                .unwrap();
+        assert_eq!(
+            metadata,
+            serde_json::json!({"subjectName": anon.pseudonym("Jane Doe"), "task": "rest"})
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_paused_samples_are_skipped_and_gaps_listed_in_events_tsv() {
+        let dir = temp_dir("gaps");
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let exporter = EegExporter::spawn(
+            futures_util::stream::unfold(rx, |mut rx| async move {
+                rx.recv().await.map(|sample| (sample, rx))
+            })
+            .boxed(),
+            ExporterOptions {
+                dir: dir.clone(),
+                encoding: ExportEncoding::Csv,
+                ..ExporterOptions::default()
+            },
+        )
+        .unwrap();
+        let now = || {
+            i64::try_from(
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_micros(),
+            )
+            .unwrap()
+        };
+        let settle = || tokio::time::sleep(Duration::from_millis(30));
+
+        tx.send(sample(now(), 0)).unwrap();
+        settle().await;
+        exporter.pause();
+        tx.send(sample(now(), 1)).unwrap();
+        settle().await;
+        exporter.resume();
+        tx.send(sample(now(), 2)).unwrap();
+        settle().await;
+        exporter.gaps().pause(GapCause::Disconnected);
+        let files = exporter.files();
+        let report = exporter.stop().await;
+
+        assert_eq!(report.written, 2);
+        assert!(report.error.is_none(), "{report:?}");
+        let counters: Vec<String> = fs::read_to_string(&files[0])
+            .unwrap()
+            .lines()
+            .skip(1)
+            .map(|row| row.split(',').nth(1).unwrap().to_string())
+            .collect();
+        assert_eq!(counters, ["0", "2"]);
+
+        let events = fs::read_to_string(dir.join("eeg_events.tsv")).unwrap();
+        let rows: Vec<Vec<&str>> = events.lines().map(|l| l.split('\t').collect()).collect();
+        assert_eq!(rows[0], ["onset", "duration", "trial_type", "cause"]);
+        assert_eq!(rows[1][2..], ["capture_gap", "paused"]);
+        assert!(rows[1][1].parse::<f64>().unwrap() >= 0.02, "{events}");
+        // Still open at stop: closed when the exporter finished.
+        assert_eq!(rows[2][3], "disconnected");
+        assert_eq!(rows.len(), 3);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[tokio::test]
+    async fn test_gzip_segments_decompress() {
+        use std::io::Read;
+
+        let dir = temp_dir("gzip");
+        let exporter = EegExporter::spawn(
+            futures_util::stream::iter(vec![sample(0, 7)]),
+            ExporterOptions {
+                dir: dir.clone(),
+                gzip: true,
+                ..ExporterOptions::default()
+            },
+        )
+        .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let files = exporter.files();
+        assert_eq!(exporter.stop().await.written, 1);
+
+        assert!(files[0].ends_with("eeg_0001.ndjson.gz"));
+        let mut text = String::new();
+        flate2::read::GzDecoder::new(File::open(&files[0]).unwrap())
+            .read_to_string(&mut text)
+            .unwrap();
+        assert!(text.contains("\"counter\":7"), "{text}");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,408 @@
+//! # Stream Supervisor
+//!
+//! Lets one long-lived process own the Cortex connection, session, and
+//! recording while analysis code runs in separate processes that can crash
+//! and restart without disturbing them.
+//!
+//! [`StreamSupervisor::start`] subscribes streams on an existing session
+//! and relays every event over a Unix domain socket. Any number of
+//! [`StreamConsumer`]s can attach, detach, and re-attach; the Cortex
+//! subscription stays up for as long as the supervisor runs.
+//!
+//! ```no_run
+//! use emotiv_cortex_v2::supervisor::{StreamConsumer, StreamSupervisor};
+//! # async fn owner(client: &emotiv_cortex_v2::ResilientClient, session_id: &str)
+//! #     -> emotiv_cortex_v2::CortexResult<()> {
+//! // Owner process:
+//! let supervisor =
+//!     StreamSupervisor::start(client, session_id, &["eeg", "met"], "/tmp/cortex.sock").await?;
+//! # let _ = supervisor;
+//! # Ok(())
+//! # }
+//! # async fn analysis() -> emotiv_cortex_v2::CortexResult<()> {
+//! // Analysis process:
+//! let mut consumer = StreamConsumer::connect("/tmp/cortex.sock").await?;
+//! while let Some(event) = consumer.next().await? {
+//!     let sample = event.parse()?;
+//!     println!("{sample:?}");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Wire Format
+//!
+//! Newline-delimited JSON, one object per stream event:
+//! `{"stream":"eeg","event":{"eeg":[...],"sid":"...","time":...}}`. The
+//! `event` is the unmodified Cortex payload, so non-Rust consumers can
+//! read the socket directly. Consumers that fall more than
+//! [`SupervisorConfig::consumer_buffer`] events behind skip the backlog
+//! and the skipped count is reported in [`SupervisorStats::dropped`].
+//!
+//! [`StreamSupervisor::stop`] relays the events still queued and lets
+//! every attached consumer receive its backlog before disconnecting it;
+//! lines a consumer could not be sent are reported as unflushed in the
+//! returned [`SinkReport`].
+//!
+//! Only Unix platforms are supported.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::UnixStream;
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
+
+use crate::error::{CortexError, CortexResult};
+use crate::fanout::{self, Counters, DEFAULT_CONSUMER_BUFFER};
+use crate::reconnect::ResilientClient;
+use crate::sink::{Sink, SinkReport};
+use crate::streams::{ParsedSample, parse_sample_value};
+
+/// Supervisor tuning.
+#[derive(Debug, Clone)]
+pub struct SupervisorConfig {
+    /// Events buffered per consumer before it starts skipping.
+    pub consumer_buffer: usize,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            consumer_buffer: DEFAULT_CONSUMER_BUFFER,
+        }
+    }
+}
+
+/// One stream event as relayed over the socket.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelayedEvent {
+    /// Cortex stream name.
+    pub stream: String,
+    /// The raw Cortex event.
+    pub event: Value,
+}
+
+impl RelayedEvent {
+    /// Parse the event with the same parsers as the `subscribe_*` helpers.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ProtocolError`] if the payload does not
+    /// match the stream's layout.
+    pub fn parse(&self) -> CortexResult<ParsedSample> {
+        parse_sample_value(&self.stream, &self.event).ok_or_else(|| CortexError::ProtocolError {
+            reason: format!("relayed '{}' event has an unexpected layout", self.stream),
+        })
+    }
+}
+
+/// Counters for a running [`StreamSupervisor`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SupervisorStats {
+    /// Consumers currently attached.
+    pub consumers: usize,
+    /// Consumers attached since start.
+    pub connections: u64,
+    /// Events received from Cortex.
+    pub events: u64,
+    /// Events skipped by consumers that fell behind.
+    pub dropped: u64,
+}
+
+// ─── Supervisor ──────────────────────────────────────────────────────────
+
+/// Relays subscribed streams to consumers over a Unix domain socket.
+pub struct StreamSupervisor {
+    path: PathBuf,
+    session_id: String,
+    streams: Vec<String>,
+    counters: Arc<Counters>,
+    /// Lines still queued for consumers that failed at shutdown.
+    undelivered: Arc<AtomicU64>,
+    /// Stops the relay tasks.
+    shutdown: watch::Sender<bool>,
+    relays: Vec<JoinHandle<()>>,
+    /// Stops accepting and disconnects consumers once relays are done.
+    disconnect: watch::Sender<bool>,
+    acceptor: JoinHandle<()>,
+}
+
+impl StreamSupervisor {
+    /// Subscribe `streams` on `session_id` and serve them at `path` with
+    /// the default [`SupervisorConfig`].
+    ///
+    /// # Errors
+    /// See [`Self::start_with_config`].
+    pub async fn start(
+        client: &ResilientClient,
+        session_id: &str,
+        streams: &[&str],
+        path: impl AsRef<Path>,
+    ) -> CortexResult<Self> {
+        Self::start_with_config(
+            client,
+            session_id,
+            streams,
+            path,
+            SupervisorConfig::default(),
+        )
+        .await
+    }
+
+    /// Subscribe `streams` on `session_id` and serve them at `path`.
+    ///
+    /// A stale socket file left by a crashed supervisor is replaced; a
+    /// socket another supervisor is still serving is not.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ConfigError`] if `path` is not a socket or is
+    /// served by another process, [`CortexError::Io`] if the socket cannot be bound, and any
+    /// error from subscribing, including per-stream subscribe failures.
+    pub async fn start_with_config(
+        client: &ResilientClient,
+        session_id: &str,
+        streams: &[&str],
+        path: impl AsRef<Path>,
+        config: SupervisorConfig,
+    ) -> CortexResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let listener = fanout::bind(&path).await?;
+
+        let mut receivers = client.create_stream_channels(streams).await;
+        let result = match client.subscribe_streams(session_id, streams).await {
+            Ok(result) => result,
+            Err(e) => {
+                let _ = std::fs::remove_file(&path);
+                return Err(e);
+            }
+        };
+        if let Some(failure) = result.failure.first() {
+            let _ = std::fs::remove_file(&path);
+            let subscribed: Vec<&str> = result
+                .success
+                .iter()
+                .map(|s| s.stream_name.as_str())
+                .collect();
+            if !subscribed.is_empty() {
+                let _ = client.unsubscribe_streams(session_id, &subscribed).await;
+            }
+            return Err(CortexError::from_api_error(
+                failure.code,
+                format!(
+                    "supervised stream '{}': {}",
+                    failure.stream_name, failure.message
+                ),
+            ));
+        }
+
+        let counters = Arc::new(Counters::default());
+        let undelivered = Arc::new(AtomicU64::new(0));
+        let (shutdown, _) = watch::channel(false);
+        let (disconnect, _) = watch::channel(false);
+        let (lines, _) = broadcast::channel(config.consumer_buffer.max(1));
+
+        let mut relays = Vec::with_capacity(streams.len());
+        for &stream in streams {
+            if let Some(rx) = receivers.remove(stream) {
+                relays.push(tokio::spawn(fanout::relay(
+                    stream.to_string(),
+                    rx,
+                    lines.clone(),
+                    Arc::clone(&counters),
+                    shutdown.subscribe(),
+                    encode_event,
+                )));
+            }
+        }
+        let acceptor = {
+            let counters = Arc::clone(&counters);
+            let undelivered = Arc::clone(&undelivered);
+            tokio::spawn(fanout::accept(
+                listener,
+                Arc::clone(&counters),
+                disconnect.subscribe(),
+                move |socket, shutdown| {
+                    serve(
+                        socket,
+                        lines.subscribe(),
+                        Arc::clone(&counters),
+                        Arc::clone(&undelivered),
+                        shutdown,
+                    )
+                },
+            ))
+        };
+
+        tracing::info!(path = %path.display(), streams = ?streams, "Stream supervisor started");
+        Ok(Self {
+            path,
+            session_id: session_id.to_string(),
+            streams: streams.iter().map(ToString::to_string).collect(),
+            counters,
+            undelivered,
+            shutdown,
+            relays,
+            disconnect,
+            acceptor,
+        })
+    }
+
+    /// Socket path consumers connect to.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Session the streams are subscribed on.
+    #[must_use]
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Streams being relayed.
+    #[must_use]
+    pub fn streams(&self) -> &[String] {
+        &self.streams
+    }
+
+    /// Current counters.
+    #[must_use]
+    pub fn stats(&self) -> SupervisorStats {
+        SupervisorStats {
+            consumers: self.counters.consumers.load(Ordering::Relaxed),
+            connections: self.counters.connections.load(Ordering::Relaxed),
+            events: self.counters.relayed.load(Ordering::Relaxed),
+            dropped: self.counters.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Stop relaying, deliver what is already queued to the attached
+    /// consumers, disconnect them, and remove the socket file. The streams
+    /// stay subscribed; unsubscribe them or close the session afterwards.
+    ///
+    /// The report counts relayed events as written, events skipped by
+    /// lagging consumers as dropped, and queued lines a consumer could no
+    /// longer be sent as unflushed.
+    pub async fn stop(self) -> SinkReport {
+        let mut report = SinkReport::new(format!("supervisor:{}", self.path.display()));
+        let _ = self.shutdown.send(true);
+        for task in self.relays {
+            if task.await.is_err() {
+                report.error = Some("relay task panicked".into());
+            }
+        }
+        let _ = self.disconnect.send(true);
+        if self.acceptor.await.is_err() {
+            report.error = Some("accept task panicked".into());
+        }
+        let _ = std::fs::remove_file(&self.path);
+
+        report.written = self.counters.relayed.load(Ordering::Relaxed);
+        report.dropped = self.counters.dropped.load(Ordering::Relaxed);
+        report.unflushed = self.undelivered.load(Ordering::Relaxed);
+        report
+    }
+}
+
+impl Sink for StreamSupervisor {
+    fn name(&self) -> String {
+        format!("supervisor:{}", self.path.display())
+    }
+
+    fn close(self: Box<Self>) -> BoxFuture<'static, Vec<SinkReport>> {
+        Box::pin(async move { vec![self.stop().await] })
+    }
+}
+
+/// Encode one event as a [`RelayedEvent`] line.
+fn encode_event(stream: &str, event: Value) -> Option<String> {
+    let relayed = RelayedEvent {
+        stream: stream.to_string(),
+        event,
+    };
+    serde_json::to_string(&relayed)
+        .inspect_err(|e| tracing::warn!(stream = %stream, error = %e, "Failed to encode event"))
+        .ok()
+}
+
+async fn serve(
+    mut socket: UnixStream,
+    mut lines: broadcast::Receiver<Arc<str>>,
+    counters: Arc<Counters>,
+    undelivered: Arc<AtomicU64>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    counters.consumers.fetch_add(1, Ordering::Relaxed);
+    loop {
+        tokio::select! {
+            line = fanout::recv(&mut lines, &counters) => {
+                let Some(line) = line else { break };
+                if socket.write_all(line.as_bytes()).await.is_err() {
+                    break;
+                }
+            }
+            _ = shutdown.changed() => {
+                drain(&mut socket, &mut lines, &counters, &undelivered).await;
+                break;
+            }
+        }
+    }
+    let _ = socket.shutdown().await;
+    counters.consumers.fetch_sub(1, Ordering::Relaxed);
+}
+
+/// Send a consumer the lines still queued for it at shutdown.
+async fn drain(
+    socket: &mut UnixStream,
+    lines: &mut broadcast::Receiver<Arc<str>>,
+    counters: &Counters,
+    undelivered: &AtomicU64,
+) {
+    while let Some(line) = fanout::try_recv(lines, counters) {
+        if socket.write_all(line.as_bytes()).await.is_err() {
+            let remaining = u64::try_from(lines.len()).unwrap_or(u64::MAX);
+            undelivered.fetch_add(remaining + 1, Ordering::Relaxed);
+            return;
+        }
+    }
+}
+
+// ─── Consumer ────────────────────────────────────────────────────────────
+
+/// Reads events relayed by a [`StreamSupervisor`].
+///
+/// Reconnecting after a crash is just another [`Self::connect`]; events
+/// sent while no consumer was attached are not replayed.
+pub struct StreamConsumer {
+    lines: Lines<BufReader<UnixStream>>,
+}
+
+impl StreamConsumer {
+    /// Attach to the supervisor at `path`.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::Io`] if the socket cannot be reached.
+    pub async fn connect(path: impl AsRef<Path>) -> CortexResult<Self> {
+        let socket = UnixStream::connect(path).await?;
+        Ok(Self {
+            lines: BufReader::new(socket).lines(),
+        })
+    }
+
+    /// Next relayed event, or `None` once the supervisor stops.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::Io`] on socket errors and
+    /// [`CortexError::Json`] for malformed lines.
+    pub async fn next(&mut self) -> CortexResult<Option<RelayedEvent>> {
+        match self.lines.next_line().await? {
+            Some(line) => Ok(Some(serde_json::from_str(&line)?)),
+            None => Ok(None),
+        }
+    }
+}
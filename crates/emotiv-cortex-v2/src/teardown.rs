@@ -0,0 +1,695 @@
+//! # Ordered Teardown
+//!
+//! Cortex resources depend on each other: a record belongs to a session,
+//! stream subscriptions belong to a session and a connection, and the
+//! session belongs to the connection. Releasing them in the wrong order
+//! truncates records or leaves sessions open.
+//!
+//! [`ResilientClient`] keeps a registry of the sessions it created and the
+//! subscriptions and records it started, and releases them in dependency
+//! order ([`TeardownStage::ORDER`]) whichever way it is torn down:
+//!
+//! - [`ResilientClient::shutdown`] / [`ResilientClient::disconnect`]
+//! - [`ResilientClient::shutdown_on`], e.g. with `tokio::signal::ctrl_c()`
+//! - dropping the client inside a Tokio runtime (best effort, in a
+//!   background task)
+//!
+//! Every step is bounded by [`TeardownConfig::step_timeout_secs`]; a step
+//! that fails or times out is recorded in the [`TeardownReport`] and the
+//! remaining steps still run. Sessions this client did not create are
+//! never closed.
+//!
+//! Last, [`TeardownStage::JoinTasks`] stops and awaits every background
+//! task the client spawned (reader loops, watchers, the health event
+//! forwarder), so nothing it started outlives it and the Tokio runtime
+//! can shut down right after. Tasks that do not stop in time are listed
+//! in [`TeardownReport::leaked_tasks`];
+//! [`ResilientClient::background_tasks`] shows what is running at any
+//! time.
+//!
+//! [Sinks](crate::sink) attached with [`ResilientClient::attach_sink`] are
+//! closed first, so data already received is written out before anything
+//! is released; their data-loss accounting ends up in
+//! [`TeardownReport::sinks`].
+//!
+//! [`ResilientClient`]: crate::ResilientClient
+//! [`ResilientClient::shutdown`]: crate::ResilientClient::shutdown
+//! [`ResilientClient::disconnect`]: crate::ResilientClient::disconnect
+//! [`ResilientClient::shutdown_on`]: crate::ResilientClient::shutdown_on
+//! [`ResilientClient::attach_sink`]: crate::ResilientClient::attach_sink
+//! [`ResilientClient::background_tasks`]: crate::ResilientClient::background_tasks
+//! [`TeardownConfig::step_timeout_secs`]: crate::config::TeardownConfig::step_timeout_secs
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::Serialize;
+use tokio::task::JoinHandle;
+
+use crate::client::CortexClient;
+use crate::error::CortexResult;
+use crate::sink::{Sink, SinkReport};
+
+// ─── Dependency Graph ────────────────────────────────────────────────────
+
+/// One stage of teardown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TeardownStage {
+    /// Close attached sinks, flushing their queues.
+    CloseSinks,
+    /// Stop records started by this client.
+    StopRecords,
+    /// Unsubscribe streams subscribed by this client.
+    Unsubscribe,
+    /// Close sessions created by this client.
+    CloseSessions,
+    /// Stop the background health monitor.
+    StopHealthMonitor,
+    /// Close the WebSocket connection.
+    Disconnect,
+    /// Stop and await the client's background tasks.
+    JoinTasks,
+}
+
+impl TeardownStage {
+    /// Execution order; every stage comes after the stages it
+    /// [depends on](Self::depends_on).
+    pub const ORDER: [TeardownStage; 7] = [
+        TeardownStage::CloseSinks,
+        TeardownStage::StopRecords,
+        TeardownStage::Unsubscribe,
+        TeardownStage::CloseSessions,
+        TeardownStage::StopHealthMonitor,
+        TeardownStage::Disconnect,
+        TeardownStage::JoinTasks,
+    ];
+
+    /// Stages that must finish before this one starts.
+    #[must_use]
+    pub fn depends_on(self) -> &'static [TeardownStage] {
+        match self {
+            // The health monitor only has to stop before the connection
+            // closes, or it would report the close as a failure.
+            TeardownStage::CloseSinks
+            | TeardownStage::StopRecords
+            | TeardownStage::StopHealthMonitor => &[],
+            // Sinks drain what their streams already delivered.
+            TeardownStage::Unsubscribe => &[TeardownStage::CloseSinks],
+            // Closing a session ends its record and subscriptions abruptly.
+            TeardownStage::CloseSessions => {
+                &[TeardownStage::StopRecords, TeardownStage::Unsubscribe]
+            }
+            // Every RPC stage needs the connection.
+            TeardownStage::Disconnect => &[
+                TeardownStage::StopRecords,
+                TeardownStage::Unsubscribe,
+                TeardownStage::CloseSessions,
+                TeardownStage::StopHealthMonitor,
+            ],
+            // The reader loop ends with the connection.
+            TeardownStage::JoinTasks => &[TeardownStage::Disconnect],
+        }
+    }
+}
+
+// ─── Report ──────────────────────────────────────────────────────────────
+
+/// Result of one teardown step.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "status", content = "detail")]
+pub enum TeardownOutcome {
+    /// The step completed.
+    Done,
+    /// The step returned an error.
+    Failed(String),
+    /// The step did not finish within the step timeout.
+    TimedOut,
+}
+
+/// One executed teardown step.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TeardownStep {
+    /// Stage the step belongs to.
+    pub stage: TeardownStage,
+    /// What the step acted on (session ID, stream list, ...).
+    pub target: String,
+    /// How it went.
+    pub outcome: TeardownOutcome,
+}
+
+/// Steps executed during teardown, in execution order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TeardownReport {
+    /// Executed steps.
+    pub steps: Vec<TeardownStep>,
+    /// Data-loss accounting of the sinks closed in
+    /// [`TeardownStage::CloseSinks`], one entry per output.
+    pub sinks: Vec<SinkReport>,
+    /// Background tasks still running when
+    /// [`TeardownStage::JoinTasks`] timed out.
+    pub leaked_tasks: Vec<String>,
+}
+
+impl TeardownReport {
+    /// Whether every step completed and no sink lost data.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.steps
+            .iter()
+            .all(|s| s.outcome == TeardownOutcome::Done)
+            && self.sinks.iter().all(SinkReport::is_lossless)
+            && self.leaked_tasks.is_empty()
+    }
+
+    /// Samples the closed sinks dropped or could not flush.
+    #[must_use]
+    pub fn lost_samples(&self) -> u64 {
+        self.sinks.iter().map(SinkReport::lost).sum()
+    }
+
+    /// Steps that failed or timed out.
+    #[must_use]
+    pub fn problems(&self) -> Vec<&TeardownStep> {
+        self.steps
+            .iter()
+            .filter(|s| s.outcome != TeardownOutcome::Done)
+            .collect()
+    }
+
+    pub(crate) async fn run<T>(
+        &mut self,
+        stage: TeardownStage,
+        target: impl Into<String>,
+        timeout: Duration,
+        step: impl Future<Output = CortexResult<T>>,
+    ) {
+        let target = target.into();
+        let outcome = match tokio::time::timeout(timeout, step).await {
+            Ok(Ok(_)) => TeardownOutcome::Done,
+            Ok(Err(e)) => TeardownOutcome::Failed(e.to_string()),
+            Err(_) => TeardownOutcome::TimedOut,
+        };
+        if outcome != TeardownOutcome::Done {
+            tracing::warn!(?stage, target, ?outcome, "Teardown step did not complete");
+        }
+        self.steps.push(TeardownStep {
+            stage,
+            target,
+            outcome,
+        });
+    }
+}
+
+/// Close `sinks` in attach order, one [`TeardownStage::CloseSinks`] step
+/// each. A sink that loses data is recorded as failed.
+pub(crate) async fn close_sinks(
+    sinks: Vec<Box<dyn Sink>>,
+    step_timeout: Duration,
+    report: &mut TeardownReport,
+) {
+    for sink in sinks {
+        let target = sink.name();
+        let outcome = match tokio::time::timeout(step_timeout, sink.close()).await {
+            Ok(closed) => {
+                let lost: u64 = closed.iter().map(SinkReport::lost).sum();
+                let errors: Vec<&str> = closed.iter().filter_map(|r| r.error.as_deref()).collect();
+                report.sinks.extend(closed.iter().cloned());
+                if lost > 0 {
+                    TeardownOutcome::Failed(format!("{lost} samples lost"))
+                } else if !errors.is_empty() {
+                    TeardownOutcome::Failed(errors.join("; "))
+                } else {
+                    TeardownOutcome::Done
+                }
+            }
+            Err(_) => TeardownOutcome::TimedOut,
+        };
+        if outcome != TeardownOutcome::Done {
+            tracing::warn!(target, ?outcome, "Sink did not close cleanly");
+        }
+        report.steps.push(TeardownStep {
+            stage: TeardownStage::CloseSinks,
+            target,
+            outcome,
+        });
+    }
+}
+
+// ─── Resource Registry ───────────────────────────────────────────────────
+
+/// What this client holds on one session.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct SessionResources {
+    /// Created by this client, so teardown may close it.
+    pub(crate) owned: bool,
+    /// Headset the session was created for, if created by this client.
+    pub(crate) headset: Option<String>,
+    /// Streams subscribed through this client.
+    pub(crate) streams: BTreeSet<String>,
+    /// A record started through this client is running.
+    pub(crate) recording: bool,
+    /// Known to be active: created with `status = "active"` or activated
+    /// since. Only these are probed by the session keep-alive.
+    pub(crate) activated: bool,
+    /// Since when an owned session has had no streams and no record.
+    idle: Option<IdleMark>,
+}
+
+/// Start of an idle period of an owned session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct IdleMark {
+    since: SystemTime,
+    started: Instant,
+    /// The idle alert for this period has been raised.
+    alerted: bool,
+}
+
+impl SessionResources {
+    fn is_empty(&self) -> bool {
+        !self.owned && self.streams.is_empty() && !self.recording
+    }
+
+    /// Start or end the idle period after a change.
+    fn refresh_idle(&mut self) {
+        let idle = self.owned && self.streams.is_empty() && !self.recording;
+        if !idle {
+            self.idle = None;
+        } else if self.idle.is_none() {
+            self.idle = Some(IdleMark {
+                since: SystemTime::now(),
+                started: Instant::now(),
+                alerted: false,
+            });
+        }
+    }
+}
+
+/// Streams a session had subscribed when its connection was replaced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LostSubscriptions {
+    pub(crate) session_id: String,
+    /// Headset to create a replacement session for, if known.
+    pub(crate) headset: Option<String>,
+    pub(crate) streams: Vec<String>,
+}
+
+/// Sessions, subscriptions, and records opened through a
+/// [`ResilientClient`](crate::ResilientClient).
+///
+/// The authoritative registry across reconnects: teardown and resubscribe
+/// read from here. Each [`CortexClient`](crate::CortexClient) also keeps
+/// the subscriptions made on its own socket, but only to unsubscribe them
+/// when that connection shuts down.
+#[derive(Debug, Default)]
+pub(crate) struct OpenResources {
+    sessions: Mutex<BTreeMap<String, SessionResources>>,
+}
+
+impl OpenResources {
+    fn update(&self, session_id: &str, f: impl FnOnce(&mut SessionResources)) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            let entry = sessions.entry(session_id.to_string()).or_default();
+            f(entry);
+            entry.refresh_idle();
+            if entry.is_empty() {
+                sessions.remove(session_id);
+            }
+        }
+    }
+
+    pub(crate) fn session_created(&self, session_id: &str, headset_id: &str, activated: bool) {
+        self.update(session_id, |s| {
+            s.owned = true;
+            s.headset = Some(headset_id.to_string());
+            s.activated = activated;
+        });
+    }
+
+    pub(crate) fn session_activated(&self, session_id: &str) {
+        self.update(session_id, |s| s.activated = true);
+    }
+
+    pub(crate) fn session_closed(&self, session_id: &str) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            sessions.remove(session_id);
+        }
+    }
+
+    pub(crate) fn subscribed<'a>(
+        &self,
+        session_id: &str,
+        streams: impl IntoIterator<Item = &'a str>,
+    ) {
+        self.update(session_id, |s| {
+            s.streams.extend(streams.into_iter().map(str::to_string));
+        });
+    }
+
+    pub(crate) fn unsubscribed(&self, session_id: &str, streams: &[&str]) {
+        self.update(session_id, |s| {
+            for stream in streams {
+                s.streams.remove(*stream);
+            }
+        });
+    }
+
+    pub(crate) fn record_started(&self, session_id: &str) {
+        self.update(session_id, |s| s.recording = true);
+    }
+
+    pub(crate) fn record_stopped(&self, session_id: &str) {
+        self.update(session_id, |s| s.recording = false);
+    }
+
+    /// Subscriptions are bound to the WebSocket connection and do not
+    /// survive a reconnect; sessions and records do. Returns the
+    /// subscriptions that were dropped.
+    pub(crate) fn connection_replaced(&self) -> Vec<LostSubscriptions> {
+        let Ok(mut sessions) = self.sessions.lock() else {
+            return Vec::new();
+        };
+        let mut lost = Vec::new();
+        for (session_id, s) in sessions.iter_mut() {
+            if !s.streams.is_empty() {
+                lost.push(LostSubscriptions {
+                    session_id: session_id.clone(),
+                    headset: s.headset.clone(),
+                    streams: std::mem::take(&mut s.streams).into_iter().collect(),
+                });
+            }
+            s.refresh_idle();
+        }
+        sessions.retain(|_, s| !s.is_empty());
+        lost
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.sessions.lock().map_or(true, |s| s.is_empty())
+    }
+
+    /// Sessions idle for at least `after` that have not been reported for
+    /// their current idle period, with the start of the period. Each
+    /// period is reported once.
+    pub(crate) fn take_idle(&self, after: Duration) -> Vec<(String, SystemTime)> {
+        let Ok(mut sessions) = self.sessions.lock() else {
+            return Vec::new();
+        };
+        sessions
+            .iter_mut()
+            .filter_map(|(id, s)| {
+                let mark = s.idle.as_mut()?;
+                if mark.alerted || mark.started.elapsed() < after {
+                    return None;
+                }
+                mark.alerted = true;
+                Some((id.clone(), mark.since))
+            })
+            .collect()
+    }
+
+    /// Owned sessions known to be active, for the keep-alive probe.
+    pub(crate) fn activated_sessions(&self) -> Vec<String> {
+        self.sessions.lock().map_or_else(
+            |_| Vec::new(),
+            |sessions| {
+                sessions
+                    .iter()
+                    .filter(|(_, s)| s.owned && s.activated)
+                    .map(|(id, _)| id.clone())
+                    .collect()
+            },
+        )
+    }
+
+    /// Remove and return everything, leaving the registry empty.
+    pub(crate) fn take(&self) -> BTreeMap<String, SessionResources> {
+        self.sessions
+            .lock()
+            .map(|mut s| std::mem::take(&mut *s))
+            .unwrap_or_default()
+    }
+}
+
+/// Run the RPC stages ([`TeardownStage::StopRecords`],
+/// [`TeardownStage::Unsubscribe`], [`TeardownStage::CloseSessions`]) for
+/// `sessions` on `client`, in [`TeardownStage::ORDER`].
+///
+/// Calls go straight to `client`, without reconnecting: a dead
+/// connection fails each step fast instead of reconnecting only to tear
+/// down again.
+pub(crate) async fn release(
+    client: Arc<CortexClient>,
+    token: &str,
+    sessions: &BTreeMap<String, SessionResources>,
+    step_timeout: Duration,
+    report: &mut TeardownReport,
+) {
+    for stage in TeardownStage::ORDER {
+        for (session_id, held) in sessions {
+            match stage {
+                TeardownStage::StopRecords if held.recording => {
+                    report
+                        .run(
+                            stage,
+                            session_id.as_str(),
+                            step_timeout,
+                            client.stop_record(token, session_id),
+                        )
+                        .await;
+                }
+                TeardownStage::Unsubscribe if !held.streams.is_empty() => {
+                    let streams: Vec<&str> = held.streams.iter().map(String::as_str).collect();
+                    report
+                        .run(
+                            stage,
+                            format!("{session_id}: {}", streams.join(",")),
+                            step_timeout,
+                            client.unsubscribe_streams(token, session_id, &streams),
+                        )
+                        .await;
+                }
+                TeardownStage::CloseSessions if held.owned => {
+                    report
+                        .run(
+                            stage,
+                            session_id.as_str(),
+                            step_timeout,
+                            client.close_session(token, session_id),
+                        )
+                        .await;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// ─── Task Registry ───────────────────────────────────────────────────────
+
+/// Named handles of the background tasks a
+/// [`ResilientClient`](crate::ResilientClient) spawned.
+#[derive(Debug, Default)]
+pub(crate) struct TaskRegistry {
+    tasks: Mutex<TaskList>,
+}
+
+#[derive(Debug, Default)]
+struct TaskList {
+    running: Vec<(&'static str, JoinHandle<()>)>,
+    /// Joined; tasks registered from now on are aborted at once.
+    closed: bool,
+}
+
+impl TaskRegistry {
+    /// Spawn `task` as `name`.
+    pub(crate) fn spawn<F>(&self, name: &'static str, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.adopt(name, tokio::spawn(task));
+    }
+
+    /// Track an already spawned task as `name`.
+    pub(crate) fn adopt(&self, name: &'static str, handle: JoinHandle<()>) {
+        let Ok(mut tasks) = self.tasks.lock() else {
+            return;
+        };
+        if tasks.closed {
+            handle.abort();
+            return;
+        }
+        tasks.running.retain(|(_, h)| !h.is_finished());
+        tasks.running.push((name, handle));
+    }
+
+    /// Names of the tasks still running.
+    pub(crate) fn running(&self) -> Vec<String> {
+        self.tasks.lock().map_or_else(
+            |_| Vec::new(),
+            |tasks| {
+                tasks
+                    .running
+                    .iter()
+                    .filter(|(_, h)| !h.is_finished())
+                    .map(|(name, _)| (*name).to_string())
+                    .collect()
+            },
+        )
+    }
+
+    /// Abort every task and wait up to `timeout` for all of them to end.
+    /// Returns the names of those that did not.
+    pub(crate) async fn join_all(&self, timeout: Duration) -> Vec<String> {
+        let running = match self.tasks.lock() {
+            Ok(mut tasks) => {
+                tasks.closed = true;
+                std::mem::take(&mut tasks.running)
+            }
+            Err(_) => return Vec::new(),
+        };
+        for (_, handle) in &running {
+            handle.abort();
+        }
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut leaked = Vec::new();
+        for (name, handle) in running {
+            if tokio::time::timeout_at(deadline, handle).await.is_err() {
+                leaked.push(name.to_string());
+            }
+        }
+        leaked
+    }
+}
+
+/// Run [`TeardownStage::JoinTasks`] for `tasks`.
+pub(crate) async fn join_tasks(
+    tasks: &TaskRegistry,
+    step_timeout: Duration,
+    report: &mut TeardownReport,
+) {
+    let leaked = tasks.join_all(step_timeout).await;
+    let outcome = if leaked.is_empty() {
+        TeardownOutcome::Done
+    } else {
+        tracing::warn!(?leaked, "Background tasks did not stop");
+        TeardownOutcome::TimedOut
+    };
+    report.steps.push(TeardownStep {
+        stage: TeardownStage::JoinTasks,
+        target: "background tasks".into(),
+        outcome,
+    });
+    report.leaked_tasks = leaked;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_respects_dependencies() {
+        for (i, stage) in TeardownStage::ORDER.iter().enumerate() {
+            for dep in stage.depends_on() {
+                let dep_index = TeardownStage::ORDER.iter().position(|s| s == dep).unwrap();
+                assert!(dep_index < i, "{stage:?} runs before {dep:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_registry_tracks_and_forgets_resources() {
+        let resources = OpenResources::default();
+        resources.session_created("owned", "HS-1", true);
+        resources.subscribed("owned", ["eeg", "met"]);
+        resources.record_started("owned");
+        resources.subscribed("foreign", ["pow"]);
+
+        resources.unsubscribed("owned", &["met"]);
+        resources.unsubscribed("foreign", &["pow"]);
+        assert!(!resources.is_empty());
+
+        let lost = resources.connection_replaced();
+        assert_eq!(
+            lost,
+            [LostSubscriptions {
+                session_id: "owned".into(),
+                headset: Some("HS-1".into()),
+                streams: vec!["eeg".into()],
+            }]
+        );
+        let held = resources.take();
+        assert_eq!(held.len(), 1);
+        assert_eq!(
+            held["owned"],
+            SessionResources {
+                owned: true,
+                headset: Some("HS-1".into()),
+                streams: BTreeSet::new(),
+                recording: true,
+                activated: true,
+                idle: None,
+            }
+        );
+        assert!(resources.is_empty());
+    }
+
+    #[test]
+    fn test_open_sessions_join_keep_alive_once_activated() {
+        let resources = OpenResources::default();
+        resources.session_created("active", "HS-1", true);
+        resources.session_created("open", "HS-2", false);
+        resources.session_activated("foreign");
+        assert_eq!(resources.activated_sessions(), ["active"]);
+
+        resources.session_activated("open");
+        assert_eq!(resources.activated_sessions(), ["active", "open"]);
+        resources.session_closed("active");
+        assert_eq!(resources.activated_sessions(), ["open"]);
+    }
+
+    #[tokio::test]
+    async fn test_task_registry_joins_and_refuses_late_tasks() {
+        let tasks = TaskRegistry::default();
+        tasks.spawn("pending", std::future::pending());
+        tasks.spawn("done", async {});
+        tokio::task::yield_now().await;
+        assert_eq!(tasks.running(), ["pending"]);
+
+        let leaked = tasks.join_all(Duration::from_secs(1)).await;
+        assert!(leaked.is_empty(), "{leaked:?}");
+        assert!(tasks.running().is_empty());
+
+        tasks.spawn("late", std::future::pending());
+        assert!(tasks.running().is_empty());
+    }
+
+    #[test]
+    fn test_idle_sessions_are_reported_once_per_idle_period() {
+        let resources = OpenResources::default();
+        resources.session_created("idle", "HS-1", true);
+        resources.session_created("busy", "HS-2", true);
+        resources.subscribed("busy", ["eeg"]);
+        resources.subscribed("foreign", ["eeg"]);
+
+        assert!(resources.take_idle(Duration::from_secs(60)).is_empty());
+        let idle = resources.take_idle(Duration::ZERO);
+        assert_eq!(idle.len(), 1);
+        assert_eq!(idle[0].0, "idle");
+        assert!(resources.take_idle(Duration::ZERO).is_empty());
+
+        // Starting a record ends the period; stopping it starts a new one.
+        resources.record_started("idle");
+        resources.record_stopped("idle");
+        assert_eq!(resources.take_idle(Duration::ZERO).len(), 1);
+
+        // Subscriptions do not survive a reconnect.
+        resources.connection_replaced();
+        let idle = resources.take_idle(Duration::ZERO);
+        assert_eq!(idle.len(), 1);
+        assert_eq!(idle[0].0, "busy");
+    }
+}
@@ -0,0 +1,316 @@
+//! # Telemetry Export
+//!
+//! With the `otel` feature enabled, the client reports RPC traces and
+//! acquisition metrics through the
+//! [`opentelemetry`](https://docs.rs/opentelemetry) API. The crate does
+//! not install an exporter: install an OTLP tracer/meter provider in your
+//! application (e.g. with `opentelemetry-otlp`) **before connecting**, and
+//! the data flows to your collector with no further glue.
+//!
+//! With the `metrics` feature enabled, the same metrics are emitted
+//! through the [`metrics`](https://docs.rs/metrics) facade, to whatever
+//! recorder the application installs (e.g. `metrics-exporter-prometheus`).
+//! Both features can be enabled together.
+//!
+//! Without either feature every hook compiles to a no-op.
+//!
+//! ## Traces
+//!
+//! One client span per JSON-RPC call, named after the Cortex method
+//! (`subscribe`, `createSession`, …) with `rpc.system = "jsonrpc"`,
+//! `rpc.method`, and on failure an error status plus
+//! `rpc.jsonrpc.error_code` for Cortex API errors.
+//!
+//! ## Metrics
+//!
+//! | Instrument | Kind | Attributes |
+//! |------------|------|------------|
+//! | [`RPC_DURATION`] | histogram (s) | `rpc.method`, `outcome` |
+//! | [`STREAM_SAMPLES`] | counter | `stream` |
+//! | [`STREAM_DROPS`] | counter | `stream`, `reason` (`full` / `closed`) |
+//! | [`STREAM_SUBSCRIPTIONS`] | up/down counter | `stream` |
+//! | [`RECONNECT_ATTEMPTS`] | counter | — |
+//! | [`RECONNECTS`] | counter | — |
+//! | [`TOKEN_REFRESHES`] | counter | `outcome` (`ok` / `error`) |
+//!
+//! Through the `metrics` facade, up/down counters are gauges. RPC
+//! outcomes are `ok`, `timeout` or `error`.
+//!
+//! Sample rates are the per-second rate of [`STREAM_SAMPLES`] as computed
+//! by the collector or backend.
+
+/// Instrumentation scope name used for the tracer and meter.
+pub const INSTRUMENTATION_SCOPE: &str = "emotiv-cortex-v2";
+
+/// Histogram of JSON-RPC call latency in seconds.
+pub const RPC_DURATION: &str = "cortex.rpc.duration";
+
+/// Counter of stream samples delivered to subscribers.
+pub const STREAM_SAMPLES: &str = "cortex.stream.samples";
+
+/// Counter of stream samples dropped by the reader loop.
+pub const STREAM_DROPS: &str = "cortex.stream.drops";
+
+/// Up/down counter of active stream subscriptions.
+pub const STREAM_SUBSCRIPTIONS: &str = "cortex.stream.subscriptions";
+
+/// Counter of `ResilientClient` reconnect attempts, successful or not.
+pub const RECONNECT_ATTEMPTS: &str = "cortex.reconnect.attempts";
+
+/// Counter of successful `ResilientClient` reconnects.
+pub const RECONNECTS: &str = "cortex.reconnects";
+
+/// Counter of `ResilientClient` token refreshes.
+pub const TOKEN_REFRESHES: &str = "cortex.token.refreshes";
+
+use std::time::Instant;
+
+use crate::error::CortexError;
+
+/// An RPC call being measured.
+pub(crate) struct RpcSpan {
+    method: &'static str,
+    started: Instant,
+    #[cfg(feature = "otel")]
+    span: opentelemetry::global::BoxedSpan,
+}
+
+pub(crate) fn rpc_started(method: &'static str) -> RpcSpan {
+    RpcSpan {
+        method,
+        started: Instant::now(),
+        #[cfg(feature = "otel")]
+        span: otel::rpc_span(method),
+    }
+}
+
+#[cfg_attr(
+    not(any(feature = "otel", feature = "metrics")),
+    allow(unused_variables)
+)]
+// The span is only consumed when it holds an OpenTelemetry span.
+#[cfg_attr(not(feature = "otel"), allow(clippy::needless_pass_by_value))]
+pub(crate) fn rpc_finished<T>(call: RpcSpan, result: &Result<T, CortexError>) {
+    let RpcSpan {
+        method,
+        started,
+        #[cfg(feature = "otel")]
+        span,
+    } = call;
+    let seconds = started.elapsed().as_secs_f64();
+    let outcome = match result {
+        Ok(_) => "ok",
+        Err(CortexError::Timeout { .. }) => "timeout",
+        Err(_) => "error",
+    };
+    #[cfg(feature = "otel")]
+    otel::rpc_finished(span, method, seconds, outcome, result);
+    #[cfg(feature = "metrics")]
+    metrics::histogram!(RPC_DURATION, "rpc.method" => method, "outcome" => outcome).record(seconds);
+}
+
+#[cfg_attr(
+    not(any(feature = "otel", feature = "metrics")),
+    allow(unused_variables)
+)]
+#[inline]
+pub(crate) fn stream_sample_delivered(stream: &'static str) {
+    #[cfg(feature = "otel")]
+    otel::stream_sample_delivered(stream);
+    #[cfg(feature = "metrics")]
+    metrics::counter!(STREAM_SAMPLES, "stream" => stream).increment(1);
+}
+
+#[cfg_attr(
+    not(any(feature = "otel", feature = "metrics")),
+    allow(unused_variables)
+)]
+#[inline]
+pub(crate) fn stream_sample_dropped(stream: &'static str, reason: &'static str) {
+    #[cfg(feature = "otel")]
+    otel::stream_sample_dropped(stream, reason);
+    #[cfg(feature = "metrics")]
+    metrics::counter!(STREAM_DROPS, "stream" => stream, "reason" => reason).increment(1);
+}
+
+#[cfg_attr(
+    not(any(feature = "otel", feature = "metrics")),
+    allow(unused_variables)
+)]
+#[inline]
+pub(crate) fn stream_subscribed(stream: &str) {
+    #[cfg(feature = "otel")]
+    otel::stream_subscriptions(stream, 1);
+    #[cfg(feature = "metrics")]
+    metrics::gauge!(STREAM_SUBSCRIPTIONS, "stream" => stream.to_string()).increment(1.0);
+}
+
+#[cfg_attr(
+    not(any(feature = "otel", feature = "metrics")),
+    allow(unused_variables)
+)]
+#[inline]
+pub(crate) fn stream_unsubscribed(stream: &str) {
+    #[cfg(feature = "otel")]
+    otel::stream_subscriptions(stream, -1);
+    #[cfg(feature = "metrics")]
+    metrics::gauge!(STREAM_SUBSCRIPTIONS, "stream" => stream.to_string()).decrement(1.0);
+}
+
+#[inline]
+pub(crate) fn reconnect_attempted() {
+    #[cfg(feature = "otel")]
+    otel::instruments().reconnect_attempts.add(1, &[]);
+    #[cfg(feature = "metrics")]
+    metrics::counter!(RECONNECT_ATTEMPTS).increment(1);
+}
+
+#[inline]
+pub(crate) fn reconnected() {
+    #[cfg(feature = "otel")]
+    otel::instruments().reconnects.add(1, &[]);
+    #[cfg(feature = "metrics")]
+    metrics::counter!(RECONNECTS).increment(1);
+}
+
+#[cfg_attr(
+    not(any(feature = "otel", feature = "metrics")),
+    allow(unused_variables)
+)]
+#[inline]
+pub(crate) fn token_refreshed(ok: bool) {
+    let outcome = if ok { "ok" } else { "error" };
+    #[cfg(feature = "otel")]
+    otel::token_refreshed(outcome);
+    #[cfg(feature = "metrics")]
+    metrics::counter!(TOKEN_REFRESHES, "outcome" => outcome).increment(1);
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use std::sync::OnceLock;
+
+    use opentelemetry::KeyValue;
+    use opentelemetry::global::{self, BoxedSpan};
+    use opentelemetry::metrics::{Counter, Histogram, UpDownCounter};
+    use opentelemetry::trace::{Span, SpanKind, Status, Tracer};
+
+    use super::{
+        INSTRUMENTATION_SCOPE, RECONNECT_ATTEMPTS, RECONNECTS, RPC_DURATION, STREAM_DROPS,
+        STREAM_SAMPLES, STREAM_SUBSCRIPTIONS, TOKEN_REFRESHES,
+    };
+    use crate::error::CortexError;
+
+    pub(crate) struct Instruments {
+        rpc_duration: Histogram<f64>,
+        stream_samples: Counter<u64>,
+        stream_drops: Counter<u64>,
+        stream_subscriptions: UpDownCounter<i64>,
+        pub(crate) reconnect_attempts: Counter<u64>,
+        pub(crate) reconnects: Counter<u64>,
+        token_refreshes: Counter<u64>,
+    }
+
+    /// Instruments are created from the global meter provider on first
+    /// use, which is why providers must be installed before connecting.
+    pub(crate) fn instruments() -> &'static Instruments {
+        static INSTRUMENTS: OnceLock<Instruments> = OnceLock::new();
+        INSTRUMENTS.get_or_init(|| {
+            let meter = global::meter(INSTRUMENTATION_SCOPE);
+            Instruments {
+                rpc_duration: meter
+                    .f64_histogram(RPC_DURATION)
+                    .with_unit("s")
+                    .with_description("Cortex JSON-RPC call latency")
+                    .build(),
+                stream_samples: meter
+                    .u64_counter(STREAM_SAMPLES)
+                    .with_description("Stream samples delivered to subscribers")
+                    .build(),
+                stream_drops: meter
+                    .u64_counter(STREAM_DROPS)
+                    .with_description("Stream samples dropped by the reader loop")
+                    .build(),
+                stream_subscriptions: meter
+                    .i64_up_down_counter(STREAM_SUBSCRIPTIONS)
+                    .with_description("Active stream subscriptions")
+                    .build(),
+                reconnect_attempts: meter
+                    .u64_counter(RECONNECT_ATTEMPTS)
+                    .with_description("Reconnect attempts")
+                    .build(),
+                reconnects: meter
+                    .u64_counter(RECONNECTS)
+                    .with_description("Successful reconnects")
+                    .build(),
+                token_refreshes: meter
+                    .u64_counter(TOKEN_REFRESHES)
+                    .with_description("Cortex token refreshes")
+                    .build(),
+            }
+        })
+    }
+
+    pub(crate) fn rpc_span(method: &'static str) -> BoxedSpan {
+        let tracer = global::tracer(INSTRUMENTATION_SCOPE);
+        tracer
+            .span_builder(method)
+            .with_kind(SpanKind::Client)
+            .with_attributes([
+                KeyValue::new("rpc.system", "jsonrpc"),
+                KeyValue::new("rpc.method", method),
+            ])
+            .start(&tracer)
+    }
+
+    pub(crate) fn rpc_finished<T>(
+        mut span: BoxedSpan,
+        method: &'static str,
+        seconds: f64,
+        outcome: &'static str,
+        result: &Result<T, CortexError>,
+    ) {
+        if let Err(e) = result {
+            if let Some(code) = e.error_code() {
+                span.set_attribute(KeyValue::new("rpc.jsonrpc.error_code", i64::from(code)));
+            }
+            span.set_status(Status::error(e.to_string()));
+        }
+        span.end();
+        instruments().rpc_duration.record(
+            seconds,
+            &[
+                KeyValue::new("rpc.method", method),
+                KeyValue::new("outcome", outcome),
+            ],
+        );
+    }
+
+    pub(crate) fn stream_sample_delivered(stream: &'static str) {
+        instruments()
+            .stream_samples
+            .add(1, &[KeyValue::new("stream", stream)]);
+    }
+
+    pub(crate) fn stream_sample_dropped(stream: &'static str, reason: &'static str) {
+        instruments().stream_drops.add(
+            1,
+            &[
+                KeyValue::new("stream", stream),
+                KeyValue::new("reason", reason),
+            ],
+        );
+    }
+
+    pub(crate) fn stream_subscriptions(stream: &str, delta: i64) {
+        instruments()
+            .stream_subscriptions
+            .add(delta, &[KeyValue::new("stream", stream.to_string())]);
+    }
+
+    pub(crate) fn token_refreshed(outcome: &'static str) {
+        instruments()
+            .token_refreshes
+            .add(1, &[KeyValue::new("outcome", outcome)]);
+    }
+}
@@ -0,0 +1,342 @@
+//! # Deterministic Test Signals
+//!
+//! Generators for typed stream samples shaped like a given
+//! [`HeadsetModel`]'s streams, for unit tests of downstream pipelines
+//! (filters, recorders, aligners) that need exact expected outputs
+//! without a headset or a Cortex service.
+//!
+//! Every generator is a pure function of its arguments: the same
+//! [`GeneratorSpec`] (including [`seed`](GeneratorSpec::seed)) always
+//! yields bit-identical samples.
+//!
+//! ```
+//! use std::time::Duration;
+//! use emotiv_cortex_v2::HeadsetModel;
+//! use emotiv_cortex_v2::testing::{GeneratorSpec, Sine, gen_eeg_samples};
+//!
+//! let spec = GeneratorSpec {
+//!     sines: vec![Sine { freq_hz: 10.0, amplitude_uv: 20.0 }],
+//!     noise_uv: 0.0,
+//!     ..GeneratorSpec::default()
+//! };
+//! let eeg = gen_eeg_samples(&HeadsetModel::Insight, Duration::from_secs(2), &spec);
+//! assert_eq!(eeg.len(), 256);
+//! assert_eq!(eeg[0].channels.len(), 5);
+//!
+//! // Deterministic: the same spec gives the same samples.
+//! let again = gen_eeg_samples(&HeadsetModel::Insight, Duration::from_secs(2), &spec);
+//! assert_eq!(eeg[100].channels, again[100].channels);
+//! ```
+
+use std::f64::consts::TAU;
+use std::time::Duration;
+
+use crate::headset::HeadsetModel;
+use crate::protocol::streams::{BandPowerData, EegData, EegQuality};
+use crate::util::{f64_to_f32, f64_to_i64, f64_to_u64, u64_to_f64, usize_to_f64};
+
+/// Rate of the generated `pow` samples, matching Cortex.
+pub const POW_RATE_HZ: f64 = 8.0;
+
+/// Rate of the generated `eq` samples, matching Cortex.
+pub const EQ_RATE_HZ: f64 = 2.0;
+
+/// Default DC offset of generated EEG, close to what Emotiv headsets
+/// report.
+pub const DEFAULT_DC_OFFSET_UV: f32 = 4200.0;
+
+/// Band edges in Hz for [`BandPowerData::channel_powers`]
+/// (theta, alpha, betaL, betaH, gamma).
+const BAND_EDGES_HZ: [(f64, f64); 5] = [
+    (4.0, 8.0),
+    (8.0, 12.0),
+    (12.0, 16.0),
+    (16.0, 25.0),
+    (25.0, 45.0),
+];
+
+/// One sinusoidal component of the generated signal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sine {
+    /// Frequency in Hz.
+    pub freq_hz: f64,
+    /// Peak amplitude in microvolts.
+    pub amplitude_uv: f32,
+}
+
+/// What the generators produce.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratorSpec {
+    /// Seed of the noise generator.
+    pub seed: u64,
+    /// Sinusoids summed into every channel. Each channel's phase is
+    /// shifted by `channel * π / 8` so channels are distinguishable.
+    pub sines: Vec<Sine>,
+    /// Peak amplitude of the uniform noise added to every EEG value, in
+    /// microvolts.
+    pub noise_uv: f32,
+    /// Constant added to every EEG value, in microvolts.
+    pub dc_offset_uv: f32,
+    /// Timestamp of the first sample, in microseconds.
+    pub start_timestamp: i64,
+    /// Channels with no contact: their EEG is noise only (ten times
+    /// [`noise_uv`](Self::noise_uv)), their band power is noise floor,
+    /// and their `eq` quality is 0.
+    pub bad_channels: Vec<usize>,
+}
+
+impl Default for GeneratorSpec {
+    /// 10 Hz alpha at 10 µV with 2 µV of noise, seed 0.
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            sines: vec![Sine {
+                freq_hz: 10.0,
+                amplitude_uv: 10.0,
+            }],
+            noise_uv: 2.0,
+            dc_offset_uv: DEFAULT_DC_OFFSET_UV,
+            start_timestamp: 0,
+            bad_channels: Vec::new(),
+        }
+    }
+}
+
+impl GeneratorSpec {
+    fn is_bad(&self, channel: usize) -> bool {
+        self.bad_channels.contains(&channel)
+    }
+}
+
+/// Generate `duration` of `eeg` samples at the model's sampling rate.
+///
+/// The counter wraps at the sampling rate, as on the headsets.
+#[must_use]
+pub fn gen_eeg_samples(
+    model: &HeadsetModel,
+    duration: Duration,
+    spec: &GeneratorSpec,
+) -> Vec<EegData> {
+    let rate = model.sampling_rate_hz();
+    let num_channels = model.num_channels();
+    let mut noise = SplitMix64::new(spec.seed);
+
+    (0..sample_count(duration, rate))
+        .map(|i| {
+            let t = u64_to_f64(i) / rate;
+            let channels = (0..num_channels)
+                .map(|channel| {
+                    let noise_uv = if spec.is_bad(channel) {
+                        spec.noise_uv * 10.0
+                    } else {
+                        spec.noise_uv
+                    };
+                    let mut value = f64::from(spec.dc_offset_uv)
+                        + f64::from(noise_uv) * noise.next_signed_unit();
+                    if !spec.is_bad(channel) {
+                        let phase = usize_to_f64(channel) * std::f64::consts::PI / 8.0;
+                        for sine in &spec.sines {
+                            value += f64::from(sine.amplitude_uv)
+                                * (TAU * sine.freq_hz * t + phase).sin();
+                        }
+                    }
+                    f64_to_f32(value)
+                })
+                .collect();
+            EegData {
+                timestamp: timestamp_at(spec, i, rate),
+                counter: u32::try_from(i % f64_to_u64(rate).max(1)).unwrap_or(0),
+                interpolated: false,
+                channels,
+                raw_cq: if spec.bad_channels.is_empty() {
+                    4.0
+                } else {
+                    2.0
+                },
+            }
+        })
+        .collect()
+}
+
+/// Generate `duration` of `pow` samples at [`POW_RATE_HZ`].
+///
+/// Each sine contributes its mean power (`amplitude² / 2`) to the band
+/// containing its frequency, on top of a noise floor of `noise_uv² / 3`
+/// per band. Bad channels get the noise floor only. With
+/// [`noise_uv`](GeneratorSpec::noise_uv) zero the values are exact.
+#[must_use]
+pub fn gen_band_power_samples(
+    model: &HeadsetModel,
+    duration: Duration,
+    spec: &GeneratorSpec,
+) -> Vec<BandPowerData> {
+    let floor = f64::from(spec.noise_uv).powi(2) / 3.0;
+    let mut powers = [floor; 5];
+    for sine in &spec.sines {
+        if let Some(band) = BAND_EDGES_HZ
+            .iter()
+            .position(|(low, high)| (*low..*high).contains(&sine.freq_hz))
+        {
+            powers[band] += f64::from(sine.amplitude_uv).powi(2) / 2.0;
+        }
+    }
+    let good = powers.map(f64_to_f32);
+    let bad = [f64_to_f32(floor); 5];
+
+    (0..sample_count(duration, POW_RATE_HZ))
+        .map(|i| BandPowerData {
+            timestamp: timestamp_at(spec, i, POW_RATE_HZ),
+            channel_powers: (0..model.num_channels())
+                .map(|channel| if spec.is_bad(channel) { bad } else { good })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Generate `duration` of `eq` samples at [`EQ_RATE_HZ`]: full battery,
+/// quality 1.0 on every channel except the
+/// [`bad_channels`](GeneratorSpec::bad_channels).
+#[must_use]
+pub fn gen_eq_samples(
+    model: &HeadsetModel,
+    duration: Duration,
+    spec: &GeneratorSpec,
+) -> Vec<EegQuality> {
+    let num_channels = model.num_channels();
+    let sensor_quality: Vec<f32> = (0..num_channels)
+        .map(|channel| if spec.is_bad(channel) { 0.0 } else { 1.0 })
+        .collect();
+    let good = sensor_quality.iter().filter(|q| **q > 0.0).count();
+    let overall = f64_to_f32(usize_to_f64(good) / usize_to_f64(num_channels.max(1)));
+
+    (0..sample_count(duration, EQ_RATE_HZ))
+        .map(|_| EegQuality {
+            battery_percent: 100,
+            overall,
+            sample_rate_quality: 1.0,
+            sensor_quality: sensor_quality.clone(),
+        })
+        .collect()
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────────
+
+/// `SplitMix64`: tiny, fast, and identical on every platform.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[-1, 1)`.
+    fn next_signed_unit(&mut self) -> f64 {
+        // 53 random bits -> [0, 1)
+        let unit = u64_to_f64(self.next_u64() >> 11) / u64_to_f64(1 << 53);
+        unit * 2.0 - 1.0
+    }
+}
+
+fn sample_count(duration: Duration, rate: f64) -> u64 {
+    f64_to_u64((duration.as_secs_f64() * rate).round())
+}
+
+fn timestamp_at(spec: &GeneratorSpec, index: u64, rate: f64) -> i64 {
+    spec.start_timestamp + f64_to_i64((u64_to_f64(index) * 1_000_000.0 / rate).round())
+}
+
+/// A non-interpolated EEG sample with full contact quality, for unit tests
+/// that build samples by hand.
+#[cfg(test)]
+pub(crate) fn eeg_sample(timestamp: i64, counter: u32, channels: Vec<f32>) -> EegData {
+    EegData {
+        timestamp,
+        counter,
+        interpolated: false,
+        channels,
+        raw_cq: 4.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eeg_is_seeded_and_shaped_like_the_model() {
+        let spec = GeneratorSpec::default();
+        let a = gen_eeg_samples(&HeadsetModel::EpocX, Duration::from_secs(1), &spec);
+        let b = gen_eeg_samples(&HeadsetModel::EpocX, Duration::from_secs(1), &spec);
+        assert_eq!(a.len(), 256);
+        assert!(a.iter().all(|s| s.channels.len() == 14));
+        assert_eq!(a[255].counter, 255);
+        assert_eq!(a[255].timestamp, 996_094);
+        for (x, y) in a.iter().zip(&b) {
+            assert_eq!(x.channels, y.channels);
+        }
+
+        let other = gen_eeg_samples(
+            &HeadsetModel::EpocX,
+            Duration::from_secs(1),
+            &GeneratorSpec { seed: 1, ..spec },
+        );
+        assert_ne!(a[0].channels, other[0].channels);
+    }
+
+    #[test]
+    fn test_noiseless_eeg_is_exact_sine() {
+        let spec = GeneratorSpec {
+            noise_uv: 0.0,
+            dc_offset_uv: 0.0,
+            start_timestamp: 1_000_000,
+            ..GeneratorSpec::default()
+        };
+        let eeg = gen_eeg_samples(&HeadsetModel::Insight, Duration::from_secs(1), &spec);
+        assert_eq!(eeg[0].timestamp, 1_000_000);
+        assert!(eeg[0].channels[0].abs() < 1e-6);
+        // Sample 32 is at 0.25 s, i.e. 2.5 periods of 10 Hz.
+        assert!(eeg[32].channels[0].abs() < 1e-4);
+        let peak = eeg.iter().map(|s| s.channels[0]).fold(f32::MIN, f32::max);
+        assert!((peak - 10.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_band_power_and_quality_follow_spec() {
+        let spec = GeneratorSpec {
+            sines: vec![
+                Sine {
+                    freq_hz: 10.0,
+                    amplitude_uv: 4.0,
+                },
+                Sine {
+                    freq_hz: 6.0,
+                    amplitude_uv: 2.0,
+                },
+            ],
+            noise_uv: 0.0,
+            bad_channels: vec![2],
+            ..GeneratorSpec::default()
+        };
+        let pow = gen_band_power_samples(&HeadsetModel::Insight, Duration::from_secs(2), &spec);
+        assert_eq!(pow.len(), 16);
+        assert_eq!(pow[1].timestamp, 125_000);
+        let expected = [2.0, 8.0, 0.0, 0.0, 0.0];
+        for (power, expected) in pow[0].channel_powers[0].iter().zip(expected) {
+            assert!((power - expected).abs() < 1e-6);
+        }
+        assert!(pow[0].channel_powers[2].iter().all(|p| *p == 0.0));
+
+        let eq = gen_eq_samples(&HeadsetModel::Insight, Duration::from_secs(2), &spec);
+        assert_eq!(eq.len(), 4);
+        assert_eq!(eq[0].sensor_quality, [1.0, 1.0, 0.0, 1.0, 1.0]);
+        assert!((eq[0].overall - 0.8).abs() < 1e-6);
+    }
+}
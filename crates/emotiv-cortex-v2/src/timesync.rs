@@ -0,0 +1,378 @@
+//! # Timestamp Alignment
+//!
+//! Cortex stamps samples with its own clock, in Unix epoch seconds. For
+//! ERP work those stamps have to be compared with events timed on the
+//! host's monotonic clock (stimulus onsets, button presses), and the two
+//! clocks drift apart over a session.
+//!
+//! [`TimeSync`] runs `syncWithHeadsetClock` rounds periodically, timing
+//! each round trip on the monotonic clock, and keeps a [`ClockModel`]:
+//! a least-squares fit of offset and drift over the recent rounds, NTP
+//! style, ignoring rounds whose round trip was much slower than the
+//! best. [`TimeSync::to_host_time`] then maps a sample's Cortex time to
+//! an [`Instant`].
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use emotiv_cortex_v2::{CortexConfig, ResilientClient};
+//! use emotiv_cortex_v2::timesync::{TimeSync, TimeSyncConfig};
+//!
+//! # async fn demo(headset_id: &str, sample_time: f64) -> emotiv_cortex_v2::CortexResult<()> {
+//! let client = Arc::new(ResilientClient::connect(CortexConfig::discover(None)?).await?);
+//! let sync = TimeSync::spawn(client, headset_id, TimeSyncConfig::default());
+//! sync.sync_now().await?;
+//! if let Some(at) = sync.to_host_time(sample_time) {
+//!     println!("sample taken {:?} ago", at.elapsed());
+//! }
+//! sync.stop().await;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::error::CortexResult;
+use crate::reconnect::ResilientClient;
+use crate::util::{epoch_secs, usize_to_f64};
+
+/// How often and over how many rounds [`TimeSync`] estimates the clocks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeSyncConfig {
+    /// Delay between sync rounds.
+    pub interval: Duration,
+    /// Rounds the model is fitted over.
+    pub window: usize,
+    /// Rounds whose round trip exceeds this many times the fastest round
+    /// in the window are left out of the fit.
+    pub max_rtt_ratio: f64,
+}
+
+impl Default for TimeSyncConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(10),
+            window: 16,
+            max_rtt_ratio: 2.0,
+        }
+    }
+}
+
+/// One sync round, in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SyncSample {
+    /// Host monotonic time at the middle of the round trip, relative to
+    /// the model's origin.
+    pub host: f64,
+    /// Cortex time minus `host` at that moment.
+    pub offset: f64,
+    /// Round-trip time of the request.
+    pub rtt: f64,
+}
+
+/// Current estimate of the Cortex clock against the host clock.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ClockEstimate {
+    /// Cortex time minus host monotonic time at the model origin,
+    /// in seconds.
+    pub offset: f64,
+    /// Rate at which the offset grows, in seconds per second.
+    pub drift: f64,
+    /// Half the fastest round trip in the fit: a bound on the error of
+    /// a single round, in seconds.
+    pub uncertainty: f64,
+    /// Rounds the fit used.
+    pub samples: usize,
+}
+
+/// Offset/drift model fitted to recent [`SyncSample`]s.
+///
+/// Time is measured in seconds from `origin`, a monotonic [`Instant`].
+#[derive(Debug, Clone)]
+pub struct ClockModel {
+    origin: Instant,
+    window: usize,
+    max_rtt_ratio: f64,
+    samples: VecDeque<SyncSample>,
+    estimate: Option<ClockEstimate>,
+}
+
+impl ClockModel {
+    /// An empty model measuring host time from `origin`.
+    #[must_use]
+    pub fn new(origin: Instant, window: usize, max_rtt_ratio: f64) -> Self {
+        Self {
+            origin,
+            window: window.max(1),
+            max_rtt_ratio,
+            samples: VecDeque::new(),
+            estimate: None,
+        }
+    }
+
+    /// Host monotonic time the model measures from.
+    #[must_use]
+    pub fn origin(&self) -> Instant {
+        self.origin
+    }
+
+    /// Add a round and refit.
+    pub fn add(&mut self, sample: SyncSample) {
+        if self.samples.len() == self.window {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+        self.estimate = self.fit();
+    }
+
+    /// Current estimate; `None` before the first round.
+    #[must_use]
+    pub fn estimate(&self) -> Option<ClockEstimate> {
+        self.estimate
+    }
+
+    /// Cortex time at `host` seconds from the origin.
+    #[must_use]
+    pub fn to_cortex(&self, host: f64) -> Option<f64> {
+        let estimate = self.estimate?;
+        Some(host + estimate.offset + estimate.drift * host)
+    }
+
+    /// Host seconds from the origin at Cortex time `cortex_time`.
+    #[must_use]
+    pub fn to_host(&self, cortex_time: f64) -> Option<f64> {
+        let estimate = self.estimate?;
+        Some((cortex_time - estimate.offset) / (1.0 + estimate.drift))
+    }
+
+    /// Least-squares line through the rounds whose round trip is within
+    /// `max_rtt_ratio` of the fastest one.
+    fn fit(&self) -> Option<ClockEstimate> {
+        let best_rtt = self.samples.iter().map(|s| s.rtt).min_by(f64::total_cmp)?;
+        let limit = best_rtt * self.max_rtt_ratio.max(1.0);
+        let used: Vec<&SyncSample> = self.samples.iter().filter(|s| s.rtt <= limit).collect();
+
+        let n = usize_to_f64(used.len());
+        let mean_host = used.iter().map(|s| s.host).sum::<f64>() / n;
+        let mean_offset = used.iter().map(|s| s.offset).sum::<f64>() / n;
+        let (mut covariance, mut variance) = (0.0, 0.0);
+        for s in &used {
+            covariance += (s.host - mean_host) * (s.offset - mean_offset);
+            variance += (s.host - mean_host).powi(2);
+        }
+        let drift = if variance > f64::EPSILON {
+            covariance / variance
+        } else {
+            0.0
+        };
+        Some(ClockEstimate {
+            offset: mean_offset - drift * mean_host,
+            drift,
+            uncertainty: best_rtt / 2.0,
+            samples: used.len(),
+        })
+    }
+}
+
+// ─── Service ─────────────────────────────────────────────────────────────
+
+/// Keeps a [`ClockModel`] for one headset up to date. See the
+/// [module documentation](self).
+pub struct TimeSync {
+    client: Arc<ResilientClient>,
+    headset_id: String,
+    model: Arc<Mutex<ClockModel>>,
+    shutdown: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+impl TimeSync {
+    /// Start sync rounds against `headset_id` every
+    /// [`TimeSyncConfig::interval`], beginning straight away.
+    #[must_use]
+    pub fn spawn(
+        client: Arc<ResilientClient>,
+        headset_id: impl Into<String>,
+        config: TimeSyncConfig,
+    ) -> Self {
+        let headset_id = headset_id.into();
+        let model = Arc::new(Mutex::new(ClockModel::new(
+            Instant::now(),
+            config.window,
+            config.max_rtt_ratio,
+        )));
+        let (shutdown, mut shutdown_rx) = watch::channel(false);
+
+        let task = tokio::spawn({
+            let client = Arc::clone(&client);
+            let headset_id = headset_id.clone();
+            let model = Arc::clone(&model);
+            async move {
+                let mut ticks = tokio::time::interval(config.interval);
+                loop {
+                    tokio::select! {
+                        _ = ticks.tick() => {}
+                        _ = shutdown_rx.changed() => break,
+                    }
+                    if let Err(e) = sync_round(&client, &headset_id, &model).await {
+                        tracing::debug!(headset_id, error = %e, "Clock sync round failed");
+                    }
+                }
+            }
+        });
+
+        Self {
+            client,
+            headset_id,
+            model,
+            shutdown,
+            task,
+        }
+    }
+
+    /// Run a sync round now and return the updated estimate.
+    ///
+    /// # Errors
+    /// Returns any error of [`ResilientClient::sync_with_headset_clock`].
+    pub async fn sync_now(&self) -> CortexResult<ClockEstimate> {
+        sync_round(&self.client, &self.headset_id, &self.model).await
+    }
+
+    /// Current estimate; `None` until a round has succeeded.
+    #[must_use]
+    pub fn estimate(&self) -> Option<ClockEstimate> {
+        self.model().estimate()
+    }
+
+    /// A copy of the model, for converting many timestamps at once.
+    #[must_use]
+    pub fn model(&self) -> ClockModel {
+        self.model
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Host monotonic time at which Cortex's clock read `cortex_time`
+    /// (a sample's `time`, in epoch seconds). `None` until a round has
+    /// succeeded, or if the time is too far before the model origin to
+    /// be represented.
+    #[must_use]
+    pub fn to_host_time(&self, cortex_time: f64) -> Option<Instant> {
+        let model = self.model();
+        let host = model.to_host(cortex_time)?;
+        let offset = Duration::try_from_secs_f64(host.abs()).ok()?;
+        if host >= 0.0 {
+            model.origin().checked_add(offset)
+        } else {
+            model.origin().checked_sub(offset)
+        }
+    }
+
+    /// Cortex time, in epoch seconds, at host instant `at`.
+    #[must_use]
+    pub fn to_cortex_time(&self, at: Instant) -> Option<f64> {
+        let model = self.model();
+        let host = match at.checked_duration_since(model.origin()) {
+            Some(after) => after.as_secs_f64(),
+            None => -model.origin().duration_since(at).as_secs_f64(),
+        };
+        model.to_cortex(host)
+    }
+
+    /// Stop the periodic rounds.
+    pub async fn stop(self) {
+        let _ = self.shutdown.send(true);
+        let _ = self.task.await;
+    }
+}
+
+/// Time one `syncWithHeadsetClock` call and feed it to `model`.
+async fn sync_round(
+    client: &ResilientClient,
+    headset_id: &str,
+    model: &Mutex<ClockModel>,
+) -> CortexResult<ClockEstimate> {
+    let sent = Instant::now();
+    let sent_epoch = epoch_secs();
+    let result = client.sync_with_headset_clock(headset_id).await?;
+    let received = Instant::now();
+    let received_epoch = epoch_secs();
+
+    let rtt = received.duration_since(sent).as_secs_f64();
+    let mut model = model.lock().unwrap_or_else(PoisonError::into_inner);
+    let host = sent.saturating_duration_since(model.origin()).as_secs_f64() + rtt / 2.0;
+    // Cortex reports how far its clock is from the host's wall clock.
+    let cortex = f64::midpoint(sent_epoch, received_epoch) + result.adjustment;
+    model.add(SyncSample {
+        host,
+        offset: cortex - host,
+        rtt,
+    });
+    let estimate = model.estimate().unwrap_or(ClockEstimate {
+        offset: cortex - host,
+        drift: 0.0,
+        uncertainty: rtt / 2.0,
+        samples: 1,
+    });
+    tracing::debug!(
+        headset_id,
+        rtt_ms = rtt * 1000.0,
+        offset = estimate.offset,
+        drift = estimate.drift,
+        "Clock sync round"
+    );
+    Ok(estimate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(host: f64, offset: f64, rtt: f64) -> SyncSample {
+        SyncSample { host, offset, rtt }
+    }
+
+    #[test]
+    fn test_model_fits_offset_and_drift() {
+        let mut model = ClockModel::new(Instant::now(), 8, 2.0);
+        assert!(model.estimate().is_none());
+        assert!(model.to_host(100.0).is_none());
+
+        // Cortex runs 20 ppm fast, 1000 s ahead at the origin.
+        for i in 0..8 {
+            let host = f64::from(i) * 10.0;
+            model.add(sample(host, 1000.0 + 20e-6 * host, 0.002));
+        }
+        let estimate = model.estimate().unwrap();
+        assert!((estimate.offset - 1000.0).abs() < 1e-9);
+        assert!((estimate.drift - 20e-6).abs() < 1e-12);
+        assert_eq!(estimate.samples, 8);
+        assert!((estimate.uncertainty - 0.001).abs() < f64::EPSILON);
+
+        let cortex = model.to_cortex(500.0).unwrap();
+        assert!((model.to_host(cortex).unwrap() - 500.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_model_ignores_slow_rounds_and_slides_window() {
+        let mut model = ClockModel::new(Instant::now(), 3, 2.0);
+        model.add(sample(0.0, 5.0, 0.001));
+        // A delayed reply skews its offset; the fit must not follow it.
+        model.add(sample(1.0, 5.5, 0.050));
+        let estimate = model.estimate().unwrap();
+        assert_eq!(estimate.samples, 1);
+        assert!((estimate.offset - 5.0).abs() < f64::EPSILON);
+        assert!(estimate.drift.abs() < f64::EPSILON);
+
+        model.add(sample(2.0, 5.0, 0.001));
+        model.add(sample(3.0, 5.0, 0.001));
+        // The first round has left the window; the slow one is still ignored.
+        assert_eq!(model.estimate().unwrap().samples, 2);
+    }
+}
@@ -0,0 +1,392 @@
+//! # Token Cache
+//!
+//! Cortex tokens stay valid across restarts, and renewing one with
+//! `generateNewToken` does not debit a session from the license the way a
+//! fresh `authorize` with [`CortexConfig::debit`] does. With
+//! [`TokenCacheConfig::enabled`] set, [`ResilientClient`] saves its token
+//! to disk and, on the next start, renews the saved token instead of
+//! authorizing again. It falls back to a full authorization when the
+//! renewal is refused (the token expired, or the user logged out).
+//!
+//! ```toml
+//! license = "ABCD-1234-EFGH-5678"
+//! debit = 10
+//!
+//! [token_cache]
+//! enabled = true
+//! # path = "/var/lib/rig/cortex-token.json"
+//! # encrypt = true
+//! ```
+//!
+//! The cache file holds one token for one client ID and is written with
+//! owner-only permissions on Unix. With [`TokenCacheConfig::encrypt`] set
+//! (requires the `token-encryption` feature) the token is sealed with
+//! ChaCha20-Poly1305 under a key derived from the client secret, so the
+//! file is useless without the secret.
+//!
+//! [`ResilientClient`]: crate::ResilientClient
+//! [`TokenCacheConfig::enabled`]: crate::config::TokenCacheConfig::enabled
+//! [`TokenCacheConfig::encrypt`]: crate::config::TokenCacheConfig::encrypt
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::CortexConfig;
+use crate::error::{CortexError, CortexResult};
+
+/// On-disk form of a cached token.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheFile {
+    client_id: String,
+    /// Seconds since the Unix epoch.
+    saved_at: u64,
+    #[serde(flatten)]
+    token: StoredToken,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum StoredToken {
+    Plain {
+        #[serde(rename = "cortexToken")]
+        cortex_token: String,
+    },
+    Sealed {
+        nonce: String,
+        ciphertext: String,
+    },
+}
+
+/// A token cache file. See the [module docs](self).
+#[derive(Debug, Clone)]
+pub struct TokenCache {
+    path: PathBuf,
+    encrypt: bool,
+}
+
+impl TokenCache {
+    /// Plain-text cache at `path`.
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            encrypt: false,
+        }
+    }
+
+    /// Encrypt cached tokens with a key derived from the client secret.
+    /// Storing fails without the `token-encryption` feature.
+    #[must_use]
+    pub fn encrypted(mut self, encrypt: bool) -> Self {
+        self.encrypt = encrypt;
+        self
+    }
+
+    /// The cache configured in `config`, or `None` when caching is off or
+    /// no path is configured and the platform has no default.
+    #[must_use]
+    pub fn from_config(config: &CortexConfig) -> Option<Self> {
+        let settings = &config.token_cache;
+        if !settings.enabled {
+            return None;
+        }
+        let path = settings.path.clone().or_else(Self::default_path)?;
+        Some(Self::new(path).encrypted(settings.encrypt))
+    }
+
+    /// `token.json` next to the per-user config file
+    /// ([`CortexConfig::user_config_path`]).
+    #[must_use]
+    pub fn default_path() -> Option<PathBuf> {
+        CortexConfig::user_config_path()
+            .and_then(|config| config.parent().map(|dir| dir.join("token.json")))
+    }
+
+    /// Cache file location.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The cached token for `client_id`, if any.
+    ///
+    /// A missing, unreadable, or undecryptable file, or one saved for
+    /// another client ID, reads as no token.
+    #[must_use]
+    pub fn load(&self, client_id: &str, client_secret: &str) -> Option<String> {
+        let contents = std::fs::read_to_string(&self.path).ok()?;
+        let file: CacheFile = match serde_json::from_str(&contents) {
+            Ok(file) => file,
+            Err(e) => {
+                tracing::warn!(path = %self.path.display(), error = %e, "Ignoring malformed token cache");
+                return None;
+            }
+        };
+        if file.client_id != client_id {
+            tracing::debug!("Token cache belongs to another client ID");
+            return None;
+        }
+        match file.token {
+            StoredToken::Plain { cortex_token } => Some(cortex_token),
+            StoredToken::Sealed { nonce, ciphertext } => {
+                let token = open(client_secret, &nonce, &ciphertext);
+                if token.is_none() {
+                    tracing::warn!(path = %self.path.display(), "Cannot decrypt cached token");
+                }
+                token
+            }
+        }
+    }
+
+    /// Save `cortex_token` for `client_id`, replacing any cached token.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::Io`] if the file cannot be written and
+    /// [`CortexError::ConfigError`] if encryption is requested without the
+    /// `token-encryption` feature.
+    pub fn store(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+        cortex_token: &str,
+    ) -> CortexResult<()> {
+        let token = if self.encrypt {
+            seal(client_secret, cortex_token)?
+        } else {
+            StoredToken::Plain {
+                cortex_token: cortex_token.to_string(),
+            }
+        };
+        let saved_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let file = CacheFile {
+            client_id: client_id.to_string(),
+            saved_at,
+            token,
+        };
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        // Write beside the cache and rename, so a crash never leaves a
+        // truncated file behind.
+        let staging = write_staging(&self.path, serde_json::to_string_pretty(&file)?.as_bytes())?;
+        if let Err(e) = std::fs::rename(&staging, &self.path) {
+            let _ = std::fs::remove_file(&staging);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// Delete the cache file. A missing file is not an error.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::Io`] if the file exists but cannot be removed.
+    pub fn clear(&self) -> CortexResult<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Write `contents` to a new file beside `path`, readable by the owner
+/// only, and return the new file's path. The name is unpredictable and the
+/// file is created exclusively, so nothing already there is followed or
+/// reused.
+fn write_staging(path: &Path, contents: &[u8]) -> std::io::Result<PathBuf> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut attempts = 0;
+    let (staging, mut file) = loop {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(".{:016x}.tmp", rand::random::<u64>()));
+        let staging = path.with_file_name(name);
+        match options.open(&staging) {
+            Ok(file) => break (staging, file),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists && attempts < 8 => {
+                attempts += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    };
+    if let Err(e) = file.write_all(contents) {
+        let _ = std::fs::remove_file(&staging);
+        return Err(e);
+    }
+    Ok(staging)
+}
+
+// ─── Encryption ──────────────────────────────────────────────────────────
+
+#[cfg(feature = "token-encryption")]
+fn key(client_secret: &str) -> Option<ring::aead::LessSafeKey> {
+    use sha2::{Digest, Sha256};
+
+    let digest = Sha256::new()
+        .chain_update(b"emotiv-cortex-v2 token cache\0")
+        .chain_update(client_secret.as_bytes())
+        .finalize();
+    let unbound = ring::aead::UnboundKey::new(&ring::aead::CHACHA20_POLY1305, &digest).ok()?;
+    Some(ring::aead::LessSafeKey::new(unbound))
+}
+
+#[cfg(feature = "token-encryption")]
+fn seal(client_secret: &str, cortex_token: &str) -> CortexResult<StoredToken> {
+    use ring::aead::{Aad, NONCE_LEN, Nonce};
+    use ring::rand::{SecureRandom, SystemRandom};
+
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| CortexError::ConfigError {
+            reason: "no system randomness for the token cache nonce".into(),
+        })?;
+    let mut sealed = cortex_token.as_bytes().to_vec();
+    key(client_secret)
+        .and_then(|key| {
+            key.seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut sealed,
+            )
+            .ok()
+        })
+        .ok_or_else(|| CortexError::ConfigError {
+            reason: "failed to encrypt the cached token".into(),
+        })?;
+    Ok(StoredToken::Sealed {
+        nonce: to_hex(&nonce),
+        ciphertext: to_hex(&sealed),
+    })
+}
+
+#[cfg(feature = "token-encryption")]
+fn open(client_secret: &str, nonce: &str, ciphertext: &str) -> Option<String> {
+    use ring::aead::{Aad, Nonce};
+
+    let nonce = Nonce::try_assume_unique_for_key(&from_hex(nonce)?).ok()?;
+    let mut sealed = from_hex(ciphertext)?;
+    let token = key(client_secret)?
+        .open_in_place(nonce, Aad::empty(), &mut sealed)
+        .ok()?;
+    String::from_utf8(token.to_vec()).ok()
+}
+
+#[cfg(not(feature = "token-encryption"))]
+fn seal(_client_secret: &str, _cortex_token: &str) -> CortexResult<StoredToken> {
+    Err(CortexError::ConfigError {
+        reason: "token_cache.encrypt requires the `token-encryption` feature".into(),
+    })
+}
+
+#[cfg(not(feature = "token-encryption"))]
+fn open(_client_secret: &str, _nonce: &str, _ciphertext: &str) -> Option<String> {
+    tracing::warn!("Cached token is encrypted but the `token-encryption` feature is off");
+    None
+}
+
+#[cfg(feature = "token-encryption")]
+fn to_hex(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+#[cfg(feature = "token-encryption")]
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "emotiv-token-cache-{name}-{}.json",
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn test_plain_cache_round_trips_per_client_id() {
+        let cache = TokenCache::new(cache_path("plain"));
+        cache.store("client-a", "secret", "token-1").unwrap();
+
+        assert_eq!(cache.load("client-a", "secret").as_deref(), Some("token-1"));
+        assert_eq!(cache.load("client-b", "secret"), None);
+
+        cache.clear().unwrap();
+        assert_eq!(cache.load("client-a", "secret"), None);
+        cache.clear().unwrap();
+    }
+
+    #[test]
+    fn test_store_leaves_existing_tmp_file_alone() {
+        let cache = TokenCache::new(cache_path("staging"));
+        let planted = cache.path().with_extension("tmp");
+        std::fs::write(&planted, "keep me").unwrap();
+
+        cache.store("client-a", "secret", "token-1").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&planted).unwrap(), "keep me");
+        assert_eq!(cache.load("client-a", "secret").as_deref(), Some("token-1"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(cache.path())
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        cache.clear().unwrap();
+        std::fs::remove_file(&planted).unwrap();
+    }
+
+    #[cfg(feature = "token-encryption")]
+    #[test]
+    fn test_encrypted_cache_needs_the_client_secret() {
+        let cache = TokenCache::new(cache_path("sealed")).encrypted(true);
+        cache.store("client-a", "secret", "token-1").unwrap();
+
+        let contents = std::fs::read_to_string(cache.path()).unwrap();
+        assert!(!contents.contains("token-1"));
+        assert_eq!(cache.load("client-a", "secret").as_deref(), Some("token-1"));
+        assert_eq!(cache.load("client-a", "other-secret"), None);
+        cache.clear().unwrap();
+    }
+
+    #[cfg(not(feature = "token-encryption"))]
+    #[test]
+    fn test_encryption_requires_the_feature() {
+        let cache = TokenCache::new(cache_path("unsupported")).encrypted(true);
+        assert!(matches!(
+            cache.store("client-a", "secret", "token-1"),
+            Err(CortexError::ConfigError { .. })
+        ));
+    }
+}
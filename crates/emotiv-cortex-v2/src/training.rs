@@ -0,0 +1,1272 @@
+//! # Profile Training
+//!
+//! Helpers for working with trained profiles: a read-only summary of what
+//! a profile has learned, a guided [`Trainer`] that runs the mental
+//! command training lifecycle, and a [`FacialTrainer`] that does the same
+//! for facial expressions.
+//!
+//! ## Summary
+//!
+//! [`profile_summary`] gathers everything a "profile status" page needs in
+//! one call: trained actions and counts for both detections, the mental
+//! command training threshold, facial expression thresholds per trained
+//! action, and — when the profile is loaded on the given headset with an
+//! open session — the session-scoped sensitivities and last training times.
+//!
+//! ```no_run
+//! use emotiv_cortex_v2::{CortexClient, training};
+//!
+//! # async fn demo(client: &CortexClient, token: &str) -> emotiv_cortex_v2::CortexResult<()> {
+//! let summary = training::profile_summary(client, token, "my_profile", "INSIGHT-A1B2C3D4").await?;
+//! for action in &summary.mental_command.trained_actions {
+//!     println!("{}: trained {} times", action.action, action.times);
+//! }
+//! if let Some(session) = &summary.session {
+//!     println!("sensitivities: {:?}", session.mental_command_sensitivities);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Guided Training
+//!
+//! Training one action takes several calls and `sys` events: `training
+//! start`, `MC_Started`, about eight seconds of recording, `MC_Succeeded`
+//! or `MC_Failed`, then `accept` or `reject` answered by `MC_Completed` or
+//! `MC_Rejected`. [`Trainer::train_action`] runs that exchange, makes
+//! sure the action is active first, retries failed recordings, asks a
+//! callback whether to keep a successful one, and saves the profile
+//! afterwards when asked to:
+//!
+//! ```no_run
+//! use emotiv_cortex_v2::CortexClient;
+//! use emotiv_cortex_v2::training::{ProfileTarget, Trainer, TrainerConfig, TrainingDecision};
+//!
+//! # async fn demo(client: &CortexClient, token: &str, session_id: &str) -> emotiv_cortex_v2::CortexResult<()> {
+//! let config = TrainerConfig {
+//!     save_profile: Some(ProfileTarget::new("INSIGHT-A1B2C3D4", "my_profile")),
+//!     ..TrainerConfig::default()
+//! };
+//! let mut trainer = Trainer::subscribe(client, token, session_id, config).await?;
+//! let outcome = trainer
+//!     .train_action("push", async |attempt| {
+//!         println!("attempt {} of push succeeded, keeping it", attempt.attempt);
+//!         TrainingDecision::Accept
+//!     })
+//!     .await?;
+//! println!("{:?} after {} attempts, profile {:?}", outcome.result, outcome.attempts, outcome.profile);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Both trainers broadcast [`TrainingProgress`] events to the receivers
+//! returned by their `progress()` method, for UIs that show the recording
+//! state while `train_action` runs.
+//!
+//! ## Facial Expressions
+//!
+//! [`FacialTrainer`] runs the same lifecycle on `FE_*` events and also
+//! manages what the detection runs on: the signature type
+//! ([`FacialSignatureType::Universal`] or the profile's
+//! [`FacialSignatureType::Trained`] one) and the per-action thresholds.
+//!
+//! ```no_run
+//! use emotiv_cortex_v2::CortexClient;
+//! use emotiv_cortex_v2::training::{
+//!     FacialSignatureType, FacialTrainer, TrainerConfig, TrainingDecision,
+//! };
+//!
+//! # async fn demo(client: &CortexClient, token: &str, session_id: &str) -> emotiv_cortex_v2::CortexResult<()> {
+//! let mut trainer = FacialTrainer::subscribe(client, token, session_id, TrainerConfig::default()).await?;
+//! for action in ["neutral", "smile"] {
+//!     trainer.train_action(action, async |_| TrainingDecision::Accept).await?;
+//! }
+//! if trainer.signature().await?.available.contains(&FacialSignatureType::Trained) {
+//!     trainer.set_signature(FacialSignatureType::Trained).await?;
+//! }
+//! trainer.set_threshold("smile", 600).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::pin::Pin;
+use std::time::Duration;
+
+use futures_core::Stream;
+use futures_util::StreamExt;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+use crate::client::CortexClient;
+use crate::error::{CortexError, CortexResult};
+use crate::protocol::profiles::ProfileAction;
+use crate::protocol::streams::SysEvent;
+use crate::protocol::training::{
+    DetectionType, FacialExpressionSignatureTypeRequest, FacialExpressionThresholdRequest,
+    MentalCommandTrainingThresholdRequest, TrainedAction, TrainingStatus,
+};
+use crate::streams;
+
+/// Training state of one detection type within a profile.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DetectionTrainingSummary {
+    /// Total number of accepted trainings.
+    pub total_times_training: u32,
+    /// Per-action training counts.
+    pub trained_actions: Vec<TrainedAction>,
+}
+
+impl DetectionTrainingSummary {
+    /// Whether `action` has been trained at least once.
+    #[must_use]
+    pub fn is_trained(&self, action: &str) -> bool {
+        self.trained_actions
+            .iter()
+            .any(|a| a.action == action && a.times > 0)
+    }
+}
+
+/// Threshold of a trained facial expression action (0–1000).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FacialExpressionThreshold {
+    /// Facial action name.
+    pub action: String,
+    /// Current threshold.
+    pub threshold: u32,
+}
+
+/// Mental command training threshold of a profile.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MentalCommandThreshold {
+    /// Threshold a training must reach to be accepted.
+    pub current: f64,
+    /// Score of the most recent training, if any.
+    pub last_training_score: Option<f64>,
+}
+
+/// Values only available from a session with the profile loaded.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionTrainingState {
+    /// Session the values were read from.
+    pub session_id: String,
+    /// Active mental command actions.
+    pub mental_command_active_actions: Vec<String>,
+    /// Sensitivity (1–10) of each active non-neutral action, in order.
+    pub mental_command_sensitivities: Vec<i32>,
+    /// Duration of the last mental command training in seconds.
+    pub mental_command_training_time: f64,
+    /// Duration of the last facial expression training in seconds.
+    pub facial_expression_training_time: f64,
+}
+
+/// Combined training statistics for one profile; see [`profile_summary`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProfileTrainingSummary {
+    /// Profile name.
+    pub profile: String,
+    /// Headset the summary was requested for.
+    pub headset_id: String,
+    /// Whether the profile is currently loaded on the headset.
+    pub loaded: bool,
+    /// Mental command trained actions.
+    pub mental_command: DetectionTrainingSummary,
+    /// Facial expression trained actions.
+    pub facial_expression: DetectionTrainingSummary,
+    /// Mental command training threshold.
+    pub mental_command_threshold: MentalCommandThreshold,
+    /// Threshold of each trained facial expression action except neutral.
+    pub facial_expression_thresholds: Vec<FacialExpressionThreshold>,
+    /// Session-scoped values, when the profile is loaded on the headset
+    /// and the headset has an open session.
+    pub session: Option<SessionTrainingState>,
+}
+
+/// Collect the training statistics of `profile` into one
+/// [`ProfileTrainingSummary`].
+///
+/// Profile-scoped values are always read. Sensitivities and training
+/// times are session-scoped in Cortex, so they are only filled in when
+/// `profile` is the one loaded on `headset_id` and the headset has an open
+/// session; otherwise [`ProfileTrainingSummary::session`] is `None`.
+///
+/// # Errors
+/// Returns any error produced by the underlying Cortex API calls, or
+/// [`CortexError::ProtocolError`] if a threshold response has an
+/// unexpected shape.
+pub async fn profile_summary(
+    client: &CortexClient,
+    cortex_token: &str,
+    profile: &str,
+    headset_id: &str,
+) -> CortexResult<ProfileTrainingSummary> {
+    let mental_command =
+        detection_summary(client, cortex_token, DetectionType::MentalCommand, profile).await?;
+    let facial_expression = detection_summary(
+        client,
+        cortex_token,
+        DetectionType::FacialExpression,
+        profile,
+    )
+    .await?;
+
+    let threshold = client
+        .mental_command_training_threshold_with_request(
+            cortex_token,
+            &MentalCommandTrainingThresholdRequest {
+                session_id: None,
+                profile: Some(profile.to_string()),
+                status: Some("get".to_string()),
+                value: None,
+            },
+        )
+        .await?;
+    let mental_command_threshold = parse_mental_command_threshold(&threshold)?;
+
+    let mut facial_expression_thresholds = Vec::new();
+    for action in &facial_expression.trained_actions {
+        if action.action == "neutral" || action.times == 0 {
+            continue;
+        }
+        let result = client
+            .facial_expression_threshold_with(
+                cortex_token,
+                &FacialExpressionThresholdRequest {
+                    status: "get".to_string(),
+                    action: action.action.clone(),
+                    profile: Some(profile.to_string()),
+                    session: None,
+                    value: None,
+                },
+            )
+            .await?;
+        facial_expression_thresholds.push(FacialExpressionThreshold {
+            action: action.action.clone(),
+            threshold: parse_facial_expression_threshold(&result)?,
+        });
+    }
+
+    let current = client.get_current_profile(cortex_token, headset_id).await?;
+    let loaded = current.name.as_deref() == Some(profile);
+    let session = if loaded {
+        session_state(client, cortex_token, headset_id).await?
+    } else {
+        None
+    };
+
+    Ok(ProfileTrainingSummary {
+        profile: profile.to_string(),
+        headset_id: headset_id.to_string(),
+        loaded,
+        mental_command,
+        facial_expression,
+        mental_command_threshold,
+        facial_expression_thresholds,
+        session,
+    })
+}
+
+async fn detection_summary(
+    client: &CortexClient,
+    cortex_token: &str,
+    detection: DetectionType,
+    profile: &str,
+) -> CortexResult<DetectionTrainingSummary> {
+    let trained = client
+        .get_trained_signature_actions(cortex_token, detection, Some(profile), None)
+        .await?;
+    Ok(DetectionTrainingSummary {
+        total_times_training: trained.total_times_training,
+        trained_actions: trained.trained_actions,
+    })
+}
+
+async fn session_state(
+    client: &CortexClient,
+    cortex_token: &str,
+    headset_id: &str,
+) -> CortexResult<Option<SessionTrainingState>> {
+    let sessions = client.query_sessions(cortex_token).await?;
+    let Some(session) = sessions
+        .into_iter()
+        .find(|s| s.stopped.is_none() && s.headset.as_ref().is_some_and(|h| h.id == headset_id))
+    else {
+        return Ok(None);
+    };
+    let session_id = session.id;
+
+    let active = client
+        .mental_command_active_action(cortex_token, &session_id, None)
+        .await?;
+    let sensitivities = client
+        .mental_command_action_sensitivity(cortex_token, &session_id, None)
+        .await?;
+    let mental_command_time = client
+        .get_training_time(cortex_token, DetectionType::MentalCommand, &session_id)
+        .await?;
+    let facial_expression_time = client
+        .get_training_time(cortex_token, DetectionType::FacialExpression, &session_id)
+        .await?;
+
+    Ok(Some(SessionTrainingState {
+        mental_command_active_actions: parse_list(&active, "mental command active actions")?,
+        mental_command_sensitivities: parse_list(&sensitivities, "mental command sensitivities")?,
+        mental_command_training_time: mental_command_time.time,
+        facial_expression_training_time: facial_expression_time.time,
+        session_id,
+    }))
+}
+
+fn parse_list<T: serde::de::DeserializeOwned>(value: &Value, what: &str) -> CortexResult<Vec<T>> {
+    serde_json::from_value(value.clone()).map_err(|e| CortexError::ProtocolError {
+        reason: format!("Failed to parse {what}: {e}"),
+    })
+}
+
+fn parse_mental_command_threshold(value: &Value) -> CortexResult<MentalCommandThreshold> {
+    let current = value
+        .get("currentThreshold")
+        .and_then(Value::as_f64)
+        .ok_or_else(|| CortexError::ProtocolError {
+            reason: format!("Failed to parse mental command threshold: {value}"),
+        })?;
+    Ok(MentalCommandThreshold {
+        current,
+        last_training_score: value.get("lastTrainingScore").and_then(Value::as_f64),
+    })
+}
+
+/// Accepts either a bare number or `{"currentThreshold": n}`.
+fn parse_facial_expression_threshold(value: &Value) -> CortexResult<u32> {
+    value
+        .get("currentThreshold")
+        .unwrap_or(value)
+        .as_u64()
+        .and_then(|n| u32::try_from(n).ok())
+        .ok_or_else(|| CortexError::ProtocolError {
+            reason: format!("Failed to parse facial expression threshold: {value}"),
+        })
+}
+
+// ─── Guided Training ────────────────────────────────────────────────────
+
+/// Non-neutral mental command actions Cortex allows to be active at once.
+pub const MAX_ACTIVE_MENTAL_COMMAND_ACTIONS: usize = 4;
+
+/// Default number of recordings tried before giving up on an action.
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Default wait for the next training event. A recording lasts about 8 s.
+pub const DEFAULT_EVENT_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// A training event from the `sys` stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrainingEvent {
+    /// Recording started.
+    Started,
+    /// Recording finished and can be accepted or rejected.
+    Succeeded,
+    /// Recording failed, e.g. because of poor signal.
+    Failed,
+    /// The accepted training was added to the profile.
+    Completed,
+    /// The training was rejected.
+    Rejected,
+    /// The training was cancelled.
+    Reset,
+    /// The action's training data was erased.
+    DataErased,
+    /// Any other event of the detection, e.g. `AutoReject`.
+    Other(String),
+}
+
+impl TrainingEvent {
+    /// Parse a `sys` event such as `["mentalCommand", "MC_Succeeded"]` into
+    /// its detection and training event.
+    ///
+    /// Returns `None` for `sys` events that are not about training.
+    #[must_use]
+    pub fn parse(event: &SysEvent) -> Option<(DetectionType, Self)> {
+        let name = event.sys.get(1)?.as_str()?;
+        let (detection, name) = if let Some(name) = name.strip_prefix("MC_") {
+            (DetectionType::MentalCommand, name)
+        } else {
+            (DetectionType::FacialExpression, name.strip_prefix("FE_")?)
+        };
+        let event = match name {
+            "Started" => Self::Started,
+            "Succeeded" => Self::Succeeded,
+            "Failed" => Self::Failed,
+            "Completed" => Self::Completed,
+            "Rejected" => Self::Rejected,
+            "Reset" => Self::Reset,
+            "DataErased" => Self::DataErased,
+            other => Self::Other(other.to_string()),
+        };
+        Some((detection, event))
+    }
+}
+
+/// Headset and profile to save after an accepted training.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProfileTarget {
+    /// Headset the profile is loaded on.
+    pub headset_id: String,
+    /// Profile name.
+    pub profile: String,
+}
+
+impl ProfileTarget {
+    /// Save `profile` as loaded on `headset_id`.
+    pub fn new(headset_id: impl Into<String>, profile: impl Into<String>) -> Self {
+        Self {
+            headset_id: headset_id.into(),
+            profile: profile.into(),
+        }
+    }
+}
+
+/// Settings for a [`Trainer`].
+#[derive(Debug, Clone)]
+pub struct TrainerConfig {
+    /// Recordings tried per action before giving up. At least one
+    /// recording is always tried.
+    pub max_attempts: u32,
+    /// Longest wait for the next training event before the training is
+    /// reset and [`CortexError::Timeout`] returned.
+    pub event_timeout: Duration,
+    /// Profile to save after an accepted training, if any.
+    pub save_profile: Option<ProfileTarget>,
+}
+
+impl Default for TrainerConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            event_timeout: DEFAULT_EVENT_TIMEOUT,
+            save_profile: None,
+        }
+    }
+}
+
+/// A successful recording waiting for a [`TrainingDecision`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrainingAttempt {
+    /// Action being trained.
+    pub action: String,
+    /// Recording number, starting at 1.
+    pub attempt: u32,
+    /// Recordings allowed in total.
+    pub max_attempts: u32,
+}
+
+/// What to do with a successful recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrainingDecision {
+    /// Add the recording to the profile.
+    Accept,
+    /// Discard the recording and stop.
+    Reject,
+    /// Discard the recording and record again, if attempts remain.
+    Retry,
+}
+
+/// How a guided training ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TrainingResult {
+    /// A recording was accepted into the profile.
+    Accepted,
+    /// The last successful recording was rejected.
+    Rejected,
+    /// Every attempt failed or was retried.
+    Failed,
+    /// Cortex reported a reset, e.g. from another application.
+    Cancelled,
+}
+
+/// Whether the profile was saved after the training.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum ProfileSaveStatus {
+    /// No [`TrainerConfig::save_profile`] target, or nothing was accepted.
+    NotRequested,
+    /// The profile was saved.
+    Saved,
+    /// Saving failed; the training itself is still in the session.
+    Failed {
+        /// Error from `setupProfile`.
+        reason: String,
+    },
+}
+
+/// Report of one [`Trainer::train_action`] or
+/// [`FacialTrainer::train_action`] call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TrainingOutcome {
+    /// Action trained.
+    pub action: String,
+    /// How the training ended.
+    pub result: TrainingResult,
+    /// Recordings started.
+    pub attempts: u32,
+    /// Recordings Cortex reported as failed.
+    pub failures: u32,
+    /// Whether the profile was saved afterwards.
+    pub profile: ProfileSaveStatus,
+}
+
+impl TrainingOutcome {
+    /// Whether a recording was accepted.
+    #[must_use]
+    pub fn is_accepted(&self) -> bool {
+        self.result == TrainingResult::Accepted
+    }
+}
+
+/// Progress of a guided training, broadcast to the receivers returned by
+/// [`Trainer::progress`] and [`FacialTrainer::progress`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrainingProgress {
+    /// Cortex started recording.
+    Recording {
+        /// Action being trained.
+        action: String,
+        /// Recording number, starting at 1.
+        attempt: u32,
+    },
+    /// The recording failed and will be retried if attempts remain.
+    Failed {
+        /// Action being trained.
+        action: String,
+        /// Recording number, starting at 1.
+        attempt: u32,
+    },
+    /// The recording succeeded; the decision callback is asked next.
+    Succeeded {
+        /// Action being trained.
+        action: String,
+        /// Recording number, starting at 1.
+        attempt: u32,
+    },
+    /// The decision callback answered; the decision is sent next.
+    Decided {
+        /// Action being trained.
+        action: String,
+        /// Recording number, starting at 1.
+        attempt: u32,
+        /// The answer.
+        decision: TrainingDecision,
+    },
+    /// The training ended, after the profile save if one was requested.
+    Finished(TrainingOutcome),
+    /// [`FacialTrainer::set_signature`] switched the signature.
+    SignatureChanged(FacialSignatureType),
+    /// [`FacialTrainer::set_threshold`] changed an action's threshold.
+    ThresholdChanged {
+        /// Facial action.
+        action: String,
+        /// New threshold.
+        threshold: u32,
+    },
+}
+
+/// Progress events buffered per receiver before the oldest are dropped.
+const PROGRESS_CAPACITY: usize = 64;
+
+/// `training` calls and the `sys` events answering them for one session.
+struct TrainingChannel<'a> {
+    client: &'a CortexClient,
+    cortex_token: String,
+    session_id: String,
+    sys: Pin<Box<dyn Stream<Item = SysEvent> + Send>>,
+    progress: broadcast::Sender<TrainingProgress>,
+}
+
+impl<'a> TrainingChannel<'a> {
+    fn new(
+        client: &'a CortexClient,
+        cortex_token: &str,
+        session_id: &str,
+        sys: Pin<Box<dyn Stream<Item = SysEvent> + Send>>,
+    ) -> Self {
+        Self {
+            client,
+            cortex_token: cortex_token.to_string(),
+            session_id: session_id.to_string(),
+            sys,
+            progress: broadcast::channel(PROGRESS_CAPACITY).0,
+        }
+    }
+
+    fn report(&self, progress: TrainingProgress) {
+        // No receivers is fine; progress is optional.
+        let _ = self.progress.send(progress);
+    }
+
+    async fn send(
+        &self,
+        detection: DetectionType,
+        status: TrainingStatus,
+        action: &str,
+    ) -> CortexResult<()> {
+        self.client
+            .training(
+                &self.cortex_token,
+                &self.session_id,
+                detection,
+                status,
+                action,
+            )
+            .await
+            .map(drop)
+    }
+
+    /// Wait for the next event of `detection` on this session that
+    /// `accept` selects. On timeout the training is reset.
+    async fn wait_for(
+        &mut self,
+        detection: DetectionType,
+        action: &str,
+        timeout: Duration,
+        accept: impl Fn(&TrainingEvent) -> bool,
+    ) -> CortexResult<TrainingEvent> {
+        let session_id = &self.session_id;
+        let sys = &mut self.sys;
+        let wait = async {
+            while let Some(event) = sys.next().await {
+                if event.sid != *session_id {
+                    continue;
+                }
+                match TrainingEvent::parse(&event) {
+                    Some((d, event)) if d == detection && accept(&event) => return Ok(event),
+                    _ => {}
+                }
+            }
+            Err(CortexError::StreamError {
+                reason: "sys stream ended during training".to_string(),
+            })
+        };
+        if let Ok(result) = tokio::time::timeout(timeout, wait).await {
+            return result;
+        }
+        if let Err(e) = self.send(detection, TrainingStatus::Reset, action).await {
+            tracing::warn!("Failed to reset timed-out training of {action}: {e}");
+        }
+        Err(CortexError::Timeout {
+            seconds: timeout.as_secs(),
+        })
+    }
+
+    async fn run<F>(
+        &mut self,
+        detection: DetectionType,
+        action: &str,
+        config: &TrainerConfig,
+        mut decide: F,
+    ) -> CortexResult<TrainingOutcome>
+    where
+        F: AsyncFnMut(&TrainingAttempt) -> TrainingDecision,
+    {
+        let max_attempts = config.max_attempts.max(1);
+        let mut outcome = TrainingOutcome {
+            action: action.to_string(),
+            result: TrainingResult::Failed,
+            attempts: 0,
+            failures: 0,
+            profile: ProfileSaveStatus::NotRequested,
+        };
+
+        while outcome.attempts < max_attempts {
+            outcome.attempts += 1;
+            let attempt = outcome.attempts;
+            match self
+                .record(detection, action, attempt, config.event_timeout)
+                .await?
+            {
+                TrainingEvent::Succeeded => {}
+                TrainingEvent::Failed => {
+                    outcome.failures += 1;
+                    continue;
+                }
+                _ => {
+                    outcome.result = TrainingResult::Cancelled;
+                    break;
+                }
+            }
+
+            let decision = decide(&TrainingAttempt {
+                action: action.to_string(),
+                attempt,
+                max_attempts,
+            })
+            .await;
+            self.report(TrainingProgress::Decided {
+                action: action.to_string(),
+                attempt,
+                decision,
+            });
+            let answer = self
+                .conclude(detection, action, decision, config.event_timeout)
+                .await?;
+            outcome.result = match (answer, decision) {
+                (TrainingEvent::Completed, _) => TrainingResult::Accepted,
+                (TrainingEvent::Rejected, TrainingDecision::Retry) => continue,
+                (TrainingEvent::Rejected, _) => TrainingResult::Rejected,
+                _ => TrainingResult::Cancelled,
+            };
+            break;
+        }
+
+        if let (TrainingResult::Accepted, Some(target)) = (outcome.result, &config.save_profile) {
+            outcome.profile = self.save_profile(target).await;
+        }
+        self.report(TrainingProgress::Finished(outcome.clone()));
+        Ok(outcome)
+    }
+
+    /// Start one recording and wait for `Succeeded`, `Failed` or `Reset`.
+    async fn record(
+        &mut self,
+        detection: DetectionType,
+        action: &str,
+        attempt: u32,
+        timeout: Duration,
+    ) -> CortexResult<TrainingEvent> {
+        self.send(detection, TrainingStatus::Start, action).await?;
+        loop {
+            let event = self
+                .wait_for(detection, action, timeout, |e| {
+                    matches!(
+                        e,
+                        TrainingEvent::Started
+                            | TrainingEvent::Succeeded
+                            | TrainingEvent::Failed
+                            | TrainingEvent::Reset
+                    )
+                })
+                .await?;
+            let name = action.to_string();
+            match event {
+                TrainingEvent::Started => {
+                    self.report(TrainingProgress::Recording {
+                        action: name,
+                        attempt,
+                    });
+                    continue;
+                }
+                TrainingEvent::Succeeded => self.report(TrainingProgress::Succeeded {
+                    action: name,
+                    attempt,
+                }),
+                TrainingEvent::Failed => self.report(TrainingProgress::Failed {
+                    action: name,
+                    attempt,
+                }),
+                _ => {}
+            }
+            return Ok(event);
+        }
+    }
+
+    /// Send `decision` and wait for `Completed`, `Rejected` or `Reset`.
+    async fn conclude(
+        &mut self,
+        detection: DetectionType,
+        action: &str,
+        decision: TrainingDecision,
+        timeout: Duration,
+    ) -> CortexResult<TrainingEvent> {
+        let status = if decision == TrainingDecision::Accept {
+            TrainingStatus::Accept
+        } else {
+            TrainingStatus::Reject
+        };
+        self.send(detection, status, action).await?;
+        self.wait_for(detection, action, timeout, |e| {
+            matches!(
+                e,
+                TrainingEvent::Completed | TrainingEvent::Rejected | TrainingEvent::Reset
+            )
+        })
+        .await
+    }
+
+    async fn save_profile(&self, target: &ProfileTarget) -> ProfileSaveStatus {
+        match self
+            .client
+            .setup_profile(
+                &self.cortex_token,
+                &target.headset_id,
+                &target.profile,
+                ProfileAction::Save,
+            )
+            .await
+        {
+            Ok(()) => ProfileSaveStatus::Saved,
+            Err(e) => ProfileSaveStatus::Failed {
+                reason: e.to_string(),
+            },
+        }
+    }
+}
+
+/// Guided mental command training for one session. See the
+/// [module documentation](self).
+pub struct Trainer<'a> {
+    channel: TrainingChannel<'a>,
+    config: TrainerConfig,
+}
+
+impl<'a> Trainer<'a> {
+    /// Train on `session_id`, reading training events from `sys`, a
+    /// subscription to that session's `sys` stream.
+    pub fn new(
+        client: &'a CortexClient,
+        cortex_token: &str,
+        session_id: &str,
+        sys: Pin<Box<dyn Stream<Item = SysEvent> + Send>>,
+        config: TrainerConfig,
+    ) -> Self {
+        Self {
+            channel: TrainingChannel::new(client, cortex_token, session_id, sys),
+            config,
+        }
+    }
+
+    /// Subscribe to the `sys` stream of `session_id` and train on it.
+    ///
+    /// # Errors
+    /// Returns any error from subscribing to the `sys` stream.
+    pub async fn subscribe(
+        client: &'a CortexClient,
+        cortex_token: &str,
+        session_id: &str,
+        config: TrainerConfig,
+    ) -> CortexResult<Self> {
+        let sys = streams::subscribe_sys(client, cortex_token, session_id).await?;
+        Ok(Self::new(client, cortex_token, session_id, sys, config))
+    }
+
+    /// The settings.
+    #[must_use]
+    pub fn config(&self) -> &TrainerConfig {
+        &self.config
+    }
+
+    /// Receive [`TrainingProgress`] events from now on.
+    #[must_use]
+    pub fn progress(&self) -> broadcast::Receiver<TrainingProgress> {
+        self.channel.progress.subscribe()
+    }
+
+    /// Make `action` one of the session's active mental command actions,
+    /// adding it to the current ones if needed, and return the active
+    /// actions. `neutral` is always trainable and is left alone.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ConfigError`] if
+    /// [`MAX_ACTIVE_MENTAL_COMMAND_ACTIONS`] other actions are already
+    /// active, [`CortexError::ProtocolError`] if the active actions cannot
+    /// be parsed, or any error from `mentalCommandActiveAction`.
+    pub async fn ensure_active(&self, action: &str) -> CortexResult<Vec<String>> {
+        let channel = &self.channel;
+        let current = channel
+            .client
+            .mental_command_active_action(&channel.cortex_token, &channel.session_id, None)
+            .await?;
+        let mut active: Vec<String> = parse_list(&current, "mental command active actions")?;
+        if action == "neutral" || active.iter().any(|a| a == action) {
+            return Ok(active);
+        }
+        if active.iter().filter(|a| *a != "neutral").count() >= MAX_ACTIVE_MENTAL_COMMAND_ACTIONS {
+            return Err(CortexError::ConfigError {
+                reason: format!(
+                    "Cannot activate {action}: {MAX_ACTIVE_MENTAL_COMMAND_ACTIONS} mental command actions are already active ({})",
+                    active.join(", ")
+                ),
+            });
+        }
+        active.push(action.to_string());
+        let actions: Vec<&str> = active.iter().map(String::as_str).collect();
+        channel
+            .client
+            .mental_command_active_action(
+                &channel.cortex_token,
+                &channel.session_id,
+                Some(&actions),
+            )
+            .await?;
+        Ok(active)
+    }
+
+    /// Train `action` until a recording is accepted or rejected, or the
+    /// attempts run out.
+    ///
+    /// The action is activated first (see [`Self::ensure_active`]). Each
+    /// attempt sends `training start` and waits for `MC_Succeeded` or
+    /// `MC_Failed`; failed recordings are retried up to
+    /// [`TrainerConfig::max_attempts`] times. After a successful recording
+    /// `decide` chooses whether to accept it, reject it, or record again.
+    /// Once a recording is accepted the profile is saved if
+    /// [`TrainerConfig::save_profile`] is set; a failed save is reported in
+    /// [`TrainingOutcome::profile`] rather than as an error.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::Timeout`] if an expected event does not
+    /// arrive within [`TrainerConfig::event_timeout`],
+    /// [`CortexError::StreamError`] if the `sys` stream ends, or any error
+    /// from activating the action or the `training` calls.
+    pub async fn train_action<F>(
+        &mut self,
+        action: &str,
+        decide: F,
+    ) -> CortexResult<TrainingOutcome>
+    where
+        F: AsyncFnMut(&TrainingAttempt) -> TrainingDecision,
+    {
+        self.ensure_active(action).await?;
+        self.channel
+            .run(DetectionType::MentalCommand, action, &self.config, decide)
+            .await
+    }
+}
+
+// ─── Facial Expression Training ─────────────────────────────────────────
+
+/// Largest facial expression threshold Cortex accepts.
+pub const MAX_FACIAL_EXPRESSION_THRESHOLD: u32 = 1000;
+
+/// Signature a facial expression detection runs on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum FacialSignatureType {
+    /// Built-in signature that works without training.
+    Universal,
+    /// Signature built from the profile's trained actions.
+    Trained,
+}
+
+impl FacialSignatureType {
+    /// Returns the Cortex API string for this signature type.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FacialSignatureType::Universal => "universal",
+            FacialSignatureType::Trained => "trained",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "universal" => Some(FacialSignatureType::Universal),
+            "trained" => Some(FacialSignatureType::Trained),
+            _ => None,
+        }
+    }
+}
+
+/// Facial expression signature of a session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FacialSignature {
+    /// Signature in use.
+    pub current: FacialSignatureType,
+    /// Signatures the profile can switch to. `Trained` appears once
+    /// neutral and at least one other action are trained.
+    pub available: Vec<FacialSignatureType>,
+}
+
+/// Guided facial expression training for one session, with signature and
+/// threshold management. The mental command counterpart is [`Trainer`].
+///
+/// Signature and threshold changes apply to the profile loaded in the
+/// session; [`Self::save_profile`] persists them.
+pub struct FacialTrainer<'a> {
+    channel: TrainingChannel<'a>,
+    config: TrainerConfig,
+}
+
+impl<'a> FacialTrainer<'a> {
+    /// Train on `session_id`, reading training events from `sys`, a
+    /// subscription to that session's `sys` stream.
+    pub fn new(
+        client: &'a CortexClient,
+        cortex_token: &str,
+        session_id: &str,
+        sys: Pin<Box<dyn Stream<Item = SysEvent> + Send>>,
+        config: TrainerConfig,
+    ) -> Self {
+        Self {
+            channel: TrainingChannel::new(client, cortex_token, session_id, sys),
+            config,
+        }
+    }
+
+    /// Subscribe to the `sys` stream of `session_id` and train on it.
+    ///
+    /// # Errors
+    /// Returns any error from subscribing to the `sys` stream.
+    pub async fn subscribe(
+        client: &'a CortexClient,
+        cortex_token: &str,
+        session_id: &str,
+        config: TrainerConfig,
+    ) -> CortexResult<Self> {
+        let sys = streams::subscribe_sys(client, cortex_token, session_id).await?;
+        Ok(Self::new(client, cortex_token, session_id, sys, config))
+    }
+
+    /// The settings.
+    #[must_use]
+    pub fn config(&self) -> &TrainerConfig {
+        &self.config
+    }
+
+    /// Receive [`TrainingProgress`] events from now on.
+    #[must_use]
+    pub fn progress(&self) -> broadcast::Receiver<TrainingProgress> {
+        self.channel.progress.subscribe()
+    }
+
+    /// Train `action` until a recording is accepted or rejected, or the
+    /// attempts run out. Works like [`Trainer::train_action`] with
+    /// `FE_*` events; facial actions need no activation.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::Timeout`] if an expected event does not
+    /// arrive within [`TrainerConfig::event_timeout`],
+    /// [`CortexError::StreamError`] if the `sys` stream ends, or any error
+    /// from the `training` calls.
+    pub async fn train_action<F>(
+        &mut self,
+        action: &str,
+        decide: F,
+    ) -> CortexResult<TrainingOutcome>
+    where
+        F: AsyncFnMut(&TrainingAttempt) -> TrainingDecision,
+    {
+        self.channel
+            .run(
+                DetectionType::FacialExpression,
+                action,
+                &self.config,
+                decide,
+            )
+            .await
+    }
+
+    /// The session's current and available signatures.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ProtocolError`] if the response has an
+    /// unexpected shape, or any error from `facialExpressionSignatureType`.
+    pub async fn signature(&self) -> CortexResult<FacialSignature> {
+        let result = self.signature_request("get", None).await?;
+        parse_signature(&result)
+    }
+
+    /// Switch the session to `signature`. Cortex refuses
+    /// [`FacialSignatureType::Trained`] until it is available.
+    ///
+    /// # Errors
+    /// Returns any error from `facialExpressionSignatureType`.
+    pub async fn set_signature(&self, signature: FacialSignatureType) -> CortexResult<()> {
+        self.signature_request("set", Some(signature)).await?;
+        self.channel
+            .report(TrainingProgress::SignatureChanged(signature));
+        Ok(())
+    }
+
+    async fn signature_request(
+        &self,
+        status: &str,
+        signature: Option<FacialSignatureType>,
+    ) -> CortexResult<Value> {
+        let channel = &self.channel;
+        channel
+            .client
+            .facial_expression_signature_type_with(
+                &channel.cortex_token,
+                &FacialExpressionSignatureTypeRequest {
+                    status: status.to_string(),
+                    profile: None,
+                    session: Some(channel.session_id.clone()),
+                    signature: signature.map(|s| s.as_str().to_string()),
+                },
+            )
+            .await
+    }
+
+    /// Threshold (0–1000) of `action` in the session.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ProtocolError`] if the response has an
+    /// unexpected shape, or any error from `facialExpressionThreshold`.
+    pub async fn threshold(&self, action: &str) -> CortexResult<u32> {
+        let result = self.threshold_request("get", action, None).await?;
+        parse_facial_expression_threshold(&result)
+    }
+
+    /// Set the threshold of `action`. Higher values make the action harder
+    /// to trigger.
+    ///
+    /// # Errors
+    /// Returns [`CortexError::ConfigError`] if `threshold` exceeds
+    /// [`MAX_FACIAL_EXPRESSION_THRESHOLD`], or any error from
+    /// `facialExpressionThreshold`.
+    pub async fn set_threshold(&self, action: &str, threshold: u32) -> CortexResult<()> {
+        if threshold > MAX_FACIAL_EXPRESSION_THRESHOLD {
+            return Err(CortexError::ConfigError {
+                reason: format!(
+                    "Facial expression threshold {threshold} for {action} exceeds {MAX_FACIAL_EXPRESSION_THRESHOLD}"
+                ),
+            });
+        }
+        self.threshold_request("set", action, Some(threshold))
+            .await?;
+        self.channel.report(TrainingProgress::ThresholdChanged {
+            action: action.to_string(),
+            threshold,
+        });
+        Ok(())
+    }
+
+    async fn threshold_request(
+        &self,
+        status: &str,
+        action: &str,
+        value: Option<u32>,
+    ) -> CortexResult<Value> {
+        let channel = &self.channel;
+        channel
+            .client
+            .facial_expression_threshold_with(
+                &channel.cortex_token,
+                &FacialExpressionThresholdRequest {
+                    status: status.to_string(),
+                    action: action.to_string(),
+                    profile: None,
+                    session: Some(channel.session_id.clone()),
+                    value,
+                },
+            )
+            .await
+    }
+
+    /// Save the profile named in [`TrainerConfig::save_profile`], e.g.
+    /// after changing the signature or thresholds.
+    pub async fn save_profile(&self) -> ProfileSaveStatus {
+        match &self.config.save_profile {
+            Some(target) => self.channel.save_profile(target).await,
+            None => ProfileSaveStatus::NotRequested,
+        }
+    }
+}
+
+fn parse_signature(value: &Value) -> CortexResult<FacialSignature> {
+    let current = value
+        .get("currentSig")
+        .and_then(Value::as_str)
+        .and_then(FacialSignatureType::parse)
+        .ok_or_else(|| CortexError::ProtocolError {
+            reason: format!("Failed to parse facial expression signature type: {value}"),
+        })?;
+    let available = value
+        .get("availableSig")
+        .and_then(Value::as_array)
+        .map_or_else(
+            || vec![current],
+            |names| {
+                names
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .filter_map(FacialSignatureType::parse)
+                    .collect()
+            },
+        );
+    Ok(FacialSignature { current, available })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_thresholds() {
+        let mc = parse_mental_command_threshold(
+            &json!({"currentThreshold": 0.5, "lastTrainingScore": 0.75}),
+        )
+        .unwrap();
+        assert!((mc.current - 0.5).abs() < f64::EPSILON);
+        assert!((mc.last_training_score.unwrap() - 0.75).abs() < f64::EPSILON);
+        assert!(parse_mental_command_threshold(&json!({})).is_err());
+
+        assert_eq!(
+            parse_facial_expression_threshold(&json!({"currentThreshold": 600})).unwrap(),
+            600
+        );
+        assert_eq!(parse_facial_expression_threshold(&json!(300)).unwrap(), 300);
+        assert!(parse_facial_expression_threshold(&json!("high")).is_err());
+    }
+
+    fn sys(detection: &str, event: &str) -> SysEvent {
+        SysEvent {
+            sid: "session-1".into(),
+            time: 0.0,
+            sys: vec![detection.into(), event.into()],
+        }
+    }
+
+    #[test]
+    fn test_training_event_parse() {
+        assert_eq!(
+            TrainingEvent::parse(&sys("mentalCommand", "MC_Succeeded")),
+            Some((DetectionType::MentalCommand, TrainingEvent::Succeeded))
+        );
+        assert_eq!(
+            TrainingEvent::parse(&sys("facialExpression", "FE_AutoReject")),
+            Some((
+                DetectionType::FacialExpression,
+                TrainingEvent::Other("AutoReject".into())
+            ))
+        );
+        assert_eq!(
+            TrainingEvent::parse(&sys("headset", "HeadsetConnected")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_signature() {
+        let signature = parse_signature(
+            &json!({"currentSig": "universal", "availableSig": ["universal", "trained"]}),
+        )
+        .unwrap();
+        assert_eq!(signature.current, FacialSignatureType::Universal);
+        assert_eq!(
+            signature.available,
+            [FacialSignatureType::Universal, FacialSignatureType::Trained]
+        );
+        let signature = parse_signature(&json!({"currentSig": "trained"})).unwrap();
+        assert_eq!(signature.available, [FacialSignatureType::Trained]);
+        assert!(parse_signature(&json!({"currentSig": "custom"})).is_err());
+    }
+
+    #[test]
+    fn test_detection_summary_is_trained() {
+        let summary = DetectionTrainingSummary {
+            total_times_training: 3,
+            trained_actions: vec![
+                TrainedAction {
+                    action: "neutral".into(),
+                    times: 3,
+                },
+                TrainedAction {
+                    action: "push".into(),
+                    times: 0,
+                },
+            ],
+        };
+        assert!(summary.is_trained("neutral"));
+        assert!(!summary.is_trained("push"));
+        assert!(!summary.is_trained("pull"));
+    }
+}
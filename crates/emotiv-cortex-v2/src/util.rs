@@ -0,0 +1,107 @@
+//! Crate-internal numeric conversions and clock helpers.
+//!
+//! The casts are the lossy `as` conversions the DSP and statistics code
+//! accepts by design; keeping them here keeps the clippy allowances in
+//! one place.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Wall-clock time in Unix epoch microseconds. A clock before the epoch
+/// reads as `0`; one past the `i64` range saturates at `i64::MAX`.
+pub(crate) fn now_micros() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            i64::try_from(elapsed.as_micros()).unwrap_or(i64::MAX)
+        })
+}
+
+/// Wall-clock time in Unix epoch milliseconds, with sub-millisecond
+/// precision. A clock before the epoch reads as `0`.
+pub(crate) fn epoch_millis() -> f64 {
+    epoch_secs() * 1000.0
+}
+
+/// Wall-clock time in Unix epoch seconds. A clock before the epoch reads
+/// as `0`.
+pub(crate) fn epoch_secs() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |elapsed| elapsed.as_secs_f64())
+}
+
+/// Number of samples `duration` spans at `sampling_rate_hz`, rounded;
+/// `0` for a non-positive or non-finite product.
+pub(crate) fn duration_to_samples(duration: Duration, sampling_rate_hz: f64) -> usize {
+    let samples = (duration.as_secs_f64() * sampling_rate_hz).round();
+    if samples.is_finite() && samples > 0.0 {
+        f64_to_usize(samples)
+    } else {
+        0
+    }
+}
+
+/// `duration` in nanoseconds, saturating at `u64::MAX`.
+pub(crate) fn duration_to_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// Saturates at `u64::MAX`, which no supported target reaches.
+pub(crate) fn usize_to_u64(value: usize) -> u64 {
+    u64::try_from(value).unwrap_or(u64::MAX)
+}
+
+/// For values known to fit, such as indices below a buffer length.
+#[allow(clippy::cast_possible_truncation)]
+pub(crate) fn u64_to_usize(value: u64) -> usize {
+    value as usize
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn usize_to_f64(value: usize) -> f64 {
+    value as f64
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn usize_to_f32(value: usize) -> f32 {
+    value as f32
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn u64_to_f64(value: u64) -> f64 {
+    value as f64
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn i64_to_f64(value: i64) -> f64 {
+    value as f64
+}
+
+/// Nearest `f32`; values beyond its range become infinite.
+#[allow(clippy::cast_possible_truncation)]
+pub(crate) fn f64_to_f32(value: f64) -> f32 {
+    value as f32
+}
+
+// Float-to-integer casts truncate toward zero: NaN becomes `0` and values
+// outside the target's range saturate at its nearest bound.
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub(crate) fn f64_to_usize(value: f64) -> usize {
+    value as usize
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub(crate) fn f64_to_u64(value: f64) -> u64 {
+    value as u64
+}
+
+#[allow(clippy::cast_possible_truncation)]
+pub(crate) fn f64_to_i64(value: f64) -> i64 {
+    value as i64
+}
+
+#[allow(clippy::cast_possible_truncation)]
+pub(crate) fn f64_to_i16(value: f64) -> i16 {
+    value as i16
+}
@@ -0,0 +1,243 @@
+//! # Outgoing Frame Path
+//!
+//! The reader half of the WebSocket belongs to the client's reader loop;
+//! the writer half is shared by every task that sends: RPC calls,
+//! subscriptions, marker injection. By default senders take turns on a
+//! lock around the socket sink. When many tasks send at once (markers at
+//! stimulus rate plus UI-driven RPCs), they queue on that lock.
+//!
+//! [`CortexClient::writer_stats`] reports how often a send had to wait
+//! and for how long, so the bottleneck is measurable. With
+//! [`WriterConfig::dedicated_task`] set, a dedicated task owns the sink
+//! and senders hand it frames through a bounded queue instead; a sender
+//! then only waits while the queue is full.
+//!
+//! ```toml
+//! [writer]
+//! dedicated_task = true
+//! queue_capacity = 256
+//! ```
+//!
+//! In both modes a send completes once the frame has been written to the
+//! socket, so send errors still reach the caller.
+//!
+//! [`CortexClient::writer_stats`]: crate::CortexClient::writer_stats
+//! [`WriterConfig::dedicated_task`]: crate::config::WriterConfig::dedicated_task
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use futures_util::SinkExt;
+use serde::Serialize;
+use tokio::sync::{Mutex, mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+
+use crate::client::WsWriter;
+use crate::config::WriterConfig;
+use crate::util::duration_to_nanos;
+
+/// Snapshot of the outgoing frame path's counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct WriterStats {
+    /// Whether frames go through the dedicated writer task.
+    pub dedicated_task: bool,
+    /// Frames sent (or attempted), including the Close frame.
+    pub sends: u64,
+    /// Sends that found the socket busy: the lock held by another sender,
+    /// or other frames already queued for the writer task.
+    pub contended_sends: u64,
+    /// Total time senders spent waiting before their frame was written.
+    pub total_wait: Duration,
+    /// Longest single wait.
+    pub max_wait: Duration,
+    /// Frames currently queued for the writer task (always 0 without it).
+    pub queued: usize,
+}
+
+impl WriterStats {
+    /// Mean wait per send.
+    #[must_use]
+    pub fn mean_wait(&self) -> Duration {
+        if self.sends == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_nanos(duration_to_nanos(self.total_wait) / self.sends)
+    }
+}
+
+#[derive(Debug, Default)]
+struct WriterCounters {
+    sends: AtomicU64,
+    contended_sends: AtomicU64,
+    wait_nanos: AtomicU64,
+    max_wait_nanos: AtomicU64,
+}
+
+impl WriterCounters {
+    fn record_start(&self, contended: bool) {
+        self.sends.fetch_add(1, Ordering::Relaxed);
+        if contended {
+            self.contended_sends.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn record_wait(&self, wait: Duration) {
+        let nanos = duration_to_nanos(wait);
+        self.wait_nanos.fetch_add(nanos, Ordering::Relaxed);
+        self.max_wait_nanos.fetch_max(nanos, Ordering::Relaxed);
+    }
+}
+
+/// Work for the dedicated writer task.
+enum WriterCommand {
+    Send {
+        message: Message,
+        enqueued: Instant,
+        done: oneshot::Sender<Result<(), WsError>>,
+    },
+    Close {
+        done: oneshot::Sender<Result<(), WsError>>,
+    },
+}
+
+enum WritePath {
+    /// Senders lock the sink in turn.
+    Locked(Mutex<WsWriter>),
+    /// A task owns the sink and drains `queue`.
+    Queued {
+        queue: mpsc::Sender<WriterCommand>,
+        task: JoinHandle<()>,
+    },
+}
+
+/// Shared write half of a connection. See the [module documentation](self).
+pub(crate) struct FrameWriter {
+    path: WritePath,
+    counters: Arc<WriterCounters>,
+}
+
+impl FrameWriter {
+    /// Wrap the socket sink, spawning the writer task if configured.
+    pub(crate) fn new(sink: WsWriter, config: &WriterConfig) -> Self {
+        let counters = Arc::new(WriterCounters::default());
+        let path = if config.dedicated_task {
+            let (queue, commands) = mpsc::channel(config.queue_capacity.max(1));
+            let task = tokio::spawn(Self::writer_task(sink, commands, Arc::clone(&counters)));
+            WritePath::Queued { queue, task }
+        } else {
+            WritePath::Locked(Mutex::new(sink))
+        };
+        Self { path, counters }
+    }
+
+    /// Write one frame, returning once it is on the socket.
+    pub(crate) async fn send(&self, message: Message) -> Result<(), WsError> {
+        let started = Instant::now();
+        match &self.path {
+            WritePath::Locked(sink) => {
+                let (mut sink, contended) = match sink.try_lock() {
+                    Ok(guard) => (guard, false),
+                    Err(_) => (sink.lock().await, true),
+                };
+                self.counters.record_start(contended);
+                self.counters.record_wait(started.elapsed());
+                sink.send(message).await
+            }
+            WritePath::Queued { queue, .. } => {
+                let (done, result) = oneshot::channel();
+                self.enqueue(
+                    queue,
+                    WriterCommand::Send {
+                        message,
+                        enqueued: started,
+                        done,
+                    },
+                )
+                .await?;
+                result.await.unwrap_or(Err(WsError::AlreadyClosed))
+            }
+        }
+    }
+
+    /// Send a Close frame and close the sink. Frames queued before the
+    /// call are written first.
+    pub(crate) async fn close(&self) -> Result<(), WsError> {
+        match &self.path {
+            WritePath::Locked(sink) => {
+                let started = Instant::now();
+                let mut sink = sink.lock().await;
+                self.counters.record_start(false);
+                self.counters.record_wait(started.elapsed());
+                sink.close().await
+            }
+            WritePath::Queued { queue, .. } => {
+                let (done, result) = oneshot::channel();
+                self.enqueue(queue, WriterCommand::Close { done }).await?;
+                result.await.unwrap_or(Err(WsError::AlreadyClosed))
+            }
+        }
+    }
+
+    /// Current counters.
+    pub(crate) fn stats(&self) -> WriterStats {
+        let (dedicated_task, queued) = match &self.path {
+            WritePath::Locked(_) => (false, 0),
+            WritePath::Queued { queue, .. } => (true, queue.max_capacity() - queue.capacity()),
+        };
+        WriterStats {
+            dedicated_task,
+            sends: self.counters.sends.load(Ordering::Relaxed),
+            contended_sends: self.counters.contended_sends.load(Ordering::Relaxed),
+            total_wait: Duration::from_nanos(self.counters.wait_nanos.load(Ordering::Relaxed)),
+            max_wait: Duration::from_nanos(self.counters.max_wait_nanos.load(Ordering::Relaxed)),
+            queued,
+        }
+    }
+
+    async fn enqueue(
+        &self,
+        queue: &mpsc::Sender<WriterCommand>,
+        command: WriterCommand,
+    ) -> Result<(), WsError> {
+        self.counters
+            .record_start(queue.capacity() < queue.max_capacity());
+        queue
+            .send(command)
+            .await
+            .map_err(|_| WsError::AlreadyClosed)
+    }
+
+    async fn writer_task(
+        mut sink: WsWriter,
+        mut commands: mpsc::Receiver<WriterCommand>,
+        counters: Arc<WriterCounters>,
+    ) {
+        while let Some(command) = commands.recv().await {
+            match command {
+                WriterCommand::Send {
+                    message,
+                    enqueued,
+                    done,
+                } => {
+                    counters.record_wait(enqueued.elapsed());
+                    let _ = done.send(sink.send(message).await);
+                }
+                WriterCommand::Close { done } => {
+                    let _ = done.send(sink.close().await);
+                    break;
+                }
+            }
+        }
+        tracing::debug!("Writer task stopped");
+    }
+}
+
+impl Drop for FrameWriter {
+    fn drop(&mut self) {
+        if let WritePath::Queued { task, .. } = &self.path {
+            task.abort();
+        }
+    }
+}
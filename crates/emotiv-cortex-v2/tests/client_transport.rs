@@ -1,7 +1,15 @@
+#![allow(clippy::manual_let_else, clippy::unreadable_literal)]
+
 mod support;
 
+use std::sync::Arc;
+
+use emotiv_cortex_v2::config::StrictProtocolMode;
+use emotiv_cortex_v2::ownership::CleanupScope;
 use emotiv_cortex_v2::protocol::constants::{Methods, Streams};
 use emotiv_cortex_v2::protocol::headset::QueryHeadsetsOptions;
+use emotiv_cortex_v2::protocol::rpc::{ConnectionPrefixedIds, CounterIds};
+use emotiv_cortex_v2::replay::{FrameCapture, ReplayClient, ReplayOptions};
 use emotiv_cortex_v2::{CortexClient, CortexConfig, CortexError, streams};
 use futures_util::StreamExt;
 use serde_json::{Value, json};
@@ -197,6 +205,46 @@ async fn rpc_timeout_is_reported_and_next_call_still_works() {
     client.disconnect().await.unwrap();
 }
 
+#[tokio::test]
+async fn rate_limiter_paces_calls_past_the_burst() {
+    let Some(mut server) = start_server_or_skip("rate_limiter_paces_calls_past_the_burst").await
+    else {
+        return;
+    };
+    let mut config = test_config(server.ws_url());
+    config.rate_limit.enabled = true;
+    config.rate_limit.query.rate_per_sec = 20.0;
+    config.rate_limit.query.burst = 1;
+    let mut client = CortexClient::connect(&config).await.unwrap();
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        for _ in 0..2 {
+            let request = connection
+                .recv_request_method(Methods::GET_CORTEX_INFO)
+                .await;
+            connection
+                .send_result(rpc_id(&request), json!({"ok": true}))
+                .await;
+        }
+    });
+
+    client.get_cortex_info().await.unwrap();
+    client.get_cortex_info().await.unwrap();
+    responder.await.unwrap();
+
+    let stats = client.rate_limit_stats();
+    let query = stats
+        .iter()
+        .find(|s| s.class == emotiv_cortex_v2::rate_limit::MethodClass::Query)
+        .unwrap();
+    assert_eq!(query.acquired, 2);
+    assert_eq!(query.delayed, 1);
+    assert!(query.total_wait >= std::time::Duration::from_millis(20));
+
+    client.disconnect().await.unwrap();
+}
+
 #[tokio::test]
 async fn send_failure_cleans_pending_response_entry() {
     let mut server = match start_server_or_skip("send_failure_cleans_pending_response_entry").await
@@ -215,6 +263,49 @@ async fn send_failure_cleans_pending_response_entry() {
     assert_eq!(client.pending_response_count().await, 0);
 }
 
+#[tokio::test]
+async fn dedicated_writer_task_sends_concurrent_calls_and_counts_them() {
+    let Some(mut server) =
+        start_server_or_skip("dedicated_writer_task_sends_concurrent_calls_and_counts_them").await
+    else {
+        return;
+    };
+    let mut config = test_config(server.ws_url());
+    config.writer.dedicated_task = true;
+    config.writer.queue_capacity = 2;
+    let mut client = CortexClient::connect(&config).await.unwrap();
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        for _ in 0..8 {
+            let request = connection
+                .recv_request_method(Methods::GET_CORTEX_INFO)
+                .await;
+            connection
+                .send_result(rpc_id(&request), json!({"ok": true}))
+                .await;
+        }
+    });
+
+    let calls: Vec<_> = (0..8).map(|_| client.get_cortex_info()).collect();
+    for result in futures_util::future::join_all(calls).await {
+        assert_eq!(result.unwrap()["ok"], true);
+    }
+    responder.await.unwrap();
+
+    let stats = client.writer_stats();
+    assert!(stats.dedicated_task);
+    assert_eq!(stats.sends, 8);
+    assert!(stats.contended_sends <= 8);
+    assert_eq!(stats.queued, 0);
+    assert!(stats.max_wait <= stats.total_wait);
+
+    client.disconnect().await.unwrap();
+    let err = client.get_cortex_info().await.unwrap_err();
+    assert!(matches!(err, CortexError::WebSocket(_)));
+    assert_eq!(client.pending_response_count().await, 0);
+}
+
 #[tokio::test]
 async fn stop_reader_finishes_without_polling_delay() {
     let mut server = match start_server_or_skip("stop_reader_finishes_without_polling_delay").await
@@ -239,6 +330,175 @@ async fn stop_reader_finishes_without_polling_delay() {
     client.disconnect().await.unwrap();
 }
 
+#[tokio::test]
+async fn ping_keeps_an_answering_connection_alive() {
+    let Some(mut server) = start_server_or_skip("ping_keeps_an_answering_connection_alive").await
+    else {
+        return;
+    };
+    let mut config = test_config(server.ws_url());
+    config.ping.enabled = true;
+    config.ping.interval_secs = 1;
+    config.ping.pong_timeout_secs = 2;
+    let client = CortexClient::connect(&config).await.unwrap();
+    let mut lost = client.liveness_receiver();
+    let _connection = server.accept_connection().await;
+
+    tokio::time::sleep(std::time::Duration::from_millis(3500)).await;
+
+    assert!(client.is_connected());
+    assert!(matches!(
+        lost.try_recv(),
+        Err(tokio::sync::broadcast::error::TryRecvError::Empty)
+    ));
+}
+
+#[tokio::test]
+async fn ping_drops_a_silent_connection_and_fails_pending_calls() {
+    // A server that completes the handshake and then never reads again,
+    // so pings go unanswered.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+        drop(ws);
+    });
+
+    let mut config = test_config(url);
+    config.timeouts.rpc_timeout_secs = 30;
+    config.ping.enabled = true;
+    config.ping.interval_secs = 1;
+    config.ping.pong_timeout_secs = 2;
+    let client = Arc::new(CortexClient::connect(&config).await.unwrap());
+    let mut lost = client.liveness_receiver();
+
+    let started = std::time::Instant::now();
+    let pending = {
+        let client = Arc::clone(&client);
+        tokio::spawn(async move { client.get_cortex_info().await })
+    };
+    let event = tokio::time::timeout(std::time::Duration::from_secs(5), lost.recv())
+        .await
+        .expect("liveness loss not reported")
+        .unwrap();
+    let err = pending.await.unwrap().unwrap_err();
+
+    assert!(event.silent_for >= std::time::Duration::from_secs(2));
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    assert!(matches!(err, CortexError::ConnectionLost { .. }));
+    assert!(matches!(
+        client.get_cortex_info().await,
+        Err(CortexError::ConnectionLost { .. })
+    ));
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(!client.is_connected());
+    server.abort();
+}
+
+#[tokio::test]
+async fn shutdown_drains_in_flight_calls_and_unsubscribes() {
+    let Some(mut server) =
+        start_server_or_skip("shutdown_drains_in_flight_calls_and_unsubscribes").await
+    else {
+        return;
+    };
+    let config = test_config(server.ws_url());
+    let client = Arc::new(CortexClient::connect(&config).await.unwrap());
+    let mut connection = server.accept_connection().await;
+
+    let subscribe = {
+        let client = Arc::clone(&client);
+        tokio::spawn(async move {
+            client
+                .subscribe_streams("token", "session-1", &[Streams::EEG])
+                .await
+        })
+    };
+    let request = connection.recv_request_method(Methods::SUBSCRIBE).await;
+    connection
+        .send_result(
+            rpc_id(&request),
+            json!({"success": [{"streamName": "eeg", "cols": ["COUNTER"], "sid": "session-1"}], "failure": []}),
+        )
+        .await;
+    subscribe.await.unwrap().unwrap();
+
+    let in_flight = {
+        let client = Arc::clone(&client);
+        tokio::spawn(async move { client.get_cortex_info().await })
+    };
+    let info_request = connection
+        .recv_request_method(Methods::GET_CORTEX_INFO)
+        .await;
+
+    let shutdown = {
+        let client = Arc::clone(&client);
+        tokio::spawn(async move { client.shutdown(std::time::Duration::from_secs(1)).await })
+    };
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let refused = client.get_cortex_info().await.unwrap_err();
+    assert!(matches!(refused, CortexError::ConnectionLost { .. }));
+
+    connection
+        .send_result(rpc_id(&info_request), json!({"version": "mock-1.0.0"}))
+        .await;
+    assert_eq!(in_flight.await.unwrap().unwrap()["version"], "mock-1.0.0");
+
+    let unsubscribe = connection.recv_request_method(Methods::UNSUBSCRIBE).await;
+    assert_eq!(unsubscribe["params"]["session"], "session-1");
+    assert_eq!(unsubscribe["params"]["streams"], json!(["eeg"]));
+    connection
+        .send_result(rpc_id(&unsubscribe), json!({"success": [], "failure": []}))
+        .await;
+
+    let report = shutdown.await.unwrap();
+    assert_eq!(report.abandoned_requests, 0);
+    assert_eq!(
+        report.unsubscribed,
+        vec![("session-1".to_string(), "eeg".to_string())]
+    );
+    assert!(report.unsubscribe_errors.is_empty());
+    assert!(!client.is_connected());
+}
+
+#[tokio::test]
+async fn shutdown_wakes_when_last_in_flight_call_is_answered() {
+    let Some(mut server) =
+        start_server_or_skip("shutdown_wakes_when_last_in_flight_call_is_answered").await
+    else {
+        return;
+    };
+    let config = test_config(server.ws_url());
+    let client = Arc::new(CortexClient::connect(&config).await.unwrap());
+    let mut connection = server.accept_connection().await;
+
+    let in_flight = {
+        let client = Arc::clone(&client);
+        tokio::spawn(async move { client.get_cortex_info().await })
+    };
+    let info_request = connection
+        .recv_request_method(Methods::GET_CORTEX_INFO)
+        .await;
+
+    let shutdown = {
+        let client = Arc::clone(&client);
+        tokio::spawn(async move { client.shutdown(std::time::Duration::from_secs(60)).await })
+    };
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    connection
+        .send_result(rpc_id(&info_request), json!({"version": "mock-1.0.0"}))
+        .await;
+    assert_eq!(in_flight.await.unwrap().unwrap()["version"], "mock-1.0.0");
+
+    let report = tokio::time::timeout(std::time::Duration::from_secs(10), shutdown)
+        .await
+        .expect("shutdown should not wait out its grace period")
+        .unwrap();
+    assert_eq!(report.abandoned_requests, 0);
+}
+
 #[tokio::test]
 async fn stream_dispatch_stats_track_overflow_drops() {
     let mut server = match start_server_or_skip("stream_dispatch_stats_track_overflow_drops").await
@@ -316,6 +576,76 @@ async fn subscribe_eeg_routes_stream_event_to_typed_stream() {
     client.disconnect().await.unwrap();
 }
 
+#[tokio::test]
+async fn subscribe_all_returns_supported_streams_and_reports_the_rest() {
+    use emotiv_cortex_v2::headset::HeadsetModel;
+
+    let Some(mut server) =
+        start_server_or_skip("subscribe_all_returns_supported_streams_and_reports_the_rest").await
+    else {
+        return;
+    };
+    let config = test_config(server.ws_url());
+    let mut client = CortexClient::connect(&config).await.unwrap();
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        let request = connection.recv_request_method(Methods::SUBSCRIBE).await;
+        assert_eq!(request["params"]["streams"], json!(Streams::ALL));
+        connection
+            .send_result(
+                rpc_id(&request),
+                json!({
+                    "success": [
+                        {"streamName": "dev", "cols": [], "sid": "session-1"},
+                        "mot", "eq", "pow", "met", "fac", "sys"
+                    ],
+                    "failure": [
+                        {"streamName": "eeg", "code": -32002, "message": "No EEG license"},
+                        {"streamName": "com", "code": -32016, "message": "No profile loaded"}
+                    ]
+                }),
+            )
+            .await;
+        connection
+            .push_event(json!({
+                "sid": "session-1",
+                "time": 1_609_459_200.0,
+                "dev": [3, 2, [4, 4, 4, 4, 4, 100], 100]
+            }))
+            .await;
+    });
+
+    let mut all = streams::subscribe_all(&client, "token", "session-1", &HeadsetModel::Insight)
+        .await
+        .unwrap();
+    responder.await.unwrap();
+
+    assert!(all.eeg.is_none());
+    assert!(all.mental_commands.is_none());
+    assert_eq!(
+        all.subscribed(),
+        vec!["dev", "mot", "eq", "pow", "met", "fac", "sys"]
+    );
+    let unsupported: Vec<_> = all
+        .unsupported
+        .iter()
+        .map(|f| (f.stream_name.as_str(), f.code))
+        .collect();
+    assert_eq!(unsupported, vec![("eeg", -32002), ("com", -32016)]);
+
+    let dev = tokio::time::timeout(
+        std::time::Duration::from_secs(2),
+        all.dev.as_mut().unwrap().next(),
+    )
+    .await
+    .expect("timed out waiting for dev sample")
+    .expect("typed stream ended unexpectedly");
+    assert_eq!(dev.battery_level, 3);
+
+    client.disconnect().await.unwrap();
+}
+
 #[tokio::test]
 async fn api_error_code_maps_to_domain_error() {
     let mut server = match start_server_or_skip("api_error_code_maps_to_domain_error").await {
@@ -377,200 +707,700 @@ async fn query_headsets_options_round_trip_over_transport() {
     client.disconnect().await.unwrap();
 }
 
-// ─── Error-path tests: protocol and API error mapping ───────────────────────
-
 #[tokio::test]
-async fn rpc_response_null_result_no_error_yields_protocol_error() {
-    let mut server =
-        match start_server_or_skip("rpc_response_null_result_no_error_yields_protocol_error").await
-        {
-            Some(server) => server,
-            None => return,
-        };
+async fn detected_service_version_selects_request_shapes() {
+    use emotiv_cortex_v2::compat::CortexVersion;
+
+    let Some(mut server) =
+        start_server_or_skip("detected_service_version_selects_request_shapes").await
+    else {
+        return;
+    };
     let config = test_config(server.ws_url());
     let mut client = CortexClient::connect(&config).await.unwrap();
+    assert_eq!(client.protocol_compat().version(), None);
 
     let mut connection = server.accept_connection().await;
     let responder = tokio::spawn(async move {
-        let request = connection
+        let info = connection
             .recv_request_method(Methods::GET_CORTEX_INFO)
             .await;
         connection
-            .send_json(json!({"jsonrpc": "2.0", "id": rpc_id(&request)}))
+            .send_result(rpc_id(&info), json!({"version": "2.7.3.100"}))
             .await;
-        request
+        let mut requests = Vec::new();
+        for _ in 0..2 {
+            let request = connection.recv_request().await;
+            connection.send_result(rpc_id(&request), json!([])).await;
+            requests.push(request);
+        }
+        requests
     });
 
-    let err = client.get_cortex_info().await.unwrap_err();
-    responder.await.unwrap();
-
-    assert!(matches!(err, CortexError::ProtocolError { .. }));
-    assert!(
-        err.to_string().contains("no result or error"),
-        "expected protocol error about missing result/error, got: {err}"
+    client.get_cortex_info().await.unwrap();
+    assert_eq!(
+        client.protocol_compat().version(),
+        Some(CortexVersion::new(2, 7, 3))
     );
+    let _ = client
+        .query_headsets(QueryHeadsetsOptions {
+            id: None,
+            include_flex_mappings: true,
+        })
+        .await
+        .unwrap();
+    let _ = client
+        .update_headset_custom_info("token", "HS-123", None, Some("Lab 1"))
+        .await
+        .unwrap();
+
+    let requests = responder.await.unwrap();
+    assert!(requests[0]["params"].get("includeFlexMappings").is_none());
+    assert_eq!(requests[1]["params"]["headset"], "HS-123");
+    assert!(requests[1]["params"].get("headsetId").is_none());
 
     client.disconnect().await.unwrap();
 }
 
 #[tokio::test]
-async fn rpc_response_malformed_json_yields_protocol_error() {
-    let mut server =
-        match start_server_or_skip("rpc_response_malformed_json_yields_protocol_error").await {
-            Some(server) => server,
-            None => return,
-        };
+async fn subscribe_partial_failure_drops_failed_stream_channels() {
+    let Some(mut server) =
+        start_server_or_skip("subscribe_partial_failure_drops_failed_stream_channels").await
+    else {
+        return;
+    };
     let config = test_config(server.ws_url());
     let mut client = CortexClient::connect(&config).await.unwrap();
+    let mut receivers = client.create_stream_channels(&[Streams::EEG, Streams::POW]);
 
     let mut connection = server.accept_connection().await;
     let responder = tokio::spawn(async move {
-        let request = connection
-            .recv_request_method(Methods::GET_CORTEX_INFO)
-            .await;
-        let id = rpc_id(&request);
-        // Valid numeric id so the client routes to the pending request; "error" as string
-        // so CortexResponse deserialization fails (expects { code, message }).
+        let request = connection.recv_request_method(Methods::SUBSCRIBE).await;
         connection
-            .send_json(json!({"jsonrpc": "2.0", "id": id, "error": "not an object"}))
+            .send_result(
+                rpc_id(&request),
+                json!({
+                    "success": [{"streamName": "eeg", "cols": ["COUNTER"], "sid": "session-1"}],
+                    "failure": [{"streamName": "pow", "code": -32016, "message": "no license"}]
+                }),
+            )
             .await;
-        request
     });
 
-    let err = client.get_cortex_info().await.unwrap_err();
+    let result = client
+        .subscribe_streams("token", "session-1", &[Streams::EEG, Streams::POW])
+        .await
+        .unwrap();
     responder.await.unwrap();
 
-    assert!(matches!(err, CortexError::ProtocolError { .. }));
-    assert!(
-        err.to_string().contains("parse") || err.to_string().contains("Protocol"),
-        "expected protocol/parse error, got: {err}"
-    );
+    assert!(!result.is_complete());
+    assert!(result.subscription(Streams::EEG).is_some());
+    assert_eq!(result.failed_streams(), vec![Streams::POW]);
+
+    let stats = client.stream_dispatch_stats();
+    assert!(stats.contains_key("eeg"));
+    assert!(!stats.contains_key("pow"));
+    let mut pow_rx = receivers.remove(Streams::POW).unwrap();
+    assert!(pow_rx.recv().await.is_none());
 
     client.disconnect().await.unwrap();
 }
 
 #[tokio::test]
-async fn get_user_login_result_wrong_type_yields_protocol_error() {
-    let mut server = match start_server_or_skip(
-        "get_user_login_result_wrong_type_yields_protocol_error",
-    )
-    .await
-    {
-        Some(server) => server,
-        None => return,
+async fn subscribe_helper_errors_when_stream_is_rejected() {
+    let Some(mut server) =
+        start_server_or_skip("subscribe_helper_errors_when_stream_is_rejected").await
+    else {
+        return;
     };
     let config = test_config(server.ws_url());
     let mut client = CortexClient::connect(&config).await.unwrap();
 
     let mut connection = server.accept_connection().await;
     let responder = tokio::spawn(async move {
-        let request = connection
-            .recv_request_method(Methods::GET_USER_LOGIN)
+        let request = connection.recv_request_method(Methods::SUBSCRIBE).await;
+        connection
+            .send_result(
+                rpc_id(&request),
+                json!({
+                    "success": [],
+                    "failure": [{"streamName": "pow", "code": -32016, "message": "bad stream"}]
+                }),
+            )
             .await;
-        // Result must be an array of UserLoginInfo; a number is invalid.
-        connection.send_result(rpc_id(&request), json!(123)).await;
-        request
     });
 
-    let err = client.get_user_login().await.unwrap_err();
+    let err = streams::subscribe_band_power(&client, "token", "session-1", 5)
+        .await
+        .err()
+        .expect("rejected stream must fail the helper");
     responder.await.unwrap();
 
-    assert!(matches!(err, CortexError::ProtocolError { .. }));
-    assert!(
-        err.to_string().contains("parse")
-            || err.to_string().contains("Protocol")
-            || err.to_string().contains("user login"),
-        "expected protocol/parse error for wrong result type, got: {err}"
-    );
+    assert!(matches!(err, CortexError::StreamError { .. }));
+    assert!(!client.stream_dispatch_stats().contains_key("pow"));
 
     client.disconnect().await.unwrap();
 }
 
 #[tokio::test]
-async fn api_error_token_expired_maps_to_token_expired() {
-    let mut server =
-        match start_server_or_skip("api_error_token_expired_maps_to_token_expired").await {
-            Some(server) => server,
-            None => return,
-        };
+async fn subscribe_with_retry_resubscribes_only_failed_streams() {
+    let Some(mut server) =
+        start_server_or_skip("subscribe_with_retry_resubscribes_only_failed_streams").await
+    else {
+        return;
+    };
     let config = test_config(server.ws_url());
     let mut client = CortexClient::connect(&config).await.unwrap();
+    let _receivers = client.create_stream_channels(&[Streams::EEG, Streams::MET]);
 
     let mut connection = server.accept_connection().await;
     let responder = tokio::spawn(async move {
-        let request = connection
-            .recv_request_method(Methods::GET_CORTEX_INFO)
+        let first = connection.recv_request_method(Methods::SUBSCRIBE).await;
+        connection
+            .send_result(
+                rpc_id(&first),
+                json!({
+                    "success": ["eeg"],
+                    "failure": [{"streamName": "met", "code": -32016, "message": "not ready"}]
+                }),
+            )
             .await;
+        let second = connection.recv_request_method(Methods::SUBSCRIBE).await;
+        let retried = second["params"]["streams"].clone();
         connection
-            .send_error(rpc_id(&request), -32015, "cortex token expired")
+            .send_result(rpc_id(&second), json!({"success": ["met"], "failure": []}))
             .await;
+        retried
     });
 
-    let err = client.get_cortex_info().await.unwrap_err();
-    responder.await.unwrap();
+    let result = client
+        .subscribe_streams_with_retry("token", "session-1", &[Streams::EEG, Streams::MET])
+        .await
+        .unwrap();
+    let retried = responder.await.unwrap();
 
-    assert!(matches!(err, CortexError::TokenExpired));
+    assert_eq!(retried, json!([Streams::MET]));
+    assert!(result.is_complete());
+    assert!(result.subscription(Streams::MET).is_some());
+    assert!(client.stream_dispatch_stats().contains_key("met"));
 
     client.disconnect().await.unwrap();
 }
 
 #[tokio::test]
-async fn api_error_session_error_maps_and_preserves_message() {
-    let mut server =
-        match start_server_or_skip("api_error_session_error_maps_and_preserves_message").await {
-            Some(server) => server,
-            None => return,
-        };
-    let config = test_config(server.ws_url());
-    let mut client = CortexClient::connect(&config).await.unwrap();
+async fn autodiscover_selects_first_healthy_candidate() {
+    let Some(mut server) =
+        start_server_or_skip("autodiscover_selects_first_healthy_candidate").await
+    else {
+        return;
+    };
+    let healthy_url = server.ws_url();
+    let mut config = test_config("ws://127.0.0.1:1".to_string());
+    config.discovery.candidates = vec!["ws://127.0.0.1:1".to_string(), healthy_url.clone()];
+    config.discovery.probe_timeout_secs = 1;
 
-    let mut connection = server.accept_connection().await;
     let responder = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
         let request = connection
             .recv_request_method(Methods::GET_CORTEX_INFO)
             .await;
         connection
-            .send_error(
-                rpc_id(&request),
-                -32005,
-                "session already exists for this headset",
-            )
+            .send_result(rpc_id(&request), json!({"buildNumber": "test"}))
             .await;
     });
 
-    let err = client.get_cortex_info().await.unwrap_err();
+    let report = config.autodiscover().await.unwrap();
     responder.await.unwrap();
 
-    match &err {
-        CortexError::SessionError { reason } => assert!(reason.contains("session already exists")),
-        _ => panic!("expected SessionError with message, got {err:?}"),
-    }
+    assert_eq!(report.selected_url, healthy_url);
+    assert_eq!(config.cortex_url, healthy_url);
+    assert_eq!(report.cortex_info["buildNumber"], "test");
+    assert_eq!(report.probes.len(), 2);
+    assert!(report.probes[0].error.is_some());
+    assert!(report.probes[1].error.is_none());
+}
 
-    client.disconnect().await.unwrap();
+#[tokio::test]
+async fn autodiscover_reports_every_failed_candidate() {
+    let mut config = test_config("ws://127.0.0.1:1".to_string());
+    config.discovery.candidates = vec!["ws://127.0.0.1:1".to_string()];
+
+    let err = config.autodiscover().await.unwrap_err();
+    assert!(matches!(err, CortexError::ConnectionFailed { .. }));
+    assert_eq!(config.cortex_url, "ws://127.0.0.1:1");
+
+    config.discovery.candidates.clear();
+    assert!(matches!(
+        config.autodiscover().await.unwrap_err(),
+        CortexError::ConfigError { .. }
+    ));
 }
 
+#[cfg(feature = "support-bundle")]
 #[tokio::test]
-async fn api_error_stream_error_maps_and_preserves_message() {
-    let mut server =
-        match start_server_or_skip("api_error_stream_error_maps_and_preserves_message").await {
-            Some(server) => server,
-            None => return,
-        };
+async fn support_bundle_zip_contains_redacted_diagnostics() {
+    use emotiv_cortex_v2::diagnostics::{SupportBundleInputs, collect_support_bundle};
+    use emotiv_cortex_v2::reconnect::ConnectionEvent;
+    use std::io::Read;
+
+    let Some(mut server) =
+        start_server_or_skip("support_bundle_zip_contains_redacted_diagnostics").await
+    else {
+        return;
+    };
     let config = test_config(server.ws_url());
     let mut client = CortexClient::connect(&config).await.unwrap();
 
     let mut connection = server.accept_connection().await;
     let responder = tokio::spawn(async move {
-        let request = connection
+        let info = connection
             .recv_request_method(Methods::GET_CORTEX_INFO)
             .await;
         connection
-            .send_error(rpc_id(&request), -32016, "invalid stream name")
+            .send_result(rpc_id(&info), json!({"buildNumber": "1.2.3"}))
             .await;
-    });
-
-    let err = client.get_cortex_info().await.unwrap_err();
-    responder.await.unwrap();
+        let license = connection
+            .recv_request_method(Methods::GET_LICENSE_INFO)
+            .await;
+        connection
+            .send_result(
+                rpc_id(&license),
+                json!({"license": "SECRET-LICENSE", "validTo": "2030-01-01"}),
+            )
+            .await;
+        let headsets = connection
+            .recv_request_method(Methods::QUERY_HEADSETS)
+            .await;
+        connection
+            .send_result(
+                rpc_id(&headsets),
+                json!([{"id": "INSIGHT-1", "status": "connected"}]),
+            )
+            .await;
+    });
+
+    let path =
+        std::env::temp_dir().join(format!("emotiv-support-bundle-{}.zip", std::process::id()));
+    let inputs = SupportBundleInputs {
+        cortex_token: Some("token".into()),
+        connection_events: vec![ConnectionEvent::Connected],
+        wire_frames: vec![
+            r#"{"id":1,"params":{"cortexToken":"t-old"}}"#.into(),
+            r#"{"id":2,"params":{"cortexToken":"t-new"}}"#.into(),
+        ],
+        max_wire_frames: 1,
+        ..SupportBundleInputs::default()
+    };
+    let bundle = collect_support_bundle(&client, &path, inputs)
+        .await
+        .unwrap();
+    responder.await.unwrap();
+
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(&path).unwrap()).unwrap();
+    let mut read_entry = |name: &str| {
+        let mut contents = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        contents
+    };
+
+    assert!(read_entry("cortex_info.json").contains("1.2.3"));
+    let license = read_entry("license_info.json");
+    assert!(!license.contains("SECRET-LICENSE"));
+    assert!(license.contains("2030-01-01"));
+    assert!(read_entry("headsets.json").contains("INSIGHT-1"));
+    assert!(read_entry("connection_events.json").contains("Connected"));
+    let frames = read_entry("wire_frames.jsonl");
+    assert_eq!(frames.lines().count(), 1);
+    assert!(frames.contains(r#""id":2"#));
+    assert!(!frames.contains("t-new"));
+    assert!(read_entry("manifest.json").contains(env!("CARGO_PKG_VERSION")));
+    assert_eq!(archive.len(), bundle.entries.len());
+
+    let _ = std::fs::remove_file(&path);
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn idle_reader_loop_does_not_wake_and_stops_promptly() {
+    let Some(mut server) =
+        start_server_or_skip("idle_reader_loop_does_not_wake_and_stops_promptly").await
+    else {
+        return;
+    };
+    let config = test_config(server.ws_url());
+    let mut client = CortexClient::connect(&config).await.unwrap();
+    let connection = server.accept_connection().await;
+    let _receivers = client.create_stream_channels(&[Streams::DEV]);
+
+    // An idle socket must not wake the loop (no polling timer).
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    assert_eq!(client.reader_wakeups(), 0);
+
+    for _ in 0..3 {
+        connection
+            .push_event(json!({"sid": "s", "time": 1.0, "dev": [4, 1.0, [4, 100], 90]}))
+            .await;
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert_eq!(client.reader_wakeups(), 3);
+
+    let started = std::time::Instant::now();
+    client.stop_reader().await;
+    assert!(started.elapsed() < std::time::Duration::from_millis(250));
+    assert!(!client.is_connected());
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn connection_prefixed_request_ids_are_used_on_the_wire() {
+    let Some(mut server) =
+        start_server_or_skip("connection_prefixed_request_ids_are_used_on_the_wire").await
+    else {
+        return;
+    };
+    let config = test_config(server.ws_url());
+    let generator = Arc::new(ConnectionPrefixedIds::per_connection());
+    let prefix = generator.prefix();
+    let mut client = CortexClient::connect(&config)
+        .await
+        .unwrap()
+        .with_request_id_generator(generator);
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            let request = connection
+                .recv_request_method(Methods::GET_CORTEX_INFO)
+                .await;
+            ids.push(rpc_id(&request));
+            connection
+                .send_result(rpc_id(&request), json!({"version": "2.0"}))
+                .await;
+        }
+        ids
+    });
+
+    client.get_cortex_info().await.unwrap();
+    client.get_cortex_info().await.unwrap();
+    let ids = responder.await.unwrap();
+
+    assert_eq!(ids[0] % ConnectionPrefixedIds::COUNTER_SPAN, 1);
+    assert_eq!(ids[1], ids[0] + 1);
+    assert_eq!(ids[0] / ConnectionPrefixedIds::COUNTER_SPAN, prefix);
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn custom_request_id_generator_is_used_on_the_wire() {
+    let Some(mut server) =
+        start_server_or_skip("custom_request_id_generator_is_used_on_the_wire").await
+    else {
+        return;
+    };
+    let config = test_config(server.ws_url());
+    let mut client = CortexClient::connect(&config)
+        .await
+        .unwrap()
+        .with_request_id_generator(Arc::new(CounterIds::starting_at(500)));
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        let request = connection
+            .recv_request_method(Methods::GET_CORTEX_INFO)
+            .await;
+        connection
+            .send_result(rpc_id(&request), json!({"version": "2.0"}))
+            .await;
+        rpc_id(&request)
+    });
+
+    client.get_cortex_info().await.unwrap();
+    assert_eq!(responder.await.unwrap(), 500);
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn strict_protocol_warn_records_unmodeled_fields_digest() {
+    let Some(mut server) =
+        start_server_or_skip("strict_protocol_warn_records_unmodeled_fields_digest").await
+    else {
+        return;
+    };
+    let mut config = test_config(server.ws_url());
+    config.strict_protocol = StrictProtocolMode::Warn;
+    let mut client = CortexClient::connect(&config).await.unwrap();
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        let request = connection
+            .recv_request_method(Methods::QUERY_HEADSETS)
+            .await;
+        connection
+            .send_result(
+                rpc_id(&request),
+                json!([{
+                    "id": "INSIGHT-1",
+                    "status": "connected",
+                    "brandNewField": 1
+                }]),
+            )
+            .await;
+    });
+
+    let headsets = client
+        .query_headsets(QueryHeadsetsOptions::default())
+        .await
+        .unwrap();
+    responder.await.unwrap();
+
+    assert_eq!(headsets.len(), 1);
+    let digest = client.unmodeled_fields();
+    let fields = digest.get(Methods::QUERY_HEADSETS).unwrap();
+    assert!(fields.contains("brandNewField"));
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn strict_protocol_error_fails_call_on_unmodeled_fields() {
+    let Some(mut server) =
+        start_server_or_skip("strict_protocol_error_fails_call_on_unmodeled_fields").await
+    else {
+        return;
+    };
+    let mut config = test_config(server.ws_url());
+    config.strict_protocol = StrictProtocolMode::Error;
+    let mut client = CortexClient::connect(&config).await.unwrap();
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        let request = connection
+            .recv_request_method(Methods::GET_CURRENT_PROFILE)
+            .await;
+        connection
+            .send_result(
+                rpc_id(&request),
+                json!({"name": null, "loadedByThisApp": false, "surprise": true}),
+            )
+            .await;
+    });
+
+    let err = client
+        .get_current_profile("token", "INSIGHT-1")
+        .await
+        .unwrap_err();
+    responder.await.unwrap();
+
+    match err {
+        CortexError::ProtocolError { reason } => assert!(reason.contains("surprise")),
+        other => panic!("expected ProtocolError, got {other:?}"),
+    }
+
+    client.disconnect().await.unwrap();
+}
+
+// ─── Error-path tests: protocol and API error mapping ───────────────────────
+
+#[tokio::test]
+async fn rpc_response_null_result_no_error_yields_protocol_error() {
+    let mut server =
+        match start_server_or_skip("rpc_response_null_result_no_error_yields_protocol_error").await
+        {
+            Some(server) => server,
+            None => return,
+        };
+    let config = test_config(server.ws_url());
+    let mut client = CortexClient::connect(&config).await.unwrap();
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        let request = connection
+            .recv_request_method(Methods::GET_CORTEX_INFO)
+            .await;
+        connection
+            .send_json(json!({"jsonrpc": "2.0", "id": rpc_id(&request)}))
+            .await;
+        request
+    });
+
+    let err = client.get_cortex_info().await.unwrap_err();
+    responder.await.unwrap();
+
+    assert!(matches!(err, CortexError::ProtocolError { .. }));
+    assert!(
+        err.to_string().contains("no result or error"),
+        "expected protocol error about missing result/error, got: {err}"
+    );
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn rpc_response_malformed_json_yields_protocol_error() {
+    let mut server =
+        match start_server_or_skip("rpc_response_malformed_json_yields_protocol_error").await {
+            Some(server) => server,
+            None => return,
+        };
+    let config = test_config(server.ws_url());
+    let mut client = CortexClient::connect(&config).await.unwrap();
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        let request = connection
+            .recv_request_method(Methods::GET_CORTEX_INFO)
+            .await;
+        let id = rpc_id(&request);
+        // Valid numeric id so the client routes to the pending request; "error" as string
+        // so CortexResponse deserialization fails (expects { code, message }).
+        connection
+            .send_json(json!({"jsonrpc": "2.0", "id": id, "error": "not an object"}))
+            .await;
+        request
+    });
+
+    let err = client.get_cortex_info().await.unwrap_err();
+    responder.await.unwrap();
+
+    assert!(matches!(err, CortexError::ProtocolError { .. }));
+    assert!(
+        err.to_string().contains("parse") || err.to_string().contains("Protocol"),
+        "expected protocol/parse error, got: {err}"
+    );
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn get_user_login_result_wrong_type_yields_protocol_error() {
+    let mut server = match start_server_or_skip(
+        "get_user_login_result_wrong_type_yields_protocol_error",
+    )
+    .await
+    {
+        Some(server) => server,
+        None => return,
+    };
+    let config = test_config(server.ws_url());
+    let mut client = CortexClient::connect(&config).await.unwrap();
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        let request = connection
+            .recv_request_method(Methods::GET_USER_LOGIN)
+            .await;
+        // Result must be an array of UserLoginInfo; a number is invalid.
+        connection.send_result(rpc_id(&request), json!(123)).await;
+        request
+    });
+
+    let err = client.get_user_login().await.unwrap_err();
+    responder.await.unwrap();
+
+    assert!(matches!(err, CortexError::ProtocolError { .. }));
+    assert!(
+        err.to_string().contains("parse")
+            || err.to_string().contains("Protocol")
+            || err.to_string().contains("user login"),
+        "expected protocol/parse error for wrong result type, got: {err}"
+    );
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn api_error_token_expired_maps_to_token_expired() {
+    let mut server =
+        match start_server_or_skip("api_error_token_expired_maps_to_token_expired").await {
+            Some(server) => server,
+            None => return,
+        };
+    let config = test_config(server.ws_url());
+    let mut client = CortexClient::connect(&config).await.unwrap();
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        let request = connection
+            .recv_request_method(Methods::GET_CORTEX_INFO)
+            .await;
+        connection
+            .send_error(rpc_id(&request), -32015, "cortex token expired")
+            .await;
+    });
+
+    let err = client.get_cortex_info().await.unwrap_err();
+    responder.await.unwrap();
+
+    assert!(matches!(err, CortexError::TokenExpired));
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn api_error_session_error_maps_and_preserves_message() {
+    let mut server =
+        match start_server_or_skip("api_error_session_error_maps_and_preserves_message").await {
+            Some(server) => server,
+            None => return,
+        };
+    let config = test_config(server.ws_url());
+    let mut client = CortexClient::connect(&config).await.unwrap();
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        let request = connection
+            .recv_request_method(Methods::GET_CORTEX_INFO)
+            .await;
+        connection
+            .send_error(
+                rpc_id(&request),
+                -32005,
+                "session already exists for this headset",
+            )
+            .await;
+    });
+
+    let err = client.get_cortex_info().await.unwrap_err();
+    responder.await.unwrap();
+
+    match &err {
+        CortexError::SessionError { reason } => assert!(reason.contains("session already exists")),
+        _ => panic!("expected SessionError with message, got {err:?}"),
+    }
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn api_error_stream_error_maps_and_preserves_message() {
+    let mut server =
+        match start_server_or_skip("api_error_stream_error_maps_and_preserves_message").await {
+            Some(server) => server,
+            None => return,
+        };
+    let config = test_config(server.ws_url());
+    let mut client = CortexClient::connect(&config).await.unwrap();
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        let request = connection
+            .recv_request_method(Methods::GET_CORTEX_INFO)
+            .await;
+        connection
+            .send_error(rpc_id(&request), -32016, "invalid stream name")
+            .await;
+    });
+
+    let err = client.get_cortex_info().await.unwrap_err();
+    responder.await.unwrap();
 
     match &err {
         CortexError::StreamError { reason } => assert!(reason.contains("invalid stream")),
@@ -667,12 +1497,15 @@ async fn api_error_license_error_maps_and_preserves_message() {
 }
 
 #[tokio::test]
-async fn api_error_headset_error_maps_and_preserves_message() {
-    let mut server =
-        match start_server_or_skip("api_error_headset_error_maps_and_preserves_message").await {
-            Some(server) => server,
-            None => return,
-        };
+async fn api_error_headset_not_ready_maps_and_preserves_message() {
+    let mut server = match start_server_or_skip(
+        "api_error_headset_not_ready_maps_and_preserves_message",
+    )
+    .await
+    {
+        Some(server) => server,
+        None => return,
+    };
     let config = test_config(server.ws_url());
     let mut client = CortexClient::connect(&config).await.unwrap();
 
@@ -690,8 +1523,8 @@ async fn api_error_headset_error_maps_and_preserves_message() {
     responder.await.unwrap();
 
     match &err {
-        CortexError::HeadsetError { reason } => assert!(reason.contains("headset not ready")),
-        _ => panic!("expected HeadsetError with message, got {err:?}"),
+        CortexError::HeadsetNotReady { reason } => assert!(reason.contains("headset not ready")),
+        _ => panic!("expected HeadsetNotReady with message, got {err:?}"),
     }
 
     client.disconnect().await.unwrap();
@@ -727,3 +1560,470 @@ async fn api_error_method_not_found_includes_method_name() {
 
     client.disconnect().await.unwrap();
 }
+
+#[tokio::test]
+async fn binary_frames_are_counted_and_routed_through_handler() {
+    let Some(mut server) =
+        start_server_or_skip("binary_frames_are_counted_and_routed_through_handler").await
+    else {
+        return;
+    };
+    let config = test_config(server.ws_url());
+    let client = CortexClient::connect(&config).await.unwrap();
+    let mut receivers = client.create_stream_channels(&[Streams::EEG]);
+    let mut eeg_rx = receivers.remove(Streams::EEG).unwrap();
+    let connection = server.accept_connection().await;
+
+    // Without a handler the frame is skipped and counted.
+    connection.send_binary(vec![1, 2, 3]).await;
+    tokio::time::timeout(std::time::Duration::from_secs(2), async {
+        while client.unhandled_binary_frames() == 0 {
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("binary frame was not counted");
+    assert_eq!(client.binary_frames_received(), 1);
+
+    // With a handler, decoded frames are dispatched like text frames.
+    let client = client.with_binary_frame_handler(Arc::new(|frame: &[u8]| {
+        serde_json::from_slice::<Value>(frame).ok()
+    }));
+    let event = json!({"sid": "session-1", "time": 1.0, "eeg": [1, 0, 4200.0, 0.0, []]});
+    connection
+        .send_binary(serde_json::to_vec(&event).unwrap())
+        .await;
+    let routed = tokio::time::timeout(std::time::Duration::from_secs(2), eeg_rx.recv())
+        .await
+        .expect("timed out waiting for decoded event")
+        .expect("eeg channel closed");
+
+    assert_eq!(routed, event);
+    assert_eq!(client.binary_frames_received(), 2);
+    assert_eq!(client.unhandled_binary_frames(), 1);
+}
+
+#[tokio::test]
+async fn close_sessions_leaves_other_applications_sessions_open() {
+    let Some(mut server) =
+        start_server_or_skip("close_sessions_leaves_other_applications_sessions_open").await
+    else {
+        return;
+    };
+    let mut config = test_config(server.ws_url());
+    config.app_id = Some("com.example.app".into());
+    let mut client = CortexClient::connect(&config).await.unwrap();
+
+    let session = |id: &str, app_id: &str| {
+        json!({
+            "id": id, "status": "activated", "owner": "user", "license": "",
+            "appId": app_id, "started": "", "streams": [], "recordIds": [],
+            "recording": false
+        })
+    };
+    let sessions = json!([
+        session("mine", "com.example.app"),
+        session("theirs", "com.other.app"),
+    ]);
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        let mut closed = Vec::new();
+        // `Owned` closes one session, the `All` override both.
+        for expected_closes in [1, 2] {
+            let request = connection
+                .recv_request_method(Methods::QUERY_SESSIONS)
+                .await;
+            connection
+                .send_result(rpc_id(&request), sessions.clone())
+                .await;
+            for _ in 0..expected_closes {
+                let request = connection
+                    .recv_request_method(Methods::UPDATE_SESSION)
+                    .await;
+                let id = request["params"]["session"].as_str().unwrap().to_string();
+                connection
+                    .send_result(rpc_id(&request), session(&id, "com.example.app"))
+                    .await;
+                closed.push(id);
+            }
+        }
+        closed
+    });
+
+    let closed = client
+        .close_sessions("token", CleanupScope::Owned, None)
+        .await
+        .unwrap();
+    assert_eq!(closed.len(), 1);
+    assert_eq!(closed[0].0, "mine");
+    assert!(closed[0].1.is_ok());
+
+    // An explicit override also closes the other application's session.
+    let closed = client
+        .close_sessions("token", CleanupScope::All, None)
+        .await
+        .unwrap();
+    assert_eq!(closed.len(), 2);
+
+    assert_eq!(responder.await.unwrap(), ["mine", "mine", "theirs"]);
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn captured_session_replays_responses_and_stream_events() {
+    let Some(mut server) =
+        start_server_or_skip("captured_session_replays_responses_and_stream_events").await
+    else {
+        return;
+    };
+    let path = std::env::temp_dir().join(format!("replay-{}.ndjson", std::process::id()));
+    let capture = FrameCapture::create(&path).unwrap();
+    let config = test_config(server.ws_url());
+    let mut client = CortexClient::connect(&config)
+        .await
+        .unwrap()
+        .with_capture(Arc::clone(&capture));
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        let request = connection
+            .recv_request_method(Methods::GET_CORTEX_INFO)
+            .await;
+        connection
+            .send_result(rpc_id(&request), json!({"version": "mock-1.0.0"}))
+            .await;
+        let request = connection.recv_request_method(Methods::SUBSCRIBE).await;
+        connection
+            .send_result(rpc_id(&request), json!({"success": [Streams::EEG]}))
+            .await;
+        for counter in [29, 30] {
+            connection
+                .push_event(json!({
+                    "sid": "session-1",
+                    "time": 1_609_459_200.0,
+                    "eeg": [counter, 0, 4262.5, 4264.6, 4265.1, 4267.2, 4263.6, 0.0, 0, []]
+                }))
+                .await;
+        }
+    });
+
+    client.get_cortex_info().await.unwrap();
+    let mut eeg = streams::subscribe_eeg(&client, "token", "session-1", 5)
+        .await
+        .unwrap();
+    for _ in 0..2 {
+        tokio::time::timeout(std::time::Duration::from_secs(2), eeg.next())
+            .await
+            .expect("timed out waiting for eeg sample");
+    }
+    responder.await.unwrap();
+    client.disconnect().await.unwrap();
+    capture.flush().unwrap();
+    assert_eq!(capture.frames(), 4);
+
+    // Replay the capture without the mock server.
+    let mut replay = ReplayClient::open(&path, ReplayOptions { speed: 0.0 })
+        .await
+        .unwrap();
+    let info = replay.get_cortex_info().await.unwrap();
+    assert_eq!(info["version"], "mock-1.0.0");
+    let mut eeg = streams::subscribe_eeg(&replay, "token", "session-1", 5)
+        .await
+        .unwrap();
+    let mut counters = Vec::new();
+    for _ in 0..2 {
+        let sample = tokio::time::timeout(std::time::Duration::from_secs(2), eeg.next())
+            .await
+            .expect("timed out waiting for replayed sample")
+            .expect("replayed stream ended");
+        counters.push(sample.counter);
+    }
+    assert_eq!(counters, [29, 30]);
+    let missing = replay.query_headsets(QueryHeadsetsOptions::default()).await;
+    assert!(
+        matches!(missing, Err(CortexError::MethodNotFound { .. })),
+        "{missing:?}"
+    );
+
+    replay.disconnect().await.unwrap();
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn trainer_retries_failed_recording_and_saves_profile() {
+    use emotiv_cortex_v2::training::{
+        ProfileSaveStatus, ProfileTarget, Trainer, TrainerConfig, TrainingDecision, TrainingResult,
+    };
+
+    let Some(mut server) =
+        start_server_or_skip("trainer_retries_failed_recording_and_saves_profile").await
+    else {
+        return;
+    };
+    let config = test_config(server.ws_url());
+    let mut client = CortexClient::connect(&config).await.unwrap();
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        let sys = |event: &str| json!({"sid": "session-1", "time": 1_609_459_200.0, "sys": ["mentalCommand", event]});
+        let request = connection.recv_request_method(Methods::SUBSCRIBE).await;
+        connection
+            .send_result(rpc_id(&request), json!({"success": [Streams::SYS]}))
+            .await;
+
+        let request = connection
+            .recv_request_method(Methods::MENTAL_COMMAND_ACTIVE_ACTION)
+            .await;
+        assert_eq!(request["params"]["status"], "get");
+        connection
+            .send_result(rpc_id(&request), json!(["neutral", "pull"]))
+            .await;
+        let request = connection
+            .recv_request_method(Methods::MENTAL_COMMAND_ACTIVE_ACTION)
+            .await;
+        assert_eq!(
+            request["params"]["actions"],
+            json!(["neutral", "pull", "push"])
+        );
+        connection.send_result(rpc_id(&request), json!({})).await;
+
+        for (status, events) in [
+            ("start", &["MC_Started", "MC_Failed"][..]),
+            ("start", &["MC_Started", "MC_Succeeded"][..]),
+            ("accept", &["MC_Completed"][..]),
+        ] {
+            let request = connection.recv_request_method(Methods::TRAINING).await;
+            assert_eq!(request["params"]["status"], status);
+            assert_eq!(request["params"]["action"], "push");
+            connection.send_result(rpc_id(&request), json!({})).await;
+            // Events of other detections and sessions are ignored.
+            connection
+                .push_event(json!({"sid": "session-1", "time": 0.0, "sys": ["facialExpression", "FE_Failed"]}))
+                .await;
+            connection
+                .push_event(
+                    json!({"sid": "session-2", "time": 0.0, "sys": ["mentalCommand", "MC_Failed"]}),
+                )
+                .await;
+            for event in events {
+                connection.push_event(sys(event)).await;
+            }
+        }
+
+        let request = connection.recv_request_method(Methods::SETUP_PROFILE).await;
+        assert_eq!(request["params"]["status"], "save");
+        assert_eq!(request["params"]["profile"], "my_profile");
+        connection
+            .send_result(
+                rpc_id(&request),
+                json!({"action": "save", "name": "my_profile"}),
+            )
+            .await;
+    });
+
+    let config = TrainerConfig {
+        event_timeout: std::time::Duration::from_secs(2),
+        save_profile: Some(ProfileTarget::new("INSIGHT-1", "my_profile")),
+        ..TrainerConfig::default()
+    };
+    let mut trainer = Trainer::subscribe(&client, "token", "session-1", config)
+        .await
+        .unwrap();
+    let mut prompts = Vec::new();
+    let outcome = trainer
+        .train_action("push", async |attempt| {
+            prompts.push(attempt.attempt);
+            TrainingDecision::Accept
+        })
+        .await
+        .unwrap();
+
+    responder.await.unwrap();
+    assert_eq!(prompts, [2]);
+    assert_eq!(outcome.result, TrainingResult::Accepted);
+    assert_eq!((outcome.attempts, outcome.failures), (2, 1));
+    assert_eq!(outcome.profile, ProfileSaveStatus::Saved);
+
+    drop(trainer);
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn facial_trainer_reports_training_progress() {
+    use emotiv_cortex_v2::training::{
+        FacialTrainer, TrainerConfig, TrainingDecision, TrainingProgress, TrainingResult,
+    };
+
+    let Some(mut server) = start_server_or_skip("facial_trainer_reports_training_progress").await
+    else {
+        return;
+    };
+    let config = test_config(server.ws_url());
+    let mut client = CortexClient::connect(&config).await.unwrap();
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        let request = connection.recv_request_method(Methods::SUBSCRIBE).await;
+        connection
+            .send_result(rpc_id(&request), json!({"success": [Streams::SYS]}))
+            .await;
+
+        for (status, events) in [
+            ("start", &["FE_Started", "FE_Failed"][..]),
+            ("start", &["FE_Started", "FE_Succeeded"][..]),
+            ("reject", &["FE_Rejected"][..]),
+        ] {
+            let request = connection.recv_request_method(Methods::TRAINING).await;
+            assert_eq!(request["params"]["detection"], "facialExpression");
+            assert_eq!(request["params"]["status"], status);
+            connection.send_result(rpc_id(&request), json!({})).await;
+            for event in events {
+                connection
+                    .push_event(json!({"sid": "session-1", "time": 0.0, "sys": ["facialExpression", event]}))
+                    .await;
+            }
+        }
+    });
+
+    let config = TrainerConfig {
+        event_timeout: std::time::Duration::from_secs(2),
+        ..TrainerConfig::default()
+    };
+    let mut trainer = FacialTrainer::subscribe(&client, "token", "session-1", config)
+        .await
+        .unwrap();
+    let mut progress = trainer.progress();
+    let outcome = trainer
+        .train_action("smile", async |_| TrainingDecision::Reject)
+        .await
+        .unwrap();
+    responder.await.unwrap();
+
+    assert_eq!(outcome.result, TrainingResult::Rejected);
+    assert_eq!((outcome.attempts, outcome.failures), (2, 1));
+    let mut events = Vec::new();
+    while let Ok(event) = progress.try_recv() {
+        events.push(event);
+    }
+    let smile = || "smile".to_string();
+    assert_eq!(
+        events,
+        [
+            TrainingProgress::Recording {
+                action: smile(),
+                attempt: 1
+            },
+            TrainingProgress::Failed {
+                action: smile(),
+                attempt: 1
+            },
+            TrainingProgress::Recording {
+                action: smile(),
+                attempt: 2
+            },
+            TrainingProgress::Succeeded {
+                action: smile(),
+                attempt: 2
+            },
+            TrainingProgress::Decided {
+                action: smile(),
+                attempt: 2,
+                decision: TrainingDecision::Reject
+            },
+            TrainingProgress::Finished(outcome),
+        ]
+    );
+
+    drop(trainer);
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn facial_trainer_switches_signature_and_tunes_thresholds() {
+    use emotiv_cortex_v2::training::{
+        FacialSignatureType, FacialTrainer, TrainerConfig, TrainingProgress,
+    };
+
+    let Some(mut server) =
+        start_server_or_skip("facial_trainer_switches_signature_and_tunes_thresholds").await
+    else {
+        return;
+    };
+    let config = test_config(server.ws_url());
+    let mut client = CortexClient::connect(&config).await.unwrap();
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        let request = connection.recv_request_method(Methods::SUBSCRIBE).await;
+        connection
+            .send_result(rpc_id(&request), json!({"success": [Streams::SYS]}))
+            .await;
+
+        let request = connection
+            .recv_request_method(Methods::FACIAL_EXPRESSION_SIGNATURE_TYPE)
+            .await;
+        assert_eq!(request["params"]["status"], "get");
+        connection
+            .send_result(
+                rpc_id(&request),
+                json!({"currentSig": "universal", "availableSig": ["universal", "trained"]}),
+            )
+            .await;
+        let request = connection
+            .recv_request_method(Methods::FACIAL_EXPRESSION_SIGNATURE_TYPE)
+            .await;
+        assert_eq!(request["params"]["signature"], "trained");
+        assert_eq!(request["params"]["session"], "session-1");
+        connection.send_result(rpc_id(&request), json!({})).await;
+
+        let request = connection
+            .recv_request_method(Methods::FACIAL_EXPRESSION_THRESHOLD)
+            .await;
+        assert_eq!(request["params"]["action"], "smile");
+        assert_eq!(request["params"]["value"], 600);
+        connection.send_result(rpc_id(&request), json!({})).await;
+        let request = connection
+            .recv_request_method(Methods::FACIAL_EXPRESSION_THRESHOLD)
+            .await;
+        assert_eq!(request["params"]["status"], "get");
+        connection
+            .send_result(rpc_id(&request), json!({"currentThreshold": 600}))
+            .await;
+    });
+
+    let trainer = FacialTrainer::subscribe(&client, "token", "session-1", TrainerConfig::default())
+        .await
+        .unwrap();
+    let mut progress = trainer.progress();
+
+    let signature = trainer.signature().await.unwrap();
+    assert_eq!(signature.current, FacialSignatureType::Universal);
+    assert!(signature.available.contains(&FacialSignatureType::Trained));
+    trainer
+        .set_signature(FacialSignatureType::Trained)
+        .await
+        .unwrap();
+    assert!(matches!(
+        trainer.set_threshold("smile", 1001).await,
+        Err(CortexError::ConfigError { .. })
+    ));
+    trainer.set_threshold("smile", 600).await.unwrap();
+    assert_eq!(trainer.threshold("smile").await.unwrap(), 600);
+    responder.await.unwrap();
+
+    assert_eq!(
+        progress.try_recv().unwrap(),
+        TrainingProgress::SignatureChanged(FacialSignatureType::Trained)
+    );
+    assert_eq!(
+        progress.try_recv().unwrap(),
+        TrainingProgress::ThresholdChanged {
+            action: "smile".to_string(),
+            threshold: 600
+        }
+    );
+
+    drop(trainer);
+    client.disconnect().await.unwrap();
+}
@@ -1,2 +1,4 @@
+#![allow(clippy::manual_let_else, clippy::too_many_lines)]
+
 mod endpoint_contracts_suite;
 mod support;
@@ -35,7 +35,7 @@ pub(super) async fn execute_cortex_step(client: &CortexClient, kind: &StepKind)
             assert_eq!(records[0].uuid, "record-q1");
         }
         StepKind::ExportRecord => {
-            client
+            let exported = client
                 .export_record(
                     TOKEN_CORTEX,
                     &record_ids(),
@@ -44,6 +44,7 @@ pub(super) async fn execute_cortex_step(client: &CortexClient, kind: &StepKind)
                 )
                 .await
                 .unwrap();
+            assert!(exported.is_complete());
         }
         StepKind::UpdateRecord => {
             let request = UpdateRecordRequest {
@@ -321,10 +322,11 @@ pub(super) async fn execute_resilient_step(client: &ResilientClient, kind: &Step
             assert_eq!(records[0].uuid, "record-q1");
         }
         StepKind::ExportRecord => {
-            client
+            let exported = client
                 .export_record(&record_ids(), "/tmp/export", ExportFormat::Csv)
                 .await
                 .unwrap();
+            assert!(exported.is_complete());
         }
         StepKind::UpdateRecord => {
             let request = UpdateRecordRequest {
@@ -157,7 +157,8 @@ pub(super) fn build_contract_steps(token: &str) -> Vec<ContractStep> {
             }),
             absent_params: vec![],
             response: json!({
-                "success": true
+                "success": [{"recordId": "record-1"}, {"recordId": "record-2"}],
+                "failure": []
             }),
         },
         ContractStep {
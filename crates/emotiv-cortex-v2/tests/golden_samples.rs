@@ -0,0 +1,138 @@
+//! Table-driven checks that every typed stream parser accepts the golden
+//! stream messages under `testdata/<model>/<stream>.jsonl`.
+
+use std::path::{Path, PathBuf};
+
+use emotiv_cortex_v2::HeadsetModel;
+use emotiv_cortex_v2::protocol::constants::Streams;
+use emotiv_cortex_v2::streams::{ParsedSample, StreamSample, parse_sample};
+
+const MODELS: &[(&str, HeadsetModel)] = &[
+    ("insight", HeadsetModel::Insight),
+    ("epoc_plus", HeadsetModel::EpocPlus),
+    ("epoc_x", HeadsetModel::EpocX),
+];
+
+const STREAMS: &[&str] = &[
+    Streams::EEG,
+    Streams::DEV,
+    Streams::MOT,
+    Streams::EQ,
+    Streams::POW,
+    Streams::MET,
+    Streams::COM,
+    Streams::FAC,
+    Streams::SYS,
+];
+
+fn testdata_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata")
+}
+
+fn golden_lines(model: &str, stream: &str) -> Vec<String> {
+    let path = testdata_dir().join(model).join(format!("{stream}.jsonl"));
+    let contents = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("missing golden file {}: {e}", path.display()));
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Channel count carried by a parsed sample, for streams that have one.
+fn channel_count(sample: &ParsedSample) -> Option<usize> {
+    match sample {
+        ParsedSample::Eeg(eeg) => Some(eeg.channels.len()),
+        ParsedSample::Dev(dev) => Some(dev.channel_quality.len()),
+        ParsedSample::Eq(eq) => Some(eq.sensor_quality.len()),
+        ParsedSample::Pow(pow) => Some(pow.channel_powers.len()),
+        _ => None,
+    }
+}
+
+#[test]
+fn every_golden_sample_parses() {
+    for (model_dir, model) in MODELS {
+        for stream in STREAMS {
+            let lines = golden_lines(model_dir, stream);
+            assert!(!lines.is_empty(), "{model_dir}/{stream} has no samples");
+
+            for (n, line) in lines.iter().enumerate() {
+                let sample = parse_sample(stream, line)
+                    .unwrap_or_else(|e| panic!("{model_dir}/{stream}.jsonl line {}: {e}", n + 1));
+                if let Some(count) = channel_count(&sample) {
+                    assert_eq!(
+                        count,
+                        model.num_channels(),
+                        "{model_dir}/{stream}.jsonl line {} channel count",
+                        n + 1
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn golden_samples_are_routed_to_the_matching_variant() {
+    for stream in STREAMS {
+        let line = &golden_lines("insight", stream)[0];
+        let sample = parse_sample(stream, line).unwrap();
+        let matches = match sample {
+            ParsedSample::Eeg(_) => *stream == Streams::EEG,
+            ParsedSample::Dev(_) => *stream == Streams::DEV,
+            ParsedSample::Mot(_) => *stream == Streams::MOT,
+            ParsedSample::Eq(_) => *stream == Streams::EQ,
+            ParsedSample::Pow(_) => *stream == Streams::POW,
+            ParsedSample::Met(_) => *stream == Streams::MET,
+            ParsedSample::Com(_) => *stream == Streams::COM,
+            ParsedSample::Fac(_) => *stream == Streams::FAC,
+            ParsedSample::Sys(_) => *stream == Streams::SYS,
+        };
+        assert!(matches, "{stream} parsed into the wrong variant");
+    }
+}
+
+#[test]
+fn golden_samples_report_their_kind_through_stream_sample() {
+    for stream in STREAMS {
+        let line = &golden_lines("insight", stream)[0];
+        let sample = parse_sample(stream, line).unwrap();
+        assert_eq!(sample.kind(), *stream);
+        if matches!(sample, ParsedSample::Eeg(_) | ParsedSample::Sys(_)) {
+            assert!(sample.timestamp().is_some(), "{stream} lost its timestamp");
+        }
+    }
+}
+
+#[test]
+fn met_golden_samples_map_columns_by_layout() {
+    let lines = golden_lines("insight", Streams::MET);
+
+    let ParsedSample::Met(standard) = parse_sample(Streams::MET, &lines[0]).unwrap() else {
+        panic!("expected met sample");
+    };
+    assert!(standard.attention.is_none());
+    assert!(standard.engagement.is_some());
+
+    let ParsedSample::Met(inactive) = parse_sample(Streams::MET, &lines[1]).unwrap() else {
+        panic!("expected met sample");
+    };
+    assert!(inactive.engagement.is_none());
+    assert!(inactive.relaxation.is_some());
+
+    let ParsedSample::Met(with_attention) = parse_sample(Streams::MET, &lines[2]).unwrap() else {
+        panic!("expected met sample");
+    };
+    assert!(with_attention.attention.is_some());
+    assert!(with_attention.focus.is_some());
+}
+
+#[test]
+fn mismatched_samples_are_rejected() {
+    let eeg = &golden_lines("insight", Streams::EEG)[0];
+    assert!(parse_sample(Streams::MOT, eeg).is_err());
+    assert!(parse_sample("bogus", eeg).is_err());
+    assert!(parse_sample(Streams::EEG, "not json").is_err());
+}
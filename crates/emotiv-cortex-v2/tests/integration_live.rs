@@ -0,0 +1,195 @@
+//! Full hardware bring-up against a real Cortex service and headset.
+//!
+//! Only built with `--features integration-live`:
+//!
+//! ```bash
+//! EMOTIV_CLIENT_ID=... EMOTIV_CLIENT_SECRET=... \
+//!     cargo test -p emotiv-cortex-v2 --features integration-live -- --nocapture
+//! ```
+//!
+//! Optional: `EMOTIV_HEADSET_ID` selects the headset, `EMOTIV_CORTEX_URL`
+//! overrides the endpoint, `EMOTIV_EXPORT_DIR` sets the export folder
+//! (defaults to the system temp dir). Missing credentials or hardware skip
+//! the test with the reason printed; failures past that point fail it.
+
+use std::time::{Duration, Instant};
+
+use emotiv_cortex_v2::protocol::constants::Streams;
+use emotiv_cortex_v2::protocol::headset::{HeadsetInfo, QueryHeadsetsOptions};
+use emotiv_cortex_v2::protocol::records::ExportFormat;
+use emotiv_cortex_v2::{CortexClient, CortexConfig, HeadsetModel, streams};
+use futures_util::StreamExt;
+
+const DEV_SUBSCRIBE_DURATION: Duration = Duration::from_secs(5);
+const HEADSET_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+fn live_config() -> Result<CortexConfig, String> {
+    if std::env::var("EMOTIV_SKIP_LIVE_TESTS")
+        .ok()
+        .is_some_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+    {
+        return Err("EMOTIV_SKIP_LIVE_TESTS is enabled".to_string());
+    }
+
+    let client_id =
+        std::env::var("EMOTIV_CLIENT_ID").map_err(|_| "EMOTIV_CLIENT_ID is not set".to_string())?;
+    let client_secret = std::env::var("EMOTIV_CLIENT_SECRET")
+        .map_err(|_| "EMOTIV_CLIENT_SECRET is not set".to_string())?;
+
+    let mut config = CortexConfig::new(client_id, client_secret);
+    if let Ok(cortex_url) = std::env::var("EMOTIV_CORTEX_URL") {
+        config.cortex_url = cortex_url;
+    }
+
+    // Real hardware is slow: Bluetooth pairing, license checks, and export
+    // processing routinely exceed the defaults.
+    config.timeouts.rpc_timeout_secs = 60;
+    config.timeouts.subscribe_timeout_secs = 60;
+    config.timeouts.headset_connect_timeout_secs = 120;
+
+    Ok(config)
+}
+
+/// Pick the requested (or first) headset and make sure it is connected.
+async fn connected_headset(
+    client: &CortexClient,
+    config: &CortexConfig,
+) -> Result<HeadsetInfo, String> {
+    let preferred = std::env::var("EMOTIV_HEADSET_ID").ok();
+    let deadline =
+        Instant::now() + Duration::from_secs(config.timeouts.headset_connect_timeout_secs);
+    let mut connect_requested = false;
+
+    loop {
+        let headsets = client
+            .query_headsets(QueryHeadsetsOptions::default())
+            .await
+            .map_err(|e| format!("queryHeadsets failed: {e}"))?;
+
+        let selected = match preferred.as_deref() {
+            Some(id) => headsets
+                .into_iter()
+                .find(|h| h.id == id)
+                .ok_or_else(|| format!("requested EMOTIV_HEADSET_ID '{id}' was not discovered"))?,
+            None => headsets
+                .into_iter()
+                .next()
+                .ok_or_else(|| "no headsets were discovered".to_string())?,
+        };
+
+        if selected.status == "connected" {
+            return Ok(selected);
+        }
+        if Instant::now() >= deadline {
+            return Err(format!(
+                "headset '{}' did not connect within {}s (status='{}')",
+                selected.id, config.timeouts.headset_connect_timeout_secs, selected.status
+            ));
+        }
+        if !connect_requested && selected.status == "discovered" {
+            client
+                .connect_headset(&selected.id)
+                .await
+                .map_err(|e| format!("connecting headset '{}' failed: {e}", selected.id))?;
+            connect_requested = true;
+        }
+        tokio::time::sleep(HEADSET_POLL_INTERVAL).await;
+    }
+}
+
+#[tokio::test]
+async fn live_full_bring_up() {
+    let config = match live_config() {
+        Ok(config) => config,
+        Err(reason) => {
+            eprintln!("Skipping live bring-up: {reason}");
+            return;
+        }
+    };
+
+    let mut client = match CortexClient::connect(&config).await {
+        Ok(client) => client,
+        Err(err) => {
+            eprintln!(
+                "Skipping live bring-up: cannot reach Cortex at {}: {err}",
+                config.cortex_url
+            );
+            return;
+        }
+    };
+
+    let token = client
+        .authenticate(&config.client_id, &config.client_secret)
+        .await
+        .expect("authentication failed (is the app approved in the EMOTIV Launcher?)");
+
+    let headset = match connected_headset(&client, &config).await {
+        Ok(headset) => headset,
+        Err(reason) => {
+            eprintln!("Skipping live bring-up: {reason}");
+            client.disconnect().await.unwrap();
+            return;
+        }
+    };
+    eprintln!("Using headset {} ({})", headset.id, headset.status);
+
+    let session = client
+        .create_session(&token, &headset.id)
+        .await
+        .expect("createSession failed");
+
+    // Subscribe to device quality for a few seconds.
+    let num_channels = HeadsetModel::from_headset_info(&headset).num_channels();
+    let mut dev = streams::subscribe_dev(&client, &token, &session.id, num_channels)
+        .await
+        .expect("subscribe dev failed");
+    let mut dev_samples = 0_usize;
+    let collect_until = tokio::time::Instant::now() + DEV_SUBSCRIBE_DURATION;
+    while let Ok(Some(_)) = tokio::time::timeout_at(collect_until, dev.next()).await {
+        dev_samples += 1;
+    }
+    drop(dev);
+    client
+        .unsubscribe_streams(&token, &session.id, &[Streams::DEV])
+        .await
+        .expect("unsubscribe dev failed");
+    assert!(
+        dev_samples > 0,
+        "no dev samples received in {DEV_SUBSCRIBE_DURATION:?}"
+    );
+    eprintln!("Received {dev_samples} dev samples");
+
+    // Record with two markers.
+    let record = client
+        .create_record(&token, &session.id, "emotiv-cortex-v2 live bring-up")
+        .await
+        .expect("createRecord failed (recording needs a license that allows it)");
+    for (label, value) in [("bring-up-start", 1), ("bring-up-end", 2)] {
+        client
+            .inject_marker(&token, &session.id, label, value, "integration-live", None)
+            .await
+            .unwrap_or_else(|e| panic!("injectMarker '{label}' failed: {e}"));
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    client
+        .stop_record(&token, &session.id)
+        .await
+        .expect("stopRecord failed");
+
+    // Export.
+    let folder = std::env::var("EMOTIV_EXPORT_DIR")
+        .map_or_else(|_| std::env::temp_dir(), std::path::PathBuf::from);
+    client
+        .export_record(
+            &token,
+            std::slice::from_ref(&record.uuid),
+            &folder.to_string_lossy(),
+            ExportFormat::Csv,
+        )
+        .await
+        .expect("exportRecord failed");
+    eprintln!("Exported record {} to {}", record.uuid, folder.display());
+
+    client.close_session(&token, &session.id).await.unwrap();
+    client.disconnect().await.unwrap();
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::manual_let_else, clippy::unreadable_literal)]
+
 mod support;
 
 use std::time::Duration;
@@ -206,3 +208,2510 @@ async fn generate_new_token_updates_resilient_state() {
     client.disconnect().await.unwrap();
     server_task.await.unwrap();
 }
+
+#[tokio::test]
+async fn cached_token_is_renewed_before_authorizing_with_debit() {
+    use emotiv_cortex_v2::token_cache::TokenCache;
+
+    let Some(mut server) =
+        start_server_or_skip("cached_token_is_renewed_before_authorizing_with_debit").await
+    else {
+        return;
+    };
+    let path = std::env::temp_dir().join(format!("emotiv-token-{}.json", std::process::id()));
+    let cache = TokenCache::new(&path);
+    cache
+        .store("test-client-id", "test-client-secret", "token-cached")
+        .unwrap();
+
+    let mut config = resilient_test_config(server.ws_url());
+    config.license = Some("LICENSE-1".into());
+    config.debit = Some(5);
+    config.token_cache.enabled = true;
+    config.token_cache.path = Some(path.clone());
+
+    let server_task = tokio::spawn(async move {
+        // First start: the cached token is renewed; nothing is debited.
+        let mut connection = server.accept_connection().await;
+        let renew = connection.recv_request().await;
+        assert_eq!(renew["method"], Methods::GENERATE_NEW_TOKEN);
+        assert_eq!(renew["params"]["cortexToken"], "token-cached");
+        connection
+            .send_result(rpc_id(&renew), json!({"cortexToken": "token-renewed"}))
+            .await;
+
+        // Second start: Cortex refuses the renewal, so the client
+        // authorizes with the license and debit.
+        let mut connection = server.accept_connection().await;
+        let renew = connection
+            .recv_request_method(Methods::GENERATE_NEW_TOKEN)
+            .await;
+        assert_eq!(renew["params"]["cortexToken"], "token-renewed");
+        connection
+            .send_error(rpc_id(&renew), -32014, "Invalid token")
+            .await;
+        let info = connection
+            .recv_request_method(Methods::GET_CORTEX_INFO)
+            .await;
+        connection
+            .send_result(rpc_id(&info), json!({"version": "mock"}))
+            .await;
+        let access = connection
+            .recv_request_method(Methods::REQUEST_ACCESS)
+            .await;
+        connection
+            .send_result(rpc_id(&access), json!({"accessGranted": true}))
+            .await;
+        let authorize = connection.recv_request_method(Methods::AUTHORIZE).await;
+        assert_eq!(authorize["params"]["license"], "LICENSE-1");
+        assert_eq!(authorize["params"]["debit"], 5);
+        connection
+            .send_result(
+                rpc_id(&authorize),
+                json!({"cortexToken": "token-authorized"}),
+            )
+            .await;
+    });
+
+    let client = ResilientClient::connect(config.clone()).await.unwrap();
+    assert_eq!(client.cortex_token().await, "token-renewed");
+    client.disconnect().await.unwrap();
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    assert_eq!(client.cortex_token().await, "token-authorized");
+    assert_eq!(
+        cache
+            .load("test-client-id", "test-client-secret")
+            .as_deref(),
+        Some("token-authorized")
+    );
+    client.disconnect().await.unwrap();
+
+    server_task.await.unwrap();
+    cache.clear().unwrap();
+}
+
+#[tokio::test]
+async fn with_raw_client_lends_current_client_and_token() {
+    let Some(mut server) =
+        start_server_or_skip("with_raw_client_lends_current_client_and_token").await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-raw").await;
+
+        let request = connection
+            .recv_request_method(Methods::QUERY_SESSIONS)
+            .await;
+        let token = request["params"]["cortexToken"].clone();
+        connection.send_result(rpc_id(&request), json!([])).await;
+        token
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let (lent_token, sessions) = tokio::spawn(async move {
+        let result = client
+            .with_raw_client(async |raw, token| {
+                (token.to_string(), raw.query_sessions(token).await)
+            })
+            .await
+            .unwrap();
+        client.disconnect().await.unwrap();
+        result
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(lent_token, "token-raw");
+    assert!(sessions.unwrap().is_empty());
+    assert_eq!(server_task.await.unwrap(), json!("token-raw"));
+}
+
+#[tokio::test]
+async fn session_not_activated_is_reactivated_and_call_retried() {
+    let Some(mut server) =
+        start_server_or_skip("session_not_activated_is_reactivated_and_call_retried").await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-session").await;
+
+        let first = connection.recv_request_method(Methods::SUBSCRIBE).await;
+        connection
+            .send_error(rpc_id(&first), -32012, "session must be activated")
+            .await;
+
+        let activate = connection
+            .recv_request_method(Methods::UPDATE_SESSION)
+            .await;
+        connection
+            .send_result(rpc_id(&activate), json!({"id": "session-1"}))
+            .await;
+
+        let retry = connection.recv_request_method(Methods::SUBSCRIBE).await;
+        connection
+            .send_result(rpc_id(&retry), json!({"success": ["eeg"]}))
+            .await;
+        activate
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let mut events = client.event_receiver();
+    let result = client
+        .subscribe_streams("session-1", &["eeg"])
+        .await
+        .unwrap();
+    assert!(result.is_complete());
+
+    let activate = server_task.await.unwrap();
+    assert_eq!(activate["params"]["session"], "session-1");
+    assert_eq!(activate["params"]["status"], "active");
+
+    let mut saw_reactivated = false;
+    while let Ok(event) = events.try_recv() {
+        if event
+            == (ConnectionEvent::SessionReactivated {
+                session_id: "session-1".into(),
+            })
+        {
+            saw_reactivated = true;
+        }
+    }
+    assert!(saw_reactivated, "missing SessionReactivated event");
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn retry_hooks_retry_configured_methods_and_report_retries() {
+    use emotiv_cortex_v2::retry::{RetryHooks, RetryPolicy};
+    use std::sync::{Arc, Mutex};
+
+    let Some(mut server) =
+        start_server_or_skip("retry_hooks_retry_configured_methods_and_report_retries").await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-retry").await;
+
+        let first = connection
+            .recv_request_method(Methods::QUERY_HEADSETS)
+            .await;
+        connection
+            .send_error(rpc_id(&first), -32004, "headset unavailable")
+            .await;
+        let retry = connection
+            .recv_request_method(Methods::QUERY_HEADSETS)
+            .await;
+        connection.send_result(rpc_id(&retry), json!([])).await;
+
+        // Methods without a budget fail on the first error.
+        let record = connection.recv_request_method(Methods::CREATE_RECORD).await;
+        connection
+            .send_error(rpc_id(&record), -32004, "headset unavailable")
+            .await;
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let retried = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&retried);
+    client.set_retry_hooks(
+        RetryHooks::new()
+            .method(
+                Methods::QUERY_HEADSETS,
+                RetryPolicy::custom(2, Duration::ZERO, Duration::ZERO),
+            )
+            .retryable_codes([-32004])
+            .on_retry(move |method, retry| {
+                log.lock()
+                    .unwrap()
+                    .push((method.to_string(), retry.attempt));
+            }),
+    );
+
+    let headsets = client
+        .query_headsets(QueryHeadsetsOptions::default())
+        .await
+        .unwrap();
+    assert!(headsets.is_empty());
+    assert!(client.create_record("session-1", "no retry").await.is_err());
+
+    server_task.await.unwrap();
+    assert_eq!(
+        *retried.lock().unwrap(),
+        vec![(Methods::QUERY_HEADSETS.to_string(), 1)]
+    );
+    client.disconnect().await.unwrap();
+}
+
+#[cfg(unix)]
+async fn wait_for_consumers(
+    supervisor: &emotiv_cortex_v2::supervisor::StreamSupervisor,
+    count: usize,
+) {
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while supervisor.stats().consumers != count {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("consumer count not reached");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn supervisor_relays_streams_to_reattached_consumers() {
+    use emotiv_cortex_v2::streams::ParsedSample;
+    use emotiv_cortex_v2::supervisor::{StreamConsumer, StreamSupervisor};
+
+    let Some(mut server) =
+        start_server_or_skip("supervisor_relays_streams_to_reattached_consumers").await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+    let (push_tx, mut push_rx) = tokio::sync::mpsc::channel::<Value>(4);
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-supervisor").await;
+        let subscribe = connection.recv_request_method(Methods::SUBSCRIBE).await;
+        connection
+            .send_result(
+                rpc_id(&subscribe),
+                json!({"success": [{"streamName": "com", "cols": ["act", "pow"], "sid": "session-1"}], "failure": []}),
+            )
+            .await;
+        while let Some(event) = push_rx.recv().await {
+            connection.push_event(event).await;
+        }
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let path = std::env::temp_dir().join(format!("emotiv-supervisor-{}.sock", std::process::id()));
+    let supervisor = StreamSupervisor::start(&client, "session-1", &["com"], &path)
+        .await
+        .unwrap();
+
+    for (action, time) in [("push", 1.0), ("pull", 2.0)] {
+        let mut consumer = StreamConsumer::connect(&path).await.unwrap();
+        wait_for_consumers(&supervisor, 1).await;
+        push_tx
+            .send(json!({"com": [action, 0.5], "sid": "session-1", "time": time}))
+            .await
+            .unwrap();
+
+        let event = tokio::time::timeout(Duration::from_secs(2), consumer.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.stream, "com");
+        let ParsedSample::Com(cmd) = event.parse().unwrap() else {
+            panic!("expected a mental command");
+        };
+        assert_eq!(cmd.action, action);
+
+        // Simulate the analysis process crashing.
+        drop(consumer);
+        push_tx
+            .send(json!({"com": ["neutral", 0.0], "sid": "session-1", "time": 9.0}))
+            .await
+            .unwrap();
+        wait_for_consumers(&supervisor, 0).await;
+    }
+
+    let stats = supervisor.stats();
+    assert_eq!(stats.connections, 2);
+    assert!(stats.events >= 3);
+    supervisor.stop().await;
+    assert!(!path.exists());
+
+    drop(push_tx);
+    server_task.await.unwrap();
+    client.disconnect().await.unwrap();
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn ipc_server_shares_session_and_subscription_between_processes() {
+    use std::sync::Arc;
+
+    use emotiv_cortex_v2::ipc::IpcServer;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::UnixStream;
+
+    let Some(mut server) =
+        start_server_or_skip("ipc_server_shares_session_and_subscription_between_processes").await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+    let (push_tx, mut push_rx) = tokio::sync::mpsc::channel::<Value>(4);
+
+    // Cortex sees one session and one subscription, however many
+    // processes ask for them.
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-ipc").await;
+        let create = connection
+            .recv_request_method(Methods::CREATE_SESSION)
+            .await;
+        assert_eq!(create["params"]["headset"], "INSIGHT-1");
+        connection
+            .send_result(
+                rpc_id(&create),
+                json!({
+                    "id": "session-1", "status": "activated", "owner": "user",
+                    "license": "", "appId": "app", "started": "", "streams": [], "recordIds": [],
+                    "recording": false
+                }),
+            )
+            .await;
+        let subscribe = connection.recv_request_method(Methods::SUBSCRIBE).await;
+        connection
+            .send_result(
+                rpc_id(&subscribe),
+                json!({"success": [{"streamName": "com", "cols": ["act", "pow"], "sid": "session-1"}], "failure": []}),
+            )
+            .await;
+        while let Some(event) = push_rx.recv().await {
+            connection.push_event(event).await;
+        }
+    });
+
+    let client = Arc::new(ResilientClient::connect(config).await.unwrap());
+    let path = std::env::temp_dir().join(format!("emotiv-ipc-{}.sock", std::process::id()));
+    let ipc = IpcServer::start(Arc::clone(&client), &path).await.unwrap();
+
+    let mut processes = Vec::new();
+    for _ in 0..2 {
+        let (reader, mut writer) = UnixStream::connect(&path).await.unwrap().into_split();
+        let mut lines = BufReader::new(reader).lines();
+        let mut call = async |id: u64, method: &str, params: Value| {
+            let request = json!({"id": id, "method": method, "params": params});
+            writer
+                .write_all(format!("{request}\n").as_bytes())
+                .await
+                .unwrap();
+            let line = lines.next_line().await.unwrap().unwrap();
+            serde_json::from_str::<Value>(&line).unwrap()
+        };
+
+        let session = call(1, "createSession", json!({"headset": "INSIGHT-1"})).await;
+        assert_eq!(session["id"], 1);
+        assert_eq!(session["result"]["id"], "session-1");
+        let subscribed = call(
+            2,
+            "subscribe",
+            json!({"session": "session-1", "streams": ["com"]}),
+        )
+        .await;
+        assert_eq!(subscribed["result"]["streams"], json!(["com"]));
+        let unknown = call(3, "frobnicate", Value::Null).await;
+        assert!(unknown["error"]["message"].is_string());
+        processes.push(lines);
+    }
+
+    push_tx
+        .send(json!({"com": ["push", 0.5], "sid": "session-1", "time": 1.0}))
+        .await
+        .unwrap();
+    for lines in &mut processes {
+        let line = tokio::time::timeout(Duration::from_secs(2), lines.next_line())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let sample: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(sample["stream"], "com");
+        assert_eq!(sample["sample"]["action"], "push");
+    }
+
+    let stats = ipc.stats();
+    assert_eq!(stats.connections, 2);
+    assert_eq!(stats.requests, 6);
+    let report = ipc.stop().await;
+    assert_eq!(report.written, 1);
+    assert!(!path.exists());
+
+    drop(push_tx);
+    server_task.await.unwrap();
+    Arc::into_inner(client).unwrap().disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn shutdown_releases_resources_in_dependency_order() {
+    use emotiv_cortex_v2::teardown::TeardownStage;
+
+    let Some(mut server) =
+        start_server_or_skip("shutdown_releases_resources_in_dependency_order").await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-teardown").await;
+
+        let create = connection
+            .recv_request_method(Methods::CREATE_SESSION)
+            .await;
+        connection
+            .send_result(
+                rpc_id(&create),
+                json!({
+                    "id": "session-1", "status": "activated", "owner": "user",
+                    "license": "", "appId": "app", "started": "", "streams": [], "recordIds": [],
+                    "recording": false
+                }),
+            )
+            .await;
+        for stream in ["eeg", "met"] {
+            let subscribe = connection.recv_request_method(Methods::SUBSCRIBE).await;
+            connection
+                .send_result(rpc_id(&subscribe), json!({"success": [stream]}))
+                .await;
+        }
+        let record = connection.recv_request_method(Methods::CREATE_RECORD).await;
+        connection
+            .send_result(rpc_id(&record), json!({"record": {"uuid": "rec-1"}}))
+            .await;
+
+        let mut teardown = Vec::new();
+        for _ in 0..4 {
+            let request = connection.recv_request().await;
+            let result = if request["method"] == Methods::STOP_RECORD {
+                json!({"record": {"uuid": "rec-1"}})
+            } else {
+                json!({})
+            };
+            connection.send_result(rpc_id(&request), result).await;
+            teardown.push(request);
+        }
+        teardown
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let session = client.create_session("HS-1").await.unwrap();
+    client
+        .subscribe_streams(&session.id, &["eeg"])
+        .await
+        .unwrap();
+    // Subscribed on a session this client did not create.
+    client.subscribe_streams("foreign", &["met"]).await.unwrap();
+    client.create_record(&session.id, "run").await.unwrap();
+
+    let report = client.shutdown().await;
+    assert!(report.is_clean(), "{report:?}");
+    let stages: Vec<TeardownStage> = report.steps.iter().map(|s| s.stage).collect();
+    assert_eq!(
+        stages,
+        [
+            TeardownStage::StopRecords,
+            TeardownStage::Unsubscribe,
+            TeardownStage::Unsubscribe,
+            TeardownStage::CloseSessions,
+            TeardownStage::Disconnect,
+            TeardownStage::JoinTasks,
+        ]
+    );
+
+    let requests = server_task.await.unwrap();
+    let methods: Vec<&str> = requests
+        .iter()
+        .map(|r| r["method"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        methods,
+        [
+            Methods::STOP_RECORD,
+            Methods::UNSUBSCRIBE,
+            Methods::UNSUBSCRIBE,
+            Methods::UPDATE_SESSION
+        ]
+    );
+    assert_eq!(requests[3]["params"]["session"], "session-1");
+    assert_eq!(requests[3]["params"]["status"], "close");
+}
+
+#[tokio::test]
+async fn shutdown_closes_attached_routes_before_unsubscribing() {
+    use emotiv_cortex_v2::teardown::TeardownStage;
+
+    let Some(mut server) =
+        start_server_or_skip("shutdown_closes_attached_routes_before_unsubscribing").await
+    else {
+        return;
+    };
+    let csv = std::env::temp_dir().join(format!("emotiv-sink-close-{}.csv", std::process::id()));
+    let _ = std::fs::remove_file(&csv);
+    let mut config = resilient_test_config(server.ws_url());
+    config.routes = vec![format!("pow -> csv:{}", csv.display()).parse().unwrap()];
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-sinks").await;
+        let subscribe = connection.recv_request_method(Methods::SUBSCRIBE).await;
+        connection
+            .send_result(
+                rpc_id(&subscribe),
+                json!({"success": [{"streamName": "pow", "cols": ["a", "b"], "sid": "session-1"}], "failure": []}),
+            )
+            .await;
+        for i in 0..50 {
+            connection
+                .push_event(json!({"sid": "session-1", "time": f64::from(i), "pow": [1.0, 2.0]}))
+                .await;
+        }
+        // Answered after the events, so the client has dispatched them all
+        // once this call returns.
+        let query = connection
+            .recv_request_method(Methods::QUERY_HEADSETS)
+            .await;
+        connection.send_result(rpc_id(&query), json!([])).await;
+
+        let unsubscribe = connection.recv_request_method(Methods::UNSUBSCRIBE).await;
+        connection
+            .send_result(rpc_id(&unsubscribe), json!({}))
+            .await;
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let routes = client.start_routes("session-1").await.unwrap();
+    client.attach_sink(routes).await;
+    client
+        .query_headsets(QueryHeadsetsOptions::default())
+        .await
+        .unwrap();
+
+    let report = client.shutdown().await;
+    server_task.await.unwrap();
+    assert!(report.is_clean(), "{report:?}");
+    assert_eq!(report.steps[0].stage, TeardownStage::CloseSinks);
+    assert_eq!(report.steps[1].stage, TeardownStage::Unsubscribe);
+    assert_eq!(report.sinks.len(), 1);
+    assert_eq!(report.sinks[0].written, 50);
+    assert_eq!(report.lost_samples(), 0);
+
+    let written = std::fs::read_to_string(&csv).unwrap();
+    assert_eq!(written.lines().count(), 51, "header plus one row per event");
+    let _ = std::fs::remove_file(&csv);
+}
+
+#[tokio::test]
+async fn with_deadline_bounds_reconnect_cycles_and_reports_breakdown() {
+    use emotiv_cortex_v2::CortexError;
+    use emotiv_cortex_v2::deadline::DeadlinePhase;
+
+    let Some(mut server) =
+        start_server_or_skip("with_deadline_bounds_reconnect_cycles_and_reports_breakdown").await
+    else {
+        return;
+    };
+    let mut config = resilient_test_config(server.ws_url());
+    config.reconnect.base_delay_secs = 1;
+    config.reconnect.max_delay_secs = 1;
+    config.reconnect.max_attempts = 10;
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-deadline").await;
+        let query = connection
+            .recv_request_method(Methods::QUERY_HEADSETS)
+            .await;
+        assert_eq!(query["method"], Methods::QUERY_HEADSETS);
+        // Drop the connection and refuse every reconnect.
+        connection.force_close().await;
+        drop(server);
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let started = std::time::Instant::now();
+    let result = client
+        .with_deadline(Duration::from_millis(1500), async |c| {
+            c.query_headsets(QueryHeadsetsOptions::default()).await
+        })
+        .await;
+    let elapsed = started.elapsed();
+    server_task.await.unwrap();
+
+    let Err(CortexError::DeadlineExceeded { breakdown }) = result else {
+        panic!("expected DeadlineExceeded, got {result:?}");
+    };
+    // Without the deadline this would take ten reconnect attempts.
+    assert!(elapsed < Duration::from_secs(3), "took {elapsed:?}");
+    assert_eq!(breakdown.budget, Duration::from_millis(1500));
+    assert_eq!(breakdown.rpc_attempts, 1);
+    assert!(breakdown.reconnect_attempts >= 1, "{breakdown}");
+    assert!(breakdown.backoff > Duration::ZERO, "{breakdown}");
+    assert_eq!(breakdown.interrupted, Some(DeadlinePhase::Backoff));
+}
+
+#[tokio::test]
+async fn session_handle_migrates_run_to_spare_headset() {
+    use std::sync::Arc;
+
+    use emotiv_cortex_v2::session_handle::{SessionHandle, SessionHandleOptions};
+
+    let Some(mut server) =
+        start_server_or_skip("session_handle_migrates_run_to_spare_headset").await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-migrate").await;
+
+        let mut requests = Vec::new();
+        let mut sessions = 0;
+        let mut records = 0;
+        // open: createSession, setupProfile, createRecord, subscribe
+        // migrate: controlDevice, createSession, setupProfile, createRecord,
+        //          injectMarker, unsubscribe, subscribe, stopRecord, updateSession
+        for _ in 0..13 {
+            let request = connection.recv_request().await;
+            let result = match request["method"].as_str().unwrap() {
+                m if m == Methods::CREATE_SESSION => {
+                    sessions += 1;
+                    json!({
+                        "id": format!("session-{sessions}"), "status": "activated",
+                        "owner": "user", "license": "", "appId": "app", "started": "",
+                        "streams": [], "recordIds": [], "recording": false
+                    })
+                }
+                m if m == Methods::CREATE_RECORD => {
+                    records += 1;
+                    json!({"record": {"uuid": format!("rec-{records}")}})
+                }
+                m if m == Methods::STOP_RECORD => json!({"record": {"uuid": "rec-1"}}),
+                m if m == Methods::INJECT_MARKER => json!({"marker": {"uuid": "marker-1"}}),
+                m if m == Methods::SUBSCRIBE => json!({"success": ["eeg"]}),
+                _ => json!({}),
+            };
+            connection.send_result(rpc_id(&request), result).await;
+            requests.push(request);
+        }
+        connection
+            .push_event(json!({"sid": "session-2", "time": 1.0, "eeg": [1, 0, 4000.0]}))
+            .await;
+        requests
+    });
+
+    let client = Arc::new(ResilientClient::connect(config).await.unwrap());
+    let options = SessionHandleOptions {
+        streams: vec!["eeg".to_string()],
+        profile: Some("alice".to_string()),
+        record_title: Some("study".to_string()),
+        record_template: None,
+        replay_window: None,
+    };
+    let (handle, mut receivers) = SessionHandle::open(client, "HS-1", options).await.unwrap();
+    let mut eeg = receivers.remove("eeg").unwrap();
+    let mut binding = handle.watch();
+    assert_eq!(handle.binding().record_id.as_deref(), Some("rec-1"));
+
+    let migration = handle.migrate_to_headset("HS-2").await.unwrap();
+    assert!(migration.cleanup_errors.is_empty(), "{migration:?}");
+    assert_eq!(migration.previous.session_id, "session-1");
+    assert_eq!(migration.current.session_id, "session-2");
+    assert_eq!(migration.current.headset_id, "HS-2");
+    assert_eq!(migration.current.record_id.as_deref(), Some("rec-2"));
+    assert_eq!(migration.current.generation, 1);
+    assert!(binding.has_changed().unwrap());
+    assert_eq!(*binding.borrow_and_update(), migration.current);
+
+    // The receiver handed out on open now carries the spare's samples.
+    let event = tokio::time::timeout(Duration::from_secs(1), eeg.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event["sid"], "session-2");
+
+    let requests = server_task.await.unwrap();
+    let methods: Vec<&str> = requests
+        .iter()
+        .map(|r| r["method"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        &methods[4..],
+        [
+            Methods::CONTROL_DEVICE,
+            Methods::CREATE_SESSION,
+            Methods::SETUP_PROFILE,
+            Methods::CREATE_RECORD,
+            Methods::INJECT_MARKER,
+            Methods::UNSUBSCRIBE,
+            Methods::SUBSCRIBE,
+            Methods::STOP_RECORD,
+            Methods::UPDATE_SESSION,
+        ]
+    );
+    assert_eq!(requests[6]["params"]["profile"], "alice");
+    assert_eq!(requests[6]["params"]["headset"], "HS-2");
+    assert_eq!(requests[7]["params"]["title"], "study (part 2)");
+    assert_eq!(requests[8]["params"]["label"], "continues:rec-1");
+    assert_eq!(requests[9]["params"]["session"], "session-1");
+    assert_eq!(requests[11]["params"]["session"], "session-1");
+    assert_eq!(requests[12]["params"]["session"], "session-1");
+}
+
+#[tokio::test]
+async fn session_handle_buffers_eeg_for_epochs() {
+    use std::sync::Arc;
+
+    use emotiv_cortex_v2::session_handle::{SessionHandle, SessionHandleOptions};
+
+    let Some(mut server) = start_server_or_skip("session_handle_buffers_eeg_for_epochs").await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-epochs").await;
+
+        let create = connection
+            .recv_request_method(Methods::CREATE_SESSION)
+            .await;
+        connection
+            .send_result(
+                rpc_id(&create),
+                json!({
+                    "id": "session-1", "status": "activated",
+                    "owner": "user", "license": "", "appId": "app", "started": "",
+                    "streams": [], "recordIds": [], "recording": false
+                }),
+            )
+            .await;
+        let subscribe = connection.recv_request_method(Methods::SUBSCRIBE).await;
+        connection
+            .send_result(rpc_id(&subscribe), json!({"success": ["eeg"]}))
+            .await;
+        // 40 Insight samples at 128 Hz, value = sample index.
+        for n in 0..40_u32 {
+            let value = f64::from(n);
+            connection
+                .push_event(json!({
+                    "sid": "session-1",
+                    "time": 1_000.0 + f64::from(n) / 128.0,
+                    "eeg": [n, 0, value, value, value, value, value, 0.0, 0, []]
+                }))
+                .await;
+        }
+    });
+
+    let client = Arc::new(ResilientClient::connect(config).await.unwrap());
+    let options = SessionHandleOptions {
+        streams: vec!["eeg".to_string()],
+        replay_window: Some(Duration::from_secs(1)),
+        ..SessionHandleOptions::default()
+    };
+    let (handle, mut receivers) = SessionHandle::open(client, "INSIGHT-1", options)
+        .await
+        .unwrap();
+    let mut eeg = receivers.remove("eeg").unwrap();
+    server_task.await.unwrap();
+
+    // Every event still reaches the consumer.
+    for n in 0..40 {
+        let event = tokio::time::timeout(Duration::from_secs(1), eeg.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event["eeg"][0], n);
+    }
+
+    // Marker on sample 20; 6 samples before and 13 from it at 128 Hz.
+    let marker_us = 1_000_000_000 + 20 * 1_000_000 / 128;
+    let epoch = handle
+        .epoch_around(
+            marker_us,
+            Duration::from_millis(50),
+            Duration::from_millis(100),
+        )
+        .unwrap();
+    assert_eq!((epoch.num_channels(), epoch.num_samples()), (5, 19));
+    assert!((epoch.get(0, epoch.pre_samples).unwrap() - 20.0).abs() < f32::EPSILON);
+    assert!((epoch.get(4, 0).unwrap() - 14.0).abs() < f32::EPSILON);
+    assert_eq!(handle.replay_buffer().unwrap().len(), 40);
+    assert_eq!(handle.replay_dropped(), 0);
+    assert!(
+        handle
+            .client()
+            .background_tasks()
+            .contains(&"eeg replay tap".to_string())
+    );
+}
+
+#[tokio::test]
+async fn session_handle_titles_record_with_next_template_run() {
+    use std::sync::Arc;
+
+    use emotiv_cortex_v2::record_template::{RecordFields, RecordNaming, RecordTemplate};
+    use emotiv_cortex_v2::session_handle::{SessionHandle, SessionHandleOptions};
+
+    let Some(mut server) =
+        start_server_or_skip("session_handle_titles_record_with_next_template_run").await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-template").await;
+
+        let query = connection.recv_request_method(Methods::QUERY_RECORDS).await;
+        connection
+            .send_result(
+                rpc_id(&query),
+                json!({"records": [
+                    {"uuid": "r3", "title": "SUBJ01_task-rest_run-02"},
+                    {"uuid": "r2", "title": "SUBJ02_task-rest_run-05"},
+                    {"uuid": "r1", "title": "SUBJ01_task-rest_run-01"},
+                    {"uuid": "r0", "title": null},
+                ]}),
+            )
+            .await;
+        let create = connection
+            .recv_request_method(Methods::CREATE_SESSION)
+            .await;
+        connection
+            .send_result(
+                rpc_id(&create),
+                json!({
+                    "id": "session-1", "status": "activated",
+                    "owner": "user", "license": "", "appId": "app", "started": "",
+                    "streams": [], "recordIds": [], "recording": false
+                }),
+            )
+            .await;
+        let record = connection.recv_request_method(Methods::CREATE_RECORD).await;
+        connection
+            .send_result(rpc_id(&record), json!({"record": {"uuid": "rec-1"}}))
+            .await;
+        (query, record)
+    });
+
+    let client = Arc::new(ResilientClient::connect(config).await.unwrap());
+    let options = SessionHandleOptions {
+        record_template: Some(RecordNaming {
+            template: RecordTemplate::new("SUBJ{subject}_task-{task}_run-{run:02}").unwrap(),
+            fields: RecordFields::new("01", "rest"),
+        }),
+        ..SessionHandleOptions::default()
+    };
+    let (handle, _receivers) = SessionHandle::open(client, "HS-1", options).await.unwrap();
+    assert_eq!(handle.record_title(), Some("SUBJ01_task-rest_run-03"));
+    assert_eq!(
+        handle.record_file_name("csv").as_deref(),
+        Some("SUBJ01_task-rest_run-03.csv")
+    );
+    assert_eq!(handle.binding().record_id.as_deref(), Some("rec-1"));
+
+    let (query, record) = server_task.await.unwrap();
+    assert_eq!(query["params"]["limit"], 100);
+    assert_eq!(query["params"]["offset"], 0);
+    assert_eq!(record["params"]["title"], "SUBJ01_task-rest_run-03");
+}
+
+#[tokio::test]
+async fn self_test_checks_cortex_and_simulated_stream_path() {
+    use emotiv_cortex_v2::self_test::CheckStatus;
+
+    let Some(mut server) =
+        start_server_or_skip("self_test_checks_cortex_and_simulated_stream_path").await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-self-test").await;
+        let info = connection
+            .recv_request_method(Methods::GET_CORTEX_INFO)
+            .await;
+        connection
+            .send_result(rpc_id(&info), json!({"version": "3.7.0"}))
+            .await;
+        connection
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let report = client.self_test().await;
+    let _connection = server_task.await.unwrap();
+
+    assert!(report.passed(), "{report}");
+    let names: Vec<&str> = report.checks.iter().map(|c| c.name).collect();
+    assert_eq!(names, ["features", "runtime", "cortex", "stream"]);
+    let cortex = report.check("cortex").unwrap();
+    assert_eq!(cortex.status, CheckStatus::Pass);
+    assert!(cortex.detail.contains("3.7.0"), "{report}");
+    assert_eq!(report.check("stream").unwrap().status, CheckStatus::Pass);
+}
+
+#[tokio::test]
+async fn unloaded_profile_is_reported_and_reloaded() {
+    use emotiv_cortex_v2::protocol::constants::WarningCodes;
+    use emotiv_cortex_v2::protocol::profiles::ProfileAction;
+
+    let Some(mut server) = start_server_or_skip("unloaded_profile_is_reported_and_reloaded").await
+    else {
+        return;
+    };
+    let mut config = resilient_test_config(server.ws_url());
+    config.auto_reload_profile = true;
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-profile").await;
+
+        let load = connection.recv_request_method(Methods::SETUP_PROFILE).await;
+        connection.send_result(rpc_id(&load), json!({})).await;
+
+        connection
+            .push_event(json!({
+                "warning": {
+                    "code": WarningCodes::CORTEX_AUTO_UNLOAD_PROFILE,
+                    "message": {"headsetId": "HS-1", "profileName": "alice"}
+                }
+            }))
+            .await;
+
+        let reload = connection.recv_request_method(Methods::SETUP_PROFILE).await;
+        connection.send_result(rpc_id(&reload), json!({})).await;
+        reload
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let mut events = client.event_receiver();
+    client
+        .setup_profile("HS-1", "alice", ProfileAction::Load)
+        .await
+        .unwrap();
+
+    let mut seen = Vec::new();
+    while seen.len() < 2 {
+        let event = tokio::time::timeout(Duration::from_secs(2), events.recv())
+            .await
+            .expect("timed out waiting for profile events")
+            .unwrap();
+        if matches!(
+            event,
+            ConnectionEvent::ProfileUnloaded { .. } | ConnectionEvent::ProfileReloaded { .. }
+        ) {
+            seen.push(event);
+        }
+    }
+    assert_eq!(
+        seen,
+        [
+            ConnectionEvent::ProfileUnloaded {
+                headset_id: Some("HS-1".into()),
+                profile: Some("alice".into()),
+                automatic: true,
+            },
+            ConnectionEvent::ProfileReloaded {
+                headset_id: "HS-1".into(),
+                profile: "alice".into(),
+            },
+        ]
+    );
+
+    let reload = server_task.await.unwrap();
+    assert_eq!(reload["params"]["headset"], "HS-1");
+    assert_eq!(reload["params"]["profile"], "alice");
+    assert_eq!(reload["params"]["status"], "load");
+    assert_eq!(
+        client.loaded_profiles().get("HS-1").map(String::as_str),
+        Some("alice")
+    );
+}
+
+#[tokio::test]
+async fn snapshot_issues_startup_queries_concurrently() {
+    let Some(mut server) =
+        start_server_or_skip("snapshot_issues_startup_queries_concurrently").await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-snapshot").await;
+
+        // All seven queries are in flight before any of them is answered.
+        let mut requests = Vec::new();
+        for _ in 0..7 {
+            requests.push(connection.recv_request().await);
+        }
+        let mut methods = Vec::new();
+        for request in requests {
+            let method = request["method"].as_str().unwrap().to_string();
+            let result = match method.as_str() {
+                Methods::GET_CORTEX_INFO => json!({"version": "3.7.5"}),
+                Methods::GET_USER_LOGIN => json!([{"username": "alice"}]),
+                Methods::GET_USER_INFO => json!({"username": "alice"}),
+                Methods::GET_LICENSE_INFO => {
+                    json!({"isOnline": true, "license": {"scopes": ["eeg"], "expired": false}})
+                }
+                Methods::QUERY_HEADSETS => json!([{"id": "INSIGHT-1", "status": "connected"}]),
+                Methods::QUERY_SESSIONS => json!([]),
+                Methods::QUERY_PROFILE => json!([{
+                    "uuid": "p-1",
+                    "name": "alice-profile",
+                    "readOnly": false,
+                    "eegChannels": ["AF3"]
+                }]),
+                other => panic!("unexpected snapshot request {other}"),
+            };
+            connection.send_result(rpc_id(&request), result).await;
+            methods.push(method);
+        }
+        methods.sort();
+        methods
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let snapshot = client.snapshot().await.unwrap();
+
+    assert_eq!(snapshot.cortex_info["version"], "3.7.5");
+    assert_eq!(snapshot.user_login[0].username, "alice");
+    assert_eq!(snapshot.user_info.username, "alice");
+    assert!(snapshot.license_info.has_eeg_access());
+    assert_eq!(snapshot.headsets[0].id, "INSIGHT-1");
+    assert!(snapshot.sessions.is_empty());
+    assert_eq!(snapshot.profiles[0].name, "alice-profile");
+
+    let methods = server_task.await.unwrap();
+    assert_eq!(
+        methods,
+        [
+            Methods::GET_CORTEX_INFO,
+            Methods::GET_LICENSE_INFO,
+            Methods::GET_USER_INFO,
+            Methods::GET_USER_LOGIN,
+            Methods::QUERY_HEADSETS,
+            Methods::QUERY_PROFILE,
+            Methods::QUERY_SESSIONS,
+        ]
+    );
+}
+
+#[tokio::test]
+async fn rotate_credentials_swaps_token_only_after_new_pair_is_authorized() {
+    let Some(mut server) =
+        start_server_or_skip("rotate_credentials_swaps_token_only_after_new_pair_is_authorized")
+            .await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-old").await;
+
+        // First rotation: the new client id is rejected.
+        let info = connection
+            .recv_request_method(Methods::GET_CORTEX_INFO)
+            .await;
+        connection
+            .send_result(rpc_id(&info), json!({"version": "mock"}))
+            .await;
+        let rejected = connection
+            .recv_request_method(Methods::REQUEST_ACCESS)
+            .await;
+        assert_eq!(rejected["params"]["clientId"], "bad-id");
+        connection
+            .send_error(rpc_id(&rejected), -32021, "invalid client credentials")
+            .await;
+
+        let sessions = connection
+            .recv_request_method(Methods::QUERY_SESSIONS)
+            .await;
+        let token_after_failure = sessions["params"]["cortexToken"].clone();
+        connection.send_result(rpc_id(&sessions), json!([])).await;
+
+        // Second rotation succeeds.
+        drive_auth_handshake(&mut connection, "token-new").await;
+        let sessions = connection
+            .recv_request_method(Methods::QUERY_SESSIONS)
+            .await;
+        let token_after_rotation = sessions["params"]["cortexToken"].clone();
+        connection.send_result(rpc_id(&sessions), json!([])).await;
+
+        (token_after_failure, token_after_rotation)
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let mut events = client.event_receiver();
+
+    client
+        .rotate_credentials("bad-id", "bad-secret")
+        .await
+        .unwrap_err();
+    client.query_sessions().await.unwrap();
+
+    client
+        .rotate_credentials("new-id", "new-secret")
+        .await
+        .unwrap();
+    client.query_sessions().await.unwrap();
+    assert_eq!(client.cortex_token().await, "token-new");
+
+    let (token_after_failure, token_after_rotation) = server_task.await.unwrap();
+    assert_eq!(token_after_failure, "token-old");
+    assert_eq!(token_after_rotation, "token-new");
+
+    let mut rotated = None;
+    while let Ok(event) = events.try_recv() {
+        if let ConnectionEvent::CredentialsRotated { client_id } = event {
+            rotated = Some(client_id);
+        }
+    }
+    assert_eq!(rotated.as_deref(), Some("new-id"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn reconnect_racing_stream_channel_updates_does_not_deadlock() {
+    let Some(mut server) =
+        start_server_or_skip("reconnect_racing_stream_channel_updates_does_not_deadlock").await
+    else {
+        return;
+    };
+    let mut config = resilient_test_config(server.ws_url());
+    // Long interval: the monitor is started and stopped but never probes.
+    config.health.enabled = true;
+    config.health.interval_secs = 3600;
+    // The sleeping monitor only notices `stop` at its next tick.
+    config.teardown.step_timeout_secs = 1;
+
+    let server_task = tokio::spawn(async move {
+        let mut first = server.accept_connection().await;
+        drive_auth_handshake(&mut first, "token-1").await;
+        for i in 0..200 {
+            first
+                .push_event(json!({"sid": "s", "time": f64::from(i), "pow": [1.0]}))
+                .await;
+        }
+        first.recv_request_method(Methods::QUERY_HEADSETS).await;
+        first.force_close().await;
+
+        let mut second = server.accept_connection().await;
+        drive_auth_handshake(&mut second, "token-2").await;
+        let retried = second.recv_request_method(Methods::QUERY_HEADSETS).await;
+        second.send_result(rpc_id(&retried), json!([])).await;
+        for i in 0..200 {
+            second
+                .push_event(json!({"sid": "s", "time": f64::from(i), "pow": [1.0]}))
+                .await;
+        }
+    });
+
+    let client = std::sync::Arc::new(ResilientClient::connect(config).await.unwrap());
+    let churn: Vec<_> = (0..4)
+        .map(|_| {
+            let client = std::sync::Arc::clone(&client);
+            tokio::spawn(async move {
+                for _ in 0..200 {
+                    let receivers = client.create_stream_channels(&["pow", "eeg"]).await;
+                    drop(receivers);
+                    tokio::task::yield_now().await;
+                }
+            })
+        })
+        .collect();
+
+    let outcome = tokio::time::timeout(Duration::from_secs(10), async {
+        let headsets = client
+            .query_headsets(QueryHeadsetsOptions::default())
+            .await
+            .unwrap();
+        for task in churn {
+            task.await.unwrap();
+        }
+        headsets
+    })
+    .await;
+    assert!(
+        outcome
+            .expect("reconnect and subscribe raced into a deadlock")
+            .is_empty()
+    );
+
+    let receivers = client.create_stream_channels(&["pow"]).await;
+    assert_eq!(receivers.len(), 1);
+    let Ok(client) = std::sync::Arc::try_unwrap(client) else {
+        panic!("churn tasks still hold the client");
+    };
+    client.disconnect().await.unwrap();
+    server_task.await.unwrap();
+}
+
+#[tokio::test]
+async fn idle_session_is_reported_and_default_recording_started() {
+    let Some(mut server) =
+        start_server_or_skip("idle_session_is_reported_and_default_recording_started").await
+    else {
+        return;
+    };
+    let mut config = resilient_test_config(server.ws_url());
+    config.idle.after_secs = 1;
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-idle").await;
+
+        let create = connection
+            .recv_request_method(Methods::CREATE_SESSION)
+            .await;
+        connection
+            .send_result(
+                rpc_id(&create),
+                json!({
+                    "id": "session-idle", "status": "activated", "owner": "user",
+                    "license": "", "appId": "app", "started": "", "streams": [], "recordIds": [],
+                    "recording": false
+                }),
+            )
+            .await;
+
+        let record = connection.recv_request_method(Methods::CREATE_RECORD).await;
+        connection
+            .send_result(rpc_id(&record), json!({"record": {"uuid": "rec-idle"}}))
+            .await;
+        record
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let mut events = client.event_receiver();
+    let session = client.create_session("HS-1").await.unwrap();
+
+    let idle = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let Ok(ConnectionEvent::IdleSession { session_id, .. }) = events.recv().await {
+                return session_id;
+            }
+        }
+    })
+    .await
+    .expect("no IdleSession event");
+    assert_eq!(idle, session.id);
+
+    let record = client.start_default_recording(&idle).await.unwrap();
+    assert_eq!(record.uuid, "rec-idle");
+
+    let request = server_task.await.unwrap();
+    assert_eq!(request["params"]["session"], "session-idle");
+    assert_eq!(request["params"]["title"], "Untitled recording");
+}
+
+#[tokio::test]
+async fn multi_session_client_routes_each_headset_by_sid() {
+    use emotiv_cortex_v2::multi_session::MultiSessionClient;
+    use std::sync::Arc;
+
+    let Some(mut server) =
+        start_server_or_skip("multi_session_client_routes_each_headset_by_sid").await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-multi").await;
+
+        for sid in ["session-a", "session-b"] {
+            let create = connection
+                .recv_request_method(Methods::CREATE_SESSION)
+                .await;
+            connection
+                .send_result(
+                    rpc_id(&create),
+                    json!({
+                        "id": sid, "status": "activated", "owner": "user", "license": "",
+                        "appId": "app", "started": "", "streams": [], "recordIds": [],
+                        "recording": false
+                    }),
+                )
+                .await;
+        }
+        for _ in 0..2 {
+            let subscribe = connection.recv_request_method(Methods::SUBSCRIBE).await;
+            connection
+                .send_result(rpc_id(&subscribe), json!({"success": ["eeg"]}))
+                .await;
+        }
+        for i in 0..3 {
+            for sid in ["session-a", "session-b"] {
+                connection
+                    .push_event(json!({"sid": sid, "time": f64::from(i), "eeg": [i, 0, 4200.0]}))
+                    .await;
+            }
+        }
+        connection
+    });
+
+    let client = Arc::new(ResilientClient::connect(config).await.unwrap());
+    let multi = MultiSessionClient::new(Arc::clone(&client));
+    assert_eq!(multi.open("EPOCX-A").await.unwrap().id, "session-a");
+    assert_eq!(multi.open("EPOCX-B").await.unwrap().id, "session-b");
+    // Opening again returns the session already open.
+    assert_eq!(multi.open("EPOCX-A").await.unwrap().id, "session-a");
+
+    let mut a = multi.subscribe("EPOCX-A", &["eeg"]).await.unwrap();
+    let mut b = multi.subscribe("EPOCX-B", &["eeg"]).await.unwrap();
+    let (a, b) = (a.get_mut("eeg").unwrap(), b.get_mut("eeg").unwrap());
+    for expected in 0..3 {
+        for (rx, sid) in [(&mut *a, "session-a"), (&mut *b, "session-b")] {
+            let event = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+                .await
+                .expect("no event")
+                .unwrap();
+            assert_eq!(event["sid"], sid);
+            assert_eq!(event["eeg"][0], expected);
+        }
+    }
+
+    let sessions = multi.sessions();
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(sessions[0].0, "EPOCX-A");
+    drop(server_task.await.unwrap());
+}
+
+#[test]
+fn shutdown_joins_background_tasks_so_runtime_stops_at_once() {
+    // The mock server runs on its own runtime, so only the client's tasks
+    // live on the runtime under test.
+    let server_runtime = tokio::runtime::Runtime::new().unwrap();
+    let Some(mut server) = server_runtime.block_on(start_server_or_skip(
+        "shutdown_joins_background_tasks_so_runtime_stops_at_once",
+    )) else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+    let server_task = server_runtime.spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-tasks").await;
+        connection
+    });
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let report = runtime.block_on(async {
+        let client = ResilientClient::connect(config).await.unwrap();
+        let tasks = client.background_tasks();
+        for name in ["reader loop", "profile watcher", "idle session watcher"] {
+            assert!(tasks.iter().any(|t| t == name), "{name} missing: {tasks:?}");
+        }
+        client.shutdown().await
+    });
+
+    assert!(report.is_clean(), "{report:?}");
+    assert!(report.leaked_tasks.is_empty());
+    assert_eq!(runtime.metrics().num_alive_tasks(), 0);
+    runtime.shutdown_timeout(Duration::ZERO);
+
+    drop(server_runtime.block_on(server_task).unwrap());
+}
+
+#[tokio::test]
+async fn resubscribe_recreates_session_and_keeps_stream_receiver() {
+    let Some(mut server) =
+        start_server_or_skip("resubscribe_recreates_session_and_keeps_stream_receiver").await
+    else {
+        return;
+    };
+    let mut config = resilient_test_config(server.ws_url());
+    config.reconnect.resubscribe = true;
+
+    let session = |id: &str| {
+        json!({
+            "id": id, "status": "activated", "owner": "user", "license": "",
+            "appId": "app", "started": "", "streams": [], "recordIds": [], "recording": false
+        })
+    };
+    let subscribed = |sid: &str| json!({"success": [{"streamName": "met", "cols": ["a"], "sid": sid}], "failure": []});
+
+    let server_task = tokio::spawn(async move {
+        let mut first = server.accept_connection().await;
+        drive_auth_handshake(&mut first, "token-first").await;
+        let create = first.recv_request_method(Methods::CREATE_SESSION).await;
+        first
+            .send_result(rpc_id(&create), session("session-1"))
+            .await;
+        let subscribe = first.recv_request_method(Methods::SUBSCRIBE).await;
+        first
+            .send_result(rpc_id(&subscribe), subscribed("session-1"))
+            .await;
+        first
+            .push_event(json!({"sid": "session-1", "time": 1.0, "met": [0.1]}))
+            .await;
+        first.recv_request_method(Methods::QUERY_HEADSETS).await;
+        first.force_close().await;
+
+        let mut second = server.accept_connection().await;
+        drive_auth_handshake(&mut second, "token-second").await;
+        let stale = second.recv_request_method(Methods::SUBSCRIBE).await;
+        assert_eq!(stale["params"]["session"], "session-1");
+        second
+            .send_error(rpc_id(&stale), -32005, "session does not exist")
+            .await;
+        let create = second.recv_request_method(Methods::CREATE_SESSION).await;
+        assert_eq!(create["params"]["headset"], "HS-1");
+        second
+            .send_result(rpc_id(&create), session("session-2"))
+            .await;
+        let subscribe = second.recv_request_method(Methods::SUBSCRIBE).await;
+        assert_eq!(subscribe["params"]["session"], "session-2");
+        assert_eq!(subscribe["params"]["streams"], json!(["met"]));
+        second
+            .send_result(rpc_id(&subscribe), subscribed("session-2"))
+            .await;
+        second
+            .push_event(json!({"sid": "session-2", "time": 2.0, "met": [0.2]}))
+            .await;
+        let query = second.recv_request_method(Methods::QUERY_HEADSETS).await;
+        second.send_result(rpc_id(&query), json!([])).await;
+        second
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let mut events = client.event_receiver();
+    let mut receivers = client.create_stream_channels(&["met"]).await;
+    let mut met = receivers.remove("met").unwrap();
+    let session = client.create_session("HS-1").await.unwrap();
+    client
+        .subscribe_streams(&session.id, &["met"])
+        .await
+        .unwrap();
+
+    let first = met.recv().await.unwrap();
+    assert_eq!(first["sid"], "session-1");
+
+    client
+        .query_headsets(QueryHeadsetsOptions::default())
+        .await
+        .unwrap();
+    let second = tokio::time::timeout(Duration::from_secs(5), met.recv())
+        .await
+        .expect("receiver stalled after reconnect")
+        .expect("receiver closed by reconnect");
+    assert_eq!(second["sid"], "session-2");
+
+    let mut resubscribed = None;
+    while let Ok(event) = events.try_recv() {
+        if let ConnectionEvent::Resubscribed { .. } = event {
+            resubscribed = Some(event);
+        }
+    }
+    assert_eq!(
+        resubscribed,
+        Some(ConnectionEvent::Resubscribed {
+            previous_session_id: "session-1".into(),
+            session_id: "session-2".into(),
+            streams: vec!["met".into()],
+        })
+    );
+    drop(server_task.await.unwrap());
+}
+
+#[tokio::test]
+async fn file_recorder_writes_labelled_csv_and_markers() {
+    use emotiv_cortex_v2::recorder::{FileRecorder, FileRecorderOptions};
+
+    let Some(mut server) =
+        start_server_or_skip("file_recorder_writes_labelled_csv_and_markers").await
+    else {
+        return;
+    };
+    let dir = std::env::temp_dir().join(format!("emotiv-file-recorder-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-files").await;
+        let subscribe = connection.recv_request_method(Methods::SUBSCRIBE).await;
+        connection
+            .send_result(
+                rpc_id(&subscribe),
+                json!({"success": [{
+                    "streamName": "eeg",
+                    "cols": ["COUNTER", "AF3", "AF4", "MARKERS"],
+                    "sid": "session-1"
+                }], "failure": []}),
+            )
+            .await;
+        for i in 0..3 {
+            connection
+                .push_event(json!({
+                    "sid": "session-1",
+                    "time": 100.0 + f64::from(i),
+                    "eeg": [i, 4200.5, null, []]
+                }))
+                .await;
+        }
+        let marker = connection.recv_request_method(Methods::INJECT_MARKER).await;
+        assert_eq!(marker["params"]["label"], "eyes-closed");
+        connection
+            .send_result(rpc_id(&marker), json!({"marker": {"uuid": "m-1"}}))
+            .await;
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let options = FileRecorderOptions {
+        dir: dir.clone(),
+        name: "SUBJ01".into(),
+        ..FileRecorderOptions::default()
+    };
+    let recorder = FileRecorder::start(&client, "session-1", &["eeg"], options)
+        .await
+        .unwrap();
+    // The marker round trip follows the events, so they have all been
+    // dispatched once it returns.
+    let info = recorder
+        .inject_marker(&client, "eyes-closed", 1, "app")
+        .await
+        .unwrap();
+    assert_eq!(info.uuid, "m-1");
+    let reports = recorder.stop().await;
+    server_task.await.unwrap();
+
+    assert_eq!(reports.len(), 2);
+    assert!(reports.iter().all(|r| r.error.is_none()), "{reports:?}");
+    assert_eq!(reports[0].written, 3);
+    assert_eq!(reports[1].written, 1);
+    let eeg = std::fs::read_to_string(dir.join("SUBJ01_eeg.csv")).unwrap();
+    assert_eq!(
+        eeg.lines().collect::<Vec<_>>(),
+        [
+            "time,COUNTER,AF3,AF4",
+            "100,0,4200.5,",
+            "101,1,4200.5,",
+            "102,2,4200.5,"
+        ]
+    );
+    let markers = std::fs::read_to_string(dir.join("SUBJ01_markers.csv")).unwrap();
+    let rows: Vec<&str> = markers.lines().collect();
+    assert_eq!(rows[0], "time,label,value,port,duration");
+    assert!(rows[1].ends_with(",eyes-closed,1,app,0"), "{}", rows[1]);
+
+    let _ = client.shutdown().await;
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn file_recorder_anonymizes_times_and_metadata() {
+    use emotiv_cortex_v2::anonymize::{AnonymizeOptions, Anonymizer};
+    use emotiv_cortex_v2::recorder::{FileRecorder, FileRecorderOptions};
+    use emotiv_cortex_v2::streams::StreamMarker;
+
+    let Some(mut server) =
+        start_server_or_skip("file_recorder_anonymizes_times_and_metadata").await
+    else {
+        return;
+    };
+    let dir = std::env::temp_dir().join(format!("emotiv-file-anon-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-anon").await;
+        let subscribe = connection.recv_request_method(Methods::SUBSCRIBE).await;
+        connection
+            .send_result(
+                rpc_id(&subscribe),
+                json!({"success": [{
+                    "streamName": "eeg",
+                    "cols": ["COUNTER", "AF3"],
+                    "sid": "session-1"
+                }], "failure": []}),
+            )
+            .await;
+        connection
+            .push_event(json!({"sid": "session-1", "time": 1_700_000_000.0, "eeg": [0, 1.5]}))
+            .await;
+        // Hold the connection until the client is done.
+        let _ = connection.recv_request().await;
+    });
+
+    let anon = Anonymizer::new(AnonymizeOptions::with_salt("lab"));
+    let client = ResilientClient::connect(config).await.unwrap();
+    let options = FileRecorderOptions {
+        dir: dir.clone(),
+        name: "rest".into(),
+        subject: Some("Jane Doe".into()),
+        metadata: Some(json!({"email": "jane@example.com", "task": "rest"})),
+        anonymize: Some(anon.clone()),
+        ..FileRecorderOptions::default()
+    };
+    let recorder = FileRecorder::start(&client, "session-1", &["eeg"], options)
+        .await
+        .unwrap();
+    recorder.add_marker(&StreamMarker {
+        timestamp: 1_700_000_000_500_000,
+        label: "stim".into(),
+        value: 1,
+        port: "app".into(),
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let files = recorder.files().to_vec();
+    let reports = recorder.stop().await;
+    assert!(reports.iter().all(|r| r.error.is_none()), "{reports:?}");
+
+    let offset = anon.time_offset("Jane Doe").as_secs_f64();
+    assert!(offset > 0.0);
+    let eeg = std::fs::read_to_string(dir.join("rest_eeg.csv")).unwrap();
+    let row = eeg.lines().nth(1).unwrap();
+    let time: f64 = row.split(',').next().unwrap().parse().unwrap();
+    assert!((time - (1_700_000_000.0 - offset)).abs() < 1e-3, "{row}");
+    let markers = std::fs::read_to_string(dir.join("rest_markers.csv")).unwrap();
+    let marker_time: f64 = markers
+        .lines()
+        .nth(1)
+        .unwrap()
+        .split(',')
+        .next()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((marker_time - (1_700_000_000.5 - offset)).abs() < 1e-3);
+
+    assert!(files.contains(&dir.join("rest_metadata.json")), "{files:?}");
+    let metadata: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join("rest_metadata.json")).unwrap())
+            .unwrap();
+    assert_eq!(
+        metadata,
+        json!({"subjectName": anon.pseudonym("Jane Doe"), "task": "rest"})
+    );
+
+    let _ = client.shutdown().await;
+    server_task.abort();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn file_recorder_writes_pause_and_disconnect_gaps() {
+    use emotiv_cortex_v2::recorder::{FileRecorder, FileRecorderOptions};
+
+    let Some(mut server) =
+        start_server_or_skip("file_recorder_writes_pause_and_disconnect_gaps").await
+    else {
+        return;
+    };
+    let dir = std::env::temp_dir().join(format!("emotiv-file-gaps-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut first = server.accept_connection().await;
+        drive_auth_handshake(&mut first, "token-gaps").await;
+        let subscribe = first.recv_request_method(Methods::SUBSCRIBE).await;
+        first
+            .send_result(
+                rpc_id(&subscribe),
+                json!({"success": [{"streamName": "eeg", "cols": ["COUNTER", "AF3"], "sid": "session-1"}], "failure": []}),
+            )
+            .await;
+        for counter in 0..2 {
+            // One sample before the pause, one during it.
+            let marker = first.recv_request_method(Methods::INJECT_MARKER).await;
+            first
+                .push_event(json!({"sid": "session-1", "time": 100.0, "eeg": [counter, 1.0]}))
+                .await;
+            first
+                .send_result(rpc_id(&marker), json!({"marker": {"uuid": "m"}}))
+                .await;
+        }
+        first.recv_request_method(Methods::QUERY_HEADSETS).await;
+        first.force_close().await;
+
+        let mut second = server.accept_connection().await;
+        drive_auth_handshake(&mut second, "token-gaps-2").await;
+        let retried = second.recv_request_method(Methods::QUERY_HEADSETS).await;
+        second.send_result(rpc_id(&retried), json!([])).await;
+        second
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let options = FileRecorderOptions {
+        dir: dir.clone(),
+        name: "gaps".into(),
+        ..FileRecorderOptions::default()
+    };
+    let recorder = FileRecorder::start(&client, "session-1", &["eeg"], options)
+        .await
+        .unwrap();
+    recorder
+        .inject_marker(&client, "before", 1, "app")
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    recorder.pause();
+    recorder
+        .inject_marker(&client, "during", 2, "app")
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    recorder.resume();
+    // The first connection drops under this call; it succeeds on the next.
+    client
+        .query_headsets(QueryHeadsetsOptions::default())
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!recorder.gaps().is_paused());
+    let reports = recorder.stop().await;
+    assert!(reports.iter().all(|r| r.error.is_none()), "{reports:?}");
+
+    let eeg = std::fs::read_to_string(dir.join("gaps_eeg.csv")).unwrap();
+    // The sample pushed while paused was skipped.
+    assert_eq!(
+        eeg.lines().collect::<Vec<_>>(),
+        ["time,COUNTER,AF3", "100,0,1"]
+    );
+    let markers = std::fs::read_to_string(dir.join("gaps_markers.csv")).unwrap();
+    let labels: Vec<&str> = markers
+        .lines()
+        .skip(1)
+        .map(|row| row.split(',').nth(1).unwrap())
+        .collect();
+    assert_eq!(
+        labels,
+        [
+            "before",
+            "during",
+            "capture_gap:paused",
+            "capture_gap:disconnected"
+        ]
+    );
+    let pause: Vec<&str> = markers.lines().nth(3).unwrap().split(',').collect();
+    assert_eq!(pause[2..4], ["0", ""]);
+    assert!(pause[4].parse::<f64>().unwrap() >= 0.04, "{pause:?}");
+
+    let _ = client.shutdown().await;
+    drop(server_task.await.unwrap());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn session_manager_waits_for_connection_and_cleans_up_on_drop() {
+    use std::sync::Arc;
+
+    use emotiv_cortex_v2::headset::HeadsetModel;
+    use emotiv_cortex_v2::session_manager::{
+        HeadsetSelector, SessionManager, SessionManagerConfig,
+    };
+
+    let Some(mut server) =
+        start_server_or_skip("session_manager_waits_for_connection_and_cleans_up_on_drop").await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-manager").await;
+
+        let mut statuses = ["discovered", "connecting", "connected"].into_iter();
+        let mut methods = Vec::new();
+        // queryHeadsets, controlDevice, queryHeadsets x2, createSession,
+        // then on drop: updateSession, controlDevice
+        for _ in 0..7 {
+            let request = connection.recv_request().await;
+            let method = request["method"].as_str().unwrap().to_string();
+            let result = match method.as_str() {
+                m if m == Methods::QUERY_HEADSETS => {
+                    let status = statuses.next().unwrap();
+                    json!([
+                        {"id": "EPOCX-11223344", "status": "discovered"},
+                        {"id": "INSIGHT-A1B2C3D4", "status": status}
+                    ])
+                }
+                m if m == Methods::CREATE_SESSION => {
+                    assert_eq!(request["params"]["headset"], "INSIGHT-A1B2C3D4");
+                    json!({
+                        "id": "session-1", "status": "activated",
+                        "owner": "user", "license": "", "appId": "app", "started": "",
+                        "streams": [], "recordIds": [], "recording": false
+                    })
+                }
+                m if m == Methods::CONTROL_DEVICE => {
+                    methods.push(format!(
+                        "{m}:{}",
+                        request["params"]["command"].as_str().unwrap()
+                    ));
+                    json!({})
+                }
+                _ => json!({}),
+            };
+            if method != Methods::CONTROL_DEVICE {
+                methods.push(method);
+            }
+            connection.send_result(rpc_id(&request), result).await;
+        }
+        methods
+    });
+
+    let client = Arc::new(ResilientClient::connect(config).await.unwrap());
+    let manager = SessionManager::new(
+        client,
+        SessionManagerConfig {
+            poll_interval: Duration::from_millis(10),
+            ..SessionManagerConfig::default()
+        },
+    );
+    let session = manager
+        .open(&HeadsetSelector::Model(HeadsetModel::Insight))
+        .await
+        .unwrap();
+    assert_eq!(session.session_id(), "session-1");
+    assert_eq!(session.headset_id(), "INSIGHT-A1B2C3D4");
+    assert_eq!(session.headset().status, "connected");
+    assert_eq!(session.headset_model(), &HeadsetModel::Insight);
+    drop(session);
+
+    let methods = tokio::time::timeout(Duration::from_secs(5), server_task)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        methods,
+        [
+            Methods::QUERY_HEADSETS.to_string(),
+            format!("{}:connect", Methods::CONTROL_DEVICE),
+            Methods::QUERY_HEADSETS.to_string(),
+            Methods::QUERY_HEADSETS.to_string(),
+            Methods::CREATE_SESSION.to_string(),
+            Methods::UPDATE_SESSION.to_string(),
+            format!("{}:disconnect", Methods::CONTROL_DEVICE),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn warnings_are_decoded_and_forwarded() {
+    use emotiv_cortex_v2::protocol::warnings::WarningEvent;
+
+    let Some(mut server) = start_server_or_skip("warnings_are_decoded_and_forwarded").await else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+    let (ready_tx, ready_rx) = tokio::sync::oneshot::channel();
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-warnings").await;
+        ready_rx.await.unwrap();
+        connection
+            .push_event(json!({"warning": {
+                "code": 103,
+                "message": {"headsetId": "INSIGHT-A1B2C3D4", "behavior": "disconnected"}
+            }}))
+            .await;
+        connection
+            .push_event(json!({"warning": {"code": 142, "message": "done"}}))
+            .await;
+        connection
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let mut warnings = client.warning_receiver();
+    ready_tx.send(()).unwrap();
+
+    let unplugged = tokio::time::timeout(Duration::from_secs(2), warnings.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        unplugged,
+        WarningEvent::HeadsetDisconnected {
+            headset_id: Some("INSIGHT-A1B2C3D4".into())
+        }
+    );
+    let scan = tokio::time::timeout(Duration::from_secs(2), warnings.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(scan, WarningEvent::HeadsetScanFinished);
+
+    let _connection = server_task.await.unwrap();
+}
+
+#[tokio::test]
+async fn headset_watcher_reports_state_changes_and_loss() {
+    use std::sync::Arc;
+
+    use emotiv_cortex_v2::headset_watcher::{
+        HeadsetEvent, HeadsetState, HeadsetWatcher, HeadsetWatcherConfig,
+    };
+
+    let Some(mut server) =
+        start_server_or_skip("headset_watcher_reports_state_changes_and_loss").await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-watcher").await;
+
+        let mut statuses = ["connecting", "connected"].into_iter();
+        loop {
+            let request = connection.recv_request().await;
+            let result = match statuses.next() {
+                Some(status) => json!([
+                    {"id": "INSIGHT-A1B2C3D4", "status": status, "batteryPercent": 10}
+                ]),
+                None => json!([]),
+            };
+            connection.send_result(rpc_id(&request), result).await;
+        }
+    });
+
+    let client = Arc::new(ResilientClient::connect(config).await.unwrap());
+    let watcher = HeadsetWatcher::spawn(
+        client,
+        HeadsetWatcherConfig {
+            poll_interval: Duration::from_millis(10),
+            debounce: Duration::ZERO,
+            ..HeadsetWatcherConfig::default()
+        },
+    );
+    let mut events = watcher.subscribe();
+
+    let mut seen = Vec::new();
+    while seen.len() < 4 {
+        let event = tokio::time::timeout(Duration::from_secs(5), events.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        seen.push(event);
+    }
+    assert!(matches!(seen[0], HeadsetEvent::Connecting { .. }));
+    assert!(matches!(
+        seen[1],
+        HeadsetEvent::BatteryLow {
+            battery_percent: 10,
+            ..
+        }
+    ));
+    assert!(matches!(seen[2], HeadsetEvent::Connected { .. }));
+    assert!(matches!(
+        &seen[3],
+        HeadsetEvent::Lost {
+            headset_id,
+            last_state: HeadsetState::Connected,
+        } if headset_id == "INSIGHT-A1B2C3D4"
+    ));
+
+    watcher.stop().await;
+    server_task.abort();
+}
+
+#[tokio::test]
+async fn ensure_virtual_headset_creates_one_when_none_exists() {
+    use emotiv_cortex_v2::headset::HeadsetModel;
+    use emotiv_cortex_v2::protocol::headset::VirtualHeadsetOptions;
+
+    let Some(mut server) =
+        start_server_or_skip("ensure_virtual_headset_creates_one_when_none_exists").await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-virtual").await;
+
+        let query = connection.recv_request().await;
+        assert_eq!(query["method"], Methods::QUERY_HEADSETS);
+        connection
+            .send_result(
+                rpc_id(&query),
+                json!([{"id": "INSIGHT-A1B2C3D4", "status": "discovered"}]),
+            )
+            .await;
+
+        let create = connection.recv_request().await;
+        assert_eq!(create["method"], Methods::CREATE_VIRTUAL_HEADSET);
+        assert_eq!(create["params"]["cortexToken"], "token-virtual");
+        assert_eq!(create["params"]["channelCount"], 5);
+        connection
+            .send_result(
+                rpc_id(&create),
+                json!({
+                    "id": "VIRTUAL-0001", "status": "connected", "isVirtual": true,
+                    "sensors": ["AF3", "F7", "F3", "FC5", "T7"]
+                }),
+            )
+            .await;
+
+        let delete = connection.recv_request().await;
+        assert_eq!(delete["method"], Methods::DELETE_VIRTUAL_HEADSET);
+        assert_eq!(delete["params"]["headsetId"], "VIRTUAL-0001");
+        connection.send_result(rpc_id(&delete), json!({})).await;
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let headset = client
+        .ensure_virtual_headset(&VirtualHeadsetOptions::new(5))
+        .await
+        .unwrap();
+    assert_eq!(headset.id, "VIRTUAL-0001");
+    assert_eq!(
+        HeadsetModel::from_headset_info(&headset),
+        HeadsetModel::Virtual { channel_count: 5 }
+    );
+    client.delete_virtual_headset(&headset.id).await.unwrap();
+
+    tokio::time::timeout(Duration::from_secs(5), server_task)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn keep_alive_reports_sessions_closed_server_side() {
+    use emotiv_cortex_v2::protocol::session::SessionStatus;
+
+    let Some(mut server) =
+        start_server_or_skip("keep_alive_reports_sessions_closed_server_side").await
+    else {
+        return;
+    };
+    let mut config = resilient_test_config(server.ws_url());
+    config.keep_alive.enabled = true;
+    config.keep_alive.interval_secs = 1;
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-keepalive").await;
+
+        let create = connection
+            .recv_request_method(Methods::CREATE_SESSION)
+            .await;
+        assert_eq!(create["params"]["status"], "open");
+        connection
+            .send_result(
+                rpc_id(&create),
+                json!({
+                    "id": "session-1", "status": "opened",
+                    "owner": "user", "license": "", "appId": "app", "started": "",
+                    "streams": [], "recordIds": [], "recording": false
+                }),
+            )
+            .await;
+
+        // Explicit activation, then the first keep-alive probe.
+        let activate = connection
+            .recv_request_method(Methods::UPDATE_SESSION)
+            .await;
+        assert_eq!(activate["params"]["status"], "active");
+        connection.send_result(rpc_id(&activate), json!({})).await;
+
+        let probe = connection
+            .recv_request_method(Methods::UPDATE_SESSION)
+            .await;
+        assert_eq!(probe["params"]["session"], "session-1");
+        connection
+            .send_error(rpc_id(&probe), -32007, "Session does not exist")
+            .await;
+        connection
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let mut events = client.event_receiver();
+    let session = client
+        .create_session_with_status("INSIGHT-A1B2C3D4", SessionStatus::Open)
+        .await
+        .unwrap();
+    client.activate_session(&session.id).await.unwrap();
+
+    let closed = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let ConnectionEvent::SessionClosed { session_id, reason } =
+                events.recv().await.unwrap()
+            {
+                break (session_id, reason);
+            }
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(closed.0, "session-1");
+    assert!(closed.1.contains("Session does not exist"), "{}", closed.1);
+
+    let _connection = tokio::time::timeout(Duration::from_secs(5), server_task)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn keep_alive_keeps_tracking_session_after_unrelated_api_error() {
+    use emotiv_cortex_v2::protocol::session::SessionStatus;
+
+    let Some(mut server) =
+        start_server_or_skip("keep_alive_keeps_tracking_session_after_unrelated_api_error").await
+    else {
+        return;
+    };
+    let mut config = resilient_test_config(server.ws_url());
+    config.keep_alive.enabled = true;
+    config.keep_alive.interval_secs = 1;
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-keepalive").await;
+        let create = connection
+            .recv_request_method(Methods::CREATE_SESSION)
+            .await;
+        connection
+            .send_result(
+                rpc_id(&create),
+                json!({
+                    "id": "session-1", "status": "activated",
+                    "owner": "user", "license": "", "appId": "app", "started": "",
+                    "streams": [], "recordIds": [], "recording": false
+                }),
+            )
+            .await;
+
+        let probe = connection
+            .recv_request_method(Methods::UPDATE_SESSION)
+            .await;
+        connection
+            .send_error(rpc_id(&probe), -32999, "Something unrelated")
+            .await;
+        // Still tracked: the next probe targets the same session.
+        let probe = connection
+            .recv_request_method(Methods::UPDATE_SESSION)
+            .await;
+        assert_eq!(probe["params"]["session"], "session-1");
+        connection
+            .send_error(rpc_id(&probe), -32007, "Session does not exist")
+            .await;
+        connection
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let mut events = client.event_receiver();
+    client
+        .create_session_with_status("INSIGHT-A1B2C3D4", SessionStatus::Active)
+        .await
+        .unwrap();
+
+    let reason = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let ConnectionEvent::SessionClosed { reason, .. } = events.recv().await.unwrap() {
+                break reason;
+            }
+        }
+    })
+    .await
+    .unwrap();
+    assert!(reason.contains("Session does not exist"), "{reason}");
+
+    let _connection = tokio::time::timeout(Duration::from_secs(5), server_task)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn marker_batcher_replays_markers_after_reconnect_with_original_time() {
+    use std::sync::Arc;
+
+    use emotiv_cortex_v2::markers::{MarkerBatcher, MarkerBatcherConfig};
+
+    let Some(mut server) =
+        start_server_or_skip("marker_batcher_replays_markers_after_reconnect_with_original_time")
+            .await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut first = server.accept_connection().await;
+        drive_auth_handshake(&mut first, "token-1").await;
+        let a = first.recv_request_method(Methods::INJECT_MARKER).await;
+        assert_eq!(a["params"]["label"], "a");
+        first
+            .send_result(rpc_id(&a), json!({"marker": {"uuid": "marker-a"}}))
+            .await;
+        // The connection drops before "b" is answered.
+        let lost = first.recv_request_method(Methods::INJECT_MARKER).await;
+        assert_eq!(lost["params"]["label"], "b");
+        first.force_close().await;
+
+        let mut second = server.accept_connection().await;
+        drive_auth_handshake(&mut second, "token-2").await;
+        let b = second.recv_request_method(Methods::INJECT_MARKER).await;
+        assert_eq!(b["params"]["label"], "b");
+        assert_eq!(b["params"]["time"], lost["params"]["time"]);
+        second
+            .send_result(rpc_id(&b), json!({"marker": {"uuid": "marker-b"}}))
+            .await;
+        b["params"]["time"].as_f64().unwrap()
+    });
+
+    let client = Arc::new(ResilientClient::connect(config).await.unwrap());
+    let markers = MarkerBatcher::spawn(
+        Arc::clone(&client),
+        "session-1",
+        MarkerBatcherConfig {
+            retry_delay: Duration::from_millis(10),
+            ..MarkerBatcherConfig::default()
+        },
+    );
+    markers.mark("a", 1).unwrap();
+    let b_time = markers.mark("b", 2).unwrap();
+
+    let sent_time = tokio::time::timeout(Duration::from_secs(5), server_task)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!((sent_time - b_time).abs() < 1e-3);
+
+    for _ in 0..100 {
+        if markers.pending() == 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let report = markers.stop().await;
+    assert_eq!(report.written, 2);
+    assert!(report.is_lossless(), "{report:?}");
+}
+
+#[tokio::test]
+async fn export_record_and_wait_collects_files_from_completion_warnings() {
+    use std::path::PathBuf;
+
+    use emotiv_cortex_v2::CortexError;
+    use emotiv_cortex_v2::protocol::records::{ExportFormat, ExportRecordOptions};
+
+    let Some(mut server) =
+        start_server_or_skip("export_record_and_wait_collects_files_from_completion_warnings")
+            .await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-export").await;
+
+        let export = connection.recv_request_method(Methods::EXPORT_RECORD).await;
+        assert_eq!(export["params"]["recordIds"], json!(["r-1", "r-2"]));
+        connection
+            .send_result(
+                rpc_id(&export),
+                json!({"success": [{"recordId": "r-1"}, {"recordId": "r-2"}], "failure": []}),
+            )
+            .await;
+        for warning in [
+            json!({"code": 31, "message": {"recordId": "r-1", "progress": 50}}),
+            json!({"code": 32, "message": {"recordId": "r-1", "files": ["/out/r-1.csv"]}}),
+            json!({"code": 32, "message": {"recordId": "other", "files": ["/out/other.csv"]}}),
+            json!({"code": 32, "message": {"recordId": "r-2", "files": ["/out/r-2.csv"]}}),
+        ] {
+            connection.push_event(json!({"warning": warning})).await;
+        }
+
+        let export = connection.recv_request_method(Methods::EXPORT_RECORD).await;
+        connection
+            .send_result(
+                rpc_id(&export),
+                json!({"success": [{"recordId": "r-3"}], "failure": []}),
+            )
+            .await;
+        connection
+            .push_event(json!({"warning": {
+                "code": 32,
+                "message": {"recordId": "r-3", "error": "disk full"}
+            }}))
+            .await;
+        connection
+    });
+
+    let client = ResilientClient::connect(config).await.unwrap();
+    let files = client
+        .export_record_and_wait(
+            &ExportRecordOptions::new(["r-1", "r-2"], "/out", ExportFormat::Csv),
+            Duration::from_secs(2),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        files,
+        vec![PathBuf::from("/out/r-1.csv"), PathBuf::from("/out/r-2.csv")]
+    );
+
+    let failed = client
+        .export_record_and_wait(
+            &ExportRecordOptions::new(["r-3"], "/out", ExportFormat::Csv),
+            Duration::from_secs(2),
+        )
+        .await;
+    assert!(matches!(
+        failed,
+        Err(CortexError::ExportFailed { record_id, reason }) if record_id == "r-3" && reason == "disk full"
+    ));
+
+    let _connection = server_task.await.unwrap();
+}
+
+#[tokio::test]
+async fn timesync_maps_cortex_time_to_host_instant() {
+    use std::sync::Arc;
+    use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+    use emotiv_cortex_v2::timesync::{TimeSync, TimeSyncConfig};
+
+    let Some(mut server) = start_server_or_skip("timesync_maps_cortex_time_to_host_instant").await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept_connection().await;
+        drive_auth_handshake(&mut connection, "token-timesync").await;
+        // One periodic round, then one from sync_now.
+        for _ in 0..2 {
+            let sync = connection
+                .recv_request_method(Methods::SYNC_WITH_HEADSET_CLOCK)
+                .await;
+            assert_eq!(sync["params"]["headset"], "INSIGHT-A1B2C3D4");
+            connection
+                .send_result(
+                    rpc_id(&sync),
+                    json!({"adjustment": 2.5, "headset": "INSIGHT-A1B2C3D4"}),
+                )
+                .await;
+        }
+        connection
+    });
+
+    let client = Arc::new(ResilientClient::connect(config).await.unwrap());
+    let sync = TimeSync::spawn(
+        client,
+        "INSIGHT-A1B2C3D4",
+        TimeSyncConfig {
+            interval: Duration::from_secs(3600),
+            ..TimeSyncConfig::default()
+        },
+    );
+    for _ in 0..100 {
+        if sync.estimate().is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(sync.estimate().is_some(), "periodic round did not run");
+    let estimate = sync.sync_now().await.unwrap();
+    assert!(estimate.samples >= 1);
+    assert!(estimate.uncertainty < 0.5);
+
+    // A sample Cortex stamped "now" on its clock happened now on ours.
+    let now = Instant::now();
+    let epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs_f64();
+    let host = sync.to_host_time(epoch + 2.5).unwrap();
+    let error = if host > now { host - now } else { now - host };
+    assert!(error < Duration::from_millis(100), "{error:?}");
+    let cortex = sync.to_cortex_time(now).unwrap();
+    assert!((cortex - (epoch + 2.5)).abs() < 0.1);
+
+    sync.stop().await;
+    let _connection = server_task.await.unwrap();
+}
+
+#[tokio::test]
+async fn custom_request_id_generator_survives_reconnect() {
+    use std::sync::Arc;
+
+    use emotiv_cortex_v2::protocol::rpc::CounterIds;
+
+    let Some(mut server) =
+        start_server_or_skip("custom_request_id_generator_survives_reconnect").await
+    else {
+        return;
+    };
+    let config = resilient_test_config(server.ws_url());
+
+    let server_task = tokio::spawn(async move {
+        let mut first = server.accept_connection().await;
+        drive_auth_handshake(&mut first, "token-initial").await;
+        let query = first.recv_request_method(Methods::QUERY_HEADSETS).await;
+        let first_id = rpc_id(&query);
+        first.force_close().await;
+
+        let mut second = server.accept_connection().await;
+        let info = second.recv_request_method(Methods::GET_CORTEX_INFO).await;
+        let reconnect_id = rpc_id(&info);
+        second
+            .send_result(reconnect_id, json!({"version": "mock"}))
+            .await;
+        let request_access = second.recv_request_method(Methods::REQUEST_ACCESS).await;
+        second
+            .send_result(rpc_id(&request_access), json!({"accessGranted": true}))
+            .await;
+        let authorize = second.recv_request_method(Methods::AUTHORIZE).await;
+        second
+            .send_result(rpc_id(&authorize), json!({"cortexToken": "token-2"}))
+            .await;
+        let retried = second.recv_request_method(Methods::QUERY_HEADSETS).await;
+        second.send_result(rpc_id(&retried), json!([])).await;
+        (first_id, reconnect_id)
+    });
+
+    let client =
+        ResilientClient::connect_with_request_ids(config, Arc::new(CounterIds::starting_at(5_000)))
+            .await
+            .unwrap();
+    client
+        .query_headsets(QueryHeadsetsOptions::default())
+        .await
+        .unwrap();
+
+    let (first_id, reconnect_id) = server_task.await.unwrap();
+    // getCortexInfo, requestAccess, authorize, then the query.
+    assert_eq!(first_id, 5_003);
+    // The new connection keeps drawing from the same generator.
+    assert_eq!(reconnect_id, 5_004);
+}
@@ -0,0 +1,161 @@
+mod support;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use emotiv_cortex_v2::protocol::constants::{Methods, Streams};
+use emotiv_cortex_v2::session_pool::{SessionPool, SessionPoolConfig};
+use emotiv_cortex_v2::{CortexClient, CortexConfig, CortexError};
+use serde_json::{Value, json};
+
+use support::mock_cortex::{MockConnection, MockCortexServer};
+
+fn test_config(url: String) -> CortexConfig {
+    let mut config = CortexConfig::new("test-client-id", "test-client-secret");
+    config.cortex_url = url;
+    config.timeouts.rpc_timeout_secs = 1;
+    config
+}
+
+fn rpc_id(request: &Value) -> u64 {
+    request
+        .get("id")
+        .and_then(Value::as_u64)
+        .expect("request missing numeric id")
+}
+
+async fn start_server_or_skip(test_name: &str) -> Option<MockCortexServer> {
+    match MockCortexServer::start().await {
+        Ok(server) => Some(server),
+        Err(err) => {
+            eprintln!("Skipping {test_name}: unable to start mock server: {err}");
+            None
+        }
+    }
+}
+
+fn session_json(id: &str) -> Value {
+    json!({
+        "id": id,
+        "status": "activated",
+        "owner": "user",
+        "license": "license",
+        "appId": "com.example.app",
+        "started": "2024-01-15T10:30:00Z",
+        "streams": [],
+        "recordIds": [],
+        "recording": false
+    })
+}
+
+async fn answer_create_session(connection: &mut MockConnection, session_id: &str) -> Value {
+    let request = connection
+        .recv_request_method(Methods::CREATE_SESSION)
+        .await;
+    connection
+        .send_result(rpc_id(&request), session_json(session_id))
+        .await;
+    request
+}
+
+#[tokio::test]
+async fn returned_lease_resets_subscriptions_and_reuses_session() {
+    let Some(mut server) =
+        start_server_or_skip("returned_lease_resets_subscriptions_and_reuses_session").await
+    else {
+        return;
+    };
+    let config = test_config(server.ws_url());
+    let client = CortexClient::connect(&config).await.unwrap();
+    let pool = SessionPool::new(Arc::new(client), "token", SessionPoolConfig::default());
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        answer_create_session(&mut connection, "session-1").await;
+
+        let subscribe = connection.recv_request_method(Methods::SUBSCRIBE).await;
+        connection
+            .send_result(rpc_id(&subscribe), json!({"success": [Streams::EEG]}))
+            .await;
+
+        let unsubscribe = connection.recv_request_method(Methods::UNSUBSCRIBE).await;
+        connection
+            .send_result(rpc_id(&unsubscribe), json!({"success": [Streams::EEG]}))
+            .await;
+
+        // The second lease must not open a new session: the next request
+        // on the wire is its subscribe.
+        let subscribe = connection.recv_request_method(Methods::SUBSCRIBE).await;
+        connection
+            .send_result(rpc_id(&subscribe), json!({"success": [Streams::MOT]}))
+            .await;
+        (unsubscribe, subscribe)
+    });
+
+    let mut lease = pool.acquire("headset-1").await.unwrap();
+    assert_eq!(lease.session_id(), "session-1");
+    lease.subscribe(&[Streams::EEG]).await.unwrap();
+    assert_eq!(lease.subscribed_streams(), ["eeg"]);
+
+    assert!(matches!(
+        pool.acquire("headset-1").await,
+        Err(CortexError::SessionError { .. })
+    ));
+
+    lease.release().await.unwrap();
+    assert_eq!(pool.idle_count(), 1);
+
+    let mut lease = pool.acquire("headset-1").await.unwrap();
+    assert_eq!(lease.session_id(), "session-1");
+    assert_eq!(pool.idle_count(), 0);
+    lease.subscribe(&[Streams::MOT]).await.unwrap();
+
+    let (unsubscribe, subscribe) = responder.await.unwrap();
+    assert_eq!(unsubscribe["params"]["session"], "session-1");
+    assert_eq!(unsubscribe["params"]["streams"], json!(["eeg"]));
+    assert_eq!(subscribe["params"]["session"], "session-1");
+    assert_eq!(pool.len(), 1);
+}
+
+#[tokio::test]
+async fn idle_sessions_expire_on_next_acquire() {
+    let Some(mut server) = start_server_or_skip("idle_sessions_expire_on_next_acquire").await
+    else {
+        return;
+    };
+    let config = test_config(server.ws_url());
+    let client = CortexClient::connect(&config).await.unwrap();
+    let pool = SessionPool::new(
+        Arc::new(client),
+        "token",
+        SessionPoolConfig {
+            idle_timeout: Duration::ZERO,
+        },
+    );
+
+    let mut connection = server.accept_connection().await;
+    let responder = tokio::spawn(async move {
+        answer_create_session(&mut connection, "session-1").await;
+
+        let close = connection
+            .recv_request_method(Methods::UPDATE_SESSION)
+            .await;
+        connection
+            .send_result(rpc_id(&close), session_json("session-1"))
+            .await;
+
+        answer_create_session(&mut connection, "session-2").await;
+        close
+    });
+
+    let lease = pool.acquire("headset-1").await.unwrap();
+    drop(lease);
+    assert_eq!(pool.idle_count(), 1);
+
+    let lease = pool.acquire("headset-1").await.unwrap();
+    assert_eq!(lease.session_id(), "session-2");
+
+    let close = responder.await.unwrap();
+    assert_eq!(close["params"]["session"], "session-1");
+    assert_eq!(close["params"]["status"], "close");
+}
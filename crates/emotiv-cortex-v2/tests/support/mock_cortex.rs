@@ -18,6 +18,7 @@ pub const STEP_TIMEOUT: Duration = Duration::from_secs(3);
 
 enum ConnectionCommand {
     SendJson(Value),
+    SendBinary(Vec<u8>),
     ForceClose,
 }
 
@@ -78,6 +79,13 @@ impl MockConnection {
         self.send_json(event).await;
     }
 
+    pub async fn send_binary(&self, data: Vec<u8>) {
+        self.command_tx
+            .send(ConnectionCommand::SendBinary(data))
+            .await
+            .expect("failed to send command to mock connection");
+    }
+
     pub async fn force_close(&self) {
         let _ = self.command_tx.send(ConnectionCommand::ForceClose).await;
     }
@@ -90,6 +98,7 @@ pub struct MockCortexServer {
 }
 
 impl MockCortexServer {
+    #[allow(clippy::manual_let_else, clippy::match_same_arms)]
     pub async fn start() -> std::io::Result<Self> {
         let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
         let addr = listener.local_addr()?;
@@ -136,6 +145,11 @@ impl MockCortexServer {
                                             break;
                                         }
                                     }
+                                    Some(ConnectionCommand::SendBinary(data)) => {
+                                        if ws_sink.send(Message::Binary(data.into())).await.is_err() {
+                                            break;
+                                        }
+                                    }
                                     Some(ConnectionCommand::ForceClose) => {
                                         break;
                                     }